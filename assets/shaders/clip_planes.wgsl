@@ -0,0 +1,88 @@
+// Used both as main pass and prepass fragment shader of the clip material
+
+#ifdef PREPASS_PIPELINE
+#import bevy_pbr::{
+    prepass_io::{VertexOutput, FragmentOutput},
+    pbr_prepass_functions,
+}
+#else
+#import bevy_pbr::{
+    forward_io::{VertexOutput, FragmentOutput},
+    pbr_fragment::pbr_input_from_standard_material,
+    pbr_functions::{alpha_discard, apply_pbr_lighting, main_pass_post_lighting_processing},
+}
+#endif
+
+struct ClipPlanes {
+    planes: array<vec4<f32>, 6>,
+    count: u32,
+};
+
+@group(2) @binding(100) var<uniform> clip: ClipPlanes;
+
+// Check whether a world position lies on the positive side of any plane
+fn is_clipped(world_position: vec3<f32>) -> bool {
+    for (var i = 0u; i < clip.count; i++) {
+        let plane = clip.planes[i];
+        if dot(plane.xyz, world_position) + plane.w > 0.0 {
+            return true;
+        }
+    }
+    return false;
+}
+
+#ifdef PREPASS_PIPELINE
+
+#ifdef PREPASS_FRAGMENT
+@fragment
+fn fragment(in: VertexOutput) -> FragmentOutput {
+    if is_clipped(in.world_position.xyz) {
+        discard;
+    }
+
+    var out: FragmentOutput;
+#ifdef DEPTH_CLAMP_ORTHO
+    out.frag_depth = in.clip_position_unclamped.z;
+#endif
+#ifdef NORMAL_PREPASS
+    out.normal = vec4(normalize(in.world_normal) * 0.5 + vec3(0.5), 1.0);
+#endif
+#ifdef MOTION_VECTOR_PREPASS
+    out.motion_vector = pbr_prepass_functions::calculate_motion_vector(
+        in.world_position,
+        in.previous_world_position,
+    );
+#endif
+    return out;
+}
+#else
+// Depth only (including shadows)
+@fragment
+fn fragment(in: VertexOutput) {
+    if is_clipped(in.world_position.xyz) {
+        discard;
+    }
+}
+#endif
+
+#else
+
+@fragment
+fn fragment(
+    in: VertexOutput,
+    @builtin(front_facing) is_front: bool,
+) -> FragmentOutput {
+    if is_clipped(in.world_position.xyz) {
+        discard;
+    }
+
+    var pbr_input = pbr_input_from_standard_material(in, is_front);
+    pbr_input.material.base_color = alpha_discard(pbr_input.material, pbr_input.material.base_color);
+
+    var out: FragmentOutput;
+    out.color = apply_pbr_lighting(pbr_input);
+    out.color = main_pass_post_lighting_processing(pbr_input, out.color);
+    return out;
+}
+
+#endif
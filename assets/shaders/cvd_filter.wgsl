@@ -0,0 +1,26 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+@group(0) @binding(0) var screen_texture: texture_2d<f32>;
+@group(0) @binding(1) var texture_sampler: sampler;
+
+struct CvdFilter {
+    row_r: vec4<f32>,
+    row_g: vec4<f32>,
+    row_b: vec4<f32>,
+};
+
+@group(0) @binding(2) var<uniform> filter: CvdFilter;
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    // Linear RGB of the tonemapped image
+    let color = textureSample(screen_texture, texture_sampler, in.uv);
+
+    let simulated = vec3<f32>(
+        dot(filter.row_r.xyz, color.rgb),
+        dot(filter.row_g.xyz, color.rgb),
+        dot(filter.row_b.xyz, color.rgb),
+    );
+
+    return vec4<f32>(clamp(simulated, vec3<f32>(0.0), vec3<f32>(1.0)), color.a);
+}
@@ -0,0 +1,63 @@
+// Vertex clustering pre-pass of the GPU mesh simplification
+
+struct Params {
+    grid_min: vec3<f32>,
+    cell_size: f32,
+    resolution: u32,
+    vertex_count: u32,
+    table_mask: u32,
+    _padding: u32,
+};
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> positions: array<f32>;
+@group(0) @binding(2) var<storage, read_write> cluster_keys: array<u32>;
+// Occupancy of hashed cells for linear counting of the distinct clusters
+@group(0) @binding(3) var<storage, read_write> cluster_table: array<atomic<u32>>;
+@group(0) @binding(4) var<storage, read_write> occupied_slots: atomic<u32>;
+
+const WORKGROUP_SIZE: u32 = 256u;
+
+// Large inputs are dispatched as a 2D grid of workgroups
+fn invocation_index(id: vec3<u32>, groups: vec3<u32>) -> u32 {
+    return id.x + id.y * groups.x * WORKGROUP_SIZE;
+}
+
+// Integer hash with good low bits (lowbias32)
+fn hash(value: u32) -> u32 {
+    var x = value;
+    x ^= x >> 16u;
+    x *= 0x7feb352du;
+    x ^= x >> 15u;
+    x *= 0x846ca68bu;
+    x ^= x >> 16u;
+    return x;
+}
+
+// Assign every vertex the key of the grid cell containing it and mark the
+// hashed slot of the cell
+@compute @workgroup_size(256)
+fn assign_clusters(
+    @builtin(global_invocation_id) id: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let vertex = invocation_index(id, groups);
+    if vertex >= params.vertex_count {
+        return;
+    }
+
+    let position = vec3(
+        positions[vertex * 3u],
+        positions[vertex * 3u + 1u],
+        positions[vertex * 3u + 2u],
+    );
+    let max_cell = f32(params.resolution - 1u);
+    let cell = vec3<u32>(clamp(floor((position - params.grid_min) / params.cell_size), vec3(0.0), vec3(max_cell)));
+    let key = cell.x + params.resolution * (cell.y + params.resolution * cell.z);
+    cluster_keys[vertex] = key;
+
+    let slot = hash(key) & params.table_mask;
+    if atomicExchange(&cluster_table[slot], 1u) == 0u {
+        atomicAdd(&occupied_slots, 1u);
+    }
+}
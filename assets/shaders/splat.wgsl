@@ -0,0 +1,67 @@
+#import bevy_pbr::mesh_functions::get_world_from_local
+#import bevy_pbr::mesh_view_bindings::view
+
+struct SplatUniformData {
+    opacity: f32,
+    sharpness: f32,
+    shaded: u32,
+    _padding: f32,
+};
+
+@group(2) @binding(0) var<uniform> material: SplatUniformData;
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+    @location(0) position: vec3<f32>,
+    @location(1) corner: vec2<f32>,
+    @location(2) color: vec4<f32>,
+    @location(3) radius: f32,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) corner: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+
+    // Particle center in world and view space
+    let world_from_local = get_world_from_local(vertex.instance_index);
+    let world_center = world_from_local * vec4<f32>(vertex.position, 1.0);
+    let view_center = view.view_from_world * world_center;
+
+    // The radius follows the model scale
+    let scale = length(world_from_local[0].xyz);
+
+    // Expand the quad in view space so it always faces the camera
+    let offset = vertex.corner * vertex.radius * scale;
+    let view_position = vec4<f32>(view_center.xy + offset, view_center.zw);
+
+    out.clip_position = view.clip_from_view * view_position;
+    out.corner = vertex.corner;
+    out.color = vertex.color;
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let r2 = dot(in.corner, in.corner);
+    if (r2 > 1.0) {
+        discard;
+    }
+
+    // Opaque disc lit like a sphere facing the camera
+    if (material.shaded == 1u) {
+        let normal = vec3<f32>(in.corner, sqrt(1.0 - r2));
+        let light = normalize(vec3<f32>(0.4, 0.5, 1.0));
+        let shade = 0.3 + 0.7 * max(dot(normal, light), 0.0);
+        return vec4<f32>(in.color.rgb * shade, 1.0);
+    }
+
+    // Gaussian falloff from the particle center
+    let weight = exp(-material.sharpness * r2);
+    return vec4<f32>(in.color.rgb, in.color.a * weight * material.opacity);
+}
@@ -1,18 +1,52 @@
 // Time series animation system
 use crate::mesh::color_maps::{ColorMapper, ColorMappingConfig};
-use crate::mesh::vtk::VtkMeshExtractor;
+use crate::mesh::vtk::AttributeLocation;
 use bevy::prelude::*;
-use std::path::PathBuf;
+use bevy::utils::HashMap;
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+/// Single component point scalar attributes of one time step by name
+pub type FrameScalars = HashMap<String, Vec<f32>>;
+
+/// Point vector attributes of one time step by name
+pub type FrameVectors = HashMap<String, Vec<[f32; 3]>>;
 
 /// Scalar data for each frame in the time series
 #[derive(Clone, Debug)]
 pub struct TimeStepData {
     pub scalars: Vec<f32>, // Scalar values for this time step
+    /// Point scalar attributes, empty for multi-step files (Exodus, netCDF, XDMF)
+    pub point_scalars: FrameScalars,
+    /// Point vector attributes, empty for multi-step files (Exodus, netCDF, XDMF)
+    pub vectors: FrameVectors,
     #[allow(dead_code)]
     pub time_step: usize, // Time step index
+    pub time: f32,          // Physical time (the index for plain file series)
     pub file_path: PathBuf, // Source file path
 }
 
+impl TimeStepData {
+    /// Values to color this time step by
+    ///
+    /// # Parameters
+    /// - `attribute`: Attribute selected in the color bar; vectors give their magnitude
+    ///
+    /// # Returns
+    /// - Point values of the attribute, or the default scalars if the step does not have it
+    pub fn color_values(&self, attribute: Option<&(String, AttributeLocation)>) -> Cow<'_, [f32]> {
+        if let Some((name, AttributeLocation::Point)) = attribute {
+            if let Some(values) = self.point_scalars.get(name) {
+                return Cow::Borrowed(values);
+            }
+            if let Some(vectors) = self.vectors.get(name) {
+                return Cow::Owned(vectors.iter().map(|&v| Vec3::from(v).length()).collect());
+            }
+        }
+        Cow::Borrowed(&self.scalars)
+    }
+}
+
 /// Time series asset - Contains static mesh and scalar data for all time steps
 #[derive(Resource)]
 pub struct TimeSeriesAsset {
@@ -23,6 +57,7 @@ pub struct TimeSeriesAsset {
 
     // Step 2: Time series data
     pub all_file_paths: Vec<PathBuf>,  // All file paths
+    pub time_values: Vec<f32>,         // Physical time of each file (empty if unknown)
     pub time_steps: Vec<TimeStepData>, // Scalar data for all time steps
     pub is_step2_complete: bool,       // Check if step 2 is complete
 
@@ -46,7 +81,8 @@ pub struct TimeSeriesAsset {
 /// Time series animation events
 #[derive(Event)]
 pub enum TimeSeriesEvent {
-    LoadSeries(Vec<PathBuf>), // Load time series files
+    LoadSeries(Vec<PathBuf>),               // Load time series files
+    LoadCollection(Vec<PathBuf>, Vec<f32>), // Load time series files with physical times (.pvd)
     // Animation control events
     Play,               // Play animation
     Pause,              // Pause animation
@@ -66,6 +102,7 @@ impl Default for TimeSeriesAsset {
             is_step1_complete: false,
 
             all_file_paths: Vec::new(),
+            time_values: Vec::new(),
             time_steps: Vec::new(),
             is_step2_complete: false,
 
@@ -111,6 +148,11 @@ impl TimeSeriesAsset {
         }
     }
 
+    /// Whether the time steps carry physical times (loaded from a collection file)
+    pub fn has_physical_time(&self) -> bool {
+        !self.time_values.is_empty()
+    }
+
     /// Get current time step data (for UI display)
     pub fn get_current_time_step_data(&self) -> Option<&TimeStepData> {
         self.time_steps.get(self.current_time_step)
@@ -239,6 +281,16 @@ fn handle_time_series_events(
 
                 time_series_asset.start_loading(file_paths.clone());
             }
+            TimeSeriesEvent::LoadCollection(file_paths, times) => {
+                let _cleared_count = crate::ui::clear_existing_models_silent(
+                    &mut commands,
+                    &mesh_entities,
+                    &mut current_model,
+                );
+
+                time_series_asset.start_loading(file_paths.clone());
+                time_series_asset.time_values = times.clone();
+            }
             TimeSeriesEvent::Play => {
                 time_series_asset.play();
             }
@@ -305,11 +357,12 @@ fn update_animation_colors(
         for mesh3d in mesh_query.iter() {
             if let Some(mesh) = meshes.get_mut(&mesh3d.0) {
                 // Update vertex colors
-                apply_scalar_colors_to_mesh(mesh, &current_data.scalars, &color_bar_config);
+                let scalars = current_data.color_values(color_bar_config.attribute.as_ref());
+                apply_scalar_colors_to_mesh(mesh, &scalars, &color_bar_config);
                 println!(
                     "Updated mesh colors for time step {} with {} scalars",
                     time_series_asset.current_time_step,
-                    current_data.scalars.len()
+                    scalars.len()
                 );
             }
         }
@@ -329,6 +382,13 @@ fn apply_scalar_colors_to_mesh(
         min_value: color_bar_config.min_value,
         max_value: color_bar_config.max_value,
         use_custom_range: color_bar_config.max_value > color_bar_config.min_value,
+        attribute: None,
+        log_scale: color_bar_config.log_scale,
+        mode: color_bar_config.mode,
+        bins: color_bar_config.bins,
+        nan_color: color_bar_config.nan_color,
+        below_color: color_bar_config.below_color,
+        above_color: color_bar_config.above_color,
     };
 
     ColorMapper::apply_scalars_to_mesh(mesh, scalars, &config);
@@ -408,10 +468,54 @@ fn load_all_time_series_data(mut time_series_asset: ResMut<TimeSeriesAsset>) {
         let file_paths = time_series_asset.all_file_paths.clone();
 
         for (index, file_path) in file_paths.iter().enumerate() {
-            if let Ok((_, _, scalars)) = load_full_mesh_data(file_path) {
+            // Exodus, netCDF and XDMF files hold all time steps of the series
+            let series = if crate::mesh::exodus::is_exodus_file(file_path) {
+                Some(crate::mesh::exodus::load_nodal_time_series(file_path))
+            } else if crate::mesh::climate::is_netcdf_file(file_path) {
+                Some(crate::mesh::climate::load_time_series(file_path))
+            } else if crate::mesh::xdmf::is_xdmf_file(file_path) {
+                Some(crate::mesh::xdmf::load_time_series(file_path))
+            } else {
+                None
+            };
+            if let Some(series) = series {
+                match series {
+                    Ok(steps) => {
+                        for (time, scalars) in steps {
+                            let time_step = time_series_asset.time_steps.len();
+                            time_series_asset.time_values.push(time);
+                            time_series_asset.time_steps.push(TimeStepData {
+                                scalars,
+                                point_scalars: FrameScalars::new(),
+                                vectors: FrameVectors::new(),
+                                time_step,
+                                time,
+                                file_path: file_path.clone(),
+                            });
+                        }
+                        loaded_count += 1;
+                    }
+                    Err(e) => eprintln!(
+                        "Failed to load time steps from {}: {}",
+                        file_path.display(),
+                        e
+                    ),
+                }
+                continue;
+            }
+
+            if let Ok((_, _, scalars, point_scalars, vectors)) = load_full_mesh_data(file_path) {
+                let time = time_series_asset
+                    .time_values
+                    .get(index)
+                    .copied()
+                    .unwrap_or(index as f32);
                 time_series_asset.time_steps.push(TimeStepData {
                     scalars,
+                    point_scalars,
+                    vectors,
                     time_step: index,
+                    time,
                     file_path: file_path.clone(),
                 });
                 loaded_count += 1;
@@ -435,22 +539,14 @@ fn load_all_time_series_data(mut time_series_asset: ResMut<TimeSeriesAsset>) {
     }
 }
 
+/// Vertices, indices, default scalars, point scalars and point vectors of one file
+type FrameMeshData = (Vec<Vec3>, Vec<u32>, Vec<f32>, FrameScalars, FrameVectors);
+
 /// Load complete data from file
-fn load_full_mesh_data(
-    path: &PathBuf,
-) -> Result<(Vec<Vec3>, Vec<u32>, Vec<f32>), Box<dyn std::error::Error>> {
+fn load_full_mesh_data(path: &Path) -> Result<FrameMeshData, Box<dyn std::error::Error>> {
     println!("Loading full mesh data from: {}", path.display());
-    let vtk = vtkio::Vtk::import(path)?;
-
-    let geometry = match &vtk.data {
-        vtkio::model::DataSet::UnstructuredGrid { pieces, .. } => {
-            let extractor = crate::mesh::vtk::UnstructuredGridExtractor;
-            extractor.process_legacy(pieces.clone())?
-        }
-        _ => {
-            return Err("Only UnstructuredGrid format is supported".into());
-        }
-    };
+    // XML files go through the XML pipeline, legacy files through the legacy one
+    let geometry = crate::mesh::load_geometry_file(path)?;
 
     // Extract vertices
     let vertices: Vec<Vec3> = geometry
@@ -481,12 +577,43 @@ fn load_full_mesh_data(
         vec![0.0; vertices.len()]
     };
 
+    // All point scalars, for coloring by the attribute selected in the color bar
+    let point_scalars: FrameScalars = geometry
+        .attributes
+        .iter()
+        .flatten()
+        .filter_map(|((name, location), attr)| match (location, attr) {
+            (
+                AttributeLocation::Point,
+                crate::mesh::vtk::AttributeType::Scalar {
+                    num_comp: 1, data, ..
+                },
+            ) => Some((name.clone(), data.clone())),
+            _ => None,
+        })
+        .collect();
+
+    // Point vectors, for particle tracing through the time steps
+    let vectors: FrameVectors = geometry
+        .attributes
+        .iter()
+        .flatten()
+        .filter_map(|((name, location), attr)| match (location, attr) {
+            (
+                crate::mesh::vtk::AttributeLocation::Point,
+                crate::mesh::vtk::AttributeType::Vector(data),
+            ) => Some((name.clone(), data.clone())),
+            _ => None,
+        })
+        .collect();
+
     println!(
-        "Extracted: {} vertices, {} indices, {} scalars",
+        "Extracted: {} vertices, {} indices, {} scalars, {} vector attributes",
         vertices.len(),
         indices.len(),
-        scalars.len()
+        scalars.len(),
+        vectors.len()
     );
 
-    Ok((vertices, indices, scalars))
+    Ok((vertices, indices, scalars, point_scalars, vectors))
 }
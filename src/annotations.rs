@@ -0,0 +1,291 @@
+//! # Annotations Module
+//!
+//! This module attaches text notes to locations on the model:
+//! - Left click on the surface places a note at the hit point, in model space,
+//!   so it moves with the model transform
+//! - Notes are drawn in screen space next to a marker, so they always face the
+//!   camera, and are listed in the Annotations window for editing
+//! - Notes are part of the session snapshot, so project files and session
+//!   recovery restore them
+//!
+//! ## Control Scheme (Tools > Annotate)
+//! - Left click on the surface: Place a note with the text of the panel
+
+use crate::camera::WorldModelCamera;
+use crate::picking::{
+    cursor_ray_in_model_space, model_matrix, pointer_over_ui, InteractionMode, PickingBvh,
+};
+use crate::preferences::UiSettings;
+use crate::render::OverlayGizmos;
+use crate::ui::{CurrentModelData, ModelLoadedEvent, UserModelMesh};
+use crate::widgets::{widget_drag_input, WidgetState};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Marker radius relative to the model size
+const MARKER_RADIUS_FACTOR: f32 = 0.006;
+
+const MARKER_COLOR: Color = Color::srgb(0.4, 1.0, 0.4);
+
+/// Note text color
+const TEXT_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 255, 230);
+
+/// Text note at a model location
+#[derive(Clone, Debug, PartialEq)]
+pub struct Annotation {
+    /// Position in model space
+    pub position: Vec3,
+    /// Note text, a single line
+    pub text: String,
+}
+
+/// Annotation state
+#[derive(Resource)]
+pub struct AnnotationState {
+    pub annotations: Vec<Annotation>,
+    /// Draw the notes
+    pub visible: bool,
+    /// Text of the next note
+    pub next_text: String,
+    /// Marker radius
+    pub marker_radius: f32,
+}
+
+impl Default for AnnotationState {
+    fn default() -> Self {
+        Self {
+            annotations: Vec::new(),
+            visible: true,
+            next_text: String::new(),
+            marker_radius: 0.012,
+        }
+    }
+}
+
+pub struct AnnotationPlugin;
+
+impl Plugin for AnnotationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AnnotationState>().add_systems(
+            Update,
+            (
+                reset_annotations_on_model_load,
+                annotation_input.after(widget_drag_input),
+                draw_annotation_markers,
+                annotation_panel.after(EguiSet::InitContexts),
+                draw_annotation_text.after(EguiSet::InitContexts),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Remove the notes of the previous model and adapt the marker size
+fn reset_annotations_on_model_load(
+    mut model_loaded_events: EventReader<ModelLoadedEvent>,
+    mut state: ResMut<AnnotationState>,
+) {
+    for event in model_loaded_events.read() {
+        state.annotations.clear();
+        if let (Some(min), Some(max)) = (event.bounds_min, event.bounds_max) {
+            state.marker_radius = (max - min).length().max(1e-3) * MARKER_RADIUS_FACTOR;
+        }
+    }
+}
+
+/// Place a note on the surface on left click
+#[allow(clippy::too_many_arguments)]
+fn annotation_input(
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    interaction_mode: Res<InteractionMode>,
+    current_model: Res<CurrentModelData>,
+    widget_state: Res<WidgetState>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<WorldModelCamera>>,
+    model_query: Query<&GlobalTransform, With<UserModelMesh>>,
+    mut contexts: EguiContexts,
+    mut state: ResMut<AnnotationState>,
+    mut picking_bvh: ResMut<PickingBvh>,
+) {
+    if *interaction_mode != InteractionMode::Annotate
+        || !mouse_button_input.just_pressed(MouseButton::Left)
+    {
+        return;
+    }
+
+    let alt_pressed =
+        keyboard_input.pressed(KeyCode::AltLeft) || keyboard_input.pressed(KeyCode::AltRight);
+    if alt_pressed || widget_state.is_dragging() || pointer_over_ui(&mut contexts) {
+        return;
+    }
+
+    let Some(ref geometry) = current_model.geometry else {
+        return;
+    };
+    let Some((ray_origin, ray_direction)) =
+        cursor_ray_in_model_space(&windows, &camera_query, model_matrix(&model_query))
+    else {
+        return;
+    };
+
+    if let Some(hit) = picking_bvh.pick(geometry, ray_origin, ray_direction) {
+        let text = match state.next_text.trim() {
+            "" => format!("Note {}", state.annotations.len() + 1),
+            text => text.to_string(),
+        };
+        println!(
+            "Annotation '{}' at ({:.4}, {:.4}, {:.4})",
+            text, hit.position.x, hit.position.y, hit.position.z
+        );
+        state.annotations.push(Annotation {
+            position: hit.position,
+            text,
+        });
+    }
+}
+
+/// Draw a marker at every note
+fn draw_annotation_markers(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    state: Res<AnnotationState>,
+    model_query: Query<&GlobalTransform, With<UserModelMesh>>,
+) {
+    if !state.visible || state.annotations.is_empty() {
+        return;
+    }
+    let model_to_world = model_matrix(&model_query);
+    for annotation in &state.annotations {
+        gizmos.sphere(
+            Isometry3d::from_translation(model_to_world.transform_point3(annotation.position)),
+            state.marker_radius,
+            MARKER_COLOR,
+        );
+    }
+}
+
+/// Draw the note texts at the projected marker positions
+fn draw_annotation_text(
+    mut contexts: EguiContexts,
+    state: Res<AnnotationState>,
+    ui_settings: Res<UiSettings>,
+    model_query: Query<&GlobalTransform, With<UserModelMesh>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<WorldModelCamera>>,
+) {
+    if !state.visible || state.annotations.is_empty() {
+        return;
+    }
+    let Some((camera, camera_transform)) = camera_query.iter().next() else {
+        return;
+    };
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let model_to_world = model_matrix(&model_query);
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Foreground,
+        egui::Id::new("annotations"),
+    ));
+    let screen = ctx.screen_rect();
+    let font = egui::FontId::proportional(13.0);
+
+    for annotation in &state.annotations {
+        let Ok(screen_position) = camera.world_to_viewport(
+            camera_transform,
+            model_to_world.transform_point3(annotation.position),
+        ) else {
+            continue;
+        };
+        let position = ui_settings.ui_position(screen_position);
+        if !screen.contains(position) {
+            continue;
+        }
+        let galley = painter.layout_no_wrap(annotation.text.clone(), font.clone(), TEXT_COLOR);
+        let rect =
+            egui::Align2::LEFT_BOTTOM.anchor_size(position + egui::vec2(8.0, -8.0), galley.size());
+        painter.line_segment(
+            [position, rect.left_bottom()],
+            egui::Stroke::new(1.0, TEXT_COLOR),
+        );
+        painter.rect_filled(rect.expand(3.0), 3.0, egui::Color32::from_black_alpha(180));
+        painter.galley(rect.min, galley, TEXT_COLOR);
+    }
+}
+
+/// Annotation list panel
+fn annotation_panel(
+    mut contexts: EguiContexts,
+    mut interaction_mode: ResMut<InteractionMode>,
+    mut state: ResMut<AnnotationState>,
+    windows: Query<&Window>,
+) {
+    if *interaction_mode != InteractionMode::Annotate || windows.iter().next().is_none() {
+        return;
+    }
+
+    let mut annotations = state.annotations.clone();
+    let mut next_text = state.next_text.clone();
+    let mut visible = state.visible;
+
+    egui::Window::new("Annotations")
+        .default_width(280.0)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label("Click on the model to place a note.");
+            ui.horizontal(|ui| {
+                ui.label("Text:");
+                ui.add(egui::TextEdit::singleline(&mut next_text).hint_text("Note"));
+            });
+            ui.checkbox(&mut visible, "Show notes");
+            ui.separator();
+
+            if annotations.is_empty() {
+                ui.label("No notes yet.");
+            } else {
+                let mut removed = None;
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        for (index, annotation) in annotations.iter_mut().enumerate() {
+                            ui.horizontal(|ui| {
+                                ui.label(format!("{}.", index + 1));
+                                ui.text_edit_singleline(&mut annotation.text);
+                                if ui.small_button("x").clicked() {
+                                    removed = Some(index);
+                                }
+                            })
+                            .response
+                            .on_hover_text(format!(
+                                "({:.4}, {:.4}, {:.4})",
+                                annotation.position.x, annotation.position.y, annotation.position.z
+                            ));
+                        }
+                    });
+                if let Some(index) = removed {
+                    annotations.remove(index);
+                }
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(!annotations.is_empty(), egui::Button::new("Clear All"))
+                    .clicked()
+                {
+                    annotations.clear();
+                }
+                if ui.button("Close").clicked() {
+                    *interaction_mode = InteractionMode::Navigate;
+                }
+            });
+        });
+
+    if annotations != state.annotations {
+        state.annotations = annotations;
+    }
+    if next_text != state.next_text || visible != state.visible {
+        state.next_text = next_text;
+        state.visible = visible;
+    }
+}
@@ -0,0 +1,219 @@
+//! # Calculator Module
+//!
+//! This module derives new attributes from expressions over existing ones:
+//! - Expressions such as `sqrt(u*u + v*v)` or `pressure*2 - 101325`
+//! - Point or cell data, with scalar or vector results
+//! - Results are stored in the model geometry like any loaded attribute
+//!
+//! Open the panel via Mesh > Calculator.
+
+use crate::mesh::calculator::{add_derived_attribute, variable_names};
+use crate::mesh::AttributeLocation;
+use crate::ui::color_bar::apply_custom_color_mapping;
+use crate::ui::{ColorBarConfig, CurrentModelData, UserModelMesh};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Calculator settings
+#[derive(Resource)]
+pub struct CalculatorSettings {
+    /// Show the calculator panel
+    pub show_panel: bool,
+    /// Name of the result attribute
+    pub result_name: String,
+    /// Expression to evaluate
+    pub expression: String,
+    /// Evaluate over point or cell attributes
+    pub location: AttributeLocation,
+    /// Evaluation requested in the panel
+    evaluate: bool,
+    /// Outcome of the last evaluation, `Err` holds the error message
+    status: Option<Result<String, String>>,
+}
+
+impl Default for CalculatorSettings {
+    fn default() -> Self {
+        Self {
+            show_panel: false,
+            result_name: "Result".to_string(),
+            expression: String::new(),
+            location: AttributeLocation::Point,
+            evaluate: false,
+            status: None,
+        }
+    }
+}
+
+pub struct CalculatorPlugin;
+
+impl Plugin for CalculatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CalculatorSettings>().add_systems(
+            Update,
+            (
+                calculator_panel.after(EguiSet::InitContexts),
+                evaluate_expression,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Calculator panel
+fn calculator_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<CalculatorSettings>,
+    current_model: Res<CurrentModelData>,
+    windows: Query<&Window>,
+) {
+    if !settings.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let mut result_name = settings.result_name.clone();
+    let mut expression = settings.expression.clone();
+    let mut location = settings.location.clone();
+    let mut evaluate = false;
+    let mut open = true;
+
+    egui::Window::new("Calculator")
+        .default_width(320.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            let Some(geometry) = current_model.geometry.as_ref() else {
+                ui.label("Load a model to derive attributes from.");
+                return;
+            };
+
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut location, AttributeLocation::Point, "Point Data");
+                ui.radio_value(&mut location, AttributeLocation::Cell, "Cell Data");
+            });
+            ui.horizontal(|ui| {
+                ui.label("Result:");
+                ui.text_edit_singleline(&mut result_name);
+            });
+            ui.label("Expression:");
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut expression)
+                    .hint_text("sqrt(u*u + v*v)")
+                    .desired_width(f32::INFINITY),
+            );
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                evaluate = true;
+            }
+
+            // Clicking a variable appends it to the expression
+            ui.label("Variables:");
+            ui.horizontal_wrapped(|ui| {
+                let mut names = variable_names(geometry, &location);
+                names.push("coords".to_string());
+                for name in names {
+                    if ui.small_button(&name).clicked() {
+                        if name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                            expression.push_str(&name);
+                        } else {
+                            expression.push_str(&format!("\"{}\"", name));
+                        }
+                    }
+                }
+            });
+            ui.label(
+                egui::RichText::new(
+                    "+ - * / ^, sqrt abs exp ln log10 sin cos tan min max pow, \
+                     mag norm dot cross vec, v.x v.y v.z, pi e",
+                )
+                .small()
+                .weak(),
+            );
+
+            ui.separator();
+            if ui
+                .add_enabled(
+                    !expression.trim().is_empty() && !result_name.trim().is_empty(),
+                    egui::Button::new("Evaluate"),
+                )
+                .clicked()
+            {
+                evaluate = true;
+            }
+            match &settings.status {
+                Some(Ok(message)) => {
+                    ui.colored_label(egui::Color32::LIGHT_GREEN, message);
+                }
+                Some(Err(message)) => {
+                    ui.colored_label(egui::Color32::LIGHT_RED, message);
+                }
+                None => {}
+            }
+        });
+
+    if result_name != settings.result_name {
+        settings.result_name = result_name;
+    }
+    if expression != settings.expression {
+        settings.expression = expression;
+    }
+    if location != settings.location {
+        settings.location = location;
+    }
+    if evaluate {
+        settings.evaluate = true;
+    }
+
+    if !open {
+        settings.show_panel = false;
+    }
+}
+
+/// Evaluate the expression and store the result in the model geometry
+fn evaluate_expression(
+    mut settings: ResMut<CalculatorSettings>,
+    mut current_model: ResMut<CurrentModelData>,
+    color_bar_config: Res<ColorBarConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    model_entities: Query<&Mesh3d, With<UserModelMesh>>,
+) {
+    if !settings.evaluate {
+        return;
+    }
+    settings.evaluate = false;
+    let Some(geometry) = current_model.geometry.as_mut() else {
+        return;
+    };
+
+    let name = settings.result_name.trim().to_string();
+    let result = add_derived_attribute(
+        geometry,
+        &name,
+        &settings.expression,
+        settings.location.clone(),
+    );
+    settings.status = Some(match result {
+        Ok(()) => {
+            println!(
+                "Calculator: {:?} attribute '{}' = {}",
+                settings.location, name, settings.expression
+            );
+            Ok(format!(
+                "Created {:?} attribute '{}'",
+                settings.location, name
+            ))
+        }
+        Err(e) => {
+            println!("Calculator error: {}", e);
+            Err(e.to_string())
+        }
+    });
+
+    // Refresh the colors, the new attribute may be the one shown
+    if let Some(mesh) = model_entities
+        .get_single()
+        .ok()
+        .and_then(|mesh3d| meshes.get_mut(&mesh3d.0))
+    {
+        if let Err(e) = apply_custom_color_mapping(geometry, mesh, &color_bar_config) {
+            println!("Failed to apply color mapping after calculation: {:?}", e);
+        }
+    }
+}
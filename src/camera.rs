@@ -6,6 +6,8 @@
 //! - Keyboard movement control
 //! - Mouse wheel zoom
 //! - Automatic focus on loaded models
+//! - Split-view cameras, controlled while the cursor is over their viewport,
+//!   optionally linked so that all viewports share one view
 //!
 //! ## Control Scheme
 //!
@@ -21,12 +23,15 @@
 //! - Right-click drag: Rotate view
 //! - Scroll wheel: Zoom
 
+use crate::preferences::{Action, KeyBindings};
 use crate::ui::ModelLoadedEvent;
+use crate::viewports::ViewportSettings;
 use bevy::input::{
     mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll},
     ButtonInput,
 };
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
 
 /// Camera movement speed (units per second)
 const MOVEMENT_SPEED: f32 = 5.0;
@@ -39,7 +44,7 @@ const CAMERA_DISTANCE_FACTOR: f32 = 2.0;
 ///
 /// This component is used to identify the main camera in the scene for rendering 3D models and scenes.
 #[derive(Debug, Component)]
-struct WorldModelCamera;
+pub struct WorldModelCamera;
 
 /// Camera rotation controller
 ///
@@ -85,6 +90,50 @@ impl Default for CameraRotationController {
     }
 }
 
+/// Camera of an additional split-view viewport
+///
+/// Holds the viewport index; index 0 is the main [`WorldModelCamera`].
+#[derive(Debug, Component)]
+pub struct ViewportCamera(pub usize);
+
+/// Components of a split-view camera starting with the view of `transform`
+///
+/// The camera controller steers the camera while the cursor is over its
+/// viewport.
+///
+/// # Parameters
+/// * `index` - Viewport index, 1 or higher
+/// * `transform` - Initial camera transform
+pub fn viewport_camera(index: usize, transform: Transform) -> impl Bundle {
+    let (yaw, pitch, _) = transform.rotation.to_euler(EulerRot::YXZ);
+    (
+        ViewportCamera(index),
+        CameraRotationController {
+            yaw,
+            pitch,
+            ..default()
+        },
+        Camera3d::default(),
+        transform,
+    )
+}
+
+/// Event to move the camera to a stored view
+#[derive(Event)]
+pub struct SetCameraViewEvent {
+    pub translation: Vec3,
+    pub rotation: Quat,
+}
+
+/// Camera entity, transform, controller, camera and whether it is the main camera
+type ControlledCamera = (
+    Entity,
+    &'static mut Transform,
+    &'static mut CameraRotationController,
+    &'static Camera,
+    Has<WorldModelCamera>,
+);
+
 /// Camera control plugin
 ///
 /// Responsible for registering camera-related systems to the Bevy app, including:
@@ -100,9 +149,16 @@ impl Plugin for CameraPlugin {
     /// 1. Startup: Spawn camera
     /// 2. Update: Camera control and model focusing
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_camera)
+        app.add_event::<SetCameraViewEvent>()
+            .add_systems(Startup, spawn_camera)
             .add_systems(Update, camera_controller)
-            .add_systems(Update, focus_camera_on_model);
+            .add_systems(Update, (focus_camera_on_model, apply_camera_view).chain())
+            .add_systems(
+                Update,
+                sync_linked_cameras
+                    .after(camera_controller)
+                    .after(apply_camera_view),
+            );
     }
 }
 
@@ -206,6 +262,31 @@ fn focus_camera_on_model(
     }
 }
 
+/// Moves the camera to views requested by [`SetCameraViewEvent`]
+///
+/// Runs after the model focusing, so a stored view wins over the automatic focus.
+/// The rotation controller angles are derived from the rotation so that the
+/// view is kept by the camera controller.
+fn apply_camera_view(
+    mut view_events: EventReader<SetCameraViewEvent>,
+    mut camera_query: Query<
+        (&mut Transform, &mut CameraRotationController),
+        With<WorldModelCamera>,
+    >,
+) {
+    for event in view_events.read() {
+        if let Ok((mut camera_transform, mut rotation_controller)) = camera_query.get_single_mut() {
+            camera_transform.translation = event.translation;
+            camera_transform.rotation = event.rotation;
+
+            // The controller composes yaw around Y with pitch around X
+            let (yaw, pitch, _) = event.rotation.to_euler(EulerRot::YXZ);
+            rotation_controller.yaw = yaw;
+            rotation_controller.pitch = pitch;
+        }
+    }
+}
+
 /// Camera control system
 ///
 /// Handles all camera input and movement logic, including:
@@ -213,6 +294,9 @@ fn focus_camera_on_model(
 /// ## Keyboard Movement Controls
 /// - WASD/Arrow keys: Forward, left, backward, right movement
 /// - QE: Up and down movement
+/// - R: Reset the camera
+///
+/// The keys are the defaults of the configurable key bindings.
 ///
 /// ## Mouse Controls
 /// - Scroll wheel: Forward/backward zoom
@@ -220,20 +304,49 @@ fn focus_camera_on_model(
 ///
 /// # Parameters
 /// * `keyboard_input` - Keyboard input state
+/// * `key_bindings` - Keyboard shortcuts of the movement and reset actions
 /// * `mouse_button_input` - Mouse button input state  
 /// * `accumulated_mouse_motion` - Accumulated mouse movement delta
 /// * `accumulated_mouse_scroll` - Accumulated mouse scroll input
 /// * `controller_query` - Query for camera transform and rotation controller
+/// * `windows` - Primary window, for the cursor position
 /// * `time` - Time resource for frame-rate independent movement
+#[allow(clippy::too_many_arguments)]
 fn camera_controller(
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
     mouse_button_input: Res<ButtonInput<MouseButton>>,
     accumulated_mouse_motion: Res<AccumulatedMouseMotion>,
     accumulated_mouse_scroll: Res<AccumulatedMouseScroll>,
-    mut controller_query: Query<(&mut Transform, &mut CameraRotationController), With<Camera3d>>,
+    mut controller_query: Query<ControlledCamera, With<Camera3d>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
     time: Res<Time>,
 ) {
-    if let Ok((mut transform, mut rotation_controller)) = controller_query.get_single_mut() {
+    // With split views, the camera of the viewport under the cursor is steered
+    let cursor = windows
+        .get_single()
+        .ok()
+        .and_then(|window| window.physical_cursor_position());
+    let active = controller_query
+        .iter()
+        .find(|(_, _, _, camera, _)| {
+            let (Some(cursor), Some(viewport)) = (cursor, camera.viewport.as_ref()) else {
+                return false;
+            };
+            let min = viewport.physical_position.as_vec2();
+            let max = min + viewport.physical_size.as_vec2();
+            cursor.cmpge(min).all() && cursor.cmplt(max).all()
+        })
+        .or_else(|| {
+            controller_query
+                .iter()
+                .find(|(_, _, _, _, is_main)| *is_main)
+        })
+        .map(|(entity, ..)| entity);
+
+    if let Some((_, mut transform, mut rotation_controller, _, _)) =
+        active.and_then(|entity| controller_query.get_mut(entity).ok())
+    {
         let mut movement = Vec3::ZERO;
 
         // check if Shift key is pressed for fast movement
@@ -243,27 +356,27 @@ fn camera_controller(
 
         // Translation controls
         // Keyboard input
-        if keyboard_input.pressed(KeyCode::KeyW) || keyboard_input.pressed(KeyCode::ArrowUp) {
+        if key_bindings.pressed(&keyboard_input, Action::MoveForward) {
             movement += transform.forward() * MOVEMENT_SPEED * movement_multiplier;
         }
 
-        if keyboard_input.pressed(KeyCode::KeyA) || keyboard_input.pressed(KeyCode::ArrowLeft) {
+        if key_bindings.pressed(&keyboard_input, Action::MoveLeft) {
             movement += transform.left() * MOVEMENT_SPEED * movement_multiplier;
         }
 
-        if keyboard_input.pressed(KeyCode::KeyS) || keyboard_input.pressed(KeyCode::ArrowDown) {
+        if key_bindings.pressed(&keyboard_input, Action::MoveBackward) {
             movement += transform.back() * MOVEMENT_SPEED * movement_multiplier;
         }
 
-        if keyboard_input.pressed(KeyCode::KeyD) || keyboard_input.pressed(KeyCode::ArrowRight) {
+        if key_bindings.pressed(&keyboard_input, Action::MoveRight) {
             movement += transform.right() * MOVEMENT_SPEED * movement_multiplier;
         }
 
-        if keyboard_input.pressed(KeyCode::KeyQ) {
+        if key_bindings.pressed(&keyboard_input, Action::MoveUp) {
             movement += transform.up() * MOVEMENT_SPEED * movement_multiplier;
         }
 
-        if keyboard_input.pressed(KeyCode::KeyE) {
+        if key_bindings.pressed(&keyboard_input, Action::MoveDown) {
             movement += transform.down() * MOVEMENT_SPEED * movement_multiplier;
         }
 
@@ -290,7 +403,7 @@ fn camera_controller(
         }
 
         // Reset camera position - Press R to reset camera to default position
-        if keyboard_input.just_pressed(KeyCode::KeyR) {
+        if key_bindings.just_pressed(&keyboard_input, Action::ResetCamera) {
             transform.translation = Vec3::new(10.0, 10.0, 10.0);
             transform.look_at(Vec3::ZERO, Vec3::Y);
 
@@ -327,3 +440,51 @@ fn camera_controller(
         transform.rotation = yaw_rotation * pitch_rotation;
     }
 }
+
+/// Copy the view of the camera that moved to all other cameras while the
+/// viewport cameras are linked
+///
+/// The main camera wins when several cameras moved, so stored views and model
+/// focusing reach every viewport. Turning the link on aligns all viewports with
+/// the main camera.
+///
+/// # Parameters
+/// * `settings` - Split view settings with the link toggle
+/// * `cameras` - Query for the transform and rotation controller of every camera
+fn sync_linked_cameras(
+    settings: Res<ViewportSettings>,
+    mut cameras: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut CameraRotationController,
+            Has<WorldModelCamera>,
+        ),
+        With<Camera3d>,
+    >,
+) {
+    if !settings.link_cameras || !settings.is_split() {
+        return;
+    }
+
+    let mut source = None;
+    for (entity, transform, controller, is_main) in cameras.iter_mut() {
+        let moved = transform.is_changed() || (is_main && settings.is_changed());
+        if moved && (source.is_none() || is_main) {
+            source = Some((entity, *transform, controller.yaw, controller.pitch));
+        }
+    }
+    let Some((source_entity, source_transform, yaw, pitch)) = source else {
+        return;
+    };
+
+    for (entity, mut transform, mut controller, _) in cameras.iter_mut() {
+        if entity == source_entity {
+            continue;
+        }
+        transform.translation = source_transform.translation;
+        transform.rotation = source_transform.rotation;
+        controller.yaw = yaw;
+        controller.pitch = pitch;
+    }
+}
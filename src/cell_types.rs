@@ -0,0 +1,232 @@
+//! # Cell Types Module
+//!
+//! This module extracts the cells of selected VTK cell types:
+//! - The panel lists the cell types of the model with their cell counts
+//! - The checked types are extracted into a separate entity (e.g. only the
+//!   triangles, only the quadratic triangles or only the tetrahedra surfaces)
+//! - The rest of the model can be hidden while the extraction is shown
+//!
+//! The extraction is a child of the model, so it follows the model transform and
+//! is removed with the model. Open the panel via Mesh > Extract Cells by Type.
+
+use crate::mesh;
+use crate::mesh::cell_types::{cell_type_counts, cell_type_name, extract_cell_types};
+use crate::ui::color_bar::apply_custom_color_mapping;
+use crate::ui::{ColorBarConfig, CurrentModelData, ModelLoadedEvent, UserModelMesh};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Cell type extraction settings
+#[derive(Resource)]
+pub struct CellTypeSettings {
+    /// Show the extraction panel
+    pub show_panel: bool,
+    /// VTK cell type ids to extract
+    pub types: BTreeSet<u8>,
+    /// Hide the model while the extraction is shown
+    pub hide_model: bool,
+    /// Extraction requested in the panel
+    extract: bool,
+    /// Removal of the extraction requested in the panel
+    clear: bool,
+    /// Cell count of every type of the current model
+    counts: Option<BTreeMap<u8, usize>>,
+    /// Triangles of the current extraction, `None` without extraction
+    triangles: Option<usize>,
+}
+
+impl Default for CellTypeSettings {
+    fn default() -> Self {
+        Self {
+            show_panel: false,
+            types: BTreeSet::new(),
+            hide_model: true,
+            extract: false,
+            clear: false,
+            counts: None,
+            triangles: None,
+        }
+    }
+}
+
+/// Marker component of the extracted cells entity
+#[derive(Component)]
+pub struct CellTypeExtraction;
+
+pub struct CellTypePlugin;
+
+impl Plugin for CellTypePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CellTypeSettings>().add_systems(
+            Update,
+            (
+                reset_on_model_load,
+                cell_type_panel.after(EguiSet::InitContexts),
+                update_extraction,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Types and extraction of the previous model no longer apply
+fn reset_on_model_load(
+    mut loaded_events: EventReader<ModelLoadedEvent>,
+    mut settings: ResMut<CellTypeSettings>,
+) {
+    if loaded_events.read().count() > 0 {
+        settings.counts = None;
+        settings.types.clear();
+        settings.triangles = None;
+    }
+}
+
+/// Cell type panel
+fn cell_type_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<CellTypeSettings>,
+    current_model: Res<CurrentModelData>,
+    windows: Query<&Window>,
+) {
+    if !settings.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+    let Some(ref geometry) = current_model.geometry else {
+        settings.counts = None;
+        return;
+    };
+    // Filters may replace the cells of the model
+    if settings.counts.is_none() || current_model.is_changed() {
+        settings.counts = Some(cell_type_counts(geometry));
+    }
+
+    let mut types = settings.types.clone();
+    let mut hide_model = settings.hide_model;
+    let mut extract = false;
+    let mut clear = false;
+    let mut open = true;
+
+    egui::Window::new("Extract Cells by Type")
+        .default_width(280.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            if geometry.cell_types.is_none() {
+                ui.label("Cell types inferred from the triangles of each cell.");
+            }
+            for (&cell_type, &count) in settings.counts.iter().flatten() {
+                let mut checked = types.contains(&cell_type);
+                let label = format!("{} ({} cells)", cell_type_name(cell_type), count);
+                if ui.checkbox(&mut checked, label).changed() {
+                    if checked {
+                        types.insert(cell_type);
+                    } else {
+                        types.remove(&cell_type);
+                    }
+                }
+            }
+
+            ui.separator();
+            ui.checkbox(&mut hide_model, "Hide the rest of the model");
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(!types.is_empty(), egui::Button::new("Extract"))
+                    .clicked()
+                {
+                    extract = true;
+                }
+                if ui
+                    .add_enabled(settings.triangles.is_some(), egui::Button::new("Clear"))
+                    .clicked()
+                {
+                    clear = true;
+                }
+            });
+            if let Some(triangles) = settings.triangles {
+                ui.label(format!("Extraction: {} triangles", triangles));
+            }
+        });
+
+    if types != settings.types {
+        settings.types = types;
+    }
+    if hide_model != settings.hide_model {
+        settings.hide_model = hide_model;
+        // Apply the visibility to the current extraction
+        extract |= settings.triangles.is_some();
+    }
+    if extract {
+        settings.extract = true;
+    }
+    if clear {
+        settings.clear = true;
+    }
+
+    if !open {
+        settings.show_panel = false;
+    }
+}
+
+/// Replace or remove the extraction entity
+#[allow(clippy::too_many_arguments)]
+fn update_extraction(
+    mut commands: Commands,
+    mut settings: ResMut<CellTypeSettings>,
+    current_model: Res<CurrentModelData>,
+    color_bar_config: Res<ColorBarConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut models: Query<(Entity, &mut Visibility), With<UserModelMesh>>,
+    extractions: Query<Entity, With<CellTypeExtraction>>,
+) {
+    if !settings.extract && !settings.clear {
+        return;
+    }
+    let extract = std::mem::take(&mut settings.extract) && !settings.clear;
+    settings.clear = false;
+    for entity in extractions.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    settings.triangles = None;
+    let Ok((model, mut visibility)) = models.get_single_mut() else {
+        return;
+    };
+    *visibility = Visibility::Inherited;
+
+    let Some(geometry) = current_model.geometry.as_ref().filter(|_| extract) else {
+        return;
+    };
+    let extracted = extract_cell_types(geometry, &settings.types);
+    let names: Vec<&str> = settings.types.iter().map(|&t| cell_type_name(t)).collect();
+    println!(
+        "Extracted {} triangles of cell types {}",
+        extracted.indices.len() / 3,
+        names.join(", ")
+    );
+    settings.triangles = Some(extracted.indices.len() / 3);
+
+    let mut mesh = mesh::create_mesh_from_geometry(&extracted);
+    if let Err(e) = apply_custom_color_mapping(&extracted, &mut mesh, &color_bar_config) {
+        println!("Failed to apply color mapping to extracted cells: {:?}", e);
+    }
+    if settings.hide_model {
+        *visibility = Visibility::Hidden;
+    }
+    commands
+        .spawn((
+            Name::new("Extracted Cells"),
+            Mesh3d(meshes.add(mesh)),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: Color::WHITE,
+                perceptual_roughness: 0.5,
+                cull_mode: None,
+                double_sided: true,
+                ..default()
+            })),
+            Transform::IDENTITY,
+            // Visible even while the model is hidden
+            Visibility::Visible,
+            CellTypeExtraction,
+        ))
+        .set_parent(model);
+}
@@ -0,0 +1,336 @@
+//! # Command Line Module
+//!
+//! This module runs subcommands that work without the interactive viewer:
+//! - `render <input> <output.png>`: Render a model file offscreen
+//! - `convert <input_dir> <output_dir>`: Convert every VTK file of a folder
+//!
+//! Options of `render`:
+//! - `--size WxH`: Image size (default 1280x720)
+//! - `--camera-position x,y,z`: Camera position (default: fitted to the model)
+//! - `--camera-target x,y,z`: Look-at target (default: model center)
+//! - `--fov DEGREES`: Vertical field of view (default 45)
+//! - `--color-map NAME`: Color map (default, viridis, hot, cool, warm)
+//!
+//! Options of `convert`:
+//! - `--format FORMAT`: Output format (glb, stl, vtu, vtk; default glb)
+//! - `--simplify RATIO`: Keep this ratio of triangles (0.1 - 1.0)
+//! - `--simplify-triangles N`: Keep at most N triangles
+//! - `--simplify-error ERROR`: Simplify while the QEM error stays below ERROR
+//! - `--subdivide N`: Subdivide N times (after simplification)
+//! - `--color-map NAME`: Color map baked into glTF vertex colors
+//!
+//! Without a subcommand the viewer starts as usual.
+
+use crate::lod::{simplify_mesh, SimplificationTarget};
+use crate::mesh::color_maps::{ColorMapper, ColorMappingConfig};
+use crate::mesh::{self, export, subdivision, GeometryData, VtkError};
+use crate::offscreen::{render_to_png, OffscreenCamera, OffscreenRenderRequest};
+use bevy::math::Vec3;
+use bevy::render::mesh::{Mesh, VertexAttributeValues};
+use std::path::{Path, PathBuf};
+
+/// Output formats of the `convert` subcommand
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ConvertFormat {
+    Glb,
+    Stl,
+    Vtu,
+    Vtk,
+}
+
+impl ConvertFormat {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value.to_ascii_lowercase().as_str() {
+            "gltf" | "glb" => Ok(Self::Glb),
+            "stl" => Ok(Self::Stl),
+            "vtu" => Ok(Self::Vtu),
+            "vtk" => Ok(Self::Vtk),
+            _ => Err(format!(
+                "unknown format '{}', expected glb, stl, vtu or vtk",
+                value
+            )),
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Glb => "glb",
+            Self::Stl => "stl",
+            Self::Vtu => "vtu",
+            Self::Vtk => "vtk",
+        }
+    }
+}
+
+/// Options of the `convert` subcommand
+struct ConvertOptions {
+    format: ConvertFormat,
+    simplify: Option<SimplificationTarget>,
+    subdivide: u32,
+    color_map: String,
+}
+
+/// Run a command line subcommand
+///
+/// # Parameters
+/// - `args`: Command line arguments without the program name
+///
+/// # Returns
+/// - `Some(code)`: A subcommand ran; the process should exit with `code`
+/// - `None`: No subcommand given, start the viewer
+pub fn run_command(args: &[String]) -> Option<i32> {
+    let (command, options) = args.split_first()?;
+
+    let result = match command.as_str() {
+        "render" => run_render(options),
+        "convert" => run_convert(options),
+        "help" | "--help" | "-h" => {
+            print_usage();
+            Ok(())
+        }
+        _ => Err(format!("unknown command '{}'", command)),
+    };
+
+    Some(match result {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            print_usage();
+            1
+        }
+    })
+}
+
+fn print_usage() {
+    println!("Usage:");
+    println!("  data_visualization                        Start the viewer");
+    println!("  data_visualization render <input> <output.png> [options]");
+    println!("      --size WxH  --camera-position x,y,z  --camera-target x,y,z");
+    println!("      --fov DEGREES  --color-map NAME");
+    println!("  data_visualization convert <input_dir> <output_dir> [options]");
+    println!("      --format glb|stl|vtu|vtk  --simplify RATIO  --subdivide N");
+    println!("      --simplify-triangles N  --simplify-error ERROR");
+    println!("      --color-map NAME");
+}
+
+/// `render` subcommand
+fn run_render(args: &[String]) -> Result<(), String> {
+    let mut positional = Vec::new();
+    let mut size = None;
+    let mut camera = OffscreenCamera::default();
+    let mut color_map = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if !arg.starts_with("--") {
+            positional.push(arg.as_str());
+            continue;
+        }
+        let value = iter
+            .next()
+            .ok_or_else(|| format!("missing value for {}", arg))?;
+        match arg.as_str() {
+            "--size" => size = Some(parse_size(value)?),
+            "--camera-position" => camera.position = Some(parse_vec3(value)?),
+            "--camera-target" => camera.target = Some(parse_vec3(value)?),
+            "--fov" => {
+                camera.fov_degrees = value
+                    .parse()
+                    .map_err(|_| format!("invalid field of view '{}'", value))?
+            }
+            "--color-map" => color_map = Some(value.clone()),
+            _ => return Err(format!("unknown option {}", arg)),
+        }
+    }
+
+    let [input, output] = positional[..] else {
+        return Err("render expects <input> and <output> paths".to_string());
+    };
+
+    let mut request = OffscreenRenderRequest::new(input).with_camera(camera);
+    if let Some((width, height)) = size {
+        request = request.with_size(width, height);
+    }
+    if let Some(color_map) = color_map {
+        request = request.with_color_map(color_map);
+    }
+
+    render_to_png(&request, Path::new(output)).map_err(|err| err.to_string())
+}
+
+/// `convert` subcommand
+fn run_convert(args: &[String]) -> Result<(), String> {
+    let mut positional = Vec::new();
+    let mut options = ConvertOptions {
+        format: ConvertFormat::Glb,
+        simplify: None,
+        subdivide: 0,
+        color_map: "default".to_string(),
+    };
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if !arg.starts_with("--") {
+            positional.push(arg.as_str());
+            continue;
+        }
+        let value = iter
+            .next()
+            .ok_or_else(|| format!("missing value for {}", arg))?;
+        match arg.as_str() {
+            "--format" => options.format = ConvertFormat::parse(value)?,
+            "--simplify" => {
+                let ratio: f32 = value
+                    .parse()
+                    .map_err(|_| format!("invalid simplification ratio '{}'", value))?;
+                if !(0.1..=1.0).contains(&ratio) {
+                    return Err("simplification ratio must be between 0.1 and 1.0".to_string());
+                }
+                options.simplify = Some(SimplificationTarget::Ratio(ratio));
+            }
+            "--simplify-triangles" => {
+                let count: usize = value
+                    .parse()
+                    .map_err(|_| format!("invalid triangle count '{}'", value))?;
+                options.simplify = Some(SimplificationTarget::TriangleCount(count));
+            }
+            "--simplify-error" => {
+                let max_error: f32 = value
+                    .parse()
+                    .map_err(|_| format!("invalid simplification error '{}'", value))?;
+                if !max_error.is_finite() || max_error < 0.0 {
+                    return Err("simplification error must be a non-negative number".to_string());
+                }
+                options.simplify = Some(SimplificationTarget::MaxError(max_error));
+            }
+            "--subdivide" => {
+                options.subdivide = value
+                    .parse()
+                    .map_err(|_| format!("invalid subdivision level '{}'", value))?
+            }
+            "--color-map" => options.color_map = value.clone(),
+            _ => return Err(format!("unknown option {}", arg)),
+        }
+    }
+
+    let [input_dir, output_dir] = positional[..] else {
+        return Err("convert expects <input_dir> and <output_dir> paths".to_string());
+    };
+
+    let mut inputs: Vec<PathBuf> = std::fs::read_dir(input_dir)
+        .map_err(|err| format!("failed to read {}: {}", input_dir, err))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && matches!(
+                    path.extension().and_then(|ext| ext.to_str()),
+                    Some("vtk" | "vtu" | "vtp" | "vts" | "vtr" | "vti")
+                )
+        })
+        .collect();
+    inputs.sort();
+    if inputs.is_empty() {
+        return Err(format!("no VTK files found in {}", input_dir));
+    }
+
+    std::fs::create_dir_all(output_dir)
+        .map_err(|err| format!("failed to create {}: {}", output_dir, err))?;
+
+    let mut failed = 0;
+    for input in &inputs {
+        let Some(stem) = input.file_stem() else {
+            continue;
+        };
+        let output = Path::new(output_dir)
+            .join(stem)
+            .with_extension(options.format.extension());
+        match convert_file(input, &output, &options) {
+            Ok(()) => println!("Converted {} -> {}", input.display(), output.display()),
+            Err(err) => {
+                eprintln!("Failed to convert {}: {}", input.display(), err);
+                failed += 1;
+            }
+        }
+    }
+
+    println!(
+        "Converted {} of {} files",
+        inputs.len() - failed,
+        inputs.len()
+    );
+    if failed > 0 {
+        return Err(format!("{} files failed to convert", failed));
+    }
+    Ok(())
+}
+
+/// Load, process and write a single file
+fn convert_file(input: &Path, output: &Path, options: &ConvertOptions) -> Result<(), VtkError> {
+    let mut geometry = mesh::load_geometry_file(input)?;
+
+    if let Some(target) = options.simplify {
+        geometry = simplify_mesh(&geometry, target)?;
+    }
+    for _ in 0..options.subdivide {
+        geometry = subdivision::subdivide_mesh(&geometry)?;
+    }
+
+    match options.format {
+        ConvertFormat::Glb => {
+            let colors = vertex_colors(&geometry, &options.color_map)?;
+            export::write_glb(&geometry, colors.as_deref(), output)
+        }
+        ConvertFormat::Stl => export::write_stl_binary(&geometry, output),
+        ConvertFormat::Vtu => export::write_vtu_ascii(&geometry, output),
+        ConvertFormat::Vtk => export::write_vtk_ascii(&geometry, output),
+    }
+}
+
+/// Vertex colors the viewer would show for the geometry
+///
+/// # Returns
+/// - `Ok(Some(colors))`: Linear RGBA color per vertex
+/// - `Ok(None)`: The geometry has no attribute to color by
+fn vertex_colors(
+    geometry: &GeometryData,
+    color_map: &str,
+) -> Result<Option<Vec<[f32; 4]>>, VtkError> {
+    let mut model_mesh = mesh::create_mesh_from_geometry(geometry);
+    let config = ColorMappingConfig {
+        color_map_name: color_map.to_string(),
+        ..Default::default()
+    };
+    ColorMapper::apply_scalar_attributes_with_color_map(geometry, &mut model_mesh, &config)?;
+
+    Ok(match model_mesh.attribute(Mesh::ATTRIBUTE_COLOR) {
+        // Vertices split at sharp edges follow the geometry vertices
+        Some(VertexAttributeValues::Float32x4(colors))
+            if colors.len() >= geometry.vertices.len() =>
+        {
+            Some(colors[..geometry.vertices.len()].to_vec())
+        }
+        _ => None,
+    })
+}
+
+/// Parse `WxH`
+fn parse_size(value: &str) -> Result<(u32, u32), String> {
+    let invalid = || format!("invalid size '{}', expected WxH", value);
+    let (width, height) = value.split_once(['x', 'X']).ok_or_else(invalid)?;
+    let width = width.trim().parse().map_err(|_| invalid())?;
+    let height = height.trim().parse().map_err(|_| invalid())?;
+    Ok((width, height))
+}
+
+/// Parse `x,y,z`
+fn parse_vec3(value: &str) -> Result<Vec3, String> {
+    let components = value
+        .split(',')
+        .map(|c| c.trim().parse::<f32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| format!("invalid vector '{}', expected x,y,z", value))?;
+    match components[..] {
+        [x, y, z] => Ok(Vec3::new(x, y, z)),
+        _ => Err(format!("invalid vector '{}', expected x,y,z", value)),
+    }
+}
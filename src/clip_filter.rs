@@ -0,0 +1,204 @@
+//! # Clip Filter Module
+//!
+//! This module cuts the model geometry itself, unlike the clipping planes that
+//! only hide fragments:
+//! - Plane mode removes the geometry on the normal side of the plane widget
+//! - Box mode removes the geometry outside the box widget
+//! - Triangles on the boundary are split and point attributes interpolated
+//!
+//! Applying the clip replaces the model geometry, so picking, selection,
+//! statistics and export work on the clipped model. The geometry before the
+//! first clip is kept and can be restored until another model is loaded.
+//! Open the panel via Mesh > Clip.
+
+use crate::mesh::clip::{clip_by_box, clip_by_plane};
+use crate::mesh::{self, GeometryData};
+use crate::ui::color_bar::apply_custom_color_mapping;
+use crate::ui::{ColorBarConfig, CurrentModelData, ModelLoadedEvent, UserModelMesh};
+use crate::widgets::WidgetState;
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Region removed by the clip filter
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ClipMode {
+    /// Normal side of the plane widget
+    #[default]
+    Plane,
+    /// Outside of the box widget
+    Box,
+}
+
+/// Requested clip operation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ClipAction {
+    Apply,
+    Restore,
+}
+
+/// Clip filter settings
+#[derive(Resource, Default)]
+pub struct ClipFilterSettings {
+    /// Show the clip panel
+    pub show_panel: bool,
+    /// Region removed when applying
+    pub mode: ClipMode,
+    /// Operation requested in the panel
+    action: Option<ClipAction>,
+    /// Model geometry before the first clip
+    original: Option<GeometryData>,
+}
+
+pub struct ClipFilterPlugin;
+
+impl Plugin for ClipFilterPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ClipFilterSettings>().add_systems(
+            Update,
+            (
+                forget_original_on_load,
+                clip_panel.after(EguiSet::InitContexts),
+                apply_clip,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Drop the stored original geometry when another model is loaded
+fn forget_original_on_load(
+    mut loaded_events: EventReader<ModelLoadedEvent>,
+    mut settings: ResMut<ClipFilterSettings>,
+) {
+    if loaded_events.read().count() > 0 {
+        settings.original = None;
+    }
+}
+
+/// Clip panel
+fn clip_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<ClipFilterSettings>,
+    mut widget_state: ResMut<WidgetState>,
+    current_model: Res<CurrentModelData>,
+    windows: Query<&Window>,
+) {
+    if !settings.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let mut mode = settings.mode;
+    let mut action = None;
+    let mut open = true;
+
+    egui::Window::new("Clip")
+        .default_width(260.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            let Some(geometry) = current_model.geometry.as_ref() else {
+                ui.label("Load a model to clip.");
+                return;
+            };
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut mode, ClipMode::Plane, "Plane");
+                ui.radio_value(&mut mode, ClipMode::Box, "Box");
+            });
+            ui.label(match mode {
+                ClipMode::Plane => "Removes the geometry on the normal side of the plane widget.",
+                ClipMode::Box => "Removes the geometry outside the box widget.",
+            });
+            let widget_enabled = match mode {
+                ClipMode::Plane => &mut widget_state.plane.enabled,
+                ClipMode::Box => &mut widget_state.box_widget.enabled,
+            };
+            ui.checkbox(widget_enabled, "Show widget");
+            ui.label(format!("{} triangles", geometry.indices.len() / 3));
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("Apply").clicked() {
+                    action = Some(ClipAction::Apply);
+                }
+                if ui
+                    .add_enabled(
+                        settings.original.is_some(),
+                        egui::Button::new("Restore Original"),
+                    )
+                    .clicked()
+                {
+                    action = Some(ClipAction::Restore);
+                }
+            });
+        });
+
+    if mode != settings.mode {
+        settings.mode = mode;
+    }
+    if action.is_some() {
+        settings.action = action;
+    }
+
+    if !open {
+        settings.show_panel = false;
+    }
+}
+
+/// Clip the model geometry or restore the original one
+fn apply_clip(
+    mut settings: ResMut<ClipFilterSettings>,
+    widget_state: Res<WidgetState>,
+    mut current_model: ResMut<CurrentModelData>,
+    color_bar_config: Res<ColorBarConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut model_entities: Query<&mut Mesh3d, With<UserModelMesh>>,
+) {
+    let Some(action) = settings.action.take() else {
+        return;
+    };
+    let Some(geometry) = current_model.geometry.as_ref() else {
+        return;
+    };
+    let Ok(mut mesh3d) = model_entities.get_single_mut() else {
+        println!("No model entity found for clipping");
+        return;
+    };
+
+    let clipped = match action {
+        ClipAction::Apply => {
+            let clipped = match settings.mode {
+                ClipMode::Plane => clip_by_plane(
+                    geometry,
+                    widget_state.plane.origin,
+                    widget_state.plane.normal,
+                ),
+                ClipMode::Box => clip_by_box(
+                    geometry,
+                    widget_state.box_widget.min,
+                    widget_state.box_widget.max,
+                ),
+            };
+            println!(
+                "Clipped model: {} -> {} triangles",
+                geometry.indices.len() / 3,
+                clipped.indices.len() / 3
+            );
+            if settings.original.is_none() {
+                settings.original = Some(geometry.clone());
+            }
+            clipped
+        }
+        ClipAction::Restore => {
+            let Some(original) = settings.original.take() else {
+                return;
+            };
+            println!("Restored model before clipping");
+            original
+        }
+    };
+
+    let mut new_mesh = mesh::create_mesh_from_geometry(&clipped);
+    if let Err(e) = apply_custom_color_mapping(&clipped, &mut new_mesh, &color_bar_config) {
+        println!("Failed to apply color mapping to clipped mesh: {:?}", e);
+    }
+    *mesh3d = Mesh3d(meshes.add(new_mesh));
+    current_model.geometry = Some(clipped);
+}
@@ -0,0 +1,228 @@
+//! # Clipping Planes Module
+//!
+//! This module clips the loaded model with up to six user planes:
+//! - Clipping happens per fragment in the model material, so moving a plane
+//!   never regenerates geometry
+//! - Every plane has its own enable toggle and a draggable plane widget
+//! - Geometry on the normal side of an enabled plane is removed
+//!
+//! Model materials are replaced by `ClipMaterial` when the model is spawned.
+//! Plane geometry lives in `WidgetState::clip_planes` (model space) and is
+//! converted to world space whenever the planes or the model transform change.
+//!
+//! Toggle the panel via View > Clipping Planes.
+
+use crate::picking::model_matrix;
+use crate::render::{create_clip_material, ClipMaterial, ClipPlanesUniform, MAX_CLIP_PLANES};
+use crate::ui::UserModelMesh;
+use crate::widgets::{vec3_editor, PlaneWidget, WidgetChangedEvent, WidgetKind, WidgetState};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Clipping panel settings
+#[derive(Resource, Default)]
+pub struct ClippingSettings {
+    /// Show the clipping planes panel
+    pub show_panel: bool,
+}
+
+/// World space planes currently uploaded to the clip materials
+#[derive(Resource, Default)]
+struct ClipState {
+    uniform: ClipPlanesUniform,
+}
+
+pub struct ClippingPlugin;
+
+impl Plugin for ClippingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(MaterialPlugin::<ClipMaterial>::default())
+            .init_resource::<ClippingSettings>()
+            .init_resource::<ClipState>()
+            .add_systems(
+                Update,
+                (
+                    clipping_panel.after(EguiSet::InitContexts),
+                    update_clip_planes,
+                    use_clip_materials,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Convert the enabled model space planes to the shader representation
+///
+/// # Parameters
+/// - `clip_planes`: Planes in model space
+/// - `model_to_world`: Model transform
+///
+/// # Returns
+/// - Uniform with world space planes `(normal, -dot(normal, origin))`
+fn world_clip_planes(clip_planes: &[PlaneWidget], model_to_world: Mat4) -> ClipPlanesUniform {
+    let normal_matrix = model_to_world.inverse().transpose();
+    let mut uniform = ClipPlanesUniform::default();
+
+    for clip_plane in clip_planes
+        .iter()
+        .filter(|p| p.enabled)
+        .take(MAX_CLIP_PLANES)
+    {
+        let origin = model_to_world.transform_point3(clip_plane.origin);
+        let Some(normal) = normal_matrix
+            .transform_vector3(clip_plane.normal)
+            .try_normalize()
+        else {
+            continue;
+        };
+        uniform.planes[uniform.count as usize] = normal.extend(-normal.dot(origin));
+        uniform.count += 1;
+    }
+
+    uniform
+}
+
+/// Upload the planes to all clip materials when they change
+fn update_clip_planes(
+    widget_state: Res<WidgetState>,
+    model_query: Query<&GlobalTransform, With<UserModelMesh>>,
+    mut clip_state: ResMut<ClipState>,
+    mut materials: ResMut<Assets<ClipMaterial>>,
+) {
+    let uniform = world_clip_planes(&widget_state.clip_planes, model_matrix(&model_query));
+    if uniform == clip_state.uniform {
+        return;
+    }
+
+    for (_, material) in materials.iter_mut() {
+        material.extension.clip_planes = uniform.clone();
+    }
+    clip_state.uniform = uniform;
+}
+
+/// Replace the standard materials of newly spawned model meshes by clip materials
+///
+/// glTF scenes are covered as well: their meshes are descendants of the model root.
+fn use_clip_materials(
+    mut commands: Commands,
+    new_meshes: Query<
+        (Entity, &MeshMaterial3d<StandardMaterial>),
+        Added<MeshMaterial3d<StandardMaterial>>,
+    >,
+    parents: Query<&Parent>,
+    models: Query<(), With<UserModelMesh>>,
+    standard_materials: Res<Assets<StandardMaterial>>,
+    mut clip_materials: ResMut<Assets<ClipMaterial>>,
+    clip_state: Res<ClipState>,
+) {
+    for (entity, material) in new_meshes.iter() {
+        let is_model =
+            models.contains(entity) || parents.iter_ancestors(entity).any(|a| models.contains(a));
+        if !is_model {
+            continue;
+        }
+        let Some(base) = standard_materials.get(&material.0) else {
+            println!("Clip material skipped: standard material not loaded");
+            continue;
+        };
+
+        let clip_material = clip_materials.add(create_clip_material(
+            base.clone(),
+            clip_state.uniform.clone(),
+        ));
+        commands
+            .entity(entity)
+            .remove::<MeshMaterial3d<StandardMaterial>>()
+            .insert(MeshMaterial3d(clip_material));
+    }
+}
+
+/// Clipping planes panel
+fn clipping_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<ClippingSettings>,
+    mut widget_state: ResMut<WidgetState>,
+    mut widget_changed_events: EventWriter<WidgetChangedEvent>,
+    windows: Query<&Window>,
+) {
+    if !settings.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let mut clip_planes = widget_state.clip_planes.clone();
+    let mut show_widgets = widget_state.show_clip_widgets;
+    let mut add_plane = false;
+    let mut remove_plane = None;
+    let mut changed = Vec::new();
+    let speed = (widget_state.scale * 0.005).max(1e-4) as f64;
+    let mut open = true;
+
+    egui::Window::new("Clipping Planes")
+        .default_width(240.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(
+                        clip_planes.len() < MAX_CLIP_PLANES,
+                        egui::Button::new("Add Plane"),
+                    )
+                    .clicked()
+                {
+                    add_plane = true;
+                }
+                ui.label(format!("{} / {}", clip_planes.len(), MAX_CLIP_PLANES));
+            });
+            ui.checkbox(&mut show_widgets, "Show plane widgets");
+            ui.label("Geometry on the normal side is clipped away.");
+
+            for (index, clip_plane) in clip_planes.iter_mut().enumerate() {
+                ui.separator();
+                ui.push_id(index, |ui| {
+                    let mut plane_changed = false;
+                    ui.horizontal(|ui| {
+                        plane_changed |= ui
+                            .checkbox(&mut clip_plane.enabled, format!("Plane {}", index + 1))
+                            .changed();
+                        if ui.button("Flip").clicked() {
+                            clip_plane.normal = -clip_plane.normal;
+                            plane_changed = true;
+                        }
+                        if ui.button("Remove").clicked() {
+                            remove_plane = Some(index);
+                        }
+                    });
+                    plane_changed |= vec3_editor(ui, "Origin", &mut clip_plane.origin, speed);
+                    if vec3_editor(ui, "Normal", &mut clip_plane.normal, 0.01) {
+                        clip_plane.normal = clip_plane.normal.try_normalize().unwrap_or(Vec3::X);
+                        plane_changed = true;
+                    }
+                    if plane_changed {
+                        changed.push(WidgetKind::ClipPlane(index));
+                    }
+                });
+            }
+        });
+
+    if !changed.is_empty() || show_widgets != widget_state.show_clip_widgets {
+        widget_state.clip_planes = clip_planes;
+        widget_state.show_clip_widgets = show_widgets;
+    }
+
+    if let Some(index) = remove_plane {
+        widget_state.remove_clip_plane(index);
+        println!("Removed clip plane {}", index + 1);
+    }
+
+    if add_plane && widget_state.add_clip_plane() {
+        println!("Added clip plane {}", widget_state.clip_planes.len());
+    }
+
+    for kind in changed {
+        widget_changed_events.send(WidgetChangedEvent(kind));
+    }
+
+    if !open {
+        settings.show_panel = false;
+    }
+}
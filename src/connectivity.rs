@@ -0,0 +1,216 @@
+//! # Connectivity Module
+//!
+//! This module finds the connected regions of the model:
+//! - Cells sharing a point belong to the same region; region 0 is the largest
+//! - Region ids are stored as the cell scalar `RegionId` for coloring and export
+//! - Regions can be hidden individually or isolated
+//!
+//! Hiding only changes the displayed mesh; the model geometry keeps all regions.
+//! Open the panel via Mesh > Connectivity.
+
+use crate::mesh::connectivity::{
+    add_region_attribute, connected_regions, without_regions, Regions,
+};
+use crate::mesh::{self, GeometryData};
+use crate::ui::color_bar::apply_custom_color_mapping;
+use crate::ui::{ColorBarConfig, CurrentModelData, ModelLoadedEvent, UserModelMesh};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+use std::collections::BTreeSet;
+
+/// Regions listed in the panel at most
+const MAX_LISTED_REGIONS: usize = 200;
+
+/// Connectivity settings
+#[derive(Resource, Default)]
+pub struct ConnectivitySettings {
+    /// Show the connectivity panel
+    pub show_panel: bool,
+    /// Region labeling requested in the panel
+    compute: bool,
+    /// Regions of the model
+    regions: Option<Regions>,
+    /// Regions left out of the displayed mesh
+    hidden: BTreeSet<usize>,
+    /// Hidden regions changed, the model mesh needs to be rebuilt
+    changed: bool,
+}
+
+pub struct ConnectivityPlugin;
+
+impl Plugin for ConnectivityPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ConnectivitySettings>().add_systems(
+            Update,
+            (
+                reset_on_model_load,
+                connectivity_panel.after(EguiSet::InitContexts),
+                compute_regions,
+                update_model_mesh,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Regions of the previous model no longer apply
+fn reset_on_model_load(
+    mut loaded_events: EventReader<ModelLoadedEvent>,
+    mut settings: ResMut<ConnectivitySettings>,
+) {
+    if loaded_events.read().count() > 0 {
+        settings.regions = None;
+        settings.hidden.clear();
+    }
+}
+
+/// Connectivity panel
+fn connectivity_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<ConnectivitySettings>,
+    current_model: Res<CurrentModelData>,
+    windows: Query<&Window>,
+) {
+    if !settings.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let mut compute = false;
+    let mut hidden = settings.hidden.clone();
+    let mut open = true;
+
+    egui::Window::new("Connectivity")
+        .default_width(260.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            if current_model.geometry.is_none() {
+                ui.label("Load a model to find its regions.");
+                return;
+            }
+            if ui.button("Label Regions").clicked() {
+                compute = true;
+            }
+            let Some(regions) = settings.regions.as_ref() else {
+                ui.label("Regions are stored as the cell scalar 'RegionId'.");
+                return;
+            };
+
+            ui.label(format!(
+                "{} regions, {} hidden",
+                regions.sizes.len(),
+                hidden.len()
+            ));
+            ui.horizontal(|ui| {
+                if ui.button("Show All").clicked() {
+                    hidden.clear();
+                }
+                if ui.button("Hide All").clicked() {
+                    hidden = (0..regions.sizes.len()).collect();
+                }
+            });
+            ui.separator();
+
+            egui::ScrollArea::vertical()
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    for (region, size) in regions.sizes.iter().enumerate().take(MAX_LISTED_REGIONS)
+                    {
+                        ui.horizontal(|ui| {
+                            let mut visible = !hidden.contains(&region);
+                            if ui
+                                .checkbox(&mut visible, format!("Region {}", region))
+                                .changed()
+                            {
+                                if visible {
+                                    hidden.remove(&region);
+                                } else {
+                                    hidden.insert(region);
+                                }
+                            }
+                            ui.label(format!("{} cells", size));
+                            if ui.small_button("Isolate").clicked() {
+                                hidden = (0..regions.sizes.len())
+                                    .filter(|&other| other != region)
+                                    .collect();
+                            }
+                        });
+                    }
+                    if regions.sizes.len() > MAX_LISTED_REGIONS {
+                        ui.label(format!(
+                            "{} smaller regions not listed",
+                            regions.sizes.len() - MAX_LISTED_REGIONS
+                        ));
+                    }
+                });
+        });
+
+    if compute {
+        settings.compute = true;
+    }
+    if hidden != settings.hidden {
+        settings.hidden = hidden;
+        settings.changed = true;
+    }
+
+    if !open {
+        settings.show_panel = false;
+    }
+}
+
+/// Label the regions and store them as cell scalar
+fn compute_regions(
+    mut settings: ResMut<ConnectivitySettings>,
+    mut current_model: ResMut<CurrentModelData>,
+) {
+    if !settings.compute {
+        return;
+    }
+    settings.compute = false;
+    let Some(geometry) = current_model.geometry.as_mut() else {
+        return;
+    };
+
+    let start = std::time::Instant::now();
+    let regions = connected_regions(geometry);
+    add_region_attribute(geometry, &regions);
+    println!(
+        "Found {} connected regions in {:?}",
+        regions.sizes.len(),
+        start.elapsed()
+    );
+    settings.regions = Some(regions);
+    settings.hidden.clear();
+    settings.changed = true;
+}
+
+/// Rebuild the model mesh without the hidden regions
+fn update_model_mesh(
+    mut settings: ResMut<ConnectivitySettings>,
+    current_model: Res<CurrentModelData>,
+    color_bar_config: Res<ColorBarConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut model_entities: Query<&mut Mesh3d, With<UserModelMesh>>,
+) {
+    if !settings.changed {
+        return;
+    }
+    settings.changed = false;
+    let (Some(geometry), Ok(mut mesh3d)) = (
+        current_model.geometry.as_ref(),
+        model_entities.get_single_mut(),
+    ) else {
+        return;
+    };
+
+    let filtered: Option<GeometryData> = settings
+        .regions
+        .as_ref()
+        .filter(|_| !settings.hidden.is_empty())
+        .map(|regions| without_regions(geometry, regions, &settings.hidden));
+    let shown = filtered.as_ref().unwrap_or(geometry);
+    let mut new_mesh = mesh::create_mesh_from_geometry(shown);
+    if let Err(e) = apply_custom_color_mapping(shown, &mut new_mesh, &color_bar_config) {
+        println!("Failed to apply color mapping to region mesh: {:?}", e);
+    }
+    *mesh3d = Mesh3d(meshes.add(new_mesh));
+}
@@ -0,0 +1,254 @@
+//! # Cube Axes Module
+//!
+//! This module annotates the model with cube axes:
+//! - A wire box around the model bounds, in the model frame, so it follows the
+//!   model transform
+//! - Tick marks at round values on one edge per axis, the edge furthest from
+//!   the box center on screen, so the labels stay outside the data
+//! - Numeric labels in data units next to the ticks and the axis name at the
+//!   middle of each labelled edge
+//! - Optional grid lines on the three box faces behind the model
+//!
+//! Configure the axes via View > Cube Axes.
+
+use crate::camera::WorldModelCamera;
+use crate::lod::bounding_box_corners;
+use crate::picking::model_matrix;
+use crate::preferences::UiSettings;
+use crate::ui::color_bar::format_legend_value;
+use crate::ui::{CurrentModelData, UserModelMesh};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Box and tick color
+const AXES_COLOR: Color = Color::srgb(0.85, 0.85, 0.85);
+/// Grid line color
+const GRID_COLOR: Color = Color::srgba(0.6, 0.6, 0.6, 0.35);
+/// Label color
+const LABEL_COLOR: egui::Color32 = egui::Color32::from_rgb(220, 220, 220);
+/// Tick length relative to the box diagonal
+const TICK_LENGTH_FACTOR: f32 = 0.02;
+const AXIS_NAMES: [&str; 3] = ["X", "Y", "Z"];
+
+/// Cube axes settings
+#[derive(Resource)]
+pub struct CubeAxesSettings {
+    /// Draw the cube axes
+    pub visible: bool,
+    /// Draw grid lines on the back faces
+    pub grid: bool,
+    /// Approximate number of ticks per axis
+    pub ticks: usize,
+    /// Draw the axis names
+    pub titles: bool,
+}
+
+impl Default for CubeAxesSettings {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            grid: false,
+            ticks: 5,
+            titles: true,
+        }
+    }
+}
+
+pub struct CubeAxesPlugin;
+
+impl Plugin for CubeAxesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CubeAxesSettings>()
+            .add_systems(Update, draw_cube_axes.after(EguiSet::InitContexts));
+    }
+}
+
+/// Round tick values inside a range
+///
+/// The step is 1, 2 or 5 times a power of ten, chosen to give about `target`
+/// ticks.
+///
+/// # Parameters
+/// - `min`, `max`: Value range
+/// - `target`: Approximate number of ticks
+///
+/// # Returns
+/// - Tick values with the step, `min` alone for an empty range
+pub fn nice_ticks(min: f32, max: f32, target: usize) -> (Vec<f32>, f32) {
+    let span = max - min;
+    if !span.is_finite() || span <= f32::EPSILON * min.abs().max(1.0) {
+        return (vec![min], 0.0);
+    }
+    let raw = span / target.max(1) as f32;
+    let magnitude = 10f32.powf(raw.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|&step| step >= raw)
+        .unwrap_or(10.0 * magnitude);
+
+    let first = (min / step).ceil() as i64;
+    let last = (max / step).floor() as i64;
+    let ticks = (first..=last).map(|index| index as f32 * step).collect();
+    (ticks, step)
+}
+
+/// Decimals needed to tell ticks with this step apart
+fn step_precision(step: f32) -> usize {
+    if step > 0.0 {
+        // The margin absorbs rounding of steps such as 0.0009999
+        (-(step.log10() + 1e-3).floor()).max(0.0) as usize
+    } else {
+        2
+    }
+}
+
+/// Model space point of the box with the coordinates of `sides` (false: min)
+fn box_point(min: Vec3, max: Vec3, sides: [bool; 3]) -> Vec3 {
+    Vec3::new(
+        if sides[0] { max.x } else { min.x },
+        if sides[1] { max.y } else { min.y },
+        if sides[2] { max.z } else { min.z },
+    )
+}
+
+/// Draw the box, ticks, grid and labels
+#[allow(clippy::too_many_arguments)]
+fn draw_cube_axes(
+    mut contexts: EguiContexts,
+    mut gizmos: Gizmos,
+    settings: Res<CubeAxesSettings>,
+    ui_settings: Res<UiSettings>,
+    current_model: Res<CurrentModelData>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<WorldModelCamera>>,
+    model_query: Query<&GlobalTransform, With<UserModelMesh>>,
+    mut bounds: Local<Option<(Vec3, Vec3)>>,
+) {
+    if current_model.is_changed() {
+        *bounds = current_model
+            .geometry
+            .as_ref()
+            .and_then(|geometry| bounding_box_corners(&geometry.vertices));
+    }
+    if !settings.visible {
+        return;
+    }
+    let (Some((min, max)), Some((camera, camera_transform))) =
+        (*bounds, camera_query.iter().next())
+    else {
+        return;
+    };
+
+    let model_to_world = model_matrix(&model_query);
+    let world = |point: Vec3| model_to_world.transform_point3(point);
+    let center = (min + max) * 0.5;
+    let tick_length = (max - min).length().max(1e-6) * TICK_LENGTH_FACTOR;
+
+    // Box edges
+    for axis in 0..3 {
+        for side in 0..4 {
+            let mut sides = [false; 3];
+            sides[(axis + 1) % 3] = side & 1 != 0;
+            sides[(axis + 2) % 3] = side & 2 != 0;
+            let start = box_point(min, max, sides);
+            sides[axis] = true;
+            gizmos.line(world(start), world(box_point(min, max, sides)), AXES_COLOR);
+        }
+    }
+
+    let ticks: [(Vec<f32>, f32); 3] =
+        std::array::from_fn(|axis| nice_ticks(min[axis], max[axis], settings.ticks));
+
+    // Grid lines on the faces the camera looks at from the inside
+    if settings.grid {
+        let camera_in_model = model_to_world
+            .inverse()
+            .transform_point3(camera_transform.translation());
+        for normal in 0..3 {
+            let at_max = camera_in_model[normal] < center[normal];
+            for axis in [(normal + 1) % 3, (normal + 2) % 3] {
+                let across = 3 - normal - axis;
+                for &value in &ticks[axis].0 {
+                    let mut start = min;
+                    start[normal] = if at_max { max[normal] } else { min[normal] };
+                    start[axis] = value;
+                    let mut end = start;
+                    end[across] = max[across];
+                    gizmos.line(world(start), world(end), GRID_COLOR);
+                }
+            }
+        }
+    }
+
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let to_screen = |point: Vec3| {
+        camera
+            .world_to_viewport(camera_transform, world(point))
+            .ok()
+            .map(|p| ui_settings.ui_position(p))
+    };
+    let Some(screen_center) = to_screen(center) else {
+        return;
+    };
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Background,
+        egui::Id::new("cube_axes"),
+    ));
+    let font = egui::FontId::proportional(12.0);
+
+    for axis in 0..3 {
+        let (first, second) = ((axis + 1) % 3, (axis + 2) % 3);
+
+        // Label the edge furthest from the box center on screen
+        let Some((outward, _)) = (0..4)
+            .filter_map(|side| {
+                let mut sides = [false; 3];
+                sides[first] = side & 1 != 0;
+                sides[second] = side & 2 != 0;
+                let mut middle = box_point(min, max, sides);
+                middle[axis] = center[axis];
+                let distance = to_screen(middle)?.distance(screen_center);
+                Some((middle, distance))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+        else {
+            continue;
+        };
+        let mut direction = outward - center;
+        direction[axis] = 0.0;
+        let direction = direction.normalize_or_zero();
+
+        let (values, step) = &ticks[axis];
+        let precision = step_precision(*step);
+        for &value in values {
+            let mut position = outward;
+            position[axis] = value;
+            let tick_end = position + direction * tick_length;
+            gizmos.line(world(position), world(tick_end), AXES_COLOR);
+
+            if let Some(label) = to_screen(position + direction * tick_length * 2.0) {
+                painter.text(
+                    label,
+                    egui::Align2::CENTER_CENTER,
+                    format_legend_value(value, precision, ""),
+                    font.clone(),
+                    LABEL_COLOR,
+                );
+            }
+        }
+
+        if settings.titles {
+            if let Some(title) = to_screen(outward + direction * tick_length * 5.0) {
+                painter.text(
+                    title,
+                    egui::Align2::CENTER_CENTER,
+                    AXIS_NAMES[axis],
+                    egui::FontId::proportional(14.0),
+                    LABEL_COLOR,
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,158 @@
+//! # Data Conversion Module
+//!
+//! This module converts attributes between point and cell data:
+//! - Cell data is averaged to the points using each cell
+//! - Point data is averaged over the corners of each cell
+//! - Converted attributes are stored next to the original ones
+//!
+//! Open the panel via Mesh > Cell/Point Data.
+
+use crate::mesh::conversion::convert_attribute;
+use crate::mesh::AttributeLocation;
+use crate::ui::color_bar::apply_custom_color_mapping;
+use crate::ui::{ColorBarConfig, CurrentModelData, UserModelMesh};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Data conversion settings
+#[derive(Resource, Default)]
+pub struct DataConversionSettings {
+    /// Show the conversion panel
+    pub show_panel: bool,
+    /// Attribute to convert, requested in the panel
+    request: Option<(String, AttributeLocation)>,
+    /// Outcome of the last conversion, `Err` holds the error message
+    status: Option<Result<String, String>>,
+}
+
+pub struct DataConversionPlugin;
+
+impl Plugin for DataConversionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DataConversionSettings>().add_systems(
+            Update,
+            (
+                conversion_panel.after(EguiSet::InitContexts),
+                convert_requested_attribute,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Conversion panel
+fn conversion_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<DataConversionSettings>,
+    current_model: Res<CurrentModelData>,
+    windows: Query<&Window>,
+) {
+    if !settings.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let mut request = None;
+    let mut open = true;
+
+    egui::Window::new("Cell/Point Data")
+        .default_width(280.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            let Some(attributes) = current_model
+                .geometry
+                .as_ref()
+                .and_then(|geometry| geometry.attributes.as_ref())
+            else {
+                ui.label("Load a model with attributes.");
+                return;
+            };
+            let mut names: Vec<&(String, AttributeLocation)> = attributes
+                .keys()
+                .filter(|(name, _)| !name.starts_with("__lut_"))
+                .collect();
+            names.sort_by(|a, b| a.0.cmp(&b.0));
+
+            for (location, heading, button) in [
+                (AttributeLocation::Cell, "Cell data", "To Points"),
+                (AttributeLocation::Point, "Point data", "To Cells"),
+            ] {
+                ui.strong(heading);
+                let mut any = false;
+                for (name, _) in names.iter().filter(|(_, l)| *l == location) {
+                    any = true;
+                    ui.horizontal(|ui| {
+                        ui.label(name);
+                        if ui.small_button(button).clicked() {
+                            request = Some((name.clone(), location.clone()));
+                        }
+                    });
+                }
+                if !any {
+                    ui.label("None");
+                }
+                ui.separator();
+            }
+
+            match &settings.status {
+                Some(Ok(message)) => {
+                    ui.colored_label(egui::Color32::LIGHT_GREEN, message);
+                }
+                Some(Err(message)) => {
+                    ui.colored_label(egui::Color32::LIGHT_RED, message);
+                }
+                None => {}
+            }
+        });
+
+    if request.is_some() {
+        settings.request = request;
+    }
+
+    if !open {
+        settings.show_panel = false;
+    }
+}
+
+/// Convert the requested attribute and refresh the model colors
+fn convert_requested_attribute(
+    mut settings: ResMut<DataConversionSettings>,
+    mut current_model: ResMut<CurrentModelData>,
+    color_bar_config: Res<ColorBarConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    model_entities: Query<&Mesh3d, With<UserModelMesh>>,
+) {
+    let Some((name, location)) = settings.request.take() else {
+        return;
+    };
+    let Some(geometry) = current_model.geometry.as_mut() else {
+        return;
+    };
+
+    settings.status = Some(match convert_attribute(geometry, &name, &location) {
+        Ok(new_name) => {
+            let target = match location {
+                AttributeLocation::Cell => "point",
+                AttributeLocation::Point => "cell",
+            };
+            println!(
+                "Converted {:?} attribute '{}' to {} attribute '{}'",
+                location, name, target, new_name
+            );
+            Ok(format!("Created {} attribute '{}'", target, new_name))
+        }
+        Err(e) => {
+            println!("Failed to convert attribute '{}': {}", name, e);
+            Err(e.to_string())
+        }
+    });
+
+    if let Some(mesh) = model_entities
+        .get_single()
+        .ok()
+        .and_then(|mesh3d| meshes.get_mut(&mesh3d.0))
+    {
+        if let Err(e) = apply_custom_color_mapping(geometry, mesh, &color_bar_config) {
+            println!("Failed to apply color mapping after conversion: {:?}", e);
+        }
+    }
+}
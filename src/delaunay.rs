@@ -0,0 +1,198 @@
+//! # Delaunay Module
+//!
+//! This module turns scattered points into a surface:
+//! - The points of the model are triangulated in the plane normal to the height
+//!   axis (2.5D Delaunay), keeping their heights
+//! - Long triangles along a concave outline can be dropped by a maximum edge length
+//! - Point attributes are kept, so measurements can be color-mapped as a terrain
+//!
+//! Open the panel via Mesh > Delaunay 2.5D.
+
+use crate::mesh;
+use crate::mesh::delaunay::{triangulate_points, HeightAxis};
+use crate::splatting::SplatSettings;
+use crate::ui::color_bar::apply_custom_color_mapping;
+use crate::ui::{ColorBarConfig, CurrentModelData, UserModelMesh};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Delaunay triangulation settings
+#[derive(Resource)]
+pub struct DelaunaySettings {
+    /// Show the triangulation panel
+    pub show_panel: bool,
+    /// Axis used as height
+    pub axis: HeightAxis,
+    /// Drop triangles with longer edges than `max_edge`
+    pub limit_edges: bool,
+    /// Maximum edge length in the plane (model units)
+    pub max_edge: f32,
+    /// Triangulation requested in the panel
+    apply: bool,
+    /// Outcome of the last triangulation, `Err` holds the error message
+    status: Option<Result<String, String>>,
+}
+
+impl Default for DelaunaySettings {
+    fn default() -> Self {
+        Self {
+            show_panel: false,
+            axis: HeightAxis::Z,
+            limit_edges: false,
+            max_edge: 1.0,
+            apply: false,
+            status: None,
+        }
+    }
+}
+
+pub struct DelaunayPlugin;
+
+impl Plugin for DelaunayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DelaunaySettings>().add_systems(
+            Update,
+            (
+                delaunay_panel.after(EguiSet::InitContexts),
+                triangulate_model,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Triangulation panel
+fn delaunay_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<DelaunaySettings>,
+    current_model: Res<CurrentModelData>,
+    windows: Query<&Window>,
+) {
+    if !settings.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let mut axis = settings.axis;
+    let mut limit_edges = settings.limit_edges;
+    let mut max_edge = settings.max_edge;
+    let mut apply = false;
+    let mut open = true;
+
+    egui::Window::new("Delaunay 2.5D")
+        .default_width(260.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            let Some(ref geometry) = current_model.geometry else {
+                ui.label("Load a point set to triangulate.");
+                return;
+            };
+            ui.label(format!(
+                "{} points, {} triangles",
+                geometry.vertices.len(),
+                geometry.indices.len() / 3
+            ));
+            if !geometry.indices.is_empty() {
+                ui.label("The current cells will be replaced.");
+            }
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.label("Height axis:");
+                ui.radio_value(&mut axis, HeightAxis::X, "X");
+                ui.radio_value(&mut axis, HeightAxis::Y, "Y");
+                ui.radio_value(&mut axis, HeightAxis::Z, "Z");
+            });
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut limit_edges, "Max edge length:");
+                ui.add_enabled(
+                    limit_edges,
+                    egui::DragValue::new(&mut max_edge)
+                        .speed(0.01)
+                        .range(0.0..=f32::MAX),
+                );
+            });
+
+            if ui.button("Triangulate").clicked() {
+                apply = true;
+            }
+
+            match &settings.status {
+                Some(Ok(message)) => {
+                    ui.colored_label(egui::Color32::LIGHT_GREEN, message);
+                }
+                Some(Err(message)) => {
+                    ui.colored_label(egui::Color32::LIGHT_RED, message);
+                }
+                None => {}
+            }
+        });
+
+    if axis != settings.axis || limit_edges != settings.limit_edges || max_edge != settings.max_edge
+    {
+        settings.axis = axis;
+        settings.limit_edges = limit_edges;
+        settings.max_edge = max_edge;
+    }
+    if apply {
+        settings.apply = true;
+    }
+
+    if !open {
+        settings.show_panel = false;
+    }
+}
+
+/// Replace the model by the triangulation of its points
+fn triangulate_model(
+    mut settings: ResMut<DelaunaySettings>,
+    mut current_model: ResMut<CurrentModelData>,
+    mut splat_settings: ResMut<SplatSettings>,
+    color_bar_config: Res<ColorBarConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut model_entities: Query<&mut Mesh3d, With<UserModelMesh>>,
+) {
+    if !settings.apply {
+        return;
+    }
+    settings.apply = false;
+    let (Some(geometry), Ok(mut mesh3d)) = (
+        current_model.geometry.as_ref(),
+        model_entities.get_single_mut(),
+    ) else {
+        return;
+    };
+
+    let start = std::time::Instant::now();
+    let max_edge = settings.limit_edges.then_some(settings.max_edge);
+    match triangulate_points(geometry, settings.axis, max_edge) {
+        Ok(triangulated) => {
+            let triangles = triangulated.indices.len() / 3;
+            println!(
+                "Delaunay triangulation of {} points: {} triangles in {:?}",
+                triangulated.vertices.len(),
+                triangles,
+                start.elapsed()
+            );
+
+            let mut new_mesh = mesh::create_mesh_from_geometry(&triangulated);
+            if let Err(e) =
+                apply_custom_color_mapping(&triangulated, &mut new_mesh, &color_bar_config)
+            {
+                println!("Failed to apply color mapping to triangulation: {:?}", e);
+            }
+            *mesh3d = Mesh3d(meshes.add(new_mesh));
+            current_model.geometry = Some(triangulated);
+
+            // The surface replaces the splats of the point set
+            if splat_settings.enabled {
+                splat_settings.enabled = false;
+                splat_settings.changed = true;
+            }
+            settings.status = Some(Ok(format!("Created {} triangles", triangles)));
+        }
+        Err(e) => {
+            println!("Delaunay triangulation failed: {}", e);
+            settings.status = Some(Err(e.to_string()));
+        }
+    }
+}
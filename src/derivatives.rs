@@ -0,0 +1,195 @@
+//! # Derivatives Module
+//!
+//! This module derives divergence and curl from point vector attributes:
+//! - Derivatives are computed over the tetrahedra of volume cells, or over the
+//!   triangles of surfaces, and averaged to the points
+//! - The divergence is stored as point scalar `<vectors>_divergence`, the curl
+//!   as point vector `<vectors>_curl`, next to the loaded attributes
+//!
+//! Open the panel via Mesh > Divergence and Curl.
+
+use crate::mesh::derivatives::add_vector_derivatives;
+use crate::mesh::streamlines::vector_attribute_names;
+use crate::ui::color_bar::apply_custom_color_mapping;
+use crate::ui::{ColorBarConfig, CurrentModelData, UserModelMesh};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Derivative settings
+#[derive(Resource)]
+pub struct DerivativeSettings {
+    /// Show the derivative panel
+    pub show_panel: bool,
+    /// Differentiated point vector attribute
+    pub attribute: Option<String>,
+    /// Compute the divergence
+    pub divergence: bool,
+    /// Compute the curl
+    pub curl: bool,
+    /// Computation requested in the panel
+    compute: bool,
+    /// Outcome of the last computation, `Err` holds the error message
+    status: Option<Result<String, String>>,
+}
+
+impl Default for DerivativeSettings {
+    fn default() -> Self {
+        Self {
+            show_panel: false,
+            attribute: None,
+            divergence: true,
+            curl: true,
+            compute: false,
+            status: None,
+        }
+    }
+}
+
+pub struct DerivativePlugin;
+
+impl Plugin for DerivativePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DerivativeSettings>().add_systems(
+            Update,
+            (
+                derivative_panel.after(EguiSet::InitContexts),
+                compute_derivatives,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Divergence and curl panel
+fn derivative_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<DerivativeSettings>,
+    current_model: Res<CurrentModelData>,
+    windows: Query<&Window>,
+) {
+    if !settings.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let mut attribute = settings.attribute.clone();
+    let mut divergence = settings.divergence;
+    let mut curl = settings.curl;
+    let mut compute = false;
+    let mut open = true;
+
+    egui::Window::new("Divergence and Curl")
+        .default_width(280.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            let Some(geometry) = current_model.geometry.as_ref() else {
+                ui.label("Load a model with point vectors.");
+                return;
+            };
+            let names = geometry
+                .attributes
+                .as_ref()
+                .map(vector_attribute_names)
+                .unwrap_or_default();
+            if names.is_empty() {
+                ui.label("The model has no point vectors.");
+                return;
+            }
+            if attribute.as_ref().is_none_or(|name| !names.contains(name)) {
+                attribute = names.first().cloned();
+            }
+
+            egui::ComboBox::from_label("Vectors")
+                .selected_text(attribute.clone().unwrap_or_default())
+                .show_ui(ui, |ui| {
+                    for name in &names {
+                        ui.selectable_value(&mut attribute, Some(name.clone()), name);
+                    }
+                });
+            ui.checkbox(&mut divergence, "Divergence (scalar)");
+            ui.checkbox(&mut curl, "Curl (vector)");
+            if geometry
+                .volume_cells
+                .as_ref()
+                .is_none_or(|cells| cells.is_empty())
+            {
+                ui.label(
+                    egui::RichText::new("Surface only: derivatives along the normal are zero.")
+                        .small()
+                        .weak(),
+                );
+            }
+
+            ui.separator();
+            if ui
+                .add_enabled(divergence || curl, egui::Button::new("Compute"))
+                .clicked()
+            {
+                compute = true;
+            }
+            match &settings.status {
+                Some(Ok(message)) => {
+                    ui.colored_label(egui::Color32::LIGHT_GREEN, message);
+                }
+                Some(Err(message)) => {
+                    ui.colored_label(egui::Color32::LIGHT_RED, message);
+                }
+                None => {}
+            }
+        });
+
+    if attribute != settings.attribute || divergence != settings.divergence || curl != settings.curl
+    {
+        settings.attribute = attribute;
+        settings.divergence = divergence;
+        settings.curl = curl;
+    }
+    if compute {
+        settings.compute = true;
+    }
+
+    if !open {
+        settings.show_panel = false;
+    }
+}
+
+/// Compute the derivatives and store them in the model geometry
+fn compute_derivatives(
+    mut settings: ResMut<DerivativeSettings>,
+    mut current_model: ResMut<CurrentModelData>,
+    color_bar_config: Res<ColorBarConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    model_entities: Query<&Mesh3d, With<UserModelMesh>>,
+) {
+    if !settings.compute {
+        return;
+    }
+    settings.compute = false;
+    let (Some(geometry), Some(attribute)) =
+        (current_model.geometry.as_mut(), settings.attribute.clone())
+    else {
+        return;
+    };
+
+    let result = add_vector_derivatives(geometry, &attribute, settings.divergence, settings.curl);
+    settings.status = Some(match result {
+        Ok(names) => {
+            println!("Derivatives of '{}': {}", attribute, names.join(", "));
+            Ok(format!("Created {}", names.join(", ")))
+        }
+        Err(e) => {
+            println!("Derivative computation failed: {}", e);
+            Err(e.to_string())
+        }
+    });
+
+    // Refresh the colors, a new attribute may be the one shown
+    if let Some(mesh) = model_entities
+        .get_single()
+        .ok()
+        .and_then(|mesh3d| meshes.get_mut(&mesh3d.0))
+    {
+        if let Err(e) = apply_custom_color_mapping(geometry, mesh, &color_bar_config) {
+            println!("Failed to apply color mapping after derivatives: {:?}", e);
+        }
+    }
+}
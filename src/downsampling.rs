@@ -0,0 +1,192 @@
+//! # Point Downsampling Module
+//!
+//! This module keeps huge point clouds and particle files interactive:
+//! - Point datasets above the point budget are voxel-grid downsampled on import
+//! - Point attributes are averaged over the points merged into each voxel
+//! - The full resolution dataset is kept and can be exported from the panel
+//!
+//! Surface meshes are never downsampled. Toggle the panel via View > Point Downsampling.
+
+use crate::mesh::downsample::{is_point_set, voxel_downsample};
+use crate::mesh::export::{write_vtk_ascii, write_vtu_ascii};
+use crate::mesh::{GeometryData, VtkError};
+use crate::ui::{CurrentModelData, FileDialogs};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+use rfd::AsyncFileDialog;
+
+/// Point downsampling settings
+#[derive(Resource)]
+pub struct PointDownsampling {
+    /// Downsample point datasets above the budget on import
+    pub enabled: bool,
+    /// Maximum number of points shown
+    pub point_budget: usize,
+    /// Show the downsampling panel
+    pub show_panel: bool,
+    /// Full resolution data of the current (downsampled) model
+    original: Option<GeometryData>,
+}
+
+impl Default for PointDownsampling {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            point_budget: 1_000_000,
+            show_panel: false,
+            original: None,
+        }
+    }
+}
+
+impl PointDownsampling {
+    /// Geometry to show for a newly imported model
+    ///
+    /// Point sets above the budget are downsampled and the original is kept for
+    /// export; any other geometry is passed through.
+    ///
+    /// # Parameters
+    /// - `geometry`: Imported geometry
+    ///
+    /// # Returns
+    /// - Geometry to display
+    pub fn prepare_import(&mut self, geometry: GeometryData) -> GeometryData {
+        self.original = None;
+        if !self.enabled || !is_point_set(&geometry) || geometry.vertices.len() <= self.point_budget
+        {
+            return geometry;
+        }
+
+        let downsampled = voxel_downsample(&geometry, self.point_budget);
+        println!(
+            "Point dataset downsampled for display: {} of {} points",
+            downsampled.vertices.len(),
+            geometry.vertices.len()
+        );
+        self.original = Some(geometry);
+        downsampled
+    }
+
+    /// Full resolution point count of the current model, if it was downsampled
+    pub fn original_point_count(&self) -> Option<usize> {
+        self.original
+            .as_ref()
+            .map(|original| original.vertices.len())
+    }
+}
+
+pub struct DownsamplingPlugin;
+
+impl Plugin for DownsamplingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PointDownsampling>().add_systems(
+            Update,
+            (
+                clear_original_with_model,
+                downsampling_panel.after(EguiSet::InitContexts),
+            ),
+        );
+    }
+}
+
+/// Forget the original data once the model is cleared
+fn clear_original_with_model(
+    current_model: Res<CurrentModelData>,
+    mut downsampling: ResMut<PointDownsampling>,
+) {
+    if current_model.geometry.is_none() && downsampling.original.is_some() {
+        downsampling.original = None;
+    }
+}
+
+/// Point downsampling panel
+fn downsampling_panel(
+    mut contexts: EguiContexts,
+    mut downsampling: ResMut<PointDownsampling>,
+    current_model: Res<CurrentModelData>,
+    mut file_dialogs: ResMut<FileDialogs>,
+    windows: Query<&Window>,
+) {
+    if !downsampling.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let mut enabled = downsampling.enabled;
+    let mut point_budget = downsampling.point_budget;
+    let mut export_original = false;
+    let mut open = true;
+
+    egui::Window::new("Point Downsampling")
+        .default_width(260.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.checkbox(&mut enabled, "Downsample point clouds on import");
+            ui.horizontal(|ui| {
+                ui.label("Point budget:");
+                ui.add_enabled(
+                    enabled,
+                    egui::DragValue::new(&mut point_budget)
+                        .speed(1000.0)
+                        .range(1000..=100_000_000),
+                );
+            });
+            ui.label("Applies to the next import.");
+
+            ui.separator();
+            let shown = current_model
+                .geometry
+                .as_ref()
+                .map_or(0, |geometry| geometry.vertices.len());
+            match downsampling.original_point_count() {
+                Some(original) => {
+                    ui.label(format!("Showing {} of {} points", shown, original));
+                    if ui.button("Export Original...").clicked() {
+                        export_original = true;
+                    }
+                }
+                None => {
+                    ui.label("Current model is shown at full resolution.");
+                }
+            }
+        });
+
+    if enabled != downsampling.enabled || point_budget != downsampling.point_budget {
+        downsampling.enabled = enabled;
+        downsampling.point_budget = point_budget;
+    }
+
+    if export_original {
+        if let Some(original) = downsampling.original.clone() {
+            export_full_resolution(original, &mut file_dialogs);
+        }
+    }
+
+    if !open {
+        downsampling.show_panel = false;
+    }
+}
+
+/// Ask for a file name and write the full resolution data to it
+///
+/// The file dialog and writing run on the IO task pool to avoid blocking the
+/// main thread.
+fn export_full_resolution(geometry: GeometryData, file_dialogs: &mut FileDialogs) {
+    let dialog = AsyncFileDialog::new()
+        .add_filter("VTK", &["vtk"])
+        .add_filter("VTK XML", &["vtu"])
+        .set_file_name("original.vtk")
+        .set_directory(std::env::var("HOME").unwrap_or_else(|_| "/".to_string()));
+    file_dialogs.open(async move {
+        let file = dialog.save_file().await?;
+        let path = file.path();
+        let result: Result<(), VtkError> = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("vtu") => write_vtu_ascii(&geometry, path),
+            _ => write_vtk_ascii(&geometry, path),
+        };
+        match result {
+            Ok(()) => println!("Original points exported to {}", path.display()),
+            Err(e) => eprintln!("Failed to export original points: {}", e),
+        }
+        None
+    });
+}
@@ -0,0 +1,243 @@
+//! # Extrude Module
+//!
+//! This module gives surfaces a thickness by linear extrusion:
+//! - The surface is swept along its normal by a length, or along an entered
+//!   vector
+//! - Side quads are built from the boundary edges; caps close the solid
+//! - Point and cell attributes are kept, so 2D simulation slices can be shown
+//!   as color-mapped slabs
+//!
+//! The extruded geometry replaces the model. Open the panel via Mesh > Extrude.
+
+use crate::lod::LODManager;
+use crate::mesh;
+use crate::mesh::extrude::{extrude_geometry, surface_normal};
+use crate::model_transform::ModelCenter;
+use crate::ui::color_bar::apply_custom_color_mapping;
+use crate::ui::{ColorBarConfig, CurrentModelData, UserModelMesh};
+use crate::volume_slices::VolumeSlices;
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Direction of the extrusion
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ExtrudeDirection {
+    /// Average normal of the surface
+    #[default]
+    Normal,
+    /// Vector entered in the panel
+    Vector,
+}
+
+/// Extrusion settings
+#[derive(Resource)]
+pub struct ExtrudeSettings {
+    /// Show the extrusion panel
+    pub show_panel: bool,
+    /// Extrude along the surface normal or the entered vector
+    pub direction: ExtrudeDirection,
+    /// Extrusion length along the surface normal
+    pub length: f32,
+    /// Extrusion vector
+    pub vector: [f32; 3],
+    /// Close the solid with the surface and its translated copy
+    pub capping: bool,
+    /// Extrusion requested in the panel
+    apply: bool,
+    /// Outcome of the last extrusion, `Err` holds the error message
+    status: Option<Result<String, String>>,
+}
+
+impl Default for ExtrudeSettings {
+    fn default() -> Self {
+        Self {
+            show_panel: false,
+            direction: ExtrudeDirection::default(),
+            length: 0.1,
+            vector: [0.0, 0.0, 0.1],
+            capping: true,
+            apply: false,
+            status: None,
+        }
+    }
+}
+
+pub struct ExtrudePlugin;
+
+impl Plugin for ExtrudePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ExtrudeSettings>().add_systems(
+            Update,
+            (extrude_panel.after(EguiSet::InitContexts), extrude_model).chain(),
+        );
+    }
+}
+
+/// Extrusion panel
+fn extrude_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<ExtrudeSettings>,
+    current_model: Res<CurrentModelData>,
+    windows: Query<&Window>,
+) {
+    if !settings.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let mut direction = settings.direction;
+    let mut length = settings.length;
+    let mut vector = settings.vector;
+    let mut capping = settings.capping;
+    let mut apply = false;
+    let mut open = true;
+
+    egui::Window::new("Extrude")
+        .default_width(280.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            let Some(ref geometry) = current_model.geometry else {
+                ui.label("Load a surface to extrude.");
+                return;
+            };
+            if geometry.indices.is_empty() {
+                ui.label("The model has no surface to extrude.");
+                return;
+            }
+            let normal = surface_normal(geometry);
+            match normal {
+                Some(n) => ui.label(format!(
+                    "Surface normal: {:.3}, {:.3}, {:.3}",
+                    n.x, n.y, n.z
+                )),
+                None => ui.label("The surface has no area."),
+            };
+            if geometry.volume_cells.is_some() {
+                ui.label("The volume cells of the model will be dropped.");
+            }
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut direction, ExtrudeDirection::Normal, "Along normal");
+                ui.radio_value(&mut direction, ExtrudeDirection::Vector, "Vector");
+            });
+            match direction {
+                ExtrudeDirection::Normal => {
+                    ui.horizontal(|ui| {
+                        ui.label("Length:");
+                        ui.add(egui::DragValue::new(&mut length).speed(0.01));
+                    });
+                }
+                ExtrudeDirection::Vector => {
+                    ui.horizontal(|ui| {
+                        ui.label("Vector:");
+                        for value in vector.iter_mut() {
+                            ui.add(egui::DragValue::new(value).speed(0.01));
+                        }
+                    });
+                }
+            }
+            ui.checkbox(&mut capping, "Cap the ends");
+
+            if ui
+                .add_enabled(
+                    direction == ExtrudeDirection::Vector || normal.is_some(),
+                    egui::Button::new("Extrude"),
+                )
+                .clicked()
+            {
+                apply = true;
+            }
+
+            match &settings.status {
+                Some(Ok(message)) => {
+                    ui.colored_label(egui::Color32::LIGHT_GREEN, message);
+                }
+                Some(Err(message)) => {
+                    ui.colored_label(egui::Color32::LIGHT_RED, message);
+                }
+                None => {}
+            }
+        });
+
+    if direction != settings.direction
+        || length != settings.length
+        || vector != settings.vector
+        || capping != settings.capping
+    {
+        settings.direction = direction;
+        settings.length = length;
+        settings.vector = vector;
+        settings.capping = capping;
+    }
+    if apply {
+        settings.apply = true;
+    }
+
+    if !open {
+        settings.show_panel = false;
+    }
+}
+
+/// Replace the model by its extrusion
+#[allow(clippy::too_many_arguments)]
+fn extrude_model(
+    mut settings: ResMut<ExtrudeSettings>,
+    mut current_model: ResMut<CurrentModelData>,
+    mut model_center: ResMut<ModelCenter>,
+    mut volume_slices: ResMut<VolumeSlices>,
+    color_bar_config: Res<ColorBarConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut model_entities: Query<(&mut Mesh3d, Option<&mut LODManager>), With<UserModelMesh>>,
+) {
+    if !settings.apply {
+        return;
+    }
+    settings.apply = false;
+    let (Some(geometry), Ok((mut mesh3d, lod_manager))) = (
+        current_model.geometry.as_ref(),
+        model_entities.get_single_mut(),
+    ) else {
+        return;
+    };
+
+    let vector = match settings.direction {
+        ExtrudeDirection::Normal => surface_normal(geometry).unwrap_or(Vec3::Z) * settings.length,
+        ExtrudeDirection::Vector => Vec3::from(settings.vector),
+    };
+    let extruded = match extrude_geometry(geometry, vector, settings.capping) {
+        Ok(extruded) => extruded,
+        Err(e) => {
+            println!("Extrusion failed: {}", e);
+            settings.status = Some(Err(e.to_string()));
+            return;
+        }
+    };
+    settings.status = Some(Ok(format!(
+        "Created {} triangles",
+        extruded.indices.len() / 3
+    )));
+    // The extruded geometry is no longer a slice of the volume
+    if volume_slices.volume().is_some() {
+        volume_slices.clear();
+    }
+
+    let (min, max) = extruded.vertices.iter().fold(
+        (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+        |(min, max), v| (min.min(Vec3::from(*v)), max.max(Vec3::from(*v))),
+    );
+    if min.cmple(max).all() {
+        model_center.center = (min + max) / 2.0;
+    }
+
+    let mut new_mesh = mesh::create_mesh_from_geometry(&extruded);
+    if let Err(e) = apply_custom_color_mapping(&extruded, &mut new_mesh, &color_bar_config) {
+        println!("Failed to apply color mapping to extruded model: {:?}", e);
+    }
+    if let Some(mut lod_manager) = lod_manager {
+        if let Err(e) = lod_manager.rebuild(extruded.clone(), &mut meshes) {
+            println!("Failed to rebuild LOD levels of extruded model: {:?}", e);
+        }
+    }
+    *mesh3d = Mesh3d(meshes.add(new_mesh));
+    current_model.geometry = Some(extruded);
+}
@@ -0,0 +1,565 @@
+//! # glTF Scene Module
+//!
+//! This module imports glTF/GLB scenes while keeping their node hierarchy:
+//! - Every glTF node becomes an entity parented to its glTF parent, with the node
+//!   name and local transform
+//! - Mesh primitives are spawned as children of their node
+//! - The scene root is tagged with `UserModelMesh`, so model transforms and
+//!   clearing apply to the whole scene
+//! - All triangle primitives are merged, with their node transforms applied, into
+//!   the geometry of `CurrentModelData`
+//!
+//! Subdivision and LOD work on the merged geometry: before they run, the scene is
+//! flattened into a single mesh on the root entity (node hierarchy and glTF
+//! materials are dropped).
+//!
+//! The "glTF Scene" panel lists the node tree with per-node visibility toggles,
+//! bounding box outlines and editable local transforms.
+
+use crate::mesh::{self, GeometryData};
+use crate::outline::BoundingBoxOutline;
+use crate::ui::events::{GenerateLODEvent, SubdivideMeshEvent};
+use crate::ui::{
+    color_bar, handle_lod_generation, handle_subdivision, ColorBarConfig, CurrentModelData,
+    ModelLoadFailedEvent, ModelLoadedEvent, UserModelMesh,
+};
+use bevy::asset::LoadState;
+use bevy::gltf::{Gltf, GltfMesh, GltfNode};
+use bevy::prelude::*;
+use bevy::render::mesh::{PrimitiveTopology, VertexAttributeValues};
+use bevy::utils::{HashMap, HashSet};
+use bevy_egui::{egui, EguiContexts, EguiSet};
+use std::path::{Path, PathBuf};
+
+/// Default model position, same as other imported models
+const SCENE_POSITION: Vec3 = Vec3::new(0.0, 0.5, 0.0);
+
+/// glTF import state
+#[derive(Resource, Default)]
+pub struct GltfImportState {
+    /// glTF asset waiting to finish loading
+    pending: Option<(Handle<Gltf>, PathBuf)>,
+    /// Show the node hierarchy panel
+    pub show_panel: bool,
+}
+
+impl GltfImportState {
+    /// Start loading a glTF/GLB file; the scene is spawned once loading finished
+    pub fn request_load(&mut self, asset_server: &AssetServer, path: &Path) {
+        let handle = asset_server.load(format!("{}", path.to_string_lossy()));
+        self.pending = Some((handle, path.to_path_buf()));
+    }
+}
+
+/// Marker component for the root entity of an imported glTF scene
+#[derive(Component)]
+pub struct GltfSceneRoot;
+
+/// glTF node entity
+#[derive(Component)]
+pub struct GltfSceneNode {
+    /// Node index in the glTF file
+    pub index: usize,
+}
+
+pub struct GltfScenePlugin;
+
+impl Plugin for GltfScenePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GltfImportState>().add_systems(
+            Update,
+            (
+                spawn_loaded_gltf,
+                gltf_scene_panel.after(EguiSet::InitContexts),
+                flatten_gltf_scene
+                    .before(handle_subdivision)
+                    .before(handle_lod_generation),
+            ),
+        );
+    }
+}
+
+/// Spawn the node hierarchy once the pending glTF asset is loaded
+#[allow(clippy::too_many_arguments)]
+fn spawn_loaded_gltf(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    gltf_assets: Res<Assets<Gltf>>,
+    node_assets: Res<Assets<GltfNode>>,
+    mesh_assets: Res<Assets<GltfMesh>>,
+    meshes: Res<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut import_state: ResMut<GltfImportState>,
+    mut current_model: ResMut<CurrentModelData>,
+    mut model_loaded_events: EventWriter<ModelLoadedEvent>,
+    mut failed_events: EventWriter<ModelLoadFailedEvent>,
+) {
+    let Some((ref handle, ref path)) = import_state.pending else {
+        return;
+    };
+
+    if let Some(LoadState::Failed(err)) = asset_server.get_load_state(handle) {
+        println!("load glTF file failed: {}", err);
+        failed_events.send(ModelLoadFailedEvent {
+            path: path.clone(),
+            error: err.to_string(),
+        });
+        import_state.pending = None;
+        return;
+    }
+    if !asset_server.is_loaded_with_dependencies(handle) {
+        return;
+    }
+    let Some(gltf) = gltf_assets.get(handle) else {
+        return;
+    };
+
+    // Root nodes are nodes that are nobody's child
+    let child_nodes: HashSet<AssetId<GltfNode>> = gltf
+        .nodes
+        .iter()
+        .filter_map(|node| node_assets.get(node))
+        .flat_map(|node| node.children.iter().map(|child| child.id()))
+        .collect();
+
+    let scene_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "glTF Scene".to_string());
+    let root = commands
+        .spawn((
+            Name::new(scene_name),
+            Transform::from_translation(SCENE_POSITION),
+            Visibility::Visible,
+            UserModelMesh,
+            GltfSceneRoot,
+        ))
+        .id();
+
+    let default_material = materials.add(StandardMaterial {
+        base_color: Color::WHITE,
+        metallic: 0.2,
+        perceptual_roughness: 0.4,
+        reflectance: 0.5,
+        ..default()
+    });
+
+    let mut spawner = NodeSpawner {
+        node_assets: &node_assets,
+        mesh_assets: &mesh_assets,
+        meshes: &meshes,
+        default_material,
+        bounds: None,
+        node_count: 0,
+        primitive_count: 0,
+        geometry: SceneGeometry::default(),
+    };
+    for node in gltf
+        .nodes
+        .iter()
+        .filter(|node| !child_nodes.contains(&node.id()))
+    {
+        spawner.spawn_node(&mut commands, node, root, Mat4::IDENTITY);
+    }
+
+    println!(
+        "Imported glTF scene {}: {} nodes, {} mesh primitives",
+        path.display(),
+        spawner.node_count,
+        spawner.primitive_count
+    );
+    current_model.geometry = spawner.geometry.into_geometry();
+
+    model_loaded_events.send(ModelLoadedEvent {
+        position: SCENE_POSITION,
+        scale: Vec3::ONE,
+        bounds_min: spawner.bounds.map(|(min, _)| min),
+        bounds_max: spawner.bounds.map(|(_, max)| max),
+    });
+
+    import_state.pending = None;
+    import_state.show_panel = true;
+}
+
+/// Recursive spawner for glTF nodes
+struct NodeSpawner<'a> {
+    node_assets: &'a Assets<GltfNode>,
+    mesh_assets: &'a Assets<GltfMesh>,
+    meshes: &'a Assets<Mesh>,
+    default_material: Handle<StandardMaterial>,
+    /// Scene bounds relative to the scene root
+    bounds: Option<(Vec3, Vec3)>,
+    node_count: usize,
+    primitive_count: usize,
+    /// Merged triangles of all primitives, relative to the scene root
+    geometry: SceneGeometry,
+}
+
+impl NodeSpawner<'_> {
+    fn spawn_node(
+        &mut self,
+        commands: &mut Commands,
+        handle: &Handle<GltfNode>,
+        parent: Entity,
+        parent_matrix: Mat4,
+    ) {
+        let Some(node) = self.node_assets.get(handle) else {
+            return;
+        };
+        self.node_count += 1;
+
+        let name = if node.name.is_empty() {
+            format!("Node {}", node.index)
+        } else {
+            node.name.clone()
+        };
+        let entity = commands
+            .spawn((
+                Name::new(name),
+                node.transform,
+                Visibility::Inherited,
+                GltfSceneNode { index: node.index },
+            ))
+            .set_parent(parent)
+            .id();
+        let matrix = parent_matrix * node.transform.compute_matrix();
+
+        if let Some(gltf_mesh) = node.mesh.as_ref().and_then(|m| self.mesh_assets.get(m)) {
+            for primitive in &gltf_mesh.primitives {
+                self.primitive_count += 1;
+                self.include_bounds(&primitive.mesh, matrix);
+                if let Some(mesh) = self.meshes.get(&primitive.mesh) {
+                    self.geometry.append(mesh, matrix);
+                }
+                commands
+                    .spawn((
+                        Name::new(primitive.name.clone()),
+                        Mesh3d(primitive.mesh.clone()),
+                        MeshMaterial3d(
+                            primitive
+                                .material
+                                .clone()
+                                .unwrap_or_else(|| self.default_material.clone()),
+                        ),
+                        Transform::default(),
+                        Visibility::Inherited,
+                    ))
+                    .set_parent(entity);
+            }
+        }
+
+        for child in &node.children {
+            self.spawn_node(commands, child, entity, matrix);
+        }
+    }
+
+    /// Grow the scene bounds by the transformed positions of a mesh
+    fn include_bounds(&mut self, mesh: &Handle<Mesh>, matrix: Mat4) {
+        let Some(VertexAttributeValues::Float32x3(positions)) = self
+            .meshes
+            .get(mesh)
+            .and_then(|mesh| mesh.attribute(Mesh::ATTRIBUTE_POSITION))
+        else {
+            return;
+        };
+
+        for position in positions {
+            let point = matrix.transform_point3(Vec3::from(*position));
+            self.bounds = Some(match self.bounds {
+                Some((min, max)) => (min.min(point), max.max(point)),
+                None => (point, point),
+            });
+        }
+    }
+}
+
+/// Merged triangle geometry of a glTF scene
+#[derive(Default)]
+struct SceneGeometry {
+    vertices: Vec<[f32; 3]>,
+    indices: Vec<u32>,
+    /// Normals and uvs are kept only if every primitive has them
+    normals: Option<Vec<[f32; 3]>>,
+    uvs: Option<Vec<[f32; 2]>>,
+}
+
+impl SceneGeometry {
+    /// Append the triangles of a primitive, transformed into scene root space
+    fn append(&mut self, mesh: &Mesh, matrix: Mat4) {
+        if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+            return;
+        }
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            return;
+        };
+
+        let first = self.vertices.len();
+        let offset = first as u32;
+        match mesh.indices() {
+            Some(indices) => self
+                .indices
+                .extend(indices.iter().map(|index| offset + index as u32)),
+            None => self
+                .indices
+                .extend((0..positions.len() as u32).map(|index| offset + index)),
+        }
+        self.vertices.extend(
+            positions
+                .iter()
+                .map(|position| matrix.transform_point3(Vec3::from(*position)).to_array()),
+        );
+
+        // Each attribute is used only if present on all primitives appended so far
+        let normal_matrix = Mat3::from_mat4(matrix).inverse().transpose();
+        match (
+            self.normals.as_mut(),
+            mesh.attribute(Mesh::ATTRIBUTE_NORMAL),
+        ) {
+            (Some(normals), Some(VertexAttributeValues::Float32x3(values))) => {
+                normals.extend(values.iter().map(|normal| {
+                    (normal_matrix * Vec3::from(*normal))
+                        .normalize_or_zero()
+                        .to_array()
+                }))
+            }
+            (None, Some(VertexAttributeValues::Float32x3(values))) if first == 0 => {
+                self.normals = Some(
+                    values
+                        .iter()
+                        .map(|normal| {
+                            (normal_matrix * Vec3::from(*normal))
+                                .normalize_or_zero()
+                                .to_array()
+                        })
+                        .collect(),
+                )
+            }
+            _ => self.normals = None,
+        }
+        match (self.uvs.as_mut(), mesh.attribute(Mesh::ATTRIBUTE_UV_0)) {
+            (Some(uvs), Some(VertexAttributeValues::Float32x2(values))) => uvs.extend(values),
+            (None, Some(VertexAttributeValues::Float32x2(values))) if first == 0 => {
+                self.uvs = Some(values.clone())
+            }
+            _ => self.uvs = None,
+        }
+    }
+
+    /// Geometry data of the merged triangles, one cell per triangle
+    fn into_geometry(self) -> Option<GeometryData> {
+        if self.indices.is_empty() {
+            return None;
+        }
+        let triangle_count = self.indices.len() / 3;
+        let mut geometry = GeometryData::new(self.vertices, self.indices, HashMap::new())
+            .add_triangle_to_cell_mapping((0..triangle_count).collect());
+        if let Some(normals) = self.normals {
+            geometry = geometry.add_normals(normals);
+        }
+        if let Some(uvs) = self.uvs {
+            geometry = geometry.add_uvs(uvs);
+        }
+        Some(geometry)
+    }
+}
+
+/// Replace the node hierarchy by one mesh of the merged geometry before
+/// subdivision or LOD generation, which work on a single model mesh
+#[allow(clippy::too_many_arguments)]
+fn flatten_gltf_scene(
+    mut commands: Commands,
+    mut subdivide_events: EventReader<SubdivideMeshEvent>,
+    mut lod_events: EventReader<GenerateLODEvent>,
+    current_model: Res<CurrentModelData>,
+    color_bar_config: Res<ColorBarConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut import_state: ResMut<GltfImportState>,
+    roots: Query<Entity, (With<GltfSceneRoot>, Without<Mesh3d>)>,
+) {
+    let requested = subdivide_events.read().count() + lod_events.read().count() > 0;
+    if !requested {
+        return;
+    }
+    let (Ok(root), Some(geometry)) = (roots.get_single(), current_model.geometry.as_ref()) else {
+        return;
+    };
+
+    let mut mesh = mesh::create_mesh_from_geometry(geometry);
+    if let Err(e) = color_bar::apply_custom_color_mapping(geometry, &mut mesh, &color_bar_config) {
+        println!("Failed to apply color mapping to glTF scene: {:?}", e);
+    }
+    commands
+        .entity(root)
+        .despawn_descendants()
+        .remove::<GltfSceneRoot>()
+        .insert((
+            Mesh3d(meshes.add(mesh)),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: Color::WHITE,
+                metallic: 0.2,
+                perceptual_roughness: 0.4,
+                reflectance: 0.5,
+                cull_mode: None,
+                ..default()
+            })),
+        ));
+    import_state.show_panel = false;
+    println!(
+        "Flattened glTF scene into one mesh with {} triangles",
+        geometry.indices.len() / 3
+    );
+}
+
+/// Components of scene nodes shown in the panel
+type SceneNodeItems = (
+    &'static Name,
+    &'static GltfSceneNode,
+    &'static mut Visibility,
+    &'static mut Transform,
+    Has<BoundingBoxOutline>,
+);
+
+/// Node hierarchy panel with visibility toggles, outlines and transforms
+fn gltf_scene_panel(
+    mut commands: Commands,
+    mut contexts: EguiContexts,
+    mut import_state: ResMut<GltfImportState>,
+    roots: Query<(Entity, &Name), With<GltfSceneRoot>>,
+    children_query: Query<&Children>,
+    mut nodes: Query<SceneNodeItems>,
+    windows: Query<&Window>,
+) {
+    if !import_state.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+    let Some((root, root_name)) = roots.iter().next() else {
+        return;
+    };
+
+    let mut open = true;
+    egui::Window::new("glTF Scene")
+        .default_width(280.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label(root_name.as_str());
+            ui.separator();
+            egui::ScrollArea::vertical()
+                .max_height(400.0)
+                .show(ui, |ui| {
+                    if let Ok(children) = children_query.get(root) {
+                        for &child in children {
+                            node_tree_ui(ui, &mut commands, child, &children_query, &mut nodes);
+                        }
+                    }
+                });
+        });
+
+    if !open {
+        import_state.show_panel = false;
+    }
+}
+
+/// Draw one node and its child nodes
+fn node_tree_ui(
+    ui: &mut egui::Ui,
+    commands: &mut Commands,
+    entity: Entity,
+    children_query: &Query<&Children>,
+    nodes: &mut Query<SceneNodeItems>,
+) {
+    let Ok((name, node, visibility, transform, outlined)) = nodes.get(entity) else {
+        return;
+    };
+    let name = name.to_string();
+    let node_index = node.index;
+    let mut visible = *visibility != Visibility::Hidden;
+    let mut outline = outlined;
+    let mut new_transform = *transform;
+
+    let id = ui.make_persistent_id(entity);
+    let (_, header_response, _) =
+        egui::collapsing_header::CollapsingState::load_with_default_open(ui.ctx(), id, false)
+            .show_header(ui, |ui| {
+                ui.checkbox(&mut visible, name)
+                    .on_hover_text(format!("glTF node {}", node_index))
+                    .changed()
+            })
+            .body(|ui| {
+                ui.checkbox(&mut outline, "Bounding box");
+                transform_editor(ui, &mut new_transform);
+                if let Ok(children) = children_query.get(entity) {
+                    for &child in children {
+                        node_tree_ui(ui, commands, child, children_query, nodes);
+                    }
+                }
+            });
+    let visibility_changed = header_response.inner;
+
+    if outline != outlined {
+        if outline {
+            commands.entity(entity).insert(BoundingBoxOutline);
+        } else {
+            commands.entity(entity).remove::<BoundingBoxOutline>();
+        }
+    }
+    if let Ok((_, _, mut visibility, mut transform, _)) = nodes.get_mut(entity) {
+        if visibility_changed {
+            *visibility = if visible {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+        }
+        if new_transform != *transform {
+            *transform = new_transform;
+        }
+    }
+}
+
+/// Editable local translation, rotation (Euler degrees) and scale
+fn transform_editor(ui: &mut egui::Ui, transform: &mut Transform) {
+    ui.horizontal(|ui| {
+        ui.label("Translation:");
+        for value in [
+            &mut transform.translation.x,
+            &mut transform.translation.y,
+            &mut transform.translation.z,
+        ] {
+            ui.add(egui::DragValue::new(value).speed(0.01));
+        }
+    });
+
+    let (x, y, z) = transform.rotation.to_euler(EulerRot::XYZ);
+    let mut degrees = [x.to_degrees(), y.to_degrees(), z.to_degrees()];
+    let mut rotation_changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Rotation:");
+        for value in degrees.iter_mut() {
+            rotation_changed |= ui
+                .add(egui::DragValue::new(value).speed(1.0).suffix("°"))
+                .changed();
+        }
+    });
+    if rotation_changed {
+        transform.rotation = Quat::from_euler(
+            EulerRot::XYZ,
+            degrees[0].to_radians(),
+            degrees[1].to_radians(),
+            degrees[2].to_radians(),
+        );
+    }
+
+    ui.horizontal(|ui| {
+        ui.label("Scale:");
+        for value in [
+            &mut transform.scale.x,
+            &mut transform.scale.y,
+            &mut transform.scale.z,
+        ] {
+            ui.add(egui::DragValue::new(value).speed(0.01));
+        }
+    });
+}
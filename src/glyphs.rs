@@ -0,0 +1,355 @@
+//! # Glyphs Module
+//!
+//! This module shows point vector attributes as arrow glyphs:
+//! - Arrows are placed at the model points (or volume points), thinned out by a
+//!   stride and a maximum count
+//! - Arrows point along the vector and are scaled by its magnitude or have a
+//!   uniform length
+//! - Arrows are colored by the magnitude with the color map of the color bar
+//!
+//! The glyphs are a child of the model, so they follow the model transform and
+//! are removed with the model. Toggle via Mesh > Vector Glyphs.
+
+use crate::mesh::color_maps::get_color_map;
+use crate::mesh::glyphs::{arrow_count, arrow_glyphs, GlyphSettings};
+use crate::mesh::streamlines::vector_attribute_names;
+use crate::mesh::{self, AttributeLocation, AttributeType, GeometryData};
+use crate::ui::{ColorBarConfig, CurrentModelData, ModelLoadedEvent, UserModelMesh};
+use crate::volume_slices::VolumeSlices;
+use crate::widgets::WidgetState;
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Glyph settings
+#[derive(Resource)]
+pub struct GlyphPanelSettings {
+    /// Show the glyph panel
+    pub show_panel: bool,
+    /// Show the glyphs
+    pub enabled: bool,
+    /// Point vector attribute shown by the glyphs
+    pub attribute: Option<String>,
+    /// Use every n-th point
+    pub stride: usize,
+    /// Largest number of glyphs
+    pub max_count: usize,
+    /// Length of the largest arrow relative to the scene size
+    pub scale_factor: f32,
+    /// Scale arrows by the vector magnitude
+    pub scale_by_magnitude: bool,
+    /// Settings changed, the glyphs need to be rebuilt
+    pub changed: bool,
+    /// A slider is being dragged, rebuild once released
+    dragging: bool,
+    /// Glyphs currently shown
+    glyph_count: usize,
+}
+
+impl Default for GlyphPanelSettings {
+    fn default() -> Self {
+        Self {
+            show_panel: false,
+            enabled: false,
+            attribute: None,
+            stride: 1,
+            max_count: 5000,
+            scale_factor: 0.05,
+            scale_by_magnitude: true,
+            changed: false,
+            dragging: false,
+            glyph_count: 0,
+        }
+    }
+}
+
+/// Marker component of the glyph entity
+#[derive(Component)]
+pub struct GlyphMesh;
+
+pub struct GlyphPlugin;
+
+impl Plugin for GlyphPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GlyphPanelSettings>().add_systems(
+            Update,
+            (
+                refresh_on_model_load,
+                glyph_panel.after(EguiSet::InitContexts),
+                update_glyphs,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Point positions and the vectors of one attribute
+type PointVectors<'a> = (Vec<[f32; 3]>, &'a [[f32; 3]]);
+
+/// Points and vectors of the dataset
+enum Source<'a> {
+    Volume(&'a mesh::volume::VolumeData),
+    Geometry(&'a GeometryData),
+}
+
+impl Source<'_> {
+    fn find<'a>(
+        current_model: &'a CurrentModelData,
+        volume_slices: &'a VolumeSlices,
+    ) -> Option<Source<'a>> {
+        match volume_slices.volume() {
+            Some(volume) => Some(Source::Volume(volume)),
+            None => current_model.geometry.as_ref().map(Source::Geometry),
+        }
+    }
+
+    fn attribute_names(&self) -> Vec<String> {
+        match self {
+            Source::Volume(volume) => vector_attribute_names(&volume.attributes),
+            Source::Geometry(geometry) => geometry
+                .attributes
+                .as_ref()
+                .map(vector_attribute_names)
+                .unwrap_or_default(),
+        }
+    }
+
+    fn point_count(&self) -> usize {
+        match self {
+            Source::Volume(volume) => volume.point_count(),
+            Source::Geometry(geometry) => geometry.vertices.len(),
+        }
+    }
+
+    /// Point positions and vectors of an attribute
+    fn points_and_vectors(&self, name: &str) -> Option<PointVectors<'_>> {
+        let key = (name.to_string(), AttributeLocation::Point);
+        match self {
+            Source::Volume(volume) => {
+                let AttributeType::Vector(vectors) = volume.attributes.get(&key)? else {
+                    return None;
+                };
+                let mut positions = Vec::with_capacity(volume.point_count());
+                for z in 0..volume.dims[2] {
+                    for y in 0..volume.dims[1] {
+                        for x in 0..volume.dims[0] {
+                            positions.push(volume.point_position([x, y, z]));
+                        }
+                    }
+                }
+                Some((positions, vectors))
+            }
+            Source::Geometry(geometry) => {
+                let AttributeType::Vector(vectors) = geometry.attributes.as_ref()?.get(&key)?
+                else {
+                    return None;
+                };
+                Some((geometry.vertices.clone(), vectors))
+            }
+        }
+    }
+}
+
+/// Rebuild the glyphs for a newly loaded model
+fn refresh_on_model_load(
+    mut loaded_events: EventReader<ModelLoadedEvent>,
+    mut settings: ResMut<GlyphPanelSettings>,
+) {
+    if loaded_events.read().count() > 0 {
+        settings.changed = settings.enabled;
+    }
+}
+
+/// Glyph panel
+fn glyph_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<GlyphPanelSettings>,
+    current_model: Res<CurrentModelData>,
+    volume_slices: Res<VolumeSlices>,
+    windows: Query<&Window>,
+) {
+    if !settings.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let source = Source::find(&current_model, &volume_slices);
+    let mut enabled = settings.enabled;
+    let mut attribute = settings.attribute.clone();
+    let mut stride = settings.stride;
+    let mut max_count = settings.max_count;
+    let mut scale_factor = settings.scale_factor;
+    let mut scale_by_magnitude = settings.scale_by_magnitude;
+    let mut dragging = false;
+    let mut open = true;
+
+    egui::Window::new("Vector Glyphs")
+        .default_width(260.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            let Some(source) = source else {
+                ui.label("Load a model with point vectors.");
+                return;
+            };
+            let names = source.attribute_names();
+            if names.is_empty() {
+                ui.label("The dataset has no point vectors.");
+                return;
+            }
+            if attribute.as_ref().is_none_or(|name| !names.contains(name)) {
+                attribute = names.first().cloned();
+            }
+
+            ui.checkbox(&mut enabled, "Show glyphs");
+            ui.separator();
+            egui::ComboBox::from_label("Vectors")
+                .selected_text(attribute.clone().unwrap_or_default())
+                .show_ui(ui, |ui| {
+                    for name in &names {
+                        ui.selectable_value(&mut attribute, Some(name.clone()), name);
+                    }
+                });
+            ui.label(format!("{} points", source.point_count()));
+            let response = ui.add(egui::Slider::new(&mut stride, 1..=100).text("Stride"));
+            dragging |= response.dragged();
+            let response = ui.add(
+                egui::Slider::new(&mut max_count, 10..=100_000)
+                    .logarithmic(true)
+                    .text("Max glyphs"),
+            );
+            dragging |= response.dragged();
+            let response = ui.add(
+                egui::Slider::new(&mut scale_factor, 0.001..=0.5)
+                    .logarithmic(true)
+                    .text("Scale"),
+            );
+            dragging |= response.dragged();
+            ui.checkbox(&mut scale_by_magnitude, "Scale by magnitude");
+
+            if enabled {
+                ui.separator();
+                ui.label(format!("{} glyphs", settings.glyph_count));
+            }
+        });
+
+    if enabled != settings.enabled
+        || attribute != settings.attribute
+        || stride != settings.stride
+        || max_count != settings.max_count
+        || scale_factor != settings.scale_factor
+        || scale_by_magnitude != settings.scale_by_magnitude
+    {
+        settings.enabled = enabled;
+        settings.attribute = attribute;
+        settings.stride = stride;
+        settings.max_count = max_count;
+        settings.scale_factor = scale_factor;
+        settings.scale_by_magnitude = scale_by_magnitude;
+        settings.changed = true;
+    }
+    if dragging != settings.dragging {
+        settings.dragging = dragging;
+    }
+
+    if !open {
+        settings.show_panel = false;
+    }
+}
+
+/// Replace the glyph entity after a settings change
+#[allow(clippy::too_many_arguments)]
+fn update_glyphs(
+    mut commands: Commands,
+    mut settings: ResMut<GlyphPanelSettings>,
+    widget_state: Res<WidgetState>,
+    current_model: Res<CurrentModelData>,
+    volume_slices: Res<VolumeSlices>,
+    color_bar_config: Res<ColorBarConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    models: Query<Entity, With<UserModelMesh>>,
+    glyphs: Query<Entity, With<GlyphMesh>>,
+) {
+    if !settings.changed || settings.dragging {
+        return;
+    }
+    let remove_glyphs = |commands: &mut Commands| {
+        for entity in glyphs.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+    };
+    if !settings.enabled {
+        settings.changed = false;
+        settings.glyph_count = 0;
+        remove_glyphs(&mut commands);
+        return;
+    }
+    // A newly loaded model may not be spawned yet; retry on the next frame
+    let (Some(source), Some(model)) = (
+        Source::find(&current_model, &volume_slices),
+        models.iter().next(),
+    ) else {
+        return;
+    };
+    settings.changed = false;
+    settings.glyph_count = 0;
+    remove_glyphs(&mut commands);
+
+    // The attribute is picked in the panel; default to the first one when
+    // the glyphs were turned on from the menu
+    let Some(attribute) = settings
+        .attribute
+        .clone()
+        .or_else(|| source.attribute_names().into_iter().next())
+    else {
+        println!("No point vectors for glyphs");
+        return;
+    };
+    let Some((positions, vectors)) = source.points_and_vectors(&attribute) else {
+        println!("No point vectors '{}' for glyphs", attribute);
+        return;
+    };
+    let glyph_settings = GlyphSettings {
+        stride: settings.stride,
+        max_count: settings.max_count,
+        length: widget_state.scale * settings.scale_factor,
+        scale_by_magnitude: settings.scale_by_magnitude,
+    };
+    let geometry = arrow_glyphs(&positions, vectors, &glyph_settings);
+    let magnitudes: Vec<f32> = match geometry.get_attributes("magnitude", AttributeLocation::Point)
+    {
+        Some(AttributeType::Scalar { data, .. }) => data.clone(),
+        _ => Vec::new(),
+    };
+    settings.glyph_count = arrow_count(&geometry);
+    println!("Created {} glyphs of '{}'", settings.glyph_count, attribute);
+    if geometry.indices.is_empty() {
+        return;
+    }
+
+    let (min, max) = magnitudes
+        .iter()
+        .fold((f32::MAX, f32::MIN), |(min, max), &m| {
+            (min.min(m), max.max(m))
+        });
+    let color_map = get_color_map(&color_bar_config.color_map_name);
+    let span = (max - min).max(f32::EPSILON);
+    let colors: Vec<[f32; 4]> = magnitudes
+        .iter()
+        .map(|m| color_map.get_interpolated_color((m - min) / span))
+        .collect();
+    let mut mesh = mesh::create_mesh_from_geometry(&geometry);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+
+    commands
+        .spawn((
+            Name::new("Vector Glyphs"),
+            Mesh3d(meshes.add(mesh)),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: Color::WHITE,
+                perceptual_roughness: 0.5,
+                ..default()
+            })),
+            Transform::IDENTITY,
+            GlyphMesh,
+        ))
+        .set_parent(model);
+}
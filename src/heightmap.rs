@@ -0,0 +1,399 @@
+//! # Heightmap Module
+//!
+//! This module shows 2D data (flat image/structured grids) as a height field:
+//! - A scalar attribute is extruded along the normal of the data plane
+//!   (warp by scalar), colored with the current color map
+//! - A translucent base plane marks the zero level of the field
+//! - Axes along the plane edges and the height direction give the scale
+//!
+//! The model is hidden while the heightmap is shown. Toggle it via View > Heightmap.
+
+use crate::mesh::color_maps::{ColorMapper, ColorMappingConfig};
+use crate::mesh::topology::scalar_attribute_names;
+use crate::mesh::warp::{flat_axis, point_values, warp_by_scalar};
+use crate::mesh::AttributeLocation;
+use crate::ui::{ColorBarConfig, CurrentModelData, ModelLoadedEvent, UserModelMesh};
+use bevy::pbr::wireframe::NoWireframe;
+use bevy::pbr::NotShadowCaster;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Number of tick marks along each axis
+const AXIS_TICKS: usize = 5;
+
+/// Entities making up the heightmap
+type HeightmapEntities = Or<(With<Heightmap>, With<HeightmapBasePlane>)>;
+
+/// Heightmap settings
+#[derive(Resource)]
+pub struct HeightmapSettings {
+    /// Show the model as a height field
+    pub enabled: bool,
+    /// Scalar attribute used as height
+    pub scalar: Option<(String, AttributeLocation)>,
+    /// Height of the scalar range relative to the plane size
+    pub relief: f32,
+    /// Show the base plane
+    pub show_base_plane: bool,
+    /// Show the axes
+    pub show_axes: bool,
+    /// Settings changed, the heightmap needs to be rebuilt
+    pub changed: bool,
+}
+
+impl Default for HeightmapSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            scalar: None,
+            relief: 0.3,
+            show_base_plane: true,
+            show_axes: true,
+            changed: false,
+        }
+    }
+}
+
+/// Heightmap entity with the frame of its axes (in model coordinates)
+#[derive(Component)]
+pub struct Heightmap {
+    /// Corner of the base plane where the axes start
+    origin: Vec3,
+    /// In-plane axes and height axis, scaled to their length
+    axes: [Vec3; 3],
+}
+
+/// Marker component for the base plane entity
+#[derive(Component)]
+pub struct HeightmapBasePlane;
+
+pub struct HeightmapPlugin;
+
+impl Plugin for HeightmapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HeightmapSettings>().add_systems(
+            Update,
+            (
+                select_scalar_on_model_load,
+                heightmap_panel.after(EguiSet::InitContexts),
+                update_heightmap,
+                sync_heightmap_transform,
+                draw_heightmap_axes,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Pick the height scalar of a newly loaded model
+fn select_scalar_on_model_load(
+    mut model_loaded_events: EventReader<ModelLoadedEvent>,
+    current_model: Res<CurrentModelData>,
+    mut settings: ResMut<HeightmapSettings>,
+) {
+    for _ in model_loaded_events.read() {
+        // Prefer point data, which gives a smooth surface
+        let mut scalars = current_model
+            .geometry
+            .as_ref()
+            .map(scalar_attribute_names)
+            .unwrap_or_default();
+        scalars.sort_by_key(|(_, location)| *location != AttributeLocation::Point);
+        settings.scalar = scalars.into_iter().next();
+        settings.changed = true;
+    }
+}
+
+/// Heightmap settings panel
+fn heightmap_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<HeightmapSettings>,
+    current_model: Res<CurrentModelData>,
+    windows: Query<&Window>,
+) {
+    if !settings.enabled || windows.iter().next().is_none() {
+        return;
+    }
+
+    let flat = current_model
+        .geometry
+        .as_ref()
+        .and_then(flat_axis)
+        .is_some();
+    let scalars = current_model
+        .geometry
+        .as_ref()
+        .map(scalar_attribute_names)
+        .unwrap_or_default();
+
+    let mut scalar = settings.scalar.clone();
+    let mut relief = settings.relief;
+    let mut show_base_plane = settings.show_base_plane;
+    let mut show_axes = settings.show_axes;
+    let mut open = true;
+
+    egui::Window::new("Heightmap")
+        .default_width(240.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            if current_model.geometry.is_none() {
+                ui.label("No model loaded.");
+                return;
+            }
+            if !flat {
+                ui.label("The heightmap needs 2D data that is flat along one axis.");
+                return;
+            }
+
+            ui.label("Height scalar:");
+            egui::ComboBox::from_id_salt("heightmap_scalar")
+                .selected_text(
+                    scalar
+                        .as_ref()
+                        .map_or("None".to_string(), |(name, location)| {
+                            format!("{} ({:?})", name, location)
+                        }),
+                )
+                .show_ui(ui, |ui| {
+                    for (name, location) in &scalars {
+                        ui.selectable_value(
+                            &mut scalar,
+                            Some((name.clone(), location.clone())),
+                            format!("{} ({:?})", name, location),
+                        );
+                    }
+                });
+
+            ui.add(egui::Slider::new(&mut relief, 0.01..=2.0).text("Relief"));
+            ui.checkbox(&mut show_base_plane, "Base plane");
+            ui.checkbox(&mut show_axes, "Axes");
+        });
+
+    if scalar != settings.scalar || relief != settings.relief {
+        settings.scalar = scalar;
+        settings.relief = relief;
+        settings.changed = true;
+    }
+    if show_base_plane != settings.show_base_plane {
+        settings.show_base_plane = show_base_plane;
+        settings.changed = true;
+    }
+    if show_axes != settings.show_axes {
+        settings.show_axes = show_axes;
+    }
+
+    if !open {
+        settings.enabled = false;
+        settings.changed = true;
+    }
+}
+
+/// Rebuild the heightmap when the settings, the model or the color map changed
+#[allow(clippy::too_many_arguments)]
+fn update_heightmap(
+    mut commands: Commands,
+    mut settings: ResMut<HeightmapSettings>,
+    current_model: Res<CurrentModelData>,
+    color_bar_config: Res<ColorBarConfig>,
+    mut model_query: Query<&mut Visibility, With<UserModelMesh>>,
+    heightmaps: Query<Entity, HeightmapEntities>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut built_color_map: Local<String>,
+) {
+    let model_missing = current_model.geometry.is_none() && !heightmaps.is_empty();
+    let color_map_changed = settings.enabled && *built_color_map != color_bar_config.color_map_name;
+    if !(settings.changed || color_map_changed || model_missing) {
+        return;
+    }
+    settings.changed = false;
+    built_color_map.clone_from(&color_bar_config.color_map_name);
+
+    for entity in heightmaps.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    let heightmap = current_model
+        .geometry
+        .as_ref()
+        .filter(|geometry| settings.enabled && !geometry.indices.is_empty())
+        .and_then(|geometry| {
+            let normal = flat_axis(geometry)?;
+            let (name, location) = settings.scalar.as_ref()?;
+            let attribute = geometry.get_attributes(name, location.clone())?;
+            Some((
+                geometry,
+                normal,
+                point_values(geometry, attribute, location),
+            ))
+        });
+
+    let Some((geometry, normal, values)) = heightmap else {
+        for mut visibility in model_query.iter_mut() {
+            *visibility = Visibility::Inherited;
+        }
+        return;
+    };
+
+    let first = Vec3::from(geometry.vertices[0]);
+    let (min, max) = geometry
+        .vertices
+        .iter()
+        .fold((first, first), |(min, max), v| {
+            (min.min(Vec3::from(*v)), max.max(Vec3::from(*v)))
+        });
+    let (low, high) = values.iter().fold((f32::MAX, f32::MIN), |(low, high), &v| {
+        (low.min(v), high.max(v))
+    });
+    let size = (max - min).max_element();
+    let scale = if high > low {
+        settings.relief * size / (high - low)
+    } else {
+        0.0
+    };
+
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        warp_by_scalar(&geometry.vertices, &values, normal, low, scale),
+    );
+    mesh.insert_indices(Indices::U32(geometry.indices.clone()));
+    mesh.compute_normals();
+    let color_config = ColorMappingConfig {
+        color_map_name: color_bar_config.color_map_name.clone(),
+        ..default()
+    };
+    ColorMapper::apply_scalars_to_mesh(&mut mesh, &values, &color_config);
+
+    // Axes along the plane edges, starting at the minimum corner
+    let (u_axis, v_axis) = if normal == Vec3::X {
+        (Vec3::Y, Vec3::Z)
+    } else if normal == Vec3::Y {
+        (Vec3::Z, Vec3::X)
+    } else {
+        (Vec3::X, Vec3::Y)
+    };
+    let extent = max - min;
+    let axes = [
+        u_axis * extent.dot(u_axis),
+        v_axis * extent.dot(v_axis),
+        normal * settings.relief * size,
+    ];
+
+    commands.spawn((
+        Name::new("Heightmap"),
+        Mesh3d(meshes.add(mesh)),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::WHITE,
+            perceptual_roughness: 0.6,
+            cull_mode: None,
+            double_sided: true,
+            ..default()
+        })),
+        Heightmap { origin: min, axes },
+    ));
+
+    if settings.show_base_plane {
+        // Slightly below the zero level to avoid z-fighting with flat regions
+        let base = min - normal * size * 1e-3;
+        let corners = [
+            base,
+            base + axes[0],
+            base + axes[0] + axes[1],
+            base + axes[1],
+        ];
+        let mut plane = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        );
+        plane.insert_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            corners.iter().map(|c| c.to_array()).collect::<Vec<_>>(),
+        );
+        plane.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![normal.to_array(); 4]);
+        plane.insert_indices(Indices::U32(vec![0, 1, 2, 0, 2, 3]));
+
+        commands.spawn((
+            Name::new("Heightmap Base Plane"),
+            Mesh3d(meshes.add(plane)),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: Color::srgba(0.6, 0.6, 0.65, 0.35),
+                alpha_mode: AlphaMode::Blend,
+                cull_mode: None,
+                double_sided: true,
+                unlit: true,
+                ..default()
+            })),
+            NoWireframe,
+            NotShadowCaster,
+            HeightmapBasePlane,
+        ));
+    }
+
+    println!(
+        "Heightmap built from {} values in [{}, {}]",
+        values.len(),
+        low,
+        high
+    );
+}
+
+/// Keep the heightmap aligned with the (possibly transformed) model, which
+/// stays hidden while the heightmap is shown
+fn sync_heightmap_transform(
+    mut model_query: Query<(&Transform, &mut Visibility), With<UserModelMesh>>,
+    mut heightmaps: Query<&mut Transform, (HeightmapEntities, Without<UserModelMesh>)>,
+) {
+    if heightmaps.is_empty() {
+        return;
+    }
+    let Some((model_transform, mut visibility)) = model_query.iter_mut().next() else {
+        return;
+    };
+    if *visibility != Visibility::Hidden {
+        *visibility = Visibility::Hidden;
+    }
+    for mut transform in heightmaps.iter_mut() {
+        *transform = *model_transform;
+    }
+}
+
+/// Draw the axes with tick marks along the plane edges and the height
+fn draw_heightmap_axes(
+    settings: Res<HeightmapSettings>,
+    heightmaps: Query<(&Heightmap, &GlobalTransform)>,
+    mut gizmos: Gizmos,
+) {
+    if !settings.show_axes {
+        return;
+    }
+
+    let colors = [
+        Color::srgb(0.9, 0.3, 0.3),
+        Color::srgb(0.3, 0.9, 0.3),
+        Color::srgb(0.3, 0.5, 1.0),
+    ];
+    for (heightmap, global_transform) in heightmaps.iter() {
+        let origin = global_transform.transform_point(heightmap.origin);
+        let axes = heightmap
+            .axes
+            .map(|axis| global_transform.affine().transform_vector3(axis));
+        let tick_length = axes[0].length().max(axes[1].length()) * 0.02;
+
+        for (index, (axis, color)) in axes.iter().zip(colors).enumerate() {
+            gizmos.line(origin, origin + *axis, color);
+
+            // Ticks point away from the plane along the next axis
+            let tick_direction = axes[(index + 1) % 3].normalize_or_zero() * tick_length;
+            for tick in 0..=AXIS_TICKS {
+                let position = origin + *axis * (tick as f32 / AXIS_TICKS as f32);
+                gizmos.line(position, position - tick_direction, color);
+            }
+        }
+    }
+}
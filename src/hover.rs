@@ -0,0 +1,175 @@
+//! # Hover Module
+//!
+//! This module shows the value under the mouse cursor:
+//! - While enabled, the surface under the cursor is ray-cast at most every
+//!   `HOVER_INTERVAL` seconds, and only after the cursor moved
+//! - A tooltip next to the cursor lists the value of the attribute the model is
+//!   colored by, interpolated inside the hit triangle for point attributes, and
+//!   the world position of the hit
+//!
+//! Toggle the tooltip via View > Hover Values.
+
+use crate::camera::WorldModelCamera;
+use crate::mesh::color_maps::ColorMapper;
+use crate::mesh::probe::{probe_attribute, ProbedValue};
+use crate::picking::{cursor_ray_in_model_space, model_matrix, pointer_over_ui, PickingBvh};
+use crate::ui::color_bar::format_legend_value;
+use crate::ui::{ColorBarConfig, CurrentModelData, UserModelMesh};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Shortest time between two ray casts, in seconds
+const HOVER_INTERVAL: f32 = 0.05;
+
+/// Hover tooltip settings
+#[derive(Resource, Default)]
+pub struct HoverSettings {
+    /// Show the tooltip
+    pub enabled: bool,
+}
+
+/// Surface location under the cursor
+#[derive(Clone, Debug)]
+struct HoverHit {
+    /// Hit position in world space
+    position: Vec3,
+    /// Value of the colored attribute, `None` if the model is not colored
+    value: Option<ProbedValue>,
+}
+
+/// Ray cast state of the tooltip
+#[derive(Resource, Default)]
+struct HoverState {
+    /// Cursor position of the last ray cast
+    cursor: Option<Vec2>,
+    /// Time since the last ray cast
+    elapsed: f32,
+    /// Result of the last ray cast
+    hit: Option<HoverHit>,
+}
+
+pub struct HoverPlugin;
+
+impl Plugin for HoverPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HoverSettings>()
+            .init_resource::<HoverState>()
+            .add_systems(
+                Update,
+                (hover_pick, hover_tooltip.after(EguiSet::InitContexts)).chain(),
+            );
+    }
+}
+
+/// Ray-cast the surface under the cursor, throttled
+#[allow(clippy::too_many_arguments)]
+fn hover_pick(
+    time: Res<Time>,
+    settings: Res<HoverSettings>,
+    current_model: Res<CurrentModelData>,
+    color_bar_config: Res<ColorBarConfig>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<WorldModelCamera>>,
+    model_query: Query<&GlobalTransform, With<UserModelMesh>>,
+    mut contexts: EguiContexts,
+    mut state: ResMut<HoverState>,
+    mut picking_bvh: ResMut<PickingBvh>,
+) {
+    if !settings.enabled {
+        if state.hit.is_some() || state.cursor.is_some() {
+            *state = HoverState::default();
+        }
+        return;
+    }
+    state.elapsed += time.delta_secs();
+
+    let cursor = windows
+        .get_single()
+        .ok()
+        .and_then(|window| window.cursor_position());
+    let (Some(cursor), Some(geometry)) = (cursor, current_model.geometry.as_ref()) else {
+        state.cursor = None;
+        state.hit = None;
+        return;
+    };
+    if pointer_over_ui(&mut contexts) {
+        state.hit = None;
+        return;
+    }
+    let moved = state.cursor != Some(cursor);
+    let stale = current_model.is_changed() || color_bar_config.is_changed();
+    if !(moved || stale) || state.elapsed < HOVER_INTERVAL {
+        return;
+    }
+    state.cursor = Some(cursor);
+    state.elapsed = 0.0;
+
+    let model_to_world = model_matrix(&model_query);
+    let hit = cursor_ray_in_model_space(&windows, &camera_query, model_to_world)
+        .and_then(|(origin, direction)| picking_bvh.pick(geometry, origin, direction));
+    state.hit = hit.map(|hit| HoverHit {
+        position: model_to_world.transform_point3(hit.position),
+        value: ColorMapper::colored_attribute(geometry, color_bar_config.attribute.as_ref())
+            .and_then(|(name, location)| {
+                probe_attribute(
+                    geometry,
+                    hit.triangle,
+                    hit.barycentric.to_array(),
+                    &name,
+                    location,
+                )
+            }),
+    });
+}
+
+/// Tooltip next to the cursor
+fn hover_tooltip(
+    mut contexts: EguiContexts,
+    settings: Res<HoverSettings>,
+    state: Res<HoverState>,
+    color_bar_config: Res<ColorBarConfig>,
+) {
+    let Some(ref hit) = state.hit else {
+        return;
+    };
+    if !settings.enabled {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let Some(pointer) = ctx.pointer_hover_pos() else {
+        return;
+    };
+
+    let precision = color_bar_config.precision + 2;
+    egui::Area::new(egui::Id::new("hover_tooltip"))
+        .fixed_pos(pointer + egui::vec2(16.0, 16.0))
+        .interactable(false)
+        .order(egui::Order::Tooltip)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                if let Some(ref value) = hit.value {
+                    let magnitude = value
+                        .magnitude()
+                        .or_else(|| value.components.first().copied())
+                        .unwrap_or(f32::NAN);
+                    let label = if value.components.len() > 1 {
+                        format!("|{}|", value.name)
+                    } else {
+                        value.name.clone()
+                    };
+                    ui.label(format!(
+                        "{}: {}",
+                        label,
+                        format_legend_value(magnitude, precision, &color_bar_config.unit)
+                    ));
+                }
+                ui.label(format!(
+                    "Position: ({:.4}, {:.4}, {:.4})",
+                    hit.position.x, hit.position.y, hit.position.z
+                ));
+            });
+        });
+}
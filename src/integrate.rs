@@ -0,0 +1,179 @@
+//! # Integrate Variables Module
+//!
+//! This module reports integrated quantities of the model:
+//! - Total surface area and, for closed surfaces, the enclosed volume
+//! - Area-weighted mean, minimum and maximum of every scalar attribute
+//! - Results can be copied or exported as CSV
+//!
+//! Open the panel via Mesh > Integrate Variables.
+
+use crate::mesh::integrate::{integrate, IntegrationReport};
+use crate::mesh::AttributeLocation;
+use crate::ui::{CurrentModelData, FileDialogs, ModelLoadedEvent};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+use rfd::AsyncFileDialog;
+
+/// Integrate variables settings
+#[derive(Resource, Default)]
+pub struct IntegrateSettings {
+    /// Show the results panel
+    pub show_panel: bool,
+    /// Integration requested (the panel was opened or Recompute clicked)
+    compute: bool,
+    /// Results for the current model
+    report: Option<IntegrationReport>,
+}
+
+pub struct IntegratePlugin;
+
+impl Plugin for IntegratePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<IntegrateSettings>().add_systems(
+            Update,
+            (
+                reset_on_model_load,
+                integrate_panel.after(EguiSet::InitContexts),
+                compute_report,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Results of the previous model no longer apply
+fn reset_on_model_load(
+    mut loaded_events: EventReader<ModelLoadedEvent>,
+    mut settings: ResMut<IntegrateSettings>,
+) {
+    if loaded_events.read().count() > 0 {
+        settings.report = None;
+    }
+}
+
+/// Integration results panel
+fn integrate_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<IntegrateSettings>,
+    current_model: Res<CurrentModelData>,
+    mut file_dialogs: ResMut<FileDialogs>,
+    windows: Query<&Window>,
+) {
+    if !settings.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+    if settings.report.is_none() && current_model.geometry.is_some() {
+        settings.compute = true;
+    }
+
+    let mut compute = false;
+    let mut open = true;
+
+    egui::Window::new("Integrate Variables")
+        .default_width(380.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            let Some(ref report) = settings.report else {
+                ui.label("Load a model to integrate its variables.");
+                return;
+            };
+
+            ui.label(format!("Triangles: {}", report.triangles));
+            ui.label(format!("Surface area: {:.6}", report.area));
+            match report.volume {
+                Some(volume) => ui.label(format!("Enclosed volume: {:.6}", volume)),
+                None => ui.label("Enclosed volume: - (surface is not closed)"),
+            };
+            ui.separator();
+
+            if report.attributes.is_empty() {
+                ui.label("The model has no scalar attributes.");
+            } else {
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("integrated_attributes")
+                            .striped(true)
+                            .num_columns(5)
+                            .show(ui, |ui| {
+                                for header in ["Attribute", "Integral", "Mean", "Min", "Max"] {
+                                    ui.strong(header);
+                                }
+                                ui.end_row();
+                                for attribute in &report.attributes {
+                                    let location = match attribute.location {
+                                        AttributeLocation::Point => "point",
+                                        AttributeLocation::Cell => "cell",
+                                    };
+                                    ui.label(format!("{} ({})", attribute.name, location));
+                                    ui.label(format!("{:.4}", attribute.integral));
+                                    ui.label(format!("{:.4}", attribute.mean));
+                                    ui.label(format!("{:.4}", attribute.min));
+                                    ui.label(format!("{:.4}", attribute.max));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("Recompute").clicked() {
+                    compute = true;
+                }
+                if ui.button("Copy Values").clicked() {
+                    ui.ctx().copy_text(report.to_csv());
+                }
+                if ui.button("Export CSV...").clicked() {
+                    export_report(report.to_csv(), &mut file_dialogs);
+                }
+            });
+        });
+
+    if compute {
+        settings.compute = true;
+    }
+
+    if !open {
+        settings.show_panel = false;
+    }
+}
+
+/// Integrate the current model
+fn compute_report(mut settings: ResMut<IntegrateSettings>, current_model: Res<CurrentModelData>) {
+    if !settings.compute {
+        return;
+    }
+    settings.compute = false;
+    let Some(ref geometry) = current_model.geometry else {
+        return;
+    };
+
+    let start = std::time::Instant::now();
+    let report = integrate(geometry);
+    println!(
+        "Integrated {} triangles in {:?}: area {}, volume {:?}",
+        report.triangles,
+        start.elapsed(),
+        report.area,
+        report.volume
+    );
+    settings.report = Some(report);
+}
+
+/// Ask for a file name and write the report to it on the IO task pool
+fn export_report(csv: String, file_dialogs: &mut FileDialogs) {
+    let dialog = AsyncFileDialog::new()
+        .add_filter("CSV", &["csv"])
+        .set_file_name("integrated_variables.csv")
+        .set_directory(std::env::var("HOME").unwrap_or_else(|_| "/".to_string()));
+    file_dialogs.open(async move {
+        let file = dialog.save_file().await?;
+        let path = file.path();
+        match std::fs::write(path, csv) {
+            Ok(()) => println!("Integrated variables exported to {}", path.display()),
+            Err(e) => eprintln!("Failed to export integrated variables: {}", e),
+        }
+        None
+    });
+}
@@ -0,0 +1,350 @@
+//! # Isosurface Module
+//!
+//! This module shows contour surfaces of point scalars inside the model volume:
+//! - Sources are the volume cells (tetrahedra, hexahedra, wedges, pyramids) of
+//!   unstructured grids and image data volumes (`.vti`)
+//! - The panel selects the point attribute (vectors by magnitude) and the
+//!   isovalue within the value range of the attribute
+//! - The surface is colored at the isovalue with the color map of the color bar
+//!
+//! The isosurface is a child of the model, so it follows the model transform and
+//! is removed with the model. Open the panel via Mesh > Isosurface.
+
+use crate::mesh::color_maps::{ColorMapper, ColorMappingConfig};
+use crate::mesh::isosurface::{isosurface_cells, isosurface_volume, value_range};
+use crate::mesh::{self, AttributeLocation, AttributeType, GeometryData};
+use crate::ui::{ColorBarConfig, CurrentModelData, ModelLoadedEvent, UserModelMesh};
+use crate::volume_slices::VolumeSlices;
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Isosurface settings
+#[derive(Resource, Default)]
+pub struct IsosurfaceSettings {
+    /// Show the isosurface panel
+    pub show_panel: bool,
+    /// Show the isosurface
+    pub enabled: bool,
+    /// Contoured point attribute
+    pub attribute: Option<String>,
+    /// Contour value
+    pub isovalue: f32,
+    /// Settings changed, the surface needs to be rebuilt
+    pub changed: bool,
+    /// The isovalue slider is being dragged, rebuild once released
+    dragging: bool,
+    /// Value range of the contoured attribute
+    range: Option<(f32, f32)>,
+    /// Triangles of the current surface
+    triangles: usize,
+}
+
+/// Marker component of the isosurface entity
+#[derive(Component)]
+pub struct IsosurfaceMesh;
+
+pub struct IsosurfacePlugin;
+
+impl Plugin for IsosurfacePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<IsosurfaceSettings>().add_systems(
+            Update,
+            (
+                refresh_on_model_load,
+                isosurface_panel.after(EguiSet::InitContexts),
+                update_isosurface,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Source of the isosurface: volume cells of the model or an image volume
+enum Source<'a> {
+    Cells(&'a GeometryData),
+    Volume(&'a mesh::volume::VolumeData),
+}
+
+impl Source<'_> {
+    fn find<'a>(
+        current_model: &'a CurrentModelData,
+        volume_slices: &'a VolumeSlices,
+    ) -> Option<Source<'a>> {
+        if let Some(volume) = volume_slices.volume() {
+            return Some(Source::Volume(volume));
+        }
+        current_model
+            .geometry
+            .as_ref()
+            .filter(|geometry| {
+                geometry
+                    .volume_cells
+                    .as_ref()
+                    .is_some_and(|cells| !cells.is_empty())
+            })
+            .map(Source::Cells)
+    }
+
+    /// Point attributes that can be contoured, sorted by name
+    fn attribute_names(&self) -> Vec<String> {
+        let attributes = match self {
+            Source::Cells(geometry) => geometry.attributes.as_ref(),
+            Source::Volume(volume) => Some(&volume.attributes),
+        };
+        let mut names: Vec<String> = attributes
+            .into_iter()
+            .flatten()
+            .filter(|((name, location), attribute)| {
+                *location == AttributeLocation::Point
+                    && !name.starts_with("__lut_")
+                    && matches!(
+                        attribute,
+                        AttributeType::Scalar { .. } | AttributeType::Vector(_)
+                    )
+            })
+            .map(|((name, _), _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn attribute(&self, name: &str) -> Option<(&AttributeType, usize)> {
+        let key = (name.to_string(), AttributeLocation::Point);
+        match self {
+            Source::Cells(geometry) => geometry
+                .attributes
+                .as_ref()?
+                .get(&key)
+                .map(|attribute| (attribute, geometry.vertices.len())),
+            Source::Volume(volume) => volume
+                .attributes
+                .get(&key)
+                .map(|attribute| (attribute, volume.point_count())),
+        }
+    }
+
+    fn description(&self) -> String {
+        match self {
+            Source::Cells(geometry) => format!(
+                "{} volume cells",
+                geometry
+                    .volume_cells
+                    .as_ref()
+                    .map_or(0, |cells| cells.len())
+            ),
+            Source::Volume(volume) => format!(
+                "Volume: {} x {} x {} points",
+                volume.dims[0], volume.dims[1], volume.dims[2]
+            ),
+        }
+    }
+
+    fn isosurface(&self, attribute: &str, isovalue: f32) -> Option<GeometryData> {
+        match self {
+            Source::Cells(geometry) => isosurface_cells(geometry, attribute, isovalue),
+            Source::Volume(volume) => isosurface_volume(volume, attribute, isovalue),
+        }
+    }
+}
+
+/// Rebuild the isosurface for a newly loaded model
+fn refresh_on_model_load(
+    mut loaded_events: EventReader<ModelLoadedEvent>,
+    mut settings: ResMut<IsosurfaceSettings>,
+) {
+    if loaded_events.read().count() > 0 {
+        settings.range = None;
+        settings.changed = settings.enabled;
+    }
+}
+
+/// Isosurface panel
+fn isosurface_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<IsosurfaceSettings>,
+    current_model: Res<CurrentModelData>,
+    volume_slices: Res<VolumeSlices>,
+    windows: Query<&Window>,
+) {
+    if !settings.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let source = Source::find(&current_model, &volume_slices);
+    let mut enabled = settings.enabled;
+    let mut attribute = settings.attribute.clone();
+    let mut isovalue = settings.isovalue;
+    let mut range = settings.range;
+    let mut dragging = false;
+    let mut open = true;
+
+    egui::Window::new("Isosurface")
+        .default_width(280.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            let Some(source) = source else {
+                ui.label("Load an unstructured grid with volume cells or a .vti volume.");
+                return;
+            };
+            ui.label(source.description());
+
+            let names = source.attribute_names();
+            if names.is_empty() {
+                ui.label("The dataset has no point scalars.");
+                return;
+            }
+            if attribute.as_ref().is_none_or(|name| !names.contains(name)) {
+                attribute = names.first().cloned();
+                range = None;
+            }
+
+            ui.checkbox(&mut enabled, "Show isosurface");
+            ui.separator();
+            egui::ComboBox::from_label("Attribute")
+                .selected_text(attribute.clone().unwrap_or_default())
+                .show_ui(ui, |ui| {
+                    for name in &names {
+                        if ui
+                            .selectable_label(attribute.as_ref() == Some(name), name)
+                            .clicked()
+                        {
+                            attribute = Some(name.clone());
+                            range = None;
+                        }
+                    }
+                });
+
+            let Some(name) = attribute.as_deref() else {
+                return;
+            };
+            if range.is_none() {
+                range = source
+                    .attribute(name)
+                    .and_then(|(attribute, count)| value_range(attribute, count));
+                if let Some((min, max)) = range {
+                    isovalue = (min + max) / 2.0;
+                }
+            }
+            let Some((min, max)) = range else {
+                ui.label("The attribute has no finite values.");
+                return;
+            };
+            let response = ui.add_enabled(
+                enabled,
+                egui::Slider::new(&mut isovalue, min..=max).text("Isovalue"),
+            );
+            dragging = response.dragged();
+            ui.label(format!("Range: {:.4} .. {:.4}", min, max));
+            if enabled {
+                ui.label(format!("{} triangles", settings.triangles));
+            }
+        });
+
+    if enabled != settings.enabled
+        || attribute != settings.attribute
+        || isovalue != settings.isovalue
+        || range != settings.range
+    {
+        settings.enabled = enabled;
+        settings.attribute = attribute;
+        settings.isovalue = isovalue;
+        settings.range = range;
+        settings.changed = true;
+    }
+    if dragging != settings.dragging {
+        settings.dragging = dragging;
+    }
+
+    if !open {
+        settings.show_panel = false;
+    }
+}
+
+/// Replace the isosurface entity after a settings change
+#[allow(clippy::too_many_arguments)]
+fn update_isosurface(
+    mut commands: Commands,
+    mut settings: ResMut<IsosurfaceSettings>,
+    current_model: Res<CurrentModelData>,
+    volume_slices: Res<VolumeSlices>,
+    color_bar_config: Res<ColorBarConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    models: Query<Entity, With<UserModelMesh>>,
+    surfaces: Query<Entity, With<IsosurfaceMesh>>,
+) {
+    if !settings.changed || settings.dragging {
+        return;
+    }
+    let remove_surfaces = |commands: &mut Commands| {
+        for entity in surfaces.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+    };
+    if !settings.enabled {
+        settings.changed = false;
+        settings.triangles = 0;
+        remove_surfaces(&mut commands);
+        return;
+    }
+    // A newly loaded model may not be spawned yet; retry on the next frame
+    let (Some(source), Some(attribute), Some(model)) = (
+        Source::find(&current_model, &volume_slices),
+        settings.attribute.clone(),
+        models.iter().next(),
+    ) else {
+        return;
+    };
+    settings.changed = false;
+    settings.triangles = 0;
+    remove_surfaces(&mut commands);
+
+    let Some(geometry) = source.isosurface(&attribute, settings.isovalue) else {
+        println!("No isosurface for attribute '{}'", attribute);
+        return;
+    };
+    settings.triangles = geometry.indices.len() / 3;
+    println!(
+        "Isosurface of {} at {}: {} triangles",
+        attribute, settings.isovalue, settings.triangles
+    );
+    if geometry.indices.is_empty() {
+        return;
+    }
+
+    let mut mesh = mesh::create_mesh_from_geometry(&geometry);
+    let config = ColorMappingConfig {
+        color_map_name: color_bar_config.color_map_name.clone(),
+        min_value: color_bar_config.min_value,
+        max_value: color_bar_config.max_value,
+        use_custom_range: true,
+        attribute: None,
+        log_scale: color_bar_config.log_scale,
+        mode: color_bar_config.mode,
+        bins: color_bar_config.bins,
+        nan_color: color_bar_config.nan_color,
+        below_color: color_bar_config.below_color,
+        above_color: color_bar_config.above_color,
+    };
+    ColorMapper::apply_scalars_to_mesh(
+        &mut mesh,
+        &vec![settings.isovalue; geometry.vertices.len()],
+        &config,
+    );
+
+    commands
+        .spawn((
+            Name::new("Isosurface"),
+            Mesh3d(meshes.add(mesh)),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: Color::WHITE,
+                perceptual_roughness: 0.5,
+                cull_mode: None,
+                double_sided: true,
+                ..default()
+            })),
+            Transform::IDENTITY,
+            IsosurfaceMesh,
+        ))
+        .set_parent(model);
+}
@@ -0,0 +1,302 @@
+//! # ID Labels Module
+//!
+//! This module draws point and cell ID labels on top of the model to help debug
+//! meshes and match them against solver logs:
+//! - Point labels sit on the vertices, cell labels on the cell centers
+//! - Labels are drawn in screen space, so they always face the camera
+//! - Either the current selection (selected cells and their points) or the whole
+//!   dataset is labeled; whole datasets are limited to a maximum label count
+//!
+//! Toggle the settings panel via View > ID Labels.
+
+use crate::camera::WorldModelCamera;
+use crate::mesh::topology::{cell_triangles, cell_vertices};
+use crate::mesh::GeometryData;
+use crate::preferences::UiSettings;
+use crate::selection::SelectionState;
+use crate::ui::{CurrentModelData, UserModelMesh};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+use std::collections::BTreeSet;
+
+/// Point label text color
+const POINT_LABEL_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 200, 80);
+/// Cell label text color
+const CELL_LABEL_COLOR: egui::Color32 = egui::Color32::from_rgb(120, 220, 255);
+
+/// ID label settings
+#[derive(Resource)]
+pub struct LabelSettings {
+    /// Show the settings panel
+    pub show_panel: bool,
+    /// Label point IDs
+    pub point_ids: bool,
+    /// Label cell IDs
+    pub cell_ids: bool,
+    /// Only label the selected cells and their points
+    pub selection_only: bool,
+    /// Maximum number of labels when labeling the whole dataset
+    pub max_labels: usize,
+}
+
+impl Default for LabelSettings {
+    fn default() -> Self {
+        Self {
+            show_panel: false,
+            point_ids: false,
+            cell_ids: false,
+            selection_only: true,
+            max_labels: 2000,
+        }
+    }
+}
+
+/// Label position (model space) and text
+struct LabelAnchor {
+    position: Vec3,
+    text: String,
+    is_cell: bool,
+}
+
+/// Cached label anchors, rebuilt when the model, selection or settings change
+#[derive(Resource, Default)]
+struct LabelCache {
+    anchors: Vec<LabelAnchor>,
+    /// Number of labels that would be needed when over the limit
+    skipped: Option<usize>,
+}
+
+pub struct LabelPlugin;
+
+impl Plugin for LabelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LabelSettings>()
+            .init_resource::<LabelCache>()
+            .add_systems(
+                Update,
+                (
+                    label_panel.after(EguiSet::InitContexts),
+                    update_label_cache,
+                    draw_labels.after(EguiSet::InitContexts),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Build label anchors for the given cells and points
+fn build_anchors(
+    geometry: &GeometryData,
+    cell_points: &[Vec<u32>],
+    cells: impl Iterator<Item = usize>,
+    points: impl Iterator<Item = usize>,
+) -> Vec<LabelAnchor> {
+    let mut anchors = Vec::new();
+
+    for cell in cells {
+        let Some(vertices) = cell_points.get(cell).filter(|v| !v.is_empty()) else {
+            continue;
+        };
+        let center = vertices
+            .iter()
+            .map(|&v| Vec3::from(geometry.vertices[v as usize]))
+            .sum::<Vec3>()
+            / vertices.len() as f32;
+        anchors.push(LabelAnchor {
+            position: center,
+            text: cell.to_string(),
+            is_cell: true,
+        });
+    }
+
+    for point in points {
+        if let Some(position) = geometry.vertices.get(point) {
+            anchors.push(LabelAnchor {
+                position: Vec3::from(*position),
+                text: point.to_string(),
+                is_cell: false,
+            });
+        }
+    }
+
+    anchors
+}
+
+/// Rebuild the label anchors when needed
+fn update_label_cache(
+    settings: Res<LabelSettings>,
+    selection_state: Res<SelectionState>,
+    current_model: Res<CurrentModelData>,
+    mut cache: ResMut<LabelCache>,
+) {
+    if !(settings.is_changed() || selection_state.is_changed() || current_model.is_changed()) {
+        return;
+    }
+
+    cache.anchors.clear();
+    cache.skipped = None;
+    let Some(ref geometry) = current_model.geometry else {
+        return;
+    };
+    if !settings.point_ids && !settings.cell_ids {
+        return;
+    }
+
+    let triangles = cell_triangles(geometry);
+    let cell_points = cell_vertices(geometry, &triangles);
+
+    let (cells, points): (BTreeSet<usize>, BTreeSet<usize>) = if settings.selection_only {
+        let cells = selection_state.selected_cells.clone();
+        let points = cells
+            .iter()
+            .filter_map(|&cell| cell_points.get(cell))
+            .flatten()
+            .map(|&v| v as usize)
+            .collect();
+        (cells, points)
+    } else {
+        (
+            (0..cell_points.len()).collect(),
+            (0..geometry.vertices.len()).collect(),
+        )
+    };
+
+    let cells = if settings.cell_ids {
+        cells
+    } else {
+        BTreeSet::new()
+    };
+    let points = if settings.point_ids {
+        points
+    } else {
+        BTreeSet::new()
+    };
+
+    let label_count = cells.len() + points.len();
+    if !settings.selection_only && label_count > settings.max_labels {
+        cache.skipped = Some(label_count);
+        return;
+    }
+
+    cache.anchors = build_anchors(
+        geometry,
+        &cell_points,
+        cells.into_iter(),
+        points.into_iter(),
+    );
+}
+
+/// Draw the labels at the projected anchor positions
+fn draw_labels(
+    mut contexts: EguiContexts,
+    cache: Res<LabelCache>,
+    ui_settings: Res<UiSettings>,
+    model_query: Query<&GlobalTransform, With<UserModelMesh>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<WorldModelCamera>>,
+) {
+    if cache.anchors.is_empty() {
+        return;
+    }
+    let Some((camera, camera_transform)) = camera_query.iter().next() else {
+        return;
+    };
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let model_to_world = crate::picking::model_matrix(&model_query);
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Foreground,
+        egui::Id::new("id_labels"),
+    ));
+    let screen = ctx.screen_rect();
+    let font = egui::FontId::monospace(11.0);
+
+    for anchor in &cache.anchors {
+        let Ok(screen_position) = camera.world_to_viewport(
+            camera_transform,
+            model_to_world.transform_point3(anchor.position),
+        ) else {
+            continue;
+        };
+        let position = ui_settings.ui_position(screen_position);
+        if !screen.contains(position) {
+            continue;
+        }
+
+        let color = if anchor.is_cell {
+            CELL_LABEL_COLOR
+        } else {
+            POINT_LABEL_COLOR
+        };
+        let galley = painter.layout_no_wrap(anchor.text.clone(), font.clone(), color);
+        let rect = egui::Align2::CENTER_CENTER.anchor_size(position, galley.size());
+        painter.rect_filled(rect.expand(1.0), 2.0, egui::Color32::from_black_alpha(160));
+        painter.galley(rect.min, galley, color);
+    }
+}
+
+/// ID label settings panel
+fn label_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<LabelSettings>,
+    cache: Res<LabelCache>,
+    windows: Query<&Window>,
+) {
+    if !settings.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let mut point_ids = settings.point_ids;
+    let mut cell_ids = settings.cell_ids;
+    let mut selection_only = settings.selection_only;
+    let mut max_labels = settings.max_labels;
+    let mut open = true;
+
+    egui::Window::new("ID Labels")
+        .default_width(220.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.checkbox(&mut point_ids, "Point IDs");
+            ui.checkbox(&mut cell_ids, "Cell IDs");
+            ui.separator();
+
+            ui.radio_value(&mut selection_only, true, "Selected cells only");
+            ui.radio_value(&mut selection_only, false, "Whole dataset");
+            if !selection_only {
+                ui.horizontal(|ui| {
+                    ui.label("Max labels:");
+                    ui.add(egui::DragValue::new(&mut max_labels).range(1..=100_000));
+                });
+            }
+
+            ui.separator();
+            if let Some(count) = cache.skipped {
+                ui.colored_label(
+                    egui::Color32::YELLOW,
+                    format!("{} labels exceed the limit, select cells instead", count),
+                );
+            } else {
+                ui.label(format!("{} labels", cache.anchors.len()));
+            }
+            ui.horizontal(|ui| {
+                ui.colored_label(POINT_LABEL_COLOR, "■ point");
+                ui.colored_label(CELL_LABEL_COLOR, "■ cell");
+            });
+        });
+
+    if point_ids != settings.point_ids
+        || cell_ids != settings.cell_ids
+        || selection_only != settings.selection_only
+        || max_labels != settings.max_labels
+    {
+        settings.point_ids = point_ids;
+        settings.cell_ids = cell_ids;
+        settings.selection_only = selection_only;
+        settings.max_labels = max_labels;
+    }
+
+    if !open {
+        settings.show_panel = false;
+    }
+}
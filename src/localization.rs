@@ -0,0 +1,395 @@
+//! # Localization Module
+//!
+//! This module translates the user-facing text of the menu bar, the time
+//! series panel and the notification windows:
+//! - The English text is the key of the translation tables, so text without a
+//!   translation falls back to English
+//! - Templates with `{}` placeholders are filled in order with `fill`
+//! - The language is global, so every system and the file dialog threads can
+//!   translate without extra system parameters
+//! - Chinese needs a CJK font, which is loaded from the system fonts when the
+//!   language is selected
+//!
+//! Select the language via File > Preferences.
+
+use bevy_egui::egui;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+/// Current language, the index in `Language::ALL`
+static LANGUAGE: AtomicU8 = AtomicU8::new(0);
+
+/// Language of the user interface
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Language {
+    English,
+    Chinese,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Chinese];
+
+    /// Name of the language, in English since the CJK font is only loaded
+    /// once Chinese is selected
+    pub fn label(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Chinese => "Chinese",
+        }
+    }
+
+    /// Key of the language in the preferences file
+    pub fn key(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Chinese => "zh",
+        }
+    }
+
+    /// Language from its key in the preferences file
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|language| language.key() == key)
+    }
+
+    /// Translations of the English texts, `None` for English itself
+    fn table(self) -> Option<&'static HashMap<&'static str, &'static str>> {
+        static CHINESE_TABLE: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+        match self {
+            Language::English => None,
+            Language::Chinese => {
+                Some(CHINESE_TABLE.get_or_init(|| CHINESE.iter().copied().collect()))
+            }
+        }
+    }
+}
+
+/// Current language of the user interface
+pub fn language() -> Language {
+    Language::ALL
+        .get(LANGUAGE.load(Ordering::Relaxed) as usize)
+        .copied()
+        .unwrap_or(Language::English)
+}
+
+/// Switch the language of the user interface
+pub fn set_language(language: Language) {
+    let index = Language::ALL
+        .iter()
+        .position(|candidate| *candidate == language)
+        .unwrap_or(0);
+    LANGUAGE.store(index as u8, Ordering::Relaxed);
+}
+
+/// Translate an English text into the current language
+///
+/// # Parameters
+/// - `text`: English text, the key of the translation tables
+///
+/// # Returns
+/// - The translation, or `text` itself without one
+pub fn tr(text: &'static str) -> &'static str {
+    language()
+        .table()
+        .and_then(|table| table.get(text).copied())
+        .unwrap_or(text)
+}
+
+/// Translate a template and fill its `{}` placeholders in order
+///
+/// # Parameters
+/// - `template`: English template, the key of the translation tables
+/// - `args`: Values of the placeholders
+pub fn fill(template: &'static str, args: &[&dyn Display]) -> String {
+    let mut parts = tr(template).split("{}");
+    let mut text = parts.next().unwrap_or_default().to_string();
+    for (index, part) in parts.enumerate() {
+        if let Some(arg) = args.get(index) {
+            text.push_str(&arg.to_string());
+        }
+        text.push_str(part);
+    }
+    text
+}
+
+/// System fonts with Chinese glyphs, tried in order
+const CJK_FONT_PATHS: &[&str] = &[
+    // Linux
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/wqy/wqy-microhei.ttc",
+    "/usr/share/fonts/wenquanyi/wqy-microhei/wqy-microhei.ttc",
+    "/usr/share/fonts/truetype/droid/DroidSansFallbackFull.ttf",
+    // macOS
+    "/System/Library/Fonts/PingFang.ttc",
+    "/System/Library/Fonts/STHeiti Light.ttc",
+    "/Library/Fonts/Arial Unicode.ttf",
+    // Windows
+    "C:\\Windows\\Fonts\\msyh.ttc",
+    "C:\\Windows\\Fonts\\simhei.ttf",
+    "C:\\Windows\\Fonts\\simsun.ttc",
+];
+
+/// Add a system CJK font as fallback of the egui fonts
+///
+/// # Returns
+/// - `false` if no CJK font was found, Chinese text then shows as boxes
+pub fn install_cjk_font(ctx: &egui::Context) -> bool {
+    let Some((path, data)) = CJK_FONT_PATHS
+        .iter()
+        .find_map(|path| std::fs::read(path).ok().map(|data| (*path, data)))
+    else {
+        eprintln!("No CJK font found, Chinese text cannot be displayed");
+        return false;
+    };
+
+    let mut fonts = egui::FontDefinitions::default();
+    fonts
+        .font_data
+        .insert("cjk".to_string(), egui::FontData::from_owned(data));
+    for family in [egui::FontFamily::Proportional, egui::FontFamily::Monospace] {
+        fonts
+            .families
+            .entry(family)
+            .or_default()
+            .push("cjk".to_string());
+    }
+    ctx.set_fonts(fonts);
+    println!("Loaded CJK font {}", path);
+    true
+}
+
+/// Chinese translations of the English texts
+const CHINESE: &[(&str, &str)] = &[
+    // File menu
+    ("File", "文件"),
+    ("Import", "导入"),
+    ("model", "模型"),
+    ("netCDF Grids on Sphere", "球面 netCDF 网格"),
+    ("Merge Duplicate Points", "合并重复点"),
+    (
+        "Weld coincident points of imported models",
+        "合并导入模型中重合的点",
+    ),
+    ("Weld tolerance:", "合并容差："),
+    ("Stream Large VTK Files", "流式读取大型 VTK 文件"),
+    (
+        "Legacy VTK files of {} MB or more load in the background",
+        "{} MB 及以上的旧版 VTK 文件在后台加载",
+    ),
+    ("Memory-Map Binary VTK Files", "内存映射二进制 VTK 文件"),
+    (
+        "Decode streamed binary files straight from the mapped file",
+        "直接从映射文件解码流式二进制文件",
+    ),
+    ("Import Time Series", "导入时间序列"),
+    (
+        "Import Time Series (.pvd, Exodus, netCDF, XDMF)",
+        "导入时间序列 (.pvd, Exodus, netCDF, XDMF)",
+    ),
+    ("Time series", "时间序列"),
+    ("Recent", "最近文件"),
+    ("No recent files", "没有最近文件"),
+    ("{} (time series)", "{}（时间序列）"),
+    ("Clear Recent", "清除最近文件"),
+    ("Open Project...", "打开项目..."),
+    ("Save Project...", "保存项目..."),
+    ("Export Scene (.glb)", "导出场景 (.glb)"),
+    ("Capture Screenshot", "截图"),
+    ("Preferences...", "偏好设置..."),
+    ("Quit", "退出"),
+    // View menu
+    ("View", "视图"),
+    ("Wireframe", "线框"),
+    ("hide color bar", "隐藏色标"),
+    ("show color bar", "显示色标"),
+    ("Widgets", "交互部件"),
+    ("Plane Widget", "平面部件"),
+    ("Box Widget", "长方体部件"),
+    ("Sphere Widget", "球体部件"),
+    ("Line Widget", "线段部件"),
+    ("Scene Tree", "场景树"),
+    ("Material Editor", "材质编辑器"),
+    ("Clipping Planes", "裁剪平面"),
+    ("glTF Scene Panel", "glTF 场景面板"),
+    ("Bounding Box", "包围盒"),
+    ("Cube Axes", "立方体坐标轴"),
+    ("Show Cube Axes", "显示立方体坐标轴"),
+    ("Grid Lines", "网格线"),
+    ("Axis Titles", "坐标轴标题"),
+    ("Ticks", "刻度"),
+    ("Point Splatting", "点溅射"),
+    ("Point Downsampling", "点降采样"),
+    ("Heightmap", "高度图"),
+    ("Periodic Replication", "周期复制"),
+    ("Volume Slices", "体切片"),
+    ("ID Labels", "ID 标签"),
+    ("Hover Values", "悬停数值"),
+    ("Orientation Axes", "方向坐标轴"),
+    ("Viewport Toolbar", "视口工具栏"),
+    ("Helpers on Top", "辅助图形置顶"),
+    ("Render Settings", "渲染设置"),
+    ("Performance HUD ({})", "性能面板（{}）"),
+    ("Clear User Meshes ({})", "清除用户网格（{}）"),
+    ("Debug Info:", "调试信息："),
+    ("Time series loaded: {} frames", "已加载时间序列：{} 帧"),
+    ("Mesh entity: {}", "网格实体：{}"),
+    ("No mesh entity", "无网格实体"),
+    (
+        "Single file loaded (not a time series)",
+        "已加载单个文件（非时间序列）",
+    ),
+    ("No time series loaded", "未加载时间序列"),
+    // Mesh menu
+    ("Mesh", "网格"),
+    ("Operations:", "操作："),
+    ("Subdivide", "细分"),
+    ("Generate LOD", "生成 LOD"),
+    ("Isosurface", "等值面"),
+    ("Slice", "切片"),
+    ("Clip", "裁剪"),
+    ("Streamlines", "流线"),
+    ("Vector Glyphs", "矢量图标"),
+    ("Warp by Vector", "按矢量变形"),
+    ("Calculator", "计算器"),
+    ("Cell/Point Data", "单元/点数据"),
+    ("Connectivity", "连通性"),
+    ("Integrate Variables", "积分变量"),
+    ("Mesh Quality", "网格质量"),
+    ("Delaunay 2.5D", "Delaunay 2.5D 三角剖分"),
+    ("Resample to Grid", "重采样到网格"),
+    ("Extract Cells by Type", "按类型提取单元"),
+    ("Transform", "变换"),
+    ("Tubes", "管道"),
+    ("Extrude", "拉伸"),
+    ("Temporal Statistics", "时间统计"),
+    ("Pathlines", "迹线"),
+    ("Divergence and Curl", "散度与旋度"),
+    ("Export LOD to STL", "导出 LOD 为 STL"),
+    ("{} ({} triangles)", "{}（{} 个三角形）"),
+    ("Load a model first", "请先加载模型"),
+    ("Generate:", "生成："),
+    ("Create Wave Surface (CPU)", "创建波面 (CPU)"),
+    ("Create Wave Surface (GPU Shader)", "创建波面 (GPU 着色器)"),
+    // Tools menu
+    ("Tools", "工具"),
+    ("Mouse Mode:", "鼠标模式："),
+    ("Navigate", "导航"),
+    ("Ruler", "标尺"),
+    ("Edit Vertices", "编辑顶点"),
+    ("Select Cells", "选择单元"),
+    ("Probe", "探针"),
+    ("Annotate", "注释"),
+    // Time series panel
+    ("Time Series Animation Control", "时间序列动画控制"),
+    ("✓ Animation Ready", "✓ 动画就绪"),
+    ("● Loading...", "● 加载中..."),
+    ("⏸ Pause", "⏸ 暂停"),
+    ("▶ Play", "▶ 播放"),
+    ("⏹ Stop", "⏹ 停止"),
+    ("⏮ Prev Frame", "⏮ 上一帧"),
+    ("⏭ Next Frame", "⏭ 下一帧"),
+    ("🔄 Loop On", "🔄 循环：开"),
+    ("🔄 Loop Off", "🔄 循环：关"),
+    ("Time Step:", "时间步："),
+    ("Frame", "帧"),
+    ("Time:", "时间："),
+    ("Playback Speed:", "播放速度："),
+    ("Current File:", "当前文件："),
+    (
+        "Status: Loading time series data...",
+        "状态：正在加载时间序列数据...",
+    ),
+    ("Loaded: {}/{}", "已加载：{}/{}"),
+    // Import progress
+    ("Loading", "正在加载"),
+    ("Cancel", "取消"),
+    ("OK", "确定"),
+    ("{} triangles shown", "已显示 {} 个三角形"),
+    ("{} s elapsed", "已用时 {} 秒"),
+    ("Starting", "正在开始"),
+    ("Parsing file", "正在解析文件"),
+    ("Reading header", "正在读取文件头"),
+    ("Reading points", "正在读取点"),
+    ("Reading cells", "正在读取单元"),
+    ("Reading point data", "正在读取点数据"),
+    ("Reading cell data", "正在读取单元数据"),
+    ("Building attributes", "正在构建属性"),
+    ("Triangulating cells", "正在三角化单元"),
+    ("Building geometry", "正在构建几何"),
+    // Notifications
+    ("Error", "错误"),
+    ("Notice", "提示"),
+    ("load file failed: {}", "加载文件失败：{}"),
+    ("Not supported format", "不支持的格式"),
+    (
+        "not supported this file format, please select one of: {}",
+        "不支持此文件格式，请选择以下格式之一：{}",
+    ),
+    ("Subdivision Error", "细分错误"),
+    (
+        "Error: No user model found for subdivision",
+        "错误：未找到可细分的用户模型",
+    ),
+    (
+        "This should not happen - please report this bug",
+        "这不应发生，请报告此问题",
+    ),
+    ("Subdivision Success", "细分成功"),
+    ("Successfully completed one subdivision", "已完成一次细分"),
+    ("Subdivision failed: {}", "细分失败：{}"),
+    ("No Model", "无模型"),
+    (
+        "Please load a model first before subdivision",
+        "细分前请先加载模型",
+    ),
+    ("Wave Generated", "已生成波面"),
+    ("Successfully generated wave surface!", "波面生成成功！"),
+    ("GPU Wave Generated", "已生成 GPU 波面"),
+    (
+        "Successfully generated GPU shader wave surface!",
+        "GPU 着色器波面生成成功！",
+    ),
+    ("Features:", "特性："),
+    (
+        "  • Real-time GPU wave calculation",
+        "  • 实时 GPU 波动计算",
+    ),
+    ("  • Animated wave motion", "  • 波动动画"),
+    (
+        "  • Dynamic lighting with normals",
+        "  • 基于法线的动态光照",
+    ),
+    ("Parameters:", "参数："),
+    ("  • Amplitude: {}", "  • 振幅：{}"),
+    ("  • Wave vector: {}", "  • 波矢：{}"),
+    ("  • Frequency: {}", "  • 频率：{}"),
+    ("  • Resolution: {}", "  • 分辨率：{}"),
+    ("Clear Complete", "清除完成"),
+    ("Successfully cleared {} meshes", "已清除 {} 个网格"),
+    (
+        "No user meshes in scene to clear",
+        "场景中没有可清除的用户网格",
+    ),
+    ("LOD Generation Complete", "LOD 生成完成"),
+    (
+        "Successfully generated LOD for {} models",
+        "已为 {} 个模型生成 LOD",
+    ),
+    (
+        "LOD will automatically switch based on camera distance",
+        "LOD 将根据相机距离自动切换",
+    ),
+    (
+        "No models found that can generate LOD",
+        "未找到可生成 LOD 的模型",
+    ),
+    (
+        "Please import a model first, or LOD already exists",
+        "请先导入模型，或 LOD 已存在",
+    ),
+    ("Currently no model data", "当前没有模型数据"),
+    ("Please import a VTK file first", "请先导入 VTK 文件"),
+];
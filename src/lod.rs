@@ -4,13 +4,25 @@
 //! - LOD0: Original model (highest precision)
 //! - LOD1: Simplified model (50% triangles)
 //! - LOD2: Most simplified model (25% triangles)
+//!
+//! The simplified levels are generated in the background (LOD2 from LOD1), so
+//! the original model is shown and interactive right away.
+//!
+//! Large meshes are simplified by GPU vertex clustering when a render device is
+//! available, so LOD generation of multi-million-triangle meshes stays fast.
 
+use crate::camera::WorldModelCamera;
 use crate::mesh::{GeometryData, VtkError};
+use crate::render::GpuSimplifier;
 use crate::ui::UserModelMesh;
 use bevy::prelude::*;
+use bevy::tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task};
 use bevy::utils::HashMap;
 use std::collections::BTreeMap;
 
+/// Triangle count from which LOD levels are generated on the GPU
+const GPU_SIMPLIFICATION_MIN_TRIANGLES: usize = 200_000;
+
 /// LOD level definitions
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum LODLevel {
@@ -46,8 +58,7 @@ pub struct LODMeshData {
     pub geometry: GeometryData,
     /// Mesh handle for rendering
     pub mesh_handle: Handle<Mesh>,
-    /// Number of triangles in this LOD (for debugging)
-    #[allow(dead_code)]
+    /// Number of triangles in this LOD
     pub triangle_count: usize,
 }
 
@@ -78,22 +89,20 @@ pub struct LODManager {
 impl LODManager {
     /// Create a new LOD manager from original geometry
     ///
-    /// Generates simplified versions of the input geometry at different LOD levels
-    /// The simplification process uses Quadric Error Metrics (QEM) to maintain
-    /// visual quality while reducing triangle count.
+    /// Only LOD0 is available right away; the simplified levels are generated
+    /// in the background by a `LODGenerationTask` and added once it finishes.
     ///
     /// # Parameters
     /// - `original_geometry`: The source geometry to create LOD levels from
     /// - `meshes`: Mutable reference to Bevy's mesh asset storage
     ///
     /// # Returns
-    /// - `Ok(LODManager)`: Successfully created LOD manager with all levels
+    /// - `Ok(LODManager)`: LOD manager holding the original level
     /// - `Err(VtkError)`: Failed to process geometry or create meshes
     pub fn new(
         original_geometry: GeometryData,
         meshes: &mut ResMut<Assets<Mesh>>,
     ) -> Result<Self, VtkError> {
-        let mut lod_meshes = BTreeMap::new();
         let triangle_count = original_geometry.indices.len() / 3;
 
         println!("Creating LOD manager, original model has {} triangles", triangle_count);
@@ -101,67 +110,55 @@ impl LODManager {
         // Calculate model bounding box
         let (model_center, model_size) = calculate_bounding_box(&original_geometry.vertices);
 
-        // LOD0
-        let original_mesh = crate::mesh::create_mesh_from_geometry(&original_geometry);
-        let original_handle = meshes.add(original_mesh);
-        lod_meshes.insert(
-            LODLevel::LOD0,
-            LODMeshData {
-                geometry: original_geometry.clone(),
-                mesh_handle: original_handle,
-                triangle_count,
-            },
-        );
+        let mut lod_manager = LODManager {
+            lod_meshes: BTreeMap::new(),
+            current_lod: LODLevel::LOD0,
+            model_center,
+            model_size,
+            needs_update: false,
+        };
+        lod_manager.insert_level(LODLevel::LOD0, original_geometry, meshes);
         println!("LOD0 original model complete, {} triangles", triangle_count);
 
-        // LOD1
-        if let Ok(simplified_geometry) = simplify_mesh(&original_geometry, 0.5) {
-            let simplified_mesh = crate::mesh::create_mesh_from_geometry(&simplified_geometry);
-            let simplified_handle = meshes.add(simplified_mesh);
-            let simplified_triangle_count = simplified_geometry.indices.len() / 3;
-
-            lod_meshes.insert(
-                LODLevel::LOD1,
-                LODMeshData {
-                    geometry: simplified_geometry,
-                    mesh_handle: simplified_handle,
-                    triangle_count: simplified_triangle_count,
-                },
-            );
-            println!("LOD1 simplification complete, generated {} triangles", simplified_triangle_count);
-        }
-
-        // LOD2
-        if let Ok(most_simplified_geometry) = simplify_mesh(&original_geometry, 0.25) {
-            let most_simplified_mesh =
-                crate::mesh::create_mesh_from_geometry(&most_simplified_geometry);
-            let most_simplified_handle = meshes.add(most_simplified_mesh);
-            let most_simplified_triangle_count = most_simplified_geometry.indices.len() / 3;
-
-            lod_meshes.insert(
-                LODLevel::LOD2,
-                LODMeshData {
-                    geometry: most_simplified_geometry,
-                    mesh_handle: most_simplified_handle,
-                    triangle_count: most_simplified_triangle_count,
-                },
-            );
-            println!(
-                "LOD2 maximum simplification complete, generated {} triangles",
-                most_simplified_triangle_count
-            );
-        }
+        Ok(lod_manager)
+    }
 
-        // Initially use LOD0
-        let initial_lod = LODLevel::LOD0;
+    /// Rebuild all LOD levels from modified geometry
+    ///
+    /// Falls back to LOD0 until the simplified levels of the new geometry have
+    /// been generated, and flags the manager so that the rendered mesh is
+    /// refreshed on the next frame.
+    ///
+    /// # Parameters
+    /// - `geometry`: The modified source geometry
+    /// - `meshes`: Mutable reference to Bevy's mesh asset storage
+    pub fn rebuild(
+        &mut self,
+        geometry: GeometryData,
+        meshes: &mut ResMut<Assets<Mesh>>,
+    ) -> Result<(), VtkError> {
+        *self = Self::new(geometry, meshes)?;
+        self.needs_update = true;
+        Ok(())
+    }
 
-        Ok(LODManager {
-            lod_meshes,
-            current_lod: initial_lod,
-            model_center,
-            model_size,
-            needs_update: false,
-        })
+    /// Add or replace the mesh of a level
+    fn insert_level(
+        &mut self,
+        level: LODLevel,
+        geometry: GeometryData,
+        meshes: &mut ResMut<Assets<Mesh>>,
+    ) {
+        let mesh_handle = meshes.add(crate::mesh::create_mesh_from_geometry(&geometry));
+        let triangle_count = geometry.indices.len() / 3;
+        self.lod_meshes.insert(
+            level,
+            LODMeshData {
+                geometry,
+                mesh_handle,
+                triangle_count,
+            },
+        );
     }
 
     /// Select appropriate LOD level based on camera distance
@@ -188,8 +185,12 @@ impl LODManager {
             }
         }
 
-        // Default to lowest precision
-        LODLevel::LOD2
+        // Default to the lowest precision available
+        self.lod_meshes
+            .keys()
+            .next_back()
+            .copied()
+            .unwrap_or(LODLevel::LOD0)
     }
 
     /// Update current LOD level based on camera distance
@@ -243,6 +244,60 @@ impl LODManager {
     }
 }
 
+/// Background generation of the simplified LOD levels
+///
+/// Runs on the async compute task pool. LOD2 is simplified from LOD1 instead of
+/// the original mesh, so the second pass only processes half the triangles.
+/// Inserting a new task on an entity drops (and cancels) the previous one.
+#[derive(Component)]
+pub struct LODGenerationTask(Task<Vec<(LODLevel, GeometryData)>>);
+
+impl LODGenerationTask {
+    /// Start generating LOD1 and LOD2 for the geometry
+    ///
+    /// # Parameters
+    /// - `geometry`: The original (LOD0) geometry
+    /// - `gpu_simplifier`: GPU clustering used for large meshes, if available
+    pub fn spawn(geometry: &GeometryData, gpu_simplifier: Option<&GpuSimplifier>) -> Self {
+        let geometry = geometry.clone();
+        let gpu_simplifier = gpu_simplifier.cloned();
+
+        let task = AsyncComputeTaskPool::get().spawn(async move {
+            let start = std::time::Instant::now();
+            let mut levels = Vec::new();
+
+            let lod1 = match simplify_lod_level(&geometry, 0.5, gpu_simplifier.as_ref()) {
+                Ok(lod1) => lod1,
+                Err(e) => {
+                    println!("LOD1 simplification failed: {:?}", e);
+                    return levels;
+                }
+            };
+            println!(
+                "LOD1 simplification complete, generated {} triangles",
+                lod1.indices.len() / 3
+            );
+
+            match simplify_lod_level(&lod1, 0.5, gpu_simplifier.as_ref()) {
+                Ok(lod2) => {
+                    println!(
+                        "LOD2 maximum simplification complete, generated {} triangles",
+                        lod2.indices.len() / 3
+                    );
+                    levels.push((LODLevel::LOD2, lod2));
+                }
+                Err(e) => println!("LOD2 simplification failed: {:?}", e),
+            }
+            levels.push((LODLevel::LOD1, lod1));
+
+            println!("LOD levels generated in {:.2?}", start.elapsed());
+            levels
+        });
+
+        Self(task)
+    }
+}
+
 /// LOD system plugin
 ///
 /// Adds the LOD management systems to the Bevy app.
@@ -253,6 +308,7 @@ impl Plugin for LODPlugin {
         app.add_systems(
             Update,
             (
+                apply_generated_lod_levels,
                 update_lod_based_on_camera_distance,
                 update_lod_color_mapping,
             )
@@ -261,9 +317,40 @@ impl Plugin for LODPlugin {
     }
 }
 
+/// Add the levels of finished generation tasks to their LOD managers
+fn apply_generated_lod_levels(
+    mut commands: Commands,
+    mut tasks: Query<(Entity, &mut LODGenerationTask, &mut LODManager)>,
+    color_bar_config: Res<crate::ui::ColorBarConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    for (entity, mut task, mut lod_manager) in tasks.iter_mut() {
+        let Some(levels) = block_on(future::poll_once(&mut task.0)) else {
+            continue;
+        };
+        commands.entity(entity).remove::<LODGenerationTask>();
+
+        for (level, geometry) in levels {
+            lod_manager.insert_level(level, geometry, &mut meshes);
+            if let Some(lod_data) = lod_manager.lod_meshes.get(&level) {
+                if let Some(mesh) = meshes.get_mut(&lod_data.mesh_handle) {
+                    if let Err(e) = crate::ui::color_bar::apply_custom_color_mapping(
+                        &lod_data.geometry,
+                        mesh,
+                        &color_bar_config,
+                    ) {
+                        println!("Unable to apply color mapping for {:?} level: {:?}", level, e);
+                    }
+                }
+            }
+        }
+        lod_manager.needs_update = true;
+    }
+}
+
 /// Update LOD levels based on camera distance
 fn update_lod_based_on_camera_distance(
-    camera_query: Query<&Transform, (With<Camera3d>, Without<LODManager>)>,
+    camera_query: Query<&Transform, (With<WorldModelCamera>, Without<LODManager>)>,
     mut lod_entities: Query<(&mut LODManager, &mut Mesh3d), With<UserModelMesh>>,
     color_bar_config: Res<crate::ui::ColorBarConfig>,
     mut meshes: ResMut<Assets<Mesh>>,
@@ -279,8 +366,8 @@ fn update_lod_based_on_camera_distance(
             .distance(lod_manager.model_center);
 
         // Update LOD level
-        if lod_manager.update_lod(distance) {
-            // If LOD level changed, update the mesh
+        if lod_manager.update_lod(distance) || lod_manager.needs_update {
+            // If LOD level changed or levels were rebuilt, update the mesh
             if let Some(new_mesh_handle) = lod_manager.current_mesh_handle() {
                 let mesh_handle_clone = new_mesh_handle.clone();
                 *mesh3d = Mesh3d(mesh_handle_clone.clone());
@@ -309,9 +396,23 @@ fn update_lod_based_on_camera_distance(
 // ============================================================================
 
 /// Calculate bounding box of vertex array
-fn calculate_bounding_box(vertices: &Vec<[f32; 3]>) -> (Vec3, f32) {
-    if vertices.is_empty() {
+fn calculate_bounding_box(vertices: &[[f32; 3]]) -> (Vec3, f32) {
+    let Some((min, max)) = bounding_box_corners(vertices) else {
         return (Vec3::ZERO, 1.0);
+    };
+
+    let center = (min + max) * 0.5;
+    let size = (max - min).length();
+
+    (center, size)
+}
+
+/// Calculate minimum and maximum corner of the bounding box of vertex array
+///
+/// Returns `None` for an empty array.
+pub fn bounding_box_corners(vertices: &[[f32; 3]]) -> Option<(Vec3, Vec3)> {
+    if vertices.is_empty() {
+        return None;
     }
 
     let mut min = Vec3::new(f32::MAX, f32::MAX, f32::MAX);
@@ -323,25 +424,179 @@ fn calculate_bounding_box(vertices: &Vec<[f32; 3]>) -> (Vec3, f32) {
         max = max.max(v);
     }
 
-    let center = (min + max) * 0.5;
-    let size = (max - min).length();
+    Some((min, max))
+}
 
-    (center, size)
+/// Simplify geometry for an LOD level
+///
+/// Meshes with at least `GPU_SIMPLIFICATION_MIN_TRIANGLES` triangles use GPU
+/// vertex clustering; smaller meshes, or a failed GPU pass, use `simplify_mesh`.
+fn simplify_lod_level(
+    geometry: &GeometryData,
+    ratio: f32,
+    gpu_simplifier: Option<&GpuSimplifier>,
+) -> Result<GeometryData, VtkError> {
+    if let Some(gpu_simplifier) = gpu_simplifier {
+        if geometry.indices.len() / 3 >= GPU_SIMPLIFICATION_MIN_TRIANGLES {
+            match simplify_mesh_gpu_clustering(geometry, ratio, gpu_simplifier) {
+                Ok(simplified) => return Ok(simplified),
+                Err(e) => println!("GPU simplification failed, using CPU: {:?}", e),
+            }
+        }
+    }
+    simplify_mesh(geometry, SimplificationTarget::Ratio(ratio))
 }
 
-/// Simplify mesh geometry
-fn simplify_mesh(geometry: &GeometryData, ratio: f32) -> Result<GeometryData, VtkError> {
-    let ratio = ratio.clamp(0.1, 1.0);
-    let original_triangle_count = geometry.indices.len() / 3;
-    let target_triangle_count = ((original_triangle_count as f32) * ratio) as usize;
+/// GPU vertex clustering simplification
+///
+/// The GPU assigns every vertex a grid cell at a resolution that keeps `ratio`
+/// of the vertices (and thus about `ratio` of the triangles); vertices sharing
+/// a cell are merged into their average.
+///
+/// # Parameters
+/// - `geometry`: Source to simplify
+/// - `ratio`: Target triangle ratio
+/// - `gpu_simplifier`: Compute pipelines of the clustering pass
+///
+/// # Returns
+/// - `Ok(GeometryData)`: Simplified geometry with averaged point attributes
+/// - `Err(VtkError)`: The GPU pass failed
+fn simplify_mesh_gpu_clustering(
+    geometry: &GeometryData,
+    ratio: f32,
+    gpu_simplifier: &GpuSimplifier,
+) -> Result<GeometryData, VtkError> {
+    let start = std::time::Instant::now();
+    let target_triangle_count = ((geometry.indices.len() / 3) as f32 * ratio) as usize;
+    let target_vertex_count = (geometry.vertices.len() as f32 * ratio) as usize;
+    let keys = gpu_simplifier.cluster_keys(&geometry.vertices, target_vertex_count)?;
+
+    // Number the clusters in key order and average their positions
+    let mut order: Vec<usize> = (0..keys.len()).collect();
+    order.sort_unstable_by_key(|&vertex_idx| keys[vertex_idx]);
+
+    let mut vertex_mapping: HashMap<usize, usize> = HashMap::with_capacity(keys.len());
+    let mut new_vertices: Vec<[f32; 3]> = Vec::new();
+    let mut cluster_sizes: Vec<f32> = Vec::new();
+    let mut previous_key = None;
+
+    for vertex_idx in order {
+        if previous_key != Some(keys[vertex_idx]) {
+            previous_key = Some(keys[vertex_idx]);
+            new_vertices.push([0.0; 3]);
+            cluster_sizes.push(0.0);
+        }
+        let cluster = new_vertices.len() - 1;
+        for (sum, value) in new_vertices[cluster]
+            .iter_mut()
+            .zip(geometry.vertices[vertex_idx])
+        {
+            *sum += value;
+        }
+        cluster_sizes[cluster] += 1.0;
+        vertex_mapping.insert(vertex_idx, cluster);
+    }
+    for (vertex, size) in new_vertices.iter_mut().zip(&cluster_sizes) {
+        for component in vertex.iter_mut() {
+            *component /= size;
+        }
+    }
+
+    // Rebuild triangles, remove duplicate and degenerate triangles
+    let mut new_indices = Vec::with_capacity(target_triangle_count * 3);
+    let mut triangle_set = std::collections::HashSet::new();
+    for chunk in geometry.indices.chunks_exact(3) {
+        let v0 = vertex_mapping[&(chunk[0] as usize)];
+        let v1 = vertex_mapping[&(chunk[1] as usize)];
+        let v2 = vertex_mapping[&(chunk[2] as usize)];
+        if v0 == v1 || v1 == v2 || v2 == v0 {
+            continue;
+        }
+
+        let mut triangle = [v0, v1, v2];
+        triangle.sort();
+        if triangle_set.insert(triangle) {
+            new_indices.extend_from_slice(&[v0 as u32, v1 as u32, v2 as u32]);
+        }
+    }
+
+    let new_attributes = if let Some(ref attrs) = geometry.attributes {
+        simplify_attributes_clustered(attrs, &vertex_mapping, new_vertices.len())?
+    } else {
+        HashMap::new()
+    };
 
     println!(
-        "Simplifying mesh: from {} triangles to {} triangles",
-        original_triangle_count, target_triangle_count
+        "GPU clustering simplification: {} -> {} triangles in {:.2?}",
+        geometry.indices.len() / 3,
+        new_indices.len() / 3,
+        start.elapsed()
     );
 
-    // Use Quadric Error Metrics algorithm for simplification
-    simplify_mesh_qem(geometry, ratio)
+    Ok(GeometryData::new(new_vertices, new_indices, new_attributes))
+}
+
+/// Stopping criterion of a simplification
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SimplificationTarget {
+    /// Keep this ratio of triangles, clamped to 0.1..=1.0
+    Ratio(f32),
+    /// Keep at most this many triangles
+    TriangleCount(usize),
+    /// Collapse edges while their QEM cost stays at or below this value
+    ///
+    /// The cost is the sum of squared distances from the collapsed vertex to
+    /// the face planes accumulated around the edge, in squared model units.
+    MaxError(f32),
+}
+
+/// Simplify mesh geometry
+///
+/// # Parameters
+/// - `geometry`: Geometry to simplify
+/// - `target`: Triangle ratio, triangle count or maximum QEM error to stop at
+///
+/// # Returns
+/// - `Ok(GeometryData)`: Simplified geometry with preserved attributes
+/// - `Err(VtkError)`: Simplification failed
+pub fn simplify_mesh(
+    geometry: &GeometryData,
+    target: SimplificationTarget,
+) -> Result<GeometryData, VtkError> {
+    let original_triangle_count = geometry.indices.len() / 3;
+
+    match target {
+        SimplificationTarget::Ratio(ratio) => {
+            let target_triangle_count =
+                ((original_triangle_count as f32) * ratio.clamp(0.1, 1.0)) as usize;
+            println!(
+                "Simplifying mesh: from {} triangles to {} triangles",
+                original_triangle_count, target_triangle_count
+            );
+            simplify_mesh_qem(geometry, target_triangle_count, None)
+        }
+        SimplificationTarget::TriangleCount(count) => {
+            let target_triangle_count = count.min(original_triangle_count);
+            println!(
+                "Simplifying mesh: from {} triangles to {} triangles",
+                original_triangle_count, target_triangle_count
+            );
+            simplify_mesh_qem(geometry, target_triangle_count, None)
+        }
+        SimplificationTarget::MaxError(max_error) => {
+            if max_error.is_nan() || max_error < 0.0 {
+                return Err(VtkError::GenericError(format!(
+                    "Invalid maximum simplification error: {}",
+                    max_error
+                )));
+            }
+            println!(
+                "Simplifying mesh: {} triangles, maximum error {}",
+                original_triangle_count, max_error
+            );
+            simplify_mesh_qem(geometry, 0, Some(max_error))
+        }
+    }
 }
 
 /// Quadric Error Metrics (QEM) based mesh simplification algorithm
@@ -355,19 +610,23 @@ fn simplify_mesh(geometry: &GeometryData, ratio: f32) -> Result<GeometryData, Vt
 ///
 /// # Parameters
 /// - `geometry`: Source to simplify
-/// - `ratio`: Target triangle ratio
+/// - `target_triangle_count`: Triangle count to stop at
+/// - `max_error`: Stop before collapsing an edge costing more than this
 ///
 /// # Returns
 /// - `Ok(GeometryData)`: Successfully simplified geometry with preserved attributes
 /// - `Err(VtkError)`: Simplification failed or ratio too low
-fn simplify_mesh_qem(geometry: &GeometryData, ratio: f32) -> Result<GeometryData, VtkError> {
-    if ratio < 0.2 {
+fn simplify_mesh_qem(
+    geometry: &GeometryData,
+    target_triangle_count: usize,
+    max_error: Option<f32>,
+) -> Result<GeometryData, VtkError> {
+    let ratio = target_triangle_count as f32 / (geometry.indices.len() / 3).max(1) as f32;
+    if max_error.is_none() && ratio < 0.2 {
         println!("QEM simplification ratio too low ({}), using vertex clustering algorithm", ratio);
         return simplify_mesh_vertex_clustering(geometry, ratio);
     }
 
-    let target_triangle_count = ((geometry.indices.len() / 3) as f32 * ratio) as usize;
-
     // Build half-edge data structure
     let mut mesh = QEMMesh::from_geometry(geometry);
 
@@ -381,11 +640,15 @@ fn simplify_mesh_qem(geometry: &GeometryData, ratio: f32) -> Result<GeometryData
     let current_triangle_count = mesh.triangle_count();
     let mut max_collapses = current_triangle_count.saturating_sub(target_triangle_count);
 
-    // Limit maximum collapses to prevent over-simplification
-    max_collapses = max_collapses.min(current_triangle_count / 2);
+    // Limit maximum collapses to prevent over-simplification; an error bound
+    // limits the collapses by itself
+    if max_error.is_none() {
+        max_collapses = max_collapses.min(current_triangle_count / 2);
+    }
 
     println!("QEM simplification: planning to collapse at most {} edges", max_collapses);
 
+    let max_error = max_error.unwrap_or(f32::INFINITY);
     let mut collapsed_count = 0;
     let mut consecutive_failures = 0; // Count consecutive failures
 
@@ -400,7 +663,15 @@ fn simplify_mesh_qem(geometry: &GeometryData, ratio: f32) -> Result<GeometryData
             break;
         }
 
-        if !mesh.collapse_cheapest_edge() {
+        // No available edges, or all of them exceed the error bound
+        let Some((edge_idx, cost)) = mesh.cheapest_edge() else {
+            break;
+        };
+        if cost > max_error {
+            break;
+        }
+
+        if !mesh.collapse_edge(edge_idx) {
             consecutive_failures += 1;
             if consecutive_failures > 5 {
                 break; // Stop after multiple consecutive failures
@@ -879,25 +1150,19 @@ impl QEMMesh {
         }
     }
 
-    fn collapse_cheapest_edge(&mut self) -> bool {
-        // Find the edge with lowest cost
-        let mut best_edge_idx = None;
+    /// Edge with the lowest collapse cost and its cost
+    fn cheapest_edge(&self) -> Option<(usize, f32)> {
+        let mut best = None;
         let mut best_cost = f32::INFINITY;
 
         for (edge_idx, edge) in self.edges.iter().enumerate() {
             if !edge.is_deleted && edge.cost < best_cost {
                 best_cost = edge.cost;
-                best_edge_idx = Some(edge_idx);
+                best = Some((edge_idx, edge.cost));
             }
         }
 
-        // No available edges
-        let Some(edge_idx) = best_edge_idx else {
-            return false; 
-        };
-
-        // Execute edge collapse
-        self.collapse_edge(edge_idx)
+        best
     }
 
     fn collapse_edge(&mut self, edge_idx: usize) -> bool {
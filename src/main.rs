@@ -1,24 +1,129 @@
 mod animation;
+mod annotations;
+mod calculator;
 mod camera;
+mod cell_types;
+mod cli;
+mod clip_filter;
+mod clipping;
+mod connectivity;
+mod cube_axes;
+mod data_conversion;
+mod delaunay;
+mod derivatives;
+mod downsampling;
 mod environment;
+mod extrude;
+mod gltf_scene;
+mod glyphs;
+mod heightmap;
+mod hover;
+mod integrate;
+mod isosurface;
+mod labels;
+mod localization;
 mod lod;
+mod material_editor;
+mod measurement;
 mod mesh;
 mod model_transform;
+mod offscreen;
+mod orientation_axes;
+mod outline;
+mod pathlines;
+mod performance;
+mod periodic;
+mod picking;
+mod preferences;
+mod probe;
+mod project;
+mod quality;
+mod recent;
 mod render;
+mod resample;
+mod scene_export;
+mod scene_tree;
+mod screenshot;
+mod selection;
+mod session;
+mod slice_plane;
+mod splatting;
+mod streaming;
+mod streamlines;
+mod temporal_stats;
+mod transform_filter;
+mod tubes;
 mod ui;
+mod vertex_edit;
+mod viewports;
+mod volume_slices;
+mod warp;
+mod widgets;
 
 use animation::TimeSeriesAnimationPlugin;
+use annotations::AnnotationPlugin;
 use bevy::pbr::wireframe::WireframePlugin;
 use bevy::{pbr::MaterialPlugin, prelude::*};
 use bevy_egui::*;
-use bevy_obj::ObjPlugin;
+use calculator::CalculatorPlugin;
 use camera::CameraPlugin;
+use cell_types::CellTypePlugin;
+use clip_filter::ClipFilterPlugin;
+use clipping::ClippingPlugin;
+use connectivity::ConnectivityPlugin;
+use cube_axes::CubeAxesPlugin;
+use data_conversion::DataConversionPlugin;
+use delaunay::DelaunayPlugin;
+use derivatives::DerivativePlugin;
+use downsampling::DownsamplingPlugin;
 use environment::EnvironmentPlugin;
+use extrude::ExtrudePlugin;
+use heightmap::HeightmapPlugin;
+use hover::HoverPlugin;
+use integrate::IntegratePlugin;
+use isosurface::IsosurfacePlugin;
 use lod::LODPlugin;
+use material_editor::MaterialEditorPlugin;
+use measurement::MeasurementPlugin;
 use model_transform::ModelTransformPlugin;
-use render::{animate_wave_shader, create_wireframe_config, toggle_wireframe, WaveMaterial};
+use orientation_axes::OrientationAxesPlugin;
+use outline::OutlinePlugin;
+use pathlines::PathlinePlugin;
+use performance::PerformancePlugin;
+use periodic::PeriodicPlugin;
+use picking::PickingPlugin;
+use preferences::PreferencesPlugin;
+use probe::ProbePlugin;
+use project::ProjectPlugin;
+use quality::MeshQualityPlugin;
+use recent::RecentFilesPlugin;
+use render::{
+    animate_wave_shader, create_wireframe_config, toggle_wireframe, GpuSimplifyPlugin,
+    OverlayPlugin, RenderSettingsPlugin, WaveMaterial,
+};
+use resample::ResamplePlugin;
 // use std::sync::atomic::{AtomicBool, Ordering};
+use gltf_scene::GltfScenePlugin;
+use glyphs::GlyphPlugin;
+use labels::LabelPlugin;
+use scene_export::SceneExportPlugin;
+use scene_tree::SceneTreePlugin;
+use screenshot::ScreenshotPlugin;
+use selection::SelectionPlugin;
+use session::SessionPlugin;
+use slice_plane::SlicePlanePlugin;
+use splatting::SplatPlugin;
+use streaming::StreamingImportPlugin;
+use streamlines::StreamlinePlugin;
+use temporal_stats::TemporalStatsPlugin;
+use transform_filter::TransformFilterPlugin;
+use tubes::TubePlugin;
 use ui::UIPlugin;
+use vertex_edit::VertexEditPlugin;
+use viewports::ViewportPlugin;
+use volume_slices::VolumeSlicePlugin;
+use warp::WarpPlugin;
+use widgets::WidgetPlugin;
 
 #[derive(Component)]
 pub struct Mesh3d(pub Handle<Mesh>);
@@ -29,16 +134,74 @@ pub struct MeshMaterial3d<M: Material>(pub Handle<M>);
 // static DEBUG_PRINTED: AtomicBool = AtomicBool::new(false);
 
 fn main() {
+    // Command line subcommands (e.g. offscreen rendering) run without the viewer
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(exit_code) = cli::run_command(&args) {
+        std::process::exit(exit_code);
+    }
+
     App::new()
         .add_plugins(DefaultPlugins)
         .add_plugins(EguiPlugin)
-        .add_plugins(ObjPlugin)
         .add_plugins(UIPlugin)
         .add_plugins(CameraPlugin)
         .add_plugins(EnvironmentPlugin)
         .add_plugins(ModelTransformPlugin)
         .add_plugins(LODPlugin)
+        .add_plugins(GpuSimplifyPlugin)
         .add_plugins(TimeSeriesAnimationPlugin)
+        .add_plugins(PickingPlugin)
+        .add_plugins(WidgetPlugin)
+        .add_plugins(ClippingPlugin)
+        .add_plugins(MeasurementPlugin)
+        .add_plugins(VertexEditPlugin)
+        .add_plugins(SelectionPlugin)
+        .add_plugins(ProbePlugin)
+        .add_plugins(GltfScenePlugin)
+        .add_plugins(SceneExportPlugin)
+        .add_plugins(ScreenshotPlugin)
+        .add_plugins(ProjectPlugin)
+        .add_plugins(RecentFilesPlugin)
+        .add_plugins(StreamingImportPlugin)
+        .add_plugins(SplatPlugin)
+        .add_plugins(DownsamplingPlugin)
+        .add_plugins(HeightmapPlugin)
+        .add_plugins(PeriodicPlugin)
+        .add_plugins(VolumeSlicePlugin)
+        .add_plugins(IsosurfacePlugin)
+        .add_plugins(SlicePlanePlugin)
+        .add_plugins(ClipFilterPlugin)
+        .add_plugins(StreamlinePlugin)
+        .add_plugins(GlyphPlugin)
+        .add_plugins(WarpPlugin)
+        .add_plugins(CalculatorPlugin)
+        .add_plugins(DataConversionPlugin)
+        .add_plugins(ConnectivityPlugin)
+        .add_plugins(IntegratePlugin)
+        .add_plugins(MeshQualityPlugin)
+        .add_plugins(DelaunayPlugin)
+        .add_plugins(ResamplePlugin)
+        .add_plugins(CellTypePlugin)
+        .add_plugins(TransformFilterPlugin)
+        .add_plugins(TubePlugin)
+        .add_plugins(ExtrudePlugin)
+        .add_plugins(OutlinePlugin)
+        .add_plugins(TemporalStatsPlugin)
+        .add_plugins(PathlinePlugin)
+        .add_plugins(DerivativePlugin)
+        .add_plugins(HoverPlugin)
+        .add_plugins(AnnotationPlugin)
+        .add_plugins(OrientationAxesPlugin)
+        .add_plugins(CubeAxesPlugin)
+        .add_plugins(ViewportPlugin)
+        .add_plugins(SceneTreePlugin)
+        .add_plugins(MaterialEditorPlugin)
+        .add_plugins(PreferencesPlugin)
+        .add_plugins(SessionPlugin)
+        .add_plugins(LabelPlugin)
+        .add_plugins(OverlayPlugin)
+        .add_plugins(RenderSettingsPlugin)
+        .add_plugins(PerformancePlugin)
         .add_plugins(WireframePlugin)
         .insert_resource(create_wireframe_config())
         .add_systems(Update, toggle_wireframe)
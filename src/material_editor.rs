@@ -0,0 +1,239 @@
+//! # Material Editor Module
+//!
+//! This module edits the surface material of entities live:
+//! - Base color, metallic, roughness, reflectance, opacity, double-sided and
+//!   unlit of the entity selected in the scene tree, or of the model when
+//!   nothing is selected
+//! - Entities sharing the material (viewport and periodic copies) follow, and
+//!   clipping keeps the edited values when it takes over the material
+//! - The properties can be stored as the material of newly loaded models
+//!
+//! Open the panel via View > Material Editor.
+
+use crate::periodic::ModelMaterials;
+use crate::render::ClipMaterial;
+use crate::scene_tree::SceneTree;
+use crate::ui::UserModelMesh;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Editable material properties
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MaterialProperties {
+    /// Linear RGB base color, multiplied with the vertex colors
+    pub base_color: [f32; 3],
+    pub metallic: f32,
+    pub roughness: f32,
+    pub reflectance: f32,
+    /// 1 is opaque, lower values blend with the background
+    pub opacity: f32,
+    /// Render back faces too
+    pub double_sided: bool,
+    /// Ignore lighting
+    pub unlit: bool,
+}
+
+impl Default for MaterialProperties {
+    fn default() -> Self {
+        Self {
+            base_color: [1.0, 1.0, 1.0],
+            metallic: 0.2,
+            roughness: 0.4,
+            reflectance: 0.5,
+            opacity: 1.0,
+            double_sided: true,
+            unlit: false,
+        }
+    }
+}
+
+impl MaterialProperties {
+    /// Properties of a standard material
+    pub fn from_material(material: &StandardMaterial) -> Self {
+        let color = material.base_color.to_linear();
+        Self {
+            base_color: [color.red, color.green, color.blue],
+            metallic: material.metallic,
+            roughness: material.perceptual_roughness,
+            reflectance: material.reflectance,
+            opacity: color.alpha,
+            double_sided: material.cull_mode.is_none(),
+            unlit: material.unlit,
+        }
+    }
+
+    /// Write the properties into a standard material
+    ///
+    /// Opacities below 1 switch the material to alpha blending; opaque
+    /// materials keep their alpha mode unless they were blended.
+    pub fn apply(&self, material: &mut StandardMaterial) {
+        let [red, green, blue] = self.base_color;
+        material.base_color = Color::linear_rgba(red, green, blue, self.opacity);
+        material.metallic = self.metallic;
+        material.perceptual_roughness = self.roughness;
+        material.reflectance = self.reflectance;
+        material.double_sided = self.double_sided;
+        material.cull_mode = if self.double_sided {
+            None
+        } else {
+            Some(bevy::render::render_resource::Face::Back)
+        };
+        material.unlit = self.unlit;
+        if self.opacity < 1.0 {
+            material.alpha_mode = AlphaMode::Blend;
+        } else if material.alpha_mode == AlphaMode::Blend {
+            material.alpha_mode = AlphaMode::Opaque;
+        }
+    }
+
+    /// Standard material with these properties
+    pub fn to_material(self) -> StandardMaterial {
+        let mut material = StandardMaterial::default();
+        self.apply(&mut material);
+        material
+    }
+}
+
+/// Material editor state
+#[derive(Resource, Default)]
+pub struct MaterialEditor {
+    /// Show the material editor panel
+    pub show_panel: bool,
+    /// Material of newly loaded models
+    pub model_material: MaterialProperties,
+}
+
+/// Material assets with the material of newly loaded models
+#[derive(SystemParam)]
+pub struct NewModelMaterial<'w> {
+    editor: Res<'w, MaterialEditor>,
+    materials: ResMut<'w, Assets<StandardMaterial>>,
+}
+
+impl NewModelMaterial<'_> {
+    /// Add a material for a new model
+    pub fn add(&mut self) -> Handle<StandardMaterial> {
+        self.materials.add(self.editor.model_material.to_material())
+    }
+}
+
+pub struct MaterialEditorPlugin;
+
+impl Plugin for MaterialEditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MaterialEditor>()
+            .add_systems(Update, material_editor_panel.after(EguiSet::InitContexts));
+    }
+}
+
+/// Material editor panel
+#[allow(clippy::too_many_arguments)]
+fn material_editor_panel(
+    mut contexts: EguiContexts,
+    mut editor: ResMut<MaterialEditor>,
+    scene_tree: Res<SceneTree>,
+    entities: Query<(Option<&Name>, ModelMaterials)>,
+    models: Query<Entity, With<UserModelMesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+    mut clip_materials: ResMut<Assets<ClipMaterial>>,
+    windows: Query<&Window>,
+) {
+    if !editor.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    // The selected entity, or the model
+    let target = scene_tree
+        .selected
+        .or_else(|| models.iter().next())
+        .and_then(|entity| entities.get(entity).ok());
+    let name = target
+        .and_then(|(name, _)| name.map(|name| name.to_string()))
+        .unwrap_or_else(|| "Model".to_string());
+    let current = target.and_then(|(_, (standard, clip))| {
+        if let Some(clip) = clip {
+            clip_materials
+                .get(&clip.0)
+                .map(|material| MaterialProperties::from_material(&material.base))
+        } else {
+            standard
+                .and_then(|standard| standard_materials.get(&standard.0))
+                .map(MaterialProperties::from_material)
+        }
+    });
+
+    let mut properties = current;
+    let mut model_material = editor.model_material;
+    let mut open = true;
+
+    egui::Window::new("Material")
+        .default_width(260.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            let Some(properties) = properties.as_mut() else {
+                ui.label("Select an entity with a standard material in the scene tree.");
+                return;
+            };
+            ui.label(format!("Editing: {}", name));
+            ui.separator();
+
+            egui::Grid::new("material_properties").show(ui, |ui| {
+                ui.label("Base color");
+                ui.color_edit_button_rgb(&mut properties.base_color);
+                ui.end_row();
+                ui.label("Metallic");
+                ui.add(egui::Slider::new(&mut properties.metallic, 0.0..=1.0));
+                ui.end_row();
+                ui.label("Roughness");
+                ui.add(egui::Slider::new(&mut properties.roughness, 0.0..=1.0));
+                ui.end_row();
+                ui.label("Reflectance");
+                ui.add(egui::Slider::new(&mut properties.reflectance, 0.0..=1.0));
+                ui.end_row();
+                ui.label("Opacity");
+                ui.add(egui::Slider::new(&mut properties.opacity, 0.0..=1.0));
+                ui.end_row();
+            });
+            ui.checkbox(&mut properties.double_sided, "Double-sided");
+            ui.checkbox(&mut properties.unlit, "Unlit");
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui
+                    .button("Use for New Models")
+                    .on_hover_text("Load the next models with this material")
+                    .clicked()
+                {
+                    model_material = *properties;
+                }
+                if ui.button("Reset").clicked() {
+                    *properties = MaterialProperties::default();
+                }
+            });
+        });
+
+    if properties != current {
+        if let (Some(properties), Some((_, (standard, clip)))) = (properties, target) {
+            if let Some(material) = clip.and_then(|clip| clip_materials.get_mut(&clip.0)) {
+                properties.apply(&mut material.base);
+                // Clipping needs the prepass of alpha mask mode
+                if material.base.alpha_mode == AlphaMode::Opaque {
+                    material.base.alpha_mode = AlphaMode::Mask(0.5);
+                }
+            } else if let Some(material) =
+                standard.and_then(|standard| standard_materials.get_mut(&standard.0))
+            {
+                properties.apply(material);
+            }
+        }
+    }
+    if model_material != editor.model_material {
+        println!("Material for new models: {:?}", model_material);
+        editor.model_material = model_material;
+    }
+
+    if !open {
+        editor.show_panel = false;
+    }
+}
@@ -0,0 +1,815 @@
+//! # Measurement Module
+//!
+//! This module provides interactive measurement tools on the loaded model:
+//! - Ruler: two endpoints placed on the surface, showing the Euclidean distance
+//!   and optionally the geodesic (shortest edge path) distance
+//! - Angle: three points, showing the angle at the second point between the
+//!   directions to the first and the third
+//! - Radius: three points, showing the radius and center of the circle through
+//!   them, e.g. to check holes and fillets
+//! - Kept measurements: finished rulers stay in the scene and in the Ruler
+//!   panel with their value until removed
+//! - Values are labeled in screen space next to every measurement
+//!
+//! ## Control Scheme (Tools > Ruler)
+//! - Left click on the surface: Place the next ruler point
+//! - Left drag on a point: Slide the point over the surface
+//! - Keep button: Store the ruler as a measurement and start a new one
+
+use crate::camera::WorldModelCamera;
+use crate::mesh::geodesic::shortest_edge_path;
+use crate::picking::{
+    cursor_ray_in_model_space, model_matrix, pick_surface, pointer_over_ui, ray_sphere_distance,
+    InteractionMode,
+};
+use crate::preferences::UiSettings;
+use crate::render::OverlayGizmos;
+use crate::ui::{CurrentModelData, ModelLoadedEvent, UserModelMesh};
+use crate::widgets::{widget_drag_input, WidgetState};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Endpoint marker radius relative to the model size
+const ENDPOINT_RADIUS_FACTOR: f32 = 0.01;
+
+const RULER_COLOR: Color = Color::srgb(1.0, 0.9, 0.2);
+const GEODESIC_COLOR: Color = Color::srgb(1.0, 0.3, 0.8);
+const MEASUREMENT_COLOR: Color = Color::srgb(1.0, 0.6, 0.1);
+
+/// Radius of the angle arc relative to the shorter leg
+const ANGLE_ARC_FACTOR: f32 = 0.25;
+
+/// Label text color
+const LABEL_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 230, 80);
+
+/// Ruler endpoint on the model surface
+#[derive(Clone, Copy, Debug)]
+pub struct RulerEndpoint {
+    /// Position in model space
+    pub position: Vec3,
+    /// Closest mesh vertex, used as geodesic path anchor
+    pub vertex: u32,
+}
+
+/// Quantity measured by the ruler
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MeasureTool {
+    /// Distance between two points
+    #[default]
+    Distance,
+    /// Angle at the second of three points
+    Angle,
+    /// Radius of the circle through three points
+    Radius,
+}
+
+impl MeasureTool {
+    /// Number of points placed for the measurement
+    pub fn point_count(&self) -> usize {
+        match self {
+            MeasureTool::Distance => 2,
+            MeasureTool::Angle | MeasureTool::Radius => 3,
+        }
+    }
+}
+
+/// Circle through three points
+#[derive(Clone, Copy, Debug)]
+pub struct Circle {
+    pub center: Vec3,
+    pub radius: f32,
+    /// Unit normal of the circle plane
+    pub normal: Vec3,
+}
+
+/// Angle at `vertex` between the directions to `a` and `b`
+///
+/// # Returns
+/// - Angle in degrees, `None` if a point coincides with the vertex
+pub fn angle_degrees(a: Vec3, vertex: Vec3, b: Vec3) -> Option<f32> {
+    let (u, v) = (a - vertex, b - vertex);
+    if u.length_squared() <= f32::MIN_POSITIVE || v.length_squared() <= f32::MIN_POSITIVE {
+        return None;
+    }
+    Some(u.angle_between(v).to_degrees())
+}
+
+/// Circle through three points (circumcircle of their triangle)
+///
+/// # Returns
+/// - Circle, `None` if the points are (nearly) collinear
+pub fn circle_through(a: Vec3, b: Vec3, c: Vec3) -> Option<Circle> {
+    let (ab, ac) = (b - a, c - a);
+    let normal = ab.cross(ac);
+    let normal_squared = normal.length_squared();
+    if normal_squared <= 1e-12 * ab.length_squared() * ac.length_squared()
+        || normal_squared <= f32::MIN_POSITIVE
+    {
+        return None;
+    }
+    let center = a
+        + (normal.cross(ab) * ac.length_squared() + ac.cross(normal) * ab.length_squared())
+            / (2.0 * normal_squared);
+    Some(Circle {
+        center,
+        radius: center.distance(a),
+        normal: normal.normalize(),
+    })
+}
+
+/// Measurement of the ruler, kept after the ruler moved on
+#[derive(Clone, Copy, Debug)]
+pub struct Measurement {
+    pub tool: MeasureTool,
+    /// Points in model space; distances use the first two
+    pub points: [Vec3; 3],
+}
+
+impl Measurement {
+    /// Distance, angle in degrees or radius
+    ///
+    /// # Returns
+    /// - Value, `None` for degenerate angles and circles
+    pub fn value(&self) -> Option<f32> {
+        let [a, b, c] = self.points;
+        match self.tool {
+            MeasureTool::Distance => Some(a.distance(b)),
+            MeasureTool::Angle => angle_degrees(a, b, c),
+            MeasureTool::Radius => circle_through(a, b, c).map(|circle| circle.radius),
+        }
+    }
+
+    /// Per-axis difference from the first to the second point
+    pub fn delta(&self) -> Vec3 {
+        self.points[1] - self.points[0]
+    }
+
+    /// Value with its unit, as shown in labels
+    pub fn text(&self) -> String {
+        match (self.tool, self.value()) {
+            (_, None) => "-".to_string(),
+            (MeasureTool::Distance, Some(distance)) => format!("{:.4}", distance),
+            (MeasureTool::Angle, Some(angle)) => format!("{:.2}°", angle),
+            (MeasureTool::Radius, Some(radius)) => format!("R {:.4}", radius),
+        }
+    }
+
+    /// Position of the label: the middle of a distance, the vertex of an angle
+    /// or the center of a circle
+    pub fn anchor(&self) -> Vec3 {
+        let [a, b, c] = self.points;
+        match self.tool {
+            MeasureTool::Distance => (a + b) * 0.5,
+            MeasureTool::Angle => b,
+            MeasureTool::Radius => {
+                circle_through(a, b, c).map_or((a + b + c) / 3.0, |circle| circle.center)
+            }
+        }
+    }
+}
+
+/// Cached geodesic path between the ruler endpoints
+#[derive(Clone, Debug)]
+pub struct GeodesicPath {
+    pub length: f32,
+    /// Path points in model space (including both endpoints)
+    pub points: Vec<Vec3>,
+}
+
+/// Ruler state
+#[derive(Resource)]
+pub struct RulerState {
+    /// Quantity measured by the ruler
+    pub tool: MeasureTool,
+    /// Placed points, only the first `tool.point_count()` are used
+    pub endpoints: [Option<RulerEndpoint>; 3],
+    /// Whether to compute and show the geodesic distance
+    pub show_geodesic: bool,
+    pub geodesic: Option<GeodesicPath>,
+    /// Endpoint marker radius
+    pub endpoint_radius: f32,
+    /// Kept measurements
+    pub measurements: Vec<Measurement>,
+    /// Label the distances in the viewport
+    pub show_labels: bool,
+    /// Endpoint placed by the next click
+    next_endpoint: usize,
+    /// Endpoint currently being dragged
+    dragging: Option<usize>,
+    /// Geodesic path needs to be recomputed
+    geodesic_dirty: bool,
+}
+
+impl Default for RulerState {
+    fn default() -> Self {
+        Self {
+            tool: MeasureTool::Distance,
+            endpoints: [None; 3],
+            show_geodesic: false,
+            geodesic: None,
+            endpoint_radius: 0.02,
+            measurements: Vec::new(),
+            show_labels: true,
+            next_endpoint: 0,
+            dragging: None,
+            geodesic_dirty: false,
+        }
+    }
+}
+
+impl RulerState {
+    /// Remove all points
+    pub fn clear(&mut self) {
+        self.endpoints = [None; 3];
+        self.geodesic = None;
+        self.next_endpoint = 0;
+        self.dragging = None;
+        self.geodesic_dirty = false;
+    }
+
+    /// Measurement of the ruler, once all its points are placed
+    pub fn current(&self) -> Option<Measurement> {
+        let count = self.tool.point_count();
+        let mut points = [Vec3::ZERO; 3];
+        for (point, endpoint) in points.iter_mut().zip(&self.endpoints[..count]) {
+            *point = endpoint.as_ref()?.position;
+        }
+        Some(Measurement {
+            tool: self.tool,
+            points,
+        })
+    }
+
+    /// Switch the measured quantity, removing the placed points
+    pub fn set_tool(&mut self, tool: MeasureTool) {
+        if tool != self.tool {
+            self.tool = tool;
+            self.clear();
+        }
+    }
+
+    /// Keep the ruler as a measurement and clear the endpoints
+    pub fn keep(&mut self) {
+        if let Some(measurement) = self.current() {
+            self.measurements.push(measurement);
+            self.clear();
+        }
+    }
+
+    /// Format endpoints and distances as CSV for the clipboard
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("endpoint,x,y,z,vertex\n");
+        let count = self.tool.point_count();
+        for (index, endpoint) in self.endpoints[..count].iter().enumerate() {
+            if let Some(endpoint) = endpoint {
+                csv.push_str(&format!(
+                    "{},{},{},{},{}\n",
+                    index,
+                    endpoint.position.x,
+                    endpoint.position.y,
+                    endpoint.position.z,
+                    endpoint.vertex
+                ));
+            }
+        }
+        if let Some(measurement) = self.current() {
+            if let Some(value) = measurement.value() {
+                csv.push_str(&format!("{},{}\n", tool_name(measurement.tool), value));
+            }
+        }
+        if let Some(geodesic) = self.geodesic.as_ref().filter(|_| self.show_geodesic) {
+            csv.push_str(&format!("geodesic,{}\n", geodesic.length));
+        }
+        if !self.measurements.is_empty() {
+            csv.push_str("measurement,type,value,dx,dy,dz\n");
+        }
+        for (index, measurement) in self.measurements.iter().enumerate() {
+            let value = measurement
+                .value()
+                .map(|v| v.to_string())
+                .unwrap_or_default();
+            let delta = match measurement.tool {
+                MeasureTool::Distance => {
+                    let delta = measurement.delta();
+                    format!("{},{},{}", delta.x, delta.y, delta.z)
+                }
+                _ => ",,".to_string(),
+            };
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                index + 1,
+                tool_name(measurement.tool),
+                value,
+                delta
+            ));
+        }
+        csv
+    }
+
+    fn set_endpoint(&mut self, index: usize, endpoint: RulerEndpoint) {
+        self.endpoints[index] = Some(endpoint);
+        self.geodesic_dirty = true;
+    }
+}
+
+/// Lowercase name of a tool, as used in CSV output
+fn tool_name(tool: MeasureTool) -> &'static str {
+    match tool {
+        MeasureTool::Distance => "distance",
+        MeasureTool::Angle => "angle",
+        MeasureTool::Radius => "radius",
+    }
+}
+
+pub struct MeasurementPlugin;
+
+impl Plugin for MeasurementPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RulerState>().add_systems(
+            Update,
+            (
+                reset_ruler_on_model_load,
+                ruler_input.after(widget_drag_input),
+                update_geodesic_path,
+                draw_ruler,
+                ruler_panel.after(EguiSet::InitContexts),
+                draw_measurement_labels.after(EguiSet::InitContexts),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Clear the ruler when a new model is loaded and adapt marker size
+fn reset_ruler_on_model_load(
+    mut model_loaded_events: EventReader<ModelLoadedEvent>,
+    mut ruler_state: ResMut<RulerState>,
+) {
+    for event in model_loaded_events.read() {
+        ruler_state.clear();
+        ruler_state.measurements.clear();
+        if let (Some(min), Some(max)) = (event.bounds_min, event.bounds_max) {
+            ruler_state.endpoint_radius = (max - min).length().max(1e-3) * ENDPOINT_RADIUS_FACTOR;
+        }
+    }
+}
+
+/// Place and drag ruler endpoints on the surface
+#[allow(clippy::too_many_arguments)]
+fn ruler_input(
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    interaction_mode: Res<InteractionMode>,
+    current_model: Res<CurrentModelData>,
+    widget_state: Res<WidgetState>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<WorldModelCamera>>,
+    model_query: Query<&GlobalTransform, With<UserModelMesh>>,
+    mut contexts: EguiContexts,
+    mut ruler_state: ResMut<RulerState>,
+) {
+    if *interaction_mode != InteractionMode::Ruler {
+        ruler_state.dragging = None;
+        return;
+    }
+
+    if mouse_button_input.just_released(MouseButton::Left) {
+        ruler_state.dragging = None;
+        return;
+    }
+
+    let Some(ref geometry) = current_model.geometry else {
+        return;
+    };
+
+    let model_to_world = model_matrix(&model_query);
+    let Some((ray_origin, ray_direction)) =
+        cursor_ray_in_model_space(&windows, &camera_query, model_to_world)
+    else {
+        return;
+    };
+
+    if mouse_button_input.just_pressed(MouseButton::Left) {
+        let alt_pressed =
+            keyboard_input.pressed(KeyCode::AltLeft) || keyboard_input.pressed(KeyCode::AltRight);
+        if alt_pressed || widget_state.is_dragging() || pointer_over_ui(&mut contexts) {
+            return;
+        }
+
+        // Grab an existing endpoint first
+        let radius = ruler_state.endpoint_radius * 1.5;
+        let grabbed = (0..ruler_state.tool.point_count())
+            .filter_map(|index| {
+                let endpoint = ruler_state.endpoints[index]?;
+                ray_sphere_distance(ray_origin, ray_direction, endpoint.position, radius)
+                    .map(|distance| (index, distance))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+
+        if let Some((index, _)) = grabbed {
+            ruler_state.dragging = Some(index);
+            return;
+        }
+
+        // Otherwise place the next endpoint on the surface
+        if let Some(hit) = pick_surface(geometry, ray_origin, ray_direction) {
+            let index = ruler_state.next_endpoint;
+            ruler_state.set_endpoint(
+                index,
+                RulerEndpoint {
+                    position: hit.position,
+                    vertex: hit.nearest_vertex(geometry),
+                },
+            );
+            ruler_state.next_endpoint = (index + 1) % ruler_state.tool.point_count();
+        }
+        return;
+    }
+
+    if mouse_button_input.pressed(MouseButton::Left) {
+        if let Some(index) = ruler_state.dragging {
+            if let Some(hit) = pick_surface(geometry, ray_origin, ray_direction) {
+                ruler_state.set_endpoint(
+                    index,
+                    RulerEndpoint {
+                        position: hit.position,
+                        vertex: hit.nearest_vertex(geometry),
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// Recompute the geodesic path when endpoints changed
+///
+/// The path is only recomputed once the drag has finished, since Dijkstra over
+/// the whole mesh is too expensive to run every frame on large models.
+fn update_geodesic_path(current_model: Res<CurrentModelData>, mut ruler_state: ResMut<RulerState>) {
+    if current_model.geometry.is_none()
+        && (ruler_state.endpoints.iter().any(Option::is_some)
+            || !ruler_state.measurements.is_empty())
+    {
+        ruler_state.clear();
+        ruler_state.measurements.clear();
+        return;
+    }
+
+    if !ruler_state.show_geodesic || !ruler_state.geodesic_dirty || ruler_state.dragging.is_some() {
+        return;
+    }
+    ruler_state.geodesic_dirty = false;
+
+    let (Some(geometry), [Some(start), Some(end), _], MeasureTool::Distance) = (
+        current_model.geometry.as_ref(),
+        ruler_state.endpoints,
+        ruler_state.tool,
+    ) else {
+        ruler_state.geodesic = None;
+        return;
+    };
+
+    ruler_state.geodesic =
+        shortest_edge_path(geometry, start.vertex, end.vertex).map(|(edge_length, path)| {
+            let mut points = Vec::with_capacity(path.len() + 2);
+            points.push(start.position);
+            points.extend(
+                path.iter()
+                    .map(|&v| Vec3::from(geometry.vertices[v as usize])),
+            );
+            points.push(end.position);
+
+            let first_segment = start.position.distance(points[1]);
+            let last_segment = end.position.distance(points[points.len() - 2]);
+
+            GeodesicPath {
+                length: edge_length + first_segment + last_segment,
+                points,
+            }
+        });
+
+    if ruler_state.geodesic.is_none() {
+        println!("Ruler endpoints are not connected, geodesic distance unavailable");
+    }
+}
+
+/// Draw ruler endpoints, the straight segment, the geodesic path and the kept
+/// measurements
+fn draw_ruler(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    ruler_state: Res<RulerState>,
+    model_query: Query<&GlobalTransform, With<UserModelMesh>>,
+) {
+    let model_to_world = model_matrix(&model_query);
+    let to_world = |point: Vec3| model_to_world.transform_point3(point);
+
+    for endpoint in ruler_state.endpoints.iter().flatten() {
+        gizmos.sphere(
+            Isometry3d::from_translation(to_world(endpoint.position)),
+            ruler_state.endpoint_radius,
+            RULER_COLOR,
+        );
+    }
+
+    match ruler_state.current() {
+        Some(measurement) => draw_measurement(&mut gizmos, &measurement, to_world, RULER_COLOR),
+        // Connect the first two points of an unfinished angle
+        None => {
+            if let (MeasureTool::Angle, [Some(a), Some(b), _]) =
+                (ruler_state.tool, ruler_state.endpoints)
+            {
+                gizmos.line(to_world(a.position), to_world(b.position), RULER_COLOR);
+            }
+        }
+    }
+
+    if ruler_state.show_geodesic {
+        if let Some(ref geodesic) = ruler_state.geodesic {
+            gizmos.linestrip(
+                geodesic.points.iter().map(|&point| to_world(point)),
+                GEODESIC_COLOR,
+            );
+        }
+    }
+
+    for measurement in &ruler_state.measurements {
+        for &point in &measurement.points[..measurement.tool.point_count()] {
+            gizmos.sphere(
+                Isometry3d::from_translation(to_world(point)),
+                ruler_state.endpoint_radius * 0.7,
+                MEASUREMENT_COLOR,
+            );
+        }
+        draw_measurement(&mut gizmos, measurement, to_world, MEASUREMENT_COLOR);
+    }
+}
+
+/// Draw the segment of a distance, the legs and arc of an angle or the circle
+/// of a radius, in world space
+fn draw_measurement(
+    gizmos: &mut Gizmos<OverlayGizmos>,
+    measurement: &Measurement,
+    to_world: impl Fn(Vec3) -> Vec3,
+    color: Color,
+) {
+    let [a, b, c] = measurement.points.map(to_world);
+    match measurement.tool {
+        MeasureTool::Distance => {
+            gizmos.line(a, b, color);
+        }
+        MeasureTool::Angle => {
+            gizmos.line(a, b, color);
+            gizmos.line(b, c, color);
+            let arc_radius = a.distance(b).min(c.distance(b)) * ANGLE_ARC_FACTOR;
+            let (from, to) = (
+                b + (a - b).normalize_or_zero() * arc_radius,
+                b + (c - b).normalize_or_zero() * arc_radius,
+            );
+            if arc_radius > 0.0 {
+                gizmos.short_arc_3d_between(b, from, to, color);
+            }
+        }
+        MeasureTool::Radius => {
+            if let Some(circle) = circle_through(a, b, c) {
+                gizmos
+                    .circle(
+                        Isometry3d::new(
+                            circle.center,
+                            Quat::from_rotation_arc(Vec3::Z, circle.normal),
+                        ),
+                        circle.radius,
+                        color,
+                    )
+                    .resolution(64);
+                gizmos.line(circle.center, a, color);
+            }
+        }
+    }
+}
+
+/// Label the ruler and the kept measurements with their value, at the
+/// projected anchor of each
+fn draw_measurement_labels(
+    mut contexts: EguiContexts,
+    ruler_state: Res<RulerState>,
+    ui_settings: Res<UiSettings>,
+    model_query: Query<&GlobalTransform, With<UserModelMesh>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<WorldModelCamera>>,
+) {
+    if !ruler_state.show_labels {
+        return;
+    }
+    let kept = ruler_state
+        .measurements
+        .iter()
+        .enumerate()
+        .map(|(index, measurement)| (format!("#{}: ", index + 1), measurement));
+    let current = ruler_state.current();
+    let labels: Vec<(String, &Measurement)> = kept
+        .chain(
+            current
+                .as_ref()
+                .map(|measurement| (String::new(), measurement)),
+        )
+        .collect();
+    if labels.is_empty() {
+        return;
+    }
+    let Some((camera, camera_transform)) = camera_query.iter().next() else {
+        return;
+    };
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let model_to_world = model_matrix(&model_query);
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Foreground,
+        egui::Id::new("measurement_labels"),
+    ));
+    let screen = ctx.screen_rect();
+    let font = egui::FontId::monospace(12.0);
+
+    for (prefix, measurement) in labels {
+        let anchor = model_to_world.transform_point3(measurement.anchor());
+        let Ok(screen_position) = camera.world_to_viewport(camera_transform, anchor) else {
+            continue;
+        };
+        let position = ui_settings.ui_position(screen_position);
+        if !screen.contains(position) {
+            continue;
+        }
+        let text = format!("{}{}", prefix, measurement.text());
+        let galley = painter.layout_no_wrap(text, font.clone(), LABEL_COLOR);
+        let rect = egui::Align2::CENTER_BOTTOM.anchor_size(position, galley.size());
+        painter.rect_filled(rect.expand(2.0), 2.0, egui::Color32::from_black_alpha(170));
+        painter.galley(rect.min, galley, LABEL_COLOR);
+    }
+}
+
+/// Ruler result panel
+fn ruler_panel(
+    mut contexts: EguiContexts,
+    mut interaction_mode: ResMut<InteractionMode>,
+    mut ruler_state: ResMut<RulerState>,
+    windows: Query<&Window>,
+) {
+    if *interaction_mode != InteractionMode::Ruler || windows.iter().next().is_none() {
+        return;
+    }
+
+    egui::Window::new("Ruler")
+        .default_width(220.0)
+        .show(contexts.ctx_mut(), |ui| {
+            let mut tool = ruler_state.tool;
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut tool, MeasureTool::Distance, "Distance");
+                ui.radio_value(&mut tool, MeasureTool::Angle, "Angle");
+                ui.radio_value(&mut tool, MeasureTool::Radius, "Radius");
+            });
+            ruler_state.set_tool(tool);
+            ui.label(match tool {
+                MeasureTool::Distance => {
+                    "Click on the model to place endpoints, drag them to adjust."
+                }
+                MeasureTool::Angle => "Click a point on each leg and the vertex in between.",
+                MeasureTool::Radius => "Click three points on a circular edge.",
+            });
+            ui.separator();
+
+            let current = ruler_state.current();
+            match tool {
+                MeasureTool::Distance => match current {
+                    Some(measurement) => {
+                        let delta = measurement.delta();
+                        ui.label(format!("Distance: {}", measurement.text()));
+                        ui.label(format!(
+                            "dX: {:.4}  dY: {:.4}  dZ: {:.4}",
+                            delta.x, delta.y, delta.z
+                        ));
+                    }
+                    None => {
+                        ui.label("Distance: -");
+                    }
+                },
+                MeasureTool::Angle => {
+                    let text = current.map_or("-".to_string(), |m| m.text());
+                    ui.label(format!("Angle: {}", text));
+                }
+                MeasureTool::Radius => {
+                    let [a, b, c] = current.map_or([Vec3::ZERO; 3], |m| m.points);
+                    match current.and_then(|_| circle_through(a, b, c)) {
+                        Some(circle) => {
+                            ui.label(format!(
+                                "Radius: {:.4}  Diameter: {:.4}",
+                                circle.radius,
+                                circle.radius * 2.0
+                            ));
+                            ui.label(format!(
+                                "Center: ({:.4}, {:.4}, {:.4})",
+                                circle.center.x, circle.center.y, circle.center.z
+                            ));
+                        }
+                        None if current.is_some() => {
+                            ui.label("Radius: - (points are collinear)");
+                        }
+                        None => {
+                            ui.label("Radius: -");
+                        }
+                    }
+                }
+            }
+
+            if tool == MeasureTool::Distance {
+                let geodesic_response =
+                    ui.checkbox(&mut ruler_state.show_geodesic, "Geodesic distance");
+                if geodesic_response.changed() && ruler_state.show_geodesic {
+                    ruler_state.geodesic_dirty = true;
+                }
+            }
+            if tool == MeasureTool::Distance && ruler_state.show_geodesic {
+                match ruler_state.geodesic {
+                    Some(ref geodesic) => {
+                        ui.label(format!("Geodesic: {:.4}", geodesic.length));
+                    }
+                    None => {
+                        ui.label("Geodesic: -");
+                    }
+                }
+            }
+
+            ui.checkbox(&mut ruler_state.show_labels, "Value labels");
+
+            if !ruler_state.measurements.is_empty() {
+                ui.separator();
+                let mut removed = None;
+                egui::ScrollArea::vertical()
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("kept_measurements")
+                            .striped(true)
+                            .num_columns(6)
+                            .show(ui, |ui| {
+                                for label in ["#", "Value", "dX", "dY", "dZ", ""] {
+                                    ui.strong(label);
+                                }
+                                ui.end_row();
+                                for (index, measurement) in
+                                    ruler_state.measurements.iter().enumerate()
+                                {
+                                    ui.label((index + 1).to_string());
+                                    ui.label(measurement.text());
+                                    if measurement.tool == MeasureTool::Distance {
+                                        let delta = measurement.delta();
+                                        ui.label(format!("{:.4}", delta.x));
+                                        ui.label(format!("{:.4}", delta.y));
+                                        ui.label(format!("{:.4}", delta.z));
+                                    } else {
+                                        ui.label("");
+                                        ui.label("");
+                                        ui.label("");
+                                    }
+                                    if ui.small_button("x").clicked() {
+                                        removed = Some(index);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                if let Some(index) = removed {
+                    ruler_state.measurements.remove(index);
+                }
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(ruler_state.current().is_some(), egui::Button::new("Keep"))
+                    .clicked()
+                {
+                    ruler_state.keep();
+                }
+                if ui.button("Copy Values").clicked() {
+                    ui.ctx().copy_text(ruler_state.to_csv());
+                }
+                if ui.button("Clear").clicked() {
+                    ruler_state.clear();
+                }
+                if ui
+                    .add_enabled(
+                        !ruler_state.measurements.is_empty(),
+                        egui::Button::new("Clear All"),
+                    )
+                    .clicked()
+                {
+                    ruler_state.clear();
+                    ruler_state.measurements.clear();
+                }
+                if ui.button("Close").clicked() {
+                    ruler_state.clear();
+                    *interaction_mode = InteractionMode::Navigate;
+                }
+            });
+        });
+}
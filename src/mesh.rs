@@ -1,9 +1,55 @@
 use std::fmt;
+pub mod calculator;
+pub mod cell_types;
+pub mod cells;
+pub mod cgns;
+pub mod climate;
+pub mod clip;
 pub mod color_maps;
+pub mod color_presets;
+pub mod compression;
+pub mod connectivity;
+pub mod conversion;
+pub mod csv;
+pub mod delaunay;
+pub mod derivatives;
+pub mod downsample;
+pub mod exodus;
+pub mod export;
+pub mod extrude;
+pub mod geodesic;
+pub mod ghost;
+pub mod glyphs;
+pub mod hdf5;
+pub mod integrate;
+pub mod isosurface;
+pub mod nastran;
+pub mod netcdf;
+pub mod normals;
+pub mod obj;
+pub mod pathlines;
+pub mod ply;
+pub mod probe;
+pub mod progress;
+pub mod pvd;
+pub mod quality;
+pub mod query;
+pub mod resample;
+pub mod slice;
+pub mod streaming;
+pub mod streamlines;
 pub mod subdivision;
+pub mod temporal;
+pub mod topology;
+pub mod transform;
 pub mod triangulation;
+pub mod tubes;
+pub mod volume;
 pub mod vtk;
+pub mod warp;
 pub mod wave;
+pub mod weld;
+pub mod xdmf;
 pub use self::vtk::{AttributeLocation, AttributeType};
 // pub use self::color_maps::{ColorMapper, ColorMappingConfig};
 
@@ -40,6 +86,18 @@ pub struct QuadraticTriangle {
     pub vertices: [u32; 6],
 }
 
+/// Polyline cell (VTK line or poly line)
+///
+/// Lines form no surface; they are kept for filters that render them, such as
+/// tubes.
+#[derive(Debug, Clone)]
+pub struct PolyLine {
+    /// Index of the original cell
+    pub cell: usize,
+    /// Point indices along the line
+    pub points: Vec<u32>,
+}
+
 impl QuadraticEdge {
     /// Create a new quadratic edge
     pub fn new(vertices: [u32; 3]) -> Self {
@@ -112,15 +170,22 @@ pub struct GeometryData {
     pub attributes: Option<HashMap<(String, AttributeLocation), AttributeType>>,
     /// Lookup table data
     pub lookup_tables: HashMap<String, Vec<[f32; 4]>>,
-    /// Normal vectors
-    #[allow(dead_code)]
-    normals: Option<Vec<[f32; 3]>>,
+    /// Normal vectors (computed from the triangles if absent)
+    pub normals: Option<Vec<[f32; 3]>>,
+    /// Texture coordinates
+    pub uvs: Option<Vec<[f32; 2]>>,
     /// Mapping from triangles to original cells
     pub triangle_to_cell_mapping: Option<Vec<usize>>,
     /// Quadratic triangle data for subdivision
     pub quadratic_triangles: Option<Vec<QuadraticTriangle>>,
     /// Quadratic edge data for subdivision
     pub quadratic_edges: Option<Vec<QuadraticEdge>>,
+    /// Volumetric cells, for filters working inside the volume
+    pub volume_cells: Option<cells::VolumeCells>,
+    /// VTK cell type id of every original cell
+    pub cell_types: Option<Vec<u8>>,
+    /// Line and poly line cells
+    pub lines: Option<Vec<PolyLine>>,
 }
 
 #[allow(dead_code)]
@@ -137,9 +202,13 @@ impl GeometryData {
             attributes: Some(attributes),
             lookup_tables: HashMap::new(),
             normals: None,
+            uvs: None,
             triangle_to_cell_mapping: None,
             quadratic_triangles: None,
             quadratic_edges: None,
+            volume_cells: None,
+            cell_types: None,
+            lines: None,
         }
     }
 
@@ -155,6 +224,24 @@ impl GeometryData {
         self
     }
 
+    /// Add volumetric cells
+    pub fn add_volume_cells(mut self, volume_cells: Option<cells::VolumeCells>) -> Self {
+        self.volume_cells = volume_cells;
+        self
+    }
+
+    /// Add VTK cell type ids of the original cells
+    pub fn add_cell_types(mut self, cell_types: Vec<u8>) -> Self {
+        self.cell_types = Some(cell_types);
+        self
+    }
+
+    /// Add line and poly line cells (ignored if there are none)
+    pub fn add_lines(mut self, lines: Vec<PolyLine>) -> Self {
+        self.lines = (!lines.is_empty()).then_some(lines);
+        self
+    }
+
     /// Add attribute data
     pub fn add_attributes(
         mut self,
@@ -164,6 +251,18 @@ impl GeometryData {
         self
     }
 
+    /// Add per-vertex normals
+    pub fn add_normals(mut self, normals: Vec<[f32; 3]>) -> Self {
+        self.normals = Some(normals);
+        self
+    }
+
+    /// Add per-vertex texture coordinates
+    pub fn add_uvs(mut self, uvs: Vec<[f32; 2]>) -> Self {
+        self.uvs = Some(uvs);
+        self
+    }
+
     /// Add triangle to cell mapping
     pub fn add_triangle_to_cell_mapping(mut self, mapping: Vec<usize>) -> Self {
         self.triangle_to_cell_mapping = Some(mapping);
@@ -399,6 +498,34 @@ impl From<std::io::Error> for VtkError {
 
 //************************************* Main Process Logic**************************************//
 
+/// Load geometry from an OBJ, PLY, Exodus II, CGNS, netCDF, XDMF, Nastran or VTK file,
+/// selected by the file extension. netCDF grids are placed on a plane.
+///
+/// # Parameters
+/// - `path`: Model file path (`.obj`, `.ply`, `.vtk`, `.vtu`, `.vtp`, `.vts`, `.vtr`, `.vti`,
+///   `.pvtu`, `.pvtp`, `.e`, `.exo`, `.ex2`, `.cgns`, `.nc`, `.xdmf`, `.xmf`,
+///   `.bdf`, `.nas`)
+///
+/// # Returns
+/// - `Ok(GeometryData)`: Parsed geometry
+/// - `Err(VtkError)`: Loading failed or the format is not supported
+pub fn load_geometry_file(path: &std::path::Path) -> Result<GeometryData, VtkError> {
+    progress::report_parsing();
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("obj") => obj::load_obj(path),
+        Some("ply") => ply::load_ply(path),
+        Some("vtk" | "vtu" | "vtp" | "vts" | "vtr" | "vti") => vtk::load_vtk_geometry(path),
+        Some("pvtu" | "pvtp") => vtk::load_parallel_vtk_geometry(path),
+        Some("e" | "exo" | "ex2") => exodus::load_exodus(path),
+        Some("cgns") => cgns::load_cgns(path),
+        Some("nc" | "nc3" | "cdf") => climate::load_climate(path, climate::Projection::Plane),
+        Some("xdmf" | "xmf") => xdmf::load_xdmf(path),
+        Some("bdf" | "nas") => nastran::load_nastran(path),
+        Some("csv") => csv::load_csv_points(path),
+        _ => Err(VtkError::UnsupportedDataType),
+    }
+}
+
 // Creates an optimized Bevy rendering mesh from geometry data
 pub fn create_mesh_from_geometry(geometry: &GeometryData) -> Mesh {
     // 1. create a basic mesh
@@ -407,17 +534,32 @@ pub fn create_mesh_from_geometry(geometry: &GeometryData) -> Mesh {
         RenderAssetUsages::default(),
     );
 
-    // 2. add vertex positions
-    mesh.insert_attribute(
-        Mesh::ATTRIBUTE_POSITION,
-        VertexAttributeValues::from(geometry.vertices.clone()),
-    );
-
-    // 3. add vertex indices
-    mesh.insert_indices(Indices::U32(geometry.indices.clone()));
+    // 2. add vertex positions and indices with normals; without stored
+    // normals, vertices along edges sharper than the crease angle are split
+    match geometry.normals {
+        Some(ref normals) if normals.len() == geometry.vertices.len() => {
+            mesh.insert_attribute(
+                Mesh::ATTRIBUTE_POSITION,
+                VertexAttributeValues::from(geometry.vertices.clone()),
+            );
+            mesh.insert_indices(Indices::U32(geometry.indices.clone()));
+            mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals.clone());
+        }
+        _ => normals::apply_crease_normals(
+            &mut mesh,
+            &geometry.vertices,
+            &geometry.indices,
+            normals::DEFAULT_CREASE_ANGLE,
+        ),
+    }
 
-    // 4. compute normals
-    mesh.compute_normals();
+    // 3. add texture coordinates if available
+    if let Some(ref uvs) = geometry.uvs {
+        if uvs.len() == geometry.vertices.len() {
+            let uvs = normals::expand_to_mesh(&mesh, uvs.clone(), [0.0, 0.0]);
+            mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        }
+    }
 
     // 5. apply color attributes by priority
     // 5.1 first try to apply scalar attributes (typically the most important data)
@@ -438,7 +580,7 @@ pub fn create_mesh_from_geometry(geometry: &GeometryData) -> Mesh {
             if !point_color_applied {
                 println!("No color attributes found, applying default colors");
                 // default use white
-                let default_colors = vec![[1.0, 1.0, 1.0, 1.0]; geometry.vertices.len()];
+                let default_colors = vec![[1.0, 1.0, 1.0, 1.0]; mesh.count_vertices()];
                 mesh.insert_attribute(
                     Mesh::ATTRIBUTE_COLOR,
                     VertexAttributeValues::from(default_colors),
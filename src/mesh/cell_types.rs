@@ -0,0 +1,112 @@
+//! Cell types module
+//!
+//! Extraction of cells by their VTK cell type:
+//! - Unstructured grids keep the type id of every cell when they are loaded
+//! - Other models get types inferred from the triangles of each cell (one
+//!   triangle is a triangle, two a quad, more a polygon) and from their lines
+//! - The extracted cells keep their surface triangles and attributes, so only
+//!   tetrahedra extract the surfaces of the tetrahedra
+use super::topology::{cell_count, extract_cells, triangle_cell};
+use super::GeometryData;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// VTK_EMPTY_CELL, used for cells without triangles and unknown types
+pub const EMPTY_CELL: u8 = 0;
+
+/// Name of a VTK cell type id
+pub fn cell_type_name(cell_type: u8) -> &'static str {
+    match cell_type {
+        1 => "Vertex",
+        2 => "Poly Vertex",
+        3 => "Line",
+        4 => "Poly Line",
+        5 => "Triangle",
+        6 => "Triangle Strip",
+        7 => "Polygon",
+        8 => "Pixel",
+        9 => "Quad",
+        10 => "Tetra",
+        11 => "Voxel",
+        12 => "Hexahedron",
+        13 => "Wedge",
+        14 => "Pyramid",
+        21 => "Quadratic Edge",
+        22 => "Quadratic Triangle",
+        23 => "Quadratic Quad",
+        24 => "Quadratic Tetra",
+        25 => "Quadratic Hexahedron",
+        26 => "Quadratic Wedge",
+        27 => "Quadratic Pyramid",
+        28 => "Biquadratic Quad",
+        29 => "Triquadratic Hexahedron",
+        34 => "Biquadratic Triangle",
+        35 => "Cubic Line",
+        41 => "Convex Point Set",
+        42 => "Polyhedron",
+        _ => "Empty Cell",
+    }
+}
+
+/// VTK cell type id of every cell
+///
+/// # Returns
+/// - Stored types, or types inferred from the triangle count of each cell
+pub fn cell_types(geometry: &GeometryData) -> Vec<u8> {
+    let count = cell_count(geometry);
+    if let Some(types) = geometry
+        .cell_types
+        .as_ref()
+        .filter(|types| types.len() >= count)
+    {
+        return types.clone();
+    }
+
+    let mut triangles = vec![0usize; count];
+    for triangle in 0..geometry.indices.len() / 3 {
+        if let Some(cell) = triangles.get_mut(triangle_cell(geometry, triangle)) {
+            *cell += 1;
+        }
+    }
+    let mut types: Vec<u8> = triangles
+        .into_iter()
+        .map(|count| match count {
+            0 => EMPTY_CELL,
+            1 => 5,
+            2 => 9,
+            _ => 7,
+        })
+        .collect();
+    for line in geometry.lines.iter().flatten() {
+        if let Some(cell_type) = types.get_mut(line.cell) {
+            *cell_type = if line.points.len() == 2 { 3 } else { 4 };
+        }
+    }
+    types
+}
+
+/// Number of cells of every type present, in type id order
+pub fn cell_type_counts(geometry: &GeometryData) -> BTreeMap<u8, usize> {
+    let mut counts = BTreeMap::new();
+    for cell_type in cell_types(geometry) {
+        *counts.entry(cell_type).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Extract the cells of the given types into new geometry
+///
+/// # Parameters
+/// - `geometry`: Source geometry
+/// - `types`: VTK cell type ids to keep
+///
+/// # Returns
+/// - Geometry with the cells of the given types and their attributes
+pub fn extract_cell_types(geometry: &GeometryData, types: &BTreeSet<u8>) -> GeometryData {
+    let cells: BTreeSet<usize> = cell_types(geometry)
+        .into_iter()
+        .enumerate()
+        .filter(|(_, cell_type)| types.contains(cell_type))
+        .map(|(cell, _)| cell)
+        .collect();
+    extract_cells(geometry, &cells)
+}
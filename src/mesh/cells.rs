@@ -0,0 +1,225 @@
+//! Volume cells module
+//!
+//! The triangulated surface of an unstructured grid loses the interior of its
+//! volumetric cells. Filters that work inside the volume (isosurfaces) need those
+//! cells, so linear tetrahedra, hexahedra, wedges and pyramids are kept here next
+//! to the surface. Quadratic cells are kept by their corner points and voxels are
+//! reordered to hexahedra.
+use vtkio::model::{self, VertexNumbers};
+
+/// Shape of a volume cell (VTK point order)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CellShape {
+    Tetra,
+    Hexahedron,
+    Wedge,
+    Pyramid,
+}
+
+impl CellShape {
+    /// Decomposition into tetrahedra, as corner numbers of the cell
+    pub fn tetrahedra(self) -> &'static [[usize; 4]] {
+        match self {
+            CellShape::Tetra => &[[0, 1, 2, 3]],
+            // Six tetrahedra around the diagonal 0-6
+            CellShape::Hexahedron => &[
+                [0, 1, 2, 6],
+                [0, 2, 3, 6],
+                [0, 3, 7, 6],
+                [0, 7, 4, 6],
+                [0, 4, 5, 6],
+                [0, 5, 1, 6],
+            ],
+            CellShape::Wedge => &[[0, 1, 2, 3], [1, 2, 3, 4], [2, 3, 4, 5]],
+            CellShape::Pyramid => &[[0, 1, 2, 4], [0, 2, 3, 4]],
+        }
+    }
+
+    /// Shape and corner order of a VTK cell type, `None` for cells without volume
+    fn from_vtk(cell_type: &model::CellType) -> Option<(Self, &'static [usize])> {
+        use model::CellType::*;
+        match cell_type {
+            Tetra | QuadraticTetra => Some((CellShape::Tetra, &[0, 1, 2, 3])),
+            Hexahedron | QuadraticHexahedron => {
+                Some((CellShape::Hexahedron, &[0, 1, 2, 3, 4, 5, 6, 7]))
+            }
+            // Voxel points vary x fastest, hexahedron points run around the faces
+            Voxel => Some((CellShape::Hexahedron, &[0, 1, 3, 2, 4, 5, 7, 6])),
+            Wedge | QuadraticWedge => Some((CellShape::Wedge, &[0, 1, 2, 3, 4, 5])),
+            Pyramid | QuadraticPyramid => Some((CellShape::Pyramid, &[0, 1, 2, 3, 4])),
+            _ => None,
+        }
+    }
+}
+
+/// Volume cells of a dataset
+///
+/// Point indices refer to the vertices of the geometry the cells belong to; cell
+/// ids are the cell numbers of the dataset, which index its cell attributes.
+#[derive(Clone, Debug, Default)]
+pub struct VolumeCells {
+    /// Shape of every cell
+    pub shapes: Vec<CellShape>,
+    /// End offset of every cell in `connectivity`
+    pub offsets: Vec<usize>,
+    /// Corner points of all cells
+    pub connectivity: Vec<u32>,
+    /// Dataset cell number of every cell
+    pub cell_ids: Vec<usize>,
+}
+
+impl VolumeCells {
+    /// Collect the volume cells of VTK cells
+    ///
+    /// # Returns
+    /// - Volume cells, or `None` if there are none
+    pub fn from_vtk(cells: &model::Cells) -> Option<Self> {
+        match &cells.cell_verts {
+            VertexNumbers::Legacy {
+                num_cells,
+                vertices,
+            } => {
+                let mut volume_cells = Self::default();
+                let mut start = 0;
+                for cell in 0..*num_cells as usize {
+                    let Some(&count) = vertices.get(start) else {
+                        break;
+                    };
+                    let end = (start + 1 + count as usize).min(vertices.len());
+                    if let Some(cell_type) = cells.types.get(cell) {
+                        volume_cells.push(cell, cell_type, &vertices[start + 1..end]);
+                    }
+                    start = end;
+                }
+                volume_cells.non_empty()
+            }
+            VertexNumbers::XML {
+                connectivity,
+                offsets,
+            } => Self::from_arrays(&cells.types, offsets, connectivity),
+        }
+    }
+
+    /// Collect the volume cells of cells given by end offsets and connectivity
+    ///
+    /// # Returns
+    /// - Volume cells, or `None` if there are none
+    pub fn from_arrays(
+        types: &[model::CellType],
+        offsets: &[u64],
+        connectivity: &[u64],
+    ) -> Option<Self> {
+        let mut volume_cells = Self::default();
+        let mut start = 0;
+        for (cell, (cell_type, &end)) in types.iter().zip(offsets).enumerate() {
+            let end = (end as usize).min(connectivity.len());
+            volume_cells.push(cell, cell_type, &connectivity[start.min(end)..end]);
+            start = end;
+        }
+        volume_cells.non_empty()
+    }
+
+    /// Add a cell if it has a volume
+    fn push<T: Copy + Into<u64>>(
+        &mut self,
+        cell: usize,
+        cell_type: &model::CellType,
+        points: &[T],
+    ) {
+        let Some((shape, corners)) = CellShape::from_vtk(cell_type) else {
+            return;
+        };
+        if points.len() < corners.len() {
+            return;
+        }
+        self.connectivity
+            .extend(corners.iter().map(|&corner| points[corner].into() as u32));
+        self.offsets.push(self.connectivity.len());
+        self.shapes.push(shape);
+        self.cell_ids.push(cell);
+    }
+
+    fn non_empty(self) -> Option<Self> {
+        (!self.shapes.is_empty()).then_some(self)
+    }
+
+    /// Number of cells
+    pub fn len(&self) -> usize {
+        self.shapes.len()
+    }
+
+    /// Whether there are no cells
+    pub fn is_empty(&self) -> bool {
+        self.shapes.is_empty()
+    }
+
+    /// Shape and corner points of a cell
+    pub fn cell(&self, index: usize) -> (CellShape, &[u32]) {
+        let start = if index == 0 {
+            0
+        } else {
+            self.offsets[index - 1]
+        };
+        (
+            self.shapes[index],
+            &self.connectivity[start..self.offsets[index]],
+        )
+    }
+
+    /// Keep the cells for which `keep` returns a new cell id
+    ///
+    /// # Parameters
+    /// - `keep`: New cell id of a dataset cell id, `None` to drop the cell
+    pub fn retain_cells(&mut self, keep: impl Fn(usize) -> Option<usize>) {
+        self.retain(|cell_id, _| keep(cell_id).map(|id| (id, None)));
+    }
+
+    /// Renumber the points of all cells; cells with a removed point are dropped
+    ///
+    /// # Parameters
+    /// - `remap`: New index of a point, `None` if the point was removed
+    pub fn remap_points(&mut self, remap: impl Fn(u32) -> Option<u32>) {
+        self.retain(|cell_id, points| {
+            let points: Option<Vec<u32>> = points.iter().map(|&point| remap(point)).collect();
+            points.map(|points| (cell_id, Some(points)))
+        });
+    }
+
+    /// Rebuild the cells, keeping those for which `map` returns a new cell id
+    /// and optionally new points
+    fn retain(&mut self, map: impl Fn(usize, &[u32]) -> Option<(usize, Option<Vec<u32>>)>) {
+        let old = std::mem::take(self);
+        for index in 0..old.len() {
+            let (shape, points) = old.cell(index);
+            let Some((cell_id, new_points)) = map(old.cell_ids[index], points) else {
+                continue;
+            };
+            self.connectivity
+                .extend_from_slice(new_points.as_deref().unwrap_or(points));
+            self.offsets.push(self.connectivity.len());
+            self.shapes.push(shape);
+            self.cell_ids.push(cell_id);
+        }
+    }
+
+    /// Append the cells of another piece
+    ///
+    /// # Parameters
+    /// - `other`: Cells of the piece
+    /// - `point_offset`: Index of the first point of the piece
+    /// - `cell_offset`: Number of the first cell of the piece
+    pub fn append(&mut self, other: &VolumeCells, point_offset: u32, cell_offset: usize) {
+        let connectivity_offset = self.connectivity.len();
+        self.shapes.extend_from_slice(&other.shapes);
+        self.offsets.extend(
+            other
+                .offsets
+                .iter()
+                .map(|offset| offset + connectivity_offset),
+        );
+        self.connectivity
+            .extend(other.connectivity.iter().map(|point| point + point_offset));
+        self.cell_ids
+            .extend(other.cell_ids.iter().map(|cell| cell + cell_offset));
+    }
+}
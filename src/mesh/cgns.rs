@@ -0,0 +1,459 @@
+//! CGNS import module
+//!
+//! Reads CGNS files (`.cgns`) stored as HDF5. Every CGNS node is an HDF5 group
+//! with `name`, `label` and `type` attributes and its data in a ` data` dataset.
+//! - Unstructured zones of all bases: `GridCoordinates` and the element sections
+//!   (`Elements_t`), including `MIXED`, `NGON_n` and `NFACE_n` sections
+//! - Sections of the highest element dimension are rendered, their faces
+//!   triangulated; one cell per element, numbered in element order
+//! - Flow solutions at `Vertex` or `CellCenter` become point or cell scalars
+//! - Zones are merged into one model; the zone number is kept as cell scalar `zone`
+//!
+//! Structured zones are skipped.
+use super::exodus::{HEX_FACES, PYRAMID_FACES, TETRA_FACES, WEDGE_FACES};
+use super::hdf5::{Dataset, Hdf5File};
+use super::{vtk, AttributeLocation, AttributeType, GeometryData, VtkError};
+use bevy::utils::HashMap;
+use std::path::Path;
+
+/// Element type codes (`ElementType_t`) of polymorphic sections
+const MIXED: usize = 20;
+const NGON_N: usize = 22;
+const NFACE_N: usize = 23;
+
+/// CGNS node: an HDF5 group with its name and label
+struct Node {
+    name: String,
+    label: String,
+    address: u64,
+}
+
+/// Element section of a zone
+struct Section {
+    element_type: usize,
+    /// First and last element number
+    range: (usize, usize),
+    connectivity: Vec<i64>,
+    /// Start of every element in the connectivity (CGNS 4 polymorphic sections)
+    offsets: Option<Vec<i64>>,
+}
+
+/// Dimension and faces of the linear shape of an element type
+///
+/// Higher order elements list their corner nodes first, so the faces of the
+/// linear shape are used for them.
+fn element_shape(element_type: usize) -> Option<(usize, &'static [&'static [usize]])> {
+    const TRIANGLE: &[&[usize]] = &[&[0, 1, 2]];
+    const QUAD: &[&[usize]] = &[&[0, 1, 2, 3]];
+    match element_type {
+        2 => Some((0, &[])),
+        3 | 4 | 24 | 40 => Some((1, &[])),
+        5 | 6 | 25 | 26 | 41 | 42 => Some((2, TRIANGLE)),
+        7..=9 | 27 | 28 | 43 | 44 => Some((2, QUAD)),
+        10 | 11 | 29 | 30 | 45..=47 => Some((3, &TETRA_FACES)),
+        12 | 13 | 21 | 31..=33 | 48..=50 => Some((3, &PYRAMID_FACES)),
+        14..=16 | 34..=36 | 51..=53 => Some((3, &WEDGE_FACES)),
+        17..=19 | 37..=39 | 54..=56 => Some((3, &HEX_FACES)),
+        _ => None,
+    }
+}
+
+/// Number of nodes of a fixed-size element type
+fn node_count(element_type: usize) -> Option<usize> {
+    const COUNTS: [usize; 57] = [
+        0, 0, 1, 2, 3, 3, 6, 4, 8, 9, 4, 10, 5, 14, 6, 15, 18, 8, 20, 27, 0, 13, 0, 0, 4, 9, 10,
+        12, 16, 16, 20, 21, 29, 30, 24, 38, 40, 32, 56, 64, 5, 12, 15, 16, 25, 22, 34, 35, 29, 50,
+        55, 33, 66, 75, 44, 98, 125,
+    ];
+    COUNTS.get(element_type).copied().filter(|&count| count > 0)
+}
+
+/// Child nodes of a node, without the HDF5 data and link members
+fn nodes(file: &Hdf5File, group: u64) -> Result<Vec<Node>, VtkError> {
+    let mut nodes = Vec::new();
+    for (link, address) in file.children(group)? {
+        if link.starts_with(' ') {
+            continue;
+        }
+        let label = file
+            .attribute(address, "label")?
+            .map(|label| label.to_text())
+            .unwrap_or_default();
+        let name = file
+            .attribute(address, "name")?
+            .map(|name| name.to_text())
+            .unwrap_or(link);
+        nodes.push(Node {
+            name,
+            label,
+            address,
+        });
+    }
+    Ok(nodes)
+}
+
+/// Data of a node, `None` for nodes without data
+fn node_data(file: &Hdf5File, node: &Node) -> Result<Option<Dataset>, VtkError> {
+    match file
+        .children(node.address)?
+        .into_iter()
+        .find(|(link, _)| link == " data")
+    {
+        Some((_, address)) => file.read_dataset(address).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Numeric data of a node
+fn node_values(file: &Hdf5File, node: &Node) -> Result<Vec<f64>, VtkError> {
+    node_data(file, node)?
+        .ok_or(VtkError::MissingData("CGNS node has no data"))?
+        .to_f64()
+}
+
+/// Integer data of a named child node
+fn child_integers(
+    file: &Hdf5File,
+    children: &[Node],
+    name: &str,
+) -> Result<Option<Vec<i64>>, VtkError> {
+    match children.iter().find(|node| node.name == name) {
+        Some(node) => Ok(Some(
+            node_values(file, node)?
+                .into_iter()
+                .map(|value| value as i64)
+                .collect(),
+        )),
+        None => Ok(None),
+    }
+}
+
+/// Read an element section
+fn read_section(file: &Hdf5File, node: &Node) -> Result<Section, VtkError> {
+    let header = node_values(file, node)?;
+    let element_type = header.first().copied().unwrap_or(0.0) as usize;
+    let children = nodes(file, node.address)?;
+    let range = child_integers(file, &children, "ElementRange")?
+        .filter(|range| range.len() >= 2)
+        .ok_or(VtkError::MissingData("CGNS section has no ElementRange"))?;
+    let connectivity = child_integers(file, &children, "ElementConnectivity")?.ok_or(
+        VtkError::MissingData("CGNS section has no ElementConnectivity"),
+    )?;
+    let offsets = child_integers(file, &children, "ElementStartOffset")?;
+
+    Ok(Section {
+        element_type,
+        range: (range[0].max(1) as usize, range[1].max(0) as usize),
+        connectivity,
+        offsets,
+    })
+}
+
+impl Section {
+    fn element_count(&self) -> usize {
+        (self.range.1 + 1).saturating_sub(self.range.0)
+    }
+
+    /// Connectivity of every element as (element type, nodes)
+    ///
+    /// For `MIXED` sections the element type comes from the connectivity; for
+    /// `NGON_n` and `NFACE_n` it is the section type.
+    fn elements(&self) -> Result<Vec<(usize, &[i64])>, VtkError> {
+        let count = self.element_count();
+        let connectivity = self.connectivity.as_slice();
+        let invalid = || VtkError::InvalidFormat("Invalid CGNS element connectivity");
+        let mut elements = Vec::with_capacity(count);
+
+        match (self.element_type, &self.offsets) {
+            (MIXED | NGON_N | NFACE_N, Some(offsets)) => {
+                for window in offsets.windows(2).take(count) {
+                    let element = connectivity
+                        .get(window[0] as usize..window[1] as usize)
+                        .ok_or_else(invalid)?;
+                    elements.push(if self.element_type == MIXED {
+                        let (&code, nodes) = element.split_first().ok_or_else(invalid)?;
+                        (code as usize, nodes)
+                    } else {
+                        (self.element_type, element)
+                    });
+                }
+            }
+            (MIXED | NGON_N | NFACE_N, None) => {
+                // Every element starts with its type code (MIXED) or node count
+                let mut position = 0;
+                for _ in 0..count {
+                    let &first = connectivity.get(position).ok_or_else(invalid)?;
+                    let (element_type, size) = if self.element_type == MIXED {
+                        (
+                            first as usize,
+                            node_count(first as usize).ok_or_else(invalid)?,
+                        )
+                    } else {
+                        (self.element_type, first.max(0) as usize)
+                    };
+                    let nodes = connectivity
+                        .get(position + 1..position + 1 + size)
+                        .ok_or_else(invalid)?;
+                    elements.push((element_type, nodes));
+                    position += 1 + size;
+                }
+            }
+            (element_type, _) => {
+                let size = node_count(element_type).ok_or_else(invalid)?;
+                elements.extend(
+                    connectivity
+                        .chunks_exact(size)
+                        .take(count)
+                        .map(|nodes| (element_type, nodes)),
+                );
+            }
+        }
+        Ok(elements)
+    }
+
+    /// Element dimension (3 for polyhedra, 2 for polygons)
+    fn dimension(&self) -> usize {
+        match self.element_type {
+            NFACE_N => 3,
+            NGON_N => 2,
+            MIXED => self
+                .elements()
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|&(element_type, _)| element_shape(element_type))
+                .map(|(dimension, _)| dimension)
+                .max()
+                .unwrap_or(0),
+            element_type => element_shape(element_type).map_or(0, |(dimension, _)| dimension),
+        }
+    }
+}
+
+/// Read an unstructured zone
+///
+/// # Parameters
+/// - `zone`: Zone node
+/// - `zone_number`: Zone number stored in the `zone` cell scalar
+///
+/// # Returns
+/// - `Ok(Some(GeometryData))`: Triangulated zone with its flow solutions
+/// - `Ok(None)`: The zone is structured
+/// - `Err(VtkError)`: Missing coordinates or invalid connectivity
+fn read_zone(
+    file: &Hdf5File,
+    zone: &Node,
+    zone_number: usize,
+) -> Result<Option<GeometryData>, VtkError> {
+    let children = nodes(file, zone.address)?;
+    let zone_type = match children.iter().find(|node| node.label == "ZoneType_t") {
+        Some(node) => node_data(file, node)?
+            .map(|data| data.to_text())
+            .unwrap_or_default(),
+        None => String::new(),
+    };
+    if zone_type != "Unstructured" {
+        println!("Skipping {} zone {}", zone_type, zone.name);
+        return Ok(None);
+    }
+
+    // Coordinates of the first grid
+    let grid = children
+        .iter()
+        .find(|node| node.label == "GridCoordinates_t")
+        .ok_or(VtkError::MissingData("CGNS zone has no GridCoordinates"))?;
+    let coordinates = nodes(file, grid.address)?;
+    let mut axes = Vec::new();
+    for name in ["CoordinateX", "CoordinateY", "CoordinateZ"] {
+        match coordinates.iter().find(|node| node.name == name) {
+            Some(node) => axes.push(node_values(file, node)?),
+            None if name == "CoordinateZ" => {}
+            None => {
+                return Err(VtkError::MissingData(
+                    "CGNS zone coordinates are incomplete",
+                ))
+            }
+        }
+    }
+    let vertex_count = axes[0].len();
+    if axes.iter().any(|axis| axis.len() != vertex_count) {
+        return Err(VtkError::MissingData(
+            "CGNS zone coordinates are incomplete",
+        ));
+    }
+    let vertices: Vec<[f32; 3]> = (0..vertex_count)
+        .map(|vertex| std::array::from_fn(|axis| axes.get(axis).map_or(0.0, |a| a[vertex] as f32)))
+        .collect();
+
+    let mut sections = Vec::new();
+    for node in children.iter().filter(|node| node.label == "Elements_t") {
+        sections.push(read_section(file, node)?);
+    }
+    sections.sort_by_key(|section| section.range.0);
+    let dimension = sections.iter().map(Section::dimension).max().unwrap_or(0);
+
+    // NGON faces by element number, for NFACE cells
+    let mut polygons: HashMap<usize, &[i64]> = HashMap::new();
+    for section in sections.iter().filter(|s| s.element_type == NGON_N) {
+        for (i, (_, nodes)) in section.elements()?.into_iter().enumerate() {
+            polygons.insert(section.range.0 + i, nodes);
+        }
+    }
+
+    let mut indices = Vec::new();
+    let mut triangle_to_cell = Vec::new();
+    let mut cell_count = 0;
+    let mut add_face = |face: Vec<u32>, cell: usize| -> Result<(), VtkError> {
+        if let Some(&corner) = face.iter().find(|&&c| c as usize >= vertex_count) {
+            return Err(VtkError::IndexOutOfBounds {
+                index: corner as usize,
+                max: vertex_count.saturating_sub(1),
+            });
+        }
+        for i in 1..face.len().saturating_sub(1) {
+            indices.extend_from_slice(&[face[0], face[i], face[i + 1]]);
+            triangle_to_cell.push(cell);
+        }
+        Ok(())
+    };
+
+    for section in sections.iter().filter(|s| s.dimension() == dimension) {
+        for (element_type, nodes) in section.elements()? {
+            let cell = cell_count;
+            cell_count += 1;
+            // Node numbers are 1-based
+            let corner = |node: i64| (node as u32).wrapping_sub(1);
+            match element_type {
+                NGON_N => add_face(nodes.iter().map(|&n| corner(n)).collect(), cell)?,
+                NFACE_N => {
+                    // Signed face numbers; negative faces point into the cell
+                    for &face in nodes {
+                        let Some(polygon) = polygons.get(&(face.unsigned_abs() as usize)) else {
+                            return Err(VtkError::MissingData("CGNS NFACE_n face not found"));
+                        };
+                        let mut polygon: Vec<u32> = polygon.iter().map(|&n| corner(n)).collect();
+                        if face < 0 {
+                            polygon.reverse();
+                        }
+                        add_face(polygon, cell)?;
+                    }
+                }
+                _ => {
+                    let Some((element_dimension, faces)) = element_shape(element_type) else {
+                        continue;
+                    };
+                    let size = node_count(element_type).unwrap_or(0);
+                    if element_dimension != dimension || nodes.len() < size {
+                        continue;
+                    }
+                    for face in faces {
+                        add_face(face.iter().map(|&i| corner(nodes[i])).collect(), cell)?;
+                    }
+                }
+            }
+        }
+    }
+
+    let mut attributes = HashMap::new();
+    attributes.insert(
+        ("zone".to_string(), AttributeLocation::Cell),
+        scalar(vec![zone_number as f32; cell_count]),
+    );
+    for solution in children
+        .iter()
+        .filter(|node| node.label == "FlowSolution_t")
+    {
+        let fields = nodes(file, solution.address)?;
+        let location = match fields.iter().find(|node| node.label == "GridLocation_t") {
+            Some(node) => node_data(file, node)?
+                .map(|data| data.to_text())
+                .unwrap_or_default(),
+            None => "Vertex".to_string(),
+        };
+        let (location, expected) = match location.as_str() {
+            "Vertex" => (AttributeLocation::Point, vertex_count),
+            "CellCenter" => (AttributeLocation::Cell, cell_count),
+            other => {
+                println!("Skipping flow solution {} at {}", solution.name, other);
+                continue;
+            }
+        };
+        for field in fields.iter().filter(|node| node.label == "DataArray_t") {
+            match node_values(file, field) {
+                Ok(values) if values.len() == expected => {
+                    attributes.insert(
+                        (field.name.clone(), location.clone()),
+                        scalar(values.into_iter().map(|v| v as f32).collect()),
+                    );
+                }
+                Ok(values) => println!(
+                    "Skipping field {}: {} values, expected {}",
+                    field.name,
+                    values.len(),
+                    expected
+                ),
+                Err(e) => println!("Skipping field {}: {:?}", field.name, e),
+            }
+        }
+    }
+
+    println!(
+        "Parsed CGNS zone {}: {} vertices, {} cells of dimension {}, {} triangles",
+        zone.name,
+        vertex_count,
+        cell_count,
+        dimension,
+        indices.len() / 3
+    );
+    Ok(Some(
+        GeometryData::new(vertices, indices, attributes)
+            .add_triangle_to_cell_mapping(triangle_to_cell),
+    ))
+}
+
+/// Single-component scalar attribute
+fn scalar(data: Vec<f32>) -> AttributeType {
+    AttributeType::Scalar {
+        num_comp: 1,
+        table_name: "default".to_string(),
+        data,
+        lookup_table: None,
+    }
+}
+
+/// Load the unstructured zones of a CGNS file
+///
+/// # Parameters
+/// - `path`: CGNS file path (HDF5 storage)
+///
+/// # Returns
+/// - `Ok(GeometryData)`: Triangulated zones merged into one model
+/// - `Err(VtkError)`: The file cannot be read or has no unstructured zone
+pub fn load_cgns(path: &Path) -> Result<GeometryData, VtkError> {
+    let file = Hdf5File::open(path).map_err(|e| match e {
+        VtkError::InvalidFormat("Not an HDF5 file") => VtkError::LoadError(
+            "Only CGNS files with HDF5 storage are supported (convert ADF files with adf2hdf)"
+                .to_string(),
+        ),
+        e => e,
+    })?;
+
+    let mut zones = Vec::new();
+    for base in nodes(&file, file.root())?
+        .iter()
+        .filter(|node| node.label == "CGNSBase_t")
+    {
+        for zone in nodes(&file, base.address)?
+            .iter()
+            .filter(|node| node.label == "Zone_t")
+        {
+            if let Some(geometry) = read_zone(&file, zone, zones.len() + 1)? {
+                zones.push(geometry);
+            }
+        }
+    }
+    if zones.is_empty() {
+        return Err(VtkError::MissingData("CGNS file has no unstructured zone"));
+    }
+
+    Ok(vtk::merge_pieces(zones))
+}
@@ -0,0 +1,541 @@
+//! CF netCDF import module
+//!
+//! Reads gridded climate and ocean data from netCDF classic files (`.nc`)
+//! following the CF conventions:
+//! - Longitude/latitude coordinates are found by their `units` (`degrees_east`,
+//!   `degrees_north`), `standard_name` or common names; both 1D (regular)
+//!   and 2D (curvilinear) coordinates are supported
+//! - The grid becomes a surface, either a plane (x = longitude, y = latitude, in
+//!   radians) or the unit sphere with the north pole along +Y
+//! - Every variable on the grid becomes a point scalar; `scale_factor` and
+//!   `add_offset` are applied, `_FillValue` and `missing_value` become NaN
+//! - Extra dimensions (e.g. depth or level) use their first index; the time
+//!   dimension gives the time steps of the series
+//!
+//! Global grids get a cyclic column, so the surface closes at the date line.
+use super::netcdf::{AttributeValue, NcType, NetcdfFile, Variable};
+use super::triangulation::triangulate_structured;
+use super::{AttributeLocation, AttributeType, GeometryData, VtkError};
+use bevy::utils::HashMap;
+use std::path::Path;
+
+/// Standard name and common names of longitude coordinates
+const LONGITUDE_NAMES: &[&str] = &["longitude", "lon", "nav_lon"];
+/// Standard name and common names of latitude coordinates
+const LATITUDE_NAMES: &[&str] = &["latitude", "lat", "nav_lat"];
+
+/// Surface the longitude/latitude grid is placed on
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Projection {
+    /// Plane with x = longitude and y = latitude (radians)
+    Plane,
+    /// Unit sphere
+    Sphere,
+}
+
+/// Whether the path has a netCDF extension
+pub fn is_netcdf_file(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("nc" | "nc3" | "cdf")
+    )
+}
+
+/// Text attribute of a variable, empty if missing
+fn text_attribute<'a>(variable: &'a Variable, name: &str) -> &'a str {
+    variable
+        .attribute(name)
+        .and_then(AttributeValue::as_text)
+        .unwrap_or("")
+}
+
+/// Whether a variable is a longitude or latitude coordinate
+///
+/// # Parameters
+/// - `units`: CF units of the coordinate (`degrees_east` or `degrees_north`)
+/// - `names`: Standard name, then other common names of the coordinate
+fn is_coordinate(variable: &Variable, units: &str, names: &[&str]) -> bool {
+    let variable_units = text_attribute(variable, "units")
+        .to_ascii_lowercase()
+        .replace("degree_", "degrees_");
+    let abbreviated = format!("degrees{}", &units[8..9]);
+    variable_units == units
+        || variable_units == abbreviated
+        || text_attribute(variable, "standard_name") == names[0]
+        || names.contains(&variable.name.to_ascii_lowercase().as_str())
+}
+
+/// Longitude/latitude grid of a CF file
+struct Grid {
+    /// Dimension ids of the grid rows (latitude) and columns (longitude)
+    dimensions: [usize; 2],
+    /// Number of rows and columns
+    rows: usize,
+    columns: usize,
+    /// Longitude and latitude of every grid point in degrees, row by row
+    longitudes: Vec<f64>,
+    latitudes: Vec<f64>,
+    /// Whether the first column is repeated after the last (global grids)
+    cyclic: bool,
+}
+
+impl Grid {
+    /// Find the longitude/latitude coordinates of a file
+    fn find(file: &NetcdfFile) -> Result<Self, VtkError> {
+        let find = |units: &str, names: &[&str]| {
+            file.variables
+                .iter()
+                .filter(|variable| variable.dimensions.len() <= 2)
+                .find(|variable| is_coordinate(variable, units, names))
+        };
+        let longitude = find("degrees_east", LONGITUDE_NAMES).ok_or(VtkError::MissingData(
+            "No longitude coordinate in netCDF file",
+        ))?;
+        let latitude = find("degrees_north", LATITUDE_NAMES).ok_or(VtkError::MissingData(
+            "No latitude coordinate in netCDF file",
+        ))?;
+        let lon = file.read(&longitude.name)?;
+        let lat = file.read(&latitude.name)?;
+
+        let grid = match (&longitude.dimensions[..], &latitude.dimensions[..]) {
+            // Regular grid: lat(y), lon(x)
+            (&[x], &[y]) => {
+                let (rows, columns) = (lat.len(), lon.len());
+                let step = if columns > 1 { lon[1] - lon[0] } else { 0.0 };
+                let span = match (lon.first(), lon.last()) {
+                    (Some(first), Some(last)) => last - first + step,
+                    _ => 0.0,
+                };
+                Self {
+                    dimensions: [y, x],
+                    rows,
+                    columns,
+                    longitudes: (0..rows).flat_map(|_| lon.iter().copied()).collect(),
+                    latitudes: lat
+                        .iter()
+                        .flat_map(|&l| std::iter::repeat_n(l, columns))
+                        .collect(),
+                    cyclic: columns > 2 && (span.abs() - 360.0).abs() < 0.5 * step.abs(),
+                }
+            }
+            // Curvilinear grid: lat(y, x), lon(y, x)
+            (&[y, x], &[y2, x2]) if y == y2 && x == x2 => {
+                let shape = file.shape(longitude);
+                Self {
+                    dimensions: [y, x],
+                    rows: shape[0],
+                    columns: shape[1],
+                    longitudes: lon,
+                    latitudes: lat,
+                    cyclic: false,
+                }
+            }
+            _ => {
+                return Err(VtkError::InvalidFormat(
+                    "netCDF longitude and latitude do not span a grid",
+                ))
+            }
+        };
+        if grid.rows < 2 || grid.columns < 2 {
+            return Err(VtkError::MissingData(
+                "netCDF grid has fewer than 2x2 points",
+            ));
+        }
+        Ok(grid)
+    }
+
+    /// Number of surface columns (with the cyclic column)
+    fn surface_columns(&self) -> usize {
+        self.columns + usize::from(self.cyclic)
+    }
+
+    /// Grid values in surface order, with the cyclic column
+    fn surface_values(&self, values: &[f64]) -> Vec<f32> {
+        values
+            .chunks(self.columns)
+            .take(self.rows)
+            .flat_map(|row| {
+                let cyclic = if self.cyclic { Some(row[0]) } else { None };
+                row.iter().copied().chain(cyclic)
+            })
+            .map(|value| value as f32)
+            .collect()
+    }
+
+    /// Surface vertices and triangles
+    fn surface(&self, projection: Projection) -> (Vec<[f32; 3]>, Vec<u32>, Vec<usize>) {
+        let east = self.longitudes[1] - self.longitudes[0];
+        let north = self.latitudes[self.columns] - self.latitudes[0];
+
+        let mut longitudes = self.surface_values(&self.longitudes);
+        if self.cyclic {
+            // The cyclic column lies one turn further along the longitude axis
+            let columns = self.surface_columns();
+            for row in 0..self.rows {
+                longitudes[row * columns + columns - 1] += 360f32.copysign(east as f32);
+            }
+        }
+        let latitudes = self.surface_values(&self.latitudes);
+
+        let vertices = longitudes
+            .iter()
+            .zip(&latitudes)
+            .map(|(&lon, &lat)| {
+                let (lon, lat) = (lon.to_radians(), lat.to_radians());
+                match projection {
+                    Projection::Plane => [lon, lat, 0.0],
+                    Projection::Sphere => {
+                        [lat.cos() * lon.cos(), lat.sin(), -lat.cos() * lon.sin()]
+                    }
+                }
+            })
+            .collect();
+
+        let (mut indices, mapping) = triangulate_structured([self.surface_columns(), self.rows, 1]);
+        // Keep the surface facing +Z (outward on the sphere) for descending axes
+        if (east < 0.0) != (north < 0.0) {
+            for triangle in indices.chunks_exact_mut(3) {
+                triangle.swap(1, 2);
+            }
+        }
+        (vertices, indices, mapping)
+    }
+}
+
+/// Variable on the grid with the layout of its other dimensions
+struct Field<'a> {
+    variable: &'a Variable,
+    /// Position of the time dimension among the leading dimensions
+    time_axis: Option<usize>,
+    /// Lengths of the leading dimensions
+    leading: Vec<usize>,
+}
+
+/// CF netCDF file with its grid
+pub struct ClimateFile {
+    file: NetcdfFile,
+    grid: Grid,
+}
+
+impl ClimateFile {
+    /// Read a CF netCDF file and find its longitude/latitude grid
+    pub fn open(path: &Path) -> Result<Self, VtkError> {
+        let file = NetcdfFile::open(path)?;
+        let grid = Grid::find(&file)?;
+        Ok(Self { file, grid })
+    }
+
+    /// Whether a dimension is the time dimension: the record dimension or one with a
+    /// time coordinate
+    fn is_time_dimension(&self, id: usize) -> bool {
+        let dimension = &self.file.dimensions[id];
+        dimension.length == 0
+            || self.file.variable(&dimension.name).is_some_and(|variable| {
+                text_attribute(variable, "standard_name") == "time"
+                    || text_attribute(variable, "axis") == "T"
+                    || text_attribute(variable, "units").contains(" since ")
+            })
+    }
+
+    /// Variables on the grid, in file order
+    fn fields(&self) -> Vec<Field<'_>> {
+        self.file
+            .variables
+            .iter()
+            .filter(|variable| {
+                let dims = &variable.dimensions;
+                dims.len() >= 2
+                    && dims[dims.len() - 2..] == self.grid.dimensions
+                    && variable.nc_type != NcType::Char
+                    && !is_coordinate(variable, "degrees_east", LONGITUDE_NAMES)
+                    && !is_coordinate(variable, "degrees_north", LATITUDE_NAMES)
+            })
+            .map(|variable| {
+                let shape = self.file.shape(variable);
+                let leading = variable.dimensions.len() - 2;
+                Field {
+                    variable,
+                    time_axis: (0..leading)
+                        .find(|&i| self.is_time_dimension(variable.dimensions[i])),
+                    leading: shape[..leading].to_vec(),
+                }
+            })
+            .collect()
+    }
+
+    /// Number of time steps (1 without time dimension)
+    pub fn time_step_count(&self) -> usize {
+        self.fields()
+            .iter()
+            .filter_map(|field| field.time_axis.map(|axis| field.leading[axis]))
+            .max()
+            .unwrap_or(1)
+    }
+
+    /// Values of the time coordinate, the step index if missing
+    pub fn times(&self) -> Vec<f32> {
+        let count = self.time_step_count();
+        let time = self
+            .fields()
+            .iter()
+            .find_map(|field| field.time_axis.map(|axis| field.variable.dimensions[axis]))
+            .and_then(|id| self.file.read(&self.file.dimensions[id].name).ok())
+            .unwrap_or_default();
+        (0..count)
+            .map(|step| time.get(step).map_or(step as f32, |&t| t as f32))
+            .collect()
+    }
+
+    /// Values of a field at a time step, in surface order
+    fn field_values(&self, field: &Field, step: usize) -> Result<Vec<f32>, VtkError> {
+        let variable = field.variable;
+        let slab = self.grid.rows * self.grid.columns;
+
+        // Index of the slab: the time step along the time axis, 0 along others
+        let mut index = 0;
+        for (axis, &length) in field.leading.iter().enumerate() {
+            let position = if Some(axis) == field.time_axis {
+                step.min(length.saturating_sub(1))
+            } else {
+                0
+            };
+            index = index * length + position;
+        }
+
+        // Record variables are read one record at a time
+        let values = if field.time_axis == Some(0)
+            && self.file.dimensions[variable.dimensions[0]].length == 0
+        {
+            let record_slab = index % (field.leading[1..].iter().product::<usize>());
+            let record = self.file.read_record(
+                &variable.name,
+                step.min(self.file.record_count.saturating_sub(1)),
+            )?;
+            record
+                .get(record_slab * slab..(record_slab + 1) * slab)
+                .map(<[f64]>::to_vec)
+        } else {
+            let all = self.file.read(&variable.name)?;
+            all.get(index * slab..(index + 1) * slab)
+                .map(<[f64]>::to_vec)
+        }
+        .ok_or(VtkError::MissingData("netCDF variable data is incomplete"))?;
+
+        Ok(self.grid.surface_values(&unpack(variable, values)))
+    }
+
+    /// Surface with all grid variables at a time step
+    ///
+    /// # Parameters
+    /// - `step`: Time step index
+    /// - `projection`: Plane or sphere
+    ///
+    /// # Returns
+    /// - `Ok(GeometryData)`: Surface with one point scalar per grid variable
+    /// - `Err(VtkError)`: Invalid variable data
+    pub fn geometry(&self, step: usize, projection: Projection) -> Result<GeometryData, VtkError> {
+        let (vertices, indices, mapping) = self.grid.surface(projection);
+
+        let mut attributes = HashMap::new();
+        for field in self.fields() {
+            match self.field_values(&field, step) {
+                Ok(values) => {
+                    attributes.insert(
+                        (field.variable.name.clone(), AttributeLocation::Point),
+                        AttributeType::Scalar {
+                            num_comp: 1,
+                            table_name: "default".to_string(),
+                            data: values,
+                            lookup_table: None,
+                        },
+                    );
+                }
+                Err(e) => println!("Skipping variable {}: {:?}", field.variable.name, e),
+            }
+        }
+
+        println!(
+            "Parsed CF netCDF file: {}x{} grid{}, {} variables, time step {} of {}",
+            self.grid.columns,
+            self.grid.rows,
+            if self.grid.cyclic { " (global)" } else { "" },
+            attributes.len(),
+            step,
+            self.time_step_count()
+        );
+        Ok(GeometryData::new(vertices, indices, attributes).add_triangle_to_cell_mapping(mapping))
+    }
+}
+
+/// Apply `scale_factor`/`add_offset` and replace fill values with NaN
+fn unpack(variable: &Variable, values: Vec<f64>) -> Vec<f64> {
+    let number = |name: &str| {
+        variable
+            .attribute(name)
+            .and_then(AttributeValue::as_numbers)
+            .and_then(|values| values.first().copied())
+    };
+    let missing: Vec<f64> = ["_FillValue", "missing_value"]
+        .iter()
+        .filter_map(|name| number(name))
+        .collect();
+    let scale = number("scale_factor").unwrap_or(1.0);
+    let offset = number("add_offset").unwrap_or(0.0);
+
+    values
+        .into_iter()
+        .map(|value| {
+            if missing.contains(&value) {
+                f64::NAN
+            } else {
+                value * scale + offset
+            }
+        })
+        .collect()
+}
+
+/// Load a CF netCDF file with the variables of the first time step
+///
+/// # Parameters
+/// - `path`: netCDF file path
+/// - `projection`: Plane or sphere
+pub fn load_climate(path: &Path, projection: Projection) -> Result<GeometryData, VtkError> {
+    ClimateFile::open(path)?.geometry(0, projection)
+}
+
+/// Load the first time-varying variable at every time step
+///
+/// # Parameters
+/// - `path`: netCDF file path
+///
+/// # Returns
+/// - `Ok(Vec<(f32, Vec<f32>)>)`: Time coordinate and point values of every time step
+/// - `Err(VtkError)`: The file cannot be read or has no time-varying variable
+pub fn load_time_series(path: &Path) -> Result<Vec<(f32, Vec<f32>)>, VtkError> {
+    let climate = ClimateFile::open(path)?;
+    let fields = climate.fields();
+    let field = fields
+        .iter()
+        .find(|field| field.time_axis.is_some())
+        .ok_or(VtkError::MissingData(
+            "netCDF file has no time-varying variable",
+        ))?;
+    println!("Time series of variable {}", field.variable.name);
+
+    climate
+        .times()
+        .into_iter()
+        .enumerate()
+        .map(|(step, time)| Ok((time, climate.field_values(field, step)?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Variable of a test file: name, dimension ids, `units` and values
+    type TestVariable<'a> = (&'a str, &'a [u32], &'a str, &'a [f32]);
+
+    fn push_name(bytes: &mut Vec<u8>, name: &str) {
+        bytes.extend((name.len() as u32).to_be_bytes());
+        bytes.extend(name.as_bytes());
+        bytes.resize(bytes.len().next_multiple_of(4), 0);
+    }
+
+    /// netCDF classic file with float variables and no records
+    fn netcdf_file(dimensions: &[(&str, u32)], variables: &[TestVariable]) -> Vec<u8> {
+        let mut bytes = b"CDF\x01\0\0\0\0".to_vec();
+        bytes.extend([0, 0, 0, 0x0A]);
+        bytes.extend((dimensions.len() as u32).to_be_bytes());
+        for (name, length) in dimensions {
+            push_name(&mut bytes, name);
+            bytes.extend(length.to_be_bytes());
+        }
+        bytes.extend([0; 8]);
+
+        bytes.extend([0, 0, 0, 0x0B]);
+        bytes.extend((variables.len() as u32).to_be_bytes());
+        let mut begins = Vec::new();
+        for (name, dimension_ids, units, values) in variables {
+            push_name(&mut bytes, name);
+            bytes.extend((dimension_ids.len() as u32).to_be_bytes());
+            dimension_ids
+                .iter()
+                .for_each(|id| bytes.extend(id.to_be_bytes()));
+            bytes.extend([0, 0, 0, 0x0C, 0, 0, 0, 1]);
+            push_name(&mut bytes, "units");
+            bytes.extend([0, 0, 0, 2]);
+            push_name(&mut bytes, units);
+            bytes.extend([0, 0, 0, 5]);
+            bytes.extend((values.len() as u32 * 4).to_be_bytes());
+            begins.push(bytes.len());
+            bytes.extend([0; 4]);
+        }
+
+        for ((_, _, _, values), begin) in variables.iter().zip(begins) {
+            let offset = bytes.len() as u32;
+            bytes[begin..begin + 4].copy_from_slice(&offset.to_be_bytes());
+            values
+                .iter()
+                .for_each(|value| bytes.extend(value.to_be_bytes()));
+        }
+        bytes
+    }
+
+    /// Load `bytes` as a CF file from a temporary path
+    fn load_bytes(name: &str, bytes: &[u8]) -> Result<GeometryData, VtkError> {
+        let path = std::env::temp_dir().join(format!("{}-{}.nc", name, std::process::id()));
+        std::fs::write(&path, bytes).unwrap();
+        let result = load_climate(&path, Projection::Plane);
+        let _ = std::fs::remove_file(&path);
+        result
+    }
+
+    /// 2x3 regular grid with a `t` variable
+    fn grid_file() -> Vec<u8> {
+        netcdf_file(
+            &[("lat", 2), ("lon", 3)],
+            &[
+                ("lat", &[0], "degrees_north", &[0.0, 10.0]),
+                ("lon", &[1], "degrees_east", &[0.0, 10.0, 20.0]),
+                ("t", &[0, 1], "K", &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]),
+            ],
+        )
+    }
+
+    #[test]
+    fn regular_grid_is_loaded() {
+        let geometry = load_bytes("grid", &grid_file()).unwrap();
+        assert_eq!(geometry.vertices.len(), 6);
+        let Some(AttributeType::Scalar { data, .. }) = geometry
+            .attributes
+            .as_ref()
+            .and_then(|attributes| attributes.get(&("t".to_string(), AttributeLocation::Point)))
+        else {
+            panic!("missing t scalar");
+        };
+        assert_eq!(data, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    }
+
+    #[test]
+    fn truncated_file_is_an_error() {
+        let file = grid_file();
+        assert!(load_bytes("truncated-header", &file[..40]).is_err());
+        // Cut inside the longitudes, before the `t` values
+        assert!(load_bytes("truncated-data", &file[..file.len() - 28]).is_err());
+    }
+
+    #[test]
+    fn empty_longitude_is_an_error() {
+        // A zero length dimension is the record dimension, here without records
+        let file = netcdf_file(
+            &[("lat", 2), ("lon", 0)],
+            &[
+                ("lat", &[0], "degrees_north", &[0.0, 10.0]),
+                ("lon", &[1], "degrees_east", &[]),
+            ],
+        );
+        assert!(load_bytes("empty", &file).is_err());
+    }
+}
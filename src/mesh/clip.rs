@@ -0,0 +1,183 @@
+//! Clip module
+//!
+//! Removal of the geometry on one side of a plane or outside a box:
+//! - Triangles crossing the boundary are split, so the cut edge is exact
+//! - Point attributes, normals and texture coordinates are interpolated at the
+//!   new points; cell attributes stay valid since triangles keep their cells
+//! - Volume cells are kept if all their corners are kept
+//!
+//! Only the vertices used by the kept triangles and volume cells remain.
+use super::isosurface::{interpolate_attribute, EdgePoint};
+use super::topology::triangle_cell;
+use super::{AttributeLocation, GeometryData};
+use bevy::math::{Vec2, Vec3};
+use bevy::utils::HashMap;
+
+/// Clip a geometry with a plane, removing the geometry on the normal side
+///
+/// # Parameters
+/// - `geometry`: Source geometry
+/// - `origin`: Point on the plane (model space)
+/// - `normal`: Plane normal, pointing to the removed side
+///
+/// # Returns
+/// - Clipped geometry
+pub fn clip_by_plane(geometry: &GeometryData, origin: Vec3, normal: Vec3) -> GeometryData {
+    let normal = normal.normalize_or_zero();
+    let distances: Vec<f32> = geometry
+        .vertices
+        .iter()
+        .map(|&vertex| (Vec3::from(vertex) - origin).dot(normal))
+        .collect();
+    clip_by_distances(geometry, &distances)
+}
+
+/// Clip a geometry with an axis-aligned box, removing the geometry outside
+///
+/// # Parameters
+/// - `geometry`: Source geometry
+/// - `min`: Minimum corner of the box (model space)
+/// - `max`: Maximum corner of the box (model space)
+///
+/// # Returns
+/// - Clipped geometry
+pub fn clip_by_box(geometry: &GeometryData, min: Vec3, max: Vec3) -> GeometryData {
+    let faces = [
+        (min, Vec3::NEG_X),
+        (max, Vec3::X),
+        (min, Vec3::NEG_Y),
+        (max, Vec3::Y),
+        (min, Vec3::NEG_Z),
+        (max, Vec3::Z),
+    ];
+    faces
+        .iter()
+        .fold(geometry.clone(), |clipped, &(origin, normal)| {
+            clip_by_plane(&clipped, origin, normal)
+        })
+}
+
+/// Clip a geometry by signed point values, keeping values of at most zero
+fn clip_by_distances(geometry: &GeometryData, distances: &[f32]) -> GeometryData {
+    let kept = |vertex: u32| {
+        distances
+            .get(vertex as usize)
+            .is_some_and(|distance| *distance <= 0.0)
+    };
+
+    // Output points: kept source vertices and points on cut edges
+    let mut points: Vec<EdgePoint> = Vec::new();
+    let mut point_ids: HashMap<(u32, u32), u32> = HashMap::new();
+    let mut point_id = |from: u32, to: u32, points: &mut Vec<EdgePoint>| -> u32 {
+        let (from, to) = if from <= to { (from, to) } else { (to, from) };
+        *point_ids.entry((from, to)).or_insert_with(|| {
+            let t = if from == to {
+                0.0
+            } else {
+                let (start, end) = (distances[from as usize], distances[to as usize]);
+                (start / (start - end)).clamp(0.0, 1.0)
+            };
+            points.push(EdgePoint { from, to, t });
+            points.len() as u32 - 1
+        })
+    };
+
+    let mut indices = Vec::new();
+    let mut triangle_cells = Vec::new();
+    for (triangle, corners) in geometry.indices.chunks_exact(3).enumerate() {
+        if corners
+            .iter()
+            .any(|&corner| corner as usize >= distances.len())
+        {
+            continue;
+        }
+        // Clip the triangle polygon edge by edge, keeping the winding
+        let mut polygon = Vec::with_capacity(4);
+        for i in 0..3 {
+            let (current, next) = (corners[i], corners[(i + 1) % 3]);
+            if kept(current) {
+                polygon.push(point_id(current, current, &mut points));
+            }
+            if kept(current) != kept(next) {
+                polygon.push(point_id(current, next, &mut points));
+            }
+        }
+        for i in 1..polygon.len().saturating_sub(1) {
+            indices.extend_from_slice(&[polygon[0], polygon[i], polygon[i + 1]]);
+            triangle_cells.push(triangle_cell(geometry, triangle));
+        }
+    }
+
+    // Volume cells entirely on the kept side
+    let volume_cells = geometry.volume_cells.as_ref().and_then(|cells| {
+        let mut kept_cells = cells.clone();
+        kept_cells.remap_points(|point| kept(point).then_some(point));
+        for point in kept_cells.connectivity.iter_mut() {
+            *point = point_id(*point, *point, &mut points);
+        }
+        (!kept_cells.is_empty()).then_some(kept_cells)
+    });
+
+    let lerp_vertices = |vertices: &[[f32; 3]]| -> Vec<[f32; 3]> {
+        points
+            .iter()
+            .map(|point| {
+                Vec3::from(vertices[point.from as usize])
+                    .lerp(Vec3::from(vertices[point.to as usize]), point.t)
+                    .to_array()
+            })
+            .collect()
+    };
+    let vertices = lerp_vertices(&geometry.vertices);
+    let normals = geometry
+        .normals
+        .as_ref()
+        .filter(|normals| normals.len() == geometry.vertices.len())
+        .map(|normals| {
+            lerp_vertices(normals)
+                .into_iter()
+                .map(|normal| Vec3::from(normal).normalize_or_zero().to_array())
+                .collect()
+        });
+    let uvs = geometry
+        .uvs
+        .as_ref()
+        .filter(|uvs| uvs.len() == geometry.vertices.len())
+        .map(|uvs| {
+            points
+                .iter()
+                .map(|point| {
+                    Vec2::from(uvs[point.from as usize])
+                        .lerp(Vec2::from(uvs[point.to as usize]), point.t)
+                        .to_array()
+                })
+                .collect()
+        });
+
+    let attributes = geometry
+        .attributes
+        .as_ref()
+        .map(|attributes| {
+            attributes
+                .iter()
+                .filter_map(|((name, location), attribute)| {
+                    let attribute = match location {
+                        AttributeLocation::Point if !name.starts_with("__lut_") => {
+                            interpolate_attribute(attribute, &points)?
+                        }
+                        _ => attribute.clone(),
+                    };
+                    Some(((name.clone(), location.clone()), attribute))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut clipped = GeometryData::new(vertices, indices, attributes)
+        .add_triangle_to_cell_mapping(triangle_cells)
+        .add_volume_cells(volume_cells);
+    clipped.lookup_tables = geometry.lookup_tables.clone();
+    clipped.normals = normals;
+    clipped.uvs = uvs;
+    clipped
+}
@@ -10,12 +10,19 @@
 //! - `hot`: Heatmap color map
 //! - `cool`: Cool color map
 //! - `warm`: Warm color map
+//!
+//! Further maps, such as ParaView presets, can be registered at runtime and are
+//! then found by name like the built-in ones.
+use crate::mesh::normals;
+use crate::mesh::topology;
 use crate::mesh::vtk::{AttributeLocation, AttributeType};
 use bevy::prelude::*;
 use bevy::render::mesh::VertexAttributeValues;
+use bevy::utils::HashMap;
+use std::sync::RwLock;
+
 #[derive(Debug, Clone)]
 pub struct ColorMap {
-    #[allow(dead_code)] // For debugging
     pub name: String,
     pub colors: Vec<[f32; 4]>,
 }
@@ -219,6 +226,42 @@ pub fn get_warm_color_map() -> ColorMap {
     }
 }
 
+/// Names of the built-in color maps
+pub const BUILTIN_COLOR_MAPS: [&str; 5] = ["default", "viridis", "hot", "cool", "warm"];
+
+/// Color maps registered at runtime
+static CUSTOM_COLOR_MAPS: RwLock<Vec<ColorMap>> = RwLock::new(Vec::new());
+
+/// Register a color map, replacing a registered map with the same name
+///
+/// Built-in names cannot be replaced; such maps are registered with an
+/// ` (imported)` suffix.
+///
+/// # Returns
+/// - Name the map is found by
+pub fn register_color_map(mut map: ColorMap) -> String {
+    if BUILTIN_COLOR_MAPS.contains(&map.name.as_str()) {
+        map.name = format!("{} (imported)", map.name);
+    }
+    let name = map.name.clone();
+    let mut maps = CUSTOM_COLOR_MAPS.write().unwrap_or_else(|e| e.into_inner());
+    match maps.iter_mut().find(|existing| existing.name == name) {
+        Some(existing) => *existing = map,
+        None => maps.push(map),
+    }
+    name
+}
+
+/// Names of the built-in and registered color maps
+pub fn color_map_names() -> Vec<String> {
+    let maps = CUSTOM_COLOR_MAPS.read().unwrap_or_else(|e| e.into_inner());
+    BUILTIN_COLOR_MAPS
+        .iter()
+        .map(|name| name.to_string())
+        .chain(maps.iter().map(|map| map.name.clone()))
+        .collect()
+}
+
 /// Get color map by name
 pub fn get_color_map(name: &str) -> ColorMap {
     match name {
@@ -226,7 +269,14 @@ pub fn get_color_map(name: &str) -> ColorMap {
         "hot" => get_hot_color_map(),
         "cool" => get_cool_color_map(),
         "warm" => get_warm_color_map(),
-        _ => get_default_color_map(),
+        "default" => get_default_color_map(),
+        _ => CUSTOM_COLOR_MAPS
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .find(|map| map.name == name)
+            .cloned()
+            .unwrap_or_else(get_default_color_map),
     }
 }
 
@@ -241,8 +291,40 @@ pub struct ColorMappingConfig {
     pub min_value: f32,
     pub max_value: f32,
     pub use_custom_range: bool,
+    /// Attribute to color by, `None` picks the first scalar found
+    pub attribute: Option<(String, AttributeLocation)>,
+    /// Map the logarithm of the values, see `ValueScale`
+    pub log_scale: bool,
+    /// Continuous, binned or categorical mapping
+    pub mode: ColorMode,
+    /// Number of bins of `ColorMode::Binned`
+    pub bins: usize,
+    /// Color of NaN and infinite values
+    pub nan_color: [f32; 4],
+    /// Color of values below the range, `None` uses the lowest color
+    pub below_color: Option<[f32; 4]>,
+    /// Color of values above the range, `None` uses the highest color
+    pub above_color: Option<[f32; 4]>,
+}
+
+/// Default color of NaN and infinite values
+pub const DEFAULT_NAN_COLOR: [f32; 4] = [0.5, 0.5, 0.5, 1.0];
+
+/// How values are mapped to colors
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Colors interpolated over the value range
+    #[default]
+    Continuous,
+    /// The value range split into equal bins with one color each
+    Binned,
+    /// One distinct color per integer value, for IDs and labels
+    Categorical,
 }
 
+/// Most categories of `ColorMode::Categorical`, further values take the NaN color
+pub const MAX_CATEGORIES: usize = 256;
+
 impl Default for ColorMappingConfig {
     fn default() -> Self {
         Self {
@@ -250,6 +332,150 @@ impl Default for ColorMappingConfig {
             min_value: 0.0,
             max_value: 1.0,
             use_custom_range: false,
+            attribute: None,
+            log_scale: false,
+            mode: ColorMode::Continuous,
+            bins: 8,
+            nan_color: DEFAULT_NAN_COLOR,
+            below_color: None,
+            above_color: None,
+        }
+    }
+}
+
+impl ColorMappingConfig {
+    /// Scale of the mapped values: the custom range, or the range of the
+    /// finite values
+    pub fn value_scale(&self, values: &[f32]) -> ValueScale {
+        let (min, max) = if self.use_custom_range {
+            (self.min_value, self.max_value)
+        } else {
+            values
+                .iter()
+                .filter(|value| value.is_finite())
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &value| {
+                    (min.min(value), max.max(value))
+                })
+        };
+        let scale = ValueScale::new(min, max, self.log_scale);
+        match self.mode {
+            ColorMode::Binned => scale.with_bins(self.bins),
+            _ => scale,
+        }
+    }
+}
+
+/// Lower bound of a logarithmic range whose minimum is not positive, relative
+/// to the maximum (six decades)
+pub const LOG_RANGE_FLOOR: f32 = 1e-6;
+
+/// Placement of values along the color map, linear or logarithmic
+///
+/// Logarithmic scales need a positive range: a minimum that is not positive is
+/// raised to `LOG_RANGE_FLOOR` times the maximum, and values at or below zero
+/// take the lowest color. Without a positive maximum the scale stays linear.
+#[derive(Clone, Copy, Debug)]
+pub struct ValueScale {
+    /// Lower bound, as logarithm for logarithmic scales
+    low: f32,
+    /// Upper bound, as logarithm for logarithmic scales
+    high: f32,
+    log: bool,
+    /// Number of equal bins, 0 for a continuous scale
+    bins: usize,
+}
+
+impl ValueScale {
+    /// Scale over a value range
+    ///
+    /// # Parameters
+    /// - `min`, `max`: Value range, in any order
+    /// - `log_scale`: Place values by their logarithm
+    pub fn new(min: f32, max: f32, log_scale: bool) -> Self {
+        let (min, max) = (min.min(max), min.max(max));
+        if log_scale && max > 0.0 {
+            let min = if min > 0.0 {
+                min
+            } else {
+                max * LOG_RANGE_FLOOR
+            };
+            Self {
+                low: min.log10(),
+                high: max.log10(),
+                log: true,
+                bins: 0,
+            }
+        } else {
+            Self {
+                low: min,
+                high: max,
+                log: false,
+                bins: 0,
+            }
+        }
+    }
+
+    /// The same scale split into equal bins, colored by the center of each bin
+    pub fn with_bins(self, bins: usize) -> Self {
+        Self { bins, ..self }
+    }
+
+    /// Number of bins, 0 for a continuous scale
+    pub fn bins(&self) -> usize {
+        self.bins
+    }
+
+    /// Move a position on the color map to the center of its bin
+    pub fn quantize(&self, fraction: f32) -> f32 {
+        if self.bins == 0 {
+            return fraction;
+        }
+        let bin = ((fraction * self.bins as f32) as usize).min(self.bins - 1);
+        (bin as f32 + 0.5) / self.bins as f32
+    }
+
+    /// Position of a value on the color map, 0.5 for an empty range
+    pub fn normalize(&self, value: f32) -> f32 {
+        let value = if self.log {
+            if value <= 0.0 {
+                return 0.0;
+            }
+            value.log10()
+        } else {
+            value
+        };
+        let range = self.high - self.low;
+        if range < 1e-10 {
+            0.5
+        } else {
+            self.quantize(((value - self.low) / range).clamp(0.0, 1.0))
+        }
+    }
+
+    /// Side of the range a value lies on: `Less` below the range (zero and
+    /// negative values of logarithmic scales included), `Greater` above it
+    pub fn compare(&self, value: f32) -> std::cmp::Ordering {
+        let value = match self.log {
+            true if value <= 0.0 => return std::cmp::Ordering::Less,
+            true => value.log10(),
+            false => value,
+        };
+        if value < self.low {
+            std::cmp::Ordering::Less
+        } else if value > self.high {
+            std::cmp::Ordering::Greater
+        } else {
+            std::cmp::Ordering::Equal
+        }
+    }
+
+    /// Value at a position on the color map, the inverse of `normalize`
+    pub fn value_at(&self, fraction: f32) -> f32 {
+        let value = self.low + (self.high - self.low) * fraction;
+        if self.log {
+            10f32.powf(value)
+        } else {
+            value
         }
     }
 }
@@ -280,10 +506,7 @@ impl ColorMapper {
             if let Some((nvalues, data)) = color_scalar {
                 let colors = Self::process_point_color_scalars(geometry, *nvalues, data)?;
                 if !colors.is_empty() {
-                    mesh.insert_attribute(
-                        Mesh::ATTRIBUTE_COLOR,
-                        VertexAttributeValues::from(colors),
-                    );
+                    Self::insert_colors(mesh, colors);
                     println!("Point color scalars inserted into mesh.");
                     return Ok(());
                 }
@@ -316,10 +539,7 @@ impl ColorMapper {
             if let Some((nvalues, data)) = color_scalar {
                 let vertex_colors = Self::process_cell_color_scalars(geometry, *nvalues, data);
                 if !vertex_colors.is_empty() {
-                    mesh.insert_attribute(
-                        Mesh::ATTRIBUTE_COLOR,
-                        VertexAttributeValues::from(vertex_colors),
-                    );
+                    Self::insert_colors(mesh, vertex_colors);
                     println!("Cell color scalars inserted into mesh.");
                 }
             }
@@ -333,29 +553,25 @@ impl ColorMapper {
         mesh: &mut Mesh,
         config: &ColorMappingConfig,
     ) -> Result<(), crate::mesh::VtkError> {
-        if let Some(attributes) = &geometry.attributes {
-            // Try point scalars first
-            if Self::apply_point_scalars_with_color_map(geometry, mesh, attributes, config)? {
-                return Ok(());
-            }
-
-            // Then try cell scalars
-            if Self::apply_cell_scalars_with_color_map(geometry, mesh, attributes, config)? {
-                return Ok(());
-            }
-
-            // Finally try color scalars
-            if Self::apply_color_scalars(geometry, mesh, attributes)? {
-                return Ok(());
-            }
+        // The selected attribute wins while the model still has it, otherwise
+        // point scalars, cell scalars and color scalars are tried in turn
+        let Some((name, location)) = Self::colored_attribute(geometry, config.attribute.as_ref())
+        else {
+            return Ok(());
+        };
+        if let Some(attr) = geometry.get_attributes(&name, location.clone()) {
+            println!(
+                "Applying color mapping to {:?} attribute: {}",
+                location, name
+            );
+            Self::apply_selected_attribute(geometry, mesh, attr, &location, config);
         }
-
         Ok(())
     }
 
     /// Apply scalar values to mesh vertex colors (for animation)
     pub fn apply_scalars_to_mesh(mesh: &mut Mesh, scalars: &[f32], config: &ColorMappingConfig) {
-        let vertex_count = mesh.count_vertices();
+        let vertex_count = normals::geometry_vertex_count(mesh);
 
         if scalars.len() != vertex_count {
             println!(
@@ -366,23 +582,214 @@ impl ColorMapper {
             return;
         }
 
-        let (min_val, max_val) = if config.use_custom_range {
-            (config.min_value, config.max_value)
-        } else {
+        let color_map = get_color_map(&config.color_map_name);
+        let colors = if config.mode == ColorMode::Categorical {
+            let categories = Self::value_categories(scalars);
             scalars
                 .iter()
-                .fold((f32::MAX, f32::MIN), |(min, max), &val| {
-                    (min.min(val), max.max(val))
+                .map(|&value| match Self::category_index(&categories, value) {
+                    Some(index) => Self::category_color(&color_map, index, categories.len()),
+                    None => config.nan_color,
                 })
+                .collect()
+        } else {
+            Self::map_scalars_to_colors(scalars, &config.value_scale(scalars), &color_map, config)
         };
 
-        let color_map = get_color_map(&config.color_map_name);
-        let colors = Self::map_scalars_to_colors(scalars, min_val, max_val, &color_map);
+        Self::insert_colors(mesh, colors);
+    }
+
+    /// Insert per-vertex colors of the geometry, expanded to split mesh vertices
+    pub fn insert_colors(mesh: &mut Mesh, colors: Vec<[f32; 4]>) {
+        let colors = normals::expand_to_mesh(mesh, colors, [1.0, 1.0, 1.0, 1.0]);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, VertexAttributeValues::from(colors));
+    }
+
+    /// Attribute colored without a selection, in the order tried by
+    /// `apply_scalar_attributes_with_color_map`
+    pub fn automatic_attribute(
+        geometry: &crate::mesh::GeometryData,
+    ) -> Option<(String, AttributeLocation)> {
+        let attributes = geometry.attributes.as_ref()?;
+        let scalar_at = |wanted: AttributeLocation| {
+            attributes
+                .iter()
+                .find_map(|((name, location), attr)| match attr {
+                    AttributeType::Scalar { num_comp: 1, .. } if *location == wanted => {
+                        Some((name.clone(), wanted.clone()))
+                    }
+                    _ => None,
+                })
+        };
+        scalar_at(AttributeLocation::Point)
+            .or_else(|| scalar_at(AttributeLocation::Cell))
+            .or_else(|| {
+                attributes
+                    .iter()
+                    .find(|(_, attr)| matches!(attr, AttributeType::ColorScalar { .. }))
+                    .map(|(key, _)| key.clone())
+            })
+    }
+
+    /// Attribute the geometry is colored by: the selected one while the
+    /// geometry has it, the automatic one otherwise
+    pub fn colored_attribute(
+        geometry: &crate::mesh::GeometryData,
+        selected: Option<&(String, AttributeLocation)>,
+    ) -> Option<(String, AttributeLocation)> {
+        selected
+            .filter(|(name, location)| geometry.get_attributes(name, location.clone()).is_some())
+            .cloned()
+            .or_else(|| Self::automatic_attribute(geometry))
+    }
+
+    /// Value per point/cell of an attribute to map to colors: the value of
+    /// single component attributes, the magnitude of all others
+    pub fn attribute_magnitudes(attribute: &AttributeType) -> Vec<f32> {
+        let components = topology::attribute_components(attribute);
+        (0..topology::element_count(attribute))
+            .map(|element| {
+                if components == 1 {
+                    return topology::attribute_value(attribute, element, None);
+                }
+                (0..components)
+                    .map(|component| topology::attribute_value(attribute, element, Some(component)))
+                    .map(|value| value * value)
+                    .sum::<f32>()
+                    .sqrt()
+            })
+            .collect()
+    }
 
-        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    /// Categories of an attribute: its distinct values rounded to integers, in
+    /// increasing order, at most `MAX_CATEGORIES`
+    pub fn attribute_categories(attribute: &AttributeType) -> Vec<i64> {
+        Self::value_categories(&Self::attribute_magnitudes(attribute))
+    }
+
+    /// Distinct values rounded to integers, in increasing order, at most
+    /// `MAX_CATEGORIES`
+    pub fn value_categories(values: &[f32]) -> Vec<i64> {
+        let mut categories: Vec<i64> = values
+            .iter()
+            .filter(|value| value.is_finite())
+            .map(|value| value.round() as i64)
+            .collect();
+        categories.sort_unstable();
+        categories.dedup();
+        categories.truncate(MAX_CATEGORIES);
+        categories
+    }
+
+    /// Index of the category of a value, `None` for values outside the categories
+    fn category_index(categories: &[i64], value: f32) -> Option<usize> {
+        value
+            .is_finite()
+            .then(|| categories.binary_search(&(value.round() as i64)).ok())
+            .flatten()
+    }
+
+    /// Color of the category with an index among a number of categories,
+    /// spread evenly over the color map
+    pub fn category_color(color_map: &ColorMap, index: usize, count: usize) -> [f32; 4] {
+        if count <= 1 {
+            return color_map.get_interpolated_color(0.5);
+        }
+        color_map.get_interpolated_color(index as f32 / (count - 1) as f32)
     }
 
     // Private helper methods
+    /// Color by one attribute of any type; colors are used as stored, cell
+    /// values are averaged to the points
+    fn apply_selected_attribute(
+        geometry: &crate::mesh::GeometryData,
+        mesh: &mut Mesh,
+        attribute: &AttributeType,
+        location: &AttributeLocation,
+        config: &ColorMappingConfig,
+    ) {
+        let point_count = geometry.vertices.len();
+        let color_map = get_color_map(&config.color_map_name);
+        let mut colors = match (attribute, location) {
+            (AttributeType::ColorScalar { nvalues, data }, AttributeLocation::Point) => {
+                Self::process_point_color_scalars(geometry, *nvalues, data).unwrap_or_default()
+            }
+            (AttributeType::ColorScalar { nvalues, data }, AttributeLocation::Cell) => {
+                Self::process_cell_color_scalars(geometry, *nvalues, data)
+            }
+            _ if config.mode == ColorMode::Categorical => Self::categorical_colors(
+                geometry,
+                attribute,
+                location,
+                &color_map,
+                config.nan_color,
+            ),
+            _ => {
+                // The range follows the stored values, not their point averages
+                let element_values = Self::attribute_magnitudes(attribute);
+                let scale = config.value_scale(&element_values);
+                let values = match location {
+                    AttributeLocation::Point => element_values,
+                    AttributeLocation::Cell => Self::attribute_magnitudes(
+                        &crate::mesh::conversion::cell_to_point(geometry, attribute),
+                    ),
+                };
+                Self::map_scalars_to_colors(&values, &scale, &color_map, config)
+            }
+        };
+        colors.resize(point_count, [1.0, 1.0, 1.0, 1.0]);
+        Self::insert_colors(mesh, colors);
+    }
+
+    /// One distinct color per category; points of cell categories take the
+    /// category most of their cells have, averaging would mix categories.
+    /// Values without a category, NaN included, take the NaN color
+    fn categorical_colors(
+        geometry: &crate::mesh::GeometryData,
+        attribute: &AttributeType,
+        location: &AttributeLocation,
+        color_map: &ColorMap,
+        nan_color: [f32; 4],
+    ) -> Vec<[f32; 4]> {
+        let categories = Self::attribute_categories(attribute);
+        let element_indices: Vec<Option<usize>> = Self::attribute_magnitudes(attribute)
+            .into_iter()
+            .map(|value| Self::category_index(&categories, value))
+            .collect();
+        let point_indices = match location {
+            AttributeLocation::Point => element_indices,
+            AttributeLocation::Cell => {
+                let mut votes: Vec<HashMap<usize, u32>> =
+                    vec![HashMap::new(); geometry.vertices.len()];
+                let cells = crate::mesh::conversion::cell_points(geometry, element_indices.len());
+                for (points, index) in cells.iter().zip(&element_indices) {
+                    let Some(index) = index else { continue };
+                    for &point in points {
+                        if let Some(counts) = votes.get_mut(point as usize) {
+                            *counts.entry(*index).or_insert(0) += 1;
+                        }
+                    }
+                }
+                votes
+                    .into_iter()
+                    .map(|counts| {
+                        counts
+                            .into_iter()
+                            .max_by_key(|&(index, count)| (count, std::cmp::Reverse(index)))
+                            .map(|(index, _)| index)
+                    })
+                    .collect()
+            }
+        };
+        point_indices
+            .into_iter()
+            .map(|index| match index {
+                Some(index) => Self::category_color(color_map, index, categories.len()),
+                None => nan_color,
+            })
+            .collect()
+    }
+
     fn process_point_color_scalars(
         geometry: &crate::mesh::GeometryData,
         nvalues: u32,
@@ -487,185 +894,26 @@ impl ColorMapper {
         vertex_colors
     }
 
-    fn apply_point_scalars_with_color_map(
-        _geometry: &crate::mesh::GeometryData,
-        mesh: &mut Mesh,
-        attributes: &bevy::utils::HashMap<
-            (String, crate::mesh::vtk::AttributeLocation),
-            crate::mesh::vtk::AttributeType,
-        >,
-        config: &ColorMappingConfig,
-    ) -> Result<bool, crate::mesh::VtkError> {
-        for ((name, location), attr) in attributes.iter() {
-            if let AttributeType::Scalar { num_comp, data, .. } = attr {
-                if location == &AttributeLocation::Point && *num_comp == 1 {
-                    println!("Applying color mapping to point scalar attribute: {}", name);
-
-                    let mesh_vertex_count = mesh.count_vertices();
-                    let mut vertex_colors = vec![[1.0, 1.0, 1.0, 1.0]; mesh_vertex_count];
-
-                    let (min_val, max_val) = if config.use_custom_range {
-                        (config.min_value, config.max_value)
-                    } else {
-                        let min_val = data.iter().fold(f32::INFINITY, |a, &b| a.min(b));
-                        let max_val = data.iter().fold(f32::NEG_INFINITY, |a, &b| a.max(b));
-                        (min_val, max_val)
-                    };
-
-                    let range = max_val - min_val;
-                    let color_map = get_color_map(&config.color_map_name);
-
-                    for (i, &val) in data.iter().enumerate() {
-                        if i < vertex_colors.len() {
-                            let color = if range < 1e-10 {
-                                color_map.get_interpolated_color(0.5)
-                            } else {
-                                let normalized = (val - min_val) / range;
-                                color_map.get_interpolated_color(normalized)
-                            };
-                            vertex_colors[i] = color;
-                        }
-                    }
-
-                    mesh.insert_attribute(
-                        Mesh::ATTRIBUTE_COLOR,
-                        VertexAttributeValues::from(vertex_colors),
-                    );
-                    println!("Point scalar colors applied to mesh");
-                    return Ok(true);
-                }
-            }
-        }
-        Ok(false)
-    }
-
-    fn apply_cell_scalars_with_color_map(
-        geometry: &crate::mesh::GeometryData,
-        mesh: &mut Mesh,
-        attributes: &bevy::utils::HashMap<
-            (String, crate::mesh::vtk::AttributeLocation),
-            crate::mesh::vtk::AttributeType,
-        >,
-        config: &ColorMappingConfig,
-    ) -> Result<bool, crate::mesh::VtkError> {
-        for ((name, location), attr) in attributes.iter() {
-            if let AttributeType::Scalar { num_comp, data, .. } = attr {
-                if location == &AttributeLocation::Cell && *num_comp == 1 {
-                    println!("Applying color mapping to cell scalar attribute: {}", name);
-
-                    let mesh_vertex_count = mesh.count_vertices();
-                    let mut vertex_colors = vec![[1.0, 1.0, 1.0, 1.0]; mesh_vertex_count];
-
-                    let (min_val, max_val) = if config.use_custom_range {
-                        (config.min_value, config.max_value)
-                    } else {
-                        let min_val = data.iter().fold(f32::INFINITY, |a, &b| a.min(b));
-                        let max_val = data.iter().fold(f32::NEG_INFINITY, |a, &b| a.max(b));
-                        (min_val, max_val)
-                    };
-
-                    let range = max_val - min_val;
-                    let color_map = get_color_map(&config.color_map_name);
-
-                    if let Some(mapping) = &geometry.triangle_to_cell_mapping {
-                        for (triangle_idx, &cell_idx) in mapping.iter().enumerate() {
-                            if cell_idx >= data.len() {
-                                continue;
-                            }
-
-                            let val = data[cell_idx];
-                            let color = if range < 1e-10 {
-                                color_map.get_interpolated_color(0.5)
-                            } else {
-                                let normalized = (val - min_val) / range;
-                                color_map.get_interpolated_color(normalized)
-                            };
-
-                            let triangle_base = triangle_idx * 3;
-                            if triangle_base + 2 < geometry.indices.len() {
-                                let vertex_indices = [
-                                    geometry.indices[triangle_base] as usize,
-                                    geometry.indices[triangle_base + 1] as usize,
-                                    geometry.indices[triangle_base + 2] as usize,
-                                ];
-
-                                for &idx in &vertex_indices {
-                                    if idx < vertex_colors.len() {
-                                        vertex_colors[idx] = color;
-                                    }
-                                }
-                            }
-                        }
-                    }
-
-                    mesh.insert_attribute(
-                        Mesh::ATTRIBUTE_COLOR,
-                        VertexAttributeValues::from(vertex_colors),
-                    );
-                    println!("Cell scalar colors applied to mesh");
-                    return Ok(true);
-                }
-            }
-        }
-        Ok(false)
-    }
-
-    fn apply_color_scalars(
-        geometry: &crate::mesh::GeometryData,
-        mesh: &mut Mesh,
-        attributes: &bevy::utils::HashMap<
-            (String, crate::mesh::vtk::AttributeLocation),
-            crate::mesh::vtk::AttributeType,
-        >,
-    ) -> Result<bool, crate::mesh::VtkError> {
-        for ((_, location), attr) in attributes.iter() {
-            if let AttributeType::ColorScalar { nvalues, data } = attr {
-                match location {
-                    AttributeLocation::Point => {
-                        let colors = Self::process_point_color_scalars(geometry, *nvalues, data)?;
-                        if !colors.is_empty() {
-                            mesh.insert_attribute(
-                                Mesh::ATTRIBUTE_COLOR,
-                                VertexAttributeValues::from(colors),
-                            );
-                            println!("Point color scalars applied to mesh");
-                            return Ok(true);
-                        }
-                    }
-                    AttributeLocation::Cell => {
-                        let colors = Self::process_cell_color_scalars(geometry, *nvalues, data);
-                        if !colors.is_empty() {
-                            mesh.insert_attribute(
-                                Mesh::ATTRIBUTE_COLOR,
-                                VertexAttributeValues::from(colors),
-                            );
-                            println!("Cell color scalars applied to mesh");
-                            return Ok(true);
-                        }
-                    }
-                }
-            }
-        }
-        Ok(false)
-    }
-
+    /// Colors of values on a scale; NaN, infinite and out-of-range values take
+    /// the special colors of the configuration
     fn map_scalars_to_colors(
         scalars: &[f32],
-        min_val: f32,
-        max_val: f32,
+        scale: &ValueScale,
         color_map: &ColorMap,
+        config: &ColorMappingConfig,
     ) -> Vec<[f32; 4]> {
-        let range = max_val - min_val;
-
         scalars
             .iter()
             .map(|&scalar| {
-                let normalized = if range > 0.0 {
-                    ((scalar - min_val) / range).clamp(0.0, 1.0)
-                } else {
-                    0.5
+                if !scalar.is_finite() {
+                    return config.nan_color;
+                }
+                let special = match scale.compare(scalar) {
+                    std::cmp::Ordering::Less => config.below_color,
+                    std::cmp::Ordering::Greater => config.above_color,
+                    std::cmp::Ordering::Equal => None,
                 };
-                color_map.get_interpolated_color(normalized)
+                special.unwrap_or_else(|| color_map.get_interpolated_color(scale.normalize(scalar)))
             })
             .collect()
     }
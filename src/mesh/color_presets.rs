@@ -0,0 +1,359 @@
+//! Color map presets module
+//!
+//! Reads ParaView color map preset files into color maps:
+//! - JSON presets, as exported by ParaView's "Choose Preset" dialog: a list of
+//!   objects with `Name` and `RGBPoints` (`x, r, g, b` per control point), or
+//!   `IndexedColors` (`r, g, b` per color) for categorical presets
+//! - XML presets: `<ColorMap name="...">` elements with one `<Point x r g b/>`
+//!   per control point
+//!
+//! Control points may lie anywhere on the data axis; they are resampled to
+//! evenly spaced colors, interpolated in RGB. Opacity points are ignored.
+use super::color_maps::ColorMap;
+use super::compression::attribute;
+use super::VtkError;
+use std::path::Path;
+
+/// Number of evenly spaced colors a preset is resampled to
+const PRESET_COLORS: usize = 256;
+
+/// Read the color maps of a ParaView preset file
+///
+/// # Parameters
+/// - `path`: `.json` or `.xml` preset file
+///
+/// # Returns
+/// - `Ok(Vec<ColorMap>)`: Color maps of the file, in file order
+/// - `Err(VtkError)`: The file cannot be read or holds no usable preset
+pub fn load_color_presets(path: &Path) -> Result<Vec<ColorMap>, VtkError> {
+    let text = std::fs::read_to_string(path).map_err(VtkError::IoError)?;
+    let is_xml = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("xml"));
+    let maps = if is_xml {
+        parse_xml_presets(&text)?
+    } else {
+        parse_json_presets(&text)?
+    };
+    if maps.is_empty() {
+        return Err(VtkError::MissingData("color map presets"));
+    }
+    Ok(maps)
+}
+
+/// Parse ParaView JSON presets, a single preset object or a list of them
+pub fn parse_json_presets(text: &str) -> Result<Vec<ColorMap>, VtkError> {
+    let mut parser = JsonParser {
+        text: text.as_bytes(),
+        position: 0,
+    };
+    let value = parser.value()?;
+    let presets = match value {
+        Json::Array(items) => items,
+        object @ Json::Object(_) => vec![object],
+        _ => {
+            return Err(VtkError::GenericError(
+                "A preset file holds an object or a list of objects".to_string(),
+            ))
+        }
+    };
+
+    let mut maps = Vec::new();
+    for (index, preset) in presets.iter().enumerate() {
+        let name = match preset.get("Name") {
+            Some(Json::String(name)) => name.clone(),
+            _ => format!("preset {}", index + 1),
+        };
+        if let Some(points) = preset.get("RGBPoints").and_then(Json::numbers) {
+            let points: Vec<[f32; 4]> = points
+                .chunks_exact(4)
+                .map(|p| [p[0] as f32, p[1] as f32, p[2] as f32, p[3] as f32])
+                .collect();
+            if let Some(colors) = resample_points(&points) {
+                maps.push(ColorMap { name, colors });
+            }
+        } else if let Some(colors) = preset.get("IndexedColors").and_then(Json::numbers) {
+            let colors: Vec<[f32; 4]> = colors
+                .chunks_exact(3)
+                .map(|c| [c[0] as f32, c[1] as f32, c[2] as f32, 1.0])
+                .collect();
+            if !colors.is_empty() {
+                maps.push(ColorMap { name, colors });
+            }
+        }
+    }
+    Ok(maps)
+}
+
+/// Parse ParaView XML presets, `<ColorMap>` elements with `<Point>` children
+pub fn parse_xml_presets(text: &str) -> Result<Vec<ColorMap>, VtkError> {
+    let mut maps = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("<ColorMap") {
+        let block = &rest[start..];
+        // Skip the enclosing `<ColorMaps>` element
+        if !block[9..].starts_with(|c: char| c.is_whitespace() || c == '>' || c == '/') {
+            rest = &block[9..];
+            continue;
+        }
+        let tag_end = block
+            .find('>')
+            .ok_or(VtkError::InvalidFormat("Unterminated ColorMap tag"))?;
+        let tag = &block[..tag_end];
+        let body_end = if tag.ends_with('/') {
+            tag_end
+        } else {
+            block.find("</ColorMap>").unwrap_or(block.len())
+        };
+        let name = attribute(tag, "name")
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("preset {}", maps.len() + 1));
+
+        let mut points = Vec::new();
+        let mut body = &block[tag_end..body_end];
+        while let Some(point_start) = body.find("<Point ") {
+            let point = &body[point_start..];
+            let point_end = point.find('>').unwrap_or(point.len());
+            let tag = &point[..point_end];
+            let value = |key: &str| attribute(tag, key).and_then(|v| v.trim().parse::<f32>().ok());
+            if let (Some(x), Some(r), Some(g), Some(b)) =
+                (value("x"), value("r"), value("g"), value("b"))
+            {
+                points.push([x, r, g, b]);
+            }
+            body = &point[point_end..];
+        }
+        if let Some(colors) = resample_points(&points) {
+            maps.push(ColorMap { name, colors });
+        }
+        rest = &block[body_end..];
+    }
+    Ok(maps)
+}
+
+/// Resample `x, r, g, b` control points to evenly spaced colors
+///
+/// # Returns
+/// - Colors from the smallest to the largest `x`, `None` without points
+fn resample_points(points: &[[f32; 4]]) -> Option<Vec<[f32; 4]>> {
+    let mut points: Vec<[f32; 4]> = points
+        .iter()
+        .copied()
+        .filter(|p| p.iter().all(|v| v.is_finite()))
+        .collect();
+    points.sort_by(|a, b| a[0].total_cmp(&b[0]));
+    let (first, last) = (points.first()?[0], points.last()?[0]);
+    if points.len() == 1 || last <= first {
+        let p = points[0];
+        return Some(vec![[p[1], p[2], p[3], 1.0]]);
+    }
+
+    let colors = (0..PRESET_COLORS)
+        .map(|i| {
+            let x = first + (last - first) * i as f32 / (PRESET_COLORS - 1) as f32;
+            let next = points
+                .partition_point(|p| p[0] < x)
+                .clamp(1, points.len() - 1);
+            let (a, b) = (points[next - 1], points[next]);
+            let t = if b[0] > a[0] {
+                ((x - a[0]) / (b[0] - a[0])).clamp(0.0, 1.0)
+            } else {
+                1.0
+            };
+            [
+                a[1] + (b[1] - a[1]) * t,
+                a[2] + (b[2] - a[2]) * t,
+                a[3] + (b[3] - a[3]) * t,
+                1.0,
+            ]
+        })
+        .collect();
+    Some(colors)
+}
+
+/// JSON value, as far as presets need it
+enum Json {
+    /// `true`, `false` or `null`
+    Literal,
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Member of an object
+    fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Numbers of an array of numbers
+    fn numbers(&self) -> Option<Vec<f64>> {
+        match self {
+            Json::Array(items) => items
+                .iter()
+                .map(|item| match item {
+                    Json::Number(value) => Some(*value),
+                    _ => None,
+                })
+                .collect(),
+            _ => None,
+        }
+    }
+}
+
+/// Recursive descent JSON parser
+struct JsonParser<'a> {
+    text: &'a [u8],
+    position: usize,
+}
+
+impl JsonParser<'_> {
+    fn error(&self, message: &str) -> VtkError {
+        VtkError::GenericError(format!(
+            "{} at byte {} of the preset",
+            message, self.position
+        ))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self
+            .text
+            .get(self.position)
+            .is_some_and(|c| c.is_ascii_whitespace())
+        {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), VtkError> {
+        self.skip_whitespace();
+        if self.text.get(self.position) != Some(&byte) {
+            return Err(self.error(&format!("Expected '{}'", byte as char)));
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    fn literal(&mut self, word: &str) -> Result<Json, VtkError> {
+        if !self.text[self.position..].starts_with(word.as_bytes()) {
+            return Err(self.error("Unexpected value"));
+        }
+        self.position += word.len();
+        Ok(Json::Literal)
+    }
+
+    fn value(&mut self) -> Result<Json, VtkError> {
+        self.skip_whitespace();
+        match self.text.get(self.position) {
+            Some(b'{') => self.object(),
+            Some(b'[') => self.array(),
+            Some(b'"') => self.string().map(Json::String),
+            Some(b't') => self.literal("true"),
+            Some(b'f') => self.literal("false"),
+            Some(b'n') => self.literal("null"),
+            Some(_) => self.number(),
+            None => Err(self.error("Unexpected end")),
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, VtkError> {
+        self.expect(b'{')?;
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.text.get(self.position) == Some(&b'}') {
+            self.position += 1;
+            return Ok(Json::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(b':')?;
+            members.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.text.get(self.position) {
+                Some(b',') => self.position += 1,
+                Some(b'}') => {
+                    self.position += 1;
+                    return Ok(Json::Object(members));
+                }
+                _ => return Err(self.error("Expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, VtkError> {
+        self.expect(b'[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.text.get(self.position) == Some(&b']') {
+            self.position += 1;
+            return Ok(Json::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.text.get(self.position) {
+                Some(b',') => self.position += 1,
+                Some(b']') => {
+                    self.position += 1;
+                    return Ok(Json::Array(items));
+                }
+                _ => return Err(self.error("Expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, VtkError> {
+        self.expect(b'"')?;
+        let mut bytes = Vec::new();
+        loop {
+            let Some(&byte) = self.text.get(self.position) else {
+                return Err(self.error("Unterminated string"));
+            };
+            self.position += 1;
+            match byte {
+                b'"' => break,
+                b'\\' => {
+                    let Some(&escaped) = self.text.get(self.position) else {
+                        return Err(self.error("Unterminated string"));
+                    };
+                    self.position += 1;
+                    match escaped {
+                        b'n' => bytes.push(b'\n'),
+                        b't' => bytes.push(b'\t'),
+                        b'r' => bytes.push(b'\r'),
+                        b'b' | b'f' => {}
+                        b'u' => {
+                            // Non-ASCII escapes only occur in names, keep a placeholder
+                            self.position = (self.position + 4).min(self.text.len());
+                            bytes.push(b'?');
+                        }
+                        other => bytes.push(other),
+                    }
+                }
+                other => bytes.push(other),
+            }
+        }
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    fn number(&mut self) -> Result<Json, VtkError> {
+        let start = self.position;
+        while self
+            .text
+            .get(self.position)
+            .is_some_and(|c| c.is_ascii_digit() || b"+-.eE".contains(c))
+        {
+            self.position += 1;
+        }
+        std::str::from_utf8(&self.text[start..self.position])
+            .ok()
+            .and_then(|number| number.parse().ok())
+            .map(Json::Number)
+            .ok_or_else(|| self.error("Invalid number"))
+    }
+}
@@ -0,0 +1,593 @@
+//! Compressed XML VTK data module
+//!
+//! XML VTK files (`.vtu`, `.vtp`, ...) written by most solvers compress their
+//! binary arrays with zlib, LZ4 or LZMA (`compressor` attribute of `VTKFile`).
+//! This module rewrites such documents into an equivalent uncompressed document:
+//! - Compressed appended arrays (raw or base64 encoded) are decompressed
+//! - Compressed inline binary arrays are moved into the appended section
+//! - The result uses raw appended data with plain size headers and no `compressor`
+//!
+//! Compressed arrays are stored as a header followed by the compressed blocks:
+//! `[num_blocks, block_size, last_block_size, compressed_size_0, ...]`, where every
+//! header word is `UInt32` or `UInt64` depending on the `header_type` attribute.
+use super::VtkError;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use std::io::Read;
+
+/// Largest output an LZ4 block can expand to per compressed byte
+const LZ4_MAX_RATIO: usize = 255;
+
+/// Output preallocated per compressed byte; larger outputs grow while decoding, so
+/// corrupt size headers cannot trigger huge allocations
+const PREALLOCATED_RATIO: usize = 16;
+
+/// Block compressor used by the file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compressor {
+    Zlib,
+    Lz4,
+    Lzma,
+}
+
+impl Compressor {
+    /// Map the VTK compressor class name
+    pub fn from_vtk_name(name: &str) -> Result<Self, VtkError> {
+        match name {
+            "vtkZLibDataCompressor" => Ok(Compressor::Zlib),
+            "vtkLZ4DataCompressor" => Ok(Compressor::Lz4),
+            "vtkLZMADataCompressor" => Ok(Compressor::Lzma),
+            other => Err(VtkError::LoadError(format!(
+                "Unsupported VTK compressor '{}'",
+                other
+            ))),
+        }
+    }
+
+    /// Decompress a single block
+    fn decompress(self, block: &[u8], uncompressed_size: usize) -> Result<Vec<u8>, VtkError> {
+        let mut output = Vec::with_capacity(
+            uncompressed_size.min(block.len().saturating_mul(PREALLOCATED_RATIO)),
+        );
+        match self {
+            Compressor::Zlib => {
+                // Stop one byte past the expected size, enough to detect a mismatch
+                flate2::read::ZlibDecoder::new(block)
+                    .take((uncompressed_size as u64).saturating_add(1))
+                    .read_to_end(&mut output)
+                    .map_err(VtkError::IoError)?;
+            }
+            Compressor::Lz4 => {
+                if uncompressed_size > block.len().saturating_mul(LZ4_MAX_RATIO) {
+                    return Err(VtkError::InvalidFormat(
+                        "LZ4 block size exceeds its compressed data",
+                    ));
+                }
+                output = lz4_flex::block::decompress(block, uncompressed_size)
+                    .map_err(|e| VtkError::ConversionError(format!("LZ4: {}", e)))?;
+            }
+            Compressor::Lzma => {
+                lzma_rs::xz_decompress(&mut std::io::BufReader::new(block), &mut output)
+                    .map_err(|e| VtkError::ConversionError(format!("LZMA: {:?}", e)))?;
+            }
+        }
+
+        if output.len() != uncompressed_size {
+            return Err(VtkError::AttributeMismatch {
+                attribute_size: output.len(),
+                expected_size: uncompressed_size,
+            });
+        }
+        Ok(output)
+    }
+}
+
+/// Binary layout of the block headers
+#[derive(Clone, Copy, Debug)]
+struct HeaderFormat {
+    /// Header word size in bytes (4 or 8)
+    word_size: usize,
+    big_endian: bool,
+}
+
+impl HeaderFormat {
+    fn read_word(&self, bytes: &[u8], index: usize) -> Result<usize, VtkError> {
+        let word = index
+            .checked_mul(self.word_size)
+            .and_then(|start| bytes.get(start..start.checked_add(self.word_size)?))
+            .ok_or(VtkError::InvalidFormat("Truncated compressed block header"))?;
+
+        let mut buffer = [0u8; 8];
+        if self.big_endian {
+            buffer[8 - self.word_size..].copy_from_slice(word);
+            Ok(u64::from_be_bytes(buffer) as usize)
+        } else {
+            buffer[..self.word_size].copy_from_slice(word);
+            Ok(u64::from_le_bytes(buffer) as usize)
+        }
+    }
+
+    fn write_word(&self, value: usize, output: &mut Vec<u8>) {
+        let value = value as u64;
+        if self.big_endian {
+            output.extend_from_slice(&value.to_be_bytes()[8 - self.word_size..]);
+        } else {
+            output.extend_from_slice(&value.to_le_bytes()[..self.word_size]);
+        }
+    }
+}
+
+/// Sizes stored in a compressed array header
+struct BlockHeader {
+    block_size: usize,
+    last_block_size: usize,
+    compressed_sizes: Vec<usize>,
+}
+
+impl BlockHeader {
+    fn header_len(&self, format: &HeaderFormat) -> usize {
+        (3 + self.compressed_sizes.len()) * format.word_size
+    }
+
+    fn uncompressed_len(&self) -> Result<usize, VtkError> {
+        match self.compressed_sizes.len() {
+            0 => Some(0),
+            n => (n - 1)
+                .checked_mul(self.block_size)
+                .and_then(|size| size.checked_add(self.last_block_size)),
+        }
+        .ok_or(VtkError::InvalidFormat("Compressed array size overflows"))
+    }
+
+    fn compressed_len(&self) -> Result<usize, VtkError> {
+        self.compressed_sizes
+            .iter()
+            .try_fold(0usize, |sum, &size| sum.checked_add(size))
+            .ok_or(VtkError::InvalidFormat("Compressed array size overflows"))
+    }
+}
+
+/// Length in bytes of a block header with `num_blocks` blocks
+fn header_len(num_blocks: usize, format: &HeaderFormat) -> Result<usize, VtkError> {
+    num_blocks
+        .checked_add(3)
+        .and_then(|words| words.checked_mul(format.word_size))
+        .ok_or(VtkError::InvalidFormat("Compressed block count overflows"))
+}
+
+/// Read the block header at the start of `bytes`
+fn read_block_header(bytes: &[u8], format: &HeaderFormat) -> Result<BlockHeader, VtkError> {
+    let num_blocks = format.read_word(bytes, 0)?;
+    if header_len(num_blocks, format)? > bytes.len() {
+        return Err(VtkError::InvalidFormat(
+            "Compressed block count exceeds the data size",
+        ));
+    }
+    let block_size = format.read_word(bytes, 1)?;
+    let mut last_block_size = format.read_word(bytes, 2)?;
+    // A last block size of 0 means the last block is a full block
+    if last_block_size == 0 {
+        last_block_size = block_size;
+    }
+    let compressed_sizes = (0..num_blocks)
+        .map(|block| format.read_word(bytes, 3 + block))
+        .collect::<Result<_, _>>()?;
+
+    Ok(BlockHeader {
+        block_size,
+        last_block_size,
+        compressed_sizes,
+    })
+}
+
+/// Decompress all blocks of one array
+fn decompress_blocks(
+    header: &BlockHeader,
+    data: &[u8],
+    compressor: Compressor,
+) -> Result<Vec<u8>, VtkError> {
+    let uncompressed_len = header.uncompressed_len()?;
+    let mut output =
+        Vec::with_capacity(uncompressed_len.min(data.len().saturating_mul(PREALLOCATED_RATIO)));
+    let mut position = 0usize;
+    let last = header.compressed_sizes.len().saturating_sub(1);
+
+    for (block, &compressed_size) in header.compressed_sizes.iter().enumerate() {
+        let end = position
+            .checked_add(compressed_size)
+            .ok_or(VtkError::InvalidFormat("Truncated compressed block"))?;
+        let compressed = data
+            .get(position..end)
+            .ok_or(VtkError::InvalidFormat("Truncated compressed block"))?;
+        let uncompressed_size = if block == last {
+            header.last_block_size
+        } else {
+            header.block_size
+        };
+        output.extend(compressor.decompress(compressed, uncompressed_size)?);
+        position = end;
+    }
+
+    Ok(output)
+}
+
+/// Decode a base64 prefix of `text` that yields at least `byte_len` bytes
+///
+/// # Returns
+/// - Decoded bytes and the number of consumed characters
+fn decode_base64_prefix(text: &[u8], byte_len: usize) -> Result<(Vec<u8>, usize), VtkError> {
+    let char_len = byte_len
+        .div_ceil(3)
+        .checked_mul(4)
+        .ok_or(VtkError::InvalidFormat("Truncated base64 data"))?;
+    let chunk = text
+        .get(..char_len)
+        .ok_or(VtkError::InvalidFormat("Truncated base64 data"))?;
+    let decoded = BASE64
+        .decode(chunk)
+        .map_err(|e| VtkError::ConversionError(format!("base64: {}", e)))?;
+    Ok((decoded, char_len))
+}
+
+/// Decompress one array stored as base64 (header and data encoded separately)
+fn decompress_base64_array(
+    text: &[u8],
+    format: &HeaderFormat,
+    compressor: Compressor,
+) -> Result<Vec<u8>, VtkError> {
+    // The first three words tell the number of blocks and thus the header length
+    let (prefix, _) = decode_base64_prefix(text, 3 * format.word_size)?;
+    let num_blocks = format.read_word(&prefix, 0)?;
+    let (header_bytes, header_chars) = decode_base64_prefix(text, header_len(num_blocks, format)?)?;
+    let header = read_block_header(&header_bytes, format)?;
+
+    let (data, _) = decode_base64_prefix(&text[header_chars..], header.compressed_len()?)?;
+    decompress_blocks(&header, &data, compressor)
+}
+
+/// Decompress one array stored as raw bytes
+fn decompress_raw_array(
+    bytes: &[u8],
+    format: &HeaderFormat,
+    compressor: Compressor,
+) -> Result<Vec<u8>, VtkError> {
+    let header = read_block_header(bytes, format)?;
+    let data = bytes
+        .get(header.header_len(format)..)
+        .ok_or(VtkError::InvalidFormat("Truncated compressed array"))?;
+    decompress_blocks(&header, data, compressor)
+}
+
+/// Value of an XML attribute inside a start tag
+pub fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let pattern = format!(" {}=", name);
+    let start = tag.find(&pattern)? + pattern.len();
+    let quote = tag[start..].chars().next()?;
+    let value_start = start + quote.len_utf8();
+    let value_len = tag[value_start..].find(quote)?;
+    Some(&tag[value_start..value_start + value_len])
+}
+
+/// Set (or add) an XML attribute of a start tag
+fn set_attribute(tag: &str, name: &str, value: &str) -> String {
+    let pattern = format!(" {}=", name);
+    if let Some(old) = attribute(tag, name) {
+        let start = tag.find(&pattern).unwrap_or(0);
+        let end = start + pattern.len() + old.len() + 2;
+        return format!("{} {}=\"{}\"{}", &tag[..start], name, value, &tag[end..]);
+    }
+
+    let insert_at = if tag.ends_with("/>") {
+        tag.len() - 2
+    } else {
+        tag.len() - 1
+    };
+    format!(
+        "{} {}=\"{}\"{}",
+        tag[..insert_at].trim_end(),
+        name,
+        value,
+        &tag[insert_at..]
+    )
+}
+
+/// Remove an XML attribute from a start tag
+fn remove_attribute(tag: &str, name: &str) -> String {
+    match (tag.find(&format!(" {}=", name)), attribute(tag, name)) {
+        (Some(start), Some(value)) => {
+            let end = start + name.len() + 2 + value.len() + 2;
+            format!("{}{}", &tag[..start], &tag[end..])
+        }
+        _ => tag.to_string(),
+    }
+}
+
+/// Rewrite a compressed XML VTK document into an uncompressed one
+///
+/// # Parameters
+/// - `document`: Complete file contents
+///
+/// # Returns
+/// - `Ok(Some(bytes))`: Uncompressed document
+/// - `Ok(None)`: The document is not compressed and can be parsed as is
+/// - `Err(VtkError)`: Unsupported compressor or corrupt data
+pub fn decompress_xml_document(document: &[u8]) -> Result<Option<Vec<u8>>, VtkError> {
+    // Everything before the appended section is plain XML text
+    let appended_start = find_bytes(document, b"<AppendedData", 0);
+    let header_end = appended_start.unwrap_or(document.len());
+    let header = std::str::from_utf8(&document[..header_end])
+        .map_err(|_| VtkError::InvalidFormat("XML header is not valid UTF-8"))?;
+
+    let vtk_tag_start = header
+        .find("<VTKFile")
+        .ok_or(VtkError::InvalidFormat("Missing VTKFile element"))?;
+    let vtk_tag_end = vtk_tag_start
+        + header[vtk_tag_start..]
+            .find('>')
+            .ok_or(VtkError::InvalidFormat("Unterminated VTKFile element"))?
+        + 1;
+    let vtk_tag = &header[vtk_tag_start..vtk_tag_end];
+
+    let Some(compressor_name) = attribute(vtk_tag, "compressor") else {
+        return Ok(None);
+    };
+    let compressor = Compressor::from_vtk_name(compressor_name)?;
+    let format = HeaderFormat {
+        word_size: if attribute(vtk_tag, "header_type") == Some("UInt64") {
+            8
+        } else {
+            4
+        },
+        big_endian: attribute(vtk_tag, "byte_order") == Some("BigEndian"),
+    };
+
+    // Locate the appended payload (after the `_` marker) and its encoding
+    let mut appended: Option<(&[u8], bool)> = None;
+    let mut document_tail: &[u8] = b"</VTKFile>\n";
+    if let Some(start) = appended_start {
+        let tag_end = find_bytes(document, b">", start)
+            .ok_or(VtkError::InvalidFormat("Unterminated AppendedData element"))?;
+        let tag = std::str::from_utf8(&document[start..=tag_end])
+            .map_err(|_| VtkError::InvalidFormat("Invalid AppendedData element"))?;
+        let base64 = attribute(tag, "encoding") == Some("base64");
+        let marker = find_bytes(document, b"_", tag_end)
+            .ok_or(VtkError::InvalidFormat("Missing appended data marker"))?;
+        let end = rfind_bytes(document, b"</AppendedData>")
+            .ok_or(VtkError::InvalidFormat("Unterminated AppendedData element"))?;
+        appended = Some((&document[marker + 1..end], base64));
+        document_tail = &document[end + b"</AppendedData>".len()..];
+    }
+
+    // Rewrite every binary DataArray into an uncompressed appended array
+    let mut output_header = String::with_capacity(header.len());
+    let mut output_data: Vec<u8> = Vec::new();
+    let mut position = 0;
+
+    while let Some(found) = header[position..].find("<DataArray") {
+        let tag_start = position + found;
+        let tag_end = tag_start
+            + header[tag_start..]
+                .find('>')
+                .ok_or(VtkError::InvalidFormat("Unterminated DataArray element"))?
+            + 1;
+        let tag = &header[tag_start..tag_end];
+        output_header.push_str(&header[position..tag_start]);
+        position = tag_end;
+
+        let array = match attribute(tag, "format") {
+            Some("appended") => {
+                let (payload, base64) =
+                    appended.ok_or(VtkError::MissingData("Appended data section"))?;
+                let offset: usize = attribute(tag, "offset")
+                    .and_then(|offset| offset.trim().parse().ok())
+                    .ok_or(VtkError::InvalidFormat("Invalid appended data offset"))?;
+                let array_data = payload
+                    .get(offset..)
+                    .ok_or(VtkError::InvalidFormat("Appended data offset out of range"))?;
+                if base64 {
+                    decompress_base64_array(array_data, &format, compressor)?
+                } else {
+                    decompress_raw_array(array_data, &format, compressor)?
+                }
+            }
+            Some("binary") if !tag.ends_with("/>") => {
+                let content_end = header[tag_end..]
+                    .find("</DataArray>")
+                    .map(|end| tag_end + end)
+                    .ok_or(VtkError::InvalidFormat("Unterminated DataArray element"))?;
+                let content: Vec<u8> = header[tag_end..content_end]
+                    .bytes()
+                    .filter(|b| !b.is_ascii_whitespace())
+                    .collect();
+                position = content_end + "</DataArray>".len();
+                decompress_base64_array(&content, &format, compressor)?
+            }
+            _ => {
+                output_header.push_str(tag);
+                continue;
+            }
+        };
+
+        let mut new_tag = set_attribute(tag, "format", "appended");
+        new_tag = set_attribute(&new_tag, "offset", &output_data.len().to_string());
+        if !new_tag.ends_with("/>") {
+            new_tag = format!("{}/>", new_tag[..new_tag.len() - 1].trim_end());
+            // Skip the (empty) element content and end tag of formerly appended arrays
+            if let Some(end) = header[position..].find("</DataArray>") {
+                if header[position..position + end].trim().is_empty() {
+                    position += end + "</DataArray>".len();
+                }
+            }
+        }
+        output_header.push_str(&new_tag);
+
+        format.write_word(array.len(), &mut output_data);
+        output_data.extend(array);
+    }
+    output_header.push_str(&header[position..]);
+
+    // Drop the compressor attribute and re-attach the appended section
+    let new_vtk_tag = remove_attribute(vtk_tag, "compressor");
+    let mut output_header = output_header.replacen(vtk_tag, &new_vtk_tag, 1);
+    if appended_start.is_none() {
+        // No appended section before: cut the closing VTKFile tag, it is re-added below
+        let end = output_header
+            .rfind("</VTKFile>")
+            .ok_or(VtkError::InvalidFormat("Missing VTKFile end tag"))?;
+        output_header.truncate(end);
+    }
+
+    let mut output = output_header.into_bytes();
+    output.extend_from_slice(b"<AppendedData encoding=\"raw\">\n_");
+    output.extend(output_data);
+    output.extend_from_slice(b"\n</AppendedData>");
+    output.extend_from_slice(document_tail);
+
+    Ok(Some(output))
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack
+        .get(from..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|position| from + position)
+}
+
+fn rfind_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .rposition(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Compressed document with one appended array holding `appended`
+    fn document(compressor: &str, header_type: &str, appended: &[u8]) -> Vec<u8> {
+        let mut document = format!(
+            "<VTKFile type=\"PolyData\" compressor=\"{}\" header_type=\"{}\" byte_order=\"LittleEndian\">\n\
+             <PolyData><Piece NumberOfPoints=\"1\"><Points>\n\
+             <DataArray type=\"Float32\" NumberOfComponents=\"3\" format=\"appended\" offset=\"0\"/>\n\
+             </Points></Piece></PolyData>\n<AppendedData encoding=\"raw\">\n_",
+            compressor, header_type
+        )
+        .into_bytes();
+        document.extend_from_slice(appended);
+        document.extend_from_slice(b"\n</AppendedData>\n</VTKFile>\n");
+        document
+    }
+
+    fn words(values: &[u32]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    /// `UInt32` header and data of `values` compressed as a single block
+    fn compressed_array(compressor: Compressor, values: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let block = match compressor {
+            Compressor::Zlib => {
+                let mut encoder =
+                    flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+                std::io::Write::write_all(&mut encoder, values).unwrap();
+                encoder.finish().unwrap()
+            }
+            Compressor::Lz4 => lz4_flex::block::compress(values),
+            Compressor::Lzma => unreachable!(),
+        };
+        let length = values.len() as u32;
+        (words(&[1, length, length, block.len() as u32]), block)
+    }
+
+    /// Size header and data of the first array of an uncompressed document
+    fn first_appended_array(document: &[u8]) -> (usize, &[u8]) {
+        let appended = find_bytes(document, b"<AppendedData", 0).unwrap();
+        let start = find_bytes(document, b"_", appended).unwrap() + 1;
+        let format = HeaderFormat {
+            word_size: 4,
+            big_endian: false,
+        };
+        let size = format.read_word(&document[start..], 0).unwrap();
+        (size, &document[start + 4..start + 4 + size])
+    }
+
+    #[test]
+    fn compressed_appended_data_is_decompressed() {
+        let values: Vec<u8> = [1.0f32, 2.0, 3.0]
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect();
+        for (compressor, name) in [
+            (Compressor::Zlib, "vtkZLibDataCompressor"),
+            (Compressor::Lz4, "vtkLZ4DataCompressor"),
+        ] {
+            let (header, block) = compressed_array(compressor, &values);
+            let raw = document(name, "UInt32", &[header.clone(), block.clone()].concat());
+            // Base64 arrays encode the header and the blocks separately
+            let text = BASE64.encode(header) + &BASE64.encode(block);
+            let base64 = String::from_utf8(document(name, "UInt32", text.as_bytes()))
+                .unwrap()
+                .replace("encoding=\"raw\"", "encoding=\"base64\"");
+
+            for document in [raw, base64.into_bytes()] {
+                let output = decompress_xml_document(&document).unwrap().unwrap();
+                let appended = find_bytes(&output, b"<AppendedData", 0).unwrap();
+                let header = std::str::from_utf8(&output[..appended]).unwrap();
+                assert!(!header.contains("compressor="));
+                assert_eq!(
+                    first_appended_array(&output),
+                    (values.len(), values.as_slice())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn truncated_header_is_an_error() {
+        let appended = [1, 0, 0, 0, 12, 0];
+        let document = document("vtkZLibDataCompressor", "UInt32", &appended);
+        assert!(decompress_xml_document(&document).is_err());
+    }
+
+    #[test]
+    fn oversized_block_count_is_an_error() {
+        let appended = words(&[u32::MAX, 12, 12, 4, 0]);
+        let raw = document("vtkZLibDataCompressor", "UInt32", &appended);
+        assert!(decompress_xml_document(&raw).is_err());
+
+        // Base64 headers are decoded in two steps sized by the block count
+        let header: Vec<u8> = [u64::MAX, 12, 12]
+            .iter()
+            .flat_map(|value: &u64| value.to_le_bytes())
+            .collect();
+        let base64 = document(
+            "vtkZLibDataCompressor",
+            "UInt64",
+            BASE64.encode(header).as_bytes(),
+        );
+        let base64 = String::from_utf8(base64)
+            .unwrap()
+            .replace("encoding=\"raw\"", "encoding=\"base64\"");
+        assert!(decompress_xml_document(base64.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn overflowing_block_size_is_an_error() {
+        let appended: Vec<u8> = [2, u64::MAX, 12, 1, 1, 0]
+            .iter()
+            .flat_map(|value: &u64| value.to_le_bytes())
+            .collect();
+        let document = document("vtkZLibDataCompressor", "UInt64", &appended);
+        assert!(decompress_xml_document(&document).is_err());
+    }
+
+    #[test]
+    fn oversized_lz4_block_is_an_error() {
+        let appended = words(&[1, u32::MAX, u32::MAX, 2, 0]);
+        let document = document("vtkLZ4DataCompressor", "UInt32", &appended);
+        assert!(decompress_xml_document(&document).is_err());
+    }
+}
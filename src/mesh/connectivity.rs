@@ -0,0 +1,136 @@
+//! Connectivity module
+//!
+//! Connected regions of a mesh:
+//! - Cells sharing at least one point belong to the same region
+//! - Regions are numbered by decreasing cell count, so region 0 is the largest
+//! - Region ids can be stored as the cell scalar `RegionId`
+use super::conversion::{cell_points, location_size};
+use super::topology::triangle_cell;
+use super::{AttributeLocation, AttributeType, GeometryData};
+use std::collections::BTreeSet;
+
+/// Name of the cell scalar holding the region ids
+pub const REGION_ATTRIBUTE: &str = "RegionId";
+
+/// Connected regions of a mesh
+#[derive(Clone, Debug, Default)]
+pub struct Regions {
+    /// Region of every cell
+    pub cell_regions: Vec<usize>,
+    /// Number of cells of every region, in decreasing order
+    pub sizes: Vec<usize>,
+}
+
+/// Root of an element in a union-find forest, with path halving
+fn find(parents: &mut [usize], mut element: usize) -> usize {
+    while parents[element] != element {
+        parents[element] = parents[parents[element]];
+        element = parents[element];
+    }
+    element
+}
+
+/// Label the connected regions of a mesh
+///
+/// # Parameters
+/// - `geometry`: Geometry data
+///
+/// # Returns
+/// - Region of every cell and the region sizes
+pub fn connected_regions(geometry: &GeometryData) -> Regions {
+    let cells = cell_points(geometry, location_size(geometry, &AttributeLocation::Cell));
+
+    // Union the cells through the first cell seen at every point
+    let mut parents: Vec<usize> = (0..cells.len()).collect();
+    let mut point_cell = vec![usize::MAX; geometry.vertices.len()];
+    for (cell, points) in cells.iter().enumerate() {
+        for &point in points {
+            let Some(first) = point_cell.get_mut(point as usize) else {
+                continue;
+            };
+            if *first == usize::MAX {
+                *first = cell;
+                continue;
+            }
+            let (a, b) = (find(&mut parents, *first), find(&mut parents, cell));
+            if a != b {
+                parents[a.max(b)] = a.min(b);
+            }
+        }
+    }
+
+    // Number the roots, then sort the regions by size
+    let mut root_region = vec![usize::MAX; cells.len()];
+    let mut cell_regions = Vec::with_capacity(cells.len());
+    let mut sizes = Vec::new();
+    for cell in 0..cells.len() {
+        let root = find(&mut parents, cell);
+        if root_region[root] == usize::MAX {
+            root_region[root] = sizes.len();
+            sizes.push(0);
+        }
+        sizes[root_region[root]] += 1;
+        cell_regions.push(root_region[root]);
+    }
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by(|&a, &b| sizes[b].cmp(&sizes[a]).then(a.cmp(&b)));
+    let mut renumber = vec![0; sizes.len()];
+    for (new, &old) in order.iter().enumerate() {
+        renumber[old] = new;
+    }
+
+    Regions {
+        cell_regions: cell_regions.iter().map(|&r| renumber[r]).collect(),
+        sizes: order.iter().map(|&r| sizes[r]).collect(),
+    }
+}
+
+/// Store the region ids as cell scalar [`REGION_ATTRIBUTE`]
+pub fn add_region_attribute(geometry: &mut GeometryData, regions: &Regions) {
+    geometry
+        .attributes
+        .get_or_insert_with(Default::default)
+        .insert(
+            (REGION_ATTRIBUTE.to_string(), AttributeLocation::Cell),
+            AttributeType::Scalar {
+                num_comp: 1,
+                table_name: "default".to_string(),
+                data: regions.cell_regions.iter().map(|&r| r as f32).collect(),
+                lookup_table: None,
+            },
+        );
+}
+
+/// Copy of a geometry without the triangles of hidden regions
+///
+/// Vertices and attributes are kept, so per-vertex data of the geometry still
+/// applies to the result.
+///
+/// # Parameters
+/// - `geometry`: Geometry data
+/// - `regions`: Regions of the geometry
+/// - `hidden`: Regions to leave out
+pub fn without_regions(
+    geometry: &GeometryData,
+    regions: &Regions,
+    hidden: &BTreeSet<usize>,
+) -> GeometryData {
+    let mut filtered = geometry.clone();
+    let mut indices = Vec::with_capacity(geometry.indices.len());
+    let mut mapping = Vec::with_capacity(geometry.indices.len() / 3);
+    for (triangle, corners) in geometry.indices.chunks_exact(3).enumerate() {
+        let cell = triangle_cell(geometry, triangle);
+        let visible = regions
+            .cell_regions
+            .get(cell)
+            .is_none_or(|region| !hidden.contains(region));
+        if visible {
+            indices.extend_from_slice(corners);
+            mapping.push(cell);
+        }
+    }
+    filtered.indices = indices;
+    filtered.triangle_to_cell_mapping = Some(mapping);
+    filtered.quadratic_triangles = None;
+    filtered
+}
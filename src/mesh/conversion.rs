@@ -0,0 +1,212 @@
+//! Conversion module
+//!
+//! Conversion of attributes between point and cell data:
+//! - Cell to point: every point gets the average of the cells using it
+//! - Point to cell: every cell gets the average of its corner points
+//!
+//! Cells are described by their surface triangles and, for unstructured grids,
+//! by their volume cells, so interior points of volume meshes get values too.
+use super::topology::{cell_count, cell_triangles, cell_vertices, element_count};
+use super::{AttributeLocation, AttributeType, GeometryData, VtkError};
+
+/// Number of points or cells of a location
+///
+/// The cell count is taken from the cell attributes if there are any, since
+/// volume cells without surface triangles do not appear in the triangle mapping.
+pub fn location_size(geometry: &GeometryData, location: &AttributeLocation) -> usize {
+    match location {
+        AttributeLocation::Point => geometry.vertices.len(),
+        AttributeLocation::Cell => geometry
+            .attributes
+            .iter()
+            .flatten()
+            .filter(|((name, location), _)| {
+                *location == AttributeLocation::Cell && !name.starts_with("__lut_")
+            })
+            .map(|(_, attribute)| element_count(attribute))
+            .max()
+            .unwrap_or_else(|| cell_count(geometry)),
+    }
+}
+
+/// Unique corner points of every cell
+///
+/// # Parameters
+/// - `geometry`: Geometry data
+/// - `cells`: Number of cells
+pub fn cell_points(geometry: &GeometryData, cells: usize) -> Vec<Vec<u32>> {
+    let mut points = cell_vertices(geometry, &cell_triangles(geometry));
+    points.resize(cells.max(points.len()), Vec::new());
+    if let Some(volume_cells) = &geometry.volume_cells {
+        for (index, &cell_id) in volume_cells.cell_ids.iter().enumerate() {
+            let Some(corners) = points.get_mut(cell_id) else {
+                continue;
+            };
+            corners.extend_from_slice(volume_cells.cell(index).1);
+            corners.sort_unstable();
+            corners.dedup();
+        }
+    }
+    points.truncate(cells);
+    points
+}
+
+/// Components of an attribute value, flattened
+fn components(attribute: &AttributeType, element: usize) -> Vec<f32> {
+    match attribute {
+        AttributeType::Scalar { num_comp, data, .. } => {
+            let stride = (*num_comp).max(1);
+            (0..stride)
+                .map(|c| data.get(element * stride + c).copied().unwrap_or(0.0))
+                .collect()
+        }
+        AttributeType::ColorScalar { nvalues, data } => {
+            let mut values = data.get(element).cloned().unwrap_or_default();
+            values.resize(*nvalues as usize, 0.0);
+            values
+        }
+        AttributeType::Vector(data) => data.get(element).copied().unwrap_or_default().to_vec(),
+        AttributeType::Tensor(data) => data.get(element).copied().unwrap_or_default().to_vec(),
+    }
+}
+
+/// Attribute of the same type from averaged component sums
+fn from_sums(attribute: &AttributeType, sums: Vec<Vec<f32>>, counts: &[u32]) -> AttributeType {
+    let averages = sums.into_iter().zip(counts).map(|(sum, &count)| {
+        let scale = if count > 0 { 1.0 / count as f32 } else { 0.0 };
+        sum.into_iter().map(move |value| value * scale)
+    });
+    match attribute {
+        AttributeType::Scalar {
+            num_comp,
+            table_name,
+            lookup_table,
+            ..
+        } => AttributeType::Scalar {
+            num_comp: *num_comp,
+            table_name: table_name.clone(),
+            data: averages.flatten().collect(),
+            lookup_table: lookup_table.clone(),
+        },
+        AttributeType::ColorScalar { nvalues, .. } => AttributeType::ColorScalar {
+            nvalues: *nvalues,
+            data: averages.map(Iterator::collect).collect(),
+        },
+        AttributeType::Vector(_) => AttributeType::Vector(
+            averages
+                .map(|values| {
+                    let mut vector = [0.0; 3];
+                    vector.iter_mut().zip(values).for_each(|(v, x)| *v = x);
+                    vector
+                })
+                .collect(),
+        ),
+        AttributeType::Tensor(_) => AttributeType::Tensor(
+            averages
+                .map(|values| {
+                    let mut tensor = [0.0; 9];
+                    tensor.iter_mut().zip(values).for_each(|(t, x)| *t = x);
+                    tensor
+                })
+                .collect(),
+        ),
+    }
+}
+
+/// Average a cell attribute to the points
+///
+/// Points not used by any cell get 0.
+///
+/// # Parameters
+/// - `geometry`: Geometry data
+/// - `attribute`: Cell attribute
+///
+/// # Returns
+/// - Point attribute of the same type and component count
+pub fn cell_to_point(geometry: &GeometryData, attribute: &AttributeType) -> AttributeType {
+    let width = components(attribute, 0).len();
+    let mut sums = vec![vec![0.0; width]; geometry.vertices.len()];
+    let mut counts = vec![0u32; geometry.vertices.len()];
+    for (cell, points) in cell_points(geometry, element_count(attribute))
+        .iter()
+        .enumerate()
+    {
+        let value = components(attribute, cell);
+        for &point in points {
+            let Some(sum) = sums.get_mut(point as usize) else {
+                continue;
+            };
+            sum.iter_mut().zip(&value).for_each(|(s, v)| *s += v);
+            counts[point as usize] += 1;
+        }
+    }
+    from_sums(attribute, sums, &counts)
+}
+
+/// Average a point attribute to the cells
+///
+/// # Parameters
+/// - `geometry`: Geometry data
+/// - `attribute`: Point attribute
+///
+/// # Returns
+/// - Cell attribute of the same type and component count
+pub fn point_to_cell(geometry: &GeometryData, attribute: &AttributeType) -> AttributeType {
+    let width = components(attribute, 0).len();
+    let cells = cell_points(geometry, location_size(geometry, &AttributeLocation::Cell));
+    let mut sums = Vec::with_capacity(cells.len());
+    let mut counts = Vec::with_capacity(cells.len());
+    for points in &cells {
+        let mut sum = vec![0.0; width];
+        for &point in points {
+            let value = components(attribute, point as usize);
+            sum.iter_mut().zip(&value).for_each(|(s, v)| *s += v);
+        }
+        sums.push(sum);
+        counts.push(points.len() as u32);
+    }
+    from_sums(attribute, sums, &counts)
+}
+
+/// Convert an attribute to the other location and store it as a new attribute
+///
+/// The converted attribute keeps its name; if that name is already taken at the
+/// target location, `_points` or `_cells` is appended.
+///
+/// # Parameters
+/// - `geometry`: Geometry data
+/// - `name`: Attribute name
+/// - `location`: Current location of the attribute
+///
+/// # Returns
+/// - `Ok(name)`: Name of the new attribute
+/// - `Err(VtkError)`: The attribute does not exist
+pub fn convert_attribute(
+    geometry: &mut GeometryData,
+    name: &str,
+    location: &AttributeLocation,
+) -> Result<String, VtkError> {
+    let attribute = geometry
+        .get_attributes(name, location.clone())
+        .ok_or(VtkError::MissingData("attribute to convert"))?;
+    let (converted, target, suffix) = match location {
+        AttributeLocation::Cell => (
+            cell_to_point(geometry, attribute),
+            AttributeLocation::Point,
+            "points",
+        ),
+        AttributeLocation::Point => (
+            point_to_cell(geometry, attribute),
+            AttributeLocation::Cell,
+            "cells",
+        ),
+    };
+
+    let attributes = geometry.attributes.get_or_insert_with(Default::default);
+    let mut new_name = name.to_string();
+    if attributes.contains_key(&(new_name.clone(), target.clone())) {
+        new_name = format!("{}_{}", name, suffix);
+    }
+    attributes.insert((new_name.clone(), target), converted);
+    Ok(new_name)
+}
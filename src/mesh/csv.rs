@@ -0,0 +1,114 @@
+//! CSV module
+//!
+//! Scattered points from delimited text files:
+//! - The first line holds the column names; `,`, `;` and tab delimiters are
+//!   detected from it
+//! - Columns named `x`, `y` and `z` (any case) are the coordinates; without
+//!   them the first two or three numeric columns are used, and a missing z is 0
+//! - All other numeric columns become point scalars
+//!
+//! The result is a point set without triangles (rendered as splats until it is
+//! triangulated).
+use super::{AttributeLocation, AttributeType, GeometryData, VtkError};
+use bevy::utils::HashMap;
+use std::path::Path;
+
+/// Split a line at the delimiter, trimming quotes and whitespace
+fn fields(line: &str, delimiter: char) -> Vec<&str> {
+    line.split(delimiter)
+        .map(|field| field.trim().trim_matches('"').trim())
+        .collect()
+}
+
+/// Load the points of a CSV file
+///
+/// # Parameters
+/// - `path`: CSV file path
+///
+/// # Returns
+/// - `Ok(GeometryData)`: Point set with one scalar per extra numeric column
+/// - `Err(VtkError)`: The file cannot be read or has no coordinate columns
+pub fn load_csv_points(path: &Path) -> Result<GeometryData, VtkError> {
+    let text = std::fs::read_to_string(path).map_err(VtkError::IoError)?;
+    let mut lines = text
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'));
+    let header = lines
+        .next()
+        .ok_or(VtkError::MissingData("CSV header line"))?;
+    let delimiter = [',', ';', '\t']
+        .into_iter()
+        .max_by_key(|&d| header.matches(d).count())
+        .unwrap_or(',');
+    let names: Vec<String> = fields(header, delimiter)
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+
+    let rows: Vec<Vec<Option<f32>>> = lines
+        .map(|line| {
+            fields(line, delimiter)
+                .into_iter()
+                .map(|field| field.parse().ok())
+                .collect()
+        })
+        .collect();
+    // Columns with a number in every row
+    let numeric: Vec<usize> = (0..names.len())
+        .filter(|&column| {
+            !rows.is_empty()
+                && rows
+                    .iter()
+                    .all(|row| row.get(column).copied().flatten().is_some())
+        })
+        .collect();
+
+    let named = |axis: &str| {
+        numeric
+            .iter()
+            .copied()
+            .find(|&column| names[column].eq_ignore_ascii_case(axis))
+    };
+    let coordinates: Vec<usize> = match (named("x"), named("y"), named("z")) {
+        (Some(x), Some(y), z) => [Some(x), Some(y), z].into_iter().flatten().collect(),
+        _ => numeric.iter().copied().take(3).collect(),
+    };
+    if coordinates.len() < 2 {
+        return Err(VtkError::GenericError(format!(
+            "{}: CSV needs at least two numeric coordinate columns",
+            path.display()
+        )));
+    }
+
+    let value = |row: &Vec<Option<f32>>, column: usize| row[column].unwrap_or(0.0);
+    let vertices: Vec<[f32; 3]> = rows
+        .iter()
+        .map(|row| {
+            let mut point = [0.0; 3];
+            for (axis, &column) in coordinates.iter().enumerate() {
+                point[axis] = value(row, column);
+            }
+            point
+        })
+        .collect();
+
+    let mut attributes = HashMap::new();
+    for &column in numeric.iter().filter(|c| !coordinates.contains(c)) {
+        attributes.insert(
+            (names[column].clone(), AttributeLocation::Point),
+            AttributeType::Scalar {
+                num_comp: 1,
+                table_name: "default".to_string(),
+                data: rows.iter().map(|row| value(row, column)).collect(),
+                lookup_table: None,
+            },
+        );
+    }
+
+    println!(
+        "Loaded {} points with {} attributes from CSV",
+        vertices.len(),
+        attributes.len()
+    );
+    Ok(GeometryData::new(vertices, Vec::new(), attributes))
+}
@@ -0,0 +1,312 @@
+//! Delaunay module
+//!
+//! 2.5D Delaunay triangulation of scattered points:
+//! - Points are projected onto the plane normal to the height axis and
+//!   triangulated there (Bowyer-Watson), keeping their 3D positions
+//! - Points are inserted in Morton order and located by walking the
+//!   triangulation, so large point sets stay fast
+//! - Coincident points in the plane are inserted once
+//!
+//! The result covers the convex hull of the points; long hull triangles can be
+//! removed with a maximum edge length.
+use super::{AttributeLocation, GeometryData, VtkError};
+use bevy::math::DVec2;
+
+/// No neighbor across an edge
+const NONE: usize = usize::MAX;
+
+/// Axis used as height, the other two span the triangulation plane
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HeightAxis {
+    X,
+    Y,
+    #[default]
+    Z,
+}
+
+impl HeightAxis {
+    /// Plane coordinates of a point
+    fn project(self, point: [f32; 3]) -> DVec2 {
+        let [x, y, z] = point.map(f64::from);
+        match self {
+            Self::X => DVec2::new(y, z),
+            Self::Y => DVec2::new(z, x),
+            Self::Z => DVec2::new(x, y),
+        }
+    }
+}
+
+/// Counter-clockwise triangle with the neighbor across each edge
+///
+/// Neighbor `k` shares the edge opposite vertex `k`.
+#[derive(Clone, Copy, Debug)]
+struct Triangle {
+    vertices: [usize; 3],
+    neighbors: [usize; 3],
+    alive: bool,
+}
+
+/// Twice the signed area of (a, b, c), positive if counter-clockwise
+fn orient(a: DVec2, b: DVec2, c: DVec2) -> f64 {
+    (b - a).perp_dot(c - a)
+}
+
+/// Positive if `d` lies inside the circumcircle of the counter-clockwise (a, b, c)
+fn in_circle(a: DVec2, b: DVec2, c: DVec2, d: DVec2) -> f64 {
+    let [a, b, c] = [a - d, b - d, c - d];
+    a.length_squared() * b.perp_dot(c) - b.length_squared() * a.perp_dot(c)
+        + c.length_squared() * a.perp_dot(b)
+}
+
+/// Interleave the bits of two 16 bit coordinates
+fn morton(x: u32, y: u32) -> u32 {
+    let spread = |mut v: u32| {
+        v &= 0xffff;
+        v = (v | (v << 8)) & 0x00ff_00ff;
+        v = (v | (v << 4)) & 0x0f0f_0f0f;
+        v = (v | (v << 2)) & 0x3333_3333;
+        (v | (v << 1)) & 0x5555_5555
+    };
+    spread(x) | (spread(y) << 1)
+}
+
+/// Incremental Bowyer-Watson triangulation
+struct Triangulation {
+    points: Vec<DVec2>,
+    triangles: Vec<Triangle>,
+    /// Triangle the next point location starts from
+    last: usize,
+}
+
+impl Triangulation {
+    /// Triangulation of a super triangle enclosing all points
+    fn new(mut points: Vec<DVec2>, min: DVec2, max: DVec2) -> Self {
+        let center = (min + max) * 0.5;
+        let size = (max - min).max_element().max(1e-12) * 100.0;
+        points.push(center + DVec2::new(-size, -size));
+        points.push(center + DVec2::new(size, -size));
+        points.push(center + DVec2::new(0.0, size));
+        let n = points.len();
+        Self {
+            points,
+            triangles: vec![Triangle {
+                vertices: [n - 3, n - 2, n - 1],
+                neighbors: [NONE; 3],
+                alive: true,
+            }],
+            last: 0,
+        }
+    }
+
+    /// Find a living triangle containing a point
+    fn locate(&self, p: DVec2) -> usize {
+        let mut current = self.last;
+        // Walking terminates on Delaunay triangulations; the step limit only
+        // guards against precision problems
+        for _ in 0..self.triangles.len() {
+            let triangle = &self.triangles[current];
+            let next = (0..3).find_map(|k| {
+                let a = self.points[triangle.vertices[(k + 1) % 3]];
+                let b = self.points[triangle.vertices[(k + 2) % 3]];
+                (orient(a, b, p) < 0.0 && triangle.neighbors[k] != NONE)
+                    .then_some(triangle.neighbors[k])
+            });
+            match next {
+                Some(next) => current = next,
+                None => return current,
+            }
+        }
+        self.triangles
+            .iter()
+            .position(|triangle| {
+                triangle.alive
+                    && (0..3).all(|k| {
+                        let a = self.points[triangle.vertices[(k + 1) % 3]];
+                        let b = self.points[triangle.vertices[(k + 2) % 3]];
+                        orient(a, b, p) >= 0.0
+                    })
+            })
+            .unwrap_or(current)
+    }
+
+    /// Insert a point, returning false if it coincides with an existing vertex
+    fn insert(&mut self, point: usize, tolerance: f64) -> bool {
+        let p = self.points[point];
+        let start = self.locate(p);
+        if self.triangles[start]
+            .vertices
+            .iter()
+            .any(|&v| self.points[v].distance_squared(p) <= tolerance)
+        {
+            return false;
+        }
+
+        // Cavity: connected triangles whose circumcircle contains the point
+        let mut cavity = vec![start];
+        self.triangles[start].alive = false;
+        let mut next = 0;
+        while next < cavity.len() {
+            let triangle = self.triangles[cavity[next]];
+            next += 1;
+            for neighbor in triangle.neighbors {
+                if neighbor == NONE || !self.triangles[neighbor].alive {
+                    continue;
+                }
+                let [a, b, c] = self.triangles[neighbor].vertices.map(|v| self.points[v]);
+                if in_circle(a, b, c, p) > 0.0 {
+                    self.triangles[neighbor].alive = false;
+                    cavity.push(neighbor);
+                }
+            }
+        }
+
+        // Fan of new triangles from the cavity boundary to the point
+        let first = self.triangles.len();
+        for &dead in &cavity {
+            let triangle = self.triangles[dead];
+            for k in 0..3 {
+                let outer = triangle.neighbors[k];
+                if outer != NONE && !self.triangles[outer].alive {
+                    continue;
+                }
+                let (a, b) = (
+                    triangle.vertices[(k + 1) % 3],
+                    triangle.vertices[(k + 2) % 3],
+                );
+                let index = self.triangles.len();
+                self.triangles.push(Triangle {
+                    vertices: [a, b, point],
+                    neighbors: [NONE, NONE, outer],
+                    alive: true,
+                });
+                if outer != NONE {
+                    if let Some(slot) = self.triangles[outer]
+                        .neighbors
+                        .iter_mut()
+                        .find(|neighbor| **neighbor == dead)
+                    {
+                        *slot = index;
+                    }
+                }
+            }
+        }
+
+        // Link the new triangles around the point: the edge (b, point) of
+        // (a, b, point) is shared with the new triangle starting at b
+        let created = first..self.triangles.len();
+        for index in created.clone() {
+            let [a, b, _] = self.triangles[index].vertices;
+            for other in created.clone() {
+                let [other_a, other_b, _] = self.triangles[other].vertices;
+                if other_a == b {
+                    self.triangles[index].neighbors[0] = other;
+                }
+                if other_b == a {
+                    self.triangles[index].neighbors[1] = other;
+                }
+            }
+        }
+        self.last = first;
+        true
+    }
+}
+
+/// Triangulate points in a plane
+///
+/// # Parameters
+/// - `points`: 3D positions
+/// - `axis`: Height axis, the points are projected along it
+///
+/// # Returns
+/// - Triangle indices into `points`, counter-clockwise seen from the positive
+///   height axis
+pub fn delaunay_2d(points: &[[f32; 3]], axis: HeightAxis) -> Vec<u32> {
+    if points.len() < 3 {
+        return Vec::new();
+    }
+    let projected: Vec<DVec2> = points.iter().map(|&p| axis.project(p)).collect();
+    let (min, max) = projected
+        .iter()
+        .fold((projected[0], projected[0]), |(min, max), &p| {
+            (min.min(p), max.max(p))
+        });
+    let extent = (max - min).max(DVec2::splat(1e-12));
+    let tolerance = (extent.max_element() * 1e-9).powi(2);
+
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by_cached_key(|&i| {
+        let cell = (projected[i] - min) / extent * 65535.0;
+        morton(cell.x as u32, cell.y as u32)
+    });
+
+    let mut triangulation = Triangulation::new(projected, min, max);
+    let mut skipped = 0;
+    for point in order {
+        if !triangulation.insert(point, tolerance) {
+            skipped += 1;
+        }
+    }
+    if skipped > 0 {
+        println!(
+            "Delaunay triangulation skipped {} coincident points",
+            skipped
+        );
+    }
+
+    let n = points.len();
+    triangulation
+        .triangles
+        .iter()
+        .filter(|triangle| triangle.alive && triangle.vertices.iter().all(|&v| v < n))
+        .flat_map(|triangle| triangle.vertices.map(|v| v as u32))
+        .collect()
+}
+
+/// Replace the cells of a geometry by the Delaunay triangulation of its points
+///
+/// Point attributes are kept; cell attributes no longer apply and are dropped.
+///
+/// # Parameters
+/// - `geometry`: Geometry whose points are triangulated
+/// - `axis`: Height axis
+/// - `max_edge`: Drop triangles with a longer edge (in the plane), if given
+///
+/// # Returns
+/// - `Ok(GeometryData)`: Surface through the points
+/// - `Err(VtkError)`: Fewer than three points, or all points collinear
+pub fn triangulate_points(
+    geometry: &GeometryData,
+    axis: HeightAxis,
+    max_edge: Option<f32>,
+) -> Result<GeometryData, VtkError> {
+    let mut indices = delaunay_2d(&geometry.vertices, axis);
+    if let Some(max_edge) = max_edge {
+        let max_squared = f64::from(max_edge).powi(2);
+        let project = |v: u32| axis.project(geometry.vertices[v as usize]);
+        indices = indices
+            .chunks_exact(3)
+            .filter(|t| {
+                (0..3)
+                    .all(|k| project(t[k]).distance_squared(project(t[(k + 1) % 3])) <= max_squared)
+            })
+            .flatten()
+            .copied()
+            .collect();
+    }
+    if indices.is_empty() {
+        return Err(VtkError::GenericError(
+            "The points do not span a surface to triangulate".to_string(),
+        ));
+    }
+
+    let attributes = geometry
+        .attributes
+        .iter()
+        .flatten()
+        .filter(|((_, location), _)| *location == AttributeLocation::Point)
+        .map(|(key, attribute)| (key.clone(), attribute.clone()))
+        .collect();
+    let mut triangulated = GeometryData::new(geometry.vertices.clone(), indices, attributes);
+    triangulated.lookup_tables = geometry.lookup_tables.clone();
+    Ok(triangulated)
+}
@@ -0,0 +1,187 @@
+//! Derivatives module
+//!
+//! Divergence and curl of point vector attributes:
+//! - The field is linear over every tetrahedron of the volume cells, or over
+//!   every triangle of surfaces, so its Jacobian is constant per element
+//! - The Jacobian at a point is the volume (or area) weighted average over the
+//!   elements using the point
+//! - On surfaces, derivatives along the surface normal are taken as zero
+use super::{AttributeLocation, AttributeType, GeometryData, VtkError};
+use bevy::math::{Mat3, Vec3};
+
+/// Elements of the mesh with their corner points: tetrahedra of the volume
+/// cells, or the surface triangles if there are no volume cells
+fn elements(geometry: &GeometryData) -> Vec<Vec<u32>> {
+    let point_count = geometry.vertices.len();
+    if let Some(cells) = geometry
+        .volume_cells
+        .as_ref()
+        .filter(|cells| !cells.is_empty())
+    {
+        let mut tetrahedra = Vec::new();
+        for index in 0..cells.len() {
+            let (shape, points) = cells.cell(index);
+            if points.iter().any(|&point| point as usize >= point_count) {
+                continue;
+            }
+            tetrahedra.extend(
+                shape
+                    .tetrahedra()
+                    .iter()
+                    .map(|tetrahedron| tetrahedron.map(|corner| points[corner]).to_vec()),
+            );
+        }
+        if !tetrahedra.is_empty() {
+            return tetrahedra;
+        }
+    }
+    geometry
+        .indices
+        .chunks_exact(3)
+        .filter(|corners| corners.iter().all(|&point| (point as usize) < point_count))
+        .map(|corners| corners.to_vec())
+        .collect()
+}
+
+/// Jacobian of the field over one element with its volume or area
+fn element_jacobian(positions: &[Vec3], vectors: &[Vec3], corners: &[u32]) -> Option<(Mat3, f32)> {
+    let position = |k: usize| positions[corners[k] as usize];
+    let vector = |k: usize| vectors[corners[k] as usize];
+    let (edges, differences, size) = if corners.len() == 4 {
+        let edges = Mat3::from_cols(
+            position(1) - position(0),
+            position(2) - position(0),
+            position(3) - position(0),
+        );
+        let differences = Mat3::from_cols(
+            vector(1) - vector(0),
+            vector(2) - vector(0),
+            vector(3) - vector(0),
+        );
+        (edges, differences, edges.determinant().abs() / 6.0)
+    } else {
+        // The unit normal as third edge keeps derivatives along it at zero
+        let (a, b) = (position(1) - position(0), position(2) - position(0));
+        let normal = a.cross(b);
+        let edges = Mat3::from_cols(a, b, normal.normalize_or_zero());
+        let differences = Mat3::from_cols(vector(1) - vector(0), vector(2) - vector(0), Vec3::ZERO);
+        (edges, differences, normal.length() / 2.0)
+    };
+    if size <= f32::MIN_POSITIVE || edges.determinant().abs() <= f32::MIN_POSITIVE {
+        return None;
+    }
+    let jacobian = differences * edges.inverse();
+    jacobian.is_finite().then_some((jacobian, size))
+}
+
+/// Jacobian of a point vector field at every point
+///
+/// Column `i` of a Jacobian holds the derivative of the vector along axis `i`.
+/// Points outside every element get a zero Jacobian.
+///
+/// # Parameters
+/// - `geometry`: Geometry with volume cells or surface triangles
+/// - `vectors`: One vector per point
+pub fn point_jacobians(geometry: &GeometryData, vectors: &[Vec3]) -> Vec<Mat3> {
+    let positions: Vec<Vec3> = geometry.vertices.iter().map(|&v| Vec3::from(v)).collect();
+    let mut sums = vec![Mat3::ZERO; positions.len()];
+    let mut weights = vec![0.0f32; positions.len()];
+    for corners in elements(geometry) {
+        let Some((jacobian, size)) = element_jacobian(&positions, vectors, &corners) else {
+            continue;
+        };
+        for &point in &corners {
+            sums[point as usize] += jacobian * size;
+            weights[point as usize] += size;
+        }
+    }
+    sums.into_iter()
+        .zip(weights)
+        .map(|(sum, weight)| {
+            if weight > 0.0 {
+                sum * (1.0 / weight)
+            } else {
+                Mat3::ZERO
+            }
+        })
+        .collect()
+}
+
+/// Divergence of a Jacobian
+pub fn divergence(jacobian: &Mat3) -> f32 {
+    jacobian.x_axis.x + jacobian.y_axis.y + jacobian.z_axis.z
+}
+
+/// Curl of a Jacobian
+pub fn curl(jacobian: &Mat3) -> Vec3 {
+    Vec3::new(
+        jacobian.y_axis.z - jacobian.z_axis.y,
+        jacobian.z_axis.x - jacobian.x_axis.z,
+        jacobian.x_axis.y - jacobian.y_axis.x,
+    )
+}
+
+/// Store the divergence (`<attribute>_divergence`, point scalar) and curl
+/// (`<attribute>_curl`, point vector) of a point vector attribute
+///
+/// Existing attributes with the same names are replaced.
+///
+/// # Parameters
+/// - `geometry`: Geometry data receiving the attributes
+/// - `attribute`: Name of the point vector attribute
+/// - `with_divergence`: Add the divergence
+/// - `with_curl`: Add the curl
+///
+/// # Returns
+/// - Names of the added attributes, or an error without the vector attribute
+pub fn add_vector_derivatives(
+    geometry: &mut GeometryData,
+    attribute: &str,
+    with_divergence: bool,
+    with_curl: bool,
+) -> Result<Vec<String>, VtkError> {
+    let vectors: Vec<Vec3> = match geometry.get_attributes(attribute, AttributeLocation::Point) {
+        Some(AttributeType::Vector(data)) if data.len() >= geometry.vertices.len() => data
+            [..geometry.vertices.len()]
+            .iter()
+            .map(|&v| Vec3::from(v))
+            .collect(),
+        _ => {
+            return Err(VtkError::GenericError(format!(
+                "'{}' is not a point vector attribute",
+                attribute
+            )))
+        }
+    };
+    if geometry.indices.is_empty() && geometry.volume_cells.is_none() {
+        return Err(VtkError::GenericError(
+            "The model has no cells to differentiate over".to_string(),
+        ));
+    }
+
+    let jacobians = point_jacobians(geometry, &vectors);
+    let attributes = geometry.attributes.get_or_insert_with(Default::default);
+    let mut names = Vec::new();
+    if with_divergence {
+        let name = format!("{}_divergence", attribute);
+        attributes.insert(
+            (name.clone(), AttributeLocation::Point),
+            AttributeType::Scalar {
+                num_comp: 1,
+                table_name: "default".to_string(),
+                data: jacobians.iter().map(divergence).collect(),
+                lookup_table: None,
+            },
+        );
+        names.push(name);
+    }
+    if with_curl {
+        let name = format!("{}_curl", attribute);
+        attributes.insert(
+            (name.clone(), AttributeLocation::Point),
+            AttributeType::Vector(jacobians.iter().map(|j| curl(j).to_array()).collect()),
+        );
+        names.push(name);
+    }
+    Ok(names)
+}
@@ -0,0 +1,228 @@
+//! Point downsampling module
+//!
+//! Voxel-grid downsampling of large point sets (point clouds, particle files):
+//! all points inside a voxel are merged into one point at their centroid, and
+//! the point attributes are averaged over the merged points.
+//!
+//! The voxel size is searched so that the result stays within a point budget.
+use super::topology::element_count;
+use super::{AttributeType, GeometryData};
+use bevy::math::Vec3;
+use bevy::utils::HashMap;
+
+/// Voxel size search iterations
+const MAX_SEARCH_STEPS: usize = 24;
+
+/// Downsample a point set to at most `point_budget` points
+///
+/// Geometry already within the budget is returned unchanged.
+///
+/// # Parameters
+/// - `geometry`: Point set (triangles are dropped)
+/// - `point_budget`: Maximum number of points of the result
+///
+/// # Returns
+/// - Downsampled geometry with averaged attributes
+pub fn voxel_downsample(geometry: &GeometryData, point_budget: usize) -> GeometryData {
+    let point_budget = point_budget.max(1);
+    if geometry.vertices.len() <= point_budget {
+        return geometry.clone();
+    }
+
+    let (min, max) = geometry.vertices.iter().fold(
+        (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+        |(min, max), vertex| (min.min(Vec3::from(*vertex)), max.max(Vec3::from(*vertex))),
+    );
+    let extent = (max - min).max(Vec3::splat(1e-6));
+
+    // Bisect the voxel size between one that keeps too many points and one
+    // that fits the budget
+    let mut too_small = 0.0_f32;
+    // A single voxel always fits
+    let mut fits = extent.max_element() * 2.0;
+    let mut voxel_size = (extent.x * extent.y * extent.z / point_budget as f32)
+        .cbrt()
+        .min(fits);
+
+    for _ in 0..MAX_SEARCH_STEPS {
+        let count = occupied_voxels(&geometry.vertices, min, voxel_size);
+        if count <= point_budget {
+            fits = voxel_size;
+            // Close enough to the budget
+            if count as f32 >= point_budget as f32 * 0.95 {
+                break;
+            }
+        } else {
+            too_small = voxel_size;
+        }
+        voxel_size = if too_small == 0.0 {
+            fits * 0.5
+        } else {
+            (too_small + fits) * 0.5
+        };
+    }
+
+    voxel_grid_downsample(geometry, min, fits)
+}
+
+/// Integer voxel coordinates of a point
+fn voxel_key(vertex: &[f32; 3], min: Vec3, voxel_size: f32) -> (i32, i32, i32) {
+    let cell = ((Vec3::from(*vertex) - min) / voxel_size).floor();
+    (cell.x as i32, cell.y as i32, cell.z as i32)
+}
+
+/// Number of voxels containing at least one point
+fn occupied_voxels(vertices: &[[f32; 3]], min: Vec3, voxel_size: f32) -> usize {
+    let mut voxels = bevy::utils::HashSet::new();
+    for vertex in vertices {
+        voxels.insert(voxel_key(vertex, min, voxel_size));
+    }
+    voxels.len()
+}
+
+/// Merge the points of every voxel
+fn voxel_grid_downsample(geometry: &GeometryData, min: Vec3, voxel_size: f32) -> GeometryData {
+    let mut voxel_index: HashMap<(i32, i32, i32), usize> = HashMap::new();
+    let mut point_voxels = Vec::with_capacity(geometry.vertices.len());
+    for vertex in &geometry.vertices {
+        let next = voxel_index.len();
+        let voxel = *voxel_index
+            .entry(voxel_key(vertex, min, voxel_size))
+            .or_insert(next);
+        point_voxels.push(voxel);
+    }
+    let voxel_count = voxel_index.len();
+
+    let mut counts = vec![0.0_f32; voxel_count];
+    let mut sums = vec![Vec3::ZERO; voxel_count];
+    for (vertex, &voxel) in geometry.vertices.iter().zip(&point_voxels) {
+        counts[voxel] += 1.0;
+        sums[voxel] += Vec3::from(*vertex);
+    }
+    let vertices = sums
+        .iter()
+        .zip(&counts)
+        .map(|(sum, count)| (*sum / *count).to_array())
+        .collect();
+
+    let point_count = geometry.vertices.len();
+    let attributes = geometry
+        .attributes
+        .as_ref()
+        .map(|attributes| {
+            attributes
+                .iter()
+                .filter_map(|((name, location), attribute)| {
+                    let averaged = if name.starts_with("__lut_") {
+                        attribute.clone()
+                    } else if element_count(attribute) == point_count {
+                        // Vertex cells of point sets map one-to-one to the points
+                        average_attribute(attribute, &point_voxels, &counts)
+                    } else {
+                        println!(
+                            "Downsampling: dropped {:?} attribute '{}' (not per point)",
+                            location, name
+                        );
+                        return None;
+                    };
+                    Some(((name.clone(), location.clone()), averaged))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    println!(
+        "Voxel downsampling: {} -> {} points (voxel size {:.4})",
+        point_count, voxel_count, voxel_size
+    );
+
+    let mut downsampled = GeometryData::new(vertices, Vec::new(), attributes);
+    downsampled.lookup_tables = geometry.lookup_tables.clone();
+    downsampled
+}
+
+/// Average the attribute values of the points merged into every voxel
+///
+/// # Parameters
+/// - `attribute`: Per point attribute
+/// - `point_voxels`: Voxel of every point
+/// - `counts`: Number of points of every voxel
+fn average_attribute(
+    attribute: &AttributeType,
+    point_voxels: &[usize],
+    counts: &[f32],
+) -> AttributeType {
+    // Sum fixed-size components per voxel and divide by the point count
+    fn average<const N: usize>(
+        values: impl Iterator<Item = [f32; N]>,
+        point_voxels: &[usize],
+        counts: &[f32],
+    ) -> Vec<[f32; N]> {
+        let mut sums = vec![[0.0; N]; counts.len()];
+        for (value, &voxel) in values.zip(point_voxels) {
+            for (sum, component) in sums[voxel].iter_mut().zip(value) {
+                *sum += component;
+            }
+        }
+        for (sum, count) in sums.iter_mut().zip(counts) {
+            for component in sum.iter_mut() {
+                *component /= count;
+            }
+        }
+        sums
+    }
+
+    match attribute {
+        AttributeType::Scalar {
+            num_comp,
+            table_name,
+            data,
+            lookup_table,
+        } => {
+            let num_comp = (*num_comp).max(1);
+            let mut sums = vec![0.0; counts.len() * num_comp];
+            for (point, &voxel) in point_voxels.iter().enumerate() {
+                for component in 0..num_comp {
+                    sums[voxel * num_comp + component] += data[point * num_comp + component];
+                }
+            }
+            for (index, sum) in sums.iter_mut().enumerate() {
+                *sum /= counts[index / num_comp];
+            }
+            AttributeType::Scalar {
+                num_comp,
+                table_name: table_name.clone(),
+                data: sums,
+                lookup_table: lookup_table.clone(),
+            }
+        }
+        AttributeType::ColorScalar { nvalues, data } => {
+            let mut sums = vec![vec![0.0; *nvalues as usize]; counts.len()];
+            for (values, &voxel) in data.iter().zip(point_voxels) {
+                for (sum, value) in sums[voxel].iter_mut().zip(values) {
+                    *sum += value;
+                }
+            }
+            for (sum, count) in sums.iter_mut().zip(counts) {
+                for value in sum.iter_mut() {
+                    *value /= count;
+                }
+            }
+            AttributeType::ColorScalar {
+                nvalues: *nvalues,
+                data: sums,
+            }
+        }
+        AttributeType::Vector(data) => {
+            AttributeType::Vector(average(data.iter().copied(), point_voxels, counts))
+        }
+        AttributeType::Tensor(data) => {
+            AttributeType::Tensor(average(data.iter().copied(), point_voxels, counts))
+        }
+    }
+}
+
+/// Whether the geometry is a point set (no surface triangles)
+pub fn is_point_set(geometry: &GeometryData) -> bool {
+    geometry.indices.is_empty() && !geometry.vertices.is_empty()
+}
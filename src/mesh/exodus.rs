@@ -0,0 +1,370 @@
+//! Exodus II import module
+//!
+//! Reads Exodus II files (`.e`, `.exo`, `.ex2`) stored as netCDF classic files:
+//! - Node coordinates (`coordx`/`coordy`/`coordz` or `coord`)
+//! - Element blocks (`connect1`, `connect2`, ...), one cell per element; the
+//!   element faces are triangulated and the block id is kept as cell scalar
+//!   `block_id`
+//! - Nodal variables as point scalars and element variables as cell scalars, at a
+//!   selected time step (`time_whole`)
+//!
+//! Line and point elements (bars, beams, spheres) keep their cell number but are
+//! not rendered.
+use super::netcdf::NetcdfFile;
+use super::{AttributeLocation, AttributeType, GeometryData, VtkError};
+use bevy::utils::HashMap;
+use std::path::Path;
+
+/// Faces of a tetrahedron, outward
+pub const TETRA_FACES: [&[usize]; 4] = [&[0, 1, 3], &[1, 2, 3], &[0, 3, 2], &[0, 2, 1]];
+/// Faces of a hexahedron, outward
+pub const HEX_FACES: [&[usize]; 6] = [
+    &[0, 1, 5, 4],
+    &[1, 2, 6, 5],
+    &[2, 3, 7, 6],
+    &[3, 0, 4, 7],
+    &[0, 3, 2, 1],
+    &[4, 5, 6, 7],
+];
+/// Faces of a wedge, outward
+pub const WEDGE_FACES: [&[usize]; 5] = [
+    &[0, 2, 1],
+    &[3, 4, 5],
+    &[0, 1, 4, 3],
+    &[1, 2, 5, 4],
+    &[2, 0, 3, 5],
+];
+/// Faces of a pyramid, outward
+pub const PYRAMID_FACES: [&[usize]; 5] = [
+    &[0, 3, 2, 1],
+    &[0, 1, 4],
+    &[1, 2, 4],
+    &[2, 3, 4],
+    &[3, 0, 4],
+];
+
+/// Whether the path has an Exodus II extension
+pub fn is_exodus_file(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("e" | "exo" | "ex2")
+    )
+}
+
+/// Faces of an element type, by the Exodus element type name
+fn element_faces(element_type: &str) -> Option<&'static [&'static [usize]]> {
+    let element_type = element_type.to_ascii_uppercase();
+    if element_type.starts_with("TET") {
+        Some(&TETRA_FACES)
+    } else if element_type.starts_with("HEX") {
+        Some(&HEX_FACES)
+    } else if element_type.starts_with("WEDGE") {
+        Some(&WEDGE_FACES)
+    } else if element_type.starts_with("PYRAMID") {
+        Some(&PYRAMID_FACES)
+    } else if element_type.starts_with("TRI") {
+        Some(&[&[0, 1, 2]])
+    } else if element_type.starts_with("QUAD") || element_type.starts_with("SHELL") {
+        Some(&[&[0, 1, 2, 3]])
+    } else {
+        None
+    }
+}
+
+/// Exodus II file held in memory
+pub struct ExodusFile {
+    file: NetcdfFile,
+}
+
+impl ExodusFile {
+    /// Read an Exodus II file
+    pub fn open(path: &Path) -> Result<Self, VtkError> {
+        let file = NetcdfFile::open(path)?;
+        if file.dimension_length("num_nodes").is_none() {
+            return Err(VtkError::InvalidFormat(
+                "netCDF file is not an Exodus II file",
+            ));
+        }
+        Ok(Self { file })
+    }
+
+    /// Physical times of the time steps
+    pub fn times(&self) -> Vec<f32> {
+        self.file
+            .read("time_whole")
+            .map(|times| times.into_iter().map(|t| t as f32).collect())
+            .unwrap_or_default()
+    }
+
+    /// Names of the nodal variables
+    pub fn nodal_variable_names(&self) -> Vec<String> {
+        self.variable_names("name_nod_var", "num_nod_var")
+    }
+
+    /// Names of the element variables
+    pub fn element_variable_names(&self) -> Vec<String> {
+        self.variable_names("name_elem_var", "num_elem_var")
+    }
+
+    /// Variable names, or numbered names if the name variable is missing
+    fn variable_names(&self, names: &str, count: &str) -> Vec<String> {
+        let count = self.file.dimension_length(count).unwrap_or(0);
+        let mut names = self.file.read_strings(names).unwrap_or_default();
+        names.resize(count, String::new());
+        names
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| {
+                if name.is_empty() {
+                    format!("variable_{}", i + 1)
+                } else {
+                    name
+                }
+            })
+            .collect()
+    }
+
+    /// Values of a nodal variable at a time step
+    ///
+    /// # Parameters
+    /// - `variable`: Index of the nodal variable
+    /// - `step`: Time step index
+    pub fn nodal_variable(&self, variable: usize, step: usize) -> Result<Vec<f32>, VtkError> {
+        let node_count = self.file.dimension_length("num_nodes").unwrap_or(0);
+        let values = match self
+            .file
+            .read_record(&format!("vals_nod_var{}", variable + 1), step)
+        {
+            Ok(values) => values,
+            // Files of old writers keep all nodal variables in one array
+            Err(_) => {
+                let all = self.file.read_record("vals_nod_var", step)?;
+                all.get(variable * node_count..(variable + 1) * node_count)
+                    .ok_or(VtkError::MissingData("Nodal variable data is incomplete"))?
+                    .to_vec()
+            }
+        };
+        Ok(values.into_iter().map(|v| v as f32).collect())
+    }
+
+    /// Node coordinates, z = 0 for 2D meshes
+    fn coordinates(&self) -> Result<Vec<[f32; 3]>, VtkError> {
+        let node_count = self
+            .file
+            .dimension_length("num_nodes")
+            .ok_or(VtkError::MissingData("Exodus file has no nodes"))?;
+        let dimension = self.file.dimension_length("num_dim").unwrap_or(3).min(3);
+
+        let axes: Vec<Vec<f64>> = if self.file.variable("coordx").is_some() {
+            ["coordx", "coordy", "coordz"][..dimension]
+                .iter()
+                .map(|name| self.file.read(name))
+                .collect::<Result<_, _>>()?
+        } else {
+            let coord = self.file.read("coord")?;
+            coord
+                .chunks(node_count.max(1))
+                .take(dimension)
+                .map(|axis| axis.to_vec())
+                .collect()
+        };
+        if axes.iter().any(|axis| axis.len() < node_count) {
+            return Err(VtkError::MissingData("Exodus coordinates are incomplete"));
+        }
+
+        Ok((0..node_count)
+            .map(|node| std::array::from_fn(|axis| axes.get(axis).map_or(0.0, |a| a[node] as f32)))
+            .collect())
+    }
+
+    /// Geometry of all element blocks with the variables at a time step
+    ///
+    /// # Parameters
+    /// - `step`: Time step index for nodal and element variables
+    ///
+    /// # Returns
+    /// - `Ok(GeometryData)`: Triangulated element faces, one cell per element
+    /// - `Err(VtkError)`: Missing coordinates or invalid connectivity
+    pub fn geometry(&self, step: usize) -> Result<GeometryData, VtkError> {
+        let vertices = self.coordinates()?;
+        let block_count = self.file.dimension_length("num_el_blk").unwrap_or(0);
+        let block_ids = self.file.read("eb_prop1").unwrap_or_default();
+
+        let mut indices = Vec::new();
+        let mut triangle_to_cell = Vec::new();
+        let mut cell_block_ids = Vec::new();
+        let mut block_sizes = Vec::new();
+        for block in 0..block_count {
+            let name = format!("connect{}", block + 1);
+            let Some(variable) = self.file.variable(&name) else {
+                block_sizes.push(0);
+                continue;
+            };
+            let shape = self.file.shape(variable);
+            let (element_count, nodes_per_element) = (shape[0], shape.get(1).copied().unwrap_or(1));
+            let element_type = variable
+                .attribute("elem_type")
+                .and_then(|value| value.as_text())
+                .unwrap_or("")
+                .to_string();
+            let connectivity = self.file.read(&name)?;
+
+            let faces = element_faces(&element_type).filter(|faces| {
+                faces
+                    .iter()
+                    .flat_map(|face| face.iter())
+                    .all(|&corner| corner < nodes_per_element)
+            });
+            if faces.is_none() {
+                println!(
+                    "Element block {} ({}) is not rendered",
+                    block + 1,
+                    element_type
+                );
+            }
+
+            for element in connectivity.chunks_exact(nodes_per_element.max(1)) {
+                let cell = cell_block_ids.len();
+                for face in faces.unwrap_or(&[]) {
+                    // Connectivity is 1-based
+                    let corners: Vec<u32> = face
+                        .iter()
+                        .map(|&corner| (element[corner] as u32).wrapping_sub(1))
+                        .collect();
+                    if let Some(&corner) = corners.iter().find(|&&c| c as usize >= vertices.len()) {
+                        return Err(VtkError::IndexOutOfBounds {
+                            index: corner as usize,
+                            max: vertices.len().saturating_sub(1),
+                        });
+                    }
+                    for i in 1..corners.len() - 1 {
+                        indices.extend_from_slice(&[corners[0], corners[i], corners[i + 1]]);
+                        triangle_to_cell.push(cell);
+                    }
+                }
+                cell_block_ids
+                    .push(block_ids.get(block).copied().unwrap_or(block as f64 + 1.0) as f32);
+            }
+            block_sizes.push(element_count);
+        }
+
+        let mut attributes = HashMap::new();
+        attributes.insert(
+            ("block_id".to_string(), AttributeLocation::Cell),
+            scalar(cell_block_ids),
+        );
+
+        if step < self.file.record_count {
+            for (variable, name) in self.nodal_variable_names().into_iter().enumerate() {
+                match self.nodal_variable(variable, step) {
+                    Ok(values) => {
+                        attributes.insert((name, AttributeLocation::Point), scalar(values));
+                    }
+                    Err(e) => println!("Skipping nodal variable {}: {:?}", name, e),
+                }
+            }
+            for (variable, name) in self.element_variable_names().into_iter().enumerate() {
+                // Blocks without the variable (truth table) are filled with zeros
+                let mut values = Vec::new();
+                for (block, &size) in block_sizes.iter().enumerate() {
+                    let array = format!("vals_elem_var{}eb{}", variable + 1, block + 1);
+                    match self.file.read_record(&array, step) {
+                        Ok(block_values) if block_values.len() == size => {
+                            values.extend(block_values.into_iter().map(|v| v as f32))
+                        }
+                        _ => values.resize(values.len() + size, 0.0),
+                    }
+                }
+                attributes.insert((name, AttributeLocation::Cell), scalar(values));
+            }
+        }
+
+        println!(
+            "Parsed Exodus file: {} nodes, {} elements in {} blocks, {} triangles, time step {} of {}",
+            vertices.len(),
+            block_sizes.iter().sum::<usize>(),
+            block_count,
+            indices.len() / 3,
+            step,
+            self.file.record_count
+        );
+
+        Ok(GeometryData::new(vertices, indices, attributes)
+            .add_triangle_to_cell_mapping(triangle_to_cell))
+    }
+}
+
+/// Single-component scalar attribute
+fn scalar(data: Vec<f32>) -> AttributeType {
+    AttributeType::Scalar {
+        num_comp: 1,
+        table_name: "default".to_string(),
+        data,
+        lookup_table: None,
+    }
+}
+
+/// Load an Exodus II file with the variables of the first time step
+pub fn load_exodus(path: &Path) -> Result<GeometryData, VtkError> {
+    ExodusFile::open(path)?.geometry(0)
+}
+
+/// Load the first nodal variable at every time step
+///
+/// # Parameters
+/// - `path`: Exodus file path
+///
+/// # Returns
+/// - `Ok(Vec<(f32, Vec<f32>)>)`: Physical time and nodal values of every time step
+/// - `Err(VtkError)`: The file cannot be read or has no nodal variables
+pub fn load_nodal_time_series(path: &Path) -> Result<Vec<(f32, Vec<f32>)>, VtkError> {
+    let exodus = ExodusFile::open(path)?;
+    if exodus.nodal_variable_names().is_empty() {
+        return Err(VtkError::MissingData("Exodus file has no nodal variables"));
+    }
+
+    let times = exodus.times();
+    (0..exodus.file.record_count)
+        .map(|step| {
+            let time = times.get(step).copied().unwrap_or(step as f32);
+            Ok((time, exodus.nodal_variable(0, step)?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Load `header` as an Exodus file from a temporary path
+    fn load_header(name: &str, header: &[u8]) -> Result<GeometryData, VtkError> {
+        let path = std::env::temp_dir().join(format!("{}-{}.exo", name, std::process::id()));
+        std::fs::write(&path, header).unwrap();
+        let result = load_exodus(&path);
+        let _ = std::fs::remove_file(&path);
+        result
+    }
+
+    #[test]
+    fn truncated_header_is_an_error() {
+        assert!(load_header("truncated", b"CDF\x01\x00\x00").is_err());
+    }
+
+    #[test]
+    fn oversized_dimension_count_is_an_error() {
+        // No records, then a dimension list claiming 2^31 - 1 dimensions
+        let header = b"CDF\x01\x00\x00\x00\x00\x00\x00\x00\x0A\x7F\xFF\xFF\xFF";
+        assert!(load_header("dimensions", header).is_err());
+    }
+
+    #[test]
+    fn oversized_attribute_length_is_an_error() {
+        // No records or dimensions, then a float attribute "a" of 2^31 - 1 values
+        let mut header = b"CDF\x01\x00\x00\x00\x00".to_vec();
+        header.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0]);
+        header.extend_from_slice(&[0, 0, 0, 0x0C, 0, 0, 0, 1]);
+        header.extend_from_slice(&[0, 0, 0, 1, b'a', 0, 0, 0]);
+        header.extend_from_slice(&[0, 0, 0, 5, 0x7F, 0xFF, 0xFF, 0xFF]);
+        assert!(load_header("attribute", &header).is_err());
+    }
+}
@@ -0,0 +1,769 @@
+//! Data export module
+//!
+//! Writes geometry and attribute values to interchange formats:
+//! - CSV tables of cell or point values (also used for clipboard copies)
+//! - Legacy ASCII VTK polydata
+//! - XML VTK unstructured grids (`.vtu`, ASCII)
+//! - XML VTK image data (`.vti`, ASCII) of regular grid volumes
+//! - Binary STL
+//! - Binary glTF (`.glb`) with optional vertex colors, of one model or a scene of
+//!   transformed models
+use super::topology::{
+    attribute_components, attribute_value, cell_attribute_values, cell_triangles, cell_vertices,
+};
+use super::volume::VolumeData;
+use super::{AttributeLocation, AttributeType, GeometryData, VtkError};
+use bevy::math::Vec3;
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Sorted attributes of the geometry (lookup tables excluded)
+fn sorted_attributes(
+    geometry: &GeometryData,
+) -> Vec<(&String, &AttributeLocation, &AttributeType)> {
+    let mut attributes: Vec<_> = geometry
+        .attributes
+        .iter()
+        .flatten()
+        .filter(|((name, _), _)| !name.starts_with("__lut_"))
+        .map(|((name, location), attribute)| (name, location, attribute))
+        .collect();
+    attributes.sort_by_key(|(name, location, _)| {
+        ((*name).clone(), **location == AttributeLocation::Cell)
+    });
+    attributes
+}
+
+/// CSV column headers of an attribute (one column per component)
+fn column_headers(
+    name: &str,
+    location: &AttributeLocation,
+    attribute: &AttributeType,
+) -> Vec<String> {
+    let prefix = match location {
+        AttributeLocation::Point => format!("{} (point avg)", name),
+        AttributeLocation::Cell => name.to_string(),
+    };
+    let components = attribute_components(attribute);
+    match attribute {
+        AttributeType::Vector(_) => ["x", "y", "z"]
+            .iter()
+            .map(|axis| format!("{}_{}", prefix, axis))
+            .collect(),
+        _ if components == 1 => vec![prefix],
+        _ => (0..components)
+            .map(|c| format!("{}_{}", prefix, c))
+            .collect(),
+    }
+}
+
+/// Quote a CSV field if necessary
+pub fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Build a CSV table with one row per selected cell
+///
+/// Columns: cell id, cell center and every attribute component. Point attributes
+/// are averaged over the vertices of each cell.
+///
+/// # Parameters
+/// - `geometry`: Geometry data
+/// - `cells`: Original cell indices
+///
+/// # Returns
+/// - CSV text including a header row
+pub fn cells_to_csv(geometry: &GeometryData, cells: &BTreeSet<usize>) -> String {
+    let triangles = cell_triangles(geometry);
+    let vertices = cell_vertices(geometry, &triangles);
+    let attributes = sorted_attributes(geometry);
+
+    let mut headers: Vec<String> = ["cell_id", "center_x", "center_y", "center_z"]
+        .iter()
+        .map(|h| h.to_string())
+        .collect();
+    let mut columns: Vec<Vec<f32>> = Vec::new();
+    for (name, location, attribute) in &attributes {
+        headers.extend(column_headers(name, location, attribute));
+        for component in 0..attribute_components(attribute) {
+            columns.push(cell_attribute_values(
+                attribute,
+                location,
+                Some(component),
+                &vertices,
+            ));
+        }
+    }
+
+    let mut csv = headers
+        .iter()
+        .map(|h| csv_field(h))
+        .collect::<Vec<_>>()
+        .join(",");
+    csv.push('\n');
+
+    for &cell in cells {
+        let Some(cell_vertices) = vertices.get(cell).filter(|v| !v.is_empty()) else {
+            continue;
+        };
+        let center = cell_vertices
+            .iter()
+            .map(|&v| Vec3::from(geometry.vertices[v as usize]))
+            .sum::<Vec3>()
+            / cell_vertices.len() as f32;
+
+        let _ = write!(csv, "{},{},{},{}", cell, center.x, center.y, center.z);
+        for column in &columns {
+            let _ = write!(csv, ",{}", column[cell]);
+        }
+        csv.push('\n');
+    }
+
+    csv
+}
+
+/// Build a CSV table for a single point
+///
+/// Columns: point id, position and every point attribute component.
+pub fn point_to_csv(geometry: &GeometryData, vertex: u32) -> String {
+    let point_attributes: Vec<_> = sorted_attributes(geometry)
+        .into_iter()
+        .filter(|(_, location, _)| **location == AttributeLocation::Point)
+        .collect();
+
+    let mut headers: Vec<String> = ["point_id", "x", "y", "z"]
+        .iter()
+        .map(|h| h.to_string())
+        .collect();
+    let mut row = vec![vertex.to_string()];
+    if let Some(position) = geometry.vertices.get(vertex as usize) {
+        row.extend(position.iter().map(|c| c.to_string()));
+    }
+
+    for (name, location, attribute) in point_attributes {
+        headers.extend(column_headers(name, location, attribute));
+        row.extend(
+            (0..attribute_components(attribute))
+                .map(|c| attribute_value(attribute, vertex as usize, Some(c)).to_string()),
+        );
+    }
+
+    format!(
+        "{}\n{}\n",
+        headers
+            .iter()
+            .map(|h| csv_field(h))
+            .collect::<Vec<_>>()
+            .join(","),
+        row.join(",")
+    )
+}
+
+/// Write attribute values of one location in legacy VTK ASCII syntax
+fn write_vtk_attributes(
+    vtk: &mut String,
+    geometry: &GeometryData,
+    location: AttributeLocation,
+    element_count: usize,
+    element_index: impl Fn(usize) -> usize,
+) {
+    let attributes: Vec<_> = sorted_attributes(geometry)
+        .into_iter()
+        .filter(|(_, attribute_location, _)| **attribute_location == location)
+        .collect();
+    if attributes.is_empty() {
+        return;
+    }
+
+    let section = match location {
+        AttributeLocation::Point => "POINT_DATA",
+        AttributeLocation::Cell => "CELL_DATA",
+    };
+    let _ = writeln!(vtk, "{} {}", section, element_count);
+
+    for (name, _, attribute) in attributes {
+        // Legacy VTK names must not contain whitespace
+        let name = name.replace(char::is_whitespace, "_");
+        let _ = match attribute {
+            AttributeType::Scalar { num_comp, .. } => writeln!(
+                vtk,
+                "SCALARS {} float {}\nLOOKUP_TABLE default",
+                name,
+                (*num_comp).max(1)
+            ),
+            AttributeType::ColorScalar { nvalues, .. } => {
+                writeln!(vtk, "COLOR_SCALARS {} {}", name, nvalues)
+            }
+            AttributeType::Vector(_) => writeln!(vtk, "VECTORS {} float", name),
+            AttributeType::Tensor(_) => writeln!(vtk, "TENSORS {} float", name),
+        };
+
+        let components = attribute_components(attribute);
+        for element in 0..element_count {
+            let source = element_index(element);
+            let values: Vec<String> = (0..components)
+                .map(|c| attribute_value(attribute, source, Some(c)).to_string())
+                .collect();
+            let _ = writeln!(vtk, "{}", values.join(" "));
+        }
+    }
+}
+
+/// Write geometry as legacy ASCII VTK polydata
+///
+/// Triangles are written as polygons; cell attributes are repeated for every
+/// triangle of the original cell. Point sets are written as vertices.
+///
+/// # Parameters
+/// - `geometry`: Geometry to write
+/// - `path`: Output file path
+///
+/// # Returns
+/// - `Ok(())`: File written
+/// - `Err(VtkError::IoError)`: Writing failed
+pub fn write_vtk_ascii(geometry: &GeometryData, path: &Path) -> Result<(), VtkError> {
+    let triangle_count = geometry.indices.len() / 3;
+    let mut vtk = String::new();
+
+    let _ = writeln!(vtk, "# vtk DataFile Version 3.0");
+    let _ = writeln!(vtk, "Exported by data_visualization");
+    let _ = writeln!(vtk, "ASCII\nDATASET POLYDATA");
+    let _ = writeln!(vtk, "POINTS {} float", geometry.vertices.len());
+    for vertex in &geometry.vertices {
+        let _ = writeln!(vtk, "{} {} {}", vertex[0], vertex[1], vertex[2]);
+    }
+
+    let point_set = geometry.indices.is_empty();
+    if point_set {
+        let point_count = geometry.vertices.len();
+        let _ = writeln!(vtk, "VERTICES {} {}", point_count, point_count * 2);
+        for point in 0..point_count {
+            let _ = writeln!(vtk, "1 {}", point);
+        }
+    } else {
+        let _ = writeln!(vtk, "POLYGONS {} {}", triangle_count, triangle_count * 4);
+        for triangle in geometry.indices.chunks_exact(3) {
+            let _ = writeln!(vtk, "3 {} {} {}", triangle[0], triangle[1], triangle[2]);
+        }
+    }
+
+    write_vtk_attributes(
+        &mut vtk,
+        geometry,
+        AttributeLocation::Point,
+        geometry.vertices.len(),
+        |vertex| vertex,
+    );
+
+    if point_set {
+        write_vtk_attributes(
+            &mut vtk,
+            geometry,
+            AttributeLocation::Cell,
+            geometry.vertices.len(),
+            |point| point,
+        );
+    } else {
+        write_vtk_attributes(
+            &mut vtk,
+            geometry,
+            AttributeLocation::Cell,
+            triangle_count,
+            |triangle| super::topology::triangle_cell(geometry, triangle),
+        );
+    }
+
+    std::fs::write(path, vtk).map_err(VtkError::IoError)
+}
+
+/// Vertex normals of the geometry, area-weighted from the triangles if absent
+fn vertex_normals(geometry: &GeometryData) -> Vec<[f32; 3]> {
+    if let Some(normals) = geometry
+        .normals
+        .as_ref()
+        .filter(|normals| normals.len() == geometry.vertices.len())
+    {
+        return normals.clone();
+    }
+    smooth_normals(&geometry.vertices, &geometry.indices)
+}
+
+/// Area-weighted vertex normals of a triangle list
+pub fn smooth_normals(vertices: &[[f32; 3]], indices: &[u32]) -> Vec<[f32; 3]> {
+    let mut normals = vec![Vec3::ZERO; vertices.len()];
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| Vec3::from(vertices[triangle[i] as usize]));
+        let face_normal = (b - a).cross(c - a);
+        for &index in triangle {
+            normals[index as usize] += face_normal;
+        }
+    }
+    normals
+        .into_iter()
+        .map(|normal| normal.normalize_or(Vec3::Y).to_array())
+        .collect()
+}
+
+/// Write geometry as XML VTK unstructured grid (`.vtu`, ASCII)
+///
+/// Triangles become VTK_TRIANGLE cells; cell attributes are repeated for every
+/// triangle of the original cell. Point sets are written with one VTK_VERTEX
+/// cell per point.
+///
+/// # Parameters
+/// - `geometry`: Geometry to write
+/// - `path`: Output file path
+///
+/// # Returns
+/// - `Ok(())`: File written
+/// - `Err(VtkError::IoError)`: Writing failed
+pub fn write_vtu_ascii(geometry: &GeometryData, path: &Path) -> Result<(), VtkError> {
+    let point_set = geometry.indices.is_empty();
+    // VTK_VERTEX or VTK_TRIANGLE
+    let (cell_size, cell_type) = if point_set { (1, 1) } else { (3, 5) };
+    let cell_count = if point_set {
+        geometry.vertices.len()
+    } else {
+        geometry.indices.len() / 3
+    };
+    let mut vtu = String::new();
+
+    let _ = writeln!(vtu, "<?xml version=\"1.0\"?>");
+    let _ = writeln!(
+        vtu,
+        "<VTKFile type=\"UnstructuredGrid\" version=\"0.1\" byte_order=\"LittleEndian\">"
+    );
+    let _ = writeln!(vtu, "<UnstructuredGrid>");
+    let _ = writeln!(
+        vtu,
+        "<Piece NumberOfPoints=\"{}\" NumberOfCells=\"{}\">",
+        geometry.vertices.len(),
+        cell_count
+    );
+
+    for (location, section, element_count) in [
+        (
+            AttributeLocation::Point,
+            "PointData",
+            geometry.vertices.len(),
+        ),
+        (AttributeLocation::Cell, "CellData", cell_count),
+    ] {
+        let _ = writeln!(vtu, "<{}>", section);
+        for (name, _, attribute) in sorted_attributes(geometry)
+            .into_iter()
+            .filter(|(_, attribute_location, _)| **attribute_location == location)
+        {
+            let components = attribute_components(attribute);
+            let _ = writeln!(
+                vtu,
+                "<DataArray type=\"Float32\" Name=\"{}\" NumberOfComponents=\"{}\" format=\"ascii\">",
+                name.replace('"', "&quot;"),
+                components
+            );
+            for element in 0..element_count {
+                let source = match location {
+                    AttributeLocation::Point => element,
+                    AttributeLocation::Cell if point_set => element,
+                    AttributeLocation::Cell => super::topology::triangle_cell(geometry, element),
+                };
+                let values: Vec<String> = (0..components)
+                    .map(|c| attribute_value(attribute, source, Some(c)).to_string())
+                    .collect();
+                let _ = writeln!(vtu, "{}", values.join(" "));
+            }
+            let _ = writeln!(vtu, "</DataArray>");
+        }
+        let _ = writeln!(vtu, "</{}>", section);
+    }
+
+    let _ = writeln!(vtu, "<Points>");
+    let _ = writeln!(
+        vtu,
+        "<DataArray type=\"Float32\" NumberOfComponents=\"3\" format=\"ascii\">"
+    );
+    for vertex in &geometry.vertices {
+        let _ = writeln!(vtu, "{} {} {}", vertex[0], vertex[1], vertex[2]);
+    }
+    let _ = writeln!(vtu, "</DataArray>\n</Points>");
+
+    let _ = writeln!(vtu, "<Cells>");
+    let _ = writeln!(
+        vtu,
+        "<DataArray type=\"Int64\" Name=\"connectivity\" format=\"ascii\">"
+    );
+    if point_set {
+        for point in 0..cell_count {
+            let _ = writeln!(vtu, "{}", point);
+        }
+    } else {
+        for triangle in geometry.indices.chunks_exact(3) {
+            let _ = writeln!(vtu, "{} {} {}", triangle[0], triangle[1], triangle[2]);
+        }
+    }
+    let _ = writeln!(vtu, "</DataArray>");
+    let _ = writeln!(
+        vtu,
+        "<DataArray type=\"Int64\" Name=\"offsets\" format=\"ascii\">"
+    );
+    for cell in 1..=cell_count {
+        let _ = writeln!(vtu, "{}", cell * cell_size);
+    }
+    let _ = writeln!(vtu, "</DataArray>");
+    let _ = writeln!(
+        vtu,
+        "<DataArray type=\"UInt8\" Name=\"types\" format=\"ascii\">"
+    );
+    for _ in 0..cell_count {
+        let _ = writeln!(vtu, "{}", cell_type);
+    }
+    let _ = writeln!(vtu, "</DataArray>\n</Cells>");
+
+    let _ = writeln!(vtu, "</Piece>\n</UnstructuredGrid>\n</VTKFile>");
+
+    std::fs::write(path, vtu).map_err(VtkError::IoError)
+}
+
+/// Write a volume as XML VTK image data (`.vti`, ASCII)
+///
+/// # Parameters
+/// - `volume`: Regular grid with point and cell data
+/// - `path`: Output file path
+///
+/// # Returns
+/// - `Ok(())`: File written
+/// - `Err(VtkError::IoError)`: Writing failed
+pub fn write_vti_ascii(volume: &VolumeData, path: &Path) -> Result<(), VtkError> {
+    let extent = volume
+        .dims
+        .iter()
+        .map(|n| format!("0 {}", n.saturating_sub(1)))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut attributes: Vec<_> = volume
+        .attributes
+        .iter()
+        .filter(|((name, _), _)| !name.starts_with("__lut_"))
+        .collect();
+    attributes
+        .sort_by_key(|((name, location), _)| (name.clone(), *location == AttributeLocation::Cell));
+    let mut vti = String::new();
+
+    let _ = writeln!(vti, "<?xml version=\"1.0\"?>");
+    let _ = writeln!(
+        vti,
+        "<VTKFile type=\"ImageData\" version=\"0.1\" byte_order=\"LittleEndian\">"
+    );
+    let _ = writeln!(
+        vti,
+        "<ImageData WholeExtent=\"{}\" Origin=\"{} {} {}\" Spacing=\"{} {} {}\">",
+        extent,
+        volume.origin[0],
+        volume.origin[1],
+        volume.origin[2],
+        volume.spacing[0],
+        volume.spacing[1],
+        volume.spacing[2]
+    );
+    let _ = writeln!(vti, "<Piece Extent=\"{}\">", extent);
+
+    for (location, section, element_count) in [
+        (AttributeLocation::Point, "PointData", volume.point_count()),
+        (
+            AttributeLocation::Cell,
+            "CellData",
+            volume.cell_dims().iter().product(),
+        ),
+    ] {
+        let _ = writeln!(vti, "<{}>", section);
+        for ((name, _), attribute) in attributes
+            .iter()
+            .filter(|((_, attribute_location), _)| *attribute_location == location)
+        {
+            let components = attribute_components(attribute);
+            let _ = writeln!(
+                vti,
+                "<DataArray type=\"Float32\" Name=\"{}\" NumberOfComponents=\"{}\" format=\"ascii\">",
+                name.replace('"', "&quot;"),
+                components
+            );
+            for element in 0..element_count {
+                let values: Vec<String> = (0..components)
+                    .map(|c| attribute_value(attribute, element, Some(c)).to_string())
+                    .collect();
+                let _ = writeln!(vti, "{}", values.join(" "));
+            }
+            let _ = writeln!(vti, "</DataArray>");
+        }
+        let _ = writeln!(vti, "</{}>", section);
+    }
+
+    let _ = writeln!(vti, "</Piece>\n</ImageData>\n</VTKFile>");
+
+    std::fs::write(path, vti).map_err(VtkError::IoError)
+}
+
+/// Write the triangles as binary STL
+///
+/// # Parameters
+/// - `geometry`: Geometry to write (attributes are not stored in STL)
+/// - `path`: Output file path
+///
+/// # Returns
+/// - `Ok(())`: File written
+/// - `Err(VtkError::IoError)`: Writing failed
+pub fn write_stl_binary(geometry: &GeometryData, path: &Path) -> Result<(), VtkError> {
+    let triangle_count = geometry.indices.len() / 3;
+    let mut stl = Vec::with_capacity(84 + triangle_count * 50);
+
+    let mut header = [0u8; 80];
+    let title = b"Exported by data_visualization";
+    header[..title.len()].copy_from_slice(title);
+    stl.extend_from_slice(&header);
+    stl.extend_from_slice(&(triangle_count as u32).to_le_bytes());
+
+    for triangle in geometry.indices.chunks_exact(3) {
+        let corners = [0, 1, 2].map(|i| Vec3::from(geometry.vertices[triangle[i] as usize]));
+        let normal = (corners[1] - corners[0])
+            .cross(corners[2] - corners[0])
+            .normalize_or_zero();
+        for value in std::iter::once(normal)
+            .chain(corners)
+            .flat_map(|v| v.to_array())
+        {
+            stl.extend_from_slice(&value.to_le_bytes());
+        }
+        // Attribute byte count
+        stl.extend_from_slice(&0u16.to_le_bytes());
+    }
+
+    std::fs::write(path, stl).map_err(VtkError::IoError)
+}
+
+/// Model of a glTF scene: one mesh with its node transform
+pub struct GlbNode {
+    pub name: String,
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    /// Linear RGBA color per vertex
+    pub colors: Option<Vec<[f32; 4]>>,
+    /// Triangle list
+    pub indices: Vec<u32>,
+    pub translation: [f32; 3],
+    /// Rotation quaternion (x, y, z, w)
+    pub rotation: [f32; 4],
+    pub scale: [f32; 3],
+}
+
+/// Quote a string for JSON
+fn json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(quoted, "\\u{:04x}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Write the triangles as binary glTF 2.0 (`.glb`)
+///
+/// The file holds a single node with positions, normals, optional vertex
+/// colors (e.g. from the current color mapping) and a double-sided material.
+///
+/// # Parameters
+/// - `geometry`: Geometry to write
+/// - `colors`: Linear RGBA color per vertex
+/// - `path`: Output file path
+///
+/// # Returns
+/// - `Ok(())`: File written
+/// - `Err(VtkError)`: The color count does not match or writing failed
+pub fn write_glb(
+    geometry: &GeometryData,
+    colors: Option<&[[f32; 4]]>,
+    path: &Path,
+) -> Result<(), VtkError> {
+    let node = GlbNode {
+        name: "model".to_string(),
+        positions: geometry.vertices.clone(),
+        normals: vertex_normals(geometry),
+        colors: colors.map(<[_]>::to_vec),
+        indices: geometry.indices.clone(),
+        translation: [0.0; 3],
+        rotation: [0.0, 0.0, 0.0, 1.0],
+        scale: [1.0; 3],
+    };
+    write_glb_scene(&[node], path)
+}
+
+/// Write models with their transforms as one binary glTF 2.0 scene (`.glb`)
+///
+/// Every model becomes a node with its own mesh; all meshes share a
+/// double-sided material whose color comes from the vertex colors.
+///
+/// # Parameters
+/// - `nodes`: Models of the scene
+/// - `path`: Output file path
+///
+/// # Returns
+/// - `Ok(())`: File written
+/// - `Err(VtkError)`: A normal or color count does not match or writing failed
+pub fn write_glb_scene(nodes: &[GlbNode], path: &Path) -> Result<(), VtkError> {
+    const ARRAY_BUFFER: u32 = 34962;
+    const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+    const FLOAT: u32 = 5126;
+    const UNSIGNED_INT: u32 = 5125;
+
+    // Binary buffer: positions, normals, colors, indices of every node (4-byte aligned)
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut views = Vec::new();
+    let mut push_view = |buffer: &mut Vec<u8>, bytes: Vec<u8>, target: u32| {
+        views.push((buffer.len(), bytes.len(), target));
+        buffer.extend_from_slice(&bytes);
+        views.len() - 1
+    };
+    let float_bytes = |values: &mut dyn Iterator<Item = f32>| -> Vec<u8> {
+        values.flat_map(f32::to_le_bytes).collect()
+    };
+
+    let mut accessors = Vec::new();
+    let mut meshes = Vec::new();
+    let mut scene_nodes = Vec::new();
+    for node in nodes {
+        let vertex_count = node.positions.len();
+        for count in [Some(node.normals.len()), node.colors.as_ref().map(Vec::len)]
+            .into_iter()
+            .flatten()
+        {
+            if count != vertex_count {
+                return Err(VtkError::AttributeMismatch {
+                    attribute_size: count,
+                    expected_size: vertex_count,
+                });
+            }
+        }
+        if vertex_count == 0 {
+            continue;
+        }
+
+        let (min, max) = node.positions.iter().fold(
+            (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+            |(min, max), vertex| (min.min(Vec3::from(*vertex)), max.max(Vec3::from(*vertex))),
+        );
+
+        let positions = float_bytes(&mut node.positions.iter().flatten().copied());
+        let normals = float_bytes(&mut node.normals.iter().flatten().copied());
+        let position_view = push_view(&mut buffer, positions, ARRAY_BUFFER);
+        let normal_view = push_view(&mut buffer, normals, ARRAY_BUFFER);
+        let color_view = node.colors.as_ref().map(|colors| {
+            let bytes = float_bytes(&mut colors.iter().flatten().copied());
+            push_view(&mut buffer, bytes, ARRAY_BUFFER)
+        });
+        let indices = node.indices.iter().flat_map(|i| i.to_le_bytes()).collect();
+        let index_view = push_view(&mut buffer, indices, ELEMENT_ARRAY_BUFFER);
+
+        let first_accessor = accessors.len();
+        accessors.push(format!(
+            r#"{{"bufferView":{},"componentType":{},"count":{},"type":"VEC3","min":[{},{},{}],"max":[{},{},{}]}}"#,
+            position_view, FLOAT, vertex_count, min.x, min.y, min.z, max.x, max.y, max.z
+        ));
+        accessors.push(format!(
+            r#"{{"bufferView":{},"componentType":{},"count":{},"type":"VEC3"}}"#,
+            normal_view, FLOAT, vertex_count
+        ));
+        accessors.push(format!(
+            r#"{{"bufferView":{},"componentType":{},"count":{},"type":"SCALAR"}}"#,
+            index_view,
+            UNSIGNED_INT,
+            node.indices.len()
+        ));
+        let mut attributes = format!(
+            r#""POSITION":{},"NORMAL":{}"#,
+            first_accessor,
+            first_accessor + 1
+        );
+        if let Some(color_view) = color_view {
+            accessors.push(format!(
+                r#"{{"bufferView":{},"componentType":{},"count":{},"type":"VEC4"}}"#,
+                color_view, FLOAT, vertex_count
+            ));
+            let _ = write!(attributes, r#","COLOR_0":{}"#, first_accessor + 3);
+        }
+
+        meshes.push(format!(
+            r#"{{"name":{},"primitives":[{{"attributes":{{{}}},"indices":{},"material":0,"mode":4}}]}}"#,
+            json_string(&node.name),
+            attributes,
+            first_accessor + 2
+        ));
+        let [tx, ty, tz] = node.translation;
+        let [rx, ry, rz, rw] = node.rotation;
+        let [sx, sy, sz] = node.scale;
+        scene_nodes.push(format!(
+            r#"{{"name":{},"mesh":{},"translation":[{},{},{}],"rotation":[{},{},{},{}],"scale":[{},{},{}]}}"#,
+            json_string(&node.name),
+            meshes.len() - 1,
+            tx, ty, tz, rx, ry, rz, rw, sx, sy, sz
+        ));
+    }
+    if scene_nodes.is_empty() {
+        return Err(VtkError::MissingData("No geometry to export"));
+    }
+
+    let buffer_views: Vec<String> = views
+        .iter()
+        .map(|(offset, length, target)| {
+            format!(
+                r#"{{"buffer":0,"byteOffset":{},"byteLength":{},"target":{}}}"#,
+                offset, length, target
+            )
+        })
+        .collect();
+    let node_ids: Vec<String> = (0..scene_nodes.len()).map(|i| i.to_string()).collect();
+
+    let mut json = format!(
+        concat!(
+            r#"{{"asset":{{"version":"2.0","generator":"data_visualization"}},"#,
+            r#""scene":0,"scenes":[{{"nodes":[{}]}}],"nodes":[{}],"meshes":[{}],"#,
+            r#""materials":[{{"pbrMetallicRoughness":{{"baseColorFactor":[1,1,1,1],"metallicFactor":0.2,"roughnessFactor":0.4}},"doubleSided":true}}],"#,
+            r#""buffers":[{{"byteLength":{}}}],"bufferViews":[{}],"accessors":[{}]}}"#
+        ),
+        node_ids.join(","),
+        scene_nodes.join(","),
+        meshes.join(","),
+        buffer.len(),
+        buffer_views.join(","),
+        accessors.join(",")
+    );
+
+    // Chunks are padded to 4 bytes: JSON with spaces, binary with zeros
+    json.push_str(&" ".repeat((4 - json.len() % 4) % 4));
+    buffer.resize(buffer.len().next_multiple_of(4), 0);
+
+    let total_length = 12 + 8 + json.len() + 8 + buffer.len();
+    let mut glb = Vec::with_capacity(total_length);
+    glb.extend_from_slice(b"glTF");
+    glb.extend_from_slice(&2u32.to_le_bytes());
+    glb.extend_from_slice(&(total_length as u32).to_le_bytes());
+    glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    glb.extend_from_slice(b"JSON");
+    glb.extend_from_slice(json.as_bytes());
+    glb.extend_from_slice(&(buffer.len() as u32).to_le_bytes());
+    glb.extend_from_slice(b"BIN\0");
+    glb.extend_from_slice(&buffer);
+
+    std::fs::write(path, glb).map_err(VtkError::IoError)
+}
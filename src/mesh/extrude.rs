@@ -0,0 +1,173 @@
+//! Extrude module
+//!
+//! Linear extrusion of surfaces along a vector:
+//! - Every boundary edge of the surface becomes a side quad between the surface
+//!   and its translated copy
+//! - With capping, the surface and the translated copy close the solid
+//! - Point attributes and texture coordinates are copied to the new points and
+//!   every new triangle keeps the cell of its source triangle, so the cell
+//!   attributes stay valid
+//!
+//! Planar meshes such as 2D simulation slices become slabs of the given
+//! thickness.
+use super::topology::{subset_attribute, triangle_cell};
+use super::{AttributeLocation, GeometryData, VtkError};
+use bevy::math::Vec3;
+use bevy::utils::HashMap;
+
+/// Area weighted average normal of a surface
+///
+/// # Returns
+/// - Unit normal, `None` if the triangles have no area
+pub fn surface_normal(geometry: &GeometryData) -> Option<Vec3> {
+    let vertex = |index: u32| Vec3::from(geometry.vertices[index as usize]);
+    let sum: Vec3 = geometry
+        .indices
+        .chunks_exact(3)
+        .map(|corners| {
+            let [a, b, c] = [corners[0], corners[1], corners[2]].map(vertex);
+            (b - a).cross(c - a)
+        })
+        .sum();
+    let normal = sum.normalize_or_zero();
+    (normal != Vec3::ZERO).then_some(normal)
+}
+
+/// Boundary edges in the winding order of their triangle
+fn boundary_edges(geometry: &GeometryData) -> Vec<([u32; 2], usize)> {
+    let mut edges: HashMap<[u32; 2], (usize, [u32; 2], usize)> = HashMap::new();
+    for (triangle, corners) in geometry.indices.chunks_exact(3).enumerate() {
+        for k in 0..3 {
+            let edge = [corners[k], corners[(k + 1) % 3]];
+            let key = [edge[0].min(edge[1]), edge[0].max(edge[1])];
+            edges
+                .entry(key)
+                .and_modify(|(count, _, _)| *count += 1)
+                .or_insert((1, edge, triangle));
+        }
+    }
+    let mut boundary: Vec<([u32; 2], usize)> = edges
+        .into_values()
+        .filter(|(count, _, _)| *count == 1)
+        .map(|(_, edge, triangle)| (edge, triangle))
+        .collect();
+    // Deterministic output for the same input
+    boundary.sort_unstable_by_key(|(_, triangle)| *triangle);
+    boundary
+}
+
+/// Extrude a surface along a vector
+///
+/// The translated copy keeps the winding of the surface. If the vector points
+/// against the surface normal, all triangles are reversed so faces point
+/// outwards.
+///
+/// # Parameters
+/// - `geometry`: Surface to extrude
+/// - `vector`: Extrusion direction and length
+/// - `capping`: Add the surface and its translated copy as caps
+///
+/// # Returns
+/// - `Ok(GeometryData)`: Extruded geometry with attributes
+/// - `Err(VtkError)`: The geometry has no triangles or the vector is zero
+pub fn extrude_geometry(
+    geometry: &GeometryData,
+    vector: Vec3,
+    capping: bool,
+) -> Result<GeometryData, VtkError> {
+    if geometry.indices.is_empty() {
+        return Err(VtkError::GenericError(
+            "The model has no surface to extrude".to_string(),
+        ));
+    }
+    if vector.length_squared() <= f32::EPSILON * f32::EPSILON {
+        return Err(VtkError::GenericError(
+            "The extrusion vector must not be zero".to_string(),
+        ));
+    }
+
+    let point_count = geometry.vertices.len() as u32;
+    let mut vertices = Vec::new();
+    // Source point of every new vertex, for the point attributes
+    let mut sources: Vec<usize> = Vec::new();
+    let mut indices = Vec::new();
+    let mut triangle_to_cell = Vec::new();
+
+    if capping {
+        // Bottom cap: the surface reversed, top cap: the translated surface
+        vertices.extend(geometry.vertices.iter().copied());
+        vertices.extend(
+            geometry
+                .vertices
+                .iter()
+                .map(|&v| (Vec3::from(v) + vector).to_array()),
+        );
+        sources.extend(0..point_count as usize);
+        sources.extend(0..point_count as usize);
+        for (triangle, corners) in geometry.indices.chunks_exact(3).enumerate() {
+            indices.extend_from_slice(&[corners[0], corners[2], corners[1]]);
+            triangle_to_cell.push(triangle_cell(geometry, triangle));
+        }
+        for (triangle, corners) in geometry.indices.chunks_exact(3).enumerate() {
+            indices.extend(corners.iter().map(|&corner| corner + point_count));
+            triangle_to_cell.push(triangle_cell(geometry, triangle));
+        }
+    }
+
+    // Side quads get their own vertices, so they are shaded flat
+    let edges = boundary_edges(geometry);
+    for &([a, b], triangle) in &edges {
+        let start = vertices.len() as u32;
+        for point in [a, b] {
+            vertices.push(geometry.vertices[point as usize]);
+            sources.push(point as usize);
+        }
+        for point in [b, a] {
+            vertices.push((Vec3::from(geometry.vertices[point as usize]) + vector).to_array());
+            sources.push(point as usize);
+        }
+        indices.extend_from_slice(&[start, start + 1, start + 2, start, start + 2, start + 3]);
+        let cell = triangle_cell(geometry, triangle);
+        triangle_to_cell.extend_from_slice(&[cell, cell]);
+    }
+
+    let flip = surface_normal(geometry).is_some_and(|normal| normal.dot(vector) < 0.0);
+    if flip {
+        for triangle in indices.chunks_exact_mut(3) {
+            triangle.swap(1, 2);
+        }
+    }
+
+    let attributes = geometry
+        .attributes
+        .iter()
+        .flatten()
+        .map(|((name, location), attribute)| {
+            let attribute = match location {
+                AttributeLocation::Point if !name.starts_with("__lut_") => {
+                    subset_attribute(attribute, &sources)
+                }
+                _ => attribute.clone(),
+            };
+            ((name.clone(), location.clone()), attribute)
+        })
+        .collect();
+
+    let mut extruded = GeometryData::new(vertices, indices, attributes)
+        .add_triangle_to_cell_mapping(triangle_to_cell);
+    if let Some(uvs) = geometry
+        .uvs
+        .as_ref()
+        .filter(|uvs| uvs.len() == point_count as usize)
+    {
+        extruded = extruded.add_uvs(sources.iter().map(|&point| uvs[point]).collect());
+    }
+    extruded.lookup_tables = geometry.lookup_tables.clone();
+    println!(
+        "Extruded {} triangles with {} boundary edges by {:?}",
+        geometry.indices.len() / 3,
+        edges.len(),
+        vector
+    );
+    Ok(extruded)
+}
@@ -0,0 +1,130 @@
+//! Geodesic distance module
+//!
+//! Approximates geodesic distances on triangle meshes by shortest paths
+//! along mesh edges (Dijkstra on the vertex graph).
+use super::GeometryData;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Queue entry ordered by smallest distance first
+#[derive(Clone, Copy, PartialEq)]
+struct QueueEntry {
+    distance: f32,
+    vertex: u32,
+}
+
+impl Eq for QueueEntry {}
+
+impl Ord for QueueEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reverse order for a min-heap
+        other.distance.total_cmp(&self.distance)
+    }
+}
+
+impl PartialOrd for QueueEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Build vertex adjacency lists from triangle indices
+///
+/// # Parameters
+/// - `geometry`: Geometry data containing vertices and triangle indices
+///
+/// # Returns
+/// - Neighbor list for every vertex (without duplicates)
+pub fn build_vertex_adjacency(geometry: &GeometryData) -> Vec<Vec<u32>> {
+    let mut adjacency: Vec<Vec<u32>> = vec![Vec::new(); geometry.vertices.len()];
+
+    for triangle in geometry.indices.chunks_exact(3) {
+        for i in 0..3 {
+            let a = triangle[i];
+            let b = triangle[(i + 1) % 3];
+            if !adjacency[a as usize].contains(&b) {
+                adjacency[a as usize].push(b);
+            }
+            if !adjacency[b as usize].contains(&a) {
+                adjacency[b as usize].push(a);
+            }
+        }
+    }
+
+    adjacency
+}
+
+/// Find the shortest edge path between two vertices
+///
+/// # Parameters
+/// - `geometry`: Geometry data containing vertices and triangle indices
+/// - `start`: Start vertex index
+/// - `end`: End vertex index
+///
+/// # Returns
+/// - `Some((length, path))`: Path length and the vertex indices along the path (start to end)
+/// - `None`: Vertices are out of range or not connected
+pub fn shortest_edge_path(
+    geometry: &GeometryData,
+    start: u32,
+    end: u32,
+) -> Option<(f32, Vec<u32>)> {
+    let vertex_count = geometry.vertices.len();
+    if start as usize >= vertex_count || end as usize >= vertex_count {
+        return None;
+    }
+
+    let adjacency = build_vertex_adjacency(geometry);
+    let mut distances = vec![f32::INFINITY; vertex_count];
+    let mut previous: Vec<Option<u32>> = vec![None; vertex_count];
+    let mut queue = BinaryHeap::new();
+
+    distances[start as usize] = 0.0;
+    queue.push(QueueEntry {
+        distance: 0.0,
+        vertex: start,
+    });
+
+    while let Some(QueueEntry { distance, vertex }) = queue.pop() {
+        if vertex == end {
+            break;
+        }
+        if distance > distances[vertex as usize] {
+            continue;
+        }
+
+        let position = geometry.vertices[vertex as usize];
+        for &neighbor in &adjacency[vertex as usize] {
+            let neighbor_position = geometry.vertices[neighbor as usize];
+            let edge_length = ((position[0] - neighbor_position[0]).powi(2)
+                + (position[1] - neighbor_position[1]).powi(2)
+                + (position[2] - neighbor_position[2]).powi(2))
+            .sqrt();
+
+            let candidate = distance + edge_length;
+            if candidate < distances[neighbor as usize] {
+                distances[neighbor as usize] = candidate;
+                previous[neighbor as usize] = Some(vertex);
+                queue.push(QueueEntry {
+                    distance: candidate,
+                    vertex: neighbor,
+                });
+            }
+        }
+    }
+
+    if !distances[end as usize].is_finite() {
+        return None;
+    }
+
+    // Walk back from the end vertex
+    let mut path = vec![end];
+    let mut current = end;
+    while let Some(prev) = previous[current as usize] {
+        path.push(prev);
+        current = prev;
+    }
+    path.reverse();
+
+    Some((distances[end as usize], path))
+}
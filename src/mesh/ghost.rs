@@ -0,0 +1,280 @@
+//! Ghost cell module
+//!
+//! Partitioned datasets carry a layer of ghost cells copied from neighboring
+//! partitions, flagged in the `vtkGhostType` arrays. Ghost cells (and ghost
+//! points of point sets) are removed after loading so that partition
+//! boundaries are not rendered twice and statistics only see owned data.
+use super::{AttributeLocation, AttributeType, GeometryData};
+use vtkio::model;
+
+/// Name of the ghost flag arrays in point and cell data
+pub const GHOST_ARRAY_NAME: &str = "vtkGhostType";
+
+/// Cell owned by another partition (vtkDataSetAttributes::DUPLICATECELL)
+const DUPLICATE_CELL: u8 = 1;
+/// Cell hidden from rendering (vtkDataSetAttributes::HIDDENCELL)
+const HIDDEN_CELL: u8 = 32;
+/// Point owned by another partition (vtkDataSetAttributes::DUPLICATEPOINT)
+const DUPLICATE_POINT: u8 = 1;
+/// Point hidden from rendering (vtkDataSetAttributes::HIDDENPOINT)
+const HIDDEN_POINT: u8 = 2;
+
+/// Find the ghost flags among the point or cell data arrays of a piece
+///
+/// Both plain data arrays and arrays of legacy FIELD data are searched.
+///
+/// # Parameters
+/// - `attributes`: Point or cell attributes of the piece
+///
+/// # Returns
+/// - Ghost flags per element, or `None` if the piece has no ghost array
+pub fn ghost_flags(attributes: &[model::Attribute]) -> Option<Vec<u8>> {
+    let data = attributes.iter().find_map(|attribute| match attribute {
+        model::Attribute::DataArray(array) if array.name == GHOST_ARRAY_NAME => Some(&array.data),
+        model::Attribute::Field { data_array, .. } => data_array
+            .iter()
+            .find(|array| array.name == GHOST_ARRAY_NAME)
+            .map(|array| &array.data),
+        _ => None,
+    })?;
+
+    let values = super::vtk::buffer_to_f32(data);
+    Some(values.into_iter().map(|value| value as u8).collect())
+}
+
+/// Remove ghost cells and the points only they use
+///
+/// Point sets (geometry without triangles) instead drop their ghost points.
+/// The `vtkGhostType` attributes are removed in any case.
+///
+/// # Parameters
+/// - `geometry`: Loaded geometry
+/// - `cell_flags`: Ghost flags per cell, if the dataset has them
+/// - `point_flags`: Ghost flags per point, if the dataset has them
+///
+/// # Returns
+/// - Geometry without ghost elements
+pub fn remove_ghosts(
+    mut geometry: GeometryData,
+    cell_flags: Option<&[u8]>,
+    point_flags: Option<&[u8]>,
+) -> GeometryData {
+    if let Some(attributes) = geometry.attributes.as_mut() {
+        attributes.retain(|(name, _), _| name != GHOST_ARRAY_NAME);
+    }
+
+    if geometry.indices.is_empty() && geometry.lines.is_none() {
+        let Some(point_flags) = point_flags else {
+            return geometry;
+        };
+        let keep_points: Vec<bool> = (0..geometry.vertices.len())
+            .map(|point| {
+                point_flags
+                    .get(point)
+                    .is_none_or(|flags| flags & (DUPLICATE_POINT | HIDDEN_POINT) == 0)
+            })
+            .collect();
+        let removed = keep_points.iter().filter(|keep| !**keep).count();
+        if removed == 0 {
+            return geometry;
+        }
+        println!("Removed {} ghost points", removed);
+        return compact_vertices(geometry, &keep_points);
+    }
+
+    let Some(cell_flags) = cell_flags else {
+        return geometry;
+    };
+    let cell_count = super::topology::cell_count(&geometry).max(cell_flags.len());
+    let keep_cells: Vec<bool> = (0..cell_count)
+        .map(|cell| {
+            cell_flags
+                .get(cell)
+                .is_none_or(|flags| flags & (DUPLICATE_CELL | HIDDEN_CELL) == 0)
+        })
+        .collect();
+    let removed = keep_cells.iter().filter(|keep| !**keep).count();
+    if removed == 0 {
+        return geometry;
+    }
+
+    // Renumber the remaining cells
+    let mut new_cells = vec![usize::MAX; cell_count];
+    let mut next = 0;
+    for (cell, keep) in keep_cells.iter().enumerate() {
+        if *keep {
+            new_cells[cell] = next;
+            next += 1;
+        }
+    }
+
+    let mut indices = Vec::with_capacity(geometry.indices.len());
+    let mut mapping = Vec::with_capacity(geometry.indices.len() / 3);
+    for (triangle, corners) in geometry.indices.chunks_exact(3).enumerate() {
+        let cell = super::topology::triangle_cell(&geometry, triangle);
+        if keep_cells.get(cell).copied().unwrap_or(true) {
+            indices.extend_from_slice(corners);
+            mapping.push(new_cells.get(cell).copied().unwrap_or(cell));
+        }
+    }
+    geometry.indices = indices;
+    geometry.triangle_to_cell_mapping = Some(mapping);
+    if let Some(volume_cells) = geometry.volume_cells.as_mut() {
+        volume_cells.retain_cells(|cell| {
+            keep_cells
+                .get(cell)
+                .copied()
+                .unwrap_or(true)
+                .then(|| new_cells.get(cell).copied().unwrap_or(cell))
+        });
+    }
+
+    if let Some(lines) = geometry.lines.as_mut() {
+        lines.retain(|line| keep_cells.get(line.cell).copied().unwrap_or(true));
+        for line in lines.iter_mut() {
+            line.cell = new_cells.get(line.cell).copied().unwrap_or(line.cell);
+        }
+    }
+    if let Some(cell_types) = geometry.cell_types.as_mut() {
+        *cell_types = cell_types
+            .iter()
+            .enumerate()
+            .filter(|(cell, _)| keep_cells.get(*cell).copied().unwrap_or(true))
+            .map(|(_, &cell_type)| cell_type)
+            .collect();
+    }
+    if let Some(attributes) = geometry.attributes.as_mut() {
+        for ((name, location), attribute) in attributes.iter_mut() {
+            if *location == AttributeLocation::Cell && !name.starts_with("__lut_") {
+                filter_elements(attribute, &keep_cells);
+            }
+        }
+    }
+    println!("Removed {} ghost cells", removed);
+
+    // Interior points of volume cells are not part of the surface
+    let mut keep_points = vec![false; geometry.vertices.len()];
+    let cell_points = geometry
+        .volume_cells
+        .iter()
+        .flat_map(|volume_cells| &volume_cells.connectivity);
+    let line_points = geometry
+        .lines
+        .iter()
+        .flatten()
+        .flat_map(|line| &line.points);
+    for &index in geometry
+        .indices
+        .iter()
+        .chain(cell_points)
+        .chain(line_points)
+    {
+        if let Some(keep) = keep_points.get_mut(index as usize) {
+            *keep = true;
+        }
+    }
+    compact_vertices(geometry, &keep_points)
+}
+
+/// Keep the elements of an attribute whose flag is set
+///
+/// Attributes with a different element count are left unchanged.
+fn filter_elements(attribute: &mut AttributeType, keep: &[bool]) {
+    fn retain<T>(data: &mut Vec<T>, keep: &[bool]) {
+        if data.len() == keep.len() {
+            let mut flags = keep.iter();
+            data.retain(|_| flags.next().copied().unwrap_or(true));
+        }
+    }
+
+    match attribute {
+        AttributeType::Scalar { num_comp, data, .. } => {
+            let num_comp = (*num_comp).max(1);
+            if data.len() == keep.len() * num_comp {
+                *data = data
+                    .chunks_exact(num_comp)
+                    .zip(keep)
+                    .filter(|(_, keep)| **keep)
+                    .flat_map(|(values, _)| values.iter().copied())
+                    .collect();
+            }
+        }
+        AttributeType::ColorScalar { data, .. } => retain(data, keep),
+        AttributeType::Vector(data) => retain(data, keep),
+        AttributeType::Tensor(data) => retain(data, keep),
+    }
+}
+
+/// Drop the vertices whose flag is not set and renumber the rest
+fn compact_vertices(mut geometry: GeometryData, keep: &[bool]) -> GeometryData {
+    if keep.iter().all(|keep| *keep) {
+        return geometry;
+    }
+
+    let mut new_index = vec![u32::MAX; keep.len()];
+    let mut next = 0;
+    for (vertex, keep) in keep.iter().enumerate() {
+        if *keep {
+            new_index[vertex] = next;
+            next += 1;
+        }
+    }
+    let remap = |index: u32| new_index[index as usize];
+
+    let mut flags = keep.iter();
+    geometry
+        .vertices
+        .retain(|_| flags.next().copied().unwrap_or(true));
+    for index in geometry.indices.iter_mut() {
+        *index = remap(*index);
+    }
+    if let Some(normals) = geometry.normals.as_mut() {
+        let mut flags = keep.iter();
+        normals.retain(|_| flags.next().copied().unwrap_or(true));
+    }
+    if let Some(uvs) = geometry.uvs.as_mut() {
+        let mut flags = keep.iter();
+        uvs.retain(|_| flags.next().copied().unwrap_or(true));
+    }
+    if let Some(attributes) = geometry.attributes.as_mut() {
+        for ((name, location), attribute) in attributes.iter_mut() {
+            if *location == AttributeLocation::Point && !name.starts_with("__lut_") {
+                filter_elements(attribute, keep);
+            }
+        }
+    }
+
+    // Quadratic elements survive only if all their nodes do
+    let kept = |vertices: &[u32]| {
+        vertices
+            .iter()
+            .all(|&vertex| keep.get(vertex as usize).copied().unwrap_or(false))
+    };
+    if let Some(triangles) = geometry.quadratic_triangles.as_mut() {
+        triangles.retain(|triangle| kept(&triangle.vertices));
+        for triangle in triangles.iter_mut() {
+            triangle.vertices = triangle.vertices.map(remap);
+        }
+    }
+    if let Some(edges) = geometry.quadratic_edges.as_mut() {
+        edges.retain(|edge| kept(&edge.vertices));
+        for edge in edges.iter_mut() {
+            edge.vertices = edge.vertices.map(remap);
+        }
+    }
+    if let Some(volume_cells) = geometry.volume_cells.as_mut() {
+        volume_cells.remap_points(|point| kept(&[point]).then(|| remap(point)));
+    }
+    // Lines lose their removed points and are dropped below two points
+    if let Some(lines) = geometry.lines.as_mut() {
+        for line in lines.iter_mut() {
+            line.points.retain(|&point| kept(&[point]));
+            line.points
+                .iter_mut()
+                .for_each(|point| *point = remap(*point));
+        }
+        lines.retain(|line| line.points.len() >= 2);
+    }
+
+    geometry
+}
@@ -0,0 +1,156 @@
+//! Glyphs module
+//!
+//! Arrow glyphs of point vector attributes:
+//! - One arrow per selected point, pointing along the vector
+//! - Arrows are scaled by the vector magnitude or all have the same length
+//! - The magnitude is stored as point scalar `magnitude` for color mapping
+//!
+//! Points can be thinned out by a stride and a maximum glyph count.
+use super::{AttributeLocation, AttributeType, GeometryData};
+use bevy::math::{Quat, Vec3};
+use bevy::utils::HashMap;
+
+/// Sides of the arrow shaft and tip
+const ARROW_SIDES: usize = 8;
+/// Arrow proportions relative to the arrow length
+const SHAFT_RADIUS: f32 = 0.03;
+const TIP_RADIUS: f32 = 0.08;
+const TIP_LENGTH: f32 = 0.3;
+/// Vertices of one arrow: shaft, tip and tip cap rings and the cap center
+const ARROW_VERTICES: usize = ARROW_SIDES * 5 + 1;
+
+/// Glyph placement and size
+#[derive(Clone, Copy, Debug)]
+pub struct GlyphSettings {
+    /// Use every n-th point
+    pub stride: usize,
+    /// Largest number of glyphs; the stride is raised to stay below it
+    pub max_count: usize,
+    /// Length of the arrow of the largest vector (or of every arrow)
+    pub length: f32,
+    /// Scale arrows by the vector magnitude
+    pub scale_by_magnitude: bool,
+}
+
+/// Indices of the points that get a glyph
+///
+/// # Parameters
+/// - `point_count`: Number of points
+/// - `settings`: Stride and maximum count
+pub fn glyph_points(point_count: usize, settings: &GlyphSettings) -> Vec<usize> {
+    let mut stride = settings.stride.max(1);
+    let max_count = settings.max_count.max(1);
+    if point_count.div_ceil(stride) > max_count {
+        stride = point_count.div_ceil(max_count);
+    }
+    (0..point_count).step_by(stride).collect()
+}
+
+/// Arrow glyphs at points
+///
+/// # Parameters
+/// - `positions`: Point positions
+/// - `vectors`: Vector of every point
+/// - `settings`: Placement and size
+///
+/// # Returns
+/// - Arrow geometry with the point scalar `magnitude`
+pub fn arrow_glyphs(
+    positions: &[[f32; 3]],
+    vectors: &[[f32; 3]],
+    settings: &GlyphSettings,
+) -> GeometryData {
+    let count = positions.len().min(vectors.len());
+    let max_magnitude = vectors[..count]
+        .iter()
+        .map(|&vector| Vec3::from(vector).length())
+        .filter(|magnitude| magnitude.is_finite())
+        .fold(0.0f32, f32::max);
+
+    let mut vertices = Vec::new();
+    let mut normals = Vec::new();
+    let mut magnitudes = Vec::new();
+    let mut indices = Vec::new();
+
+    for point in glyph_points(count, settings) {
+        let vector = Vec3::from(vectors[point]);
+        let magnitude = vector.length();
+        if !magnitude.is_finite() || magnitude <= f32::EPSILON {
+            continue;
+        }
+        let length = if settings.scale_by_magnitude {
+            settings.length * magnitude / max_magnitude
+        } else {
+            settings.length
+        };
+        let rotation = Quat::from_rotation_arc(Vec3::Y, vector / magnitude);
+        let base = Vec3::from(positions[point]);
+        let start = vertices.len();
+        add_arrow(&mut vertices, &mut normals, &mut indices);
+        for i in start..vertices.len() {
+            vertices[i] = (base + rotation * (Vec3::from(vertices[i]) * length)).to_array();
+            normals[i] = (rotation * Vec3::from(normals[i])).to_array();
+        }
+        magnitudes.resize(vertices.len(), magnitude);
+    }
+
+    let mut attributes = HashMap::new();
+    attributes.insert(
+        ("magnitude".to_string(), AttributeLocation::Point),
+        AttributeType::Scalar {
+            num_comp: 1,
+            table_name: "default".to_string(),
+            data: magnitudes,
+            lookup_table: None,
+        },
+    );
+    let mut geometry = GeometryData::new(vertices, indices, attributes);
+    geometry.normals = Some(normals);
+    geometry
+}
+
+/// Number of arrows in a glyph geometry
+pub fn arrow_count(geometry: &GeometryData) -> usize {
+    geometry.vertices.len() / ARROW_VERTICES
+}
+
+/// Append a unit arrow along +Y starting at the origin
+fn add_arrow(vertices: &mut Vec<[f32; 3]>, normals: &mut Vec<[f32; 3]>, indices: &mut Vec<u32>) {
+    let shaft_end = 1.0 - TIP_LENGTH;
+    let direction = |side: usize| {
+        let angle = side as f32 / ARROW_SIDES as f32 * std::f32::consts::TAU;
+        Vec3::new(angle.cos(), 0.0, angle.sin())
+    };
+    let mut push = |position: Vec3, normal: Vec3| {
+        vertices.push(position.to_array());
+        normals.push(normal.to_array());
+        vertices.len() as u32 - 1
+    };
+
+    let mut shaft = Vec::with_capacity(ARROW_SIDES);
+    let mut tip_base = Vec::with_capacity(ARROW_SIDES);
+    let mut tips = Vec::with_capacity(ARROW_SIDES);
+    let mut cap = Vec::with_capacity(ARROW_SIDES);
+    // Tip side normals lean towards the tip
+    let slope = TIP_RADIUS / TIP_LENGTH;
+    for side in 0..ARROW_SIDES {
+        let out = direction(side);
+        shaft.push((
+            push(out * SHAFT_RADIUS, out),
+            push(out * SHAFT_RADIUS + Vec3::Y * shaft_end, out),
+        ));
+        let tip_normal = (out + Vec3::Y * slope).normalize();
+        tip_base.push(push(out * TIP_RADIUS + Vec3::Y * shaft_end, tip_normal));
+        tips.push(push(Vec3::Y, tip_normal));
+        cap.push(push(out * TIP_RADIUS + Vec3::Y * shaft_end, Vec3::NEG_Y));
+    }
+    let cap_center = push(Vec3::Y * shaft_end, Vec3::NEG_Y);
+
+    for side in 0..ARROW_SIDES {
+        let next = (side + 1) % ARROW_SIDES;
+        let ((bottom, top), (next_bottom, next_top)) = (shaft[side], shaft[next]);
+        indices.extend_from_slice(&[bottom, top, next_bottom, next_bottom, top, next_top]);
+        indices.extend_from_slice(&[tip_base[side], tips[side], tip_base[next]]);
+        indices.extend_from_slice(&[cap_center, cap[side], cap[next]]);
+    }
+}
@@ -0,0 +1,1178 @@
+//! HDF5 module
+//!
+//! Minimal reader for HDF5 files, the container of CGNS files. It covers the
+//! structures HDF5 1.8 - 1.14 writes for such files:
+//! - Superblock versions 0 - 3, object header versions 1 and 2
+//! - Groups with symbol tables, compact links and dense links (fractal heap with
+//!   a version 2 B-tree name index)
+//! - Compact attributes of fixed-size strings and numbers
+//! - Compact, contiguous and chunked datasets (version 1 B-tree or single chunk),
+//!   with deflate, shuffle and Fletcher-32 filters
+//! - Integer and floating point data of either byte order
+//!
+//! The file is memory-mapped, so only the structures and datasets that are read
+//! are loaded from disk; objects are addressed by the file address of their
+//! object header.
+use super::VtkError;
+use memmap2::Mmap;
+use std::io::Read;
+use std::path::Path;
+
+/// File signature, at offset 0 or a power of two from 512 on
+const SIGNATURE: &[u8; 8] = b"\x89HDF\r\n\x1a\n";
+/// Address of objects that were never allocated
+const UNDEFINED: u64 = u64::MAX;
+
+/// Object header message types
+const MESSAGE_DATASPACE: u16 = 0x01;
+const MESSAGE_LINK_INFO: u16 = 0x02;
+const MESSAGE_DATATYPE: u16 = 0x03;
+const MESSAGE_LINK: u16 = 0x06;
+const MESSAGE_LAYOUT: u16 = 0x08;
+const MESSAGE_FILTERS: u16 = 0x0B;
+const MESSAGE_ATTRIBUTE: u16 = 0x0C;
+const MESSAGE_CONTINUATION: u16 = 0x10;
+const MESSAGE_SYMBOL_TABLE: u16 = 0x11;
+
+/// Error for structures pointing beyond the file
+fn truncated() -> VtkError {
+    VtkError::InvalidFormat("HDF5 structure beyond end of file")
+}
+
+/// Number of bytes needed to encode values up to `value`
+fn encoded_size(value: u64) -> usize {
+    (63 - value.max(1).leading_zeros() as usize) / 8 + 1
+}
+
+/// Little-endian reader at a file position
+struct Cursor<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], VtkError> {
+        let data = self.data;
+        let bytes = data
+            .get(self.position..self.position + count)
+            .ok_or_else(truncated)?;
+        self.position += count;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, count: usize) -> Result<(), VtkError> {
+        self.bytes(count).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Result<u8, VtkError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, VtkError> {
+        Ok(self.uint(2)? as u16)
+    }
+
+    fn u32(&mut self) -> Result<u32, VtkError> {
+        Ok(self.uint(4)? as u32)
+    }
+
+    /// Unsigned integer of `size` bytes
+    fn uint(&mut self, size: usize) -> Result<u64, VtkError> {
+        Ok(self
+            .bytes(size)?
+            .iter()
+            .rev()
+            .fold(0u64, |value, &byte| (value << 8) | byte as u64))
+    }
+
+    fn signature(&mut self, signature: &[u8; 4]) -> Result<(), VtkError> {
+        if self.bytes(4)? != signature {
+            return Err(VtkError::InvalidFormat(
+                "Unexpected HDF5 structure signature",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Filter pipeline as (filter id, client values)
+type Filters = Vec<(u16, Vec<u32>)>;
+
+/// Element type of a dataset or attribute
+#[derive(Clone, Copy, Debug)]
+pub struct Datatype {
+    /// 0 = integer, 1 = floating point, 3 = string
+    class: u8,
+    /// Size of one element in bytes
+    pub size: usize,
+    big_endian: bool,
+    signed: bool,
+}
+
+/// Storage of the raw data of a dataset
+#[derive(Debug)]
+enum Layout {
+    /// Data in the object header
+    Compact { start: usize, size: usize },
+    /// One block in the file
+    Contiguous { address: u64, size: u64 },
+    /// Chunks indexed by a version 1 B-tree; chunk dims include the element size
+    ChunkedTree { address: u64, chunk: Vec<u64> },
+    /// All data in one (possibly filtered) chunk
+    SingleChunk { address: u64, size: u64, mask: u32 },
+}
+
+/// Data of a dataset or attribute
+#[derive(Debug)]
+pub struct Dataset {
+    /// Dimension lengths, the slowest varying first (empty for scalars)
+    #[allow(dead_code)]
+    pub shape: Vec<usize>,
+    pub datatype: Datatype,
+    /// Raw element bytes
+    bytes: Vec<u8>,
+}
+
+impl Dataset {
+    /// Values as `f64`
+    ///
+    /// # Returns
+    /// - `Ok(Vec<f64>)`: Values of integer or floating point data
+    /// - `Err(VtkError)`: The data is not numeric
+    pub fn to_f64(&self) -> Result<Vec<f64>, VtkError> {
+        let Datatype {
+            class,
+            size,
+            big_endian,
+            signed,
+        } = self.datatype;
+        let supported = match class {
+            0 => matches!(size, 1 | 2 | 4 | 8),
+            1 => matches!(size, 4 | 8),
+            _ => false,
+        };
+        if !supported {
+            return Err(VtkError::UnsupportedDataType);
+        }
+
+        Ok(self
+            .bytes
+            .chunks_exact(size)
+            .map(|element| {
+                let mut buffer = [0u8; 8];
+                buffer[..size].copy_from_slice(element);
+                if big_endian {
+                    buffer[..size].reverse();
+                }
+                let bits = u64::from_le_bytes(buffer);
+                match (class, size) {
+                    (1, 4) => f32::from_bits(bits as u32) as f64,
+                    (1, _) => f64::from_bits(bits),
+                    (_, _) if signed => {
+                        // Sign-extend from the element size
+                        let shift = 64 - 8 * size as u32;
+                        ((bits << shift) as i64 >> shift) as f64
+                    }
+                    _ => bits as f64,
+                }
+            })
+            .collect())
+    }
+
+    /// Bytes of string or 8-bit data as text, up to the first null
+    pub fn to_text(&self) -> String {
+        let end = self
+            .bytes
+            .iter()
+            .position(|&byte| byte == 0)
+            .unwrap_or(self.bytes.len());
+        String::from_utf8_lossy(&self.bytes[..end])
+            .trim()
+            .to_string()
+    }
+}
+
+/// Header fields of a fractal heap
+struct FractalHeap {
+    /// Bytes of the heap offset in heap IDs
+    offset_size: usize,
+    filtered: bool,
+    table_width: u64,
+    start_block_size: u64,
+    max_direct_rows: u64,
+    root_address: u64,
+    root_rows: u64,
+}
+
+impl FractalHeap {
+    /// Size of the blocks in a row of an indirect block
+    fn row_size(&self, row: u64) -> u64 {
+        self.start_block_size << row.saturating_sub(1)
+    }
+}
+
+/// Memory-mapped HDF5 file
+pub struct Hdf5File {
+    data: Mmap,
+    /// Bytes of file addresses
+    offset_size: usize,
+    /// Bytes of lengths
+    length_size: usize,
+    /// File position all addresses are relative to
+    base: u64,
+    /// Object header address of the root group
+    root: u64,
+}
+
+impl Hdf5File {
+    /// Open an HDF5 file
+    ///
+    /// # Parameters
+    /// - `path`: File path
+    ///
+    /// # Returns
+    /// - `Ok(Hdf5File)`: File with its superblock parsed
+    /// - `Err(VtkError)`: The file cannot be read or is not an HDF5 file
+    pub fn open(path: &Path) -> Result<Self, VtkError> {
+        let file = std::fs::File::open(path).map_err(VtkError::IoError)?;
+        // Safety: the file is only read, and simulation output is not expected to
+        // change while it is open
+        let data = unsafe { Mmap::map(&file) }.map_err(VtkError::IoError)?;
+        Self::parse(data)
+    }
+
+    /// Parse the superblock of a mapped HDF5 file
+    fn parse(data: Mmap) -> Result<Self, VtkError> {
+        let start = std::iter::once(0)
+            .chain((9..).map(|bits| 1usize << bits))
+            .take_while(|&offset| offset + SIGNATURE.len() <= data.len())
+            .find(|&offset| data[offset..].starts_with(SIGNATURE))
+            .ok_or(VtkError::InvalidFormat("Not an HDF5 file"))?;
+
+        let mut cursor = Cursor {
+            data: &data,
+            position: start + SIGNATURE.len(),
+        };
+        let version = cursor.u8()?;
+        let (offset_size, length_size, base, root) = match version {
+            0 | 1 => {
+                cursor.skip(4)?; // free space, root group, reserved, shared header versions
+                let offset_size = cursor.u8()? as usize;
+                let length_size = cursor.u8()? as usize;
+                cursor.skip(1 + 2 + 2 + 4)?; // reserved, group K values, consistency flags
+                if version == 1 {
+                    cursor.skip(4)?; // indexed storage K, reserved
+                }
+                let base = cursor.uint(offset_size)?;
+                cursor.skip(3 * offset_size)?; // free space, end of file, driver info
+                cursor.skip(offset_size)?; // root link name offset
+                let root = cursor.uint(offset_size)?;
+                (offset_size, length_size, base, root)
+            }
+            2 | 3 => {
+                let offset_size = cursor.u8()? as usize;
+                let length_size = cursor.u8()? as usize;
+                cursor.skip(1)?; // consistency flags
+                let base = cursor.uint(offset_size)?;
+                cursor.skip(2 * offset_size)?; // superblock extension, end of file
+                let root = cursor.uint(offset_size)?;
+                (offset_size, length_size, base, root)
+            }
+            _ => {
+                return Err(VtkError::InvalidFormat(
+                    "Unsupported HDF5 superblock version",
+                ))
+            }
+        };
+        if !matches!(offset_size, 2 | 4 | 8) || !matches!(length_size, 2 | 4 | 8) {
+            return Err(VtkError::InvalidFormat("Invalid HDF5 address size"));
+        }
+
+        Ok(Self {
+            data,
+            offset_size,
+            length_size,
+            base,
+            root,
+        })
+    }
+
+    /// Object header address of the root group
+    pub fn root(&self) -> u64 {
+        self.root
+    }
+
+    /// Object header address of an object by its path
+    ///
+    /// # Parameters
+    /// - `path`: Absolute path of group names, e.g. `/Mesh/mesh/geometry`
+    ///
+    /// # Returns
+    /// - `Ok(u64)`: Object header address
+    /// - `Err(VtkError)`: A group on the path has no such member
+    pub fn find(&self, path: &str) -> Result<u64, VtkError> {
+        let mut object = self.root;
+        for name in path.split('/').filter(|name| !name.is_empty()) {
+            object = self
+                .children(object)?
+                .into_iter()
+                .find(|(child, _)| child == name)
+                .map(|(_, address)| address)
+                .ok_or_else(|| VtkError::LoadError(format!("HDF5 object not found: {}", path)))?;
+        }
+        Ok(object)
+    }
+
+    /// Cursor at a file address
+    fn cursor(&self, address: u64) -> Result<Cursor<'_>, VtkError> {
+        let position = address
+            .checked_add(self.base)
+            .filter(|&position| (position as usize) < self.data.len())
+            .ok_or_else(truncated)?;
+        Ok(Cursor {
+            data: &self.data,
+            position: position as usize,
+        })
+    }
+
+    /// Read a file address (all bits set = undefined)
+    fn address(&self, cursor: &mut Cursor) -> Result<u64, VtkError> {
+        let address = cursor.uint(self.offset_size)?;
+        if address == u64::MAX >> (64 - 8 * self.offset_size) {
+            Ok(UNDEFINED)
+        } else {
+            Ok(address)
+        }
+    }
+
+    fn length(&self, cursor: &mut Cursor) -> Result<u64, VtkError> {
+        cursor.uint(self.length_size)
+    }
+
+    /// Messages of an object header as (type, data position, data size)
+    fn messages(&self, address: u64) -> Result<Vec<(u16, usize, usize)>, VtkError> {
+        let mut cursor = self.cursor(address)?;
+        let mut messages = Vec::new();
+        // Blocks of messages: (start, end, version 2 layout)
+        let mut blocks = Vec::new();
+        let mut creation_order = false;
+
+        if cursor.bytes(4)? == b"OHDR" {
+            let version = cursor.u8()?;
+            let flags = cursor.u8()?;
+            if version != 2 {
+                return Err(VtkError::InvalidFormat("Unsupported HDF5 object header"));
+            }
+            if flags & 0x20 != 0 {
+                cursor.skip(16)?; // access, modification, change and birth times
+            }
+            if flags & 0x10 != 0 {
+                cursor.skip(4)?; // attribute storage phase change values
+            }
+            creation_order = flags & 0x04 != 0;
+            let size = cursor.uint(1 << (flags & 0x03))? as usize;
+            blocks.push((cursor.position, cursor.position + size, true));
+        } else {
+            cursor.position -= 4;
+            if cursor.u8()? != 1 {
+                return Err(VtkError::InvalidFormat("Unsupported HDF5 object header"));
+            }
+            cursor.skip(1 + 2 + 4)?; // reserved, message count, reference count
+            let size = cursor.u32()? as usize;
+            // Messages are aligned to 8 bytes after the 12-byte prefix
+            let start = cursor.position + 4;
+            blocks.push((start, start + size, false));
+        }
+
+        while let Some((start, end, version2)) = blocks.pop() {
+            let end = end.min(self.data.len());
+            let header_size = match (version2, creation_order) {
+                (false, _) => 8,
+                (true, false) => 4,
+                (true, true) => 6,
+            };
+            let mut cursor = Cursor {
+                data: &self.data,
+                position: start,
+            };
+            while cursor.position + header_size <= end {
+                let (kind, size) = if version2 {
+                    let kind = cursor.u8()? as u16;
+                    let size = cursor.u16()? as usize;
+                    cursor.skip(header_size - 3)?; // flags, creation order
+                    (kind, size)
+                } else {
+                    let kind = cursor.u16()?;
+                    let size = cursor.u16()? as usize;
+                    cursor.skip(4)?; // flags, reserved
+                    (kind, size)
+                };
+                let position = cursor.position;
+                cursor.skip(size)?;
+
+                if kind == MESSAGE_CONTINUATION {
+                    let mut continuation = Cursor {
+                        data: &self.data,
+                        position,
+                    };
+                    let address = self.address(&mut continuation)?;
+                    let length = self.length(&mut continuation)? as usize;
+                    let mut block = self.cursor(address)?;
+                    if version2 {
+                        // "OCHK" signature before and checksum after the messages
+                        block.signature(b"OCHK")?;
+                        blocks.push((block.position, block.position + length - 8, true));
+                    } else {
+                        blocks.push((block.position, block.position + length, false));
+                    }
+                } else {
+                    messages.push((kind, position, size));
+                }
+            }
+        }
+
+        Ok(messages)
+    }
+
+    /// Child links of a group as (name, object header address), hard links only
+    ///
+    /// # Parameters
+    /// - `group`: Object header address of the group
+    pub fn children(&self, group: u64) -> Result<Vec<(String, u64)>, VtkError> {
+        let mut children = Vec::new();
+        for (kind, position, _) in self.messages(group)? {
+            let mut cursor = Cursor {
+                data: &self.data,
+                position,
+            };
+            match kind {
+                MESSAGE_SYMBOL_TABLE => {
+                    let tree = self.address(&mut cursor)?;
+                    let heap = self.address(&mut cursor)?;
+                    self.symbol_table_children(tree, heap, &mut children)?;
+                }
+                MESSAGE_LINK => children.extend(self.link(&mut cursor)?),
+                MESSAGE_LINK_INFO => {
+                    cursor.skip(1)?; // version
+                    let flags = cursor.u8()?;
+                    if flags & 0x01 != 0 {
+                        cursor.skip(8)?; // maximum creation index
+                    }
+                    let heap = self.address(&mut cursor)?;
+                    let names = self.address(&mut cursor)?;
+                    if heap != UNDEFINED && names != UNDEFINED {
+                        self.dense_children(heap, names, &mut children)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(children)
+    }
+
+    /// Parse a link message; `None` for soft and external links
+    fn link(&self, cursor: &mut Cursor) -> Result<Option<(String, u64)>, VtkError> {
+        cursor.skip(1)?; // version
+        let flags = cursor.u8()?;
+        let link_type = if flags & 0x08 != 0 { cursor.u8()? } else { 0 };
+        if flags & 0x04 != 0 {
+            cursor.skip(8)?; // creation order
+        }
+        if flags & 0x10 != 0 {
+            cursor.skip(1)?; // character set
+        }
+        let name_length = cursor.uint(1 << (flags & 0x03))? as usize;
+        let name = String::from_utf8_lossy(cursor.bytes(name_length)?).to_string();
+        if link_type != 0 {
+            return Ok(None);
+        }
+        Ok(Some((name, self.address(cursor)?)))
+    }
+
+    /// Visit the children of a version 1 B-tree node and its subtrees
+    ///
+    /// # Parameters
+    /// - `address`: Node address
+    /// - `key_size`: Size of the node keys
+    /// - `visit`: Called with the key before every leaf child and the child address
+    fn visit_tree_v1(
+        &self,
+        address: u64,
+        key_size: usize,
+        visit: &mut impl FnMut(&[u8], u64) -> Result<(), VtkError>,
+    ) -> Result<(), VtkError> {
+        let mut cursor = self.cursor(address)?;
+        cursor.signature(b"TREE")?;
+        cursor.skip(1)?; // node type
+        let level = cursor.u8()?;
+        let entries = cursor.u16()? as usize;
+        cursor.skip(2 * self.offset_size)?; // siblings
+        for _ in 0..entries {
+            let key = cursor.bytes(key_size)?;
+            let child = self.address(&mut cursor)?;
+            if level > 0 {
+                self.visit_tree_v1(child, key_size, visit)?;
+            } else {
+                visit(key, child)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Children of a group stored as symbol table (B-tree of symbol nodes)
+    fn symbol_table_children(
+        &self,
+        tree: u64,
+        heap: u64,
+        children: &mut Vec<(String, u64)>,
+    ) -> Result<(), VtkError> {
+        let mut cursor = self.cursor(heap)?;
+        cursor.signature(b"HEAP")?;
+        cursor.skip(4 + 2 * self.length_size)?; // version, reserved, sizes
+        let names = self.address(&mut cursor)?;
+
+        self.visit_tree_v1(tree, self.length_size, &mut |_, node| {
+            let mut cursor = self.cursor(node)?;
+            cursor.signature(b"SNOD")?;
+            cursor.skip(2)?; // version, reserved
+            let symbols = cursor.u16()?;
+            for _ in 0..symbols {
+                let name_offset = cursor.uint(self.offset_size)?;
+                let object = self.address(&mut cursor)?;
+                cursor.skip(4 + 4 + 16)?; // cache type, reserved, scratch pad
+                let start = self.cursor(names.wrapping_add(name_offset))?.position;
+                let end = self.data[start..]
+                    .iter()
+                    .position(|&byte| byte == 0)
+                    .map_or(self.data.len(), |length| start + length);
+                children.push((
+                    String::from_utf8_lossy(&self.data[start..end]).to_string(),
+                    object,
+                ));
+            }
+            Ok(())
+        })
+    }
+
+    /// Children of a group with dense link storage
+    fn dense_children(
+        &self,
+        heap: u64,
+        names: u64,
+        children: &mut Vec<(String, u64)>,
+    ) -> Result<(), VtkError> {
+        let heap = self.fractal_heap(heap)?;
+        for record in self.tree_v2_records(names)? {
+            // Name hash, then the heap ID of the link message
+            let mut id = Cursor {
+                data: &record,
+                position: 4,
+            };
+            if (id.u8()? >> 4) & 0x03 != 0 {
+                return Err(VtkError::LoadError(
+                    "HDF5 huge or tiny link objects are not supported".to_string(),
+                ));
+            }
+            let offset = id.uint(heap.offset_size)?;
+            let address = self.heap_object(&heap, heap.root_address, heap.root_rows, 0, offset)?;
+            let mut cursor = self.cursor(address)?;
+            children.extend(self.link(&mut cursor)?);
+        }
+        Ok(())
+    }
+
+    /// Parse a fractal heap header
+    fn fractal_heap(&self, address: u64) -> Result<FractalHeap, VtkError> {
+        let mut cursor = self.cursor(address)?;
+        cursor.signature(b"FRHP")?;
+        cursor.skip(1 + 2)?; // version, heap ID length
+        let filter_length = cursor.u16()?;
+        cursor.skip(1)?; // flags
+        cursor.skip(4)?; // maximum managed object size
+        cursor.skip(self.length_size)?; // next huge object ID
+        cursor.skip(self.offset_size)?; // huge object B-tree
+        cursor.skip(self.length_size)?; // free space
+        cursor.skip(self.offset_size)?; // free space manager
+        cursor.skip(8 * self.length_size)?; // managed, huge and tiny object statistics
+        let table_width = cursor.u16()? as u64;
+        let start_block_size = self.length(&mut cursor)?;
+        let max_direct_size = self.length(&mut cursor)?;
+        let max_heap_bits = cursor.u16()? as usize;
+        cursor.skip(2)?; // starting rows of the root indirect block
+        let root_address = self.address(&mut cursor)?;
+        let root_rows = cursor.u16()? as u64;
+
+        let log2 = |value: u64| 63 - value.max(1).leading_zeros() as u64;
+        Ok(FractalHeap {
+            offset_size: max_heap_bits.div_ceil(8),
+            filtered: filter_length > 0,
+            table_width,
+            start_block_size,
+            max_direct_rows: log2(max_direct_size) - log2(start_block_size) + 2,
+            root_address,
+            root_rows,
+        })
+    }
+
+    /// File address of a managed fractal heap object
+    ///
+    /// # Parameters
+    /// - `block`: Address of the (root or child) block containing the offset
+    /// - `rows`: Rows of the indirect block (0 = direct block)
+    /// - `block_offset`: Heap offset of the block
+    /// - `offset`: Heap offset of the object
+    fn heap_object(
+        &self,
+        heap: &FractalHeap,
+        block: u64,
+        rows: u64,
+        block_offset: u64,
+        offset: u64,
+    ) -> Result<u64, VtkError> {
+        if rows == 0 {
+            return Ok(block + (offset - block_offset));
+        }
+
+        let mut cursor = self.cursor(block)?;
+        cursor.signature(b"FHIB")?;
+        cursor.skip(1 + self.offset_size + heap.offset_size)?; // version, heap, block offset
+        let entries_start = cursor.position;
+        let direct_entry_size = self.offset_size
+            + if heap.filtered {
+                self.length_size + 4
+            } else {
+                0
+            };
+        let direct_rows = rows.min(heap.max_direct_rows);
+
+        let mut start = block_offset;
+        for row in 0..rows {
+            let size = heap.row_size(row);
+            for column in 0..heap.table_width {
+                if offset < start + size {
+                    let mut entry = Cursor {
+                        data: &self.data,
+                        position: entries_start,
+                    };
+                    return if row < heap.max_direct_rows {
+                        entry.position +=
+                            (row * heap.table_width + column) as usize * direct_entry_size;
+                        let address = self.address(&mut entry)?;
+                        Ok(address + (offset - start))
+                    } else {
+                        entry.position += (direct_rows * heap.table_width) as usize
+                            * direct_entry_size
+                            + ((row - heap.max_direct_rows) * heap.table_width + column) as usize
+                                * self.offset_size;
+                        let address = self.address(&mut entry)?;
+                        let log2 = |value: u64| 63 - value.max(1).leading_zeros() as u64;
+                        let child_rows =
+                            log2(size) - log2(heap.start_block_size * heap.table_width) + 1;
+                        self.heap_object(heap, address, child_rows, start, offset)
+                    };
+                }
+                start += size;
+            }
+        }
+        Err(VtkError::InvalidFormat("HDF5 heap object beyond heap"))
+    }
+
+    /// All records of a version 2 B-tree, in order
+    fn tree_v2_records(&self, address: u64) -> Result<Vec<Vec<u8>>, VtkError> {
+        let mut cursor = self.cursor(address)?;
+        cursor.signature(b"BTHD")?;
+        cursor.skip(2)?; // version, type
+        let node_size = cursor.u32()? as u64;
+        let record_size = cursor.u16()? as usize;
+        let depth = cursor.u16()? as usize;
+        cursor.skip(2)?; // split and merge percents
+        let root = self.address(&mut cursor)?;
+        let root_records = cursor.u16()? as usize;
+
+        // Sizes of the child pointers at every depth
+        let mut max_records = vec![(node_size - 10) / record_size as u64];
+        let mut cumulative = vec![max_records[0]];
+        let records_size = encoded_size(max_records[0]);
+        let pointer_size = |depth: usize, cumulative: &[u64]| {
+            self.offset_size
+                + records_size
+                + if depth > 1 {
+                    encoded_size(cumulative[depth - 1])
+                } else {
+                    0
+                }
+        };
+        for level in 1..=depth {
+            let pointer = pointer_size(level, &cumulative) as u64;
+            let records = (node_size - 10 - pointer) / (record_size as u64 + pointer);
+            max_records.push(records);
+            cumulative.push((records + 1) * cumulative[level - 1] + records);
+        }
+
+        let mut records = Vec::new();
+        if root != UNDEFINED {
+            self.visit_tree_v2(
+                root,
+                root_records,
+                depth,
+                record_size,
+                &|level| pointer_size(level, &cumulative),
+                records_size,
+                &mut records,
+            )?;
+        }
+        Ok(records)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn visit_tree_v2(
+        &self,
+        address: u64,
+        count: usize,
+        depth: usize,
+        record_size: usize,
+        pointer_size: &dyn Fn(usize) -> usize,
+        records_size: usize,
+        records: &mut Vec<Vec<u8>>,
+    ) -> Result<(), VtkError> {
+        let mut cursor = self.cursor(address)?;
+        let signature: &[u8; 4] = if depth == 0 { b"BTLF" } else { b"BTIN" };
+        cursor.signature(signature)?;
+        cursor.skip(2)?; // version, type
+        let node_records: Vec<&[u8]> = (0..count)
+            .map(|_| cursor.bytes(record_size))
+            .collect::<Result<_, _>>()?;
+        if depth == 0 {
+            records.extend(node_records.iter().map(|record| record.to_vec()));
+            return Ok(());
+        }
+
+        let pointer = pointer_size(depth);
+        for i in 0..=count {
+            let mut child = Cursor {
+                data: &self.data,
+                position: cursor.position + i * pointer,
+            };
+            let child_address = self.address(&mut child)?;
+            let child_count = child.uint(records_size)? as usize;
+            self.visit_tree_v2(
+                child_address,
+                child_count,
+                depth - 1,
+                record_size,
+                pointer_size,
+                records_size,
+                records,
+            )?;
+            if let Some(record) = node_records.get(i) {
+                records.push(record.to_vec());
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse a datatype message
+    fn datatype(&self, position: usize) -> Result<Datatype, VtkError> {
+        let mut cursor = Cursor {
+            data: &self.data,
+            position,
+        };
+        let class = cursor.u8()? & 0x0F;
+        let bits = cursor.u8()?;
+        cursor.skip(2)?;
+        let size = cursor.u32()? as usize;
+        Ok(Datatype {
+            class,
+            size,
+            big_endian: matches!(class, 0 | 1) && bits & 0x01 != 0,
+            signed: class == 0 && bits & 0x08 != 0,
+        })
+    }
+
+    /// Parse a dataspace message into dimension lengths
+    fn dataspace(&self, position: usize) -> Result<Vec<usize>, VtkError> {
+        let mut cursor = Cursor {
+            data: &self.data,
+            position,
+        };
+        let version = cursor.u8()?;
+        let rank = cursor.u8()? as usize;
+        cursor.skip(1)?; // flags
+        if version == 1 {
+            cursor.skip(5)?; // reserved
+        } else if cursor.u8()? == 2 {
+            return Ok(vec![0]); // null dataspace
+        }
+        (0..rank)
+            .map(|_| self.length(&mut cursor).map(|length| length as usize))
+            .collect()
+    }
+
+    /// Read a named attribute of an object
+    ///
+    /// # Returns
+    /// - `Ok(Some(Dataset))`: Attribute data
+    /// - `Ok(None)`: The object has no such (compact) attribute
+    pub fn attribute(&self, object: u64, name: &str) -> Result<Option<Dataset>, VtkError> {
+        for (kind, position, _) in self.messages(object)? {
+            if kind != MESSAGE_ATTRIBUTE {
+                continue;
+            }
+            let mut cursor = Cursor {
+                data: &self.data,
+                position,
+            };
+            let version = cursor.u8()?;
+            cursor.skip(1)?; // reserved or flags
+            let name_size = cursor.u16()? as usize;
+            let datatype_size = cursor.u16()? as usize;
+            let dataspace_size = cursor.u16()? as usize;
+            if version == 3 {
+                cursor.skip(1)?; // name character set
+            }
+            // Version 1 pads every field to 8 bytes
+            let padded = |size: usize| {
+                if version == 1 {
+                    size.next_multiple_of(8)
+                } else {
+                    size
+                }
+            };
+            let attribute_name = cursor.bytes(name_size)?;
+            cursor.skip(padded(name_size) - name_size)?;
+            let attribute_name = attribute_name.strip_suffix(&[0]).unwrap_or(attribute_name);
+            if attribute_name != name.as_bytes() {
+                continue;
+            }
+
+            let datatype = self.datatype(cursor.position)?;
+            cursor.skip(padded(datatype_size))?;
+            let shape = self.dataspace(cursor.position)?;
+            cursor.skip(padded(dataspace_size))?;
+            let count: usize = shape.iter().product();
+            let bytes = cursor.bytes(count * datatype.size)?.to_vec();
+            return Ok(Some(Dataset {
+                shape,
+                datatype,
+                bytes,
+            }));
+        }
+        Ok(None)
+    }
+
+    /// Read the data of a dataset
+    ///
+    /// # Parameters
+    /// - `object`: Object header address of the dataset
+    ///
+    /// # Returns
+    /// - `Ok(Dataset)`: Shape, type and raw data
+    /// - `Err(VtkError)`: Not a dataset, or an unsupported storage layout or filter
+    pub fn read_dataset(&self, object: u64) -> Result<Dataset, VtkError> {
+        let mut datatype = None;
+        let mut shape = None;
+        let mut layout = None;
+        let mut filters = Vec::new();
+        for (kind, position, size) in self.messages(object)? {
+            match kind {
+                MESSAGE_DATATYPE => datatype = Some(self.datatype(position)?),
+                MESSAGE_DATASPACE => shape = Some(self.dataspace(position)?),
+                MESSAGE_LAYOUT => layout = Some(self.layout(position, size)?),
+                MESSAGE_FILTERS => filters = self.filters(position)?,
+                _ => {}
+            }
+        }
+        let (Some(datatype), Some(shape), Some(layout)) = (datatype, shape, layout) else {
+            return Err(VtkError::MissingData("HDF5 object is not a dataset"));
+        };
+
+        let total = shape.iter().product::<usize>() * datatype.size;
+        let bytes = match layout {
+            Layout::Compact { start, size } => self.data[start..start + size].to_vec(),
+            Layout::Contiguous { address, size } => {
+                if address == UNDEFINED {
+                    vec![0; total]
+                } else {
+                    let cursor = self.cursor(address)?;
+                    let size = (size as usize).min(total);
+                    self.data
+                        .get(cursor.position..cursor.position + size)
+                        .ok_or_else(truncated)?
+                        .to_vec()
+                }
+            }
+            Layout::SingleChunk {
+                address,
+                size,
+                mask,
+            } => {
+                let cursor = self.cursor(address)?;
+                let raw = self
+                    .data
+                    .get(cursor.position..cursor.position + size as usize)
+                    .ok_or_else(truncated)?;
+                apply_filters(raw.to_vec(), &filters, mask, datatype.size)?
+            }
+            Layout::ChunkedTree { address, chunk } => {
+                self.read_chunks(address, &chunk, &shape, &filters, datatype.size)?
+            }
+        };
+        if bytes.len() < total {
+            return Err(VtkError::MissingData("HDF5 dataset data is incomplete"));
+        }
+
+        Ok(Dataset {
+            shape,
+            datatype,
+            bytes: bytes[..total].to_vec(),
+        })
+    }
+
+    /// Parse a data layout message
+    fn layout(&self, position: usize, message_size: usize) -> Result<Layout, VtkError> {
+        let mut cursor = Cursor {
+            data: &self.data,
+            position,
+        };
+        let version = cursor.u8()?;
+        let unsupported = || VtkError::LoadError("Unsupported HDF5 dataset layout".to_string());
+
+        if version < 3 {
+            let rank = cursor.u8()? as usize;
+            let class = cursor.u8()?;
+            cursor.skip(5)?;
+            let address = if class != 0 {
+                self.address(&mut cursor)?
+            } else {
+                UNDEFINED
+            };
+            let dims: Vec<u64> = (0..rank)
+                .map(|_| cursor.u32().map(|d| d as u64))
+                .collect::<Result<_, _>>()?;
+            return match class {
+                0 => {
+                    let size = cursor.u32()? as usize;
+                    Ok(Layout::Compact {
+                        start: cursor.position,
+                        size: size.min(position + message_size - cursor.position),
+                    })
+                }
+                1 => Ok(Layout::Contiguous {
+                    address,
+                    size: dims.iter().product(),
+                }),
+                2 => Ok(Layout::ChunkedTree {
+                    address,
+                    chunk: dims,
+                }),
+                _ => Err(unsupported()),
+            };
+        }
+
+        match cursor.u8()? {
+            0 => {
+                let size = cursor.u16()? as usize;
+                Ok(Layout::Compact {
+                    start: cursor.position,
+                    size,
+                })
+            }
+            1 => {
+                let address = self.address(&mut cursor)?;
+                let size = self.length(&mut cursor)?;
+                Ok(Layout::Contiguous { address, size })
+            }
+            2 if version == 3 => {
+                let rank = cursor.u8()? as usize;
+                let address = self.address(&mut cursor)?;
+                let chunk = (0..rank)
+                    .map(|_| cursor.u32().map(|d| d as u64))
+                    .collect::<Result<_, _>>()?;
+                Ok(Layout::ChunkedTree { address, chunk })
+            }
+            2 => {
+                let flags = cursor.u8()?;
+                let rank = cursor.u8()? as usize;
+                let dim_size = cursor.u8()? as usize;
+                let chunk: Vec<u64> = (0..rank)
+                    .map(|_| cursor.uint(dim_size))
+                    .collect::<Result<_, _>>()?;
+                match cursor.u8()? {
+                    // Single chunk
+                    1 => {
+                        let (size, mask) = if flags & 0x02 != 0 {
+                            (self.length(&mut cursor)?, cursor.u32()?)
+                        } else {
+                            (chunk.iter().product(), 0)
+                        };
+                        let address = self.address(&mut cursor)?;
+                        Ok(Layout::SingleChunk {
+                            address,
+                            size,
+                            mask,
+                        })
+                    }
+                    _ => Err(unsupported()),
+                }
+            }
+            _ => Err(unsupported()),
+        }
+    }
+
+    /// Parse a filter pipeline message into (filter id, client values)
+    fn filters(&self, position: usize) -> Result<Filters, VtkError> {
+        let mut cursor = Cursor {
+            data: &self.data,
+            position,
+        };
+        let version = cursor.u8()?;
+        let count = cursor.u8()?;
+        if version == 1 {
+            cursor.skip(6)?;
+        }
+        let mut filters = Vec::new();
+        for _ in 0..count {
+            let id = cursor.u16()?;
+            let name_length = if version == 1 || id >= 256 {
+                cursor.u16()? as usize
+            } else {
+                0
+            };
+            cursor.skip(2)?; // flags
+            let values = cursor.u16()? as usize;
+            if version == 1 {
+                cursor.skip(name_length.next_multiple_of(8))?;
+            } else {
+                cursor.skip(name_length)?;
+            }
+            let client: Vec<u32> = (0..values)
+                .map(|_| cursor.u32())
+                .collect::<Result<_, _>>()?;
+            if version == 1 && values % 2 == 1 {
+                cursor.skip(4)?;
+            }
+            filters.push((id, client));
+        }
+        Ok(filters)
+    }
+
+    /// Assemble the chunks of a chunked dataset
+    fn read_chunks(
+        &self,
+        tree: u64,
+        chunk: &[u64],
+        shape: &[usize],
+        filters: &Filters,
+        element_size: usize,
+    ) -> Result<Vec<u8>, VtkError> {
+        let rank = shape.len();
+        let chunk: Vec<usize> = chunk[..rank].iter().map(|&d| d as usize).collect();
+        let chunk_elements: usize = chunk.iter().product();
+        let mut data = vec![0u8; shape.iter().product::<usize>() * element_size];
+        if tree == UNDEFINED {
+            return Ok(data);
+        }
+
+        let key_size = 4 + 4 + 8 * (rank + 1);
+        self.visit_tree_v1(tree, key_size, &mut |key, address| {
+            let mut key = Cursor {
+                data: key,
+                position: 0,
+            };
+            let size = key.u32()? as usize;
+            let mask = key.u32()?;
+            let origin: Vec<usize> = (0..rank)
+                .map(|_| key.uint(8).map(|offset| offset as usize))
+                .collect::<Result<_, _>>()?;
+
+            let cursor = self.cursor(address)?;
+            let raw = self
+                .data
+                .get(cursor.position..cursor.position + size)
+                .ok_or_else(truncated)?;
+            let bytes = apply_filters(raw.to_vec(), filters, mask, element_size)?;
+            if bytes.len() < chunk_elements * element_size {
+                return Err(VtkError::MissingData("HDF5 chunk data is incomplete"));
+            }
+
+            // Copy the chunk row by row (rows along the last dimension)
+            let row = chunk[rank - 1];
+            for start in (0..chunk_elements).step_by(row.max(1)) {
+                let mut index = 0;
+                let mut remainder = start;
+                let mut inside = true;
+                for axis in (0..rank).rev() {
+                    let position = origin[axis] + remainder % chunk[axis];
+                    remainder /= chunk[axis];
+                    if position >= shape[axis] {
+                        inside = false;
+                        break;
+                    }
+                    let stride: usize = shape[axis + 1..].iter().product();
+                    index += position * stride;
+                }
+                if !inside {
+                    continue;
+                }
+                let count = row.min(shape[rank - 1] - origin[rank - 1]);
+                data[index * element_size..(index + count) * element_size]
+                    .copy_from_slice(&bytes[start * element_size..(start + count) * element_size]);
+            }
+            Ok(())
+        })?;
+        Ok(data)
+    }
+}
+
+/// Undo the filters of a chunk (in reverse pipeline order)
+///
+/// # Parameters
+/// - `data`: Stored chunk bytes
+/// - `filters`: Filter pipeline as (filter id, client values)
+/// - `mask`: Bit `i` set if filter `i` was skipped for this chunk
+/// - `element_size`: Size of one element, for the shuffle filter
+fn apply_filters(
+    mut data: Vec<u8>,
+    filters: &Filters,
+    mask: u32,
+    element_size: usize,
+) -> Result<Vec<u8>, VtkError> {
+    for (index, (id, _)) in filters.iter().enumerate().rev() {
+        if mask & (1 << index) != 0 {
+            continue;
+        }
+        data = match id {
+            // Deflate
+            1 => {
+                let mut inflated = Vec::new();
+                flate2::read::ZlibDecoder::new(data.as_slice())
+                    .read_to_end(&mut inflated)
+                    .map_err(VtkError::IoError)?;
+                inflated
+            }
+            // Shuffle: byte planes back to elements
+            2 => {
+                let count = data.len() / element_size.max(1);
+                let mut unshuffled = data.clone();
+                for byte in 0..element_size {
+                    for element in 0..count {
+                        unshuffled[element * element_size + byte] = data[byte * count + element];
+                    }
+                }
+                unshuffled
+            }
+            // Fletcher-32: checksum at the end
+            3 => {
+                data.truncate(data.len().saturating_sub(4));
+                data
+            }
+            _ => {
+                return Err(VtkError::LoadError(format!(
+                    "Unsupported HDF5 filter {}",
+                    id
+                )))
+            }
+        };
+    }
+    Ok(data)
+}
@@ -0,0 +1,140 @@
+//! Integrate module
+//!
+//! Integrated quantities of the surface:
+//! - Total surface area of the triangles
+//! - Enclosed volume by the divergence theorem, for closed surfaces
+//! - Area-weighted mean, minimum and maximum of every scalar attribute
+//!
+//! Point attributes are integrated linearly over each triangle (the mean of the
+//! corner values times the area); cell attributes are constant per cell.
+use super::export::csv_field;
+use super::topology::{attribute_value, scalar_attribute_names, triangle_cell};
+use super::{AttributeLocation, GeometryData};
+use bevy::math::DVec3;
+use bevy::utils::HashMap;
+use std::fmt::Write;
+
+/// Integrated values of one scalar attribute
+#[derive(Clone, Debug)]
+pub struct AttributeIntegral {
+    /// Attribute name
+    pub name: String,
+    /// Where the attribute is stored
+    pub location: AttributeLocation,
+    /// Integral over the surface
+    pub integral: f64,
+    /// Area-weighted mean
+    pub mean: f64,
+    /// Smallest value on the surface
+    pub min: f32,
+    /// Largest value on the surface
+    pub max: f32,
+}
+
+/// Result of integrating a geometry
+#[derive(Clone, Debug, Default)]
+pub struct IntegrationReport {
+    /// Number of triangles integrated
+    pub triangles: usize,
+    /// Total surface area
+    pub area: f64,
+    /// Enclosed volume, `None` if the surface is not closed
+    pub volume: Option<f64>,
+    /// Integrals of the scalar attributes, sorted by name
+    pub attributes: Vec<AttributeIntegral>,
+}
+
+impl IntegrationReport {
+    /// Format the report as CSV
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("quantity,location,integral,mean,min,max\n");
+        let _ = writeln!(csv, "area,,{},,,", self.area);
+        if let Some(volume) = self.volume {
+            let _ = writeln!(csv, "volume,,{},,,", volume);
+        }
+        for attribute in &self.attributes {
+            let _ = writeln!(
+                csv,
+                "{},{:?},{},{},{},{}",
+                csv_field(&attribute.name),
+                attribute.location,
+                attribute.integral,
+                attribute.mean,
+                attribute.min,
+                attribute.max
+            );
+        }
+        csv
+    }
+}
+
+/// Check whether every edge is shared by exactly two triangles
+fn is_closed(indices: &[u32]) -> bool {
+    let mut edges: HashMap<(u32, u32), u32> = HashMap::new();
+    for corners in indices.chunks_exact(3) {
+        for k in 0..3 {
+            let (a, b) = (corners[k], corners[(k + 1) % 3]);
+            *edges.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+        }
+    }
+    !edges.is_empty() && edges.values().all(|&count| count == 2)
+}
+
+/// Integrate area, volume and scalar attributes over the surface
+///
+/// # Parameters
+/// - `geometry`: Geometry data
+///
+/// # Returns
+/// - Area, enclosed volume (closed surfaces only) and attribute integrals
+pub fn integrate(geometry: &GeometryData) -> IntegrationReport {
+    let mut areas = Vec::with_capacity(geometry.indices.len() / 3);
+    let mut signed_volume = 0.0;
+    for triangle in geometry.indices.chunks_exact(3) {
+        let [a, b, c] =
+            [0, 1, 2].map(|k| DVec3::from(geometry.vertices[triangle[k] as usize].map(f64::from)));
+        areas.push((b - a).cross(c - a).length() * 0.5);
+        signed_volume += a.dot(b.cross(c)) / 6.0;
+    }
+    let area: f64 = areas.iter().sum();
+
+    let attributes = scalar_attribute_names(geometry)
+        .into_iter()
+        .filter_map(|(name, location)| {
+            let attribute = geometry.get_attributes(&name, location.clone())?;
+            let mut integral = 0.0;
+            let (mut min, mut max) = (f32::INFINITY, f32::NEG_INFINITY);
+            for (triangle, corners) in geometry.indices.chunks_exact(3).enumerate() {
+                let values = match location {
+                    AttributeLocation::Point => {
+                        [0, 1, 2].map(|k| attribute_value(attribute, corners[k] as usize, None))
+                    }
+                    AttributeLocation::Cell => {
+                        [attribute_value(attribute, triangle_cell(geometry, triangle), None); 3]
+                    }
+                };
+                for value in values {
+                    min = min.min(value);
+                    max = max.max(value);
+                }
+                integral +=
+                    areas[triangle] * values.iter().map(|&v| f64::from(v)).sum::<f64>() / 3.0;
+            }
+            (min <= max).then(|| AttributeIntegral {
+                name,
+                location,
+                integral,
+                mean: if area > 0.0 { integral / area } else { 0.0 },
+                min,
+                max,
+            })
+        })
+        .collect();
+
+    IntegrationReport {
+        triangles: areas.len(),
+        area,
+        volume: is_closed(&geometry.indices).then_some(signed_volume.abs()),
+        attributes,
+    }
+}
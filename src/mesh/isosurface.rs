@@ -0,0 +1,382 @@
+//! Isosurface module
+//!
+//! Contour surfaces of point scalars, extracted by marching tetrahedra:
+//! - Volume cells of unstructured grids are split into tetrahedra
+//! - Image data volumes are split voxel by voxel the same way
+//! - Any per-point values can be contoured, e.g. distances to a slice plane
+//!
+//! Surface points lie on cell edges and are shared by neighboring cells. Point
+//! attributes are interpolated along the edges; cell attributes are kept through
+//! the triangle to cell mapping.
+use super::cells::CellShape;
+use super::topology::attribute_value;
+use super::volume::VolumeData;
+use super::{AttributeLocation, AttributeType, GeometryData};
+use bevy::math::Vec3;
+use bevy::utils::HashMap;
+
+/// Point on a cell edge, between two points of the source
+#[derive(Clone, Copy, Debug)]
+pub struct EdgePoint {
+    pub from: u32,
+    pub to: u32,
+    /// Position along the edge (0 = `from`, 1 = `to`)
+    pub t: f32,
+}
+
+/// Triangles of a contour with their points on the cell edges
+#[derive(Default)]
+struct Contour {
+    points: Vec<EdgePoint>,
+    point_ids: HashMap<(u32, u32), u32>,
+    indices: Vec<u32>,
+    triangle_cells: Vec<usize>,
+}
+
+impl Contour {
+    /// Index of the contour point on an edge, added on first use
+    fn edge_point(&mut self, a: u32, b: u32, values: &[f32], isovalue: f32) -> u32 {
+        let (from, to) = if a < b { (a, b) } else { (b, a) };
+        *self.point_ids.entry((from, to)).or_insert_with(|| {
+            let (start, end) = (values[from as usize], values[to as usize]);
+            let t = if (end - start).abs() > f32::EPSILON {
+                ((isovalue - start) / (end - start)).clamp(0.0, 1.0)
+            } else {
+                0.5
+            };
+            self.points.push(EdgePoint { from, to, t });
+            self.points.len() as u32 - 1
+        })
+    }
+
+    fn position(&self, point: u32, positions: &[[f32; 3]]) -> Vec3 {
+        let point = self.points[point as usize];
+        Vec3::from(positions[point.from as usize])
+            .lerp(Vec3::from(positions[point.to as usize]), point.t)
+    }
+
+    /// Contour one tetrahedron
+    ///
+    /// # Parameters
+    /// - `corners`: Source points of the tetrahedron
+    /// - `cell`: Source cell of the tetrahedron
+    fn add_tetrahedron(
+        &mut self,
+        corners: [u32; 4],
+        positions: &[[f32; 3]],
+        values: &[f32],
+        isovalue: f32,
+        cell: usize,
+    ) {
+        let mut inside = Vec::with_capacity(4);
+        let mut outside = Vec::with_capacity(4);
+        for corner in corners {
+            if values[corner as usize] >= isovalue {
+                inside.push(corner);
+            } else {
+                outside.push(corner);
+            }
+        }
+        let count = inside.len();
+        if count == 0 || count == 4 {
+            return;
+        }
+        // Direction from the lower to the higher values
+        let center = |corners: &[u32]| {
+            corners
+                .iter()
+                .map(|&corner| Vec3::from(positions[corner as usize]))
+                .sum::<Vec3>()
+                / corners.len() as f32
+        };
+        let uphill = center(&inside) - center(&outside);
+
+        if count == 2 {
+            // The surface cuts all edges between the two pairs: a quad
+            let quad = [
+                self.edge_point(inside[0], outside[0], values, isovalue),
+                self.edge_point(inside[0], outside[1], values, isovalue),
+                self.edge_point(inside[1], outside[1], values, isovalue),
+                self.edge_point(inside[1], outside[0], values, isovalue),
+            ];
+            self.add_triangle([quad[0], quad[1], quad[2]], uphill, positions, cell);
+            self.add_triangle([quad[0], quad[2], quad[3]], uphill, positions, cell);
+        } else {
+            // One corner is separated from the other three: a triangle
+            let (lone, others) = if count == 1 {
+                (inside[0], outside)
+            } else {
+                (outside[0], inside)
+            };
+            let triangle = [0, 1, 2].map(|i| self.edge_point(lone, others[i], values, isovalue));
+            self.add_triangle(triangle, uphill, positions, cell);
+        }
+    }
+
+    /// Add a triangle facing away from the higher values
+    fn add_triangle(
+        &mut self,
+        mut triangle: [u32; 3],
+        uphill: Vec3,
+        positions: &[[f32; 3]],
+        cell: usize,
+    ) {
+        let [a, b, c] = triangle.map(|point| self.position(point, positions));
+        let normal = (b - a).cross(c - a);
+        if normal.dot(uphill) > 0.0 {
+            triangle.swap(1, 2);
+        }
+        self.indices.extend_from_slice(&triangle);
+        self.triangle_cells.push(cell);
+    }
+
+    /// Build the surface geometry
+    ///
+    /// # Parameters
+    /// - `positions`: Source point positions
+    /// - `attributes`: Source attributes (point attributes are interpolated)
+    fn into_geometry(
+        self,
+        positions: &[[f32; 3]],
+        attributes: Option<&HashMap<(String, AttributeLocation), AttributeType>>,
+    ) -> GeometryData {
+        let vertices = (0..self.points.len() as u32)
+            .map(|point| self.position(point, positions).to_array())
+            .collect();
+
+        let attributes = attributes
+            .map(|attributes| {
+                attributes
+                    .iter()
+                    .filter_map(|((name, location), attribute)| {
+                        let attribute = match location {
+                            AttributeLocation::Point if !name.starts_with("__lut_") => {
+                                interpolate_attribute(attribute, &self.points)?
+                            }
+                            _ => attribute.clone(),
+                        };
+                        Some(((name.clone(), location.clone()), attribute))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        GeometryData::new(vertices, self.indices, attributes)
+            .add_triangle_to_cell_mapping(self.triangle_cells)
+    }
+}
+
+/// Interpolate a point attribute at points on edges
+///
+/// # Returns
+/// - Attribute with one element per edge point, `None` if the attribute does
+///   not cover the edge end points
+pub fn interpolate_attribute(
+    attribute: &AttributeType,
+    points: &[EdgePoint],
+) -> Option<AttributeType> {
+    fn lerp<const N: usize>(data: &[[f32; N]], points: &[EdgePoint]) -> Option<Vec<[f32; N]>> {
+        points
+            .iter()
+            .map(|point| {
+                let from = data.get(point.from as usize)?;
+                let to = data.get(point.to as usize)?;
+                Some(std::array::from_fn(|i| {
+                    from[i] + (to[i] - from[i]) * point.t
+                }))
+            })
+            .collect()
+    }
+
+    Some(match attribute {
+        AttributeType::Scalar {
+            num_comp,
+            table_name,
+            data,
+            lookup_table,
+        } => {
+            let num_comp = (*num_comp).max(1);
+            let mut values = Vec::with_capacity(points.len() * num_comp);
+            for point in points {
+                let from =
+                    data.get(point.from as usize * num_comp..(point.from as usize + 1) * num_comp)?;
+                let to =
+                    data.get(point.to as usize * num_comp..(point.to as usize + 1) * num_comp)?;
+                values.extend(from.iter().zip(to).map(|(a, b)| a + (b - a) * point.t));
+            }
+            AttributeType::Scalar {
+                num_comp,
+                table_name: table_name.clone(),
+                data: values,
+                lookup_table: lookup_table.clone(),
+            }
+        }
+        AttributeType::ColorScalar { nvalues, data } => AttributeType::ColorScalar {
+            nvalues: *nvalues,
+            data: points
+                .iter()
+                .map(|point| {
+                    let from = data.get(point.from as usize)?;
+                    let to = data.get(point.to as usize)?;
+                    Some(
+                        from.iter()
+                            .zip(to)
+                            .map(|(a, b)| a + (b - a) * point.t)
+                            .collect(),
+                    )
+                })
+                .collect::<Option<_>>()?,
+        },
+        AttributeType::Vector(data) => AttributeType::Vector(lerp(data, points)?),
+        AttributeType::Tensor(data) => AttributeType::Tensor(lerp(data, points)?),
+    })
+}
+
+/// Value range of a point attribute
+pub fn value_range(attribute: &AttributeType, point_count: usize) -> Option<(f32, f32)> {
+    (0..point_count)
+        .map(|point| attribute_value(attribute, point, None))
+        .filter(|value| value.is_finite())
+        .fold(None, |range, value| match range {
+            None => Some((value, value)),
+            Some((min, max)) => Some((min.min(value), max.max(value))),
+        })
+}
+
+/// Isosurface of a point attribute through the volume cells of a geometry
+///
+/// Vector attributes are contoured by their magnitude.
+///
+/// # Parameters
+/// - `geometry`: Geometry with volume cells
+/// - `attribute`: Name of the point attribute
+/// - `isovalue`: Contour value
+///
+/// # Returns
+/// - Surface geometry, `None` if the geometry has no volume cells or attribute
+pub fn isosurface_cells(
+    geometry: &GeometryData,
+    attribute: &str,
+    isovalue: f32,
+) -> Option<GeometryData> {
+    let attribute = geometry.get_attributes(attribute, AttributeLocation::Point)?;
+    let values: Vec<f32> = (0..geometry.vertices.len())
+        .map(|point| attribute_value(attribute, point, None))
+        .collect();
+    contour_cells(geometry, &values, isovalue)
+}
+
+/// Contour of per-point values through the volume cells of a geometry
+///
+/// # Parameters
+/// - `geometry`: Geometry with volume cells
+/// - `values`: One value per vertex of the geometry
+/// - `isovalue`: Contour value
+///
+/// # Returns
+/// - Surface geometry, `None` if the geometry has no volume cells
+pub fn contour_cells(
+    geometry: &GeometryData,
+    values: &[f32],
+    isovalue: f32,
+) -> Option<GeometryData> {
+    let cells = geometry.volume_cells.as_ref()?;
+    let mut contour = Contour::default();
+    for index in 0..cells.len() {
+        let (shape, points) = cells.cell(index);
+        if points.iter().any(|&point| point as usize >= values.len()) {
+            continue;
+        }
+        for tetrahedron in shape.tetrahedra() {
+            contour.add_tetrahedron(
+                tetrahedron.map(|corner| points[corner]),
+                &geometry.vertices,
+                values,
+                isovalue,
+                cells.cell_ids[index],
+            );
+        }
+    }
+
+    let mut surface = contour.into_geometry(&geometry.vertices, geometry.attributes.as_ref());
+    surface.lookup_tables = geometry.lookup_tables.clone();
+    Some(surface)
+}
+
+/// Isosurface of a point attribute of an image data volume
+///
+/// # Parameters
+/// - `volume`: Voxel data
+/// - `attribute`: Name of the point attribute
+/// - `isovalue`: Contour value
+///
+/// # Returns
+/// - Surface geometry, `None` for flat volumes or a missing attribute
+pub fn isosurface_volume(
+    volume: &VolumeData,
+    attribute: &str,
+    isovalue: f32,
+) -> Option<GeometryData> {
+    let attribute = volume
+        .attributes
+        .get(&(attribute.to_string(), AttributeLocation::Point))?;
+    let values: Vec<f32> = (0..volume.point_count())
+        .map(|point| attribute_value(attribute, point, None))
+        .collect();
+    contour_volume(volume, &values, isovalue)
+}
+
+/// Contour of per-point values through an image data volume
+///
+/// # Parameters
+/// - `volume`: Voxel data
+/// - `values`: One value per point of the volume
+/// - `isovalue`: Contour value
+///
+/// # Returns
+/// - Surface geometry, `None` for flat volumes
+pub fn contour_volume(volume: &VolumeData, values: &[f32], isovalue: f32) -> Option<GeometryData> {
+    if volume.dims.iter().any(|&n| n < 2) || values.len() < volume.point_count() {
+        return None;
+    }
+    let mut positions = Vec::with_capacity(volume.point_count());
+    for z in 0..volume.dims[2] {
+        for y in 0..volume.dims[1] {
+            for x in 0..volume.dims[0] {
+                positions.push(volume.point_position([x, y, z]));
+            }
+        }
+    }
+
+    let mut contour = Contour::default();
+    for z in 0..volume.dims[2] - 1 {
+        for y in 0..volume.dims[1] - 1 {
+            for x in 0..volume.dims[0] - 1 {
+                // Voxel corners in hexahedron order
+                let corners = [
+                    [x, y, z],
+                    [x + 1, y, z],
+                    [x + 1, y + 1, z],
+                    [x, y + 1, z],
+                    [x, y, z + 1],
+                    [x + 1, y, z + 1],
+                    [x + 1, y + 1, z + 1],
+                    [x, y + 1, z + 1],
+                ]
+                .map(|p| volume.point_index(p) as u32);
+                let cell = volume.cell_index([x, y, z]);
+                for tetrahedron in CellShape::Hexahedron.tetrahedra() {
+                    contour.add_tetrahedron(
+                        tetrahedron.map(|corner| corners[corner]),
+                        &positions,
+                        values,
+                        isovalue,
+                        cell,
+                    );
+                }
+            }
+        }
+    }
+
+    Some(contour.into_geometry(&positions, Some(&volume.attributes)))
+}
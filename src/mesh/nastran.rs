@@ -0,0 +1,221 @@
+//! Nastran import module
+//!
+//! Reads the bulk data section of Nastran input files (`.bdf`, `.nas`):
+//! - `GRID`: node positions in the basic coordinate system
+//! - `CTRIA3`, `CQUAD4`: shell elements
+//! - `CTETRA`: solid tetrahedra (10-node tetrahedra by their corner nodes)
+//!
+//! Cards may use small field (8 characters), large field (`GRID*`, 16 characters)
+//! or free field (comma separated) format, with continuation lines. The elements
+//! are triangulated by the triangulation module, one cell per element, and the
+//! property id is kept as cell scalar `property_id`. Other cards are skipped.
+use super::triangulation::triangulate_cells;
+use super::{AttributeLocation, AttributeType, GeometryData, VtkError};
+use bevy::utils::HashMap;
+use std::path::Path;
+use vtkio::model::{self, VertexNumbers};
+
+/// Split a physical line into its card name and data fields
+///
+/// # Returns
+/// - `(name, fields)`: `name` is empty for continuation lines
+fn split_fields(line: &str) -> (String, Vec<String>) {
+    if line.contains(',') {
+        // Free field: name, up to 8 data fields and a continuation marker
+        let mut fields = line.split(',').map(|field| field.trim().to_string());
+        let name = fields.next().unwrap_or_default();
+        let count = if name.ends_with('*') { 4 } else { 8 };
+        let mut fields: Vec<String> = fields.take(count).collect();
+        // Omitted trailing fields are blank, so continuations stay aligned
+        fields.resize(count, String::new());
+        return (name, fields);
+    }
+
+    let field = |start: usize, width: usize| -> String {
+        line.get(start..(start + width).min(line.len()))
+            .unwrap_or("")
+            .trim()
+            .to_string()
+    };
+    let name = field(0, 8);
+    // Large field cards and their continuations use 16-character data fields
+    let (width, count) = if name.ends_with('*') { (16, 4) } else { (8, 8) };
+    let fields = (0..count).map(|i| field(8 + i * width, width)).collect();
+    (name, fields)
+}
+
+/// Logical cards (name and data fields) of the bulk data section
+fn read_cards(source: &str) -> Vec<(String, Vec<String>)> {
+    let in_bulk_data = source.lines().any(|line| {
+        line.trim_start()
+            .to_ascii_uppercase()
+            .starts_with("BEGIN BULK")
+    });
+    let mut bulk = !in_bulk_data;
+    let mut cards: Vec<(String, Vec<String>)> = Vec::new();
+
+    for line in source.lines() {
+        let line = line.split('$').next().unwrap_or("").trim_end();
+        let upper = line.trim_start().to_ascii_uppercase();
+        if upper.starts_with("BEGIN BULK") {
+            bulk = true;
+            continue;
+        }
+        if upper.starts_with("ENDDATA") {
+            break;
+        }
+        if !bulk || line.trim().is_empty() {
+            continue;
+        }
+        if upper.starts_with("INCLUDE") {
+            println!("Skipping Nastran include: {}", line.trim());
+            continue;
+        }
+
+        let (name, fields) = split_fields(line);
+        let continuation = name.is_empty() || name.starts_with('+') || name.starts_with('*');
+        match cards.last_mut() {
+            Some((_, card_fields)) if continuation => card_fields.extend(fields),
+            _ if continuation => {}
+            _ => cards.push((name.trim_end_matches('*').to_ascii_uppercase(), fields)),
+        }
+    }
+
+    cards
+}
+
+/// Parse a Nastran real, which may omit the exponent letter (`1.5-3`)
+fn parse_real(field: &str) -> Option<f64> {
+    let field = field.replace(['D', 'd'], "E");
+    if let Ok(value) = field.parse() {
+        return Some(value);
+    }
+    let exponent = field
+        .char_indices()
+        .skip(1)
+        .find(|&(i, c)| matches!(c, '+' | '-') && !field[..i].ends_with(['E', 'e']))?
+        .0;
+    format!("{}E{}", &field[..exponent], &field[exponent..])
+        .parse()
+        .ok()
+}
+
+/// Parse Nastran bulk data into geometry data
+///
+/// # Parameters
+/// - `source`: Contents of a Nastran input file
+///
+/// # Returns
+/// - `Ok(GeometryData)`: Triangulated elements with the cell scalar `property_id`
+/// - `Err(VtkError)`: Malformed card or reference to an unknown grid point
+pub fn parse_nastran(source: &str) -> Result<GeometryData, VtkError> {
+    let cards = read_cards(source);
+
+    let mut vertices = Vec::new();
+    let mut grid_index: HashMap<u64, u32> = HashMap::new();
+    let mut skipped: HashMap<String, usize> = HashMap::new();
+    for (name, fields) in &cards {
+        if name != "GRID" {
+            continue;
+        }
+        let id: u64 = fields
+            .first()
+            .and_then(|id| id.parse().ok())
+            .ok_or(VtkError::InvalidFormat("Invalid Nastran GRID id"))?;
+        if fields.get(1).is_some_and(|cp| !cp.is_empty() && cp != "0") {
+            println!(
+                "GRID {} uses coordinate system {}, read as basic",
+                id, fields[1]
+            );
+        }
+        let mut position = [0.0f32; 3];
+        for (axis, value) in position.iter_mut().enumerate() {
+            let field = fields.get(2 + axis).map(String::as_str).unwrap_or("");
+            if !field.is_empty() {
+                *value = parse_real(field)
+                    .ok_or(VtkError::InvalidFormat("Invalid Nastran GRID coordinate"))?
+                    as f32;
+            }
+        }
+        grid_index.insert(id, vertices.len() as u32);
+        vertices.push(position);
+    }
+
+    let mut connectivity = Vec::new();
+    let mut offsets = Vec::new();
+    let mut types = Vec::new();
+    let mut property_ids = Vec::new();
+    for (name, fields) in &cards {
+        let (cell_type, node_count) = match name.as_str() {
+            "GRID" => continue,
+            "CTRIA3" => (model::CellType::Triangle, 3),
+            "CQUAD4" => (model::CellType::Quad, 4),
+            "CTETRA" => (model::CellType::Tetra, 4),
+            _ => {
+                *skipped.entry(name.clone()).or_default() += 1;
+                continue;
+            }
+        };
+        let property_id: f32 = fields
+            .get(1)
+            .and_then(|pid| pid.parse().ok())
+            .unwrap_or(0.0);
+        for field in fields.iter().skip(2).take(node_count) {
+            let grid: u64 = field
+                .parse()
+                .map_err(|_| VtkError::InvalidFormat("Invalid Nastran element node"))?;
+            let index = grid_index.get(&grid).ok_or_else(|| {
+                VtkError::LoadError(format!("{} references unknown GRID {}", name, grid))
+            })?;
+            connectivity.push(*index as u64);
+        }
+        if connectivity.len() as u64 != offsets.last().copied().unwrap_or(0) + node_count as u64 {
+            return Err(VtkError::MissingData("Nastran element lacks nodes"));
+        }
+        offsets.push(connectivity.len() as u64);
+        types.push(cell_type);
+        property_ids.push(property_id);
+    }
+    for (name, count) in &skipped {
+        println!("Skipped {} unsupported Nastran {} cards", count, name);
+    }
+    if types.is_empty() {
+        return Err(VtkError::MissingData(
+            "Nastran file has no supported elements",
+        ));
+    }
+
+    let cells = model::Cells {
+        cell_verts: VertexNumbers::XML {
+            connectivity,
+            offsets,
+        },
+        types,
+    };
+    let (indices, triangle_to_cell_mapping, _, _) = triangulate_cells(cells);
+
+    let mut attributes = HashMap::new();
+    attributes.insert(
+        ("property_id".to_string(), AttributeLocation::Cell),
+        AttributeType::Scalar {
+            num_comp: 1,
+            table_name: "default".to_string(),
+            data: property_ids,
+            lookup_table: None,
+        },
+    );
+
+    println!(
+        "Parsed Nastran file: {} grid points, {} triangles",
+        vertices.len(),
+        indices.len() / 3
+    );
+    Ok(GeometryData::new(vertices, indices, attributes)
+        .add_triangle_to_cell_mapping(triangle_to_cell_mapping))
+}
+
+/// Load a Nastran bulk data file into geometry data
+pub fn load_nastran(path: &Path) -> Result<GeometryData, VtkError> {
+    let source = std::fs::read_to_string(path).map_err(VtkError::IoError)?;
+    parse_nastran(&source)
+}
@@ -0,0 +1,525 @@
+//! netCDF module
+//!
+//! Reader for netCDF classic files (CDF-1, 64-bit offset CDF-2 and 64-bit data
+//! CDF-5), the container of Exodus II and most simulation output. The whole file
+//! is read into memory; variables are decoded on request into `f64` values.
+//!
+//! netCDF-4 files are HDF5 containers and are not supported; they can be
+//! converted with `nccopy -k classic`.
+use super::VtkError;
+use std::path::Path;
+
+/// Marker of a dimension list in the header
+const NC_DIMENSION: u32 = 0x0A;
+/// Marker of a variable list in the header
+const NC_VARIABLE: u32 = 0x0B;
+/// Marker of an attribute list in the header
+const NC_ATTRIBUTE: u32 = 0x0C;
+/// Record count of files written in streaming mode
+const STREAMING: u64 = 0xFFFF_FFFF;
+
+/// External data type of a variable or attribute
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NcType {
+    Byte,
+    Char,
+    Short,
+    Int,
+    Float,
+    Double,
+    UByte,
+    UShort,
+    UInt,
+    Int64,
+    UInt64,
+}
+
+impl NcType {
+    fn from_code(code: u32) -> Result<Self, VtkError> {
+        match code {
+            1 => Ok(Self::Byte),
+            2 => Ok(Self::Char),
+            3 => Ok(Self::Short),
+            4 => Ok(Self::Int),
+            5 => Ok(Self::Float),
+            6 => Ok(Self::Double),
+            7 => Ok(Self::UByte),
+            8 => Ok(Self::UShort),
+            9 => Ok(Self::UInt),
+            10 => Ok(Self::Int64),
+            11 => Ok(Self::UInt64),
+            _ => Err(VtkError::InvalidFormat("Unknown netCDF data type")),
+        }
+    }
+
+    /// Size of one value in bytes
+    fn size(self) -> usize {
+        match self {
+            Self::Byte | Self::Char | Self::UByte => 1,
+            Self::Short | Self::UShort => 2,
+            Self::Int | Self::Float | Self::UInt => 4,
+            Self::Double | Self::Int64 | Self::UInt64 => 8,
+        }
+    }
+
+    /// Decode one big-endian value
+    fn decode(self, bytes: &[u8]) -> f64 {
+        match self {
+            Self::Byte => bytes[0] as i8 as f64,
+            Self::Char | Self::UByte => bytes[0] as f64,
+            Self::Short => i16::from_be_bytes([bytes[0], bytes[1]]) as f64,
+            Self::UShort => u16::from_be_bytes([bytes[0], bytes[1]]) as f64,
+            Self::Int => i32::from_be_bytes(bytes[..4].try_into().unwrap()) as f64,
+            Self::UInt => u32::from_be_bytes(bytes[..4].try_into().unwrap()) as f64,
+            Self::Float => f32::from_be_bytes(bytes[..4].try_into().unwrap()) as f64,
+            Self::Double => f64::from_be_bytes(bytes[..8].try_into().unwrap()),
+            Self::Int64 => i64::from_be_bytes(bytes[..8].try_into().unwrap()) as f64,
+            Self::UInt64 => u64::from_be_bytes(bytes[..8].try_into().unwrap()) as f64,
+        }
+    }
+}
+
+/// Value of a global or variable attribute
+#[derive(Clone, Debug)]
+pub enum AttributeValue {
+    /// Character attribute
+    Text(String),
+    /// Numeric attribute
+    Numbers(Vec<f64>),
+}
+
+impl AttributeValue {
+    /// Text of a character attribute
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            AttributeValue::Text(text) => Some(text),
+            AttributeValue::Numbers(_) => None,
+        }
+    }
+
+    /// Values of a numeric attribute
+    pub fn as_numbers(&self) -> Option<&[f64]> {
+        match self {
+            AttributeValue::Text(_) => None,
+            AttributeValue::Numbers(values) => Some(values),
+        }
+    }
+}
+
+/// Named dimension
+#[derive(Clone, Debug)]
+pub struct Dimension {
+    pub name: String,
+    /// Length (0 for the unlimited record dimension)
+    pub length: usize,
+}
+
+/// Variable declared in the header
+#[derive(Clone, Debug)]
+pub struct Variable {
+    pub name: String,
+    /// Dimension ids, the slowest varying first
+    pub dimensions: Vec<usize>,
+    pub attributes: Vec<(String, AttributeValue)>,
+    pub nc_type: NcType,
+    /// Padded size of the variable (of one record for record variables)
+    size: usize,
+    /// Byte offset of the data (of the first record for record variables)
+    begin: usize,
+    /// Whether the first dimension is the record dimension
+    record: bool,
+}
+
+impl Variable {
+    /// Variable attribute by name
+    pub fn attribute(&self, name: &str) -> Option<&AttributeValue> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value)
+    }
+}
+
+/// netCDF classic file held in memory
+pub struct NetcdfFile {
+    pub dimensions: Vec<Dimension>,
+    pub variables: Vec<Variable>,
+    /// Number of records along the unlimited dimension
+    pub record_count: usize,
+    /// Bytes between consecutive records of a record variable
+    record_size: usize,
+    data: Vec<u8>,
+}
+
+/// Sequential reader of the header
+struct HeaderReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    version: u8,
+}
+
+impl<'a> HeaderReader<'a> {
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], VtkError> {
+        let data = self.data;
+        let end = self
+            .position
+            .checked_add(count)
+            .ok_or(VtkError::InvalidFormat("netCDF header ends early"))?;
+        let bytes = data
+            .get(self.position..end)
+            .ok_or(VtkError::InvalidFormat("netCDF header ends early"))?;
+        self.position = end;
+        Ok(bytes)
+    }
+
+    /// Element count of a list, rejected if the rest of the file cannot hold
+    /// that many elements, so corrupt counts do not allocate huge lists
+    fn count(&mut self) -> Result<usize, VtkError> {
+        let count = self.non_negative()?;
+        if count > self.data.len() - self.position {
+            return Err(VtkError::InvalidFormat(
+                "netCDF header count exceeds the file size",
+            ));
+        }
+        Ok(count)
+    }
+
+    fn u32(&mut self) -> Result<u32, VtkError> {
+        Ok(u32::from_be_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, VtkError> {
+        Ok(u64::from_be_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+
+    /// Count or length (64-bit in CDF-5)
+    fn non_negative(&mut self) -> Result<usize, VtkError> {
+        if self.version == 5 {
+            Ok(self.u64()? as usize)
+        } else {
+            Ok(self.u32()? as usize)
+        }
+    }
+
+    /// File offset (64-bit in CDF-2 and CDF-5)
+    fn offset(&mut self) -> Result<usize, VtkError> {
+        if self.version == 1 {
+            Ok(self.u32()? as usize)
+        } else {
+            Ok(self.u64()? as usize)
+        }
+    }
+
+    /// Skip the padding to the next 4-byte boundary
+    fn pad(&mut self, count: usize) -> Result<(), VtkError> {
+        self.bytes((4 - count % 4) % 4)?;
+        Ok(())
+    }
+
+    fn name(&mut self) -> Result<String, VtkError> {
+        let length = self.non_negative()?;
+        let name = String::from_utf8_lossy(self.bytes(length)?).to_string();
+        self.pad(length)?;
+        Ok(name)
+    }
+
+    /// List marker and element count; absent lists are two zeros
+    fn list(&mut self, marker: u32) -> Result<usize, VtkError> {
+        let tag = self.u32()?;
+        let count = self.count()?;
+        if tag != marker && !(tag == 0 && count == 0) {
+            return Err(VtkError::InvalidFormat("Invalid netCDF header list"));
+        }
+        Ok(count)
+    }
+
+    fn attributes(&mut self) -> Result<Vec<(String, AttributeValue)>, VtkError> {
+        let count = self.list(NC_ATTRIBUTE)?;
+        let mut attributes = Vec::with_capacity(count);
+        for _ in 0..count {
+            let name = self.name()?;
+            let nc_type = NcType::from_code(self.u32()?)?;
+            let length = self
+                .non_negative()?
+                .checked_mul(nc_type.size())
+                .ok_or(VtkError::InvalidFormat("netCDF attribute size overflows"))?;
+            let bytes = self.bytes(length)?;
+            let value = if nc_type == NcType::Char {
+                AttributeValue::Text(
+                    String::from_utf8_lossy(bytes)
+                        .trim_end_matches('\0')
+                        .to_string(),
+                )
+            } else {
+                AttributeValue::Numbers(
+                    bytes
+                        .chunks_exact(nc_type.size())
+                        .map(|value| nc_type.decode(value))
+                        .collect(),
+                )
+            };
+            self.pad(length)?;
+            attributes.push((name, value));
+        }
+        Ok(attributes)
+    }
+}
+
+impl NetcdfFile {
+    /// Read a netCDF classic file
+    ///
+    /// # Parameters
+    /// - `path`: File path
+    ///
+    /// # Returns
+    /// - `Ok(NetcdfFile)`: Parsed header with the file contents
+    /// - `Err(VtkError)`: The file cannot be read or is not a netCDF classic file
+    pub fn open(path: &Path) -> Result<Self, VtkError> {
+        let data = std::fs::read(path).map_err(VtkError::IoError)?;
+        Self::parse(data)
+    }
+
+    /// Parse the header of netCDF classic file contents
+    pub fn parse(data: Vec<u8>) -> Result<Self, VtkError> {
+        if data.starts_with(b"\x89HDF") {
+            return Err(VtkError::LoadError(
+                "netCDF-4 (HDF5) files are not supported, convert them with `nccopy -k classic`"
+                    .to_string(),
+            ));
+        }
+        if data.len() < 4 || &data[..3] != b"CDF" || ![1, 2, 5].contains(&data[3]) {
+            return Err(VtkError::InvalidFormat("Not a netCDF classic file"));
+        }
+
+        let mut reader = HeaderReader {
+            data: &data,
+            position: 4,
+            version: data[3],
+        };
+        let record_count = if reader.version == 5 {
+            reader.u64()?
+        } else {
+            reader.u32()? as u64
+        };
+
+        let dimension_count = reader.list(NC_DIMENSION)?;
+        let mut dimensions = Vec::with_capacity(dimension_count);
+        for _ in 0..dimension_count {
+            let name = reader.name()?;
+            let length = reader.non_negative()?;
+            dimensions.push(Dimension { name, length });
+        }
+
+        // Global attributes
+        reader.attributes()?;
+
+        let variable_count = reader.list(NC_VARIABLE)?;
+        let mut variables = Vec::with_capacity(variable_count);
+        for _ in 0..variable_count {
+            let name = reader.name()?;
+            let rank = reader.count()?;
+            let mut variable_dimensions = Vec::with_capacity(rank);
+            for _ in 0..rank {
+                let id = reader.non_negative()?;
+                if id >= dimensions.len() {
+                    return Err(VtkError::InvalidFormat("Invalid netCDF dimension id"));
+                }
+                variable_dimensions.push(id);
+            }
+            let variable_attributes = reader.attributes()?;
+            let nc_type = NcType::from_code(reader.u32()?)?;
+            let size = reader.non_negative()?;
+            let begin = reader.offset()?;
+            let record = variable_dimensions
+                .first()
+                .is_some_and(|&id| dimensions[id].length == 0);
+            variables.push(Variable {
+                name,
+                dimensions: variable_dimensions,
+                attributes: variable_attributes,
+                nc_type,
+                size,
+                begin,
+                record,
+            });
+        }
+
+        let mut file = Self {
+            dimensions,
+            variables,
+            record_count: 0,
+            record_size: 0,
+            data,
+        };
+
+        // Records interleave all record variables; a single record variable is not padded
+        let record_variables: Vec<&Variable> = file.variables.iter().filter(|v| v.record).collect();
+        let record_size = match record_variables.as_slice() {
+            [] => Some(0),
+            [variable] => file
+                .element_count(variable, true)?
+                .checked_mul(variable.nc_type.size()),
+            variables => variables
+                .iter()
+                .try_fold(0usize, |sum, v| sum.checked_add(v.size)),
+        }
+        .ok_or(VtkError::InvalidFormat("netCDF record size overflows"))?;
+        let first_record = record_variables.iter().map(|v| v.begin).min().unwrap_or(0);
+        file.record_size = record_size;
+        file.record_count = if record_count == STREAMING {
+            (file.data.len().saturating_sub(first_record))
+                .checked_div(record_size)
+                .unwrap_or(0)
+        } else {
+            record_count as usize
+        };
+
+        Ok(file)
+    }
+
+    /// Length of a dimension by name (the record count for the unlimited one)
+    pub fn dimension_length(&self, name: &str) -> Option<usize> {
+        self.dimensions
+            .iter()
+            .find(|dimension| dimension.name == name)
+            .map(|dimension| {
+                if dimension.length == 0 {
+                    self.record_count
+                } else {
+                    dimension.length
+                }
+            })
+    }
+
+    /// Variable by name
+    pub fn variable(&self, name: &str) -> Option<&Variable> {
+        self.variables.iter().find(|variable| variable.name == name)
+    }
+
+    /// Dimension lengths of a variable (the record count for the record dimension)
+    pub fn shape(&self, variable: &Variable) -> Vec<usize> {
+        variable
+            .dimensions
+            .iter()
+            .map(|&id| match self.dimensions[id].length {
+                0 => self.record_count,
+                length => length,
+            })
+            .collect()
+    }
+
+    /// Number of values of a variable, or of one record if `per_record`
+    fn element_count(&self, variable: &Variable, per_record: bool) -> Result<usize, VtkError> {
+        let skip = usize::from(per_record && variable.record);
+        variable.dimensions[skip.min(variable.dimensions.len())..]
+            .iter()
+            .try_fold(1usize, |count, &id| {
+                count.checked_mul(self.dimensions[id].length)
+            })
+            .ok_or(VtkError::InvalidFormat("netCDF variable size overflows"))
+    }
+
+    /// Decode `count` values at a byte offset
+    fn decode(
+        &self,
+        variable: &Variable,
+        begin: usize,
+        count: usize,
+    ) -> Result<Vec<f64>, VtkError> {
+        let size = variable.nc_type.size();
+        let end = count
+            .checked_mul(size)
+            .and_then(|length| begin.checked_add(length));
+        let bytes = end
+            .and_then(|end| self.data.get(begin..end))
+            .ok_or(VtkError::MissingData(
+                "netCDF variable data beyond end of file",
+            ))?;
+        Ok(bytes
+            .chunks_exact(size)
+            .map(|value| variable.nc_type.decode(value))
+            .collect())
+    }
+
+    /// Read all values of a variable, records one after another
+    ///
+    /// # Parameters
+    /// - `name`: Variable name
+    ///
+    /// # Returns
+    /// - `Ok(Vec<f64>)`: Values in row-major order
+    /// - `Err(VtkError)`: No such variable or truncated data
+    pub fn read(&self, name: &str) -> Result<Vec<f64>, VtkError> {
+        let variable = self
+            .variable(name)
+            .ok_or(VtkError::MissingData("netCDF variable not found"))?;
+        if !variable.record {
+            return self.decode(
+                variable,
+                variable.begin,
+                self.element_count(variable, false)?,
+            );
+        }
+
+        let mut values = Vec::new();
+        for record in 0..self.record_count {
+            values.extend(self.read_record(name, record)?);
+        }
+        Ok(values)
+    }
+
+    /// Read one record of a record variable (all values of other variables)
+    ///
+    /// # Parameters
+    /// - `name`: Variable name
+    /// - `record`: Record (time step) index
+    ///
+    /// # Returns
+    /// - `Ok(Vec<f64>)`: Values of the record in row-major order
+    /// - `Err(VtkError)`: No such variable or record
+    pub fn read_record(&self, name: &str, record: usize) -> Result<Vec<f64>, VtkError> {
+        let variable = self
+            .variable(name)
+            .ok_or(VtkError::MissingData("netCDF variable not found"))?;
+        if !variable.record {
+            return self.read(name);
+        }
+        if record >= self.record_count {
+            return Err(VtkError::IndexOutOfBounds {
+                index: record,
+                max: self.record_count.saturating_sub(1),
+            });
+        }
+        let begin = record
+            .checked_mul(self.record_size)
+            .and_then(|offset| variable.begin.checked_add(offset))
+            .ok_or(VtkError::MissingData(
+                "netCDF variable data beyond end of file",
+            ))?;
+        self.decode(variable, begin, self.element_count(variable, true)?)
+    }
+
+    /// Read a character variable as strings, one per row of its last dimension
+    pub fn read_strings(&self, name: &str) -> Result<Vec<String>, VtkError> {
+        let variable = self
+            .variable(name)
+            .ok_or(VtkError::MissingData("netCDF variable not found"))?;
+        if variable.nc_type != NcType::Char {
+            return Err(VtkError::DataTypeMismatch {
+                expected: "char",
+                found: "number",
+            });
+        }
+        let length = self.shape(variable).last().copied().unwrap_or(1).max(1);
+        let bytes: Vec<u8> = self.read(name)?.into_iter().map(|c| c as u8).collect();
+        Ok(bytes
+            .chunks(length)
+            .map(|row| {
+                String::from_utf8_lossy(row)
+                    .trim_end_matches('\0')
+                    .trim()
+                    .to_string()
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,204 @@
+//! Normals module
+//!
+//! Vertex normals with sharp-edge preservation:
+//! - Face normals are weighted by the corner angle, so the tessellation of a
+//!   face does not bias the result
+//! - Faces meeting at more than the crease angle are not averaged; their
+//!   vertices are duplicated so CAD edges stay sharp
+//!
+//! Duplicated vertices are appended after the vertices of the geometry, which
+//! keep their indices. The [`ATTRIBUTE_SOURCE_VERTEX`] mesh attribute stores the
+//! geometry vertex of every mesh vertex, so per-vertex data of the geometry
+//! (colors, positions) can be expanded to the mesh with [`expand_to_mesh`].
+use bevy::math::Vec3;
+use bevy::render::mesh::{Indices, Mesh, MeshVertexAttribute, VertexAttributeValues};
+use bevy::render::render_resource::VertexFormat;
+
+/// Default angle between faces above which an edge is kept sharp, in degrees
+pub const DEFAULT_CREASE_ANGLE: f32 = 30.0;
+
+/// Geometry vertex of every mesh vertex, present on meshes with split vertices
+pub const ATTRIBUTE_SOURCE_VERTEX: MeshVertexAttribute =
+    MeshVertexAttribute::new("Vertex_SourceVertex", 988_540_918, VertexFormat::Uint32);
+
+/// Normals of the two triangles of a corner must agree this well to share a
+/// mesh vertex
+const MERGE_TOLERANCE: f32 = 0.9999;
+
+/// Vertex normals that keep edges sharper than the crease angle
+#[derive(Clone, Debug, Default)]
+pub struct CreaseNormals {
+    /// Geometry vertex of every output vertex (identity for the first ones)
+    pub source_vertices: Vec<u32>,
+    /// Triangle indices into the output vertices
+    pub indices: Vec<u32>,
+    /// Normal of every output vertex
+    pub normals: Vec<[f32; 3]>,
+}
+
+/// Unit face normal and corner angles of a triangle
+fn face(vertices: &[[f32; 3]], corners: &[u32]) -> (Vec3, [f32; 3]) {
+    let [a, b, c] = [0, 1, 2].map(|k| Vec3::from(vertices[corners[k] as usize]));
+    let normal = (b - a).cross(c - a).normalize_or_zero();
+    let angle = |p: Vec3, q: Vec3, r: Vec3| (q - p).angle_between(r - p);
+    let angles = [angle(a, b, c), angle(b, c, a), angle(c, a, b)].map(|angle| {
+        if angle.is_finite() {
+            angle
+        } else {
+            0.0
+        }
+    });
+    (normal, angles)
+}
+
+/// Compute angle-weighted normals, splitting vertices along sharp edges
+///
+/// # Parameters
+/// - `vertices`: Vertex positions
+/// - `indices`: Triangle indices
+/// - `crease_angle`: Largest angle between faces that is smoothed, in degrees
+///
+/// # Returns
+/// - Output vertices (the input vertices followed by duplicates), triangle
+///   indices and normals
+pub fn crease_normals(vertices: &[[f32; 3]], indices: &[u32], crease_angle: f32) -> CreaseNormals {
+    let triangles = indices.len() / 3;
+    let faces: Vec<(Vec3, [f32; 3])> = indices
+        .chunks_exact(3)
+        .map(|corners| face(vertices, corners))
+        .collect();
+
+    // Triangles around every vertex, as (triangle, corner) pairs
+    let mut offsets = vec![0usize; vertices.len() + 1];
+    for &vertex in &indices[..triangles * 3] {
+        offsets[vertex as usize + 1] += 1;
+    }
+    for i in 0..vertices.len() {
+        offsets[i + 1] += offsets[i];
+    }
+    let mut fill = offsets.clone();
+    let mut incident = vec![(0usize, 0usize); triangles * 3];
+    for (corner, &vertex) in indices[..triangles * 3].iter().enumerate() {
+        incident[fill[vertex as usize]] = (corner / 3, corner % 3);
+        fill[vertex as usize] += 1;
+    }
+
+    let threshold = crease_angle.to_radians().cos();
+    let mut result = CreaseNormals {
+        source_vertices: (0..vertices.len() as u32).collect(),
+        indices: indices[..triangles * 3].to_vec(),
+        normals: vec![[0.0; 3]; vertices.len()],
+    };
+    // Normals already given to a vertex and its duplicates
+    let mut assigned: Vec<(u32, Vec3)> = Vec::new();
+    for vertex in 0..vertices.len() {
+        let around = &incident[offsets[vertex]..offsets[vertex + 1]];
+        assigned.clear();
+        for &(triangle, corner) in around {
+            let own = faces[triangle].0;
+            let mut sum = Vec3::ZERO;
+            for &(other, other_corner) in around {
+                let (normal, angles) = faces[other];
+                // Degenerate triangles take the smooth normal of the vertex
+                if own == Vec3::ZERO || own.dot(normal) >= threshold {
+                    sum += normal * angles[other_corner];
+                }
+            }
+            let normal = sum.normalize_or_zero();
+
+            let output = match assigned
+                .iter()
+                .find(|(_, existing)| existing.dot(normal) >= MERGE_TOLERANCE)
+            {
+                Some(&(output, _)) => output,
+                None if assigned.is_empty() => {
+                    result.normals[vertex] = normal.to_array();
+                    assigned.push((vertex as u32, normal));
+                    vertex as u32
+                }
+                None => {
+                    let output = result.source_vertices.len() as u32;
+                    result.source_vertices.push(vertex as u32);
+                    result.normals.push(normal.to_array());
+                    assigned.push((output, normal));
+                    output
+                }
+            };
+            result.indices[triangle * 3 + corner] = output;
+        }
+    }
+    result
+}
+
+/// Set positions, indices and crease normals of a mesh built from a geometry
+///
+/// # Parameters
+/// - `mesh`: Mesh to fill
+/// - `vertices`: Geometry vertex positions
+/// - `indices`: Geometry triangle indices
+/// - `crease_angle`: Largest angle between faces that is smoothed, in degrees
+pub fn apply_crease_normals(
+    mesh: &mut Mesh,
+    vertices: &[[f32; 3]],
+    indices: &[u32],
+    crease_angle: f32,
+) {
+    let normals = crease_normals(vertices, indices, crease_angle);
+    let split = normals.source_vertices.len() > vertices.len();
+    let positions: Vec<[f32; 3]> = normals
+        .source_vertices
+        .iter()
+        .map(|&source| vertices[source as usize])
+        .collect();
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_indices(Indices::U32(normals.indices));
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals.normals);
+    if split {
+        mesh.insert_attribute(ATTRIBUTE_SOURCE_VERTEX, normals.source_vertices);
+    } else {
+        mesh.remove_attribute(ATTRIBUTE_SOURCE_VERTEX);
+    }
+}
+
+/// Geometry vertex of every mesh vertex, `None` if no vertices were split
+pub fn source_vertices(mesh: &Mesh) -> Option<&[u32]> {
+    match mesh.attribute(ATTRIBUTE_SOURCE_VERTEX) {
+        Some(VertexAttributeValues::Uint32(sources)) => Some(sources),
+        _ => None,
+    }
+}
+
+/// Expand per-vertex values of the geometry to the vertices of a mesh
+///
+/// Values for the mesh vertices that are missing (duplicates created by
+/// [`apply_crease_normals`]) are copied from their geometry vertex; values
+/// beyond the geometry vertices are replaced the same way.
+///
+/// # Parameters
+/// - `mesh`: Mesh, possibly with split vertices
+/// - `values`: One value per geometry vertex, or per mesh vertex
+/// - `fill`: Value for vertices without a source value
+pub fn expand_to_mesh<T: Copy>(mesh: &Mesh, mut values: Vec<T>, fill: T) -> Vec<T> {
+    let Some(sources) = source_vertices(mesh) else {
+        return values;
+    };
+    values.resize(sources.len(), fill);
+    for (vertex, &source) in sources.iter().enumerate() {
+        if source as usize != vertex {
+            values[vertex] = values.get(source as usize).copied().unwrap_or(fill);
+        }
+    }
+    values
+}
+
+/// Number of mesh vertices that are geometry vertices (not duplicates)
+pub fn geometry_vertex_count(mesh: &Mesh) -> usize {
+    match source_vertices(mesh) {
+        Some(sources) => sources
+            .iter()
+            .enumerate()
+            .position(|(vertex, &source)| source as usize != vertex)
+            .unwrap_or(sources.len()),
+        None => mesh.count_vertices(),
+    }
+}
@@ -0,0 +1,220 @@
+//! OBJ import module
+//!
+//! Parses Wavefront OBJ files into `GeometryData` so OBJ models go through the
+//! same pipeline as VTK data (subdivision, LOD, color mapping, picking, export).
+//!
+//! Supported statements:
+//! - `v`, `vt`, `vn`: positions, texture coordinates and normals
+//! - `f`: polygon faces (`v`, `v/vt`, `v//vn`, `v/vt/vn`, negative indices);
+//!   polygons are fan-triangulated and each face becomes one cell
+//! - `o`, `g`: objects/groups, stored as the cell scalar `group` if there is more than one
+//!
+//! Materials (`mtllib`, `usemtl`) and other statements are ignored.
+use super::{AttributeLocation, AttributeType, GeometryData, VtkError};
+use bevy::utils::HashMap;
+use std::path::Path;
+
+/// Corner of an OBJ face: indices into the position, texture and normal lists
+type FaceCorner = (usize, Option<usize>, Option<usize>);
+
+/// Resolve a 1-based (or negative, relative) OBJ index
+fn resolve_index(token: &str, count: usize, line_number: usize) -> Result<usize, VtkError> {
+    let index: i64 = token.parse().map_err(|_| {
+        VtkError::LoadError(format!(
+            "OBJ line {}: invalid index '{}'",
+            line_number, token
+        ))
+    })?;
+
+    let resolved = if index > 0 {
+        index - 1
+    } else {
+        count as i64 + index
+    };
+
+    if index == 0 || resolved < 0 || resolved >= count as i64 {
+        return Err(VtkError::IndexOutOfBounds {
+            index: index.unsigned_abs() as usize,
+            max: count,
+        });
+    }
+    Ok(resolved as usize)
+}
+
+/// Parse `n` floats from the remaining tokens of a line
+fn parse_floats<'a>(
+    tokens: impl Iterator<Item = &'a str>,
+    n: usize,
+    line_number: usize,
+) -> Result<Vec<f32>, VtkError> {
+    let values: Vec<f32> = tokens
+        .take(n)
+        .map(|token| token.parse::<f32>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| VtkError::LoadError(format!("OBJ line {}: invalid number", line_number)))?;
+
+    if values.len() < n {
+        return Err(VtkError::LoadError(format!(
+            "OBJ line {}: expected {} values",
+            line_number, n
+        )));
+    }
+    Ok(values)
+}
+
+/// Parse OBJ text into geometry data
+///
+/// Vertices are shared between faces when they use the same position, texture
+/// coordinate and normal combination.
+///
+/// # Parameters
+/// - `source`: OBJ file contents
+///
+/// # Returns
+/// - `Ok(GeometryData)`: Triangulated geometry with optional normals, UVs and groups
+/// - `Err(VtkError)`: Malformed statement or index
+pub fn parse_obj(source: &str) -> Result<GeometryData, VtkError> {
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    let mut texcoords: Vec<[f32; 2]> = Vec::new();
+    let mut normals: Vec<[f32; 3]> = Vec::new();
+
+    let mut vertices: Vec<[f32; 3]> = Vec::new();
+    let mut vertex_uvs: Vec<Option<[f32; 2]>> = Vec::new();
+    let mut vertex_normals: Vec<Option<[f32; 3]>> = Vec::new();
+    let mut corner_map: HashMap<FaceCorner, u32> = HashMap::new();
+
+    let mut indices: Vec<u32> = Vec::new();
+    let mut triangle_to_cell: Vec<usize> = Vec::new();
+    let mut face_groups: Vec<usize> = Vec::new();
+    let mut group_names: Vec<String> = Vec::new();
+    let mut current_group = 0;
+
+    for (line_index, line) in source.lines().enumerate() {
+        let line_number = line_index + 1;
+        let line = line.split('#').next().unwrap_or("").trim();
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+
+        match keyword {
+            "v" => {
+                let v = parse_floats(tokens, 3, line_number)?;
+                positions.push([v[0], v[1], v[2]]);
+            }
+            "vt" => {
+                // The v coordinate is optional and defaults to 0
+                let uv = parse_floats(tokens.chain(["0"]), 2, line_number)?;
+                // OBJ has the texture origin at the bottom left
+                texcoords.push([uv[0], 1.0 - uv[1]]);
+            }
+            "vn" => {
+                let n = parse_floats(tokens, 3, line_number)?;
+                normals.push([n[0], n[1], n[2]]);
+            }
+            "o" | "g" => {
+                let name = tokens.collect::<Vec<_>>().join(" ");
+                current_group = match group_names.iter().position(|g| *g == name) {
+                    Some(index) => index,
+                    None => {
+                        group_names.push(name);
+                        group_names.len() - 1
+                    }
+                };
+            }
+            "f" => {
+                let mut corners = Vec::new();
+                for token in tokens {
+                    let mut parts = token.split('/');
+                    let position =
+                        resolve_index(parts.next().unwrap_or(""), positions.len(), line_number)?;
+                    let texcoord = match parts.next() {
+                        Some(part) if !part.is_empty() => {
+                            Some(resolve_index(part, texcoords.len(), line_number)?)
+                        }
+                        _ => None,
+                    };
+                    let normal = match parts.next() {
+                        Some(part) if !part.is_empty() => {
+                            Some(resolve_index(part, normals.len(), line_number)?)
+                        }
+                        _ => None,
+                    };
+
+                    let key = (position, texcoord, normal);
+                    let index = *corner_map.entry(key).or_insert_with(|| {
+                        vertices.push(positions[position]);
+                        vertex_uvs.push(texcoord.map(|t| texcoords[t]));
+                        vertex_normals.push(normal.map(|n| normals[n]));
+                        (vertices.len() - 1) as u32
+                    });
+                    corners.push(index);
+                }
+
+                if corners.len() < 3 {
+                    return Err(VtkError::LoadError(format!(
+                        "OBJ line {}: face with fewer than 3 vertices",
+                        line_number
+                    )));
+                }
+
+                // Faces before the first `o`/`g` statement belong to an unnamed group
+                if group_names.is_empty() {
+                    group_names.push(String::new());
+                }
+
+                // Fan triangulation of the polygon
+                let cell = face_groups.len();
+                for i in 1..corners.len() - 1 {
+                    indices.extend_from_slice(&[corners[0], corners[i], corners[i + 1]]);
+                    triangle_to_cell.push(cell);
+                }
+                face_groups.push(current_group);
+            }
+            _ => {}
+        }
+    }
+
+    if indices.is_empty() {
+        return Err(VtkError::MissingData("OBJ file contains no faces"));
+    }
+
+    println!(
+        "Parsed OBJ: {} vertices, {} faces, {} triangles",
+        vertices.len(),
+        face_groups.len(),
+        indices.len() / 3
+    );
+
+    let mut attributes = HashMap::new();
+    if group_names.len() > 1 {
+        attributes.insert(
+            ("group".to_string(), AttributeLocation::Cell),
+            AttributeType::Scalar {
+                num_comp: 1,
+                table_name: "default".to_string(),
+                data: face_groups.iter().map(|&g| g as f32).collect(),
+                lookup_table: None,
+            },
+        );
+    }
+
+    let mut geometry = GeometryData::new(vertices, indices, attributes)
+        .add_triangle_to_cell_mapping(triangle_to_cell);
+
+    // Only keep normals/UVs if every vertex has one
+    if let Some(normals) = vertex_normals.into_iter().collect::<Option<Vec<_>>>() {
+        geometry = geometry.add_normals(normals);
+    }
+    if let Some(uvs) = vertex_uvs.into_iter().collect::<Option<Vec<_>>>() {
+        geometry = geometry.add_uvs(uvs);
+    }
+
+    Ok(geometry)
+}
+
+/// Load an OBJ file into geometry data
+pub fn load_obj(path: &Path) -> Result<GeometryData, VtkError> {
+    let source = std::fs::read_to_string(path).map_err(VtkError::IoError)?;
+    parse_obj(&source)
+}
@@ -0,0 +1,297 @@
+//! Pathlines module
+//!
+//! Particle tracing through time-varying point vector fields:
+//! - Every time step holds vectors on the same points; between time steps the
+//!   vectors are interpolated linearly in time
+//! - Particles are integrated with fourth order Runge-Kutta in time, with a
+//!   fixed number of substeps between consecutive time steps
+//! - Pathlines follow one particle per seed from the first time step,
+//!   streaklines connect the particles released from a seed at every time step
+//!
+//! Particles stop when they leave the domain of the field.
+use super::streamlines::{Streamline, VectorField};
+use super::VtkError;
+use bevy::math::Vec3;
+
+/// Integration parameters
+#[derive(Clone, Copy, Debug)]
+pub struct ParticleSettings {
+    /// Integration steps between two time steps
+    pub substeps: usize,
+    /// Factor converting the step times into the time unit of the vectors,
+    /// e.g. the time between files of a series numbered by index
+    pub time_scale: f32,
+}
+
+/// Trajectory of one particle
+#[derive(Clone, Debug, Default)]
+pub struct Pathline {
+    pub points: Vec<Vec3>,
+    /// Time at every point, increasing
+    pub times: Vec<f32>,
+    /// Vector magnitude at every point
+    pub speeds: Vec<f32>,
+}
+
+impl Pathline {
+    fn push(&mut self, point: Vec3, time: f32, speed: f32) {
+        self.points.push(point);
+        self.times.push(time);
+        self.speeds.push(speed);
+    }
+
+    /// Position and speed of the particle at a time
+    ///
+    /// # Returns
+    /// - Position interpolated between integration points, `None` before the
+    ///   release or after the particle left the domain
+    pub fn position_at(&self, time: f32) -> Option<(Vec3, f32)> {
+        if time < *self.times.first()? || time > *self.times.last()? {
+            return None;
+        }
+        let next = self.times.partition_point(|&t| t < time);
+        if next == 0 {
+            return Some((self.points[0], self.speeds[0]));
+        }
+        let (t0, t1) = (self.times[next - 1], self.times[next]);
+        let fraction = if t1 > t0 {
+            (time - t0) / (t1 - t0)
+        } else {
+            1.0
+        };
+        Some((
+            self.points[next - 1].lerp(self.points[next], fraction),
+            self.speeds[next - 1] + (self.speeds[next] - self.speeds[next - 1]) * fraction,
+        ))
+    }
+
+    /// Trajectory up to a time, ending at the position at that time
+    pub fn until(&self, time: f32) -> Streamline {
+        let mut line = Streamline::default();
+        let end = self.times.partition_point(|&t| t < time);
+        line.points.extend_from_slice(&self.points[..end]);
+        line.speeds.extend_from_slice(&self.speeds[..end]);
+        if let Some((point, speed)) = self.position_at(time) {
+            line.points.push(point);
+            line.speeds.push(speed);
+        }
+        line
+    }
+}
+
+/// Point vector field changing over time
+pub struct TimeVaryingField {
+    /// Field locating points and interpolating in space
+    field: VectorField,
+    /// Time of every step, increasing
+    times: Vec<f32>,
+    /// Point vectors of every step
+    frames: Vec<Vec<Vec3>>,
+}
+
+impl TimeVaryingField {
+    /// Combine the time steps of a field
+    ///
+    /// # Parameters
+    /// - `field`: Field whose points carry the vectors of all time steps
+    /// - `steps`: Time and point vectors of every step, in any order
+    ///
+    /// # Returns
+    /// - `Ok(TimeVaryingField)`: Field over the time of the steps
+    /// - `Err(VtkError)`: There are no steps, two steps share a time or a step
+    ///   has another number of vectors than the field has points
+    pub fn new(field: VectorField, mut steps: Vec<(f32, Vec<Vec3>)>) -> Result<Self, VtkError> {
+        if steps.is_empty() {
+            return Err(VtkError::MissingData("time steps"));
+        }
+        let point_count = field.vectors().len();
+        if let Some((time, vectors)) = steps
+            .iter()
+            .find(|(_, vectors)| vectors.len() != point_count)
+        {
+            return Err(VtkError::GenericError(format!(
+                "The time step at {} has {} vectors, expected {}",
+                time,
+                vectors.len(),
+                point_count
+            )));
+        }
+        steps.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if steps.windows(2).any(|pair| pair[0].0 >= pair[1].0) {
+            return Err(VtkError::GenericError(
+                "The time steps need distinct times".to_string(),
+            ));
+        }
+        let (times, frames) = steps.into_iter().unzip();
+        Ok(Self {
+            field,
+            times,
+            frames,
+        })
+    }
+
+    /// Times of the steps, increasing
+    pub fn times(&self) -> &[f32] {
+        &self.times
+    }
+
+    /// Field vector at a point and time, constant before the first and after
+    /// the last time step
+    ///
+    /// # Parameters
+    /// - `hint`: Cell of the previous sample, updated to the cell of this one
+    ///
+    /// # Returns
+    /// - Interpolated vector, `None` outside the domain
+    pub fn sample(&self, point: Vec3, time: f32, hint: &mut Option<usize>) -> Option<Vec3> {
+        let next = self.times.partition_point(|&t| t <= time);
+        if next == 0 {
+            return self.field.sample_vectors(point, hint, &self.frames[0]);
+        }
+        if next == self.times.len() {
+            return self
+                .field
+                .sample_vectors(point, hint, &self.frames[next - 1]);
+        }
+        let (t0, t1) = (self.times[next - 1], self.times[next]);
+        let fraction = (time - t0) / (t1 - t0);
+        let before = self
+            .field
+            .sample_vectors(point, hint, &self.frames[next - 1])?;
+        let after = self.field.sample_vectors(point, hint, &self.frames[next])?;
+        Some(before.lerp(after, fraction))
+    }
+
+    /// Trace a particle released at a seed
+    ///
+    /// # Parameters
+    /// - `seed`: Release position
+    /// - `start`: Release time
+    /// - `end`: Time to trace to
+    ///
+    /// # Returns
+    /// - Trajectory, empty if the seed lies outside the domain
+    pub fn trace(&self, seed: Vec3, start: f32, end: f32, settings: &ParticleSettings) -> Pathline {
+        let mut line = Pathline::default();
+        let mut hint = None;
+        let Some(vector) = self.sample(seed, start, &mut hint) else {
+            return line;
+        };
+        line.push(seed, start, vector.length());
+
+        // Integrate between consecutive time steps, so steps land on them
+        let first = self.times.partition_point(|&t| t <= start);
+        let stops = self.times[first..]
+            .iter()
+            .copied()
+            .take_while(|&t| t < end)
+            .chain(std::iter::once(end));
+        let substeps = settings.substeps.max(1);
+        let mut point = seed;
+        let mut time = start;
+        for stop in stops {
+            if stop <= time {
+                continue;
+            }
+            let dt = (stop - time) / substeps as f32;
+            let h = dt * settings.time_scale;
+            for _ in 0..substeps {
+                let mut velocity = |at: Vec3, t: f32| self.sample(at, t, &mut hint);
+                let Some(k1) = velocity(point, time) else {
+                    return line;
+                };
+                let Some(k2) = velocity(point + k1 * h * 0.5, time + dt * 0.5) else {
+                    return line;
+                };
+                let Some(k3) = velocity(point + k2 * h * 0.5, time + dt * 0.5) else {
+                    return line;
+                };
+                let Some(k4) = velocity(point + k3 * h, time + dt) else {
+                    return line;
+                };
+                let next = point + (k1 + k2 * 2.0 + k3 * 2.0 + k4) * (h / 6.0);
+                let Some(vector) = velocity(next, time + dt) else {
+                    return line;
+                };
+                point = next;
+                time += dt;
+                line.push(point, time, vector.length());
+            }
+            time = stop;
+        }
+        line
+    }
+
+    /// Trace one particle per seed from the first to the last time step
+    ///
+    /// # Returns
+    /// - Pathlines of the seeds inside the domain
+    pub fn pathlines(&self, seeds: &[Vec3], settings: &ParticleSettings) -> Vec<Pathline> {
+        let (start, end) = (self.times[0], self.times[self.times.len() - 1]);
+        seeds
+            .iter()
+            .map(|&seed| self.trace(seed, start, end, settings))
+            .filter(|line| !line.points.is_empty())
+            .collect()
+    }
+
+    /// Trace the particles released from every seed at every time step
+    ///
+    /// # Returns
+    /// - Particles of every seed inside the domain, ordered by release time
+    pub fn streak_particles(
+        &self,
+        seeds: &[Vec3],
+        settings: &ParticleSettings,
+    ) -> Vec<Vec<Pathline>> {
+        let end = self.times[self.times.len() - 1];
+        seeds
+            .iter()
+            .map(|&seed| {
+                self.times
+                    .iter()
+                    .map(|&release| self.trace(seed, release, end, settings))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|particles| particles.iter().any(|line| !line.points.is_empty()))
+            .collect()
+    }
+}
+
+/// Streaklines of the particles released from one seed at a time
+///
+/// The particles released up to the time are connected from the newest, at the
+/// seed, to the oldest; the line is split where particles left the domain.
+///
+/// # Parameters
+/// - `particles`: Particles of one seed, ordered by release time
+/// - `time`: Time of the streakline
+///
+/// # Returns
+/// - Lines with at least two points
+pub fn streaklines_at(particles: &[Pathline], time: f32) -> Vec<Streamline> {
+    let mut lines = Vec::new();
+    let mut line = Streamline::default();
+    let released = particles
+        .iter()
+        .rev()
+        .filter(|particle| particle.times.first().is_some_and(|&t| t <= time));
+    for particle in released {
+        match particle.position_at(time) {
+            Some((point, speed)) => {
+                line.points.push(point);
+                line.speeds.push(speed);
+            }
+            None => {
+                let part = std::mem::take(&mut line);
+                if part.points.len() >= 2 {
+                    lines.push(part);
+                }
+            }
+        }
+    }
+    if line.points.len() >= 2 {
+        lines.push(line);
+    }
+    lines
+}
@@ -0,0 +1,479 @@
+//! PLY import module
+//!
+//! Parses Stanford PLY files (ascii, binary little and big endian) into
+//! `GeometryData`, so scans and point clouds go through the same pipeline as
+//! VTK data.
+//!
+//! Supported vertex properties:
+//! - `x`, `y`, `z`: positions
+//! - `nx`, `ny`, `nz`: normals
+//! - `s`/`t`, `u`/`v`, `texture_u`/`texture_v`: texture coordinates
+//! - `red`, `green`, `blue`, `alpha` (or `diffuse_*`): vertex colors, stored as
+//!   color scalars and shown as vertex colors
+//! - any other scalar property (e.g. `confidence`, `intensity`): point scalar
+//!
+//! Faces (`vertex_indices` / `vertex_index` lists) are fan-triangulated, each face
+//! becoming one cell; their colors and scalar properties become cell data.
+//! Files without faces are loaded as point sets. Other elements are skipped.
+use super::{AttributeLocation, AttributeType, GeometryData, VtkError};
+use bevy::utils::HashMap;
+use std::path::Path;
+
+/// Storage format of the PLY body
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+/// Scalar type of a PLY property
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ScalarKind {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl ScalarKind {
+    /// Map a PLY type name (both the old and the sized spelling)
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "char" | "int8" => Some(Self::I8),
+            "uchar" | "uint8" => Some(Self::U8),
+            "short" | "int16" => Some(Self::I16),
+            "ushort" | "uint16" => Some(Self::U16),
+            "int" | "int32" => Some(Self::I32),
+            "uint" | "uint32" => Some(Self::U32),
+            "float" | "float32" => Some(Self::F32),
+            "double" | "float64" => Some(Self::F64),
+            _ => None,
+        }
+    }
+
+    /// Size in bytes in binary files
+    fn size(self) -> usize {
+        match self {
+            Self::I8 | Self::U8 => 1,
+            Self::I16 | Self::U16 => 2,
+            Self::I32 | Self::U32 | Self::F32 => 4,
+            Self::F64 => 8,
+        }
+    }
+
+    /// Factor mapping color values of this type to 0..1
+    fn color_scale(self) -> f32 {
+        match self {
+            Self::U8 | Self::I8 => 1.0 / 255.0,
+            Self::U16 | Self::I16 => 1.0 / 65535.0,
+            _ => 1.0,
+        }
+    }
+}
+
+/// Property of a PLY element
+#[derive(Clone, Debug)]
+enum Property {
+    Scalar {
+        name: String,
+        kind: ScalarKind,
+    },
+    List {
+        name: String,
+        count: ScalarKind,
+        item: ScalarKind,
+    },
+}
+
+/// Element declared in the PLY header
+#[derive(Clone, Debug)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+/// Values of one scalar property of all element instances
+struct Column {
+    kind: ScalarKind,
+    values: Vec<f32>,
+}
+
+/// Scalar property columns of an element, by property name
+type Columns = Vec<(String, Column)>;
+
+/// Sequential reader of body values
+enum BodyReader<'a> {
+    Ascii(std::str::SplitAsciiWhitespace<'a>),
+    Binary {
+        data: &'a [u8],
+        position: usize,
+        big_endian: bool,
+    },
+}
+
+impl BodyReader<'_> {
+    /// Read the next value of the given type
+    fn read(&mut self, kind: ScalarKind) -> Result<f64, VtkError> {
+        match self {
+            BodyReader::Ascii(tokens) => tokens
+                .next()
+                .ok_or(VtkError::MissingData("PLY body ends early"))?
+                .parse::<f64>()
+                .map_err(|_| VtkError::InvalidFormat("Invalid number in PLY body")),
+            BodyReader::Binary {
+                data,
+                position,
+                big_endian,
+            } => {
+                let size = kind.size();
+                let bytes = data
+                    .get(*position..*position + size)
+                    .ok_or(VtkError::MissingData("PLY body ends early"))?;
+                *position += size;
+
+                let mut buffer = [0u8; 8];
+                buffer[..size].copy_from_slice(bytes);
+                if *big_endian {
+                    buffer[..size].reverse();
+                }
+                let value = match kind {
+                    ScalarKind::I8 => buffer[0] as i8 as f64,
+                    ScalarKind::U8 => buffer[0] as f64,
+                    ScalarKind::I16 => i16::from_le_bytes([buffer[0], buffer[1]]) as f64,
+                    ScalarKind::U16 => u16::from_le_bytes([buffer[0], buffer[1]]) as f64,
+                    ScalarKind::I32 => i32::from_le_bytes(buffer[..4].try_into().unwrap()) as f64,
+                    ScalarKind::U32 => u32::from_le_bytes(buffer[..4].try_into().unwrap()) as f64,
+                    ScalarKind::F32 => f32::from_le_bytes(buffer[..4].try_into().unwrap()) as f64,
+                    ScalarKind::F64 => f64::from_le_bytes(buffer),
+                };
+                Ok(value)
+            }
+        }
+    }
+}
+
+/// Parse the PLY header
+///
+/// # Returns
+/// - Body format, declared elements and the byte offset of the body
+fn parse_header(bytes: &[u8]) -> Result<(Format, Vec<Element>, usize), VtkError> {
+    const END: &[u8] = b"end_header";
+    let end = bytes
+        .windows(END.len())
+        .position(|window| window == END)
+        .ok_or(VtkError::InvalidFormat("PLY header has no end_header"))?;
+    let body_start = bytes[end..]
+        .iter()
+        .position(|&byte| byte == b'\n')
+        .map_or(bytes.len(), |newline| end + newline + 1);
+    let header = std::str::from_utf8(&bytes[..end])
+        .map_err(|_| VtkError::InvalidFormat("PLY header is not text"))?;
+
+    let mut lines = header.lines();
+    if lines.next().map(str::trim) != Some("ply") {
+        return Err(VtkError::InvalidFormat("Missing PLY magic number"));
+    }
+
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    for line in lines {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match tokens.as_slice() {
+            ["format", name, ..] => {
+                format = Some(match *name {
+                    "ascii" => Format::Ascii,
+                    "binary_little_endian" => Format::BinaryLittleEndian,
+                    "binary_big_endian" => Format::BinaryBigEndian,
+                    _ => return Err(VtkError::InvalidFormat("Unknown PLY format")),
+                });
+            }
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count
+                    .parse()
+                    .map_err(|_| VtkError::InvalidFormat("Invalid PLY element count"))?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", count, item, name] => {
+                let element = elements
+                    .last_mut()
+                    .ok_or(VtkError::InvalidFormat("PLY property before any element"))?;
+                element.properties.push(Property::List {
+                    name: name.to_string(),
+                    count: ScalarKind::from_name(count)
+                        .ok_or(VtkError::InvalidFormat("Unknown PLY property type"))?,
+                    item: ScalarKind::from_name(item)
+                        .ok_or(VtkError::InvalidFormat("Unknown PLY property type"))?,
+                });
+            }
+            ["property", kind, name] => {
+                let element = elements
+                    .last_mut()
+                    .ok_or(VtkError::InvalidFormat("PLY property before any element"))?;
+                element.properties.push(Property::Scalar {
+                    name: name.to_string(),
+                    kind: ScalarKind::from_name(kind)
+                        .ok_or(VtkError::InvalidFormat("Unknown PLY property type"))?,
+                });
+            }
+            _ => {} // comment, obj_info
+        }
+    }
+
+    let format = format.ok_or(VtkError::InvalidFormat("PLY header has no format"))?;
+    Ok((format, elements, body_start))
+}
+
+/// Read all instances of an element
+///
+/// # Returns
+/// - Scalar property columns by name, and the lists of the `vertex_indices`
+///   (or `vertex_index`) property
+fn read_element(
+    reader: &mut BodyReader,
+    element: &Element,
+) -> Result<(Columns, Vec<Vec<u32>>), VtkError> {
+    let mut columns: Columns = element
+        .properties
+        .iter()
+        .filter_map(|property| match property {
+            Property::Scalar { name, kind } => Some((
+                name.clone(),
+                Column {
+                    kind: *kind,
+                    values: Vec::with_capacity(element.count),
+                },
+            )),
+            Property::List { .. } => None,
+        })
+        .collect();
+    let mut lists = Vec::new();
+
+    for _ in 0..element.count {
+        let mut column = 0;
+        for property in &element.properties {
+            match property {
+                Property::Scalar { kind, .. } => {
+                    let value = reader.read(*kind)?;
+                    columns[column].1.values.push(value as f32);
+                    column += 1;
+                }
+                Property::List { name, count, item } => {
+                    let count = reader.read(*count)? as usize;
+                    let mut values = Vec::with_capacity(count);
+                    for _ in 0..count {
+                        values.push(reader.read(*item)? as u32);
+                    }
+                    if name == "vertex_indices" || name == "vertex_index" {
+                        lists.push(values);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok((columns, lists))
+}
+
+/// Take the named column out of the list
+fn take_column(columns: &mut Columns, names: &[&str]) -> Option<Column> {
+    let index = columns
+        .iter()
+        .position(|(name, _)| names.contains(&name.as_str()))?;
+    Some(columns.remove(index).1)
+}
+
+/// Take the color columns (red, green, blue and optional alpha) out of the list
+///
+/// # Returns
+/// - Colors in 0..1 with 3 or 4 components, if the element has colors
+fn take_colors(columns: &mut Columns) -> Option<AttributeType> {
+    let names = [
+        ["red", "diffuse_red"],
+        ["green", "diffuse_green"],
+        ["blue", "diffuse_blue"],
+        ["alpha", "diffuse_alpha"],
+    ];
+    if !names[..3].iter().all(|names| {
+        columns
+            .iter()
+            .any(|(name, _)| names.contains(&name.as_str()))
+    }) {
+        return None;
+    }
+
+    let channels: Vec<Column> = names
+        .iter()
+        .filter_map(|names| take_column(columns, names))
+        .collect();
+    let count = channels[0].values.len();
+    let data = (0..count)
+        .map(|i| {
+            channels
+                .iter()
+                .map(|channel| channel.values[i] * channel.kind.color_scale())
+                .collect()
+        })
+        .collect();
+    Some(AttributeType::ColorScalar {
+        nvalues: channels.len() as u32,
+        data,
+    })
+}
+
+/// Store the remaining scalar columns as attributes
+fn insert_scalars(
+    attributes: &mut HashMap<(String, AttributeLocation), AttributeType>,
+    columns: Columns,
+    location: AttributeLocation,
+) {
+    for (name, column) in columns {
+        attributes.insert(
+            (name, location.clone()),
+            AttributeType::Scalar {
+                num_comp: 1,
+                table_name: "default".to_string(),
+                data: column.values,
+                lookup_table: None,
+            },
+        );
+    }
+}
+
+/// Parse PLY data into geometry data
+///
+/// # Parameters
+/// - `bytes`: PLY file contents
+///
+/// # Returns
+/// - `Ok(GeometryData)`: Triangulated geometry (or point set) with its properties
+/// - `Err(VtkError)`: Malformed header or body
+pub fn parse_ply(bytes: &[u8]) -> Result<GeometryData, VtkError> {
+    let (format, elements, body_start) = parse_header(bytes)?;
+    let body = &bytes[body_start.min(bytes.len())..];
+    let mut reader = match format {
+        Format::Ascii => BodyReader::Ascii(
+            std::str::from_utf8(body)
+                .map_err(|_| VtkError::InvalidFormat("PLY ascii body is not text"))?
+                .split_ascii_whitespace(),
+        ),
+        Format::BinaryLittleEndian | Format::BinaryBigEndian => BodyReader::Binary {
+            data: body,
+            position: 0,
+            big_endian: format == Format::BinaryBigEndian,
+        },
+    };
+
+    let mut vertex_columns = None;
+    let mut face_columns = Vec::new();
+    let mut faces = Vec::new();
+    for element in &elements {
+        let (columns, lists) = read_element(&mut reader, element)?;
+        match element.name.as_str() {
+            "vertex" => vertex_columns = Some(columns),
+            "face" => {
+                face_columns = columns;
+                faces = lists;
+            }
+            _ => println!("Skipping PLY element '{}'", element.name),
+        }
+    }
+
+    let mut vertex_columns =
+        vertex_columns.ok_or(VtkError::MissingData("PLY file has no vertex element"))?;
+    let (Some(x), Some(y), Some(z)) = (
+        take_column(&mut vertex_columns, &["x"]),
+        take_column(&mut vertex_columns, &["y"]),
+        take_column(&mut vertex_columns, &["z"]),
+    ) else {
+        return Err(VtkError::MissingData("PLY vertices have no x, y, z"));
+    };
+    let vertex_count = x.values.len();
+    let vertices: Vec<[f32; 3]> = (0..vertex_count)
+        .map(|i| [x.values[i], y.values[i], z.values[i]])
+        .collect();
+
+    let normals = match (
+        take_column(&mut vertex_columns, &["nx"]),
+        take_column(&mut vertex_columns, &["ny"]),
+        take_column(&mut vertex_columns, &["nz"]),
+    ) {
+        (Some(nx), Some(ny), Some(nz)) => Some(
+            (0..vertex_count)
+                .map(|i| [nx.values[i], ny.values[i], nz.values[i]])
+                .collect::<Vec<_>>(),
+        ),
+        _ => None,
+    };
+    let uvs = match (
+        take_column(&mut vertex_columns, &["s", "u", "texture_u"]),
+        take_column(&mut vertex_columns, &["t", "v", "texture_v"]),
+    ) {
+        (Some(u), Some(v)) => Some(
+            (0..vertex_count)
+                .map(|i| [u.values[i], v.values[i]])
+                .collect::<Vec<_>>(),
+        ),
+        _ => None,
+    };
+
+    // Fan triangulation, one cell per face
+    let mut indices = Vec::new();
+    let mut triangle_to_cell = Vec::new();
+    for (cell, face) in faces.iter().enumerate() {
+        if let Some(&corner) = face.iter().find(|&&corner| corner as usize >= vertex_count) {
+            return Err(VtkError::IndexOutOfBounds {
+                index: corner as usize,
+                max: vertex_count.saturating_sub(1),
+            });
+        }
+        for i in 1..face.len().saturating_sub(1) {
+            indices.extend_from_slice(&[face[0], face[i], face[i + 1]]);
+            triangle_to_cell.push(cell);
+        }
+    }
+
+    let mut attributes = HashMap::new();
+    if let Some(colors) = take_colors(&mut vertex_columns) {
+        attributes.insert(("colors".to_string(), AttributeLocation::Point), colors);
+    }
+    insert_scalars(&mut attributes, vertex_columns, AttributeLocation::Point);
+    if !faces.is_empty() {
+        if let Some(colors) = take_colors(&mut face_columns) {
+            attributes.insert(("colors".to_string(), AttributeLocation::Cell), colors);
+        }
+        insert_scalars(&mut attributes, face_columns, AttributeLocation::Cell);
+    }
+
+    println!(
+        "Parsed PLY ({:?}): {} vertices, {} faces, {} triangles, attributes: {:?}",
+        format,
+        vertices.len(),
+        faces.len(),
+        indices.len() / 3,
+        attributes.keys().collect::<Vec<_>>()
+    );
+
+    let mut geometry = GeometryData::new(vertices, indices, attributes)
+        .add_triangle_to_cell_mapping(triangle_to_cell);
+    if let Some(normals) = normals {
+        geometry = geometry.add_normals(normals);
+    }
+    if let Some(uvs) = uvs {
+        geometry = geometry.add_uvs(uvs);
+    }
+
+    Ok(geometry)
+}
+
+/// Load a PLY file into geometry data
+pub fn load_ply(path: &Path) -> Result<GeometryData, VtkError> {
+    let bytes = std::fs::read(path).map_err(VtkError::IoError)?;
+    parse_ply(&bytes)
+}
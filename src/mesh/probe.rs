@@ -0,0 +1,173 @@
+//! Probe module
+//!
+//! Attribute values at a location on the surface:
+//! - Point attributes are interpolated with the barycentric coordinates of the
+//!   location inside its triangle
+//! - Cell attributes take the value of the cell owning the triangle
+//! - The picked cell is described by its vertices and outline edges, taken
+//!   from the surface triangles of the cell
+use super::topology::{attribute_components, attribute_value, triangle_cell};
+use super::{AttributeLocation, AttributeType, GeometryData};
+use std::collections::HashMap;
+
+/// Value of one attribute at a probed location
+#[derive(Clone, Debug)]
+pub struct ProbedValue {
+    /// Attribute name
+    pub name: String,
+    /// Where the attribute is stored
+    pub location: AttributeLocation,
+    /// Interpolated (point) or cell value, one entry per component
+    pub components: Vec<f32>,
+}
+
+impl ProbedValue {
+    /// Magnitude of multi-component values
+    pub fn magnitude(&self) -> Option<f32> {
+        (self.components.len() > 1)
+            .then(|| self.components.iter().map(|c| c * c).sum::<f32>().sqrt())
+    }
+}
+
+/// Components of an attribute at a point inside a triangle
+fn components_at(
+    attribute: &AttributeType,
+    location: &AttributeLocation,
+    corners: &[u32],
+    cell: usize,
+    barycentric: [f32; 3],
+) -> Vec<f32> {
+    (0..attribute_components(attribute))
+        .map(|component| match location {
+            AttributeLocation::Point => corners
+                .iter()
+                .zip(barycentric)
+                .map(|(&corner, weight)| {
+                    weight * attribute_value(attribute, corner as usize, Some(component))
+                })
+                .sum(),
+            AttributeLocation::Cell => attribute_value(attribute, cell, Some(component)),
+        })
+        .collect()
+}
+
+/// Evaluate every attribute of a geometry at a point inside a triangle
+///
+/// # Parameters
+/// - `geometry`: Geometry data
+/// - `triangle`: Triangle index (into `indices` / 3)
+/// - `barycentric`: Weights of the three triangle corners
+///
+/// # Returns
+/// - Values of all attributes, point attributes first, sorted by name
+pub fn probe_attributes(
+    geometry: &GeometryData,
+    triangle: usize,
+    barycentric: [f32; 3],
+) -> Vec<ProbedValue> {
+    let Some(corners) = geometry.indices.get(triangle * 3..triangle * 3 + 3) else {
+        return Vec::new();
+    };
+    let cell = triangle_cell(geometry, triangle);
+
+    let mut values: Vec<ProbedValue> = geometry
+        .attributes
+        .iter()
+        .flatten()
+        .filter(|((name, _), _)| !name.starts_with("__lut_"))
+        .map(|((name, location), attribute)| ProbedValue {
+            name: name.clone(),
+            location: location.clone(),
+            components: components_at(attribute, location, corners, cell, barycentric),
+        })
+        .collect();
+
+    values.sort_by(|a, b| {
+        let rank = |value: &ProbedValue| value.location == AttributeLocation::Cell;
+        rank(a).cmp(&rank(b)).then_with(|| a.name.cmp(&b.name))
+    });
+    values
+}
+
+/// Evaluate one attribute at a point inside a triangle, like `probe_attributes`
+///
+/// # Returns
+/// - Value of the attribute, `None` if the geometry does not have it
+pub fn probe_attribute(
+    geometry: &GeometryData,
+    triangle: usize,
+    barycentric: [f32; 3],
+    name: &str,
+    location: AttributeLocation,
+) -> Option<ProbedValue> {
+    let corners = geometry.indices.get(triangle * 3..triangle * 3 + 3)?;
+    let attribute = geometry.get_attributes(name, location.clone())?;
+    let cell = triangle_cell(geometry, triangle);
+    Some(ProbedValue {
+        name: name.to_string(),
+        components: components_at(attribute, &location, corners, cell, barycentric),
+        location,
+    })
+}
+
+/// Surface of one cell
+#[derive(Clone, Debug, Default)]
+pub struct CellSurface {
+    /// Vertex indices of the cell, sorted
+    pub vertices: Vec<u32>,
+    /// Outline edges, the triangle edges not shared by two triangles of the cell
+    pub edges: Vec<[u32; 2]>,
+}
+
+/// Vertices and outline of a cell, from the triangles owned by the cell
+///
+/// Quads and polygons are outlined without their triangulation diagonals;
+/// volume cells list the vertices of their visible faces.
+pub fn cell_surface(geometry: &GeometryData, cell: usize) -> CellSurface {
+    let mut vertices = Vec::new();
+    let mut edge_counts: HashMap<[u32; 2], usize> = HashMap::new();
+    let mut edges = Vec::new();
+    for (triangle, corners) in geometry.indices.chunks_exact(3).enumerate() {
+        if triangle_cell(geometry, triangle) != cell {
+            continue;
+        }
+        vertices.extend_from_slice(corners);
+        for k in 0..3 {
+            let (a, b) = (corners[k], corners[(k + 1) % 3]);
+            let edge = [a.min(b), a.max(b)];
+            let count = edge_counts.entry(edge).or_insert(0);
+            if *count == 0 {
+                edges.push(edge);
+            }
+            *count += 1;
+        }
+    }
+    vertices.sort_unstable();
+    vertices.dedup();
+    edges.retain(|edge| edge_counts[edge] == 1);
+    CellSurface { vertices, edges }
+}
+
+/// Values of the point attributes at one vertex
+///
+/// # Returns
+/// - Values of all point attributes, sorted by name
+pub fn vertex_attributes(geometry: &GeometryData, vertex: u32) -> Vec<ProbedValue> {
+    let mut values: Vec<ProbedValue> = geometry
+        .attributes
+        .iter()
+        .flatten()
+        .filter(|((name, location), _)| {
+            *location == AttributeLocation::Point && !name.starts_with("__lut_")
+        })
+        .map(|((name, location), attribute)| ProbedValue {
+            name: name.clone(),
+            location: location.clone(),
+            components: (0..attribute_components(attribute))
+                .map(|component| attribute_value(attribute, vertex as usize, Some(component)))
+                .collect(),
+        })
+        .collect();
+    values.sort_by(|a, b| a.name.cmp(&b.name));
+    values
+}
@@ -0,0 +1,113 @@
+//! Load progress module
+//!
+//! Loaders report the stage they are in while a file is imported in the background:
+//! - Stages are named ("Parsing file", "Triangulating cells", ...) and carry the
+//!   fraction of the load done when they start
+//! - Reports go to the progress installed on the current thread with
+//!   `with_progress`; loaders called without one (time series, sessions) report nothing
+//! - A cancelled load stops at the next check between pieces
+use super::VtkError;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Fraction of a load spent parsing the file, before its pieces are extracted
+const PARSED_FRACTION: f32 = 0.5;
+
+/// Stage and cancel flag of a load, shared between the loader and the UI
+pub struct LoadProgress {
+    /// Fraction done and name of the current stage
+    stage: Mutex<(f32, &'static str)>,
+    cancelled: AtomicBool,
+}
+
+impl LoadProgress {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            stage: Mutex::new((0.0, "Starting")),
+            cancelled: AtomicBool::new(false),
+        })
+    }
+
+    /// Fraction done and name of the current stage
+    pub fn stage(&self) -> (f32, &'static str) {
+        self.stage.lock().map_or((0.0, "Starting"), |stage| *stage)
+    }
+
+    /// Stop the load at the next check
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<LoadProgress>>> = const { RefCell::new(None) };
+}
+
+/// Run a load reporting its stages to `progress`
+///
+/// # Parameters
+/// - `progress`: Progress updated by the loaders called from `load`
+/// - `load`: The load, run on the current thread
+///
+/// # Returns
+/// The result of `load`
+pub fn with_progress<T>(progress: Arc<LoadProgress>, load: impl FnOnce() -> T) -> T {
+    CURRENT.with(|current| *current.borrow_mut() = Some(progress));
+    let result = load();
+    CURRENT.with(|current| *current.borrow_mut() = None);
+    result
+}
+
+/// Report the start of a stage
+///
+/// # Parameters
+/// - `fraction`: Fraction of the load done, between 0 and 1
+/// - `stage`: Name of the stage
+pub fn report_stage(fraction: f32, stage: &'static str) {
+    CURRENT.with(|current| {
+        if let Some(ref progress) = *current.borrow() {
+            if let Ok(mut current) = progress.stage.lock() {
+                *current = (fraction.clamp(0.0, 1.0), stage);
+            }
+        }
+    });
+}
+
+/// Report the start of the file parsing stage
+pub fn report_parsing() {
+    report_stage(0.0, "Parsing file");
+}
+
+/// Report a stage of extracting a piece of a parsed file
+///
+/// # Parameters
+/// - `index`, `count`: Piece being extracted and number of pieces
+/// - `part`: Fraction of the piece extracted when the stage starts
+/// - `stage`: Name of the stage
+pub fn report_piece_stage(index: usize, count: usize, part: f32, stage: &'static str) {
+    let piece = (index as f32 + part) / count.max(1) as f32;
+    report_stage(PARSED_FRACTION + (1.0 - PARSED_FRACTION) * piece, stage);
+}
+
+/// Fail if the load was cancelled
+///
+/// # Returns
+/// - `Ok(())`: The load continues
+/// - `Err(VtkError)`: The load was cancelled
+pub fn check_cancelled() -> Result<(), VtkError> {
+    let cancelled = CURRENT.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .is_some_and(|progress| progress.is_cancelled())
+    });
+    if cancelled {
+        return Err(VtkError::LoadError("Import cancelled".to_string()));
+    }
+    Ok(())
+}
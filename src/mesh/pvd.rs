@@ -0,0 +1,88 @@
+//! ParaView collection module
+//!
+//! Reads ParaView data collection (`.pvd`) files, which list the files of a
+//! time series with their physical time:
+//!
+//! ```xml
+//! <VTKFile type="Collection">
+//!   <Collection>
+//!     <DataSet timestep="0.5" part="0" file="run/out_1.vtu"/>
+//!   </Collection>
+//! </VTKFile>
+//! ```
+//!
+//! Only the first part of every time step is used; file paths are resolved
+//! against the directory of the collection file.
+use super::compression::attribute;
+use super::VtkError;
+use std::path::{Path, PathBuf};
+
+/// File of a collection at one time step
+#[derive(Clone, Debug)]
+pub struct CollectionEntry {
+    /// Physical time of the time step
+    pub time: f32,
+    /// Data file of the time step
+    pub file: PathBuf,
+}
+
+/// Read the entries of a ParaView collection file
+///
+/// # Parameters
+/// - `path`: `.pvd` file path
+///
+/// # Returns
+/// - `Ok(Vec<CollectionEntry>)`: Entries sorted by time, one per time step
+/// - `Err(VtkError)`: The file cannot be read or lists no data set
+pub fn read_pvd(path: &Path) -> Result<Vec<CollectionEntry>, VtkError> {
+    let text = std::fs::read_to_string(path).map_err(VtkError::IoError)?;
+    let directory = path.parent().unwrap_or(Path::new("."));
+
+    let mut entries: Vec<(CollectionEntry, u32)> = Vec::new();
+    let mut rest = text.as_str();
+    while let Some(start) = rest.find("<DataSet") {
+        let after = &rest[start..];
+        let end = after
+            .find('>')
+            .ok_or(VtkError::InvalidFormat("Unterminated DataSet tag"))?;
+        // Attributes may be spread over several lines
+        let tag = after[..end].replace(['\n', '\r', '\t'], " ");
+        rest = &after[end..];
+
+        let Some(file) = attribute(&tag, "file") else {
+            println!("Skipping collection entry without file: {}", tag);
+            continue;
+        };
+        let time = attribute(&tag, "timestep")
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(entries.len() as f32);
+        let part = attribute(&tag, "part")
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(0);
+
+        entries.push((
+            CollectionEntry {
+                time,
+                file: directory.join(file),
+            },
+            part,
+        ));
+    }
+
+    // One file per time step: the lowest part
+    entries.sort_by(|(a, a_part), (b, b_part)| a.time.total_cmp(&b.time).then(a_part.cmp(b_part)));
+    entries.dedup_by(|(later, _), (earlier, _)| later.time == earlier.time);
+    let entries: Vec<CollectionEntry> = entries.into_iter().map(|(entry, _)| entry).collect();
+
+    if entries.is_empty() {
+        return Err(VtkError::MissingData("No data sets in the collection"));
+    }
+    println!(
+        "Read {} time steps from {} (t = {} .. {})",
+        entries.len(),
+        path.display(),
+        entries[0].time,
+        entries[entries.len() - 1].time
+    );
+    Ok(entries)
+}
@@ -0,0 +1,147 @@
+//! Quality module
+//!
+//! Triangle quality metrics stored as cell scalars:
+//! - `AspectRatio`: Longest edge times perimeter over 4√3 times the area
+//!   (1 for an equilateral triangle, growing for slivers)
+//! - `MinAngle`: Smallest corner angle in degrees (60 for an equilateral triangle)
+//! - `Skewness`: Equiangular skewness, 0 for an equilateral triangle and 1 for a
+//!   degenerate one
+//!
+//! Cells made of several triangles take the worst value of their triangles.
+use super::conversion::location_size;
+use super::topology::triangle_cell;
+use super::{AttributeLocation, AttributeType, GeometryData};
+use bevy::math::Vec3;
+
+/// Aspect ratio given to triangles without area
+pub const DEGENERATE_ASPECT_RATIO: f32 = 1.0e6;
+
+/// Attribute names of the metrics, in the order of [`TriangleQuality::values`]
+pub const QUALITY_ATTRIBUTES: [&str; 3] = ["AspectRatio", "MinAngle", "Skewness"];
+
+/// Quality metrics of one triangle
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TriangleQuality {
+    pub aspect_ratio: f32,
+    /// Smallest corner angle in degrees
+    pub min_angle: f32,
+    pub skewness: f32,
+}
+
+impl TriangleQuality {
+    /// Metrics in the order of [`QUALITY_ATTRIBUTES`]
+    pub fn values(&self) -> [f32; 3] {
+        [self.aspect_ratio, self.min_angle, self.skewness]
+    }
+
+    /// Combine two metrics, keeping the worse value of each
+    fn worst(self, other: Self) -> Self {
+        Self {
+            aspect_ratio: self.aspect_ratio.max(other.aspect_ratio),
+            min_angle: self.min_angle.min(other.min_angle),
+            skewness: self.skewness.max(other.skewness),
+        }
+    }
+}
+
+/// Summary of one metric over all cells
+#[derive(Clone, Debug)]
+pub struct QualitySummary {
+    pub name: &'static str,
+    pub min: f32,
+    pub mean: f32,
+    pub max: f32,
+}
+
+/// Compute the quality metrics of a triangle
+pub fn triangle_quality(a: Vec3, b: Vec3, c: Vec3) -> TriangleQuality {
+    let lengths = [b.distance(c), c.distance(a), a.distance(b)];
+    let area = (b - a).cross(c - a).length() * 0.5;
+    let longest = lengths.iter().copied().fold(0.0, f32::max);
+    let perimeter: f32 = lengths.iter().sum();
+    if area <= f32::EPSILON * longest * longest || longest == 0.0 {
+        return TriangleQuality {
+            aspect_ratio: DEGENERATE_ASPECT_RATIO,
+            min_angle: 0.0,
+            skewness: 1.0,
+        };
+    }
+
+    let angle = |p: Vec3, q: Vec3, r: Vec3| (q - p).angle_between(r - p).to_degrees();
+    let angles = [angle(a, b, c), angle(b, c, a), angle(c, a, b)];
+    let min_angle = angles.iter().copied().fold(180.0, f32::min);
+    let max_angle = angles.iter().copied().fold(0.0, f32::max);
+
+    TriangleQuality {
+        aspect_ratio: longest * perimeter / (4.0 * 3.0f32.sqrt() * area),
+        min_angle,
+        skewness: ((max_angle - 60.0) / 120.0)
+            .max((60.0 - min_angle) / 60.0)
+            .clamp(0.0, 1.0),
+    }
+}
+
+/// Compute the quality metrics of every cell
+///
+/// Cells without surface triangles (lines, vertices) get NaN for all metrics, shown
+/// with the NaN color.
+pub fn cell_quality(geometry: &GeometryData) -> Vec<TriangleQuality> {
+    let mut cells: Vec<Option<TriangleQuality>> =
+        vec![None; location_size(geometry, &AttributeLocation::Cell)];
+    for (triangle, corners) in geometry.indices.chunks_exact(3).enumerate() {
+        let [a, b, c] = [0, 1, 2].map(|k| Vec3::from(geometry.vertices[corners[k] as usize]));
+        let quality = triangle_quality(a, b, c);
+        let Some(cell) = cells.get_mut(triangle_cell(geometry, triangle)) else {
+            continue;
+        };
+        *cell = Some(cell.map_or(quality, |existing| existing.worst(quality)));
+    }
+    cells
+        .into_iter()
+        .map(|quality| {
+            quality.unwrap_or(TriangleQuality {
+                aspect_ratio: f32::NAN,
+                min_angle: f32::NAN,
+                skewness: f32::NAN,
+            })
+        })
+        .collect()
+}
+
+/// Store the quality metrics as cell scalars
+///
+/// # Parameters
+/// - `geometry`: Geometry data
+///
+/// # Returns
+/// - Minimum, mean and maximum of every metric over the cells with triangles
+pub fn add_quality_attributes(geometry: &mut GeometryData) -> Vec<QualitySummary> {
+    let quality = cell_quality(geometry);
+    let attributes = geometry.attributes.get_or_insert_with(Default::default);
+
+    QUALITY_ATTRIBUTES
+        .iter()
+        .enumerate()
+        .map(|(metric, &name)| {
+            let data: Vec<f32> = quality.iter().map(|q| q.values()[metric]).collect();
+            let finite: Vec<f32> = data.iter().copied().filter(|v| v.is_finite()).collect();
+            let sum: f64 = finite.iter().map(|&v| f64::from(v)).sum();
+            let summary = QualitySummary {
+                name,
+                min: finite.iter().copied().fold(f32::INFINITY, f32::min),
+                mean: (sum / finite.len().max(1) as f64) as f32,
+                max: finite.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+            };
+            attributes.insert(
+                (name.to_string(), AttributeLocation::Cell),
+                AttributeType::Scalar {
+                    num_comp: 1,
+                    table_name: "default".to_string(),
+                    data,
+                    lookup_table: None,
+                },
+            );
+            summary
+        })
+        .collect()
+}
@@ -0,0 +1,398 @@
+//! Selection query module
+//!
+//! A small expression language for selecting cells by attribute values, e.g.
+//! `pressure > 1e5 AND region == 3`.
+//!
+//! - Comparisons: `==` (or `=`), `!=`, `<`, `<=`, `>`, `>=`
+//! - Logic: `AND` / `&&`, `OR` / `||`, `NOT` / `!`, parentheses
+//! - Fields: attribute names (quoted with `"` if they contain spaces), an optional
+//!   component index (`velocity[1]`), and the reserved field `id` (cell index)
+//!
+//! Vector attributes without a component index evaluate to their magnitude.
+//! Cell attributes are used directly; point attributes are averaged per cell.
+use super::topology::{attribute_components, cell_attribute_values, cell_triangles, cell_vertices};
+use super::{AttributeLocation, GeometryData, VtkError};
+use bevy::utils::HashMap;
+
+/// Comparison operator
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompareOp {
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+}
+
+impl CompareOp {
+    fn evaluate(self, left: f32, right: f32) -> bool {
+        match self {
+            CompareOp::Equal => left == right,
+            CompareOp::NotEqual => left != right,
+            CompareOp::Less => left < right,
+            CompareOp::LessEqual => left <= right,
+            CompareOp::Greater => left > right,
+            CompareOp::GreaterEqual => left >= right,
+        }
+    }
+}
+
+/// Attribute reference inside a query
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Field {
+    /// Attribute name (or `id`)
+    pub name: String,
+    /// Optional component index
+    pub component: Option<usize>,
+}
+
+/// Operand of a comparison
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operand {
+    Number(f32),
+    Field(Field),
+}
+
+/// Parsed query expression
+#[derive(Clone, Debug, PartialEq)]
+pub enum Query {
+    Compare(Operand, CompareOp, Operand),
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(f32),
+    Op(CompareOp),
+    And,
+    Or,
+    Not,
+    LeftParen,
+    RightParen,
+    LeftBracket,
+    RightBracket,
+}
+
+fn syntax_error(message: impl Into<String>) -> VtkError {
+    VtkError::GenericError(format!("Query syntax error: {}", message.into()))
+}
+
+/// Split the query text into tokens
+fn tokenize(text: &str) -> Result<Vec<Token>, VtkError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push(Token::LeftParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RightParen);
+                i += 1;
+            }
+            '[' => {
+                tokens.push(Token::LeftBracket);
+                i += 1;
+            }
+            ']' => {
+                tokens.push(Token::RightBracket);
+                i += 1;
+            }
+            '&' if next == Some('&') => {
+                tokens.push(Token::And);
+                i += 2;
+            }
+            '|' if next == Some('|') => {
+                tokens.push(Token::Or);
+                i += 2;
+            }
+            '=' => {
+                // Accept both `==` and `=`
+                tokens.push(Token::Op(CompareOp::Equal));
+                i += if next == Some('=') { 2 } else { 1 };
+            }
+            '!' if next == Some('=') => {
+                tokens.push(Token::Op(CompareOp::NotEqual));
+                i += 2;
+            }
+            '!' => {
+                tokens.push(Token::Not);
+                i += 1;
+            }
+            '<' | '>' => {
+                let or_equal = next == Some('=');
+                let op = match (c, or_equal) {
+                    ('<', false) => CompareOp::Less,
+                    ('<', true) => CompareOp::LessEqual,
+                    ('>', false) => CompareOp::Greater,
+                    _ => CompareOp::GreaterEqual,
+                };
+                tokens.push(Token::Op(op));
+                i += if or_equal { 2 } else { 1 };
+            }
+            '"' => {
+                let start = i + 1;
+                let Some(length) = chars[start..].iter().position(|&c| c == '"') else {
+                    return Err(syntax_error("unterminated quoted name"));
+                };
+                tokens.push(Token::Ident(chars[start..start + length].iter().collect()));
+                i = start + length + 1;
+            }
+            c if c.is_ascii_digit() || c == '.' || c == '-' || c == '+' => {
+                let start = i;
+                i += 1;
+                while i < chars.len() {
+                    let c = chars[i];
+                    let exponent_sign = (c == '-' || c == '+') && matches!(chars[i - 1], 'e' | 'E');
+                    if c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || exponent_sign {
+                        i += 1;
+                    } else {
+                        break;
+                    }
+                }
+                let literal: String = chars[start..i].iter().collect();
+                let value = literal
+                    .parse::<f32>()
+                    .map_err(|_| syntax_error(format!("invalid number '{}'", literal)))?;
+                tokens.push(Token::Number(value));
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                tokens.push(match word.to_ascii_uppercase().as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => Token::Ident(word),
+                });
+            }
+            other => return Err(syntax_error(format!("unexpected character '{}'", other))),
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive descent parser over the token list
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<Query, VtkError> {
+        let mut query = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.position += 1;
+            query = Query::Or(Box::new(query), Box::new(self.parse_and()?));
+        }
+        Ok(query)
+    }
+
+    fn parse_and(&mut self) -> Result<Query, VtkError> {
+        let mut query = self.parse_unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.position += 1;
+            query = Query::And(Box::new(query), Box::new(self.parse_unary()?));
+        }
+        Ok(query)
+    }
+
+    fn parse_unary(&mut self) -> Result<Query, VtkError> {
+        match self.peek() {
+            Some(Token::Not) => {
+                self.position += 1;
+                Ok(Query::Not(Box::new(self.parse_unary()?)))
+            }
+            Some(Token::LeftParen) => {
+                self.position += 1;
+                let query = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RightParen) => Ok(query),
+                    _ => Err(syntax_error("expected ')'")),
+                }
+            }
+            _ => self.parse_comparison(),
+        }
+    }
+
+    fn parse_comparison(&mut self) -> Result<Query, VtkError> {
+        let left = self.parse_operand()?;
+        let op = match self.next() {
+            Some(Token::Op(op)) => op,
+            _ => return Err(syntax_error("expected comparison operator")),
+        };
+        let right = self.parse_operand()?;
+        Ok(Query::Compare(left, op, right))
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, VtkError> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(Operand::Number(value)),
+            Some(Token::Ident(name)) => {
+                let mut component = None;
+                if self.peek() == Some(&Token::LeftBracket) {
+                    self.position += 1;
+                    let Some(Token::Number(index)) = self.next() else {
+                        return Err(syntax_error("expected component index"));
+                    };
+                    if self.next() != Some(Token::RightBracket) {
+                        return Err(syntax_error("expected ']'"));
+                    }
+                    if index < 0.0 || index.fract() != 0.0 {
+                        return Err(syntax_error(format!("invalid component index {}", index)));
+                    }
+                    component = Some(index as usize);
+                }
+                Ok(Operand::Field(Field { name, component }))
+            }
+            _ => Err(syntax_error("expected attribute name or number")),
+        }
+    }
+}
+
+impl Query {
+    /// Parse a query string
+    ///
+    /// # Returns
+    /// - `Ok(Query)`: Parsed expression
+    /// - `Err(VtkError)`: Syntax error
+    pub fn parse(text: &str) -> Result<Self, VtkError> {
+        let tokens = tokenize(text)?;
+        if tokens.is_empty() {
+            return Err(syntax_error("empty query"));
+        }
+
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+        };
+        let query = parser.parse_or()?;
+        if parser.position < parser.tokens.len() {
+            return Err(syntax_error(format!(
+                "unexpected token {:?}",
+                parser.tokens[parser.position]
+            )));
+        }
+        Ok(query)
+    }
+
+    /// Collect all fields referenced by the query
+    fn collect_fields(&self, fields: &mut Vec<Field>) {
+        match self {
+            Query::Compare(left, _, right) => {
+                for operand in [left, right] {
+                    if let Operand::Field(field) = operand {
+                        if !fields.contains(field) {
+                            fields.push(field.clone());
+                        }
+                    }
+                }
+            }
+            Query::And(a, b) | Query::Or(a, b) => {
+                a.collect_fields(fields);
+                b.collect_fields(fields);
+            }
+            Query::Not(a) => a.collect_fields(fields),
+        }
+    }
+
+    fn evaluate(&self, cell: usize, values: &HashMap<Field, Vec<f32>>) -> bool {
+        let operand_value = |operand: &Operand| match operand {
+            Operand::Number(value) => *value,
+            Operand::Field(field) => values[field][cell],
+        };
+        match self {
+            Query::Compare(left, op, right) => {
+                op.evaluate(operand_value(left), operand_value(right))
+            }
+            Query::And(a, b) => a.evaluate(cell, values) && b.evaluate(cell, values),
+            Query::Or(a, b) => a.evaluate(cell, values) || b.evaluate(cell, values),
+            Query::Not(a) => !a.evaluate(cell, values),
+        }
+    }
+
+    /// Evaluate the query for every cell of the geometry
+    ///
+    /// # Returns
+    /// - `Ok(cells)`: Indices of the matching cells
+    /// - `Err(VtkError)`: A referenced attribute does not exist
+    pub fn select_cells(&self, geometry: &GeometryData) -> Result<Vec<usize>, VtkError> {
+        let triangles = cell_triangles(geometry);
+        let vertices = cell_vertices(geometry, &triangles);
+
+        let mut fields = Vec::new();
+        self.collect_fields(&mut fields);
+
+        let mut values = HashMap::new();
+        for field in fields {
+            let field_values = cell_field_values(geometry, &field, &vertices)?;
+            values.insert(field, field_values);
+        }
+
+        Ok((0..triangles.len())
+            .filter(|&cell| !triangles[cell].is_empty() && self.evaluate(cell, &values))
+            .collect())
+    }
+}
+
+/// Evaluate a field for every cell
+fn cell_field_values(
+    geometry: &GeometryData,
+    field: &Field,
+    cell_vertices: &[Vec<u32>],
+) -> Result<Vec<f32>, VtkError> {
+    if field.name.eq_ignore_ascii_case("id") && field.component.is_none() {
+        return Ok((0..cell_vertices.len()).map(|cell| cell as f32).collect());
+    }
+
+    let (attribute, location) = [AttributeLocation::Cell, AttributeLocation::Point]
+        .into_iter()
+        .find_map(|location| {
+            geometry
+                .get_attributes(&field.name, location.clone())
+                .map(|attribute| (attribute, location))
+        })
+        .ok_or_else(|| VtkError::GenericError(format!("Unknown attribute '{}'", field.name)))?;
+
+    let components = attribute_components(attribute);
+    if field
+        .component
+        .is_some_and(|component| component >= components)
+    {
+        return Err(VtkError::GenericError(format!(
+            "Component {:?} out of range for '{}' ({} components)",
+            field.component, field.name, components
+        )));
+    }
+
+    Ok(cell_attribute_values(
+        attribute,
+        &location,
+        field.component,
+        cell_vertices,
+    ))
+}
@@ -0,0 +1,352 @@
+//! Resample module
+//!
+//! Probing of a dataset onto a regular grid:
+//! - Grid points inside volume cells interpolate the point attributes of the
+//!   cell and take its cell attributes
+//! - Surfaces are probed along the flat axis of a grid with one point layer
+//!   (e.g. terrain seen from above)
+//! - Points outside the dataset get 0 and a 0 in the `vtkValidPointMask` scalar
+//!
+//! The result is a [`VolumeData`] with point data only, like image data read
+//! from a `.vti` file.
+use super::streamlines::CellLocator;
+use super::topology::{attribute_components, attribute_value, triangle_cell};
+use super::volume::VolumeData;
+use super::{AttributeLocation, AttributeType, GeometryData, VtkError};
+use bevy::math::{Vec2, Vec3};
+use bevy::utils::HashMap;
+
+/// Point scalar marking grid points inside the dataset (1) or outside (0)
+pub const VALID_MASK_ATTRIBUTE: &str = "vtkValidPointMask";
+
+/// Largest number of grid points
+pub const MAX_GRID_POINTS: usize = 256 * 256 * 256;
+
+/// Regular grid to resample onto
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ResampleGrid {
+    /// Number of points along x, y and z
+    pub dims: [usize; 3],
+    /// Position of the first point
+    pub min: [f32; 3],
+    /// Position of the last point (ignored along axes with one point)
+    pub max: [f32; 3],
+}
+
+impl ResampleGrid {
+    /// Distance between neighboring points along x, y and z
+    pub fn spacing(&self) -> [f32; 3] {
+        std::array::from_fn(|axis| {
+            if self.dims[axis] > 1 {
+                (self.max[axis] - self.min[axis]) / (self.dims[axis] - 1) as f32
+            } else {
+                1.0
+            }
+        })
+    }
+
+    /// Total number of grid points
+    pub fn point_count(&self) -> usize {
+        self.dims.iter().product()
+    }
+}
+
+/// Cell containing a grid point and the interpolation weights of its points
+struct Sample {
+    cell: usize,
+    points: [u32; 4],
+    weights: [f32; 4],
+}
+
+/// Triangles binned on a regular grid in the plane normal to a flat axis
+struct TriangleLocator {
+    /// Plane axes
+    axes: [usize; 2],
+    bins: Vec<Vec<u32>>,
+    bin_count: usize,
+    min: Vec2,
+    bin_size: Vec2,
+}
+
+impl TriangleLocator {
+    fn new(geometry: &GeometryData, flat_axis: usize) -> Option<Self> {
+        let axes = match flat_axis {
+            0 => [1, 2],
+            1 => [2, 0],
+            _ => [0, 1],
+        };
+        let project = |v: [f32; 3]| Vec2::new(v[axes[0]], v[axes[1]]);
+        let (min, max) = geometry.vertices.iter().fold(None, |bounds, &v| {
+            let p = project(v);
+            match bounds {
+                None => Some((p, p)),
+                Some((min, max)) => Some((p.min(min), p.max(max))),
+            }
+        })?;
+        let triangles = geometry.indices.len() / 3;
+        let bin_count = ((triangles as f32).sqrt().ceil() as usize).clamp(1, 512);
+        let bin_size = (max - min).max(Vec2::splat(f32::EPSILON)) / bin_count as f32;
+        let mut locator = Self {
+            axes,
+            bins: vec![Vec::new(); bin_count * bin_count],
+            bin_count,
+            min,
+            bin_size,
+        };
+
+        for (triangle, corners) in geometry.indices.chunks_exact(3).enumerate() {
+            let [a, b, c] = [0, 1, 2].map(|k| project(geometry.vertices[corners[k] as usize]));
+            let (low, high) = (
+                locator.bin_of(a.min(b).min(c)),
+                locator.bin_of(a.max(b).max(c)),
+            );
+            for y in low[1]..=high[1] {
+                for x in low[0]..=high[0] {
+                    locator.bins[x + y * bin_count].push(triangle as u32);
+                }
+            }
+        }
+        Some(locator)
+    }
+
+    fn bin_of(&self, point: Vec2) -> [usize; 2] {
+        let cell = (point - self.min) / self.bin_size;
+        [cell.x, cell.y].map(|c| (c.max(0.0) as usize).min(self.bin_count - 1))
+    }
+
+    /// Triangle below or above a point with its barycentric weights
+    fn locate(&self, geometry: &GeometryData, point: [f32; 3]) -> Option<Sample> {
+        let p = Vec2::new(point[self.axes[0]], point[self.axes[1]]);
+        let offset = (p - self.min) / self.bin_size;
+        if offset.min_element() < 0.0 || offset.max_element() > self.bin_count as f32 {
+            return None;
+        }
+        let [x, y] = self.bin_of(p);
+        self.bins[x + y * self.bin_count]
+            .iter()
+            .find_map(|&triangle| {
+                let triangle = triangle as usize;
+                let corners = &geometry.indices[triangle * 3..triangle * 3 + 3];
+                let [a, b, c] = [0, 1, 2].map(|k| {
+                    let v = geometry.vertices[corners[k] as usize];
+                    Vec2::new(v[self.axes[0]], v[self.axes[1]])
+                });
+                let area = (b - a).perp_dot(c - a);
+                if area.abs() <= f32::EPSILON * (b - a).length_squared().max(f32::EPSILON) {
+                    return None;
+                }
+                let u = (c - b).perp_dot(p - b) / area;
+                let v = (a - c).perp_dot(p - c) / area;
+                let weights = [u, v, 1.0 - u - v];
+                weights.iter().all(|&w| w >= -1e-5).then(|| Sample {
+                    cell: triangle_cell(geometry, triangle),
+                    points: [corners[0], corners[1], corners[2], corners[0]],
+                    weights: [weights[0], weights[1], weights[2], 0.0],
+                })
+            })
+    }
+}
+
+/// Locate every grid point in the volume cells of a geometry
+fn sample_volume_cells(
+    geometry: &GeometryData,
+    grid: &ResampleGrid,
+) -> Option<Vec<Option<Sample>>> {
+    let cells = geometry.volume_cells.as_ref()?;
+    let positions: Vec<Vec3> = geometry.vertices.iter().map(|&v| Vec3::from(v)).collect();
+    let mut tetrahedra = Vec::new();
+    let mut tetrahedron_cells = Vec::new();
+    for index in 0..cells.len() {
+        let (shape, points) = cells.cell(index);
+        if points
+            .iter()
+            .any(|&point| point as usize >= positions.len())
+        {
+            continue;
+        }
+        for tetrahedron in shape.tetrahedra() {
+            tetrahedra.push(tetrahedron.map(|corner| points[corner]));
+            tetrahedron_cells.push(index);
+        }
+    }
+    let locator = CellLocator::new(&positions, tetrahedra.clone())?;
+
+    let mut hint = None;
+    Some(
+        grid_points(grid)
+            .map(|point| {
+                let (tetrahedron, weights) = locator.locate(Vec3::from(point), &positions, hint)?;
+                hint = Some(tetrahedron);
+                Some(Sample {
+                    cell: tetrahedron_cells[tetrahedron],
+                    points: tetrahedra[tetrahedron],
+                    weights,
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Positions of the grid points, x varying fastest
+fn grid_points(grid: &ResampleGrid) -> impl Iterator<Item = [f32; 3]> + '_ {
+    let spacing = grid.spacing();
+    (0..grid.dims[2]).flat_map(move |k| {
+        (0..grid.dims[1]).flat_map(move |j| {
+            (0..grid.dims[0]).map(move |i| {
+                let p = [i, j, k];
+                std::array::from_fn(|axis| grid.min[axis] + spacing[axis] * p[axis] as f32)
+            })
+        })
+    })
+}
+
+/// Attribute of the same kind as `template` from flat component values
+fn attribute_like(template: &AttributeType, components: usize, data: Vec<f32>) -> AttributeType {
+    match template {
+        AttributeType::Scalar {
+            table_name,
+            lookup_table,
+            ..
+        } => AttributeType::Scalar {
+            num_comp: components,
+            table_name: table_name.clone(),
+            data,
+            lookup_table: lookup_table.clone(),
+        },
+        AttributeType::ColorScalar { nvalues, .. } => AttributeType::ColorScalar {
+            nvalues: *nvalues,
+            data: data.chunks_exact(components).map(<[f32]>::to_vec).collect(),
+        },
+        AttributeType::Vector(_) => {
+            AttributeType::Vector(data.chunks_exact(3).map(|v| [v[0], v[1], v[2]]).collect())
+        }
+        AttributeType::Tensor(_) => AttributeType::Tensor(
+            data.chunks_exact(9)
+                .map(|t| std::array::from_fn(|k| t[k]))
+                .collect(),
+        ),
+    }
+}
+
+/// Resample a geometry onto a regular grid
+///
+/// Cell attributes become point attributes of the grid; if a point attribute
+/// has the same name, the cell attribute is renamed to `<name> (cell)`.
+///
+/// # Parameters
+/// - `geometry`: Dataset to probe
+/// - `grid`: Grid resolution and bounds
+///
+/// # Returns
+/// - `Ok(VolumeData)`: Grid with the probed attributes and the valid point mask
+/// - `Err(VtkError)`: The grid is empty or too large, or the geometry has no
+///   cells the grid can be probed in
+pub fn resample_to_grid(
+    geometry: &GeometryData,
+    grid: &ResampleGrid,
+) -> Result<VolumeData, VtkError> {
+    let point_count = grid.point_count();
+    if point_count == 0 || point_count > MAX_GRID_POINTS {
+        return Err(VtkError::GenericError(format!(
+            "The grid must have between 1 and {} points",
+            MAX_GRID_POINTS
+        )));
+    }
+
+    let samples = match sample_volume_cells(geometry, grid) {
+        Some(samples) => samples,
+        None if !geometry.indices.is_empty() => {
+            let flat_axis = (0..3)
+                .rev()
+                .find(|&axis| grid.dims[axis] == 1)
+                .ok_or_else(|| {
+                    VtkError::GenericError(
+                        "A surface can only be resampled onto a grid with one point along an axis"
+                            .to_string(),
+                    )
+                })?;
+            let locator = TriangleLocator::new(geometry, flat_axis)
+                .ok_or(VtkError::MissingData("surface points"))?;
+            grid_points(grid)
+                .map(|point| locator.locate(geometry, point))
+                .collect()
+        }
+        None => {
+            return Err(VtkError::GenericError(
+                "The model has no cells to resample".to_string(),
+            ))
+        }
+    };
+
+    let mut attributes = HashMap::new();
+    let source = geometry.attributes.iter().flatten();
+    for ((name, location), attribute) in source {
+        if name.starts_with("__lut_") {
+            attributes.insert((name.clone(), location.clone()), attribute.clone());
+            continue;
+        }
+        let components = attribute_components(attribute);
+        let mut data = vec![0.0; point_count * components];
+        for (values, sample) in data.chunks_exact_mut(components).zip(&samples) {
+            let Some(sample) = sample else {
+                continue;
+            };
+            for (component, value) in values.iter_mut().enumerate() {
+                *value = match location {
+                    AttributeLocation::Point => sample
+                        .points
+                        .iter()
+                        .zip(sample.weights)
+                        .map(|(&point, weight)| {
+                            weight * attribute_value(attribute, point as usize, Some(component))
+                        })
+                        .sum(),
+                    AttributeLocation::Cell => {
+                        attribute_value(attribute, sample.cell, Some(component))
+                    }
+                };
+            }
+        }
+
+        let point_name = match location {
+            AttributeLocation::Cell
+                if geometry
+                    .get_attributes(name, AttributeLocation::Point)
+                    .is_some() =>
+            {
+                format!("{} (cell)", name)
+            }
+            _ => name.clone(),
+        };
+        attributes.insert(
+            (point_name, AttributeLocation::Point),
+            attribute_like(attribute, components, data),
+        );
+    }
+
+    let valid = samples
+        .iter()
+        .map(|sample| if sample.is_some() { 1.0 } else { 0.0 })
+        .collect::<Vec<f32>>();
+    let inside = valid.iter().filter(|&&v| v > 0.0).count();
+    attributes.insert(
+        (VALID_MASK_ATTRIBUTE.to_string(), AttributeLocation::Point),
+        AttributeType::Scalar {
+            num_comp: 1,
+            table_name: "default".to_string(),
+            data: valid,
+            lookup_table: None,
+        },
+    );
+    println!(
+        "Resampled onto {} x {} x {} grid: {} of {} points inside the dataset",
+        grid.dims[0], grid.dims[1], grid.dims[2], inside, point_count
+    );
+
+    Ok(VolumeData {
+        dims: grid.dims,
+        origin: grid.min,
+        spacing: grid.spacing(),
+        attributes,
+    })
+}
@@ -0,0 +1,145 @@
+//! Slice module
+//!
+//! Cuts of a dataset with an arbitrary plane:
+//! - Volume cells and image data volumes are cut into polygons, contoured at
+//!   zero signed distance to the plane by marching tetrahedra
+//! - Surface meshes without volume cells are cut into line segments
+//!
+//! Point attributes are interpolated onto the cut in both cases.
+use super::isosurface::{contour_cells, contour_volume, interpolate_attribute, EdgePoint};
+use super::volume::VolumeData;
+use super::{AttributeLocation, AttributeType, GeometryData};
+use bevy::math::Vec3;
+use bevy::utils::HashMap;
+
+/// Line segments where a plane cuts a surface
+#[derive(Clone, Debug, Default)]
+pub struct SliceLines {
+    /// Cut points on the triangle edges
+    pub vertices: Vec<[f32; 3]>,
+    /// Point pairs of the segments
+    pub segments: Vec<u32>,
+    /// Point attributes interpolated at the cut points
+    pub attributes: HashMap<(String, AttributeLocation), AttributeType>,
+}
+
+/// Signed distances of points to a plane (positive on the normal side)
+fn signed_distances(positions: &[[f32; 3]], origin: Vec3, normal: Vec3) -> Vec<f32> {
+    let normal = normal.normalize_or_zero();
+    positions
+        .iter()
+        .map(|&position| (Vec3::from(position) - origin).dot(normal))
+        .collect()
+}
+
+/// Cut the volume cells of a geometry with a plane
+///
+/// # Parameters
+/// - `geometry`: Geometry with volume cells
+/// - `origin`: Point on the plane (model space)
+/// - `normal`: Plane normal
+///
+/// # Returns
+/// - Cut polygons as triangles, `None` if the geometry has no volume cells
+pub fn slice_cells(geometry: &GeometryData, origin: Vec3, normal: Vec3) -> Option<GeometryData> {
+    let distances = signed_distances(&geometry.vertices, origin, normal);
+    contour_cells(geometry, &distances, 0.0)
+}
+
+/// Cut an image data volume with a plane
+///
+/// # Parameters
+/// - `volume`: Voxel data
+/// - `origin`: Point on the plane (model space)
+/// - `normal`: Plane normal
+///
+/// # Returns
+/// - Cut polygons as triangles, `None` for flat volumes
+pub fn slice_volume(volume: &VolumeData, origin: Vec3, normal: Vec3) -> Option<GeometryData> {
+    let mut positions = Vec::with_capacity(volume.point_count());
+    for z in 0..volume.dims[2] {
+        for y in 0..volume.dims[1] {
+            for x in 0..volume.dims[0] {
+                positions.push(volume.point_position([x, y, z]));
+            }
+        }
+    }
+    let distances = signed_distances(&positions, origin, normal);
+    contour_volume(volume, &distances, 0.0)
+}
+
+/// Cut the triangles of a surface with a plane
+///
+/// # Parameters
+/// - `geometry`: Surface geometry
+/// - `origin`: Point on the plane (model space)
+/// - `normal`: Plane normal
+///
+/// # Returns
+/// - Cut segments; empty if the plane misses the surface
+pub fn slice_surface(geometry: &GeometryData, origin: Vec3, normal: Vec3) -> SliceLines {
+    let distances = signed_distances(&geometry.vertices, origin, normal);
+    let mut points: Vec<EdgePoint> = Vec::new();
+    let mut point_ids: HashMap<(u32, u32), u32> = HashMap::new();
+    let mut segments = Vec::new();
+
+    let mut edge_point = |a: u32, b: u32| -> u32 {
+        let (from, to) = if a < b { (a, b) } else { (b, a) };
+        *point_ids.entry((from, to)).or_insert_with(|| {
+            let (start, end) = (distances[from as usize], distances[to as usize]);
+            let t = if (end - start).abs() > f32::EPSILON {
+                (start / (start - end)).clamp(0.0, 1.0)
+            } else {
+                0.5
+            };
+            points.push(EdgePoint { from, to, t });
+            points.len() as u32 - 1
+        })
+    };
+
+    for triangle in geometry.indices.chunks_exact(3) {
+        if triangle
+            .iter()
+            .any(|&point| point as usize >= distances.len())
+        {
+            continue;
+        }
+        let (above, below): (Vec<u32>, Vec<u32>) = triangle
+            .iter()
+            .partition(|&&point| distances[point as usize] >= 0.0);
+        // One corner is separated from the other two: a segment
+        let (lone, others) = match (above.len(), below.len()) {
+            (1, 2) => (above[0], below),
+            (2, 1) => (below[0], above),
+            _ => continue,
+        };
+        segments.push(edge_point(lone, others[0]));
+        segments.push(edge_point(lone, others[1]));
+    }
+
+    let vertices = points
+        .iter()
+        .map(|point| {
+            Vec3::from(geometry.vertices[point.from as usize])
+                .lerp(Vec3::from(geometry.vertices[point.to as usize]), point.t)
+                .to_array()
+        })
+        .collect();
+    let attributes = geometry
+        .attributes
+        .iter()
+        .flatten()
+        .filter(|((name, location), _)| {
+            *location == AttributeLocation::Point && !name.starts_with("__lut_")
+        })
+        .filter_map(|(key, attribute)| {
+            interpolate_attribute(attribute, &points).map(|attribute| (key.clone(), attribute))
+        })
+        .collect();
+
+    SliceLines {
+        vertices,
+        segments,
+        attributes,
+    }
+}
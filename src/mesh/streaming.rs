@@ -0,0 +1,1045 @@
+//! Streaming legacy VTK reader module
+//!
+//! Reads legacy VTK files (`.vtk`, ASCII or binary) with an `UNSTRUCTURED_GRID`
+//! or `POLYDATA` dataset on a background thread, section by section and in
+//! chunks, instead of parsing the whole file at once:
+//! - Progress is reported as the fraction of the file read
+//! - Cells are triangulated chunk by chunk; every chunk is also sent as a preview
+//!   mesh with its own vertices, so partial geometry can be shown while loading
+//! - The complete geometry, including point and cell data, is sent at the end
+//!
+//! Both the classic cell layout (`CELLS n size`) and the `OFFSETS`/`CONNECTIVITY`
+//! layout of version 5 files are supported. Loading can be cancelled between
+//! chunks.
+//!
+//! Binary files can optionally be memory-mapped: values are then decoded straight
+//! from the mapped file instead of being copied through a read buffer, and the
+//! mapped pages can be dropped by the OS once read, which lowers the peak memory
+//! of large datasets.
+use super::cells::VolumeCells;
+use super::triangulation::{triangulate_cells, triangulate_polygon, triangulate_strips};
+use super::vtk::{generic_attribute, UnstructuredGridExtractor, VtkMeshExtractor};
+use super::{ghost, AttributeLocation, AttributeType, GeometryData, PolyLine, VtkError};
+use bevy::utils::HashMap;
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use vtkio::model::{self, ElementType, VertexNumbers};
+use vtkio::IOBuffer;
+
+/// Cells triangulated per chunk
+const CELL_CHUNK: usize = 250_000;
+/// Values read per chunk of a data section
+const VALUE_CHUNK: usize = 3_000_000;
+
+/// Message from the loading thread
+pub enum StreamMessage {
+    /// Fraction of the file read and the section being read
+    Progress { fraction: f32, phase: &'static str },
+    /// Triangles of the cells read so far
+    Preview(PreviewChunk),
+    /// The file is read completely (or loading failed or was cancelled)
+    Finished(Box<Result<GeometryData, VtkError>>),
+}
+
+/// Triangles of a chunk of cells with their own vertices
+pub struct PreviewChunk {
+    pub positions: Vec<[f32; 3]>,
+    pub indices: Vec<u32>,
+}
+
+/// Legacy VTK file being loaded on a background thread
+pub struct StreamingLoad {
+    /// Receiver behind a mutex, so the load can be kept in a resource
+    receiver: Mutex<Receiver<StreamMessage>>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl StreamingLoad {
+    /// Start loading a legacy VTK file
+    ///
+    /// # Parameters
+    /// - `path`: Legacy VTK file
+    /// - `memory_map`: Memory-map the file if it is binary
+    pub fn spawn(path: PathBuf, memory_map: bool) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let thread_cancel = cancel.clone();
+        std::thread::spawn(move || {
+            let result = read_streaming(&path, memory_map, &sender, &thread_cancel);
+            let _ = sender.send(StreamMessage::Finished(Box::new(result)));
+        });
+        Self {
+            receiver: Mutex::new(receiver),
+            cancel,
+        }
+    }
+
+    /// Next message of the loading thread, if any
+    ///
+    /// A loading thread that stopped without a result (e.g. after a panic) is
+    /// reported as a failed load.
+    pub fn try_recv(&self) -> Option<StreamMessage> {
+        match self.receiver.lock().ok()?.try_recv() {
+            Ok(message) => Some(message),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(StreamMessage::Finished(Box::new(Err(
+                VtkError::LoadError("Streaming import stopped unexpectedly".to_string()),
+            )))),
+        }
+    }
+
+    /// Stop loading at the next chunk
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Whether a file is a legacy VTK file with a dataset the streaming reader supports
+pub fn supports_streaming(path: &Path) -> bool {
+    let Ok(header) = read_file_header(path) else {
+        return false;
+    };
+    matches!(header.dataset.as_str(), "UNSTRUCTURED_GRID" | "POLYDATA")
+}
+
+/// Read the header of a legacy VTK file
+fn read_file_header(path: &Path) -> Result<Header, VtkError> {
+    let file = File::open(path).map_err(VtkError::IoError)?;
+    LegacyReader::new(BufReader::new(file), 0).read_header()
+}
+
+/// Value type of a data section
+#[derive(Clone, Copy, Debug)]
+enum DataType {
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+    F32,
+    F64,
+}
+
+impl DataType {
+    fn parse(name: &str) -> Result<Self, VtkError> {
+        Ok(match name.to_ascii_lowercase().as_str() {
+            "unsigned_char" => DataType::U8,
+            "char" => DataType::I8,
+            "unsigned_short" => DataType::U16,
+            "short" => DataType::I16,
+            "unsigned_int" => DataType::U32,
+            "int" => DataType::I32,
+            "unsigned_long" | "vtktypeuint64" => DataType::U64,
+            "long" | "vtktypeint64" | "vtkidtype" => DataType::I64,
+            "float" => DataType::F32,
+            "double" => DataType::F64,
+            _ => return Err(VtkError::UnsupportedDataType),
+        })
+    }
+
+    /// Size of a binary value in bytes
+    fn size(self) -> usize {
+        match self {
+            DataType::U8 | DataType::I8 => 1,
+            DataType::U16 | DataType::I16 => 2,
+            DataType::U32 | DataType::I32 | DataType::F32 => 4,
+            DataType::U64 | DataType::I64 | DataType::F64 => 8,
+        }
+    }
+
+    /// Decode a big-endian binary value
+    fn decode(self, bytes: &[u8]) -> f64 {
+        let array = || -> [u8; 8] {
+            let mut array = [0; 8];
+            array.copy_from_slice(&bytes[..8]);
+            array
+        };
+        match self {
+            DataType::U8 => bytes[0] as f64,
+            DataType::I8 => bytes[0] as i8 as f64,
+            DataType::U16 => u16::from_be_bytes([bytes[0], bytes[1]]) as f64,
+            DataType::I16 => i16::from_be_bytes([bytes[0], bytes[1]]) as f64,
+            DataType::U32 => u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+            DataType::I32 => i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+            DataType::F32 => f32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+            DataType::U64 => u64::from_be_bytes(array()) as f64,
+            DataType::I64 => i64::from_be_bytes(array()) as f64,
+            DataType::F64 => f64::from_be_bytes(array()),
+        }
+    }
+}
+
+/// Header of a legacy VTK file
+struct Header {
+    version: f32,
+    binary: bool,
+    dataset: String,
+}
+
+/// Token reader of legacy VTK files, tracking the bytes read
+struct LegacyReader<R: BufRead> {
+    reader: R,
+    binary: bool,
+    /// Bytes read so far
+    position: u64,
+    /// File size, for progress
+    length: u64,
+    /// The last token ended a line
+    at_line_start: bool,
+    token: Vec<u8>,
+}
+
+impl<R: BufRead> LegacyReader<R> {
+    fn new(reader: R, length: u64) -> Self {
+        Self {
+            reader,
+            binary: false,
+            position: 0,
+            length,
+            at_line_start: true,
+            token: Vec::new(),
+        }
+    }
+
+    fn fraction(&self) -> f32 {
+        if self.length == 0 {
+            return 0.0;
+        }
+        (self.position as f64 / self.length as f64).min(1.0) as f32
+    }
+
+    /// Read the next whitespace separated token into `self.token`
+    ///
+    /// # Returns
+    /// - `Ok(false)`: End of file
+    fn next_token(&mut self) -> Result<bool, VtkError> {
+        self.token.clear();
+        loop {
+            let buffer = self.reader.fill_buf().map_err(VtkError::IoError)?;
+            if buffer.is_empty() {
+                break;
+            }
+            let mut used = 0;
+            let mut done = false;
+            for &byte in buffer {
+                used += 1;
+                if byte.is_ascii_whitespace() {
+                    if !self.token.is_empty() {
+                        self.at_line_start = byte == b'\n';
+                        done = true;
+                        break;
+                    }
+                } else {
+                    self.token.push(byte);
+                }
+            }
+            self.reader.consume(used);
+            self.position += used as u64;
+            if done {
+                break;
+            }
+        }
+        Ok(!self.token.is_empty())
+    }
+
+    /// Next token as string, `None` at the end of the file
+    fn token(&mut self) -> Result<Option<String>, VtkError> {
+        if !self.next_token()? {
+            return Ok(None);
+        }
+        Ok(Some(String::from_utf8_lossy(&self.token).into_owned()))
+    }
+
+    /// Rest of the current line
+    fn rest_of_line(&mut self) -> Result<String, VtkError> {
+        if self.at_line_start {
+            return Ok(String::new());
+        }
+        self.line()
+    }
+
+    /// Next full line, without the line break
+    fn line(&mut self) -> Result<String, VtkError> {
+        let mut line = Vec::new();
+        let read = self
+            .reader
+            .read_until(b'\n', &mut line)
+            .map_err(VtkError::IoError)?;
+        self.position += read as u64;
+        self.at_line_start = true;
+        Ok(String::from_utf8_lossy(&line).trim().to_string())
+    }
+
+    /// Read the four header lines: version, title, format and dataset type
+    fn read_header(&mut self) -> Result<Header, VtkError> {
+        let first = self.line()?;
+        let version = first
+            .strip_prefix("# vtk DataFile Version")
+            .ok_or(VtkError::InvalidFormat("Not a legacy VTK file"))?
+            .trim()
+            .parse()
+            .unwrap_or(3.0);
+        let _title = self.line()?;
+        self.binary = match self.line()?.to_ascii_uppercase().as_str() {
+            "ASCII" => false,
+            "BINARY" => true,
+            _ => return Err(VtkError::InvalidFormat("Unknown legacy VTK file format")),
+        };
+        let dataset = self.line()?;
+        let dataset = dataset
+            .split_whitespace()
+            .nth(1)
+            .ok_or(VtkError::InvalidFormat("Missing legacy VTK dataset type"))?
+            .to_ascii_uppercase();
+        Ok(Header {
+            version,
+            binary: self.binary,
+            dataset,
+        })
+    }
+
+    /// Read `count` values of a section
+    fn values<T>(
+        &mut self,
+        count: usize,
+        data_type: DataType,
+        convert: impl Fn(f64) -> T,
+    ) -> Result<Vec<T>, VtkError> {
+        let mut values = Vec::with_capacity(count);
+        self.read_into(count, data_type, convert, &mut values)?;
+        Ok(values)
+    }
+
+    /// Append `count` values of a section to `values`
+    ///
+    /// Binary values are decoded from the buffer of the reader in place; for a
+    /// memory-mapped file that is the mapped file itself.
+    fn read_into<T>(
+        &mut self,
+        count: usize,
+        data_type: DataType,
+        convert: impl Fn(f64) -> T,
+        values: &mut Vec<T>,
+    ) -> Result<(), VtkError> {
+        values.reserve(count);
+        if self.binary {
+            let size = data_type.size();
+            let mut remaining = count;
+            // Bytes of a value split across two buffer fills
+            let mut partial = Vec::with_capacity(size);
+            while remaining > 0 {
+                let buffer = self.reader.fill_buf().map_err(VtkError::IoError)?;
+                if buffer.is_empty() {
+                    return Err(VtkError::MissingData("Legacy VTK section ends early"));
+                }
+                let mut used = 0;
+                if !partial.is_empty() {
+                    used = (size - partial.len()).min(buffer.len());
+                    partial.extend_from_slice(&buffer[..used]);
+                    if partial.len() == size {
+                        values.push(convert(data_type.decode(&partial)));
+                        partial.clear();
+                        remaining -= 1;
+                    }
+                }
+                let whole = ((buffer.len() - used) / size).min(remaining);
+                values.extend(
+                    buffer[used..used + whole * size]
+                        .chunks_exact(size)
+                        .map(|value| convert(data_type.decode(value))),
+                );
+                used += whole * size;
+                remaining -= whole;
+                if remaining > 0 {
+                    partial.extend_from_slice(&buffer[used..]);
+                    used = buffer.len();
+                }
+                self.reader.consume(used);
+                self.position += used as u64;
+            }
+            self.at_line_start = false;
+            return Ok(());
+        }
+
+        for _ in 0..count {
+            if !self.next_token()? {
+                return Err(VtkError::MissingData("Legacy VTK section ends early"));
+            }
+            let value: f64 = std::str::from_utf8(&self.token)
+                .ok()
+                .and_then(|token| token.parse().ok())
+                .ok_or(VtkError::InvalidFormat("Invalid number in legacy VTK file"))?;
+            values.push(convert(value));
+        }
+        Ok(())
+    }
+
+    /// Read `count` values in chunks, reporting progress after each chunk
+    fn values_chunked<T>(
+        &mut self,
+        count: usize,
+        data_type: DataType,
+        convert: impl Fn(f64) -> T + Copy,
+        progress: &mut Progress,
+    ) -> Result<Vec<T>, VtkError> {
+        let mut values = Vec::with_capacity(count);
+        while values.len() < count {
+            let chunk = (count - values.len()).min(VALUE_CHUNK);
+            self.read_into(chunk, data_type, convert, &mut values)?;
+            progress.report(self.fraction())?;
+        }
+        Ok(values)
+    }
+
+    /// Read `count` points in chunks, reporting progress after each chunk
+    fn points_chunked(
+        &mut self,
+        count: usize,
+        data_type: DataType,
+        progress: &mut Progress,
+    ) -> Result<Vec<[f32; 3]>, VtkError> {
+        let mut points = Vec::with_capacity(count);
+        let mut coordinates = Vec::new();
+        while points.len() < count {
+            let chunk = (count - points.len()).min(VALUE_CHUNK / 3);
+            coordinates.clear();
+            self.read_into(chunk * 3, data_type, |v| v as f32, &mut coordinates)?;
+            points.extend(coordinates.chunks_exact(3).map(|p| [p[0], p[1], p[2]]));
+            progress.report(self.fraction())?;
+        }
+        Ok(points)
+    }
+}
+
+/// Progress reporting and cancellation of the loading thread
+struct Progress<'a> {
+    sender: &'a Sender<StreamMessage>,
+    cancel: &'a AtomicBool,
+    phase: &'static str,
+}
+
+impl Progress<'_> {
+    fn report(&self, fraction: f32) -> Result<(), VtkError> {
+        if self.cancel.load(Ordering::Relaxed) {
+            return Err(VtkError::GenericError("Loading cancelled".to_string()));
+        }
+        let _ = self.sender.send(StreamMessage::Progress {
+            fraction,
+            phase: self.phase,
+        });
+        Ok(())
+    }
+}
+
+/// VTK cell type of a legacy cell type id; unsupported types have no triangles
+fn cell_type(id: u64) -> model::CellType {
+    use model::CellType::*;
+    match id {
+        1 => Vertex,
+        3 => Line,
+        4 => PolyLine,
+        5 => Triangle,
+        6 => TriangleStrip,
+        7 => Polygon,
+        8 => Pixel,
+        9 => Quad,
+        10 => Tetra,
+        11 => Voxel,
+        12 => Hexahedron,
+        13 => Wedge,
+        14 => Pyramid,
+        21 => QuadraticEdge,
+        22 => QuadraticTriangle,
+        23 => QuadraticQuad,
+        24 => QuadraticTetra,
+        25 => QuadraticHexahedron,
+        26 => QuadraticWedge,
+        27 => QuadraticPyramid,
+        _ => PolyVertex,
+    }
+}
+
+/// Cells of a section as end offsets and connectivity
+struct CellArrays {
+    offsets: Vec<u64>,
+    connectivity: Vec<u64>,
+}
+
+impl CellArrays {
+    fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Polylines of the cells accepted by `is_line`
+    ///
+    /// # Parameters
+    /// - `first_cell`: Cell index of the first cell of the section
+    /// - `is_line`: Whether the cell at a section index is a line
+    fn polylines(&self, first_cell: usize, is_line: impl Fn(usize) -> bool) -> Vec<PolyLine> {
+        let mut begin = 0;
+        let mut lines = Vec::new();
+        for (cell, &end) in self.offsets.iter().enumerate() {
+            let range = begin as usize..(end as usize).min(self.connectivity.len());
+            begin = end;
+            if range.len() < 2 || !is_line(cell) {
+                continue;
+            }
+            lines.push(PolyLine {
+                cell: first_cell + cell,
+                points: self.connectivity[range]
+                    .iter()
+                    .map(|&point| point as u32)
+                    .collect(),
+            });
+        }
+        lines
+    }
+}
+
+/// How the cells of a section are triangulated
+enum CellKind<'a> {
+    /// Cells of an unstructured grid, one type per cell
+    Typed(&'a [model::CellType]),
+    /// PolyData polygons
+    Polygons,
+    /// PolyData triangle strips
+    Strips,
+    /// PolyData vertices and lines, counted as cells without surface
+    NoSurface,
+}
+
+/// Triangles collected from the triangulated chunks
+#[derive(Default)]
+struct Triangles {
+    indices: Vec<u32>,
+    triangle_to_cell: Vec<usize>,
+    quadratic_triangles: Vec<super::QuadraticTriangle>,
+    quadratic_edges: Vec<super::QuadraticEdge>,
+    /// Cells triangulated so far
+    cell_count: usize,
+}
+
+impl Triangles {
+    /// Triangulate cells in chunks and send a preview of each chunk
+    ///
+    /// # Parameters
+    /// - `cells`: Cell arrays of a section
+    /// - `kind`: Triangulation of the cells
+    /// - `points`: All points, for the preview vertices
+    /// - `fraction`: Fraction of the file read, reported after each chunk
+    fn add_cells(
+        &mut self,
+        cells: &CellArrays,
+        kind: CellKind,
+        points: &[[f32; 3]],
+        progress: &mut Progress,
+        fraction: f32,
+    ) -> Result<(), VtkError> {
+        if matches!(kind, CellKind::NoSurface) {
+            self.cell_count += cells.len();
+            return Ok(());
+        }
+
+        let mut start = 0;
+        while start < cells.len() {
+            let end = (start + CELL_CHUNK).min(cells.len());
+            let first = if start == 0 {
+                0
+            } else {
+                cells.offsets[start - 1]
+            };
+            let last = cells.offsets[end - 1];
+            if last < first || last as usize > cells.connectivity.len() {
+                return Err(VtkError::InvalidFormat("Invalid legacy VTK cell offsets"));
+            }
+            let cell_verts = VertexNumbers::XML {
+                connectivity: cells.connectivity[first as usize..last as usize].to_vec(),
+                offsets: cells.offsets[start..end]
+                    .iter()
+                    .map(|offset| offset - first)
+                    .collect(),
+            };
+            let (indices, mapping, quadratic_triangles, quadratic_edges) = match kind {
+                CellKind::Typed(types) => triangulate_cells(model::Cells {
+                    cell_verts,
+                    types: types[start..end].to_vec(),
+                }),
+                CellKind::Polygons => {
+                    // The polygon triangulation reads the legacy layout
+                    let (connectivity, offsets) = cell_verts.into_xml();
+                    let mut vertices = Vec::with_capacity(connectivity.len() + offsets.len());
+                    let mut begin = 0;
+                    for &end in &offsets {
+                        vertices.push((end - begin) as u32);
+                        vertices.extend(
+                            connectivity[begin as usize..end as usize]
+                                .iter()
+                                .map(|&index| index as u32),
+                        );
+                        begin = end;
+                    }
+                    let (indices, mapping) = triangulate_polygon(VertexNumbers::Legacy {
+                        num_cells: offsets.len() as u32,
+                        vertices,
+                    });
+                    (indices, mapping, Vec::new(), Vec::new())
+                }
+                _ => {
+                    let (indices, mapping) = triangulate_strips(cell_verts);
+                    (indices, mapping, Vec::new(), Vec::new())
+                }
+            };
+            if let Some(&index) = indices
+                .iter()
+                .find(|&&index| index as usize >= points.len())
+            {
+                return Err(VtkError::IndexOutOfBounds {
+                    index: index as usize,
+                    max: points.len(),
+                });
+            }
+
+            send_preview(&indices, points, progress.sender);
+            self.indices.extend(&indices);
+            self.triangle_to_cell
+                .extend(mapping.iter().map(|cell| cell + self.cell_count + start));
+            self.quadratic_triangles.extend(quadratic_triangles);
+            self.quadratic_edges.extend(quadratic_edges);
+
+            start = end;
+            progress.report(fraction)?;
+        }
+        self.cell_count += cells.len();
+        Ok(())
+    }
+}
+
+/// Send the triangles of a chunk with their own compacted vertices
+fn send_preview(indices: &[u32], points: &[[f32; 3]], sender: &Sender<StreamMessage>) {
+    if indices.is_empty() {
+        return;
+    }
+    let mut remap: HashMap<u32, u32> = HashMap::new();
+    let mut positions = Vec::new();
+    let indices = indices
+        .iter()
+        .map(|&index| {
+            *remap.entry(index).or_insert_with(|| {
+                positions.push(points[index as usize]);
+                positions.len() as u32 - 1
+            })
+        })
+        .collect();
+    let _ = sender.send(StreamMessage::Preview(PreviewChunk { positions, indices }));
+}
+
+/// Read the cells of a section in the layout of the file version
+///
+/// # Parameters
+/// - `counts`: Rest of the section line (`n size`, or `offsets connectivity` for
+///   version 5 files)
+fn read_cell_arrays<R: BufRead>(
+    reader: &mut LegacyReader<R>,
+    version: f32,
+    counts: &str,
+    progress: &mut Progress,
+) -> Result<CellArrays, VtkError> {
+    let mut counts = counts
+        .split_whitespace()
+        .map(|count| count.parse::<usize>());
+    let (Some(Ok(first)), Some(Ok(second))) = (counts.next(), counts.next()) else {
+        return Err(VtkError::InvalidFormat("Invalid legacy VTK cell counts"));
+    };
+
+    if version >= 5.0 {
+        // OFFSETS <type> with `first` values, CONNECTIVITY <type> with `second` values
+        let mut offsets = Vec::new();
+        let mut connectivity = Vec::new();
+        for _ in 0..2 {
+            let name = reader
+                .token()?
+                .ok_or(VtkError::MissingData("Missing legacy VTK cell arrays"))?
+                .to_ascii_uppercase();
+            let data_type = DataType::parse(&reader.rest_of_line()?)?;
+            match name.as_str() {
+                "OFFSETS" => {
+                    offsets = reader.values_chunked(first, data_type, |v| v as u64, progress)?;
+                }
+                "CONNECTIVITY" => {
+                    connectivity =
+                        reader.values_chunked(second, data_type, |v| v as u64, progress)?;
+                }
+                _ => return Err(VtkError::InvalidFormat("Expected OFFSETS or CONNECTIVITY")),
+            }
+        }
+        // Offsets start with 0; the remaining values are the end offsets
+        if !offsets.is_empty() {
+            offsets.remove(0);
+        }
+        return Ok(CellArrays {
+            offsets,
+            connectivity,
+        });
+    }
+
+    // Classic layout: per cell the vertex count followed by the vertex ids
+    let data = reader.values_chunked(second, DataType::I32, |v| v as u64, progress)?;
+    let mut offsets = Vec::with_capacity(first);
+    let mut connectivity = Vec::with_capacity(second.saturating_sub(first));
+    let mut position = 0;
+    for _ in 0..first {
+        let count = *data
+            .get(position)
+            .ok_or(VtkError::MissingData("Legacy VTK cell list ends early"))?
+            as usize;
+        let vertices = data
+            .get(position + 1..position + 1 + count)
+            .ok_or(VtkError::MissingData("Legacy VTK cell list ends early"))?;
+        connectivity.extend_from_slice(vertices);
+        offsets.push(connectivity.len() as u64);
+        position += count + 1;
+    }
+    Ok(CellArrays {
+        offsets,
+        connectivity,
+    })
+}
+
+/// Read a legacy VTK file, sending progress and previews
+///
+/// # Parameters
+/// - `memory_map`: Memory-map the file if it is binary
+///
+/// # Returns
+/// - `Ok(GeometryData)`: Triangulated cells with point and cell data
+/// - `Err(VtkError)`: The file is malformed, unsupported or loading was cancelled
+fn read_streaming(
+    path: &Path,
+    memory_map: bool,
+    sender: &Sender<StreamMessage>,
+    cancel: &AtomicBool,
+) -> Result<GeometryData, VtkError> {
+    let binary = read_file_header(path)?.binary;
+    let file = File::open(path).map_err(VtkError::IoError)?;
+    let length = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+    if memory_map && binary {
+        // Safety: the file is only read, and simulation output is not expected to
+        // change while it is loaded
+        let data = unsafe { Mmap::map(&file) }.map_err(VtkError::IoError)?;
+        #[cfg(unix)]
+        let _ = data.advise(memmap2::Advice::Sequential);
+        println!("Memory-mapped {}", path.display());
+        return read_dataset(LegacyReader::new(&data[..], length), sender, cancel);
+    }
+    read_dataset(
+        LegacyReader::new(BufReader::with_capacity(1 << 20, file), length),
+        sender,
+        cancel,
+    )
+}
+
+/// Read the sections of a legacy VTK file
+fn read_dataset<R: BufRead>(
+    mut reader: LegacyReader<R>,
+    sender: &Sender<StreamMessage>,
+    cancel: &AtomicBool,
+) -> Result<GeometryData, VtkError> {
+    let header = reader.read_header()?;
+    let polydata = match header.dataset.as_str() {
+        "UNSTRUCTURED_GRID" => false,
+        "POLYDATA" => true,
+        _ => {
+            return Err(VtkError::InvalidFormat(
+                "Streaming supports UNSTRUCTURED_GRID and POLYDATA datasets",
+            ))
+        }
+    };
+
+    let mut progress = Progress {
+        sender,
+        cancel,
+        phase: "Reading points",
+    };
+    let mut points: Vec<[f32; 3]> = Vec::new();
+    let mut cells: Option<CellArrays> = None;
+    let mut triangles = Triangles::default();
+    let mut volume_cells = None;
+    let mut cell_types = None;
+    let mut lines = Vec::new();
+    let mut attributes = HashMap::new();
+    let mut location: Option<(AttributeLocation, usize)> = None;
+
+    while let Some(section) = reader.token()? {
+        let section = section.to_ascii_uppercase();
+        let line = reader.rest_of_line()?;
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let field = |index: usize| -> Result<&str, VtkError> {
+            fields.get(index).copied().ok_or(VtkError::InvalidFormat(
+                "Incomplete legacy VTK section header",
+            ))
+        };
+        let count = |index: usize| -> Result<usize, VtkError> {
+            field(index)?
+                .parse()
+                .map_err(|_| VtkError::InvalidFormat("Invalid legacy VTK count"))
+        };
+
+        match section.as_str() {
+            "POINTS" => {
+                progress.phase = "Reading points";
+                points =
+                    reader.points_chunked(count(0)?, DataType::parse(field(1)?)?, &mut progress)?;
+            }
+            "CELLS" if !polydata => {
+                progress.phase = "Reading cells";
+                cells = Some(read_cell_arrays(
+                    &mut reader,
+                    header.version,
+                    &line,
+                    &mut progress,
+                )?);
+            }
+            "CELL_TYPES" if !polydata => {
+                progress.phase = "Triangulating cells";
+                let n = count(0)?;
+                let cells = cells
+                    .take()
+                    .ok_or(VtkError::MissingData("CELL_TYPES before CELLS"))?;
+                if cells.len() != n {
+                    return Err(VtkError::AttributeMismatch {
+                        attribute_size: n,
+                        expected_size: cells.len(),
+                    });
+                }
+                let types: Vec<model::CellType> =
+                    reader.values(n, DataType::I32, |v| cell_type(v as u64))?;
+                volume_cells =
+                    VolumeCells::from_arrays(&types, &cells.offsets, &cells.connectivity);
+                cell_types = Some(types.iter().map(|&cell_type| cell_type as u8).collect());
+                lines.extend(cells.polylines(triangles.cell_count, |cell| {
+                    matches!(
+                        types[cell],
+                        model::CellType::Line | model::CellType::PolyLine
+                    )
+                }));
+                triangles.add_cells(
+                    &cells,
+                    CellKind::Typed(&types),
+                    &points,
+                    &mut progress,
+                    reader.fraction(),
+                )?;
+            }
+            "VERTICES" | "LINES" | "POLYGONS" | "TRIANGLE_STRIPS" if polydata => {
+                progress.phase = "Triangulating cells";
+                let section_cells =
+                    read_cell_arrays(&mut reader, header.version, &line, &mut progress)?;
+                // Vertices and lines precede polygons and strips in the cell numbering
+                let kind = match section.as_str() {
+                    "POLYGONS" => CellKind::Polygons,
+                    "TRIANGLE_STRIPS" => CellKind::Strips,
+                    _ => CellKind::NoSurface,
+                };
+                if section == "LINES" {
+                    lines.extend(section_cells.polylines(triangles.cell_count, |_| true));
+                }
+                triangles.add_cells(
+                    &section_cells,
+                    kind,
+                    &points,
+                    &mut progress,
+                    reader.fraction(),
+                )?;
+            }
+            "POINT_DATA" => {
+                progress.phase = "Reading point data";
+                location = Some((AttributeLocation::Point, count(0)?));
+            }
+            "CELL_DATA" => {
+                progress.phase = "Reading cell data";
+                location = Some((AttributeLocation::Cell, count(0)?));
+            }
+            "SCALARS"
+            | "VECTORS"
+            | "NORMALS"
+            | "TENSORS"
+            | "TEXTURE_COORDINATES"
+            | "COLOR_SCALARS"
+            | "LOOKUP_TABLE" => {
+                let (attribute_location, n) = location.clone().ok_or(VtkError::MissingData(
+                    "Attribute outside POINT_DATA or CELL_DATA",
+                ))?;
+                let name = field(0)?;
+                let (elem, values) = match section.as_str() {
+                    "SCALARS" => {
+                        let num_comp = fields.get(2).and_then(|n| n.parse().ok()).unwrap_or(1);
+                        let data_type = DataType::parse(field(1)?)?;
+                        // LOOKUP_TABLE line naming the table
+                        let lookup_table = match reader.token()? {
+                            Some(token) if token.eq_ignore_ascii_case("LOOKUP_TABLE") => {
+                                Some(reader.rest_of_line()?).filter(|name| name != "default")
+                            }
+                            _ => {
+                                return Err(VtkError::InvalidFormat("SCALARS without LOOKUP_TABLE"))
+                            }
+                        };
+                        let values = reader.values_chunked(
+                            n * num_comp as usize,
+                            data_type,
+                            |v| v as f32,
+                            &mut progress,
+                        )?;
+                        (
+                            ElementType::Scalars {
+                                num_comp,
+                                lookup_table,
+                            },
+                            IOBuffer::F32(values),
+                        )
+                    }
+                    "VECTORS" | "NORMALS" => {
+                        let values = reader.values_chunked(
+                            n * 3,
+                            DataType::parse(field(1)?)?,
+                            |v| v as f32,
+                            &mut progress,
+                        )?;
+                        let elem = if section == "VECTORS" {
+                            ElementType::Vectors
+                        } else {
+                            ElementType::Normals
+                        };
+                        (elem, IOBuffer::F32(values))
+                    }
+                    "TENSORS" => {
+                        let values = reader.values_chunked(
+                            n * 9,
+                            DataType::parse(field(1)?)?,
+                            |v| v as f32,
+                            &mut progress,
+                        )?;
+                        (ElementType::Tensors, IOBuffer::F32(values))
+                    }
+                    "TEXTURE_COORDINATES" => {
+                        let dim: u32 = count(1)? as u32;
+                        let values = reader.values_chunked(
+                            n * dim as usize,
+                            DataType::parse(field(2)?)?,
+                            |v| v as f32,
+                            &mut progress,
+                        )?;
+                        (ElementType::TCoords(dim), IOBuffer::F32(values))
+                    }
+                    _ => {
+                        // Colors are unsigned chars in binary files, floats in ASCII files
+                        let (elem, total) = if section == "COLOR_SCALARS" {
+                            let nvalues = count(1)? as u32;
+                            (ElementType::ColorScalars(nvalues), n * nvalues as usize)
+                        } else {
+                            (ElementType::LookupTable, count(1)? * 4)
+                        };
+                        let buffer = if reader.binary {
+                            IOBuffer::U8(reader.values(total, DataType::U8, |v| v as u8)?)
+                        } else {
+                            IOBuffer::F32(reader.values(total, DataType::F32, |v| v as f32)?)
+                        };
+                        (elem, buffer)
+                    }
+                };
+                if let Ok((name, attribute)) =
+                    UnstructuredGridExtractor.process_data_array(name, &elem, &values)
+                {
+                    attributes.insert((name, attribute_location), attribute);
+                }
+            }
+            "FIELD" => {
+                let arrays = count(1)?;
+                for _ in 0..arrays {
+                    let name = reader
+                        .token()?
+                        .ok_or(VtkError::MissingData("Missing legacy VTK field array"))?;
+                    let array_line = reader.rest_of_line()?;
+                    let array_fields: Vec<&str> = array_line.split_whitespace().collect();
+                    let (Some(num_comp), Some(tuples), Some(data_type)) = (
+                        array_fields.first().and_then(|n| n.parse::<usize>().ok()),
+                        array_fields.get(1).and_then(|n| n.parse::<usize>().ok()),
+                        array_fields.get(2),
+                    ) else {
+                        return Err(VtkError::InvalidFormat("Invalid legacy VTK field array"));
+                    };
+                    let values = reader.values_chunked(
+                        num_comp * tuples,
+                        DataType::parse(data_type)?,
+                        |v| v as f32,
+                        &mut progress,
+                    )?;
+                    // Field data of the dataset itself has no location and is skipped
+                    if let Some((attribute_location, _)) = location.clone() {
+                        if let Some(attribute) =
+                            generic_attribute(&IOBuffer::F32(values), num_comp as u32)
+                        {
+                            attributes.insert((name, attribute_location), attribute);
+                        }
+                    }
+                }
+            }
+            "METADATA" => {
+                // Information keys up to the next blank line
+                while !reader.line()?.is_empty() {}
+            }
+            _ => {
+                println!("Unsupported legacy VTK section: {}", section);
+                return Err(VtkError::InvalidFormat("Unsupported legacy VTK section"));
+            }
+        }
+    }
+
+    progress.phase = "Building geometry";
+    progress.report(1.0)?;
+    if cells.is_some() {
+        return Err(VtkError::MissingData("CELLS without CELL_TYPES"));
+    }
+    println!(
+        "Streamed legacy VTK file: {} points, {} cells, {} triangles",
+        points.len(),
+        triangles.cell_count,
+        triangles.indices.len() / 3
+    );
+
+    let ghost_flags = |location: AttributeLocation| -> Option<Vec<u8>> {
+        match attributes.get(&(ghost::GHOST_ARRAY_NAME.to_string(), location))? {
+            AttributeType::Scalar { data, .. } => {
+                Some(data.iter().map(|&value| value as u8).collect())
+            }
+            _ => None,
+        }
+    };
+    let cell_ghosts = ghost_flags(AttributeLocation::Cell);
+    let point_ghosts = ghost_flags(AttributeLocation::Point);
+
+    let mut geometry = GeometryData::new(points, triangles.indices, attributes)
+        .add_triangle_to_cell_mapping(triangles.triangle_to_cell)
+        .add_volume_cells(volume_cells)
+        .add_lines(lines);
+    geometry.cell_types = cell_types;
+    geometry.extract_lookup_tables();
+    if !triangles.quadratic_triangles.is_empty() {
+        geometry = geometry.add_quadratic_triangles(triangles.quadratic_triangles);
+    }
+    if !triangles.quadratic_edges.is_empty() {
+        geometry = geometry.add_quadratic_edges(triangles.quadratic_edges);
+    }
+    Ok(ghost::remove_ghosts(
+        geometry,
+        cell_ghosts.as_deref(),
+        point_ghosts.as_deref(),
+    ))
+}
@@ -0,0 +1,572 @@
+//! Streamlines module
+//!
+//! Streamlines of point vector attributes, traced by fourth order Runge-Kutta
+//! integration:
+//! - Vector fields are sampled inside the volume cells of unstructured grids
+//!   (linear over tetrahedra) or trilinearly inside image data volumes
+//! - Lines are integrated with a fixed arc length step along the field direction
+//!   and end when they leave the domain, reach a stagnation point or the maximum
+//!   step count
+//! - Seed points are placed on a line, a plane square or a sphere
+//!
+//! Lines can be turned into tube geometry with the speed as point scalar.
+use super::topology::attribute_value;
+use super::tubes::{TubeMesh, MIN_SIDES};
+use super::volume::VolumeData;
+use super::{AttributeLocation, AttributeType, GeometryData};
+use bevy::math::{Mat3, Vec3};
+use bevy::utils::HashMap;
+
+/// Speed below which a line ends at a stagnation point
+const MIN_SPEED: f32 = 1e-12;
+/// Tolerance of the point in tetrahedron test (barycentric coordinates)
+const BARYCENTRIC_TOLERANCE: f32 = 1e-5;
+/// Largest number of bins of the cell lookup grid along one axis
+const MAX_BINS_PER_AXIS: usize = 128;
+
+/// Integration direction relative to the vector field
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TraceDirection {
+    Forward,
+    Backward,
+    #[default]
+    Both,
+}
+
+/// Integration parameters
+#[derive(Clone, Copy, Debug)]
+pub struct TraceSettings {
+    /// Arc length of one integration step
+    pub step: f32,
+    /// Largest number of steps per direction
+    pub max_steps: usize,
+    pub direction: TraceDirection,
+}
+
+/// Traced streamline
+#[derive(Clone, Debug, Default)]
+pub struct Streamline {
+    pub points: Vec<Vec3>,
+    /// Field magnitude at every point
+    pub speeds: Vec<f32>,
+}
+
+/// Tetrahedra of the volume cells, binned on a regular grid for point location
+pub struct CellLocator {
+    tetrahedra: Vec<[u32; 4]>,
+    /// Inverse edge matrix of every tetrahedron, `None` if degenerate
+    inverses: Vec<Option<Mat3>>,
+    bins: Vec<Vec<u32>>,
+    bin_counts: [usize; 3],
+    min: Vec3,
+    bin_size: Vec3,
+}
+
+impl CellLocator {
+    /// Bin the tetrahedra of a point set, `None` without tetrahedra
+    pub fn new(positions: &[Vec3], tetrahedra: Vec<[u32; 4]>) -> Option<Self> {
+        let (min, max) = bounds(tetrahedra.iter().flatten().map(|&p| positions[p as usize]))?;
+        let per_axis =
+            ((tetrahedra.len() as f32).cbrt().ceil() as usize).clamp(1, MAX_BINS_PER_AXIS);
+        let extent = (max - min).max(Vec3::splat(f32::EPSILON));
+        let bin_counts = [per_axis; 3];
+        let bin_size = extent / per_axis as f32;
+        let mut locator = Self {
+            inverses: Vec::with_capacity(tetrahedra.len()),
+            bins: vec![Vec::new(); per_axis * per_axis * per_axis],
+            tetrahedra: Vec::new(),
+            bin_counts,
+            min,
+            bin_size,
+        };
+
+        for (index, tetrahedron) in tetrahedra.iter().enumerate() {
+            let [a, b, c, d] = tetrahedron.map(|p| positions[p as usize]);
+            let edges = Mat3::from_cols(b - a, c - a, d - a);
+            locator.inverses.push(
+                (edges.determinant().abs() > f32::EPSILON * f32::EPSILON).then(|| edges.inverse()),
+            );
+            let (low, high) = bounds([a, b, c, d].into_iter())?;
+            let (low, high) = (locator.bin_of(low), locator.bin_of(high));
+            for z in low[2]..=high[2] {
+                for y in low[1]..=high[1] {
+                    for x in low[0]..=high[0] {
+                        let bin = locator.bin_index([x, y, z]);
+                        locator.bins[bin].push(index as u32);
+                    }
+                }
+            }
+        }
+        locator.tetrahedra = tetrahedra;
+        Some(locator)
+    }
+
+    fn bin_of(&self, point: Vec3) -> [usize; 3] {
+        let cell = (point - self.min) / self.bin_size;
+        std::array::from_fn(|axis| (cell[axis].max(0.0) as usize).min(self.bin_counts[axis] - 1))
+    }
+
+    fn bin_index(&self, bin: [usize; 3]) -> usize {
+        bin[0] + self.bin_counts[0] * (bin[1] + self.bin_counts[1] * bin[2])
+    }
+
+    /// Barycentric weights of a point in a tetrahedron, if it lies inside
+    fn weights(&self, tetrahedron: usize, point: Vec3, positions: &[Vec3]) -> Option<[f32; 4]> {
+        let inverse = self.inverses[tetrahedron]?;
+        let origin = positions[self.tetrahedra[tetrahedron][0] as usize];
+        let [u, v, w] = (inverse * (point - origin)).to_array();
+        let weights = [1.0 - u - v - w, u, v, w];
+        weights
+            .iter()
+            .all(|&weight| weight >= -BARYCENTRIC_TOLERANCE)
+            .then_some(weights)
+    }
+
+    /// Tetrahedron containing a point with its barycentric weights
+    ///
+    /// # Parameters
+    /// - `hint`: Tetrahedron of the previous sample, checked first
+    pub fn locate(
+        &self,
+        point: Vec3,
+        positions: &[Vec3],
+        hint: Option<usize>,
+    ) -> Option<(usize, [f32; 4])> {
+        if let Some(weights) =
+            hint.and_then(|tetrahedron| self.weights(tetrahedron, point, positions))
+        {
+            return hint.map(|tetrahedron| (tetrahedron, weights));
+        }
+        let outside = (point - self.min).cmplt(Vec3::ZERO).any()
+            || (point - self.min)
+                .cmpgt(self.bin_size * Vec3::from(self.bin_counts.map(|n| n as f32)))
+                .any();
+        if outside {
+            return None;
+        }
+        self.bins[self.bin_index(self.bin_of(point))]
+            .iter()
+            .find_map(|&tetrahedron| {
+                let tetrahedron = tetrahedron as usize;
+                self.weights(tetrahedron, point, positions)
+                    .map(|weights| (tetrahedron, weights))
+            })
+    }
+}
+
+/// Point vector field that can be sampled anywhere inside its domain
+pub enum VectorField {
+    /// Field on the volume cells of an unstructured grid
+    Cells {
+        positions: Vec<Vec3>,
+        vectors: Vec<Vec3>,
+        locator: CellLocator,
+        cell_size: f32,
+    },
+    /// Field on an image data volume
+    Volume {
+        dims: [usize; 3],
+        origin: Vec3,
+        spacing: Vec3,
+        vectors: Vec<Vec3>,
+    },
+}
+
+/// Bounding box of points
+fn bounds(points: impl Iterator<Item = Vec3>) -> Option<(Vec3, Vec3)> {
+    points.fold(None, |bounds, point| match bounds {
+        None => Some((point, point)),
+        Some((min, max)) => Some((min.min(point), max.max(point))),
+    })
+}
+
+/// Vectors of a point vector attribute
+fn point_vectors(attribute: &AttributeType, count: usize) -> Option<Vec<Vec3>> {
+    match attribute {
+        AttributeType::Vector(data) if data.len() >= count => {
+            Some(data[..count].iter().map(|&v| Vec3::from(v)).collect())
+        }
+        _ => None,
+    }
+}
+
+/// Names of the point vector attributes, sorted
+///
+/// # Parameters
+/// - `attributes`: Attributes of a geometry or volume
+pub fn vector_attribute_names(
+    attributes: &HashMap<(String, AttributeLocation), AttributeType>,
+) -> Vec<String> {
+    let mut names: Vec<String> = attributes
+        .iter()
+        .filter(|((_, location), attribute)| {
+            *location == AttributeLocation::Point && matches!(attribute, AttributeType::Vector(_))
+        })
+        .map(|((name, _), _)| name.clone())
+        .collect();
+    names.sort();
+    names
+}
+
+impl VectorField {
+    /// Vector field on the volume cells of a geometry
+    ///
+    /// # Returns
+    /// - Field, `None` without volume cells or point vector attribute
+    pub fn from_cells(geometry: &GeometryData, attribute: &str) -> Option<Self> {
+        let cells = geometry.volume_cells.as_ref()?;
+        let positions: Vec<Vec3> = geometry.vertices.iter().map(|&v| Vec3::from(v)).collect();
+        let vectors = point_vectors(
+            geometry.get_attributes(attribute, AttributeLocation::Point)?,
+            positions.len(),
+        )?;
+
+        let mut tetrahedra = Vec::new();
+        for index in 0..cells.len() {
+            let (shape, points) = cells.cell(index);
+            if points
+                .iter()
+                .any(|&point| point as usize >= positions.len())
+            {
+                continue;
+            }
+            tetrahedra.extend(
+                shape
+                    .tetrahedra()
+                    .iter()
+                    .map(|tetrahedron| tetrahedron.map(|corner| points[corner])),
+            );
+        }
+        let edge_sum: f32 = tetrahedra
+            .iter()
+            .map(|[a, b, ..]| positions[*a as usize].distance(positions[*b as usize]))
+            .sum();
+        let cell_size = edge_sum / tetrahedra.len().max(1) as f32;
+        let locator = CellLocator::new(&positions, tetrahedra)?;
+
+        Some(VectorField::Cells {
+            positions,
+            vectors,
+            locator,
+            cell_size,
+        })
+    }
+
+    /// Vector field on an image data volume
+    ///
+    /// # Returns
+    /// - Field, `None` without point vector attribute
+    pub fn from_volume(volume: &VolumeData, attribute: &str) -> Option<Self> {
+        let vectors = point_vectors(
+            volume
+                .attributes
+                .get(&(attribute.to_string(), AttributeLocation::Point))?,
+            volume.point_count(),
+        )?;
+        Some(VectorField::Volume {
+            dims: volume.dims,
+            origin: Vec3::from(volume.origin),
+            spacing: Vec3::from(volume.spacing),
+            vectors,
+        })
+    }
+
+    /// Typical cell edge length, the unit of the integration step
+    pub fn cell_size(&self) -> f32 {
+        match self {
+            VectorField::Cells { cell_size, .. } => *cell_size,
+            VectorField::Volume { dims, spacing, .. } => {
+                let size = (0..3)
+                    .filter(|&axis| dims[axis] > 1)
+                    .map(|axis| spacing[axis].abs())
+                    .fold(f32::MAX, f32::min);
+                if size < f32::MAX {
+                    size
+                } else {
+                    1.0
+                }
+            }
+        }
+    }
+
+    /// Smallest and largest vector magnitude
+    pub fn magnitude_range(&self) -> Option<(f32, f32)> {
+        self.vectors()
+            .iter()
+            .map(|vector| vector.length())
+            .filter(|speed| speed.is_finite())
+            .fold(None, |range, speed| match range {
+                None => Some((speed, speed)),
+                Some((min, max)) => Some((min.min(speed), max.max(speed))),
+            })
+    }
+
+    /// Point vectors of the field
+    pub fn vectors(&self) -> &[Vec3] {
+        match self {
+            VectorField::Cells { vectors, .. } | VectorField::Volume { vectors, .. } => vectors,
+        }
+    }
+
+    /// Field vector at a point
+    ///
+    /// # Parameters
+    /// - `hint`: Cell of the previous sample, updated to the cell of this one
+    ///
+    /// # Returns
+    /// - Interpolated vector, `None` outside the domain
+    pub fn sample(&self, point: Vec3, hint: &mut Option<usize>) -> Option<Vec3> {
+        self.sample_vectors(point, hint, self.vectors())
+    }
+
+    /// Vector at a point interpolated from other point vectors on the same
+    /// points, such as another time step of the field
+    ///
+    /// # Parameters
+    /// - `hint`: Cell of the previous sample, updated to the cell of this one
+    /// - `vectors`: One vector per point of the field
+    ///
+    /// # Returns
+    /// - Interpolated vector, `None` outside the domain
+    pub fn sample_vectors(
+        &self,
+        point: Vec3,
+        hint: &mut Option<usize>,
+        vectors: &[Vec3],
+    ) -> Option<Vec3> {
+        match self {
+            VectorField::Cells {
+                positions, locator, ..
+            } => {
+                let (tetrahedron, weights) = locator.locate(point, positions, *hint)?;
+                *hint = Some(tetrahedron);
+                Some(
+                    locator.tetrahedra[tetrahedron]
+                        .iter()
+                        .zip(weights)
+                        .map(|(&corner, weight)| vectors[corner as usize] * weight)
+                        .sum(),
+                )
+            }
+            VectorField::Volume {
+                dims,
+                origin,
+                spacing,
+                ..
+            } => {
+                // Grid cell and position within it along every axis
+                let mut base = [0usize; 3];
+                let mut fraction = [0.0f32; 3];
+                for axis in 0..3 {
+                    if dims[axis] < 2 {
+                        continue;
+                    }
+                    let position = (point[axis] - origin[axis]) / spacing[axis];
+                    let last = (dims[axis] - 1) as f32;
+                    if !(-BARYCENTRIC_TOLERANCE..=last + BARYCENTRIC_TOLERANCE).contains(&position)
+                    {
+                        return None;
+                    }
+                    let position = position.clamp(0.0, last);
+                    base[axis] = (position.floor() as usize).min(dims[axis] - 2);
+                    fraction[axis] = position - base[axis] as f32;
+                }
+                let mut vector = Vec3::ZERO;
+                for corner in 0..8 {
+                    let mut weight = 1.0;
+                    let mut index = [0usize; 3];
+                    for axis in 0..3 {
+                        let upper = corner >> axis & 1 == 1;
+                        if upper && dims[axis] < 2 {
+                            weight = 0.0;
+                            break;
+                        }
+                        index[axis] = base[axis] + upper as usize;
+                        weight *= if upper {
+                            fraction[axis]
+                        } else {
+                            1.0 - fraction[axis]
+                        };
+                    }
+                    if weight > 0.0 {
+                        let point = index[0] + dims[0] * (index[1] + dims[1] * index[2]);
+                        vector += vectors[point] * weight;
+                    }
+                }
+                Some(vector)
+            }
+        }
+    }
+
+    /// Trace one direction from a seed
+    ///
+    /// # Parameters
+    /// - `sign`: 1 to follow the field, -1 to go against it
+    fn trace_direction(&self, seed: Vec3, settings: &TraceSettings, sign: f32) -> Streamline {
+        let mut line = Streamline::default();
+        let mut hint = None;
+        let Some(vector) = self.sample(seed, &mut hint) else {
+            return line;
+        };
+        line.points.push(seed);
+        line.speeds.push(vector.length());
+
+        let h = settings.step * sign;
+        let mut point = seed;
+        for _ in 0..settings.max_steps {
+            // Unit direction, so that every step has the same arc length
+            let mut direction = |at: Vec3| {
+                let vector = self.sample(at, &mut hint)?;
+                (vector.length() > MIN_SPEED).then(|| vector.normalize())
+            };
+            let Some(k1) = direction(point) else {
+                break;
+            };
+            let Some(k2) = direction(point + k1 * h * 0.5) else {
+                break;
+            };
+            let Some(k3) = direction(point + k2 * h * 0.5) else {
+                break;
+            };
+            let Some(k4) = direction(point + k3 * h) else {
+                break;
+            };
+            let next = point + (k1 + k2 * 2.0 + k3 * 2.0 + k4) * (h / 6.0);
+            let Some(vector) = self.sample(next, &mut hint) else {
+                break;
+            };
+            point = next;
+            line.points.push(point);
+            line.speeds.push(vector.length());
+        }
+        line
+    }
+
+    /// Trace a streamline through a seed point
+    ///
+    /// # Returns
+    /// - Streamline ordered along the field, empty if the seed lies outside
+    pub fn trace(&self, seed: Vec3, settings: &TraceSettings) -> Streamline {
+        let forward = || self.trace_direction(seed, settings, 1.0);
+        let backward = || self.trace_direction(seed, settings, -1.0);
+        match settings.direction {
+            TraceDirection::Forward => forward(),
+            TraceDirection::Backward => {
+                let mut line = backward();
+                line.points.reverse();
+                line.speeds.reverse();
+                line
+            }
+            TraceDirection::Both => {
+                let mut line = backward();
+                line.points.reverse();
+                line.speeds.reverse();
+                let forward = forward();
+                // Both halves start at the seed
+                line.points.extend(forward.points.iter().skip(1));
+                line.speeds.extend(forward.speeds.iter().skip(1));
+                line
+            }
+        }
+    }
+}
+
+/// Trace streamlines from seed points
+///
+/// # Returns
+/// - Streamlines with at least two points
+pub fn trace_streamlines(
+    field: &VectorField,
+    seeds: &[Vec3],
+    settings: &TraceSettings,
+) -> Vec<Streamline> {
+    seeds
+        .iter()
+        .map(|&seed| field.trace(seed, settings))
+        .filter(|line| line.points.len() >= 2)
+        .collect()
+}
+
+/// Seeds evenly spaced on a line segment
+pub fn line_seeds(start: Vec3, end: Vec3, count: usize) -> Vec<Vec3> {
+    match count {
+        0 => Vec::new(),
+        1 => vec![start.lerp(end, 0.5)],
+        _ => (0..count)
+            .map(|i| start.lerp(end, i as f32 / (count - 1) as f32))
+            .collect(),
+    }
+}
+
+/// Seeds on a regular grid in a square of a plane
+///
+/// # Parameters
+/// - `half_size`: Half edge length of the square
+/// - `resolution`: Seeds along each edge
+pub fn plane_seeds(origin: Vec3, normal: Vec3, half_size: f32, resolution: usize) -> Vec<Vec3> {
+    let (u, v) = normal.normalize_or_zero().any_orthonormal_pair();
+    let offsets = line_seeds(Vec3::splat(-half_size), Vec3::splat(half_size), resolution);
+    offsets
+        .iter()
+        .flat_map(|a| offsets.iter().map(move |b| origin + u * a.x + v * b.x))
+        .collect()
+}
+
+/// Seeds evenly spread over a sphere (Fibonacci lattice)
+pub fn sphere_seeds(center: Vec3, radius: f32, count: usize) -> Vec<Vec3> {
+    let golden_angle = std::f32::consts::PI * (3.0 - 5.0f32.sqrt());
+    (0..count)
+        .map(|i| {
+            let z = 1.0 - 2.0 * (i as f32 + 0.5) / count as f32;
+            let ring = (1.0 - z * z).max(0.0).sqrt();
+            let angle = golden_angle * i as f32;
+            center + Vec3::new(ring * angle.cos(), ring * angle.sin(), z) * radius
+        })
+        .collect()
+}
+
+/// Tube geometry around streamlines
+///
+/// Rings are oriented by parallel transport along each line, so tubes do not
+/// twist. The speed is stored as point scalar `speed`.
+///
+/// # Parameters
+/// - `lines`: Streamlines
+/// - `radius`: Tube radius
+/// - `sides`: Number of sides of the tube cross section
+pub fn tube_geometry(lines: &[Streamline], radius: f32, sides: usize) -> GeometryData {
+    let sides = sides.max(MIN_SIDES);
+    let mut mesh = TubeMesh::default();
+    let mut speeds = Vec::new();
+
+    for line in lines {
+        let rings = mesh.add_polyline(&line.points, radius, sides);
+        for &speed in line.speeds.iter().take(rings) {
+            speeds.extend(std::iter::repeat_n(speed, sides));
+        }
+    }
+
+    let mut attributes = HashMap::new();
+    attributes.insert(
+        ("speed".to_string(), AttributeLocation::Point),
+        AttributeType::Scalar {
+            num_comp: 1,
+            table_name: "default".to_string(),
+            data: speeds,
+            lookup_table: None,
+        },
+    );
+    GeometryData::new(mesh.vertices, mesh.indices, attributes).add_normals(mesh.normals)
+}
+
+/// Speed of every tube vertex, for color mapping
+pub fn tube_speeds(geometry: &GeometryData) -> Vec<f32> {
+    geometry
+        .get_attributes("speed", AttributeLocation::Point)
+        .map(|attribute| {
+            (0..geometry.vertices.len())
+                .map(|point| attribute_value(attribute, point, None))
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,168 @@
+//! Temporal statistics module
+//!
+//! Statistics of a point scalar field over the frames of a time series:
+//! - Mean, minimum, maximum and standard deviation of every point over all frames
+//! - Global range over all points and frames, so every frame can be colored on
+//!   the same scale
+//!
+//! Non-finite values, such as the fill values of netCDF files, are skipped.
+//! Points without any finite value get NaN statistics.
+use super::{AttributeLocation, AttributeType, GeometryData, VtkError};
+
+/// Per-point statistics of a scalar field over time
+#[derive(Clone, Debug)]
+pub struct TemporalStatistics {
+    /// Mean of every point
+    pub mean: Vec<f32>,
+    /// Minimum of every point
+    pub min: Vec<f32>,
+    /// Maximum of every point
+    pub max: Vec<f32>,
+    /// Population standard deviation of every point
+    pub stddev: Vec<f32>,
+    /// Minimum and maximum over all points and frames, `None` without finite values
+    pub range: Option<(f32, f32)>,
+    /// Number of frames
+    pub frames: usize,
+}
+
+impl TemporalStatistics {
+    /// Statistics with their attribute name suffixes
+    pub fn fields(&self) -> [(&'static str, &[f32]); 4] {
+        [
+            ("mean", &self.mean),
+            ("min", &self.min),
+            ("max", &self.max),
+            ("stddev", &self.stddev),
+        ]
+    }
+}
+
+/// Compute the statistics of every point over all frames
+///
+/// # Parameters
+/// - `frames`: Point scalars of every frame
+///
+/// # Returns
+/// - `Ok(TemporalStatistics)`: Statistics of the frames
+/// - `Err(VtkError)`: There are no frames or the frames differ in length
+pub fn temporal_statistics<'a>(
+    frames: impl IntoIterator<Item = &'a [f32]>,
+) -> Result<TemporalStatistics, VtkError> {
+    let mut frames = frames.into_iter().peekable();
+    let point_count = frames
+        .peek()
+        .map(|frame| frame.len())
+        .ok_or(VtkError::MissingData("time steps"))?;
+
+    // Sums in double precision, long series of large values lose digits in f32
+    let mut count = vec![0u32; point_count];
+    let mut sum = vec![0.0f64; point_count];
+    let mut sum_squares = vec![0.0f64; point_count];
+    let mut min = vec![f32::INFINITY; point_count];
+    let mut max = vec![f32::NEG_INFINITY; point_count];
+    let mut frame_count = 0;
+
+    for (index, frame) in frames.enumerate() {
+        if frame.len() != point_count {
+            return Err(VtkError::GenericError(format!(
+                "Time step {} has {} values, expected {}",
+                index,
+                frame.len(),
+                point_count
+            )));
+        }
+        for (point, &value) in frame.iter().enumerate() {
+            if !value.is_finite() {
+                continue;
+            }
+            count[point] += 1;
+            sum[point] += value as f64;
+            sum_squares[point] += value as f64 * value as f64;
+            min[point] = min[point].min(value);
+            max[point] = max[point].max(value);
+        }
+        frame_count += 1;
+    }
+
+    let mut mean = Vec::with_capacity(point_count);
+    let mut stddev = Vec::with_capacity(point_count);
+    for point in 0..point_count {
+        if count[point] == 0 {
+            mean.push(f32::NAN);
+            stddev.push(f32::NAN);
+            min[point] = f32::NAN;
+            max[point] = f32::NAN;
+            continue;
+        }
+        let n = count[point] as f64;
+        let point_mean = sum[point] / n;
+        let variance = (sum_squares[point] / n - point_mean * point_mean).max(0.0);
+        mean.push(point_mean as f32);
+        stddev.push(variance.sqrt() as f32);
+    }
+
+    let range = min.iter().zip(&max).filter(|(low, _)| !low.is_nan()).fold(
+        None,
+        |range: Option<(f32, f32)>, (&low, &high)| {
+            Some(match range {
+                Some((min, max)) => (min.min(low), max.max(high)),
+                None => (low, high),
+            })
+        },
+    );
+
+    Ok(TemporalStatistics {
+        mean,
+        min,
+        max,
+        stddev,
+        range,
+        frames: frame_count,
+    })
+}
+
+/// Store the statistics as point scalars named `<prefix>_mean`, `<prefix>_min`,
+/// `<prefix>_max` and `<prefix>_stddev`
+///
+/// Existing attributes with the same names are replaced.
+///
+/// # Parameters
+/// - `geometry`: Geometry data receiving the attributes
+/// - `statistics`: Statistics with one value per point of the geometry
+/// - `prefix`: Prefix of the attribute names
+///
+/// # Returns
+/// - Names of the added attributes, or an error if the point counts differ
+pub fn add_statistics_attributes(
+    geometry: &mut GeometryData,
+    statistics: &TemporalStatistics,
+    prefix: &str,
+) -> Result<Vec<String>, VtkError> {
+    if statistics.mean.len() != geometry.vertices.len() {
+        return Err(VtkError::GenericError(format!(
+            "The time steps have {} values but the model has {} points",
+            statistics.mean.len(),
+            geometry.vertices.len()
+        )));
+    }
+    let attributes = geometry.attributes.get_or_insert_with(Default::default);
+    let names = statistics
+        .fields()
+        .into_iter()
+        .map(|(suffix, data)| {
+            let name = format!("{}_{}", prefix, suffix);
+            attributes.insert(
+                (name.clone(), AttributeLocation::Point),
+                AttributeType::Scalar {
+                    num_comp: 1,
+                    table_name: "default".to_string(),
+                    data: data.to_vec(),
+                    lookup_table: None,
+                },
+            );
+            name
+        })
+        .collect();
+    Ok(names)
+}
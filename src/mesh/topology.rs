@@ -0,0 +1,405 @@
+//! Mesh topology module
+//!
+//! Helpers for navigating the relationship between rendered triangles and the
+//! original VTK cells, and between neighboring cells.
+use super::{AttributeLocation, AttributeType, GeometryData, PolyLine};
+use bevy::math::Vec3;
+use bevy::utils::HashMap;
+use std::collections::BTreeSet;
+
+/// Number of original cells represented by the geometry
+///
+/// Falls back to the triangle count if no triangle-to-cell mapping exists.
+pub fn cell_count(geometry: &GeometryData) -> usize {
+    let surface_cells = match geometry.triangle_to_cell_mapping {
+        Some(ref mapping) => mapping.iter().max().map_or(0, |&max| max + 1),
+        None => geometry.indices.len() / 3,
+    };
+    let line_cells = geometry
+        .lines
+        .iter()
+        .flatten()
+        .map(|line| line.cell + 1)
+        .max()
+        .unwrap_or(0);
+    surface_cells.max(line_cells)
+}
+
+/// Original cell index of a triangle
+pub fn triangle_cell(geometry: &GeometryData, triangle: usize) -> usize {
+    geometry
+        .triangle_to_cell_mapping
+        .as_ref()
+        .and_then(|mapping| mapping.get(triangle).copied())
+        .unwrap_or(triangle)
+}
+
+/// Group triangle indices by their original cell
+///
+/// # Returns
+/// - Triangle index list for each cell (cells without triangles have empty lists)
+pub fn cell_triangles(geometry: &GeometryData) -> Vec<Vec<usize>> {
+    let mut triangles = vec![Vec::new(); cell_count(geometry)];
+    for triangle in 0..geometry.indices.len() / 3 {
+        let cell = triangle_cell(geometry, triangle);
+        if let Some(list) = triangles.get_mut(cell) {
+            list.push(triangle);
+        }
+    }
+    triangles
+}
+
+/// Collect the unique vertex indices of each cell
+///
+/// # Parameters
+/// - `geometry`: Geometry data
+/// - `cell_triangles`: Result of [`cell_triangles`]
+pub fn cell_vertices(geometry: &GeometryData, cell_triangles: &[Vec<usize>]) -> Vec<Vec<u32>> {
+    cell_triangles
+        .iter()
+        .map(|triangles| {
+            let mut vertices: Vec<u32> = triangles
+                .iter()
+                .flat_map(|&t| geometry.indices[t * 3..t * 3 + 3].iter().copied())
+                .collect();
+            vertices.sort_unstable();
+            vertices.dedup();
+            vertices
+        })
+        .collect()
+}
+
+/// Find neighboring cells (cells sharing at least one triangle edge)
+///
+/// # Parameters
+/// - `geometry`: Geometry data
+/// - `cell_triangles`: Result of [`cell_triangles`]
+///
+/// # Returns
+/// - Neighbor cell list for each cell
+pub fn cell_neighbors(geometry: &GeometryData, cell_triangles: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    // Map each undirected edge to the cells using it
+    let mut edge_cells: HashMap<(u32, u32), Vec<usize>> = HashMap::new();
+    for (cell, triangles) in cell_triangles.iter().enumerate() {
+        for &triangle in triangles {
+            let corners = &geometry.indices[triangle * 3..triangle * 3 + 3];
+            for i in 0..3 {
+                let (a, b) = (corners[i], corners[(i + 1) % 3]);
+                let edge = (a.min(b), a.max(b));
+                let cells = edge_cells.entry(edge).or_default();
+                if !cells.contains(&cell) {
+                    cells.push(cell);
+                }
+            }
+        }
+    }
+
+    let mut neighbors = vec![Vec::new(); cell_triangles.len()];
+    for cells in edge_cells.values() {
+        for &a in cells {
+            for &b in cells {
+                if a != b && !neighbors[a].contains(&b) {
+                    neighbors[a].push(b);
+                }
+            }
+        }
+    }
+    neighbors
+}
+
+/// Evaluate a scalar attribute per cell
+///
+/// Cell attributes are used directly; point attributes are averaged over the
+/// vertices of each cell. Multi-component scalars use their first component.
+///
+/// # Returns
+/// - `Some(values)`: One value per cell
+/// - `None`: Attribute does not exist or is not a scalar
+pub fn cell_scalar_values(
+    geometry: &GeometryData,
+    name: &str,
+    location: &AttributeLocation,
+    cell_vertices: &[Vec<u32>],
+) -> Option<Vec<f32>> {
+    let AttributeType::Scalar { num_comp, data, .. } =
+        geometry.get_attributes(name, location.clone())?
+    else {
+        return None;
+    };
+    let num_comp = (*num_comp).max(1);
+
+    let values = match location {
+        AttributeLocation::Cell => (0..cell_vertices.len())
+            .map(|cell| data.get(cell * num_comp).copied().unwrap_or(0.0))
+            .collect(),
+        AttributeLocation::Point => cell_vertices
+            .iter()
+            .map(|vertices| {
+                if vertices.is_empty() {
+                    return 0.0;
+                }
+                let sum: f32 = vertices
+                    .iter()
+                    .map(|&v| data.get(v as usize * num_comp).copied().unwrap_or(0.0))
+                    .sum();
+                sum / vertices.len() as f32
+            })
+            .collect(),
+    };
+
+    Some(values)
+}
+
+/// Number of components per point/cell of an attribute
+pub fn attribute_components(attribute: &AttributeType) -> usize {
+    match attribute {
+        AttributeType::Scalar { num_comp, .. } => (*num_comp).max(1),
+        AttributeType::ColorScalar { nvalues, .. } => *nvalues as usize,
+        AttributeType::Vector(_) => 3,
+        AttributeType::Tensor(_) => 9,
+    }
+}
+
+/// Number of points/cells stored in an attribute
+pub fn element_count(attribute: &AttributeType) -> usize {
+    match attribute {
+        AttributeType::Scalar { num_comp, data, .. } => data.len() / (*num_comp).max(1),
+        AttributeType::ColorScalar { data, .. } => data.len(),
+        AttributeType::Vector(data) => data.len(),
+        AttributeType::Tensor(data) => data.len(),
+    }
+}
+
+/// Value of one component of an attribute at a point or cell
+///
+/// Without a component index, vectors evaluate to their magnitude and all other
+/// attributes to their first component. Missing values evaluate to 0.
+pub fn attribute_value(attribute: &AttributeType, element: usize, component: Option<usize>) -> f32 {
+    match attribute {
+        AttributeType::Scalar { num_comp, data, .. } => {
+            let num_comp = (*num_comp).max(1);
+            data.get(element * num_comp + component.unwrap_or(0))
+                .copied()
+                .unwrap_or(0.0)
+        }
+        AttributeType::ColorScalar { data, .. } => data
+            .get(element)
+            .and_then(|color| color.get(component.unwrap_or(0)))
+            .copied()
+            .unwrap_or(0.0),
+        AttributeType::Vector(data) => data.get(element).map_or(0.0, |v| match component {
+            Some(component) => v.get(component).copied().unwrap_or(0.0),
+            None => Vec3::from(*v).length(),
+        }),
+        AttributeType::Tensor(data) => data
+            .get(element)
+            .and_then(|t| t.get(component.unwrap_or(0)))
+            .copied()
+            .unwrap_or(0.0),
+    }
+}
+
+/// Evaluate one component of an attribute per cell
+///
+/// Cell attributes are used directly; point attributes are averaged over the
+/// vertices of each cell.
+pub fn cell_attribute_values(
+    attribute: &AttributeType,
+    location: &AttributeLocation,
+    component: Option<usize>,
+    cell_vertices: &[Vec<u32>],
+) -> Vec<f32> {
+    match location {
+        AttributeLocation::Cell => (0..cell_vertices.len())
+            .map(|cell| attribute_value(attribute, cell, component))
+            .collect(),
+        AttributeLocation::Point => cell_vertices
+            .iter()
+            .map(|vertices| {
+                if vertices.is_empty() {
+                    return 0.0;
+                }
+                let sum: f32 = vertices
+                    .iter()
+                    .map(|&v| attribute_value(attribute, v as usize, component))
+                    .sum();
+                sum / vertices.len() as f32
+            })
+            .collect(),
+    }
+}
+
+/// Copy the values of the given points or cells into a new attribute
+pub fn subset_attribute(attribute: &AttributeType, elements: &[usize]) -> AttributeType {
+    match attribute {
+        AttributeType::Scalar {
+            num_comp,
+            table_name,
+            data,
+            lookup_table,
+        } => {
+            let stride = (*num_comp).max(1);
+            AttributeType::Scalar {
+                num_comp: *num_comp,
+                table_name: table_name.clone(),
+                data: elements
+                    .iter()
+                    .flat_map(|&e| (0..stride).map(move |c| data.get(e * stride + c)))
+                    .map(|value| value.copied().unwrap_or(0.0))
+                    .collect(),
+                lookup_table: lookup_table.clone(),
+            }
+        }
+        AttributeType::ColorScalar { nvalues, data } => AttributeType::ColorScalar {
+            nvalues: *nvalues,
+            data: elements
+                .iter()
+                .map(|&e| data.get(e).cloned().unwrap_or_default())
+                .collect(),
+        },
+        AttributeType::Vector(data) => AttributeType::Vector(
+            elements
+                .iter()
+                .map(|&e| data.get(e).copied().unwrap_or_default())
+                .collect(),
+        ),
+        AttributeType::Tensor(data) => AttributeType::Tensor(
+            elements
+                .iter()
+                .map(|&e| data.get(e).copied().unwrap_or_default())
+                .collect(),
+        ),
+    }
+}
+
+/// Extract a subset of cells into new, compact geometry
+///
+/// Only the vertices used by the selected cells are kept. Point and cell
+/// attributes are copied; cells are renumbered in ascending order of their
+/// original index.
+///
+/// # Parameters
+/// - `geometry`: Source geometry
+/// - `cells`: Original cell indices to keep
+///
+/// # Returns
+/// - Geometry containing only the selected cells
+pub fn extract_cells(geometry: &GeometryData, cells: &BTreeSet<usize>) -> GeometryData {
+    let cell_map: HashMap<usize, usize> = cells
+        .iter()
+        .enumerate()
+        .map(|(new, &old)| (old, new))
+        .collect();
+
+    let mut vertex_map: HashMap<u32, u32> = HashMap::new();
+    let mut kept_vertices: Vec<usize> = Vec::new();
+    let mut indices = Vec::new();
+    let mut triangle_cells = Vec::new();
+
+    for (triangle, corners) in geometry.indices.chunks_exact(3).enumerate() {
+        let Some(&new_cell) = cell_map.get(&triangle_cell(geometry, triangle)) else {
+            continue;
+        };
+        for &corner in corners {
+            let index = *vertex_map.entry(corner).or_insert_with(|| {
+                kept_vertices.push(corner as usize);
+                (kept_vertices.len() - 1) as u32
+            });
+            indices.push(index);
+        }
+        triangle_cells.push(new_cell);
+    }
+
+    let mut lines = Vec::new();
+    for line in geometry.lines.iter().flatten() {
+        let Some(&new_cell) = cell_map.get(&line.cell) else {
+            continue;
+        };
+        let points = line
+            .points
+            .iter()
+            .map(|&point| {
+                *vertex_map.entry(point).or_insert_with(|| {
+                    kept_vertices.push(point as usize);
+                    (kept_vertices.len() - 1) as u32
+                })
+            })
+            .collect();
+        lines.push(PolyLine {
+            cell: new_cell,
+            points,
+        });
+    }
+
+    let kept_cells: Vec<usize> = cells.iter().copied().collect();
+    let attributes = geometry
+        .attributes
+        .as_ref()
+        .map(|attributes| {
+            attributes
+                .iter()
+                .map(|((name, location), attribute)| {
+                    let elements = match location {
+                        AttributeLocation::Point => &kept_vertices,
+                        AttributeLocation::Cell => &kept_cells,
+                    };
+                    let subset = if name.starts_with("__lut_") {
+                        attribute.clone()
+                    } else {
+                        subset_attribute(attribute, elements)
+                    };
+                    ((name.clone(), location.clone()), subset)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let vertices = kept_vertices
+        .iter()
+        .map(|&v| geometry.vertices[v])
+        .collect();
+
+    let mut extracted = GeometryData::new(vertices, indices, attributes)
+        .add_triangle_to_cell_mapping(triangle_cells)
+        .add_lines(lines);
+    extracted.cell_types = geometry.cell_types.as_ref().map(|types| {
+        kept_cells
+            .iter()
+            .map(|&cell| types.get(cell).copied().unwrap_or_default())
+            .collect()
+    });
+    extracted.lookup_tables = geometry.lookup_tables.clone();
+    extracted
+}
+
+/// List names and locations of all attributes (lookup tables excluded)
+pub fn attribute_names(geometry: &GeometryData) -> Vec<(String, AttributeLocation)> {
+    let mut names: Vec<(String, AttributeLocation)> = geometry
+        .attributes
+        .iter()
+        .flat_map(|attributes| attributes.keys())
+        .filter(|(name, _)| !name.starts_with("__lut_"))
+        .cloned()
+        .collect();
+    names.sort_by(|a, b| a.0.cmp(&b.0));
+    names
+}
+
+/// List names and locations of all scalar attributes (lookup tables excluded)
+pub fn scalar_attribute_names(geometry: &GeometryData) -> Vec<(String, AttributeLocation)> {
+    let mut names: Vec<(String, AttributeLocation)> = geometry
+        .attributes
+        .as_ref()
+        .map(|attributes| {
+            attributes
+                .iter()
+                .filter(|((name, _), attr)| {
+                    !name.starts_with("__lut_") && matches!(attr, AttributeType::Scalar { .. })
+                })
+                .map(|(key, _)| key.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort_by(|a, b| a.0.cmp(&b.0));
+    names
+}
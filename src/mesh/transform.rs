@@ -0,0 +1,54 @@
+//! Transform module
+//!
+//! Affine transformation of geometry data:
+//! - Vertices are transformed by the full matrix
+//! - Vector attributes are transformed by its linear part (rotation and scale)
+//! - Normals are transformed by the inverse transpose and renormalized
+//! - Mirroring transforms reverse the triangle winding, so faces keep pointing
+//!   outwards
+//!
+//! Scalars, tensors and texture coordinates are left unchanged.
+use super::{AttributeType, GeometryData};
+use bevy::math::{Mat3, Mat4, Vec3};
+
+/// Apply an affine transform to geometry in place
+///
+/// # Parameters
+/// - `geometry`: Geometry to transform
+/// - `matrix`: Affine transform (e.g. `Transform::compute_matrix`)
+pub fn transform_geometry(geometry: &mut GeometryData, matrix: Mat4) {
+    let linear = Mat3::from_mat4(matrix);
+    for vertex in &mut geometry.vertices {
+        *vertex = matrix.transform_point3(Vec3::from(*vertex)).to_array();
+    }
+
+    if let Some(attributes) = geometry.attributes.as_mut() {
+        for attribute in attributes.values_mut() {
+            if let AttributeType::Vector(vectors) = attribute {
+                for vector in vectors.iter_mut() {
+                    *vector = (linear * Vec3::from(*vector)).to_array();
+                }
+            }
+        }
+    }
+
+    let determinant = linear.determinant();
+    if let Some(normals) = geometry.normals.as_mut() {
+        let normal_matrix = if determinant.abs() > f32::EPSILON {
+            linear.inverse().transpose()
+        } else {
+            linear
+        };
+        for normal in normals.iter_mut() {
+            *normal = (normal_matrix * Vec3::from(*normal))
+                .normalize_or_zero()
+                .to_array();
+        }
+    }
+
+    if determinant < 0.0 {
+        for triangle in geometry.indices.chunks_exact_mut(3) {
+            triangle.swap(1, 2);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use super::{QuadraticEdge, QuadraticTriangle};
+use super::{PolyLine, QuadraticEdge, QuadraticTriangle};
 use vtkio::model::{self, VertexNumbers};
 
 /// Triangulation module, providing triangulation functionality for various geometric shapes
@@ -143,6 +143,168 @@ pub fn triangulate_polygon(topology: model::VertexNumbers) -> (Vec<u32>, Vec<usi
     (indices, triangle_to_cell_mapping)
 }
 
+/// Triangle strip triangulation function
+///
+/// Splits every strip into its triangles, flipping every second triangle to
+/// keep a consistent orientation. Degenerate triangles (used to join strips)
+/// are skipped.
+///
+/// # Parameters
+/// * `topology` - Strip topology structure
+///
+/// # Return value
+/// * (Triangle index list, triangle to strip mapping)
+pub fn triangulate_strips(topology: model::VertexNumbers) -> (Vec<u32>, Vec<usize>) {
+    let mut indices = Vec::new();
+    let mut triangle_to_cell_mapping = Vec::new();
+    let (connectivity, offsets) = topology.into_xml();
+
+    let mut start = 0;
+    for (cell_idx, &end) in offsets.iter().enumerate() {
+        let end = (end as usize).min(connectivity.len());
+        let strip: Vec<u32> = connectivity[start.min(end)..end]
+            .iter()
+            .map(|&v| v as u32)
+            .collect();
+        start = end;
+
+        for i in 0..strip.len().saturating_sub(2) {
+            let triangle = if i % 2 == 0 {
+                [strip[i], strip[i + 1], strip[i + 2]]
+            } else {
+                [strip[i + 1], strip[i], strip[i + 2]]
+            };
+            if triangle[0] == triangle[1]
+                || triangle[1] == triangle[2]
+                || triangle[0] == triangle[2]
+            {
+                continue;
+            }
+            indices.extend_from_slice(&triangle);
+            triangle_to_cell_mapping.push(cell_idx);
+        }
+    }
+
+    (indices, triangle_to_cell_mapping)
+}
+
+/// Structured grid surface triangulation function
+///
+/// Builds the outer surface of a structured block of `nx * ny * nz` points
+/// (x varying fastest). 3D blocks yield their six boundary faces with outward
+/// winding, 2D grids all of their quads. Line and point grids have no surface.
+///
+/// # Parameters
+/// * `dims` - Number of points along x, y and z
+///
+/// # Return value
+/// * (Triangle index list, triangle to original cell mapping)
+pub fn triangulate_structured(dims: [usize; 3]) -> (Vec<u32>, Vec<usize>) {
+    let mut indices = Vec::new();
+    let mut triangle_to_cell_mapping = Vec::new();
+
+    // Flat axes still count as one cell layer in the VTK cell numbering
+    let cell_dims = dims.map(|n| n.saturating_sub(1).max(1));
+    let point_id = |p: [usize; 3]| (p[0] + p[1] * dims[0] + p[2] * dims[0] * dims[1]) as u32;
+    let cell_id = |c: [usize; 3]| c[0] + c[1] * cell_dims[0] + c[2] * cell_dims[0] * cell_dims[1];
+
+    let solid_axes = dims.iter().filter(|&&n| n > 1).count();
+    for axis in 0..3 {
+        let (u_axis, v_axis) = ((axis + 1) % 3, (axis + 2) % 3);
+        if dims[u_axis] < 2 || dims[v_axis] < 2 {
+            continue;
+        }
+
+        // (layer along the axis, reversed winding)
+        let layers: Vec<(usize, bool)> = match solid_axes {
+            3 => vec![(0, true), (dims[axis] - 1, false)],
+            2 => vec![(0, false)],
+            _ => Vec::new(),
+        };
+
+        for (layer, reversed) in layers {
+            let cell_layer = layer.min(cell_dims[axis] - 1);
+            for v in 0..dims[v_axis] - 1 {
+                for u in 0..dims[u_axis] - 1 {
+                    let corner = |du: usize, dv: usize| {
+                        let mut p = [0; 3];
+                        p[axis] = layer;
+                        p[u_axis] = u + du;
+                        p[v_axis] = v + dv;
+                        point_id(p)
+                    };
+                    let mut quad = [corner(0, 0), corner(1, 0), corner(1, 1), corner(0, 1)];
+                    if reversed {
+                        quad.reverse();
+                    }
+                    indices.extend_from_slice(&[quad[0], quad[1], quad[2]]);
+                    indices.extend_from_slice(&[quad[0], quad[2], quad[3]]);
+
+                    let mut c = [0; 3];
+                    c[axis] = cell_layer;
+                    c[u_axis] = u;
+                    c[v_axis] = v;
+                    triangle_to_cell_mapping.push(cell_id(c));
+                    triangle_to_cell_mapping.push(cell_id(c));
+                }
+            }
+        }
+    }
+
+    (indices, triangle_to_cell_mapping)
+}
+
+/// VTK cell type id of every cell
+pub fn cell_type_ids(cells: &model::Cells) -> Vec<u8> {
+    cells
+        .types
+        .iter()
+        .map(|&cell_type| cell_type as u8)
+        .collect()
+}
+
+/// Polylines of a line topology, used for PolyData lines
+///
+/// # parameters
+/// * `topology` - Line topology
+/// * `first_cell` - Cell index of the first line
+///
+/// # return value
+/// * One polyline per cell with at least two points
+pub fn polylines(topology: &model::VertexNumbers, first_cell: usize) -> Vec<PolyLine> {
+    let (num_cells, vertices) = topology.clone().into_legacy();
+    let mut lines = Vec::with_capacity(num_cells as usize);
+    let mut data = vertices.as_slice();
+    for cell in 0..num_cells as usize {
+        let Some((&count, rest)) = data.split_first() else {
+            break;
+        };
+        let count = (count as usize).min(rest.len());
+        if count >= 2 {
+            lines.push(PolyLine {
+                cell: first_cell + cell,
+                points: rest[..count].to_vec(),
+            });
+        }
+        data = &rest[count..];
+    }
+    lines
+}
+
+/// Polylines of the line and poly line cells of an UnstructuredGrid
+pub fn cell_polylines(cells: &model::Cells) -> Vec<PolyLine> {
+    let is_line = |cell_type: &model::CellType| {
+        matches!(cell_type, model::CellType::Line | model::CellType::PolyLine)
+    };
+    if !cells.types.iter().any(is_line) {
+        return Vec::new();
+    }
+    polylines(&cells.cell_verts, 0)
+        .into_iter()
+        .filter(|line| cells.types.get(line.cell).is_some_and(is_line))
+        .collect()
+}
+
 /// triangulate different types of cells, used for UnstructuredGrid type
 ///
 /// # parameters
@@ -0,0 +1,156 @@
+//! Tubes module
+//!
+//! Tube geometry around polylines, so that 1D data is shaded like a surface:
+//! - A circular cross-section with a configurable number of sides is swept
+//!   along every line
+//! - Rings are oriented by parallel transport, so tubes do not twist
+//! - Every ring takes the point attributes of its line point and every tube
+//!   triangle the cell of its line, so color mapping works as on the lines
+use super::topology::subset_attribute;
+use super::{AttributeLocation, GeometryData};
+use bevy::math::{Quat, Vec3};
+
+/// Fewest sides of a tube cross-section
+pub const MIN_SIDES: usize = 3;
+
+/// Vertices, normals and triangles of swept tubes
+#[derive(Default)]
+pub struct TubeMesh {
+    pub vertices: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub indices: Vec<u32>,
+}
+
+impl TubeMesh {
+    /// Sweep a circular cross-section along a polyline
+    ///
+    /// One ring of `sides` vertices is added per line point.
+    ///
+    /// # Parameters
+    /// - `points`: Points along the line
+    /// - `radius`: Tube radius
+    /// - `sides`: Number of sides of the cross-section (at least 3)
+    ///
+    /// # Returns
+    /// - Number of rings added, 0 for lines with fewer than two points
+    pub fn add_polyline(&mut self, points: &[Vec3], radius: f32, sides: usize) -> usize {
+        let sides = sides.max(MIN_SIDES);
+        let count = points.len();
+        if count < 2 {
+            return 0;
+        }
+        let tangent = |i: usize| {
+            (points[(i + 1).min(count - 1)] - points[i.saturating_sub(1)]).normalize_or_zero()
+        };
+        let mut previous_tangent = tangent(0);
+        if previous_tangent == Vec3::ZERO {
+            previous_tangent = Vec3::X;
+        }
+        let mut frame = previous_tangent.any_orthonormal_vector();
+        let start = self.vertices.len() as u32;
+
+        for (i, point) in points.iter().enumerate() {
+            let mut current_tangent = tangent(i);
+            if current_tangent == Vec3::ZERO {
+                current_tangent = previous_tangent;
+            }
+            frame = Quat::from_rotation_arc(previous_tangent, current_tangent) * frame;
+            frame = (frame - current_tangent * frame.dot(current_tangent)).normalize_or_zero();
+            if frame == Vec3::ZERO {
+                frame = current_tangent.any_orthonormal_vector();
+            }
+            let binormal = current_tangent.cross(frame);
+            previous_tangent = current_tangent;
+
+            for side in 0..sides {
+                let angle = side as f32 / sides as f32 * std::f32::consts::TAU;
+                let normal = frame * angle.cos() + binormal * angle.sin();
+                self.vertices.push((*point + normal * radius).to_array());
+                self.normals.push(normal.to_array());
+            }
+        }
+
+        for i in 0..count as u32 - 1 {
+            let ring = start + i * sides as u32;
+            let next_ring = ring + sides as u32;
+            for side in 0..sides as u32 {
+                let next_side = (side + 1) % sides as u32;
+                self.indices.extend_from_slice(&[
+                    ring + side,
+                    ring + next_side,
+                    next_ring + side,
+                    ring + next_side,
+                    next_ring + next_side,
+                    next_ring + side,
+                ]);
+            }
+        }
+        count
+    }
+}
+
+/// Tube geometry around the lines of a geometry
+///
+/// Point attributes are copied to the rings and cell attributes are kept, with
+/// every tube triangle mapped to the cell of its line.
+///
+/// # Parameters
+/// - `geometry`: Geometry with lines
+/// - `radius`: Tube radius
+/// - `sides`: Number of sides of the cross-section
+///
+/// # Returns
+/// - Tube geometry, `None` if the geometry has no lines
+pub fn tube_geometry(geometry: &GeometryData, radius: f32, sides: usize) -> Option<GeometryData> {
+    let lines = geometry.lines.as_ref()?;
+    let sides = sides.max(MIN_SIDES);
+    let mut mesh = TubeMesh::default();
+    let mut sources = Vec::new();
+    let mut triangle_to_cell = Vec::new();
+
+    for line in lines {
+        let points: Vec<u32> = line
+            .points
+            .iter()
+            .copied()
+            .filter(|&point| (point as usize) < geometry.vertices.len())
+            .collect();
+        let positions: Vec<Vec3> = points
+            .iter()
+            .map(|&point| Vec3::from(geometry.vertices[point as usize]))
+            .collect();
+        let rings = mesh.add_polyline(&positions, radius, sides);
+        if rings == 0 {
+            continue;
+        }
+        for &point in &points {
+            sources.extend(std::iter::repeat_n(point as usize, sides));
+        }
+        triangle_to_cell.extend(std::iter::repeat_n(line.cell, (rings - 1) * sides * 2));
+    }
+    if mesh.indices.is_empty() {
+        return None;
+    }
+
+    let attributes = geometry
+        .attributes
+        .iter()
+        .flatten()
+        .map(|((name, location), attribute)| {
+            let attribute = match location {
+                AttributeLocation::Point if !name.starts_with("__lut_") => {
+                    subset_attribute(attribute, &sources)
+                }
+                _ => attribute.clone(),
+            };
+            ((name.clone(), location.clone()), attribute)
+        })
+        .collect();
+
+    let mut tubes = GeometryData::new(mesh.vertices, mesh.indices, attributes)
+        .add_normals(mesh.normals)
+        .add_triangle_to_cell_mapping(triangle_to_cell);
+    tubes.lookup_tables = geometry.lookup_tables.clone();
+    tubes.cell_types = geometry.cell_types.clone();
+    Some(tubes)
+}
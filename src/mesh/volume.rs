@@ -0,0 +1,173 @@
+//! Volume module
+//!
+//! Voxel data of image data (`.vti`) files. The full arrays are kept on a
+//! regular grid so that filters can work on the volume; for display,
+//! axis-aligned slices are extracted as quad meshes.
+use super::triangulation;
+use super::{AttributeLocation, AttributeType, GeometryData};
+use bevy::utils::HashMap;
+
+/// Regular grid of points with point and cell data
+#[derive(Debug, Clone)]
+pub struct VolumeData {
+    /// Number of points along x, y and z
+    pub dims: [usize; 3],
+    /// Position of the first point
+    pub origin: [f32; 3],
+    /// Distance between neighboring points along x, y and z
+    pub spacing: [f32; 3],
+    /// Point and cell data, x varying fastest
+    pub attributes: HashMap<(String, AttributeLocation), AttributeType>,
+}
+
+impl VolumeData {
+    /// Number of cells along x, y and z (flat axes count as one cell layer)
+    pub fn cell_dims(&self) -> [usize; 3] {
+        self.dims.map(|n| n.saturating_sub(1).max(1))
+    }
+
+    /// Total number of points
+    pub fn point_count(&self) -> usize {
+        self.dims.iter().product()
+    }
+
+    /// Index of the point at grid position `p`
+    pub fn point_index(&self, p: [usize; 3]) -> usize {
+        p[0] + p[1] * self.dims[0] + p[2] * self.dims[0] * self.dims[1]
+    }
+
+    /// Index of the cell at grid position `c`
+    pub fn cell_index(&self, c: [usize; 3]) -> usize {
+        let cell_dims = self.cell_dims();
+        c[0] + c[1] * cell_dims[0] + c[2] * cell_dims[0] * cell_dims[1]
+    }
+
+    /// Position of the point at grid position `p`
+    pub fn point_position(&self, p: [usize; 3]) -> [f32; 3] {
+        std::array::from_fn(|axis| self.origin[axis] + self.spacing[axis] * p[axis] as f32)
+    }
+
+    /// Axis shown by default: the flat axis of 2D images, otherwise Z
+    pub fn default_axis(&self) -> usize {
+        (0..3).rev().find(|&axis| self.dims[axis] == 1).unwrap_or(2)
+    }
+
+    /// Middle slice along the default axis
+    pub fn default_slice(&self) -> GeometryData {
+        let axis = self.default_axis();
+        self.slice(axis, self.dims[axis] / 2)
+    }
+
+    /// Extract an axis-aligned slice as a quad mesh
+    ///
+    /// Point data is taken from the points of the slice, cell data from the cell
+    /// layer the slice borders on.
+    ///
+    /// # Parameters
+    /// - `axis`: Slice normal (0 = X, 1 = Y, 2 = Z)
+    /// - `index`: Point layer along the axis (clamped to the volume)
+    ///
+    /// # Returns
+    /// - Slice geometry with the sliced attributes
+    pub fn slice(&self, axis: usize, index: usize) -> GeometryData {
+        let axis = axis.min(2);
+        let index = index.min(self.dims[axis].saturating_sub(1));
+        let cell_layer = index.min(self.cell_dims()[axis] - 1);
+
+        let mut slice_dims = self.dims;
+        slice_dims[axis] = 1;
+        let mut slice_cell_dims = self.cell_dims();
+        slice_cell_dims[axis] = 1;
+
+        // Points and cells of the slice, in the x-fastest order of a flat grid
+        let mut vertices = Vec::with_capacity(slice_dims.iter().product());
+        let mut points = Vec::with_capacity(vertices.capacity());
+        for k in 0..slice_dims[2] {
+            for j in 0..slice_dims[1] {
+                for i in 0..slice_dims[0] {
+                    let mut p = [i, j, k];
+                    p[axis] = index;
+                    vertices.push(self.point_position(p));
+                    points.push(self.point_index(p));
+                }
+            }
+        }
+        let mut cells = Vec::with_capacity(slice_cell_dims.iter().product());
+        for k in 0..slice_cell_dims[2] {
+            for j in 0..slice_cell_dims[1] {
+                for i in 0..slice_cell_dims[0] {
+                    let mut c = [i, j, k];
+                    c[axis] = cell_layer;
+                    cells.push(self.cell_index(c));
+                }
+            }
+        }
+
+        let attributes = self
+            .attributes
+            .iter()
+            .map(|((name, location), attribute)| {
+                let attribute = if name.starts_with("__lut_") {
+                    attribute.clone()
+                } else {
+                    match location {
+                        AttributeLocation::Point => gather_elements(attribute, &points),
+                        AttributeLocation::Cell => gather_elements(attribute, &cells),
+                    }
+                };
+                ((name.clone(), location.clone()), attribute)
+            })
+            .collect();
+
+        let (indices, triangle_to_cell_mapping) = triangulation::triangulate_structured(slice_dims);
+        let mut geometry = GeometryData::new(vertices, indices, attributes);
+        geometry.extract_lookup_tables();
+        geometry.add_triangle_to_cell_mapping(triangle_to_cell_mapping)
+    }
+}
+
+/// Pick elements of an attribute by index
+///
+/// Indices beyond the attribute data repeat its first element.
+///
+/// # Parameters
+/// - `attribute`: Source attribute
+/// - `elements`: Element indices to pick
+///
+/// # Returns
+/// - Attribute with one element per index
+pub fn gather_elements(attribute: &AttributeType, elements: &[usize]) -> AttributeType {
+    fn pick<T: Clone>(data: &[T], elements: &[usize]) -> Vec<T> {
+        if data.is_empty() {
+            return Vec::new();
+        }
+        elements
+            .iter()
+            .map(|&element| data.get(element).unwrap_or(&data[0]).clone())
+            .collect()
+    }
+
+    match attribute {
+        AttributeType::Scalar {
+            num_comp,
+            table_name,
+            data,
+            lookup_table,
+        } => {
+            let num_comp = (*num_comp).max(1);
+            let tuples: Vec<&[f32]> = data.chunks_exact(num_comp).collect();
+            AttributeType::Scalar {
+                num_comp,
+                table_name: table_name.clone(),
+                data: pick(&tuples, elements).concat(),
+                lookup_table: lookup_table.clone(),
+            }
+        }
+        AttributeType::ColorScalar { nvalues, data } => AttributeType::ColorScalar {
+            nvalues: *nvalues,
+            data: pick(data, elements),
+        },
+        AttributeType::Vector(data) => AttributeType::Vector(pick(data, elements)),
+        AttributeType::Tensor(data) => AttributeType::Tensor(pick(data, elements)),
+    }
+}
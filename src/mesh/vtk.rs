@@ -1,4 +1,9 @@
-use super::{GeometryData, QuadraticEdge, QuadraticTriangle, VtkError};
+use super::cells::VolumeCells;
+use super::progress;
+use super::volume::{self, VolumeData};
+use super::{
+    compression, ghost, topology, weld, GeometryData, QuadraticEdge, QuadraticTriangle, VtkError,
+};
 use crate::mesh::color_maps::{ColorMapper, ColorMappingConfig};
 use crate::mesh::triangulation;
 use bevy::prelude::*;
@@ -107,6 +112,190 @@ impl GeometryData {
     // }
 }
 
+/// Import a legacy or XML VTK file
+///
+/// XML files with compressed (zlib, LZ4, LZMA) data blocks are decompressed
+/// before parsing.
+///
+/// # Parameters
+/// - `path`: VTK file path
+///
+/// # Returns
+/// - `Ok(Vtk)`: Parsed VTK model
+/// - `Err(VtkError)`: Reading, decompressing or parsing failed
+pub fn import_vtk_file(path: &std::path::Path) -> Result<Vtk, VtkError> {
+    if is_xml_file(path) {
+        let document = std::fs::read(path).map_err(VtkError::IoError)?;
+        if let Some(decompressed) = compression::decompress_xml_document(&document)? {
+            println!("Decompressed XML VTK data blocks of {}", path.display());
+            return Vtk::parse_xml(std::io::Cursor::new(decompressed))
+                .map_err(|e| VtkError::LoadError(format!("{:?}", e)));
+        }
+    }
+
+    Vtk::import(path).map_err(|e| VtkError::LoadError(format!("{:?}", e)))
+}
+
+/// Whether a VTK file uses the XML format (`.vtu`, `.vtp`, ...), by its extension
+fn is_xml_file(path: &std::path::Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.starts_with("vt") && ext != "vtk")
+}
+
+/// Import a VTK file and extract its geometry
+///
+/// # Parameters
+/// - `path`: VTK file path
+///
+/// # Returns
+/// - `Ok(GeometryData)`: Geometry of an UnstructuredGrid, PolyData, StructuredGrid or
+///   RectilinearGrid dataset, or the middle slice of an ImageData volume
+/// - `Err(VtkError)`: Import failed or the dataset type is not supported
+pub fn load_vtk_geometry(path: &std::path::Path) -> Result<GeometryData, VtkError> {
+    progress::report_parsing();
+    let vtk = import_vtk_file(path)?;
+
+    let geometry = match vtk.data {
+        model::DataSet::UnstructuredGrid { meta: _, pieces } if is_xml_file(path) => {
+            XmlUnstructuredGridExtractor.process_xml(pieces)?
+        }
+        model::DataSet::UnstructuredGrid { meta: _, pieces } => {
+            UnstructuredGridExtractor.process_legacy(pieces)?
+        }
+        model::DataSet::PolyData { meta: _, pieces } if is_xml_file(path) => {
+            PolyDataXmlExtractor.process_xml(pieces)?
+        }
+        model::DataSet::PolyData { meta: _, pieces } => PolyDataExtractor.process_legacy(pieces)?,
+        model::DataSet::StructuredGrid { pieces, .. } => StructuredGridExtractor.process(pieces)?,
+        model::DataSet::RectilinearGrid { pieces, .. } => {
+            RectilinearGridExtractor.process(pieces)?
+        }
+        // Volumes are shown by their middle slice
+        model::DataSet::ImageData {
+            extent,
+            origin,
+            spacing,
+            pieces,
+            ..
+        } => ImageDataExtractor
+            .process(extent, origin, spacing, pieces)?
+            .default_slice(),
+        // TODO: Support other data types
+        _ => return Err(VtkError::UnsupportedDataType),
+    };
+
+    println!(
+        "Extracted geometry data attributes: {:?}",
+        &geometry.attributes
+    );
+
+    Ok(geometry)
+}
+
+/// Load a partitioned dataset from its parallel summary file (`.pvtu`, `.pvtp`)
+///
+/// Every piece file referenced by the summary is loaded like a standalone file
+/// (relative paths are resolved against the summary directory). The pieces are
+/// merged and the points duplicated on partition boundaries are welded.
+///
+/// # Parameters
+/// - `path`: Summary file path
+///
+/// # Returns
+/// - `Ok(GeometryData)`: Merged geometry of all loadable pieces
+/// - `Err(VtkError)`: The summary cannot be read or no piece could be loaded
+pub fn load_parallel_vtk_geometry(path: &std::path::Path) -> Result<GeometryData, VtkError> {
+    let vtk = import_vtk_file(path)?;
+
+    let sources = match vtk.data {
+        model::DataSet::UnstructuredGrid { pieces, .. } => piece_sources(pieces),
+        model::DataSet::PolyData { pieces, .. } => piece_sources(pieces),
+        _ => return Err(VtkError::UnsupportedDataType),
+    };
+    if sources.is_empty() {
+        return Err(VtkError::MissingData("No piece files referenced"));
+    }
+
+    let directory = path.parent().unwrap_or(std::path::Path::new("."));
+    let mut parts = Vec::new();
+    for source in &sources {
+        let piece_path = directory.join(source);
+        match load_vtk_geometry(&piece_path) {
+            Ok(geometry) => parts.push(geometry),
+            Err(e) => println!("Skipping piece {}: {}", piece_path.display(), e),
+        }
+    }
+    if parts.is_empty() {
+        return Err(VtkError::MissingData("No piece file could be loaded"));
+    }
+    println!(
+        "Loaded {} of {} pieces of {}",
+        parts.len(),
+        sources.len(),
+        path.display()
+    );
+
+    Ok(weld::weld_points(merge_pieces(parts), 0.0))
+}
+
+/// Files referenced by the pieces of a parallel summary file
+fn piece_sources<P>(pieces: Vec<model::Piece<P>>) -> Vec<String> {
+    pieces
+        .into_iter()
+        .filter_map(|piece| match piece {
+            model::Piece::Source(source, _) => Some(source),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Convert a data buffer of any VTK data type to `f32` values
+///
+/// Unlike `IOBuffer::cast_into`, the conversion cannot fail: integers are
+/// converted to the nearest `f32` and doubles outside the `f32` range become
+/// infinite. Bit arrays are unpacked into one 0/1 value per bit, most
+/// significant bit first.
+///
+/// # Parameters
+/// - `data`: Buffer of a data array, points or coordinates
+///
+/// # Returns
+/// - Values of the buffer as `f32`
+pub fn buffer_to_f32(data: &IOBuffer) -> Vec<f32> {
+    fn convert<T: Copy>(values: &[T], to_f32: impl Fn(T) -> f32) -> Vec<f32> {
+        values.iter().map(|&value| to_f32(value)).collect()
+    }
+
+    match data {
+        IOBuffer::Bit(bytes) => bytes
+            .iter()
+            .flat_map(|byte| (0..8).rev().map(move |bit| ((byte >> bit) & 1) as f32))
+            .collect(),
+        IOBuffer::U8(values) => convert(values, f32::from),
+        IOBuffer::I8(values) => convert(values, f32::from),
+        IOBuffer::U16(values) => convert(values, f32::from),
+        IOBuffer::I16(values) => convert(values, f32::from),
+        IOBuffer::U32(values) => convert(values, |value| value as f32),
+        IOBuffer::I32(values) => convert(values, |value| value as f32),
+        IOBuffer::U64(values) => convert(values, |value| value as f32),
+        IOBuffer::I64(values) => convert(values, |value| value as f32),
+        IOBuffer::F32(values) => values.clone(),
+        IOBuffer::F64(values) => convert(values, |value| value as f32),
+    }
+}
+
+/// Color components in the range 0 to 1
+///
+/// Binary legacy files store color scalars and lookup tables as unsigned
+/// chars (0 to 255), ASCII files as floats (0 to 1).
+fn color_components(data: &IOBuffer) -> Vec<f32> {
+    match data {
+        IOBuffer::U8(values) => values.iter().map(|&value| value as f32 / 255.0).collect(),
+        _ => buffer_to_f32(data),
+    }
+}
+
 pub trait VtkMeshExtractor {
     // associated type
     type PieceType;
@@ -119,9 +308,7 @@ pub trait VtkMeshExtractor {
     // basic geometry process
     fn extract_vertices(&self, points: &IOBuffer) -> Vec<[f32; 3]> {
         // process point position
-        let points = points
-            .cast_into::<f32>()
-            .expect("IOBuffer converted failed.");
+        let points = buffer_to_f32(points);
         // construct position of each vertex
         points.chunks_exact(3).map(|p| [p[0], p[1], p[2]]).collect()
     }
@@ -154,74 +341,69 @@ impl UnstructuredGridExtractor {
     }
 }
 
-impl VtkMeshExtractor for UnstructuredGridExtractor {
-    type PieceType = Vec<model::Piece<model::UnstructuredGridPiece>>;
-
-    fn extract_attributes_legacy(
+impl UnstructuredGridExtractor {
+    /// Attributes of a single legacy piece
+    fn legacy_piece_attributes(
         &self,
-        pieces: &Self::PieceType,
-    ) -> Result<HashMap<(String, AttributeLocation), AttributeType>, VtkError> {
+        piece: &model::UnstructuredGridPiece,
+    ) -> HashMap<(String, AttributeLocation), AttributeType> {
         let mut attributes = HashMap::new();
-        let piece = pieces
-            .first()
-            .ok_or(VtkError::MissingData("No pieces found"))?;
 
-        if let model::Piece::Inline(piece) = piece {
-            // Process point data attributes
-            for point_data in &piece.data.point {
-                match point_data {
-                    model::Attribute::DataArray(array) => {
-                        if let Ok((name, attr)) =
-                            self.process_data_array(&array.name, &array.elem, &array.data)
-                        {
-                            attributes.insert((name, AttributeLocation::Point), attr);
-                        }
+        // Process point data attributes
+        for point_data in &piece.data.point {
+            match point_data {
+                model::Attribute::DataArray(array) => {
+                    if let Ok((name, attr)) =
+                        self.process_data_array(&array.name, &array.elem, &array.data)
+                    {
+                        attributes.insert((name, AttributeLocation::Point), attr);
                     }
-                    _ => println!("Unsupported attribute type"),
                 }
+                _ => println!("Unsupported attribute type"),
             }
+        }
 
-            // Process cell data attributes
-            for cell_data in &piece.data.cell {
-                match cell_data {
-                    model::Attribute::DataArray(array) => {
-                        if let Ok((name, attr)) =
-                            self.process_data_array(&array.name, &array.elem, &array.data)
-                        {
-                            attributes.insert((name, AttributeLocation::Cell), attr);
-                        }
+        // Process cell data attributes
+        for cell_data in &piece.data.cell {
+            match cell_data {
+                model::Attribute::DataArray(array) => {
+                    if let Ok((name, attr)) =
+                        self.process_data_array(&array.name, &array.elem, &array.data)
+                    {
+                        attributes.insert((name, AttributeLocation::Cell), attr);
                     }
-                    _ => println!("Unsupported attribute type"),
                 }
+                _ => println!("Unsupported attribute type"),
             }
         }
 
-        Ok(attributes)
-    }
-
-    fn extract_indices(&self, pieces: Self::PieceType) -> Vec<u32> {
-        if let Some(model::Piece::Inline(piece)) = pieces.into_iter().next() {
-            let (indices, _, _, _) = self.triangulate_cells(piece.cells);
-            indices
-        } else {
-            Vec::new()
-        }
+        attributes
     }
 
-    fn process_legacy(&self, pieces: Self::PieceType) -> Result<GeometryData, VtkError> {
-        let piece = pieces
-            .first()
-            .ok_or(VtkError::MissingData("No pieces found"))?;
-        let model::Piece::Inline(piece) = piece else {
-            return Err(VtkError::InvalidFormat("Expected inline data"));
-        };
-
+    /// Geometry of a single legacy piece, without its ghost cells
+    ///
+    /// `(index, count)` is the piece being extracted and the number of pieces,
+    /// for the load progress.
+    fn process_legacy_piece(
+        &self,
+        piece: &model::UnstructuredGridPiece,
+        (index, count): (usize, usize),
+    ) -> GeometryData {
+        progress::report_piece_stage(index, count, 0.0, "Reading points");
         let vertices = self.extract_vertices(&piece.points);
+        let volume_cells = VolumeCells::from_vtk(&piece.cells);
+        let cell_types = triangulation::cell_type_ids(&piece.cells);
+        let lines = triangulation::cell_polylines(&piece.cells);
+        progress::report_piece_stage(index, count, 0.1, "Triangulating cells");
         let (indices, triangle_to_cell_mapping, quadratic_triangles, quadratic_edges) =
             self.triangulate_cells(piece.cells.clone());
-        let attributes = self.extract_attributes_legacy(&pieces)?;
+        progress::report_piece_stage(index, count, 0.6, "Building attributes");
+        let attributes = self.legacy_piece_attributes(piece);
 
-        let mut geometry = GeometryData::new(vertices, indices, attributes);
+        let mut geometry = GeometryData::new(vertices, indices, attributes)
+            .add_volume_cells(volume_cells)
+            .add_cell_types(cell_types)
+            .add_lines(lines);
         geometry.extract_lookup_tables();
         geometry = geometry.add_triangle_to_cell_mapping(triangle_to_cell_mapping);
 
@@ -235,7 +417,65 @@ impl VtkMeshExtractor for UnstructuredGridExtractor {
             geometry = geometry.add_quadratic_edges(quadratic_edges);
         }
 
-        Ok(geometry)
+        // Drop ghost cells of partitioned datasets
+        let cell_ghosts = ghost::ghost_flags(&piece.data.cell);
+        let point_ghosts = ghost::ghost_flags(&piece.data.point);
+        ghost::remove_ghosts(geometry, cell_ghosts.as_deref(), point_ghosts.as_deref())
+    }
+}
+
+impl VtkMeshExtractor for UnstructuredGridExtractor {
+    type PieceType = Vec<model::Piece<model::UnstructuredGridPiece>>;
+
+    fn extract_attributes_legacy(
+        &self,
+        pieces: &Self::PieceType,
+    ) -> Result<HashMap<(String, AttributeLocation), AttributeType>, VtkError> {
+        let piece = pieces
+            .first()
+            .ok_or(VtkError::MissingData("No pieces found"))?;
+
+        match piece {
+            model::Piece::Inline(piece) => Ok(self.legacy_piece_attributes(piece)),
+            _ => Ok(HashMap::new()),
+        }
+    }
+
+    fn extract_indices(&self, pieces: Self::PieceType) -> Vec<u32> {
+        if let Some(model::Piece::Inline(piece)) = pieces.into_iter().next() {
+            let (indices, _, _, _) = self.triangulate_cells(piece.cells);
+            indices
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Build the geometry of all inline pieces
+    ///
+    /// Pieces are merged with offset indices; attributes present in every
+    /// piece are concatenated.
+    fn process_legacy(&self, pieces: Self::PieceType) -> Result<GeometryData, VtkError> {
+        if pieces.is_empty() {
+            return Err(VtkError::MissingData("No pieces found"));
+        }
+
+        let mut parts = Vec::new();
+        for (index, piece) in pieces.iter().enumerate() {
+            let model::Piece::Inline(piece) = piece else {
+                println!("Skipping piece {}: only inline pieces are supported", index);
+                continue;
+            };
+            progress::check_cancelled()?;
+            parts.push(self.process_legacy_piece(piece, (index, pieces.len())));
+        }
+
+        if parts.is_empty() {
+            return Err(VtkError::InvalidFormat("Expected inline data"));
+        }
+        if parts.len() > 1 {
+            println!("Merging {} legacy UnstructuredGrid pieces", parts.len());
+        }
+        Ok(merge_pieces(parts))
     }
 
     fn process_data_array(
@@ -244,7 +484,7 @@ impl VtkMeshExtractor for UnstructuredGridExtractor {
         elem_type: &model::ElementType,
         data: &IOBuffer,
     ) -> Result<(String, AttributeType), VtkError> {
-        let values = data.cast_into::<f32>().unwrap();
+        let values = buffer_to_f32(data);
 
         match elem_type {
             model::ElementType::Scalars {
@@ -269,7 +509,7 @@ impl VtkMeshExtractor for UnstructuredGridExtractor {
                 ))
             }
             model::ElementType::ColorScalars(nvalues) => {
-                let color_values = values
+                let color_values = color_components(data)
                     .chunks_exact(*nvalues as usize)
                     .map(|v| v.to_vec())
                     .collect();
@@ -338,7 +578,7 @@ impl VtkMeshExtractor for UnstructuredGridExtractor {
             }
             model::ElementType::LookupTable => {
                 // Convert the lookup table data into RGBA colors
-                let colors: Vec<[f32; 4]> = values
+                let colors: Vec<[f32; 4]> = color_components(data)
                     .chunks_exact(4)
                     .map(|chunk| [chunk[0], chunk[1], chunk[2], chunk[3]])
                     .collect();
@@ -445,12 +685,26 @@ impl VtkMeshExtractor for PolyDataExtractor {
         let attributes = self.extract_attributes_legacy(&pieces)?;
         let vertices = self.extract_vertices(&piece.points);
         let (indices, triangle_to_cell_mapping) = self.process_polydata(pieces.clone())?;
-
-        let mut geometry = GeometryData::new(vertices, indices, attributes);
+        // Vertices precede lines in the cell numbering
+        let vert_count = piece.verts.as_ref().map_or(0, |verts| verts.num_cells());
+        let lines = piece
+            .lines
+            .as_ref()
+            .map(|lines| triangulation::polylines(lines, vert_count))
+            .unwrap_or_default();
+
+        let mut geometry = GeometryData::new(vertices, indices, attributes).add_lines(lines);
         geometry.extract_lookup_tables(); // Extract lookup tables
         geometry = geometry.add_triangle_to_cell_mapping(triangle_to_cell_mapping);
 
-        Ok(geometry)
+        // Drop ghost cells of partitioned datasets
+        let cell_ghosts = ghost::ghost_flags(&piece.data.cell);
+        let point_ghosts = ghost::ghost_flags(&piece.data.point);
+        Ok(ghost::remove_ghosts(
+            geometry,
+            cell_ghosts.as_deref(),
+            point_ghosts.as_deref(),
+        ))
     }
 
     fn process_data_array(
@@ -459,7 +713,7 @@ impl VtkMeshExtractor for PolyDataExtractor {
         elem_type: &model::ElementType,
         data: &IOBuffer,
     ) -> Result<(String, AttributeType), VtkError> {
-        let values = data.cast_into::<f32>().unwrap();
+        let values = buffer_to_f32(data);
 
         match elem_type {
             model::ElementType::Scalars {
@@ -484,7 +738,7 @@ impl VtkMeshExtractor for PolyDataExtractor {
                 ))
             }
             model::ElementType::ColorScalars(nvalues) => {
-                let color_values = values
+                let color_values = color_components(data)
                     .chunks_exact(*nvalues as usize)
                     .map(|v| v.to_vec())
                     .collect();
@@ -543,7 +797,7 @@ impl VtkMeshExtractor for PolyDataExtractor {
                 Ok((name.to_string(), AttributeType::Vector(tensors)))
             }
             model::ElementType::LookupTable => {
-                let colors: Vec<[f32; 4]> = values
+                let colors: Vec<[f32; 4]> = color_components(data)
                     .chunks_exact(4)
                     .map(|chunk| [chunk[0], chunk[1], chunk[2], chunk[3]])
                     .collect();
@@ -589,21 +843,30 @@ impl PolyDataExtractor {
         let mut indices = Vec::<u32>::new();
         let mut triangle_to_cell_mapping = Vec::<usize>::new();
 
-        // Process vertex topology (skip, because they don't form a surface)
-        if let Some(_) = piece.verts {
-            println!("find verts - skip, because they don't form a surface");
+        // Process vertex topology (no surface, the points are rendered as splats)
+        let has_verts = piece.verts.is_some();
+        if has_verts {
+            println!("find verts - no surface, kept as point data");
         }
 
-        // Process line topology (skip, because they don't form a surface)
-        if let Some(_) = piece.lines {
-            println!("find lines - skip, because they don't form a surface");
+        // Process line topology (no surface, the lines are kept for tubes)
+        let has_lines = piece.lines.is_some();
+        if has_lines {
+            println!("find lines - no surface, kept as polylines");
         }
 
+        // Vertices and lines precede polygons in the cell numbering
+        let cell_offset = [&piece.verts, &piece.lines]
+            .iter()
+            .filter_map(|topology| topology.as_ref())
+            .map(|topology| topology.num_cells())
+            .sum::<usize>();
+
         // Process polygon topology - main processing logic
         if let Some(polys) = piece.polys {
             let (polys_indices, polys_mapping) = self.triangulate_polygon(polys);
             indices.extend(polys_indices);
-            triangle_to_cell_mapping.extend(polys_mapping);
+            triangle_to_cell_mapping.extend(polys_mapping.iter().map(|cell| cell + cell_offset));
         }
 
         // Process triangle strips (not implemented yet)
@@ -612,7 +875,8 @@ impl PolyDataExtractor {
             // todo!()
         }
 
-        if indices.is_empty() {
+        // Point-only data (particles) is kept without triangles
+        if indices.is_empty() && !has_verts && !has_lines {
             return Err(VtkError::MissingData(
                 "No surface geometry found in the fragment",
             ));
@@ -626,3 +890,705 @@ impl PolyDataExtractor {
         triangulation::triangulate_polygon(topology)
     }
 }
+
+/// Extractor for XML UnstructuredGrid (`.vtu`) files
+///
+/// Unlike the legacy extractor it reads every piece of the file and keeps the
+/// generic data arrays of ParaView exports, which carry no legacy attribute type.
+/// The DataArray payloads (ascii, base64 binary, appended raw) are decoded by
+/// vtkio; compressed blocks are inflated in [`import_vtk_file`].
+pub struct XmlUnstructuredGridExtractor;
+
+impl XmlUnstructuredGridExtractor {
+    /// Build the geometry of all pieces
+    ///
+    /// # Parameters
+    /// - `pieces`: Pieces of the UnstructuredGrid dataset
+    ///
+    /// # Returns
+    /// - `Ok(GeometryData)`: Merged geometry of the inline pieces
+    /// - `Err(VtkError)`: The file has no inline piece
+    pub fn process_xml(
+        &self,
+        pieces: Vec<model::Piece<model::UnstructuredGridPiece>>,
+    ) -> Result<GeometryData, VtkError> {
+        let mut parts = Vec::new();
+        let count = pieces.len();
+        for (index, piece) in pieces.into_iter().enumerate() {
+            let model::Piece::Inline(piece) = piece else {
+                println!("Skipping piece {}: only inline pieces are supported", index);
+                continue;
+            };
+            progress::check_cancelled()?;
+            parts.push(self.process_piece(*piece, (index, count)));
+        }
+
+        if parts.is_empty() {
+            return Err(VtkError::MissingData("No inline pieces found"));
+        }
+        println!("Extracted {} XML UnstructuredGrid piece(s)", parts.len());
+        Ok(merge_pieces(parts))
+    }
+
+    /// Geometry of a single piece, without its ghost cells
+    ///
+    /// `(index, count)` is the piece being extracted and the number of pieces,
+    /// for the load progress.
+    fn process_piece(
+        &self,
+        piece: model::UnstructuredGridPiece,
+        (index, count): (usize, usize),
+    ) -> GeometryData {
+        progress::report_piece_stage(index, count, 0.0, "Reading points");
+        let vertices = UnstructuredGridExtractor.extract_vertices(&piece.points);
+        let cell_ghosts = ghost::ghost_flags(&piece.data.cell);
+        let point_ghosts = ghost::ghost_flags(&piece.data.point);
+
+        progress::report_piece_stage(index, count, 0.1, "Building attributes");
+        let attributes = piece_attributes(&piece.data);
+
+        progress::report_piece_stage(index, count, 0.3, "Triangulating cells");
+        let volume_cells = VolumeCells::from_vtk(&piece.cells);
+        let cell_types = triangulation::cell_type_ids(&piece.cells);
+        let lines = triangulation::cell_polylines(&piece.cells);
+        let (indices, triangle_to_cell_mapping, quadratic_triangles, quadratic_edges) =
+            triangulation::triangulate_cells(piece.cells);
+
+        let mut geometry = GeometryData::new(vertices, indices, attributes)
+            .add_volume_cells(volume_cells)
+            .add_cell_types(cell_types)
+            .add_lines(lines);
+        geometry.extract_lookup_tables();
+        geometry = geometry.add_triangle_to_cell_mapping(triangle_to_cell_mapping);
+        if !quadratic_triangles.is_empty() {
+            geometry = geometry.add_quadratic_triangles(quadratic_triangles);
+        }
+        if !quadratic_edges.is_empty() {
+            geometry = geometry.add_quadratic_edges(quadratic_edges);
+        }
+
+        ghost::remove_ghosts(geometry, cell_ghosts.as_deref(), point_ghosts.as_deref())
+    }
+}
+
+/// Extractor for XML PolyData (`.vtp`) files
+///
+/// Polygons and triangle strips become triangles. Vertices and lines have no
+/// surface but are counted as cells, so cell data stays aligned with the VTK
+/// cell order (verts, lines, polys, strips). Point-only pieces are kept as
+/// point sets.
+pub struct PolyDataXmlExtractor;
+
+impl PolyDataXmlExtractor {
+    /// Build the geometry of all pieces
+    ///
+    /// # Parameters
+    /// - `pieces`: Pieces of the PolyData dataset
+    ///
+    /// # Returns
+    /// - `Ok(GeometryData)`: Merged geometry of the inline pieces
+    /// - `Err(VtkError)`: The file has no inline piece
+    pub fn process_xml(
+        &self,
+        pieces: Vec<model::Piece<model::PolyDataPiece>>,
+    ) -> Result<GeometryData, VtkError> {
+        let mut parts = Vec::new();
+        for (index, piece) in pieces.into_iter().enumerate() {
+            let model::Piece::Inline(piece) = piece else {
+                println!("Skipping piece {}: only inline pieces are supported", index);
+                continue;
+            };
+            parts.push(self.process_piece(*piece));
+        }
+
+        if parts.is_empty() {
+            return Err(VtkError::MissingData("No inline pieces found"));
+        }
+        println!("Extracted {} XML PolyData piece(s)", parts.len());
+        Ok(merge_pieces(parts))
+    }
+
+    /// Geometry of a single piece, without its ghost cells
+    fn process_piece(&self, piece: model::PolyDataPiece) -> GeometryData {
+        let vertices = PolyDataExtractor.extract_vertices(&piece.points);
+        let cell_ghosts = ghost::ghost_flags(&piece.data.cell);
+        let point_ghosts = ghost::ghost_flags(&piece.data.point);
+
+        let attributes = piece_attributes(&piece.data);
+
+        // Vertices and lines precede polygons and strips in the cell numbering
+        let num_cells = |topology: &Option<model::VertexNumbers>| {
+            topology.as_ref().map_or(0, |topology| topology.num_cells())
+        };
+        let vert_count = num_cells(&piece.verts);
+        let lines = piece
+            .lines
+            .as_ref()
+            .map(|lines| triangulation::polylines(lines, vert_count))
+            .unwrap_or_default();
+        let mut cell_offset = vert_count + num_cells(&piece.lines);
+        if cell_offset > 0 {
+            println!(
+                "PolyData piece has {} vertex/line cells without surface",
+                cell_offset
+            );
+        }
+
+        let mut indices = Vec::new();
+        let mut triangle_to_cell_mapping = Vec::new();
+        let poly_count = num_cells(&piece.polys);
+        if let Some(polys) = piece.polys {
+            let (poly_indices, poly_mapping) = triangulation::triangulate_polygon(polys);
+            indices.extend(poly_indices);
+            triangle_to_cell_mapping.extend(poly_mapping.iter().map(|cell| cell + cell_offset));
+        }
+        cell_offset += poly_count;
+        if let Some(strips) = piece.strips {
+            let (strip_indices, strip_mapping) = triangulation::triangulate_strips(strips);
+            indices.extend(strip_indices);
+            triangle_to_cell_mapping.extend(strip_mapping.iter().map(|cell| cell + cell_offset));
+        }
+
+        let mut geometry = GeometryData::new(vertices, indices, attributes).add_lines(lines);
+        geometry.extract_lookup_tables();
+        geometry = geometry.add_triangle_to_cell_mapping(triangle_to_cell_mapping);
+
+        ghost::remove_ghosts(geometry, cell_ghosts.as_deref(), point_ghosts.as_deref())
+    }
+}
+
+/// Extractor for structured grid (`.vts` and legacy STRUCTURED_GRID) files
+///
+/// Structured grids are blocks of hexahedra; only their outer faces are
+/// triangulated, with every surface triangle mapped to its boundary cell.
+pub struct StructuredGridExtractor;
+
+impl StructuredGridExtractor {
+    /// Build the surface geometry of all pieces
+    ///
+    /// # Parameters
+    /// - `pieces`: Pieces of the StructuredGrid dataset
+    ///
+    /// # Returns
+    /// - `Ok(GeometryData)`: Merged geometry of the inline pieces
+    /// - `Err(VtkError)`: No inline piece, or a piece whose points do not match its extent
+    pub fn process(
+        &self,
+        pieces: Vec<model::Piece<model::StructuredGridPiece>>,
+    ) -> Result<GeometryData, VtkError> {
+        let mut parts = Vec::new();
+        for (index, piece) in pieces.into_iter().enumerate() {
+            let model::Piece::Inline(piece) = piece else {
+                println!("Skipping piece {}: only inline pieces are supported", index);
+                continue;
+            };
+            let vertices = UnstructuredGridExtractor.extract_vertices(&piece.points);
+            parts.push(structured_piece(
+                vertices,
+                piece.extent.into_dims(),
+                &piece.data,
+            )?);
+        }
+
+        if parts.is_empty() {
+            return Err(VtkError::MissingData("No inline pieces found"));
+        }
+        println!("Extracted {} StructuredGrid piece(s)", parts.len());
+        Ok(merge_pieces(parts))
+    }
+}
+
+/// Extractor for rectilinear grid (`.vtr` and legacy RECTILINEAR_GRID) files
+///
+/// The grid points are the tensor product of the x, y and z coordinate arrays;
+/// the surface is built like that of a structured grid.
+pub struct RectilinearGridExtractor;
+
+impl RectilinearGridExtractor {
+    /// Build the surface geometry of all pieces
+    ///
+    /// # Parameters
+    /// - `pieces`: Pieces of the RectilinearGrid dataset
+    ///
+    /// # Returns
+    /// - `Ok(GeometryData)`: Merged geometry of the inline pieces
+    /// - `Err(VtkError)`: No inline piece, or coordinates that do not match the extent
+    pub fn process(
+        &self,
+        pieces: Vec<model::Piece<model::RectilinearGridPiece>>,
+    ) -> Result<GeometryData, VtkError> {
+        let mut parts = Vec::new();
+        for (index, piece) in pieces.into_iter().enumerate() {
+            let model::Piece::Inline(piece) = piece else {
+                println!("Skipping piece {}: only inline pieces are supported", index);
+                continue;
+            };
+            let vertices = self.grid_points(&piece.coords)?;
+            parts.push(structured_piece(
+                vertices,
+                piece.extent.into_dims(),
+                &piece.data,
+            )?);
+        }
+
+        if parts.is_empty() {
+            return Err(VtkError::MissingData("No inline pieces found"));
+        }
+        println!("Extracted {} RectilinearGrid piece(s)", parts.len());
+        Ok(merge_pieces(parts))
+    }
+
+    /// Grid points from the coordinate arrays, x varying fastest
+    fn grid_points(&self, coords: &model::Coordinates) -> Result<Vec<[f32; 3]>, VtkError> {
+        let (x, y, z) = (
+            buffer_to_f32(&coords.x),
+            buffer_to_f32(&coords.y),
+            buffer_to_f32(&coords.z),
+        );
+
+        let mut points = Vec::with_capacity(x.len() * y.len() * z.len());
+        for &pz in &z {
+            for &py in &y {
+                for &px in &x {
+                    points.push([px, py, pz]);
+                }
+            }
+        }
+        Ok(points)
+    }
+}
+
+/// Extractor for image data (`.vti` and legacy STRUCTURED_POINTS) files
+///
+/// The pieces are assembled into one [`VolumeData`] covering the whole extent;
+/// points shared by neighboring pieces are taken from the last piece.
+pub struct ImageDataExtractor;
+
+impl ImageDataExtractor {
+    /// Assemble the volume of all pieces
+    ///
+    /// # Parameters
+    /// - `extent`: Whole extent of the dataset
+    /// - `origin`: Position of the point at extent index (0, 0, 0)
+    /// - `spacing`: Distance between neighboring points
+    /// - `pieces`: Pieces of the ImageData dataset
+    ///
+    /// # Returns
+    /// - `Ok(VolumeData)`: Voxel data of the whole extent
+    /// - `Err(VtkError)`: The file has no inline piece
+    pub fn process(
+        &self,
+        extent: model::Extent,
+        origin: [f32; 3],
+        spacing: [f32; 3],
+        pieces: Vec<model::Piece<model::ImageDataPiece>>,
+    ) -> Result<VolumeData, VtkError> {
+        let whole = extent.into_range_extent();
+        let start = whole.clone().map(|range| *range.start());
+        let mut volume = VolumeData {
+            dims: whole.map(|range| (range.end() - range.start() + 1).max(1) as usize),
+            origin: std::array::from_fn(|axis| origin[axis] + spacing[axis] * start[axis] as f32),
+            spacing,
+            attributes: HashMap::new(),
+        };
+
+        let mut point_source = vec![0; volume.point_count()];
+        let mut cell_source = vec![0; volume.cell_dims().iter().product()];
+        let mut point_offset = 0;
+        let mut cell_offset = 0;
+        let mut piece_count = 0;
+
+        for (index, piece) in pieces.into_iter().enumerate() {
+            let model::Piece::Inline(piece) = piece else {
+                println!("Skipping piece {}: only inline pieces are supported", index);
+                continue;
+            };
+            let range = piece.extent.into_range_extent();
+            let offset: [usize; 3] =
+                std::array::from_fn(|axis| (range[axis].start() - start[axis]).max(0) as usize);
+            let dims = range.map(|range| (range.end() - range.start() + 1).max(1) as usize);
+            let cell_dims = dims.map(|n| n.saturating_sub(1).max(1));
+
+            // Where each element of the piece goes in the whole volume
+            let mut local = 0;
+            for k in 0..dims[2] {
+                for j in 0..dims[1] {
+                    for i in 0..dims[0] {
+                        let p = [i + offset[0], j + offset[1], k + offset[2]];
+                        if p.iter().zip(&volume.dims).all(|(p, n)| p < n) {
+                            point_source[volume.point_index(p)] = point_offset + local;
+                        }
+                        local += 1;
+                    }
+                }
+            }
+            point_offset += local;
+            let whole_cell_dims = volume.cell_dims();
+            local = 0;
+            for k in 0..cell_dims[2] {
+                for j in 0..cell_dims[1] {
+                    for i in 0..cell_dims[0] {
+                        let c = [i + offset[0], j + offset[1], k + offset[2]];
+                        if c.iter().zip(&whole_cell_dims).all(|(c, n)| c < n) {
+                            cell_source[volume.cell_index(c)] = cell_offset + local;
+                        }
+                        local += 1;
+                    }
+                }
+            }
+            cell_offset += local;
+
+            // Concatenate the piece data, reordered once all pieces are known
+            let attributes = piece_attributes(&piece.data);
+            if piece_count == 0 {
+                volume.attributes = attributes;
+            } else {
+                volume
+                    .attributes
+                    .retain(|key, attribute| match attributes.get(key) {
+                        Some(_) if key.0.starts_with("__lut_") => true,
+                        Some(other) => append_attribute(attribute, other),
+                        None => false,
+                    });
+            }
+            piece_count += 1;
+        }
+
+        if piece_count == 0 {
+            return Err(VtkError::MissingData("No inline pieces found"));
+        }
+        if piece_count > 1 {
+            for ((name, location), attribute) in volume.attributes.iter_mut() {
+                if name.starts_with("__lut_") {
+                    continue;
+                }
+                let source = match location {
+                    AttributeLocation::Point => &point_source,
+                    AttributeLocation::Cell => &cell_source,
+                };
+                *attribute = volume::gather_elements(attribute, source);
+            }
+        }
+
+        println!(
+            "Extracted ImageData volume: {:?} points from {} piece(s)",
+            volume.dims, piece_count
+        );
+        Ok(volume)
+    }
+}
+
+/// Import an image data file as a volume
+///
+/// # Parameters
+/// - `path`: VTK file path (`.vti`, or legacy STRUCTURED_POINTS `.vtk`)
+///
+/// # Returns
+/// - `Ok(VolumeData)`: Voxel data of the file
+/// - `Err(VtkError)`: Import failed or the file does not contain image data
+pub fn load_vtk_volume(path: &std::path::Path) -> Result<VolumeData, VtkError> {
+    let vtk = import_vtk_file(path)?;
+
+    match vtk.data {
+        model::DataSet::ImageData {
+            extent,
+            origin,
+            spacing,
+            pieces,
+            ..
+        } => ImageDataExtractor.process(extent, origin, spacing, pieces),
+        _ => Err(VtkError::UnsupportedDataType),
+    }
+}
+
+/// Surface geometry of a structured piece, without its ghost cells
+///
+/// # Parameters
+/// - `vertices`: Grid points, x varying fastest
+/// - `dims`: Number of points along x, y and z
+/// - `data`: Point and cell data of the piece
+///
+/// # Returns
+/// - `Ok(GeometryData)`: Surface geometry with the attributes of the piece
+/// - `Err(VtkError)`: The number of points does not match the dimensions
+fn structured_piece(
+    vertices: Vec<[f32; 3]>,
+    dims: [u32; 3],
+    data: &model::Attributes,
+) -> Result<GeometryData, VtkError> {
+    let dims = dims.map(|n| n as usize);
+    if vertices.len() != dims.iter().product::<usize>() {
+        println!(
+            "Structured piece has {} points, extent {:?} requires {}",
+            vertices.len(),
+            dims,
+            dims.iter().product::<usize>()
+        );
+        return Err(VtkError::InvalidFormat(
+            "Point count does not match the grid extent",
+        ));
+    }
+
+    let cell_ghosts = ghost::ghost_flags(&data.cell);
+    let point_ghosts = ghost::ghost_flags(&data.point);
+    let (indices, triangle_to_cell_mapping) = triangulation::triangulate_structured(dims);
+
+    let mut geometry = GeometryData::new(vertices, indices, piece_attributes(data));
+    geometry.extract_lookup_tables();
+    geometry = geometry.add_triangle_to_cell_mapping(triangle_to_cell_mapping);
+
+    Ok(ghost::remove_ghosts(
+        geometry,
+        cell_ghosts.as_deref(),
+        point_ghosts.as_deref(),
+    ))
+}
+
+/// Point and cell attributes of a piece
+fn piece_attributes(
+    data: &model::Attributes,
+) -> HashMap<(String, AttributeLocation), AttributeType> {
+    let mut attributes = HashMap::new();
+    for (location, data) in [
+        (AttributeLocation::Point, &data.point),
+        (AttributeLocation::Cell, &data.cell),
+    ] {
+        for (name, attribute) in xml_attributes(data) {
+            attributes.insert((name, location.clone()), attribute);
+        }
+    }
+    attributes
+}
+
+/// Convert the data arrays of XML point or cell data into attributes
+///
+/// Arrays with a legacy attribute type are processed like legacy data; generic
+/// arrays become scalars, vectors (3 components) or tensors (9 components).
+/// Field data arrays are treated as generic arrays.
+///
+/// # Parameters
+/// - `attributes`: Point or cell data of a piece
+///
+/// # Returns
+/// - Attribute name and data of every supported array
+pub fn xml_attributes(attributes: &[model::Attribute]) -> Vec<(String, AttributeType)> {
+    let mut result = Vec::new();
+    for attribute in attributes {
+        match attribute {
+            model::Attribute::DataArray(array) => match array.elem {
+                model::ElementType::Generic(num_comp) => {
+                    if let Some(attribute) = generic_attribute(&array.data, num_comp) {
+                        result.push((array.name.clone(), attribute));
+                    }
+                }
+                ref elem => {
+                    if let Ok(named) =
+                        UnstructuredGridExtractor.process_data_array(&array.name, elem, &array.data)
+                    {
+                        result.push(named);
+                    }
+                }
+            },
+            model::Attribute::Field { data_array, .. } => {
+                for array in data_array {
+                    if let Some(attribute) = generic_attribute(&array.data, array.elem) {
+                        result.push((array.name.clone(), attribute));
+                    }
+                }
+            }
+        }
+    }
+    result
+}
+
+/// Attribute of a generic data array with the given number of components
+pub fn generic_attribute(data: &IOBuffer, num_comp: u32) -> Option<AttributeType> {
+    let values = buffer_to_f32(data);
+    let attribute = match num_comp {
+        3 => AttributeType::Vector(
+            values
+                .chunks_exact(3)
+                .map(|chunk| [chunk[0], chunk[1], chunk[2]])
+                .collect(),
+        ),
+        9 => AttributeType::Tensor(
+            values
+                .chunks_exact(9)
+                .map(|chunk| std::array::from_fn(|i| chunk[i]))
+                .collect(),
+        ),
+        _ => AttributeType::Scalar {
+            num_comp: num_comp.max(1) as usize,
+            table_name: "default".to_string(),
+            data: values,
+            lookup_table: None,
+        },
+    };
+    Some(attribute)
+}
+
+/// Merge the geometry of several pieces into one
+///
+/// Vertex indices and cell numbers of later pieces are offset. Attributes are
+/// concatenated if every piece has them with the same type; others are dropped.
+///
+/// # Parameters
+/// - `parts`: Geometry of every piece
+///
+/// # Returns
+/// - Merged geometry
+pub fn merge_pieces(mut parts: Vec<GeometryData>) -> GeometryData {
+    if parts.len() == 1 {
+        return parts.remove(0);
+    }
+
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+    let mut mapping = Vec::new();
+    let mut quadratic_triangles = Vec::new();
+    let mut quadratic_edges = Vec::new();
+    let mut volume_cells: Option<VolumeCells> = None;
+    let mut lines = Vec::new();
+    // Cell types are kept only if every piece has them
+    let mut cell_types = Some(Vec::new());
+    let mut lookup_tables = HashMap::new();
+    let mut normals = Some(Vec::new());
+    let mut uvs = Some(Vec::new());
+    let mut attributes: Option<HashMap<(String, AttributeLocation), AttributeType>> = None;
+    let mut cell_offset = 0;
+
+    for part in parts {
+        let vertex_offset = vertices.len() as u32;
+        let cell_count = part_cell_count(&part);
+
+        indices.extend(part.indices.iter().map(|index| index + vertex_offset));
+        mapping.extend(
+            (0..part.indices.len() / 3)
+                .map(|triangle| topology::triangle_cell(&part, triangle) + cell_offset),
+        );
+        for mut triangle in part.quadratic_triangles.unwrap_or_default() {
+            triangle.vertices = triangle.vertices.map(|v| v + vertex_offset);
+            quadratic_triangles.push(triangle);
+        }
+        for mut edge in part.quadratic_edges.unwrap_or_default() {
+            edge.vertices = edge.vertices.map(|v| v + vertex_offset);
+            quadratic_edges.push(edge);
+        }
+        for mut line in part.lines.unwrap_or_default() {
+            line.cell += cell_offset;
+            line.points
+                .iter_mut()
+                .for_each(|point| *point += vertex_offset);
+            lines.push(line);
+        }
+        if let Some(part_cells) = &part.volume_cells {
+            volume_cells
+                .get_or_insert_with(VolumeCells::default)
+                .append(part_cells, vertex_offset, cell_offset);
+        }
+        cell_types = cell_types
+            .zip(part.cell_types.filter(|types| types.len() == cell_count))
+            .map(|(mut all, part)| {
+                all.extend(part);
+                all
+            });
+        normals = normals.zip(part.normals).map(|(mut all, part)| {
+            all.extend(part);
+            all
+        });
+        uvs = uvs.zip(part.uvs).map(|(mut all, part)| {
+            all.extend(part);
+            all
+        });
+        lookup_tables.extend(part.lookup_tables);
+
+        let part_attributes = part.attributes.unwrap_or_default();
+        attributes = Some(match attributes {
+            None => part_attributes,
+            Some(mut merged) => {
+                merged.retain(|key, attribute| match part_attributes.get(key) {
+                    Some(_) if key.0.starts_with("__lut_") => true,
+                    Some(other) => append_attribute(attribute, other),
+                    None => {
+                        println!("Dropped attribute '{}': missing in a piece", key.0);
+                        false
+                    }
+                });
+                merged
+            }
+        });
+
+        vertices.extend(part.vertices);
+        cell_offset += cell_count;
+    }
+
+    let mut geometry = GeometryData::new(vertices, indices, attributes.unwrap_or_default())
+        .add_triangle_to_cell_mapping(mapping)
+        .add_volume_cells(volume_cells)
+        .add_lines(lines);
+    geometry.cell_types = cell_types;
+    geometry.lookup_tables = lookup_tables;
+    if !quadratic_triangles.is_empty() {
+        geometry = geometry.add_quadratic_triangles(quadratic_triangles);
+    }
+    if !quadratic_edges.is_empty() {
+        geometry = geometry.add_quadratic_edges(quadratic_edges);
+    }
+    if let Some(normals) = normals.filter(|normals| normals.len() == geometry.vertices.len()) {
+        geometry = geometry.add_normals(normals);
+    }
+    if let Some(uvs) = uvs.filter(|uvs| uvs.len() == geometry.vertices.len()) {
+        geometry = geometry.add_uvs(uvs);
+    }
+    geometry
+}
+
+/// Number of cells of a piece
+///
+/// Taken from the cell attributes when available, as trailing cells without
+/// triangles (vertices, lines) are not visible in the triangle-to-cell mapping.
+fn part_cell_count(part: &GeometryData) -> usize {
+    part.attributes
+        .as_ref()
+        .and_then(|attributes| {
+            attributes
+                .iter()
+                .find(|((name, location), _)| {
+                    *location == AttributeLocation::Cell && !name.starts_with("__lut_")
+                })
+                .map(|(_, attribute)| topology::element_count(attribute))
+        })
+        .unwrap_or_else(|| topology::cell_count(part))
+}
+
+/// Append the values of another piece to an attribute
+///
+/// # Returns
+/// - `false` if the attributes have different types or component counts
+fn append_attribute(attribute: &mut AttributeType, other: &AttributeType) -> bool {
+    match (attribute, other) {
+        (
+            AttributeType::Scalar { num_comp, data, .. },
+            AttributeType::Scalar {
+                num_comp: other_comp,
+                data: other_data,
+                ..
+            },
+        ) if num_comp == other_comp => data.extend_from_slice(other_data),
+        (
+            AttributeType::ColorScalar { nvalues, data },
+            AttributeType::ColorScalar {
+                nvalues: other_nvalues,
+                data: other_data,
+            },
+        ) if nvalues == other_nvalues => data.extend_from_slice(other_data),
+        (AttributeType::Vector(data), AttributeType::Vector(other_data)) => {
+            data.extend_from_slice(other_data)
+        }
+        (AttributeType::Tensor(data), AttributeType::Tensor(other_data)) => {
+            data.extend_from_slice(other_data)
+        }
+        _ => return false,
+    }
+    true
+}
@@ -0,0 +1,131 @@
+//! Warp module
+//!
+//! Displacement of the geometry by attribute values:
+//! - Warp by scalar, used for height field views of 2D data
+//! - Warp by vector, used for displacement results of FE analyses
+use super::topology::{attribute_value, triangle_cell};
+use super::{AttributeLocation, AttributeType, GeometryData};
+use bevy::math::Vec3;
+
+/// Relative thickness below which a dataset counts as flat
+const FLAT_TOLERANCE: f32 = 1e-4;
+
+/// Normal of a dataset that is flat along one coordinate axis
+///
+/// # Parameters
+/// - `geometry`: Geometry data
+///
+/// # Returns
+/// - Axis the data is flat along (+X, +Y or +Z), or `None` for 3D data
+pub fn flat_axis(geometry: &GeometryData) -> Option<Vec3> {
+    let first = Vec3::from(*geometry.vertices.first()?);
+    let (min, max) = geometry
+        .vertices
+        .iter()
+        .fold((first, first), |(min, max), v| {
+            (min.min(Vec3::from(*v)), max.max(Vec3::from(*v)))
+        });
+    let extent = max - min;
+    if extent.max_element() <= f32::EPSILON {
+        return None;
+    }
+
+    let tolerance = extent.max_element() * FLAT_TOLERANCE;
+    [Vec3::X, Vec3::Y, Vec3::Z]
+        .into_iter()
+        .find(|axis| extent.dot(*axis) <= tolerance)
+}
+
+/// Value of a scalar attribute at every vertex
+///
+/// Cell values are averaged over the triangles around each vertex.
+///
+/// # Parameters
+/// - `geometry`: Geometry data
+/// - `attribute`: Point or cell attribute
+/// - `location`: Attribute location
+///
+/// # Returns
+/// - One value per vertex (vectors evaluate to their magnitude)
+pub fn point_values(
+    geometry: &GeometryData,
+    attribute: &AttributeType,
+    location: &AttributeLocation,
+) -> Vec<f32> {
+    match location {
+        AttributeLocation::Point => (0..geometry.vertices.len())
+            .map(|point| attribute_value(attribute, point, None))
+            .collect(),
+        AttributeLocation::Cell => {
+            let mut sums = vec![0.0; geometry.vertices.len()];
+            let mut counts = vec![0u32; geometry.vertices.len()];
+            for (triangle, corners) in geometry.indices.chunks_exact(3).enumerate() {
+                let value = attribute_value(attribute, triangle_cell(geometry, triangle), None);
+                for &corner in corners {
+                    sums[corner as usize] += value;
+                    counts[corner as usize] += 1;
+                }
+            }
+            sums.iter()
+                .zip(&counts)
+                .map(|(sum, &count)| if count > 0 { sum / count as f32 } else { 0.0 })
+                .collect()
+        }
+    }
+}
+
+/// Displace the vertices along a direction by their scalar value
+///
+/// # Parameters
+/// - `vertices`: Vertex positions
+/// - `values`: Scalar value per vertex
+/// - `direction`: Displacement direction (unit length)
+/// - `base`: Value without displacement
+/// - `scale`: Displacement per value unit
+///
+/// # Returns
+/// - Displaced vertex positions
+pub fn warp_by_scalar(
+    vertices: &[[f32; 3]],
+    values: &[f32],
+    direction: Vec3,
+    base: f32,
+    scale: f32,
+) -> Vec<[f32; 3]> {
+    vertices
+        .iter()
+        .zip(values)
+        .map(|(vertex, value)| {
+            (Vec3::from(*vertex) + direction * (value - base) * scale).to_array()
+        })
+        .collect()
+}
+
+/// Displace the vertices by a vector per vertex
+///
+/// # Parameters
+/// - `vertices`: Vertex positions
+/// - `vectors`: Displacement vector per vertex
+/// - `scale`: Factor applied to the displacement
+///
+/// # Returns
+/// - Displaced vertex positions (vertices without a vector stay in place)
+pub fn warp_by_vector(vertices: &[[f32; 3]], vectors: &[[f32; 3]], scale: f32) -> Vec<[f32; 3]> {
+    vertices
+        .iter()
+        .enumerate()
+        .map(|(point, vertex)| match vectors.get(point) {
+            Some(vector) => (Vec3::from(*vertex) + Vec3::from(*vector) * scale).to_array(),
+            None => *vertex,
+        })
+        .collect()
+}
+
+/// Largest vector magnitude, ignoring non-finite vectors
+pub fn max_magnitude(vectors: &[[f32; 3]]) -> f32 {
+    vectors
+        .iter()
+        .map(|vector| Vec3::from(*vector).length())
+        .filter(|magnitude| magnitude.is_finite())
+        .fold(0.0, f32::max)
+}
@@ -0,0 +1,159 @@
+//! Weld module
+//!
+//! Merging of coincident points, e.g. the duplicated boundary points of
+//! partitioned datasets or the points repeated per cell by many exports.
+//! Points closer than a tolerance are found with a hash grid and replaced by
+//! the first of them.
+use super::volume::gather_elements;
+use super::{AttributeLocation, GeometryData};
+use bevy::utils::HashMap;
+
+/// Merge coincident points
+///
+/// The first point of a group of coincident points is kept, with its point
+/// attributes. Triangles that collapse to a line or point are removed.
+///
+/// # Parameters
+/// - `geometry`: Geometry data
+/// - `tolerance`: Maximum distance of merged points (0 = identical coordinates)
+///
+/// # Returns
+/// - Geometry with merged points
+pub fn weld_points(mut geometry: GeometryData, tolerance: f32) -> GeometryData {
+    let (remap, kept) = coincident_points(&geometry.vertices, tolerance);
+    if kept.len() == geometry.vertices.len() {
+        return geometry;
+    }
+    println!(
+        "Welded {} duplicate points ({} remain)",
+        geometry.vertices.len() - kept.len(),
+        kept.len()
+    );
+
+    // Remap the triangles, dropping collapsed ones with their cell mapping
+    let mapping = geometry.triangle_to_cell_mapping.take();
+    let mut indices = Vec::with_capacity(geometry.indices.len());
+    let mut triangle_to_cell_mapping = Vec::with_capacity(geometry.indices.len() / 3);
+    for (triangle, corners) in geometry.indices.chunks_exact(3).enumerate() {
+        let [a, b, c] = [0, 1, 2].map(|i| remap[corners[i] as usize]);
+        if a == b || b == c || a == c {
+            continue;
+        }
+        indices.extend_from_slice(&[a, b, c]);
+        triangle_to_cell_mapping.push(
+            mapping
+                .as_ref()
+                .and_then(|mapping| mapping.get(triangle).copied())
+                .unwrap_or(triangle),
+        );
+    }
+    geometry.indices = indices;
+    geometry.triangle_to_cell_mapping = Some(triangle_to_cell_mapping);
+
+    if let Some(triangles) = geometry.quadratic_triangles.as_mut() {
+        for triangle in triangles.iter_mut() {
+            triangle.vertices = triangle.vertices.map(|v| remap[v as usize]);
+        }
+    }
+    if let Some(edges) = geometry.quadratic_edges.as_mut() {
+        for edge in edges.iter_mut() {
+            edge.vertices = edge.vertices.map(|v| remap[v as usize]);
+        }
+    }
+    if let Some(volume_cells) = geometry.volume_cells.as_mut() {
+        volume_cells.remap_points(|point| remap.get(point as usize).copied());
+    }
+    if let Some(lines) = geometry.lines.as_mut() {
+        for line in lines.iter_mut() {
+            line.points = line.points.iter().map(|&v| remap[v as usize]).collect();
+            line.points.dedup();
+        }
+        lines.retain(|line| line.points.len() >= 2);
+    }
+
+    let pick = |data: &[[f32; 3]]| kept.iter().map(|&v| data[v]).collect::<Vec<_>>();
+    geometry.vertices = pick(&geometry.vertices);
+    if let Some(normals) = geometry.normals.as_mut() {
+        if normals.len() == remap.len() {
+            *normals = pick(normals);
+        } else {
+            geometry.normals = None;
+        }
+    }
+    if let Some(uvs) = geometry.uvs.as_mut() {
+        if uvs.len() == remap.len() {
+            *uvs = kept.iter().map(|&v| uvs[v]).collect();
+        } else {
+            geometry.uvs = None;
+        }
+    }
+    if let Some(attributes) = geometry.attributes.as_mut() {
+        for ((name, location), attribute) in attributes.iter_mut() {
+            if *location == AttributeLocation::Point && !name.starts_with("__lut_") {
+                *attribute = gather_elements(attribute, &kept);
+            }
+        }
+    }
+
+    geometry
+}
+
+/// Find groups of coincident points
+///
+/// # Returns
+/// - New index of every point, and the original index of every kept point
+fn coincident_points(vertices: &[[f32; 3]], tolerance: f32) -> (Vec<u32>, Vec<usize>) {
+    let mut remap = Vec::with_capacity(vertices.len());
+    let mut kept = Vec::new();
+
+    if tolerance <= 0.0 {
+        let mut seen: HashMap<[u32; 3], u32> = HashMap::new();
+        for (index, vertex) in vertices.iter().enumerate() {
+            let key = vertex.map(|c| if c == 0.0 { 0 } else { c.to_bits() });
+            let new_index = *seen.entry(key).or_insert_with(|| {
+                kept.push(index);
+                kept.len() as u32 - 1
+            });
+            remap.push(new_index);
+        }
+        return (remap, kept);
+    }
+
+    // Hash grid with cells of the tolerance size; neighbors are in adjacent cells
+    let cell_of = |vertex: &[f32; 3]| vertex.map(|c| (c / tolerance).floor() as i64);
+    let mut grid: HashMap<[i64; 3], Vec<u32>> = HashMap::new();
+    let tolerance_squared = tolerance * tolerance;
+    for (index, vertex) in vertices.iter().enumerate() {
+        let cell = cell_of(vertex);
+        let mut found = None;
+        'search: for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let neighbor = [cell[0] + dx, cell[1] + dy, cell[2] + dz];
+                    let Some(candidates) = grid.get(&neighbor) else {
+                        continue;
+                    };
+                    for &candidate in candidates {
+                        let other = vertices[kept[candidate as usize]];
+                        let distance_squared = (0..3)
+                            .map(|axis| (vertex[axis] - other[axis]).powi(2))
+                            .sum::<f32>();
+                        if distance_squared <= tolerance_squared {
+                            found = Some(candidate);
+                            break 'search;
+                        }
+                    }
+                }
+            }
+        }
+
+        let new_index = found.unwrap_or_else(|| {
+            kept.push(index);
+            let new_index = kept.len() as u32 - 1;
+            grid.entry(cell).or_default().push(new_index);
+            new_index
+        });
+        remap.push(new_index);
+    }
+    (remap, kept)
+}
@@ -0,0 +1,785 @@
+//! XDMF import module
+//!
+//! Reads XDMF (`.xdmf`, `.xmf`) light data, the XML description of simulation
+//! output whose arrays (heavy data) live in HDF5 or raw binary files:
+//! - Unstructured topologies (triangles to hexahedra, polygons, quadratic and
+//!   `Mixed` cells) with one cell per element
+//! - Structured topologies (`2D/3DSMesh`, `2D/3DRectMesh`, `2D/3DCoRectMesh`)
+//! - Geometry as `XYZ`, `XY`, `X_Y_Z`, `VXVYVZ` or `ORIGIN_DXDYDZ`
+//! - Node and cell attributes (scalars, vectors, tensors)
+//! - `DataItem`s in XML, HDF or Binary format, `HyperSlab` selections and
+//!   references to named items
+//! - Spatial collections (merged into one model) and temporal collections (time
+//!   steps of a series); grids without topology or geometry (shared through
+//!   `xi:include`) use those of the previous grid
+//!
+//! Heavy data is loaded lazily: HDF5 files are memory-mapped and only the arrays
+//! of the shown grid are read.
+use super::compression::attribute;
+use super::exodus::{HEX_FACES, PYRAMID_FACES, TETRA_FACES, WEDGE_FACES};
+use super::hdf5::Hdf5File;
+use super::triangulation::triangulate_structured;
+use super::{vtk, AttributeLocation, AttributeType, GeometryData, VtkError};
+use bevy::utils::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Element of the XML light data
+struct Element {
+    name: String,
+    /// Start tag with normalized whitespace, for attribute lookup
+    tag: String,
+    children: Vec<Element>,
+    text: String,
+}
+
+impl Element {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        attribute(&self.tag, name)
+    }
+
+    fn children_named(&self, name: &'static str) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    fn child(&self, name: &'static str) -> Option<&Element> {
+        self.children_named(name).next()
+    }
+
+    /// First descendant (or self) matching a predicate
+    fn find(&self, predicate: &dyn Fn(&Element) -> bool) -> Option<&Element> {
+        if predicate(self) {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(predicate))
+    }
+}
+
+/// Parse XML into an element tree
+fn parse_xml(text: &str) -> Result<Element, VtkError> {
+    let mut stack = vec![Element {
+        name: String::new(),
+        tag: String::new(),
+        children: Vec::new(),
+        text: String::new(),
+    }];
+    let mut rest = text;
+
+    while let Some(start) = rest.find('<') {
+        stack.last_mut().unwrap().text.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        // Comments, processing instructions and declarations
+        let skip_to = if rest.starts_with("<!--") {
+            Some("-->")
+        } else if rest.starts_with("<?") {
+            Some("?>")
+        } else if rest.starts_with("<!") {
+            Some(">")
+        } else {
+            None
+        };
+        if let Some(end) = skip_to {
+            let length = rest
+                .find(end)
+                .ok_or(VtkError::InvalidFormat("Unterminated XML markup"))?;
+            rest = &rest[length + end.len()..];
+            continue;
+        }
+
+        let end = rest
+            .find('>')
+            .ok_or(VtkError::InvalidFormat("Unterminated XML tag"))?;
+        let tag = rest[1..end].replace(['\n', '\r', '\t'], " ");
+        rest = &rest[end + 1..];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            let element = stack
+                .pop()
+                .filter(|_| !stack.is_empty())
+                .ok_or(VtkError::InvalidFormat("Unbalanced XML end tag"))?;
+            if element.name != name.trim() {
+                return Err(VtkError::InvalidFormat("Mismatched XML end tag"));
+            }
+            stack.last_mut().unwrap().children.push(element);
+            continue;
+        }
+
+        let self_closing = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/').to_string();
+        let element = Element {
+            name: tag.split(' ').next().unwrap_or("").to_string(),
+            tag,
+            children: Vec::new(),
+            text: String::new(),
+        };
+        if self_closing {
+            stack.last_mut().unwrap().children.push(element);
+        } else {
+            stack.push(element);
+        }
+    }
+
+    if stack.len() != 1 {
+        return Err(VtkError::InvalidFormat("Unclosed XML element"));
+    }
+    stack
+        .pop()
+        .and_then(|document| document.children.into_iter().find(|e| e.name == "Xdmf"))
+        .ok_or(VtkError::InvalidFormat("Not an XDMF file"))
+}
+
+/// Dimension list of an attribute, the slowest varying first
+fn dimensions(element: &Element, name: &str) -> Vec<usize> {
+    element
+        .attribute(name)
+        .unwrap_or("")
+        .split_whitespace()
+        .filter_map(|value| value.parse().ok())
+        .collect()
+}
+
+/// Reader of heavy data files, each HDF5 file opened once
+struct HeavyData<'a> {
+    /// Light data document, for references
+    document: &'a Element,
+    /// Directory relative file names are resolved against
+    directory: PathBuf,
+    hdf5_files: HashMap<PathBuf, Hdf5File>,
+}
+
+impl<'a> HeavyData<'a> {
+    /// Values of a data item as `f64`, in row-major order
+    fn read(&mut self, item: &Element) -> Result<Vec<f64>, VtkError> {
+        // Reference to a named item elsewhere in the document
+        if item.attribute("Reference").is_some() {
+            let target = item.text.trim();
+            let name = target
+                .split("@Name=")
+                .nth(1)
+                .map(|name| name.trim_matches(|c| matches!(c, '"' | '\'' | ']' | ' ')))
+                .ok_or_else(|| {
+                    VtkError::LoadError(format!("Unsupported XDMF reference: {}", target))
+                })?;
+            let document = self.document;
+            let referenced = document
+                .find(&|e| {
+                    e.name == "DataItem"
+                        && e.attribute("Reference").is_none()
+                        && e.attribute("Name") == Some(name)
+                })
+                .ok_or_else(|| VtkError::LoadError(format!("XDMF item not found: {}", name)))?;
+            return self.read(referenced);
+        }
+
+        match item.attribute("ItemType").unwrap_or("Uniform") {
+            "Uniform" => {}
+            "HyperSlab" => return self.read_hyperslab(item),
+            other => {
+                return Err(VtkError::LoadError(format!(
+                    "Unsupported XDMF item type: {}",
+                    other
+                )))
+            }
+        }
+
+        let count: usize = dimensions(item, "Dimensions").iter().product();
+        let values = match item.attribute("Format").unwrap_or("XML") {
+            "XML" => item
+                .text
+                .split_whitespace()
+                .map(|value| {
+                    value
+                        .parse::<f64>()
+                        .map_err(|_| VtkError::InvalidFormat("Invalid number in XDMF data"))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            "HDF" => {
+                let text = item.text.trim();
+                let (file, dataset) = text.rsplit_once(':').ok_or(VtkError::InvalidFormat(
+                    "XDMF HDF item without dataset path",
+                ))?;
+                let path = self.directory.join(file.trim());
+                if !self.hdf5_files.contains_key(&path) {
+                    let opened = Hdf5File::open(&path)?;
+                    self.hdf5_files.insert(path.clone(), opened);
+                }
+                let hdf5 = &self.hdf5_files[&path];
+                hdf5.read_dataset(hdf5.find(dataset.trim())?)?.to_f64()?
+            }
+            "Binary" => self.read_binary(item, count)?,
+            other => {
+                return Err(VtkError::LoadError(format!(
+                    "Unsupported XDMF data format: {}",
+                    other
+                )))
+            }
+        };
+
+        if values.len() < count {
+            return Err(VtkError::MissingData("XDMF data item is incomplete"));
+        }
+        Ok(values)
+    }
+
+    /// Values of a raw binary data item
+    fn read_binary(&self, item: &Element, count: usize) -> Result<Vec<f64>, VtkError> {
+        let number_type = item
+            .attribute("NumberType")
+            .or(item.attribute("DataType"))
+            .unwrap_or("Float");
+        let precision: usize = item
+            .attribute("Precision")
+            .and_then(|p| p.parse().ok())
+            .unwrap_or(if number_type == "Char" || number_type == "UChar" {
+                1
+            } else {
+                4
+            });
+        let big_endian = item.attribute("Endian") == Some("Big");
+        let seek: u64 = item
+            .attribute("Seek")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0);
+
+        let mut file = std::fs::File::open(self.directory.join(item.text.trim()))
+            .map_err(VtkError::IoError)?;
+        file.seek(SeekFrom::Start(seek))
+            .map_err(VtkError::IoError)?;
+        let mut bytes = vec![0u8; count * precision];
+        file.read_exact(&mut bytes).map_err(VtkError::IoError)?;
+
+        bytes
+            .chunks_exact(precision)
+            .map(|value| {
+                let mut buffer = [0u8; 8];
+                buffer[..precision].copy_from_slice(value);
+                if big_endian {
+                    buffer[..precision].reverse();
+                }
+                let bits = u64::from_le_bytes(buffer);
+                let shift = 64 - 8 * precision as u32;
+                Ok(match (number_type, precision) {
+                    ("Float", 4) => f32::from_bits(bits as u32) as f64,
+                    ("Float", 8) => f64::from_bits(bits),
+                    ("Int" | "Char", _) => ((bits << shift) as i64 >> shift) as f64,
+                    ("UInt" | "UChar", _) => bits as f64,
+                    _ => return Err(VtkError::UnsupportedDataType),
+                })
+            })
+            .collect()
+    }
+
+    /// Values selected by a hyperslab: (start, stride, count) per dimension
+    fn read_hyperslab(&mut self, item: &Element) -> Result<Vec<f64>, VtkError> {
+        let mut items = item.children_named("DataItem");
+        let (Some(selection), Some(source)) = (items.next(), items.next()) else {
+            return Err(VtkError::MissingData("XDMF hyperslab needs two data items"));
+        };
+        let selection: Vec<usize> = self.read(selection)?.iter().map(|&v| v as usize).collect();
+        let shape = dimensions(source, "Dimensions");
+        let values = self.read(source)?;
+        let rank = shape.len();
+        if selection.len() < 3 * rank {
+            return Err(VtkError::InvalidFormat("Invalid XDMF hyperslab selection"));
+        }
+        let (start, rest) = selection.split_at(rank);
+        let (stride, count) = rest.split_at(rank);
+
+        let total: usize = count[..rank].iter().product();
+        let mut selected = Vec::with_capacity(total);
+        for index in 0..total {
+            // Position of the selected value in the source array
+            let mut remainder = index;
+            let mut offset = 0;
+            let mut stride_size = 1;
+            for axis in (0..rank).rev() {
+                let position = start[axis] + (remainder % count[axis]) * stride[axis];
+                remainder /= count[axis];
+                offset += position * stride_size;
+                stride_size *= shape[axis];
+            }
+            selected.push(
+                *values
+                    .get(offset)
+                    .ok_or(VtkError::InvalidFormat("XDMF hyperslab beyond data"))?,
+            );
+        }
+        Ok(selected)
+    }
+}
+
+/// Number of nodes and faces of an XDMF cell type
+///
+/// # Parameters
+/// - `cell_type`: `TopologyType` name or `Mixed` type code
+///
+/// # Returns
+/// - `Some((nodes, faces))`: `nodes` is 0 for types with a node count in the data
+fn cell_shape(cell_type: &str) -> Option<(usize, &'static [&'static [usize]])> {
+    const TRIANGLE: &[&[usize]] = &[&[0, 1, 2]];
+    const QUAD: &[&[usize]] = &[&[0, 1, 2, 3]];
+    Some(match cell_type.to_ascii_lowercase().as_str() {
+        "polyvertex" | "1" => (1, &[]),
+        "polyline" | "2" => (0, &[]),
+        "polygon" | "3" => (0, &[]),
+        "triangle" | "4" => (3, TRIANGLE),
+        "quadrilateral" | "5" => (4, QUAD),
+        "tetrahedron" | "6" => (4, &TETRA_FACES),
+        "pyramid" | "7" => (5, &PYRAMID_FACES),
+        "wedge" | "8" => (6, &WEDGE_FACES),
+        "hexahedron" | "9" => (8, &HEX_FACES),
+        "edge_3" | "34" => (3, &[]),
+        "triangle_6" | "36" => (6, TRIANGLE),
+        "quadrilateral_8" | "37" => (8, QUAD),
+        "quadrilateral_9" | "35" => (9, QUAD),
+        "tetrahedron_10" | "38" => (10, &TETRA_FACES),
+        "pyramid_13" | "39" => (13, &PYRAMID_FACES),
+        "wedge_15" | "40" => (15, &WEDGE_FACES),
+        "wedge_18" | "41" => (18, &WEDGE_FACES),
+        "hexahedron_20" | "48" => (20, &HEX_FACES),
+        "hexahedron_27" | "50" => (27, &HEX_FACES),
+        _ => return None,
+    })
+}
+
+/// Triangles of an unstructured topology
+///
+/// # Returns
+/// - (Triangle indices, triangle to cell mapping, number of cells)
+fn triangulate_topology(
+    topology_type: &str,
+    topology: &Element,
+    connectivity: &[f64],
+) -> Result<(Vec<u32>, Vec<usize>, usize), VtkError> {
+    let base = topology
+        .attribute("BaseOffset")
+        .and_then(|offset| offset.parse::<i64>().ok())
+        .unwrap_or(0);
+    let element_count: Option<usize> = topology
+        .attribute("NumberOfElements")
+        .or(topology.attribute("Dimensions"))
+        .and_then(|count| count.split_whitespace().next()?.parse().ok());
+    let nodes_per_element: Option<usize> = topology
+        .attribute("NodesPerElement")
+        .and_then(|count| count.parse().ok());
+    let invalid = || VtkError::InvalidFormat("Invalid XDMF connectivity");
+
+    let mut indices = Vec::new();
+    let mut mapping = Vec::new();
+    let mut cell = 0;
+    let mut position = 0;
+    let mixed = topology_type.eq_ignore_ascii_case("mixed");
+    while position < connectivity.len() && element_count.is_none_or(|count| cell < count) {
+        let cell_type = if mixed {
+            position += 1;
+            format!("{}", connectivity[position - 1] as i64)
+        } else {
+            topology_type.to_string()
+        };
+        let (mut size, faces) = cell_shape(&cell_type).ok_or_else(|| {
+            VtkError::LoadError(format!("Unsupported XDMF cell type: {}", cell_type))
+        })?;
+        if size == 0 {
+            // Polygons and polylines: node count in the data or the topology
+            size = if mixed {
+                position += 1;
+                *connectivity.get(position - 1).ok_or_else(invalid)? as usize
+            } else {
+                nodes_per_element
+                    .ok_or(VtkError::MissingData("XDMF topology needs NodesPerElement"))?
+            };
+        }
+        let nodes: Vec<u32> = connectivity
+            .get(position..position + size)
+            .ok_or_else(invalid)?
+            .iter()
+            .map(|&node| (node as i64 - base) as u32)
+            .collect();
+        position += size;
+
+        let polygon: Vec<usize>;
+        let faces: &[&[usize]] = if cell_type == "3" || cell_type.eq_ignore_ascii_case("polygon") {
+            polygon = (0..size).collect();
+            &[&polygon]
+        } else {
+            faces
+        };
+        for face in faces {
+            for i in 1..face.len().saturating_sub(1) {
+                indices.extend_from_slice(&[nodes[face[0]], nodes[face[i]], nodes[face[i + 1]]]);
+                mapping.push(cell);
+            }
+        }
+        cell += 1;
+    }
+    Ok((indices, mapping, cell))
+}
+
+/// Geometry and attributes of a uniform grid
+///
+/// # Parameters
+/// - `grid`: Uniform grid element
+/// - `shared`: Topology and geometry of the previous grid, updated with this one's
+fn read_uniform<'a>(
+    data: &mut HeavyData,
+    grid: &'a Element,
+    shared: &mut (Option<&'a Element>, Option<&'a Element>),
+) -> Result<GeometryData, VtkError> {
+    if let Some(topology) = grid.child("Topology") {
+        shared.0 = Some(topology);
+    }
+    if let Some(geometry) = grid.child("Geometry") {
+        shared.1 = Some(geometry);
+    }
+    let (Some(topology), Some(geometry)) = *shared else {
+        return Err(VtkError::MissingData(
+            "XDMF grid has no topology or geometry",
+        ));
+    };
+
+    let topology_type = topology
+        .attribute("TopologyType")
+        .or(topology.attribute("Type"))
+        .unwrap_or("");
+    let structured = topology_type.ends_with("SMesh") || topology_type.ends_with("RectMesh");
+    // Structured dimensions are listed slowest first (z y x)
+    let mut dims = [1usize; 3];
+    if structured {
+        for (axis, &n) in dimensions(topology, "Dimensions")
+            .iter()
+            .rev()
+            .take(3)
+            .enumerate()
+        {
+            dims[axis] = n;
+        }
+    }
+
+    // Points
+    let geometry_type = geometry
+        .attribute("GeometryType")
+        .or(geometry.attribute("Type"))
+        .unwrap_or("XYZ");
+    let items: Vec<&Element> = geometry.children_named("DataItem").collect();
+    let mut item_values = Vec::new();
+    for item in &items {
+        item_values.push(data.read(item)?);
+    }
+    let point = |values: &[f64]| -> [f32; 3] {
+        std::array::from_fn(|axis| values.get(axis).copied().unwrap_or(0.0) as f32)
+    };
+    let vertices: Vec<[f32; 3]> = match (geometry_type, item_values.as_slice()) {
+        ("XYZ", [xyz]) => xyz.chunks_exact(3).map(point).collect(),
+        ("XY", [xy]) => xy.chunks_exact(2).map(point).collect(),
+        ("X_Y_Z", [x, y, z]) => (0..x.len().min(y.len()).min(z.len()))
+            .map(|i| point(&[x[i], y[i], z[i]]))
+            .collect(),
+        ("X_Y", [x, y]) => (0..x.len().min(y.len()))
+            .map(|i| point(&[x[i], y[i]]))
+            .collect(),
+        // Axis coordinates of rectilinear grids
+        ("VXVYVZ" | "VXVY", axes) => {
+            let axis = |i: usize| axes.get(i).cloned().unwrap_or_else(|| vec![0.0]);
+            let (x, y, z) = (axis(0), axis(1), axis(2));
+            dims = [x.len(), y.len(), z.len()];
+            let mut points = Vec::with_capacity(x.len() * y.len() * z.len());
+            for &z in &z {
+                for &y in &y {
+                    for &x in &x {
+                        points.push(point(&[x, y, z]));
+                    }
+                }
+            }
+            points
+        }
+        // Image grids, origin and spacing listed slowest axis first
+        ("ORIGIN_DXDYDZ" | "ORIGIN_DXDY", [origin, spacing]) => {
+            let reversed = |values: &[f64]| -> [f64; 3] {
+                let mut xyz = [0.0; 3];
+                for (axis, &value) in values.iter().rev().take(3).enumerate() {
+                    xyz[axis] = value;
+                }
+                xyz
+            };
+            let (origin, spacing) = (reversed(origin), reversed(spacing));
+            let mut points = Vec::with_capacity(dims.iter().product());
+            for k in 0..dims[2] {
+                for j in 0..dims[1] {
+                    for i in 0..dims[0] {
+                        points.push(point(&[
+                            origin[0] + i as f64 * spacing[0],
+                            origin[1] + j as f64 * spacing[1],
+                            origin[2] + k as f64 * spacing[2],
+                        ]));
+                    }
+                }
+            }
+            points
+        }
+        _ => {
+            return Err(VtkError::LoadError(format!(
+                "Unsupported XDMF geometry: {} with {} data items",
+                geometry_type,
+                items.len()
+            )))
+        }
+    };
+
+    // Cells
+    let (indices, mapping, cell_count) = if structured {
+        let (indices, mapping) = triangulate_structured(dims);
+        let cell_count = dims.iter().map(|&n| n.saturating_sub(1).max(1)).product();
+        (indices, mapping, cell_count)
+    } else {
+        let item = topology
+            .child("DataItem")
+            .ok_or(VtkError::MissingData("XDMF topology has no connectivity"))?;
+        let connectivity = data.read(item)?;
+        triangulate_topology(topology_type, topology, &connectivity)?
+    };
+    if let Some(&index) = indices.iter().find(|&&i| i as usize >= vertices.len()) {
+        return Err(VtkError::IndexOutOfBounds {
+            index: index as usize,
+            max: vertices.len().saturating_sub(1),
+        });
+    }
+
+    // Attributes
+    let mut attributes = HashMap::new();
+    for element in grid.children_named("Attribute") {
+        let name = element.attribute("Name").unwrap_or("attribute").to_string();
+        let (location, count) = match element.attribute("Center").unwrap_or("Node") {
+            "Node" => (AttributeLocation::Point, vertices.len()),
+            "Cell" => (AttributeLocation::Cell, cell_count),
+            other => {
+                println!("Skipping attribute {} at {} centers", name, other);
+                continue;
+            }
+        };
+        let Some(item) = element.child("DataItem") else {
+            continue;
+        };
+        let values = match data.read(item) {
+            Ok(values) => values,
+            Err(e) => {
+                println!("Skipping attribute {}: {:?}", name, e);
+                continue;
+            }
+        };
+        match to_attribute(element, values, count) {
+            Some(attribute) => {
+                attributes.insert((name, location), attribute);
+            }
+            None => println!("Skipping attribute {}: size does not match the grid", name),
+        }
+    }
+
+    Ok(GeometryData::new(vertices, indices, attributes).add_triangle_to_cell_mapping(mapping))
+}
+
+/// Convert attribute values by their `AttributeType`
+fn to_attribute(element: &Element, values: Vec<f64>, count: usize) -> Option<AttributeType> {
+    if count == 0 || !values.len().is_multiple_of(count) {
+        return None;
+    }
+    let components = values.len() / count;
+    let values: Vec<f32> = values.into_iter().map(|v| v as f32).collect();
+    Some(
+        match (
+            element.attribute("AttributeType").unwrap_or("Scalar"),
+            components,
+        ) {
+            ("Vector", 2 | 3) => AttributeType::Vector(
+                values
+                    .chunks_exact(components)
+                    .map(|v| [v[0], v[1], v.get(2).copied().unwrap_or(0.0)])
+                    .collect(),
+            ),
+            ("Tensor", 9) => AttributeType::Tensor(
+                values
+                    .chunks_exact(9)
+                    .map(|v| std::array::from_fn(|i| v[i]))
+                    .collect(),
+            ),
+            // Symmetric tensor: xx, xy, xz, yy, yz, zz
+            ("Tensor6", 6) => AttributeType::Tensor(
+                values
+                    .chunks_exact(6)
+                    .map(|v| [v[0], v[1], v[2], v[1], v[3], v[4], v[2], v[4], v[5]])
+                    .collect(),
+            ),
+            _ => AttributeType::Scalar {
+                num_comp: components,
+                table_name: "default".to_string(),
+                data: values,
+                lookup_table: None,
+            },
+        },
+    )
+}
+
+/// Geometry of a uniform grid or spatial collection (pieces merged)
+fn read_grid<'a>(
+    data: &mut HeavyData,
+    grid: &'a Element,
+    shared: &mut (Option<&'a Element>, Option<&'a Element>),
+) -> Result<GeometryData, VtkError> {
+    if grid.attribute("GridType") != Some("Collection") {
+        return read_uniform(data, grid, shared);
+    }
+    let mut pieces = Vec::new();
+    for piece in grid.children_named("Grid") {
+        pieces.push(read_grid(data, piece, shared)?);
+    }
+    if pieces.is_empty() {
+        return Err(VtkError::MissingData("XDMF collection has no grids"));
+    }
+    Ok(vtk::merge_pieces(pieces))
+}
+
+/// Values of a named attribute of a grid or spatial collection, pieces concatenated
+fn read_grid_attribute(
+    data: &mut HeavyData,
+    grid: &Element,
+    name: &str,
+) -> Result<Vec<f32>, VtkError> {
+    if grid.attribute("GridType") == Some("Collection") {
+        let mut values = Vec::new();
+        for piece in grid.children_named("Grid") {
+            values.extend(read_grid_attribute(data, piece, name)?);
+        }
+        return Ok(values);
+    }
+    let item = grid
+        .children_named("Attribute")
+        .find(|element| element.attribute("Name") == Some(name))
+        .and_then(|element| element.child("DataItem"))
+        .ok_or(VtkError::MissingData(
+            "XDMF time step lacks the series attribute",
+        ))?;
+    Ok(data.read(item)?.into_iter().map(|v| v as f32).collect())
+}
+
+/// XDMF document with its heavy data location
+pub struct XdmfFile {
+    document: Element,
+    directory: PathBuf,
+}
+
+impl XdmfFile {
+    /// Parse the light data of an XDMF file
+    pub fn open(path: &Path) -> Result<Self, VtkError> {
+        let text = std::fs::read_to_string(path).map_err(VtkError::IoError)?;
+        Ok(Self {
+            document: parse_xml(&text)?,
+            directory: path.parent().unwrap_or(Path::new(".")).to_path_buf(),
+        })
+    }
+
+    fn heavy_data(&self) -> HeavyData<'_> {
+        HeavyData {
+            document: &self.document,
+            directory: self.directory.clone(),
+            hdf5_files: HashMap::new(),
+        }
+    }
+
+    /// First grid of the domain
+    fn grid(&self) -> Result<&Element, VtkError> {
+        self.document
+            .child("Domain")
+            .and_then(|domain| domain.child("Grid"))
+            .ok_or(VtkError::MissingData("XDMF file has no grid"))
+    }
+
+    /// Grids of the time steps with their time (one step for a static grid)
+    fn time_steps(&self) -> Result<Vec<(f32, &Element)>, VtkError> {
+        let grid = self.grid()?;
+        if grid.attribute("CollectionType") != Some("Temporal") {
+            return Ok(vec![(0.0, grid)]);
+        }
+        Ok(grid
+            .children_named("Grid")
+            .enumerate()
+            .map(|(step, child)| {
+                let time = child
+                    .child("Time")
+                    .and_then(|time| time.attribute("Value"))
+                    .and_then(|value| value.trim().parse().ok())
+                    .unwrap_or(step as f32);
+                (time, child)
+            })
+            .collect())
+    }
+
+    /// Geometry of the first time step
+    ///
+    /// # Returns
+    /// - `Ok(GeometryData)`: Triangulated grid with its node and cell attributes
+    /// - `Err(VtkError)`: Missing or unsupported light or heavy data
+    pub fn geometry(&self) -> Result<GeometryData, VtkError> {
+        let steps = self.time_steps()?;
+        let (_, grid) = steps
+            .first()
+            .ok_or(VtkError::MissingData("XDMF collection has no grids"))?;
+        let geometry = read_grid(&mut self.heavy_data(), grid, &mut (None, None))?;
+        println!(
+            "Parsed XDMF file: {} points, {} triangles, {} time steps",
+            geometry.vertices.len(),
+            geometry.indices.len() / 3,
+            steps.len()
+        );
+        Ok(geometry)
+    }
+
+    /// Name of the first node scalar of the first time step
+    fn series_attribute(&self) -> Option<String> {
+        let steps = self.time_steps().ok()?;
+        steps
+            .first()?
+            .1
+            .find(&|element| {
+                element.name == "Attribute"
+                    && element.attribute("Center").unwrap_or("Node") == "Node"
+                    && element.attribute("AttributeType").unwrap_or("Scalar") == "Scalar"
+            })?
+            .attribute("Name")
+            .map(str::to_string)
+    }
+}
+
+/// Whether the path has an XDMF extension
+pub fn is_xdmf_file(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("xdmf" | "xmf")
+    )
+}
+
+/// Load the first time step of an XDMF file
+pub fn load_xdmf(path: &Path) -> Result<GeometryData, VtkError> {
+    XdmfFile::open(path)?.geometry()
+}
+
+/// Load the first node scalar at every time step of a temporal collection
+///
+/// # Parameters
+/// - `path`: XDMF file path
+///
+/// # Returns
+/// - `Ok(Vec<(f32, Vec<f32>)>)`: Time and point values of every time step
+/// - `Err(VtkError)`: The file cannot be read or has no node scalar
+pub fn load_time_series(path: &Path) -> Result<Vec<(f32, Vec<f32>)>, VtkError> {
+    let xdmf = XdmfFile::open(path)?;
+    let name = xdmf
+        .series_attribute()
+        .ok_or(VtkError::MissingData("XDMF file has no node scalar"))?;
+    println!("Time series of attribute {}", name);
+
+    let mut data = xdmf.heavy_data();
+    xdmf.time_steps()?
+        .into_iter()
+        .map(|(time, grid)| Ok((time, read_grid_attribute(&mut data, grid, &name)?)))
+        .collect()
+}
@@ -3,8 +3,10 @@
 //! This module provides simple model transformation functionality:
 //! - Alt + Left mouse drag: Rotate around model center
 //! - Alt + Middle mouse drag: Translate model position
-//! - Alt + R: Reset model transform
+//! - Alt + R: Reset model transform (the key is configurable in
+//!   File > Preferences)
 
+use crate::preferences::{Action, KeyBindings};
 use crate::ui::{ModelLoadedEvent, UserModelMesh};
 use bevy::input::keyboard::KeyCode;
 use bevy::input::mouse::{AccumulatedMouseMotion, MouseButton};
@@ -54,6 +56,7 @@ fn update_model_center(
 /// - Alt + R: Reset all transforms
 fn model_transform_input(
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
     mouse_button_input: Res<ButtonInput<MouseButton>>,
     accumulated_mouse_motion: Res<AccumulatedMouseMotion>,
     model_center: Res<ModelCenter>,
@@ -63,7 +66,7 @@ fn model_transform_input(
         keyboard_input.pressed(KeyCode::AltLeft) || keyboard_input.pressed(KeyCode::AltRight);
 
     // Alt + R reset transform
-    if alt_pressed && keyboard_input.just_pressed(KeyCode::KeyR) {
+    if alt_pressed && key_bindings.just_pressed(&keyboard_input, Action::ResetModelTransform) {
         for mut transform in model_query.iter_mut() {
             *transform = Transform::IDENTITY;
             println!("Model transform reset");
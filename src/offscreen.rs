@@ -0,0 +1,378 @@
+//! # Offscreen Rendering Module
+//!
+//! This module renders a model file to an image without opening a window, for
+//! regression tests of reference datasets and for the command line:
+//! - Load the file through the regular geometry pipeline (OBJ, VTK)
+//! - Apply the selected color map over the full scalar range
+//! - Place the camera explicitly or fit it to the model bounds
+//! - Render into an offscreen image and return or save it
+//!
+//! Rendering is deterministic: the scene has a fixed lighting setup without
+//! shadows or temporal effects, and the image is captured only after all render
+//! pipelines have finished compiling.
+//!
+//! ```rust
+//! let request = OffscreenRenderRequest::new("assets/sample.vtk").with_size(800, 600);
+//! render_to_png(&request, Path::new("sample.png"))?;
+//! ```
+
+use crate::mesh::color_maps::{ColorMapper, ColorMappingConfig};
+use crate::mesh::{self, VtkError};
+use bevy::app::ScheduleRunnerPlugin;
+use bevy::prelude::*;
+use bevy::render::{
+    camera::RenderTarget,
+    render_asset::RenderAssetUsages,
+    render_resource::{Extent3d, PipelineCache, TextureDimension, TextureFormat, TextureUsages},
+    view::screenshot::{Screenshot, ScreenshotCaptured},
+    ExtractSchedule, MainWorld, RenderApp,
+};
+use bevy::window::ExitCondition;
+use bevy::winit::WinitPlugin;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Frames rendered before the capture may start (assets and pipelines are queued)
+const WARMUP_FRAMES: u32 = 10;
+/// Consecutive frames without pending pipelines required before the capture
+const STABLE_FRAMES: u32 = 5;
+/// Frames after which rendering is given up
+const MAX_FRAMES: u32 = 1200;
+
+/// Camera setup of an offscreen render
+#[derive(Clone, Debug)]
+pub struct OffscreenCamera {
+    /// Camera position; fitted to the model bounds when `None`
+    pub position: Option<Vec3>,
+    /// Look-at target; the model center when `None`
+    pub target: Option<Vec3>,
+    /// Up direction
+    pub up: Vec3,
+    /// Vertical field of view in degrees
+    pub fov_degrees: f32,
+}
+
+impl Default for OffscreenCamera {
+    fn default() -> Self {
+        Self {
+            position: None,
+            target: None,
+            up: Vec3::Y,
+            fov_degrees: 45.0,
+        }
+    }
+}
+
+impl OffscreenCamera {
+    /// Camera transform for a model with the given bounds
+    ///
+    /// Fitted cameras look from the same direction as the viewer's initial
+    /// camera, far enough away for the bounding sphere to fill the view.
+    fn transform(&self, min: Vec3, max: Vec3) -> Transform {
+        let center = (min + max) * 0.5;
+        let target = self.target.unwrap_or(center);
+        let position = self.position.unwrap_or_else(|| {
+            let radius = ((max - min).length() * 0.5).max(1e-3);
+            let half_fov = (self.fov_degrees.to_radians() * 0.5).max(1e-3);
+            let distance = radius / half_fov.sin() * 1.05;
+            target + Vec3::new(0.8, 1.2, 0.8).normalize() * distance
+        });
+        Transform::from_translation(position).looking_at(target, self.up)
+    }
+}
+
+/// Offscreen render request
+#[derive(Clone, Debug)]
+pub struct OffscreenRenderRequest {
+    /// Model file to render
+    pub input: PathBuf,
+    /// Image width in pixels
+    pub width: u32,
+    /// Image height in pixels
+    pub height: u32,
+    pub camera: OffscreenCamera,
+    /// Color map applied to the first scalar attribute
+    pub color_map: String,
+    pub background: Color,
+}
+
+impl OffscreenRenderRequest {
+    /// Create a 1280x720 request with a fitted camera and the default color map
+    pub fn new(input: impl Into<PathBuf>) -> Self {
+        Self {
+            input: input.into(),
+            width: 1280,
+            height: 720,
+            camera: OffscreenCamera::default(),
+            color_map: "default".to_string(),
+            background: Color::srgb(0.15, 0.15, 0.15),
+        }
+    }
+
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn with_camera(mut self, camera: OffscreenCamera) -> Self {
+        self.camera = camera;
+        self
+    }
+
+    pub fn with_color_map(mut self, color_map: impl Into<String>) -> Self {
+        self.color_map = color_map.into();
+        self
+    }
+}
+
+/// Scene handed to the headless app
+#[derive(Resource)]
+struct OffscreenScene {
+    mesh: Option<Mesh>,
+    camera_transform: Transform,
+    fov: f32,
+    width: u32,
+    height: u32,
+}
+
+/// Capture progress of the headless app
+#[derive(Resource)]
+struct OffscreenJob {
+    target: Handle<Image>,
+    frames: u32,
+    ready_frames: u32,
+    requested: bool,
+    result: Arc<Mutex<Option<Image>>>,
+}
+
+/// Whether the render world has no pipelines left to compile
+#[derive(Resource, Default)]
+struct PipelinesReady(bool);
+
+/// Render a model file to an image
+///
+/// # Parameters
+/// - `request`: File, image size, camera and color map
+///
+/// # Returns
+/// - `Ok(Image)`: Rendered RGBA image
+/// - `Err(VtkError)`: Loading failed, the request is invalid or rendering timed out
+pub fn render_to_image(request: &OffscreenRenderRequest) -> Result<Image, VtkError> {
+    if request.width == 0 || request.height == 0 {
+        return Err(VtkError::GenericError(
+            "image size must be positive".to_string(),
+        ));
+    }
+
+    let geometry = mesh::load_geometry_file(&request.input)?;
+    if geometry.vertices.is_empty() {
+        return Err(VtkError::MissingData("vertices"));
+    }
+    let (min, max) = geometry.vertices.iter().fold(
+        (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+        |(min, max), vertex| (min.min(Vec3::from(*vertex)), max.max(Vec3::from(*vertex))),
+    );
+
+    let mut model_mesh = mesh::create_mesh_from_geometry(&geometry);
+    let color_config = ColorMappingConfig {
+        color_map_name: request.color_map.clone(),
+        ..default()
+    };
+    ColorMapper::apply_scalar_attributes_with_color_map(&geometry, &mut model_mesh, &color_config)?;
+
+    let result = Arc::new(Mutex::new(None));
+    let mut app = App::new();
+    app.add_plugins((
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                close_when_requested: false,
+            })
+            .disable::<WinitPlugin>(),
+        ScheduleRunnerPlugin::run_loop(Duration::ZERO),
+    ))
+    .insert_resource(ClearColor(request.background))
+    .insert_resource(AmbientLight {
+        color: Color::srgb(0.6, 0.6, 0.7),
+        brightness: 0.7,
+    })
+    .insert_resource(OffscreenScene {
+        mesh: Some(model_mesh),
+        camera_transform: request.camera.transform(min, max),
+        fov: request.camera.fov_degrees.to_radians(),
+        width: request.width,
+        height: request.height,
+    })
+    .insert_resource(OffscreenJob {
+        target: Handle::default(),
+        frames: 0,
+        ready_frames: 0,
+        requested: false,
+        result: result.clone(),
+    })
+    .init_resource::<PipelinesReady>()
+    .add_systems(Startup, setup_offscreen_scene)
+    .add_systems(Update, capture_when_ready);
+
+    app.sub_app_mut(RenderApp)
+        .add_systems(ExtractSchedule, update_pipelines_ready);
+
+    println!(
+        "Rendering {} offscreen at {}x{}",
+        request.input.display(),
+        request.width,
+        request.height
+    );
+    if let AppExit::Error(code) = app.run() {
+        return Err(VtkError::GenericError(format!(
+            "offscreen render failed with exit code {}",
+            code
+        )));
+    }
+
+    let image = result.lock().ok().and_then(|mut image| image.take());
+    image.ok_or_else(|| VtkError::GenericError("no image was captured".to_string()))
+}
+
+/// Render a model file and save the image (format from the output extension)
+///
+/// # Parameters
+/// - `request`: File, image size, camera and color map
+/// - `output`: Image path, e.g. `render.png`
+pub fn render_to_png(request: &OffscreenRenderRequest, output: &Path) -> Result<(), VtkError> {
+    let image = render_to_image(request)?;
+    let image = image
+        .try_into_dynamic()
+        .map_err(|e| VtkError::ConversionError(e.to_string()))?;
+    image
+        .to_rgba8()
+        .save(output)
+        .map_err(|e| VtkError::GenericError(format!("failed to save image: {}", e)))?;
+    println!("Saved offscreen render to {}", output.display());
+    Ok(())
+}
+
+/// Spawn the render target, camera, lights and model
+fn setup_offscreen_scene(
+    mut commands: Commands,
+    mut scene: ResMut<OffscreenScene>,
+    mut job: ResMut<OffscreenJob>,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let size = Extent3d {
+        width: scene.width,
+        height: scene.height,
+        depth_or_array_layers: 1,
+    };
+    let mut target = Image::new_fill(
+        size,
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    target.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING
+        | TextureUsages::COPY_SRC
+        | TextureUsages::COPY_DST
+        | TextureUsages::RENDER_ATTACHMENT;
+    job.target = images.add(target);
+
+    commands.spawn((
+        Camera3d::default(),
+        Camera {
+            target: RenderTarget::Image(job.target.clone()),
+            ..default()
+        },
+        Projection::Perspective(PerspectiveProjection {
+            fov: scene.fov,
+            ..default()
+        }),
+        scene.camera_transform,
+    ));
+
+    // Same key and fill lights as the viewer, without shadows
+    commands.spawn((
+        DirectionalLight {
+            illuminance: 15000.0,
+            shadows_enabled: false,
+            color: Color::srgb(1.0, 0.95, 0.9),
+            ..default()
+        },
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight {
+            illuminance: 3000.0,
+            shadows_enabled: false,
+            color: Color::srgb(0.8, 0.85, 1.0),
+            ..default()
+        },
+        Transform::from_xyz(-4.0, 2.0, -4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    if let Some(model_mesh) = scene.mesh.take() {
+        commands.spawn((
+            Mesh3d(meshes.add(model_mesh)),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: Color::srgb(1.0, 1.0, 1.0),
+                metallic: 0.2,
+                perceptual_roughness: 0.4,
+                reflectance: 0.5,
+                cull_mode: None,
+                ..default()
+            })),
+        ));
+    }
+}
+
+/// Report the pipeline compilation state to the main world
+fn update_pipelines_ready(mut main_world: ResMut<MainWorld>, pipelines: Res<PipelineCache>) {
+    if let Some(mut ready) = main_world.get_resource_mut::<PipelinesReady>() {
+        ready.0 = pipelines.waiting_pipelines().count() == 0;
+    }
+}
+
+/// Request the capture once the scene is stable and exit after it arrived
+fn capture_when_ready(
+    mut commands: Commands,
+    mut job: ResMut<OffscreenJob>,
+    pipelines_ready: Res<PipelinesReady>,
+    mut exit: EventWriter<AppExit>,
+) {
+    job.frames += 1;
+    if job.frames > MAX_FRAMES {
+        println!("Offscreen render timed out after {} frames", MAX_FRAMES);
+        exit.send(AppExit::error());
+        return;
+    }
+    if job.requested {
+        return;
+    }
+
+    job.ready_frames = if pipelines_ready.0 {
+        job.ready_frames + 1
+    } else {
+        0
+    };
+    if job.frames < WARMUP_FRAMES || job.ready_frames < STABLE_FRAMES {
+        return;
+    }
+
+    job.requested = true;
+    let result = job.result.clone();
+    commands
+        .spawn(Screenshot::image(job.target.clone()))
+        .observe(
+            move |trigger: Trigger<ScreenshotCaptured>, mut exit: EventWriter<AppExit>| {
+                if let Ok(mut image) = result.lock() {
+                    *image = Some(trigger.event().0.clone());
+                }
+                exit.send(AppExit::Success);
+            },
+        );
+}
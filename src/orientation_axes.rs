@@ -0,0 +1,235 @@
+//! # Orientation Axes Module
+//!
+//! This module draws an X/Y/Z axes triad in the lower left viewport corner:
+//! - The triad rotates with the camera, so it always shows the world axis
+//!   directions as seen from the current view
+//! - Axes are drawn back to front; the negative directions are marked by
+//!   hollow circles
+//! - Clicking an axis end snaps the camera to look along that axis at the
+//!   model center, keeping the current distance
+//!
+//! Toggle the triad via View > Orientation Axes.
+
+use crate::camera::{SetCameraViewEvent, WorldModelCamera};
+use crate::lod::bounding_box_corners;
+use crate::picking::model_matrix;
+use crate::ui::{CurrentModelData, UserModelMesh};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+use std::f32::consts::{FRAC_PI_2, PI};
+
+/// Side length of the triad area, in points
+const TRIAD_SIZE: f32 = 110.0;
+/// Distance of the triad area from the window corner, in points
+const TRIAD_MARGIN: f32 = 12.0;
+/// Radius of the clickable axis ends, in points
+const END_RADIUS: f32 = 9.0;
+
+const AXIS_COLORS: [egui::Color32; 3] = [
+    egui::Color32::from_rgb(230, 70, 70),
+    egui::Color32::from_rgb(90, 200, 90),
+    egui::Color32::from_rgb(80, 130, 240),
+];
+const AXIS_NAMES: [&str; 3] = ["X", "Y", "Z"];
+
+/// Orientation axes settings
+#[derive(Resource)]
+pub struct OrientationAxesSettings {
+    /// Show the triad
+    pub visible: bool,
+}
+
+impl Default for OrientationAxesSettings {
+    fn default() -> Self {
+        Self { visible: true }
+    }
+}
+
+pub struct OrientationAxesPlugin;
+
+impl Plugin for OrientationAxesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<OrientationAxesSettings>()
+            .add_systems(Update, orientation_axes.after(EguiSet::InitContexts));
+    }
+}
+
+/// One end of a triad axis
+struct AxisEnd {
+    /// Axis index, 0 for X
+    axis: usize,
+    /// Positive or negative direction
+    positive: bool,
+    /// Offset from the triad center in points, y pointing down
+    offset: egui::Vec2,
+    /// Depth in camera space, larger values are closer to the viewer
+    depth: f32,
+}
+
+/// Axis ends of the triad seen with the camera rotation
+///
+/// # Parameters
+/// - `rotation`: Camera rotation
+/// - `length`: Axis length in points
+///
+/// # Returns
+/// - The six axis ends sorted back to front
+fn axis_ends(rotation: Quat, length: f32) -> Vec<AxisEnd> {
+    let inverse = rotation.inverse();
+    let mut ends: Vec<AxisEnd> = (0..3)
+        .flat_map(|axis| [true, false].map(move |positive| (axis, positive)))
+        .map(|(axis, positive)| {
+            let mut direction = Vec3::ZERO;
+            direction[axis] = if positive { 1.0 } else { -1.0 };
+            let view = inverse * direction;
+            AxisEnd {
+                axis,
+                positive,
+                offset: egui::vec2(view.x, -view.y) * length,
+                depth: view.z,
+            }
+        })
+        .collect();
+    ends.sort_by(|a, b| a.depth.total_cmp(&b.depth));
+    ends
+}
+
+/// Camera rotation looking along the negative direction of an axis end
+///
+/// The rotation is composed of yaw and pitch like the camera controller does,
+/// so the controller keeps the view.
+///
+/// # Parameters
+/// - `axis`: Axis index, 0 for X
+/// - `positive`: View from the positive side of the axis
+pub fn axis_view_rotation(axis: usize, positive: bool) -> Quat {
+    let (yaw, pitch) = match (axis, positive) {
+        (0, true) => (FRAC_PI_2, 0.0),
+        (0, false) => (-FRAC_PI_2, 0.0),
+        (1, true) => (0.0, -FRAC_PI_2),
+        (1, false) => (0.0, FRAC_PI_2),
+        (_, true) => (0.0, 0.0),
+        (_, false) => (PI, 0.0),
+    };
+    Quat::from_rotation_y(yaw) * Quat::from_rotation_x(pitch)
+}
+
+/// World space center of the current model, the origin without a model
+fn model_center(
+    current_model: &CurrentModelData,
+    model_query: &Query<&GlobalTransform, With<UserModelMesh>>,
+) -> Vec3 {
+    let Some(geometry) = current_model.geometry.as_ref() else {
+        return Vec3::ZERO;
+    };
+    let Some((min, max)) = bounding_box_corners(&geometry.vertices) else {
+        return Vec3::ZERO;
+    };
+    model_matrix(model_query).transform_point3((min + max) * 0.5)
+}
+
+/// Draw the triad and snap the camera on clicked axis ends
+fn orientation_axes(
+    mut contexts: EguiContexts,
+    settings: Res<OrientationAxesSettings>,
+    current_model: Res<CurrentModelData>,
+    camera_query: Query<&Transform, With<WorldModelCamera>>,
+    model_query: Query<&GlobalTransform, With<UserModelMesh>>,
+    mut view_events: EventWriter<SetCameraViewEvent>,
+) {
+    if !settings.visible {
+        return;
+    }
+    let Ok(camera_transform) = camera_query.get_single() else {
+        return;
+    };
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let corner =
+        ctx.screen_rect().left_bottom() + egui::vec2(TRIAD_MARGIN, -TRIAD_MARGIN - TRIAD_SIZE);
+    let ends = axis_ends(
+        camera_transform.rotation,
+        TRIAD_SIZE * 0.5 - END_RADIUS - 2.0,
+    );
+    let mut clicked = None;
+
+    egui::Area::new(egui::Id::new("orientation_axes"))
+        .fixed_pos(corner)
+        .order(egui::Order::Background)
+        .show(ctx, |ui| {
+            let (rect, response) =
+                ui.allocate_exact_size(egui::vec2(TRIAD_SIZE, TRIAD_SIZE), egui::Sense::click());
+            let painter = ui.painter_at(rect);
+            let center = rect.center();
+            let hovered = response
+                .hover_pos()
+                .and_then(|pointer| nearest_end(&ends, center, pointer));
+
+            for (index, end) in ends.iter().enumerate() {
+                let color = AXIS_COLORS[end.axis];
+                let position = center + end.offset;
+                let highlight = hovered == Some(index);
+                if end.positive {
+                    painter.line_segment([center, position], egui::Stroke::new(2.5, color));
+                    painter.circle_filled(position, END_RADIUS, color);
+                    painter.text(
+                        position,
+                        egui::Align2::CENTER_CENTER,
+                        AXIS_NAMES[end.axis],
+                        egui::FontId::proportional(12.0),
+                        egui::Color32::BLACK,
+                    );
+                } else {
+                    painter.circle(
+                        position,
+                        END_RADIUS * 0.7,
+                        egui::Color32::from_black_alpha(160),
+                        egui::Stroke::new(1.5, color),
+                    );
+                }
+                if highlight {
+                    painter.circle_stroke(
+                        position,
+                        END_RADIUS + 2.0,
+                        egui::Stroke::new(1.5, egui::Color32::WHITE),
+                    );
+                }
+            }
+
+            if response.clicked() {
+                clicked = hovered.map(|index| (ends[index].axis, ends[index].positive));
+            }
+            if let Some(index) = hovered {
+                let end = &ends[index];
+                response.on_hover_text(format!(
+                    "View from {}{}",
+                    if end.positive { "+" } else { "-" },
+                    AXIS_NAMES[end.axis]
+                ));
+            }
+        });
+
+    if let Some((axis, positive)) = clicked {
+        let center = model_center(&current_model, &model_query);
+        let distance = camera_transform.translation.distance(center).max(0.1);
+        let rotation = axis_view_rotation(axis, positive);
+        println!(
+            "Camera snapped to {}{} view",
+            if positive { "+" } else { "-" },
+            AXIS_NAMES[axis]
+        );
+        view_events.send(SetCameraViewEvent {
+            translation: center - rotation * Vec3::NEG_Z * distance,
+            rotation,
+        });
+    }
+}
+
+/// Index of the front-most axis end under the pointer
+fn nearest_end(ends: &[AxisEnd], center: egui::Pos2, pointer: egui::Pos2) -> Option<usize> {
+    // Ends are sorted back to front, so the last hit is in front
+    ends.iter()
+        .rposition(|end| (center + end.offset).distance(pointer) <= END_RADIUS)
+}
@@ -0,0 +1,169 @@
+//! # Outline Module
+//!
+//! This module draws bounding box outlines of entities:
+//! - Entities tagged with `BoundingBoxOutline` get the 12 edges of their
+//!   bounding box drawn as lines
+//! - The box is computed with the bounding box logic of the LOD system from the
+//!   mesh positions of the entity and all its descendants, in the local frame of
+//!   the entity, so it rotates and scales with the entity
+//! - Mesh bounds are cached per mesh asset and recomputed when the mesh changes
+//!
+//! The outline of the model is toggled via View > Bounding Box; glTF nodes have
+//! their own toggle in the glTF Scene panel.
+
+use crate::lod::bounding_box_corners;
+use crate::ui::UserModelMesh;
+use bevy::prelude::*;
+use bevy::render::mesh::VertexAttributeValues;
+use bevy::utils::HashMap;
+
+/// Outline color
+const OUTLINE_COLOR: Color = Color::srgb(1.0, 0.85, 0.2);
+
+/// Edges of a box between corners indexed by their bits (bit 0: x, bit 1: y, bit 2: z)
+const EDGES: [[usize; 2]; 12] = [
+    [0, 1],
+    [2, 3],
+    [4, 5],
+    [6, 7],
+    [0, 2],
+    [1, 3],
+    [4, 6],
+    [5, 7],
+    [0, 4],
+    [1, 5],
+    [2, 6],
+    [3, 7],
+];
+
+/// Outline settings
+#[derive(Resource, Default)]
+pub struct OutlineSettings {
+    /// Outline the bounding box of the model
+    pub model_outline: bool,
+}
+
+/// Marker component of entities whose bounding box is outlined
+#[derive(Component)]
+pub struct BoundingBoxOutline;
+
+/// Local bounds of mesh assets, `None` for meshes without positions
+#[derive(Resource, Default)]
+struct MeshBounds(HashMap<AssetId<Mesh>, Option<(Vec3, Vec3)>>);
+
+pub struct OutlinePlugin;
+
+impl Plugin for OutlinePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<OutlineSettings>()
+            .init_resource::<MeshBounds>()
+            .add_systems(
+                Update,
+                (update_model_outline, invalidate_mesh_bounds, draw_outlines).chain(),
+            );
+    }
+}
+
+/// Tag or untag the model entities according to the settings
+fn update_model_outline(
+    mut commands: Commands,
+    settings: Res<OutlineSettings>,
+    models: Query<(Entity, Has<BoundingBoxOutline>), With<UserModelMesh>>,
+) {
+    for (entity, outlined) in models.iter() {
+        if settings.model_outline && !outlined {
+            commands.entity(entity).insert(BoundingBoxOutline);
+        } else if !settings.model_outline && outlined {
+            commands.entity(entity).remove::<BoundingBoxOutline>();
+        }
+    }
+}
+
+/// Forget the bounds of added, changed or removed meshes
+fn invalidate_mesh_bounds(
+    mut mesh_events: EventReader<AssetEvent<Mesh>>,
+    mut mesh_bounds: ResMut<MeshBounds>,
+) {
+    for event in mesh_events.read() {
+        match event {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } | AssetEvent::Removed { id } => {
+                mesh_bounds.0.remove(id);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Draw the bounding box edges of every outlined entity
+fn draw_outlines(
+    outlined: Query<(Entity, &GlobalTransform), With<BoundingBoxOutline>>,
+    mesh_entities: Query<(&Mesh3d, &GlobalTransform, &InheritedVisibility)>,
+    children_query: Query<&Children>,
+    meshes: Res<Assets<Mesh>>,
+    mut mesh_bounds: ResMut<MeshBounds>,
+    mut gizmos: Gizmos,
+) {
+    for (entity, global_transform) in outlined.iter() {
+        let to_local = global_transform.affine().inverse();
+        let mut bounds: Option<(Vec3, Vec3)> = None;
+
+        let descendants = std::iter::once(entity).chain(children_query.iter_descendants(entity));
+        for descendant in descendants {
+            let Ok((mesh3d, mesh_transform, visibility)) = mesh_entities.get(descendant) else {
+                continue;
+            };
+            if !visibility.get() {
+                continue;
+            }
+            let Some((min, max)) = *mesh_bounds
+                .0
+                .entry(mesh3d.id())
+                .or_insert_with(|| local_bounds(meshes.get(mesh3d.id())?))
+            else {
+                continue;
+            };
+
+            // Corners of the mesh box in the frame of the outlined entity
+            let matrix = to_local * mesh_transform.affine();
+            let corners = box_corners(min, max).map(|corner| matrix.transform_point3(corner));
+            let Some((corner_min, corner_max)) =
+                bounding_box_corners(&corners.map(|corner| corner.to_array()))
+            else {
+                continue;
+            };
+            bounds = Some(match bounds {
+                Some((min, max)) => (min.min(corner_min), max.max(corner_max)),
+                None => (corner_min, corner_max),
+            });
+        }
+
+        let Some((min, max)) = bounds else {
+            continue;
+        };
+        let corners = box_corners(min, max).map(|corner| global_transform.transform_point(corner));
+        for [a, b] in EDGES {
+            gizmos.line(corners[a], corners[b], OUTLINE_COLOR);
+        }
+    }
+}
+
+/// Bounding box of the positions of a mesh
+fn local_bounds(mesh: &Mesh) -> Option<(Vec3, Vec3)> {
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return None;
+    };
+    bounding_box_corners(positions)
+}
+
+/// The 8 corners of a box, indexed as in [`EDGES`]
+fn box_corners(min: Vec3, max: Vec3) -> [Vec3; 8] {
+    std::array::from_fn(|index| {
+        Vec3::new(
+            if index & 1 == 0 { min.x } else { max.x },
+            if index & 2 == 0 { min.y } else { max.y },
+            if index & 4 == 0 { min.z } else { max.z },
+        )
+    })
+}
@@ -0,0 +1,440 @@
+//! # Pathlines Module
+//!
+//! This module traces particles through the vector fields of a time series:
+//! - Point vectors of every time step are interpolated in space like
+//!   streamlines and linearly in time between the steps
+//! - Pathlines show the trajectory of one particle per seed up to the current
+//!   time step of the animation
+//! - Streaklines connect the particles released from each seed at every time
+//!   step, as dye injected into the flow
+//! - Seeds are placed on the line, plane or sphere widget; lines are colored by
+//!   the vector magnitude with the color map of the color bar
+//!
+//! The lines follow the animation timeline and are a child of the model. Open
+//! the panel via Mesh > Pathlines.
+
+use crate::animation::TimeSeriesAsset;
+use crate::mesh::color_maps::get_color_map;
+use crate::mesh::pathlines::{streaklines_at, ParticleSettings, Pathline, TimeVaryingField};
+use crate::streamlines::{line_mesh, SeedSource, Source};
+use crate::ui::{ColorBarConfig, CurrentModelData, ModelLoadedEvent, UserModelMesh};
+use crate::volume_slices::VolumeSlices;
+use crate::widgets::{WidgetChangedEvent, WidgetState};
+use bevy::math::Vec3;
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Lines shown for the traced particles
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParticleLines {
+    /// Trajectory of the particle released at the first time step
+    #[default]
+    Pathlines,
+    /// Particles released at every time step, connected by seed
+    Streaklines,
+}
+
+/// Pathline settings
+#[derive(Resource)]
+pub struct PathlineSettings {
+    /// Show the pathline panel
+    pub show_panel: bool,
+    /// Show the lines
+    pub enabled: bool,
+    /// Traced point vector attribute
+    pub attribute: Option<String>,
+    pub lines: ParticleLines,
+    pub seed_source: SeedSource,
+    /// Number of seeds (rounded up to a square grid on the plane)
+    pub seed_count: usize,
+    /// Integration steps between two time steps
+    pub substeps: usize,
+    /// Factor converting the step times into the time unit of the vectors
+    pub time_scale: f32,
+    /// Settings changed, the particles need to be traced again
+    pub changed: bool,
+    /// A slider is being dragged, trace once released
+    dragging: bool,
+}
+
+impl Default for PathlineSettings {
+    fn default() -> Self {
+        Self {
+            show_panel: false,
+            enabled: false,
+            attribute: None,
+            lines: ParticleLines::default(),
+            seed_source: SeedSource::default(),
+            seed_count: 20,
+            substeps: 4,
+            time_scale: 1.0,
+            changed: false,
+            dragging: false,
+        }
+    }
+}
+
+/// Particles of the last tracing
+#[derive(Resource, Default)]
+struct ParticleTraces {
+    /// Particles of every seed, ordered by release time
+    particles: Vec<Vec<Pathline>>,
+    /// Time steps of the traced series
+    time_steps: usize,
+    /// Smallest and largest speed of the particles
+    speed_range: (f32, f32),
+    /// Time the lines are shown for
+    shown_time: Option<f32>,
+    /// Outcome of the last tracing, `Err` holds the error message
+    status: Option<Result<String, String>>,
+}
+
+/// Marker component of the pathline entity
+#[derive(Component)]
+pub struct PathlineMesh;
+
+pub struct PathlinePlugin;
+
+impl Plugin for PathlinePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PathlineSettings>()
+            .init_resource::<ParticleTraces>()
+            .add_systems(
+                Update,
+                (
+                    refresh_on_change,
+                    pathline_panel.after(EguiSet::InitContexts),
+                    trace_particles,
+                    update_pathline_mesh,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Vector attributes of the model that every time step carries
+fn series_attribute_names(source: &Source, time_series: &TimeSeriesAsset) -> Vec<String> {
+    source
+        .attribute_names()
+        .into_iter()
+        .filter(|name| {
+            time_series
+                .time_steps
+                .iter()
+                .all(|step| step.vectors.contains_key(name))
+        })
+        .collect()
+}
+
+/// Trace again for a newly loaded model or series, or a moved seed widget
+fn refresh_on_change(
+    mut loaded_events: EventReader<ModelLoadedEvent>,
+    mut widget_changed_events: EventReader<WidgetChangedEvent>,
+    mut settings: ResMut<PathlineSettings>,
+    traces: Res<ParticleTraces>,
+    time_series: Res<TimeSeriesAsset>,
+) {
+    let loaded = loaded_events.read().count() > 0;
+    let seed_widget = settings.seed_source.widget();
+    let seeds_moved = widget_changed_events
+        .read()
+        .any(|event| event.0 == seed_widget);
+    let series_loaded =
+        time_series.is_step2_complete && time_series.time_steps.len() != traces.time_steps;
+    if (loaded || seeds_moved || series_loaded) && settings.enabled && !settings.changed {
+        settings.changed = true;
+    }
+}
+
+/// Pathline panel
+#[allow(clippy::too_many_arguments)]
+fn pathline_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<PathlineSettings>,
+    mut widget_state: ResMut<WidgetState>,
+    traces: Res<ParticleTraces>,
+    time_series: Res<TimeSeriesAsset>,
+    current_model: Res<CurrentModelData>,
+    volume_slices: Res<VolumeSlices>,
+    windows: Query<&Window>,
+) {
+    if !settings.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let source = Source::find(&current_model, &volume_slices);
+    let mut enabled = settings.enabled;
+    let mut attribute = settings.attribute.clone();
+    let mut lines = settings.lines;
+    let mut seed_source = settings.seed_source;
+    let mut seed_count = settings.seed_count;
+    let mut substeps = settings.substeps;
+    let mut time_scale = settings.time_scale;
+    let mut dragging = false;
+    let mut open = true;
+
+    egui::Window::new("Pathlines")
+        .default_width(280.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            if !time_series.is_step2_complete || time_series.time_steps.len() < 2 {
+                ui.label("Load a time series of unstructured grids or .vti volumes.");
+                return;
+            }
+            let Some(source) = source else {
+                ui.label("The time series has no volume cells or volume.");
+                return;
+            };
+            let names = series_attribute_names(&source, &time_series);
+            if names.is_empty() {
+                ui.label("The time steps have no common point vectors.");
+                return;
+            }
+            if attribute.as_ref().is_none_or(|name| !names.contains(name)) {
+                attribute = names.first().cloned();
+            }
+
+            ui.checkbox(&mut enabled, "Show particle lines");
+            ui.separator();
+            egui::ComboBox::from_label("Vectors")
+                .selected_text(attribute.clone().unwrap_or_default())
+                .show_ui(ui, |ui| {
+                    for name in &names {
+                        ui.selectable_value(&mut attribute, Some(name.clone()), name);
+                    }
+                });
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut lines, ParticleLines::Pathlines, "Pathlines");
+                ui.radio_value(&mut lines, ParticleLines::Streaklines, "Streaklines");
+            });
+
+            ui.label("Seeds:");
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut seed_source, SeedSource::Line, "Line");
+                ui.radio_value(&mut seed_source, SeedSource::Plane, "Plane");
+                ui.radio_value(&mut seed_source, SeedSource::Sphere, "Sphere");
+            });
+            let response = ui.add(egui::Slider::new(&mut seed_count, 1..=200).text("Seed count"));
+            dragging |= response.dragged();
+
+            let response =
+                ui.add(egui::Slider::new(&mut substeps, 1..=32).text("Steps per time step"));
+            dragging |= response.dragged();
+            ui.horizontal(|ui| {
+                ui.label("Time scale:");
+                ui.add(
+                    egui::DragValue::new(&mut time_scale)
+                        .speed(0.01)
+                        .range(0.0..=f32::MAX),
+                )
+                .on_hover_text("Vector time units per unit of the time step times");
+            });
+
+            if enabled {
+                ui.separator();
+                match &traces.status {
+                    Some(Ok(message)) => {
+                        ui.label(message);
+                    }
+                    Some(Err(message)) => {
+                        ui.colored_label(egui::Color32::LIGHT_RED, message);
+                    }
+                    None => {}
+                }
+            }
+        });
+
+    // Show the seed widget when the lines are turned on or the source changes
+    if enabled && (!settings.enabled || seed_source != settings.seed_source) {
+        seed_source.enable_widget(&mut widget_state);
+    }
+    if enabled != settings.enabled
+        || attribute != settings.attribute
+        || lines != settings.lines
+        || seed_source != settings.seed_source
+        || seed_count != settings.seed_count
+        || substeps != settings.substeps
+        || time_scale != settings.time_scale
+    {
+        settings.enabled = enabled;
+        settings.attribute = attribute;
+        settings.lines = lines;
+        settings.seed_source = seed_source;
+        settings.seed_count = seed_count;
+        settings.substeps = substeps;
+        settings.time_scale = time_scale;
+        settings.changed = true;
+    }
+    if dragging != settings.dragging {
+        settings.dragging = dragging;
+    }
+
+    if !open {
+        settings.show_panel = false;
+    }
+}
+
+/// Trace the particles after a settings, seed or series change
+fn trace_particles(
+    mut settings: ResMut<PathlineSettings>,
+    mut traces: ResMut<ParticleTraces>,
+    widget_state: Res<WidgetState>,
+    time_series: Res<TimeSeriesAsset>,
+    current_model: Res<CurrentModelData>,
+    volume_slices: Res<VolumeSlices>,
+) {
+    if !settings.changed || settings.dragging || widget_state.is_dragging() {
+        return;
+    }
+    settings.changed = false;
+    *traces = ParticleTraces {
+        time_steps: time_series.time_steps.len(),
+        ..default()
+    };
+    if !settings.enabled || !time_series.is_step2_complete {
+        return;
+    }
+    let (Some(source), Some(attribute)) = (
+        Source::find(&current_model, &volume_slices),
+        settings.attribute.clone(),
+    ) else {
+        return;
+    };
+    let Some(field) = source.field(&attribute) else {
+        traces.status = Some(Err(format!("No vector field '{}' to trace", attribute)));
+        return;
+    };
+
+    let steps = time_series
+        .time_steps
+        .iter()
+        .filter_map(|step| {
+            let vectors = step.vectors.get(&attribute)?;
+            Some((step.time, vectors.iter().map(|&v| Vec3::from(v)).collect()))
+        })
+        .collect();
+    let field = match TimeVaryingField::new(field, steps) {
+        Ok(field) => field,
+        Err(e) => {
+            println!("Pathline tracing failed: {}", e);
+            traces.status = Some(Err(e.to_string()));
+            return;
+        }
+    };
+
+    let seeds = settings
+        .seed_source
+        .seeds(&widget_state, settings.seed_count);
+    let particle_settings = ParticleSettings {
+        substeps: settings.substeps,
+        time_scale: settings.time_scale,
+    };
+    traces.particles = match settings.lines {
+        ParticleLines::Pathlines => field
+            .pathlines(&seeds, &particle_settings)
+            .into_iter()
+            .map(|line| vec![line])
+            .collect(),
+        ParticleLines::Streaklines => field.streak_particles(&seeds, &particle_settings),
+    };
+    traces.speed_range = traces
+        .particles
+        .iter()
+        .flatten()
+        .flat_map(|line| line.speeds.iter().copied())
+        .filter(|speed| speed.is_finite())
+        .fold(None, |range, speed| match range {
+            None => Some((speed, speed)),
+            Some((min, max)) => Some((f32::min(min, speed), f32::max(max, speed))),
+        })
+        .unwrap_or((0.0, 1.0));
+
+    let particle_count: usize = traces.particles.iter().map(Vec::len).sum();
+    let message = format!(
+        "{} particles from {} seeds over {} time steps",
+        particle_count,
+        traces.particles.len(),
+        field.times().len()
+    );
+    println!("Traced '{}': {}", attribute, message);
+    traces.status = Some(Ok(message));
+}
+
+/// Show the lines at the current time of the animation
+#[allow(clippy::too_many_arguments)]
+fn update_pathline_mesh(
+    mut commands: Commands,
+    mut traces: ResMut<ParticleTraces>,
+    settings: Res<PathlineSettings>,
+    time_series: Res<TimeSeriesAsset>,
+    color_bar_config: Res<ColorBarConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    models: Query<Entity, With<UserModelMesh>>,
+    pathlines: Query<(Entity, &Mesh3d), With<PathlineMesh>>,
+) {
+    let time = time_series
+        .get_current_time_step_data()
+        .map(|step| step.time)
+        .filter(|_| settings.enabled && !traces.particles.is_empty());
+    if time == traces.shown_time && (time.is_some() || pathlines.is_empty()) {
+        return;
+    }
+    let (Some(time), Some(model)) = (time, models.iter().next()) else {
+        traces.shown_time = None;
+        for (entity, _) in pathlines.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    };
+    traces.shown_time = Some(time);
+
+    let lines: Vec<_> = traces
+        .particles
+        .iter()
+        .flat_map(|particles| match settings.lines {
+            ParticleLines::Pathlines => particles
+                .iter()
+                .map(|line| line.until(time))
+                .filter(|line| line.points.len() >= 2)
+                .collect(),
+            ParticleLines::Streaklines => streaklines_at(particles, time),
+        })
+        .collect();
+    if lines.is_empty() {
+        for (entity, _) in pathlines.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+
+    let (mut mesh, speeds) = line_mesh(&lines);
+    let (min, max) = traces.speed_range;
+    let color_map = get_color_map(&color_bar_config.color_map_name);
+    let span = (max - min).max(f32::EPSILON);
+    let colors: Vec<[f32; 4]> = speeds
+        .iter()
+        .map(|speed| color_map.get_interpolated_color(((speed - min) / span).clamp(0.0, 1.0)))
+        .collect();
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+
+    // Replace the mesh of the shown lines, so the entity is kept while playing
+    if let Some((_, mesh3d)) = pathlines.iter().next() {
+        meshes.insert(&mesh3d.0, mesh);
+        return;
+    }
+    commands
+        .spawn((
+            Name::new("Pathlines"),
+            Mesh3d(meshes.add(mesh)),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: Color::WHITE,
+                unlit: true,
+                cull_mode: None,
+                ..default()
+            })),
+            Transform::IDENTITY,
+            PathlineMesh,
+        ))
+        .set_parent(model);
+}
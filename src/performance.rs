@@ -0,0 +1,213 @@
+//! # Performance Overlay Module
+//!
+//! This module shows an optional HUD in the top-left corner of the viewport to
+//! help diagnose slowdowns on big datasets:
+//! - Frame time and FPS (smoothed)
+//! - Rendered triangles and mesh draws of the visible meshes
+//! - Active LOD level of every LOD-managed model
+//! - CPU-side asset memory of meshes and textures
+//!
+//! Mesh draws count visible mesh entities, so they are an upper bound of the
+//! actual draw calls (Bevy batches meshes sharing mesh and material).
+//!
+//! Toggle the HUD via View > Performance HUD or F3 (configurable in
+//! File > Preferences).
+
+use crate::lod::LODManager;
+use crate::preferences::{Action, KeyBindings};
+use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Interval between statistics updates in seconds
+const STATS_UPDATE_INTERVAL: f32 = 0.5;
+
+/// Performance HUD settings
+#[derive(Resource, Default)]
+pub struct PerformanceHud {
+    /// Show the HUD
+    pub visible: bool,
+}
+
+/// Scene statistics, refreshed periodically while the HUD is visible
+#[derive(Resource)]
+struct PerformanceStats {
+    timer: Timer,
+    triangles: usize,
+    mesh_draws: usize,
+    lod_levels: Vec<String>,
+    mesh_memory: usize,
+    image_memory: usize,
+}
+
+impl Default for PerformanceStats {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(STATS_UPDATE_INTERVAL, TimerMode::Repeating),
+            triangles: 0,
+            mesh_draws: 0,
+            lod_levels: Vec::new(),
+            mesh_memory: 0,
+            image_memory: 0,
+        }
+    }
+}
+
+pub struct PerformancePlugin;
+
+impl Plugin for PerformancePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin);
+        }
+        app.init_resource::<PerformanceHud>()
+            .init_resource::<PerformanceStats>()
+            .add_systems(
+                Update,
+                (
+                    toggle_performance_hud,
+                    update_performance_stats,
+                    draw_performance_hud.after(EguiSet::InitContexts),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Toggle the HUD with its shortcut, F3 by default
+fn toggle_performance_hud(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut hud: ResMut<PerformanceHud>,
+) {
+    if key_bindings.just_pressed(&keyboard_input, Action::TogglePerformanceHud) {
+        hud.visible = !hud.visible;
+    }
+}
+
+/// Number of triangles drawn for a mesh
+fn triangle_count(mesh: &Mesh) -> usize {
+    if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+        return 0;
+    }
+    mesh.indices()
+        .map(|indices| indices.len())
+        .unwrap_or_else(|| mesh.count_vertices())
+        / 3
+}
+
+/// CPU-side size of a mesh in bytes (vertex attributes and indices)
+fn mesh_memory(mesh: &Mesh) -> usize {
+    let index_bytes = match mesh.indices() {
+        Some(Indices::U16(indices)) => indices.len() * 2,
+        Some(Indices::U32(indices)) => indices.len() * 4,
+        None => 0,
+    };
+    mesh.count_vertices() * mesh.get_vertex_size() as usize + index_bytes
+}
+
+/// Refresh the scene statistics
+fn update_performance_stats(
+    time: Res<Time>,
+    hud: Res<PerformanceHud>,
+    mut stats: ResMut<PerformanceStats>,
+    meshes: Res<Assets<Mesh>>,
+    images: Res<Assets<Image>>,
+    mesh_entities: Query<(&Mesh3d, &ViewVisibility)>,
+    lod_managers: Query<&LODManager>,
+) {
+    if !hud.visible || !stats.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let mut triangles = 0;
+    let mut mesh_draws = 0;
+    for (mesh, visibility) in mesh_entities.iter() {
+        if !visibility.get() {
+            continue;
+        }
+        if let Some(mesh) = meshes.get(&mesh.0) {
+            triangles += triangle_count(mesh);
+            mesh_draws += 1;
+        }
+    }
+
+    stats.triangles = triangles;
+    stats.mesh_draws = mesh_draws;
+    stats.lod_levels = lod_managers
+        .iter()
+        .map(|manager| format!("{:?}", manager.current_lod))
+        .collect();
+    stats.mesh_memory = meshes.iter().map(|(_, mesh)| mesh_memory(mesh)).sum();
+    stats.image_memory = images.iter().map(|(_, image)| image.data.len()).sum();
+}
+
+/// Format a byte count with a binary unit
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// Draw the HUD
+fn draw_performance_hud(
+    mut contexts: EguiContexts,
+    hud: Res<PerformanceHud>,
+    stats: Res<PerformanceStats>,
+    diagnostics: Res<DiagnosticsStore>,
+) {
+    if !hud.visible {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let fps = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.smoothed())
+        .unwrap_or(0.0);
+    let frame_time = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FRAME_TIME)
+        .and_then(|frame_time| frame_time.smoothed())
+        .unwrap_or(0.0);
+    let lod_levels = if stats.lod_levels.is_empty() {
+        "-".to_string()
+    } else {
+        stats.lod_levels.join(", ")
+    };
+
+    egui::Area::new(egui::Id::new("performance_hud"))
+        .anchor(egui::Align2::LEFT_TOP, [10.0, 40.0])
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::none()
+                .fill(egui::Color32::from_black_alpha(180))
+                .rounding(4.0)
+                .inner_margin(6.0)
+                .show(ui, |ui| {
+                    let lines = [
+                        format!("Frame time: {:>7.2} ms", frame_time),
+                        format!("FPS:        {:>7.1}", fps),
+                        format!("Triangles:  {:>7}", stats.triangles),
+                        format!("Mesh draws: {:>7}", stats.mesh_draws),
+                        format!("LOD:        {}", lod_levels),
+                        format!("Meshes:     {}", format_bytes(stats.mesh_memory)),
+                        format!("Textures:   {}", format_bytes(stats.image_memory)),
+                    ];
+                    for line in lines {
+                        ui.label(
+                            egui::RichText::new(line)
+                                .monospace()
+                                .color(egui::Color32::WHITE),
+                        );
+                    }
+                });
+        });
+}
@@ -0,0 +1,242 @@
+//! # Periodic Replication Module
+//!
+//! This module tiles the model for simulations with periodic boundaries:
+//! - The model is repeated N times along each chosen axis
+//! - The period of each axis defaults to the model extent and can be edited
+//! - Copies share the mesh and material of the model (instanced), so color map
+//!   changes, LOD switches and clipping apply to all of them
+//!
+//! Copies are children of the model and follow its transform. Open the panel via
+//! View > Periodic Replication.
+
+use crate::render::ClipMaterial;
+use crate::ui::{CurrentModelData, ModelLoadedEvent, UserModelMesh};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Maximum number of tiles along one axis
+const MAX_TILES: u32 = 16;
+
+/// Material of the model (standard, or clip material once clipping took over)
+pub type ModelMaterials = (
+    Option<&'static MeshMaterial3d<StandardMaterial>>,
+    Option<&'static MeshMaterial3d<ClipMaterial>>,
+);
+
+/// The model itself, without its copies
+type ModelOnly = (With<UserModelMesh>, Without<PeriodicCopy>);
+
+/// Periodic replication settings
+#[derive(Resource)]
+pub struct PeriodicSettings {
+    /// Number of tiles along X, Y and Z (1 = no replication)
+    pub tiles: [u32; 3],
+    /// Period along X, Y and Z (model units)
+    pub period: [f32; 3],
+    /// Show the replication panel
+    pub show_panel: bool,
+    /// Settings changed, the copies need to be rebuilt
+    pub changed: bool,
+}
+
+impl Default for PeriodicSettings {
+    fn default() -> Self {
+        Self {
+            tiles: [1; 3],
+            period: [1.0; 3],
+            show_panel: false,
+            changed: false,
+        }
+    }
+}
+
+/// Marker component for periodic copies of the model
+#[derive(Component)]
+pub struct PeriodicCopy;
+
+pub struct PeriodicPlugin;
+
+impl Plugin for PeriodicPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PeriodicSettings>().add_systems(
+            Update,
+            (
+                reset_period_on_model_load,
+                periodic_panel.after(EguiSet::InitContexts),
+                update_periodic_copies,
+                sync_copy_handles,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Use the extent of a newly loaded model as period
+fn reset_period_on_model_load(
+    mut model_loaded_events: EventReader<ModelLoadedEvent>,
+    current_model: Res<CurrentModelData>,
+    mut settings: ResMut<PeriodicSettings>,
+) {
+    for _ in model_loaded_events.read() {
+        let Some(ref geometry) = current_model.geometry else {
+            continue;
+        };
+        let Some(first) = geometry.vertices.first() else {
+            continue;
+        };
+        let (min, max) = geometry
+            .vertices
+            .iter()
+            .fold((Vec3::from(*first), Vec3::from(*first)), |(min, max), v| {
+                (min.min(Vec3::from(*v)), max.max(Vec3::from(*v)))
+            });
+        // Flat axes get a unit period so that tiles do not overlap
+        settings.period =
+            (max - min)
+                .to_array()
+                .map(|extent| if extent > f32::EPSILON { extent } else { 1.0 });
+        settings.changed = true;
+    }
+}
+
+/// Periodic replication panel
+fn periodic_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<PeriodicSettings>,
+    windows: Query<&Window>,
+) {
+    if !settings.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let mut tiles = settings.tiles;
+    let mut period = settings.period;
+    let mut open = true;
+
+    egui::Window::new("Periodic Replication")
+        .default_width(260.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            egui::Grid::new("periodic_axes").show(ui, |ui| {
+                ui.label("Axis");
+                ui.label("Tiles");
+                ui.label("Period");
+                ui.end_row();
+
+                for (axis, name) in ["X", "Y", "Z"].iter().enumerate() {
+                    ui.label(*name);
+                    ui.add(egui::DragValue::new(&mut tiles[axis]).range(1..=MAX_TILES));
+                    ui.add(
+                        egui::DragValue::new(&mut period[axis])
+                            .speed(0.01)
+                            .range(0.0..=f32::MAX),
+                    );
+                    ui.end_row();
+                }
+            });
+
+            let count = tiles.iter().product::<u32>();
+            ui.label(format!("{} copies of the model", count));
+            if ui.button("Reset").clicked() {
+                tiles = [1; 3];
+            }
+        });
+
+    if tiles != settings.tiles || period != settings.period {
+        settings.tiles = tiles;
+        settings.period = period;
+        settings.changed = true;
+    }
+
+    if !open {
+        settings.show_panel = false;
+    }
+}
+
+/// Rebuild the copies when the settings or the model changed
+fn update_periodic_copies(
+    mut commands: Commands,
+    mut settings: ResMut<PeriodicSettings>,
+    models: Query<(Entity, &Mesh3d, ModelMaterials), With<UserModelMesh>>,
+    copies: Query<Entity, With<PeriodicCopy>>,
+    new_models: Query<(), Added<UserModelMesh>>,
+) {
+    if !settings.changed && new_models.is_empty() {
+        return;
+    }
+    settings.changed = false;
+
+    // Recursive despawn also detaches the copies from the model
+    for entity in copies.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let Some((model, mesh, (standard_material, clip_material))) = models.iter().next() else {
+        return;
+    };
+    let period = Vec3::from(settings.period);
+
+    let mut count = 0;
+    for x in 0..settings.tiles[0] {
+        for y in 0..settings.tiles[1] {
+            for z in 0..settings.tiles[2] {
+                if x == 0 && y == 0 && z == 0 {
+                    continue;
+                }
+                let offset = period * Vec3::new(x as f32, y as f32, z as f32);
+                let mut copy = commands.spawn((
+                    Name::new("Periodic Copy"),
+                    mesh.clone(),
+                    Transform::from_translation(offset),
+                    PeriodicCopy,
+                ));
+                if let Some(material) = clip_material {
+                    copy.insert(material.clone());
+                } else if let Some(material) = standard_material {
+                    copy.insert(material.clone());
+                }
+                copy.set_parent(model);
+                count += 1;
+            }
+        }
+    }
+
+    if count > 0 {
+        println!("Periodic replication: {} copies", count);
+    }
+}
+
+/// Keep the copies on the current mesh and material of the model
+///
+/// The model mesh changes with LOD switches, its material when clipping
+/// replaces the standard material.
+fn sync_copy_handles(
+    mut commands: Commands,
+    models: Query<(&Mesh3d, ModelMaterials), ModelOnly>,
+    mut copies: Query<(Entity, &mut Mesh3d, ModelMaterials), With<PeriodicCopy>>,
+) {
+    let Some((mesh, (standard_material, clip_material))) = models.iter().next() else {
+        return;
+    };
+    for (entity, mut copy_mesh, (copy_standard_material, copy_clip_material)) in copies.iter_mut() {
+        if copy_mesh.0 != mesh.0 {
+            copy_mesh.0 = mesh.0.clone();
+        }
+
+        if let Some(material) = clip_material {
+            if copy_clip_material.is_none_or(|copy| copy.0 != material.0) {
+                commands
+                    .entity(entity)
+                    .remove::<MeshMaterial3d<StandardMaterial>>()
+                    .insert(material.clone());
+            }
+        } else if let Some(material) = standard_material {
+            if copy_standard_material.is_none_or(|copy| copy.0 != material.0) {
+                commands
+                    .entity(entity)
+                    .remove::<MeshMaterial3d<ClipMaterial>>()
+                    .insert(material.clone());
+            }
+        }
+    }
+}
@@ -0,0 +1,425 @@
+//! # Picking Module
+//!
+//! Shared ray-casting helpers used by interactive tools:
+//! - Cursor ray construction in model space
+//! - Ray/triangle intersection against `GeometryData`
+//! - Bounding volume hierarchy over the surface triangles, rebuilt on demand
+//!   after the model changed
+//! - Current interaction mode (which tool receives left clicks)
+
+use crate::camera::WorldModelCamera;
+use crate::mesh::GeometryData;
+use crate::ui::{CurrentModelData, UserModelMesh};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_egui::EguiContexts;
+
+/// Tool that currently receives left mouse clicks in the viewport
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InteractionMode {
+    /// Only camera, model transform and widget handles react to the mouse
+    #[default]
+    Navigate,
+    /// Place and drag ruler endpoints on the surface
+    Ruler,
+    /// Select and drag individual mesh vertices
+    VertexEdit,
+    /// Select cells (pick or region growing)
+    Select,
+    /// Query attribute values on the surface
+    Probe,
+    /// Place text notes on the surface
+    Annotate,
+}
+
+pub struct PickingPlugin;
+
+impl Plugin for PickingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InteractionMode>()
+            .init_resource::<PickingBvh>()
+            .add_systems(First, invalidate_picking_bvh);
+    }
+}
+
+/// Result of a ray cast against the model surface
+#[derive(Clone, Copy, Debug)]
+pub struct SurfaceHit {
+    /// Hit position in model space
+    pub position: Vec3,
+    /// Index of the hit triangle (into `indices` / 3)
+    pub triangle: usize,
+    /// Barycentric coordinates of the hit point inside the triangle
+    pub barycentric: Vec3,
+    /// Distance along the ray
+    pub distance: f32,
+}
+
+impl SurfaceHit {
+    /// Vertex index of the triangle corner closest to the hit point
+    pub fn nearest_vertex(&self, geometry: &GeometryData) -> u32 {
+        let corners = &geometry.indices[self.triangle * 3..self.triangle * 3 + 3];
+        let b = self.barycentric;
+        if b.x >= b.y && b.x >= b.z {
+            corners[0]
+        } else if b.y >= b.z {
+            corners[1]
+        } else {
+            corners[2]
+        }
+    }
+
+    /// Original cell index of the hit triangle
+    pub fn cell_id(&self, geometry: &GeometryData) -> usize {
+        crate::mesh::topology::triangle_cell(geometry, self.triangle)
+    }
+}
+
+/// Compute the model-to-world matrix of the user model (identity if none is loaded)
+pub fn model_matrix(model_query: &Query<&GlobalTransform, With<UserModelMesh>>) -> Mat4 {
+    model_query
+        .iter()
+        .next()
+        .map(|transform| transform.compute_matrix())
+        .unwrap_or(Mat4::IDENTITY)
+}
+
+/// Cast a ray through the cursor and return it in model space
+///
+/// # Returns
+/// - `Some((origin, direction))`: Ray origin and unit direction in model space
+/// - `None`: Cursor is outside the window or no camera is available
+pub fn cursor_ray_in_model_space(
+    windows: &Query<&Window, With<PrimaryWindow>>,
+    camera_query: &Query<(&Camera, &GlobalTransform), With<WorldModelCamera>>,
+    model_to_world: Mat4,
+) -> Option<(Vec3, Vec3)> {
+    let window = windows.get_single().ok()?;
+    let cursor_position = window.cursor_position()?;
+    let (camera, camera_transform) = camera_query.get_single().ok()?;
+    let ray = camera
+        .viewport_to_world(camera_transform, cursor_position)
+        .ok()?;
+
+    let world_to_model = model_to_world.inverse();
+    let origin = world_to_model.transform_point3(ray.origin);
+    let direction = world_to_model
+        .transform_vector3(*ray.direction)
+        .normalize_or_zero();
+
+    if direction == Vec3::ZERO {
+        None
+    } else {
+        Some((origin, direction))
+    }
+}
+
+/// Check whether the pointer is over an egui panel or window
+pub fn pointer_over_ui(contexts: &mut EguiContexts) -> bool {
+    contexts
+        .try_ctx_mut()
+        .is_some_and(|ctx| ctx.is_pointer_over_area())
+}
+
+/// Distance along a ray to the first intersection with a sphere
+pub fn ray_sphere_distance(
+    origin: Vec3,
+    direction: Vec3,
+    center: Vec3,
+    radius: f32,
+) -> Option<f32> {
+    let to_center = center - origin;
+    let projection = to_center.dot(direction);
+    let distance_sq = to_center.length_squared() - projection * projection;
+    if projection < 0.0 || distance_sq > radius * radius {
+        return None;
+    }
+    Some(projection - (radius * radius - distance_sq).sqrt())
+}
+
+/// Intersect a ray with a plane
+pub fn ray_plane_intersection(
+    origin: Vec3,
+    direction: Vec3,
+    plane_point: Vec3,
+    plane_normal: Vec3,
+) -> Option<Vec3> {
+    let denominator = direction.dot(plane_normal);
+    if denominator.abs() < 1e-6 {
+        return None;
+    }
+    let t = (plane_point - origin).dot(plane_normal) / denominator;
+    if t < 0.0 {
+        return None;
+    }
+    Some(origin + direction * t)
+}
+
+/// Möller–Trumbore ray/triangle intersection (double sided)
+///
+/// # Returns
+/// - `Some((t, u, v))`: Ray parameter and barycentric coordinates of corners b and c
+/// - `None`: No intersection in front of the ray origin
+pub fn ray_triangle_intersection(
+    origin: Vec3,
+    direction: Vec3,
+    a: Vec3,
+    b: Vec3,
+    c: Vec3,
+) -> Option<(f32, f32, f32)> {
+    let edge1 = b - a;
+    let edge2 = c - a;
+    let p = direction.cross(edge2);
+    let determinant = edge1.dot(p);
+    if determinant.abs() < 1e-12 {
+        return None;
+    }
+
+    let inv_determinant = 1.0 / determinant;
+    let s = origin - a;
+    let u = s.dot(p) * inv_determinant;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let q = s.cross(edge1);
+    let v = direction.dot(q) * inv_determinant;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let t = edge2.dot(q) * inv_determinant;
+    if t < 0.0 {
+        return None;
+    }
+    Some((t, u, v))
+}
+
+/// Find the closest triangle of the geometry hit by a ray (model space)
+pub fn pick_surface(geometry: &GeometryData, origin: Vec3, direction: Vec3) -> Option<SurfaceHit> {
+    let mut closest: Option<SurfaceHit> = None;
+
+    for (triangle, corners) in geometry.indices.chunks_exact(3).enumerate() {
+        let a = Vec3::from(geometry.vertices[corners[0] as usize]);
+        let b = Vec3::from(geometry.vertices[corners[1] as usize]);
+        let c = Vec3::from(geometry.vertices[corners[2] as usize]);
+
+        if let Some((t, u, v)) = ray_triangle_intersection(origin, direction, a, b, c) {
+            if closest.is_none_or(|hit| t < hit.distance) {
+                closest = Some(SurfaceHit {
+                    position: origin + direction * t,
+                    triangle,
+                    barycentric: Vec3::new(1.0 - u - v, u, v),
+                    distance: t,
+                });
+            }
+        }
+    }
+
+    closest
+}
+
+/// Triangles per BVH leaf
+const BVH_LEAF_SIZE: usize = 4;
+
+/// Node of a `TriangleBvh`
+#[derive(Clone, Copy, Debug)]
+struct BvhNode {
+    min: Vec3,
+    max: Vec3,
+    /// First triangle of a leaf, or index of the second child of an inner node
+    /// (the first child follows the node)
+    start: usize,
+    /// Triangles of a leaf, 0 for inner nodes
+    count: usize,
+}
+
+/// Bounding volume hierarchy over the triangles of a geometry
+#[derive(Clone, Debug, Default)]
+pub struct TriangleBvh {
+    nodes: Vec<BvhNode>,
+    /// Triangle indices, grouped by leaf
+    triangles: Vec<usize>,
+    /// Vertex count of the geometry the tree was built for
+    vertex_count: usize,
+}
+
+impl TriangleBvh {
+    /// Build the tree, splitting at the median triangle center along the
+    /// longest axis
+    pub fn build(geometry: &GeometryData) -> Self {
+        let vertex_count = geometry.vertices.len();
+        let vertex = |index: u32| Vec3::from(geometry.vertices[index as usize]);
+        let mut bounds = Vec::with_capacity(geometry.indices.len() / 3);
+        let mut triangles = Vec::with_capacity(geometry.indices.len() / 3);
+        for (triangle, corners) in geometry.indices.chunks_exact(3).enumerate() {
+            if corners.iter().any(|&c| c as usize >= vertex_count) {
+                continue;
+            }
+            let (a, b, c) = (vertex(corners[0]), vertex(corners[1]), vertex(corners[2]));
+            bounds.push((a.min(b).min(c), a.max(b).max(c)));
+            triangles.push(triangle);
+        }
+
+        let mut tree = Self {
+            nodes: Vec::new(),
+            triangles: Vec::new(),
+            vertex_count,
+        };
+        let mut items: Vec<usize> = (0..triangles.len()).collect();
+        if !items.is_empty() {
+            tree.build_node(&bounds, &mut items, 0);
+        }
+        tree.triangles = items.into_iter().map(|item| triangles[item]).collect();
+        tree
+    }
+
+    /// Append the node over `items`, starting at `offset` of all items, and
+    /// its children
+    ///
+    /// `items` is reordered so every leaf covers a contiguous range.
+    fn build_node(&mut self, bounds: &[(Vec3, Vec3)], items: &mut [usize], offset: usize) -> usize {
+        let (min, max) = items.iter().fold(
+            (Vec3::splat(f32::INFINITY), Vec3::splat(f32::NEG_INFINITY)),
+            |(min, max), &item| (min.min(bounds[item].0), max.max(bounds[item].1)),
+        );
+        let index = self.nodes.len();
+        self.nodes.push(BvhNode {
+            min,
+            max,
+            start: offset,
+            count: items.len(),
+        });
+        if items.len() <= BVH_LEAF_SIZE {
+            return index;
+        }
+
+        let extent = max - min;
+        let axis = if extent.x >= extent.y && extent.x >= extent.z {
+            0
+        } else if extent.y >= extent.z {
+            1
+        } else {
+            2
+        };
+        let center = |item: usize| (bounds[item].0[axis] + bounds[item].1[axis]) * 0.5;
+        let middle = items.len() / 2;
+        items.select_nth_unstable_by(middle, |&a, &b| center(a).total_cmp(&center(b)));
+        let (left, right) = items.split_at_mut(middle);
+        self.build_node(bounds, left, offset);
+        let second = self.build_node(bounds, right, offset + middle);
+        self.nodes[index].start = second;
+        self.nodes[index].count = 0;
+        index
+    }
+
+    /// Whether the tree was built for a geometry with this many vertices
+    pub fn matches(&self, geometry: &GeometryData) -> bool {
+        self.vertex_count == geometry.vertices.len()
+    }
+
+    /// Find the closest triangle hit by a ray (model space)
+    ///
+    /// # Parameters
+    /// - `geometry`: Geometry the tree was built for
+    pub fn pick(
+        &self,
+        geometry: &GeometryData,
+        origin: Vec3,
+        direction: Vec3,
+    ) -> Option<SurfaceHit> {
+        let inverse_direction = direction.recip();
+        let mut closest: Option<SurfaceHit> = None;
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let Some(node) = self.nodes.get(index) else {
+                continue;
+            };
+            let limit = closest.map_or(f32::INFINITY, |hit| hit.distance);
+            if !ray_box_hit(origin, inverse_direction, node.min, node.max, limit) {
+                continue;
+            }
+            if node.count == 0 {
+                stack.push(node.start);
+                stack.push(index + 1);
+                continue;
+            }
+            for &triangle in &self.triangles[node.start..node.start + node.count] {
+                let Some(corners) = geometry.indices.get(triangle * 3..triangle * 3 + 3) else {
+                    continue;
+                };
+                let corner = |k: usize| {
+                    geometry
+                        .vertices
+                        .get(corners[k] as usize)
+                        .map(|&v| Vec3::from(v))
+                };
+                let (Some(a), Some(b), Some(c)) = (corner(0), corner(1), corner(2)) else {
+                    continue;
+                };
+                if let Some((t, u, v)) = ray_triangle_intersection(origin, direction, a, b, c) {
+                    if closest.is_none_or(|hit| t < hit.distance) {
+                        closest = Some(SurfaceHit {
+                            position: origin + direction * t,
+                            triangle,
+                            barycentric: Vec3::new(1.0 - u - v, u, v),
+                            distance: t,
+                        });
+                    }
+                }
+            }
+        }
+        closest
+    }
+}
+
+/// Slab test of a ray against an axis aligned box, up to a distance
+fn ray_box_hit(origin: Vec3, inverse_direction: Vec3, min: Vec3, max: Vec3, limit: f32) -> bool {
+    let t1 = (min - origin) * inverse_direction;
+    let t2 = (max - origin) * inverse_direction;
+    // NaN from 0 * infinity (ray in a box face) counts as inside the slab
+    let near = t1.min(t2).max_element().max(0.0);
+    let far = t1.max(t2).min_element().min(limit);
+    near <= far || near.is_nan() || far.is_nan()
+}
+
+/// Picking tree of the current model, built on the first pick after a change
+#[derive(Resource, Default)]
+pub struct PickingBvh {
+    tree: Option<TriangleBvh>,
+}
+
+impl PickingBvh {
+    /// Find the closest triangle of the geometry hit by a ray (model space),
+    /// like `pick_surface`
+    pub fn pick(
+        &mut self,
+        geometry: &GeometryData,
+        origin: Vec3,
+        direction: Vec3,
+    ) -> Option<SurfaceHit> {
+        self.tree(geometry).pick(geometry, origin, direction)
+    }
+
+    /// Tree of the geometry, built if missing or outdated
+    pub fn tree(&mut self, geometry: &GeometryData) -> &TriangleBvh {
+        if self
+            .tree
+            .as_ref()
+            .is_none_or(|tree| !tree.matches(geometry))
+        {
+            self.tree = Some(TriangleBvh::build(geometry));
+        }
+        self.tree.get_or_insert_with(Default::default)
+    }
+}
+
+/// Drop the picking tree when the model changed
+fn invalidate_picking_bvh(
+    current_model: Res<CurrentModelData>,
+    mut picking_bvh: ResMut<PickingBvh>,
+) {
+    if current_model.is_changed() && picking_bvh.tree.is_some() {
+        picking_bvh.tree = None;
+    }
+}
@@ -0,0 +1,670 @@
+//! # Preferences Module
+//!
+//! This module holds the user preferences and the Preferences dialog:
+//! - Keyboard shortcuts: every key-driven action (camera movement, resets,
+//!   wireframe toggle, clearing meshes, performance HUD) has up to two keys,
+//!   which are assigned by clicking a slot and pressing the new key
+//! - Shortcuts are ignored while a text field has the keyboard focus or a key
+//!   is being assigned
+//! - Appearance: dark or light theme, font size and a UI scale on top of the
+//!   display scale factor for HiDPI displays
+//! - Language of the menu bar and notifications, English or Chinese
+//!
+//! The preferences are kept in `~/.data_visualization/preferences.txt`, so
+//! they persist across sessions.
+//!
+//! Open the dialog via File > Preferences.
+
+use crate::localization::{install_cjk_font, set_language, Language};
+use crate::session::session_dir;
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet, EguiSettings};
+
+/// Preferences file name
+const PREFERENCES_FILE: &str = "preferences.txt";
+
+/// Body text size of the default egui style, in points
+const DEFAULT_FONT_SIZE: f32 = 12.5;
+
+/// UI scales offered in the dialog
+const UI_SCALES: [f32; 8] = [0.75, 1.0, 1.25, 1.5, 1.75, 2.0, 2.5, 3.0];
+
+/// Keys that can be assigned to an action
+const BINDABLE_KEYS: &[KeyCode] = &[
+    KeyCode::KeyA,
+    KeyCode::KeyB,
+    KeyCode::KeyC,
+    KeyCode::KeyD,
+    KeyCode::KeyE,
+    KeyCode::KeyF,
+    KeyCode::KeyG,
+    KeyCode::KeyH,
+    KeyCode::KeyI,
+    KeyCode::KeyJ,
+    KeyCode::KeyK,
+    KeyCode::KeyL,
+    KeyCode::KeyM,
+    KeyCode::KeyN,
+    KeyCode::KeyO,
+    KeyCode::KeyP,
+    KeyCode::KeyQ,
+    KeyCode::KeyR,
+    KeyCode::KeyS,
+    KeyCode::KeyT,
+    KeyCode::KeyU,
+    KeyCode::KeyV,
+    KeyCode::KeyW,
+    KeyCode::KeyX,
+    KeyCode::KeyY,
+    KeyCode::KeyZ,
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+    KeyCode::ArrowUp,
+    KeyCode::ArrowDown,
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowRight,
+    KeyCode::Space,
+    KeyCode::Tab,
+    KeyCode::Enter,
+    KeyCode::Backspace,
+    KeyCode::Delete,
+    KeyCode::Insert,
+    KeyCode::Home,
+    KeyCode::End,
+    KeyCode::PageUp,
+    KeyCode::PageDown,
+    KeyCode::Minus,
+    KeyCode::Equal,
+    KeyCode::BracketLeft,
+    KeyCode::BracketRight,
+    KeyCode::Semicolon,
+    KeyCode::Quote,
+    KeyCode::Comma,
+    KeyCode::Period,
+    KeyCode::Slash,
+    KeyCode::Backslash,
+    KeyCode::Backquote,
+    KeyCode::Numpad0,
+    KeyCode::Numpad1,
+    KeyCode::Numpad2,
+    KeyCode::Numpad3,
+    KeyCode::Numpad4,
+    KeyCode::Numpad5,
+    KeyCode::Numpad6,
+    KeyCode::Numpad7,
+    KeyCode::Numpad8,
+    KeyCode::Numpad9,
+];
+
+/// Action triggered by a keyboard shortcut
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    MoveForward,
+    MoveBackward,
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    ResetCamera,
+    ResetModelTransform,
+    ToggleWireframe,
+    ClearMeshes,
+    TogglePerformanceHud,
+}
+
+impl Action {
+    pub const ALL: [Action; 11] = [
+        Action::MoveForward,
+        Action::MoveBackward,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::ResetCamera,
+        Action::ResetModelTransform,
+        Action::ToggleWireframe,
+        Action::ClearMeshes,
+        Action::TogglePerformanceHud,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::MoveForward => "Move forward",
+            Action::MoveBackward => "Move backward",
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::MoveUp => "Move up",
+            Action::MoveDown => "Move down",
+            Action::ResetCamera => "Reset camera",
+            Action::ResetModelTransform => "Reset model transform (with Alt)",
+            Action::ToggleWireframe => "Toggle wireframe",
+            Action::ClearMeshes => "Clear user meshes",
+            Action::TogglePerformanceHud => "Toggle performance HUD",
+        }
+    }
+
+    /// Key of the action in the preferences file
+    fn key(self) -> &'static str {
+        match self {
+            Action::MoveForward => "move_forward",
+            Action::MoveBackward => "move_backward",
+            Action::MoveLeft => "move_left",
+            Action::MoveRight => "move_right",
+            Action::MoveUp => "move_up",
+            Action::MoveDown => "move_down",
+            Action::ResetCamera => "reset_camera",
+            Action::ResetModelTransform => "reset_model_transform",
+            Action::ToggleWireframe => "toggle_wireframe",
+            Action::ClearMeshes => "clear_meshes",
+            Action::TogglePerformanceHud => "toggle_performance_hud",
+        }
+    }
+
+    fn default_keys(self) -> [Option<KeyCode>; 2] {
+        match self {
+            Action::MoveForward => [Some(KeyCode::KeyW), Some(KeyCode::ArrowUp)],
+            Action::MoveBackward => [Some(KeyCode::KeyS), Some(KeyCode::ArrowDown)],
+            Action::MoveLeft => [Some(KeyCode::KeyA), Some(KeyCode::ArrowLeft)],
+            Action::MoveRight => [Some(KeyCode::KeyD), Some(KeyCode::ArrowRight)],
+            Action::MoveUp => [Some(KeyCode::KeyQ), None],
+            Action::MoveDown => [Some(KeyCode::KeyE), None],
+            Action::ResetCamera => [Some(KeyCode::KeyR), None],
+            Action::ResetModelTransform => [Some(KeyCode::KeyR), None],
+            Action::ToggleWireframe => [Some(KeyCode::KeyZ), None],
+            Action::ClearMeshes => [Some(KeyCode::Delete), None],
+            Action::TogglePerformanceHud => [Some(KeyCode::F3), None],
+        }
+    }
+
+    fn index(self) -> usize {
+        Action::ALL
+            .iter()
+            .position(|action| *action == self)
+            .unwrap_or(0)
+    }
+}
+
+/// Keys of every action, indexed like `Action::ALL`
+type KeyMap = [[Option<KeyCode>; 2]; Action::ALL.len()];
+
+/// Keyboard shortcuts
+#[derive(Resource)]
+pub struct KeyBindings {
+    keys: KeyMap,
+    /// Ignore shortcuts, while a text field has the keyboard focus or a key
+    /// is being assigned
+    suppressed: bool,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            keys: read_key_bindings(&read_preferences_file()),
+            suppressed: false,
+        }
+    }
+}
+
+impl KeyBindings {
+    /// Keys assigned to an action
+    pub fn keys(&self, action: Action) -> [Option<KeyCode>; 2] {
+        self.keys[action.index()]
+    }
+
+    /// Whether a key of the action is held down
+    pub fn pressed(&self, input: &ButtonInput<KeyCode>, action: Action) -> bool {
+        !self.suppressed
+            && self
+                .keys(action)
+                .into_iter()
+                .flatten()
+                .any(|key| input.pressed(key))
+    }
+
+    /// Whether a key of the action was pressed this frame
+    pub fn just_pressed(&self, input: &ButtonInput<KeyCode>, action: Action) -> bool {
+        !self.suppressed
+            && self
+                .keys(action)
+                .into_iter()
+                .flatten()
+                .any(|key| input.just_pressed(key))
+    }
+
+    /// Display text of the keys of an action, for menu entries
+    pub fn label(&self, action: Action) -> String {
+        let keys: Vec<String> = self
+            .keys(action)
+            .into_iter()
+            .flatten()
+            .map(key_label)
+            .collect();
+        if keys.is_empty() {
+            "unassigned".to_string()
+        } else {
+            keys.join(" / ")
+        }
+    }
+}
+
+/// Display name of a key, e.g. "W" for `KeyCode::KeyW`
+pub fn key_label(key: KeyCode) -> String {
+    let name = format!("{:?}", key);
+    match name
+        .strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))
+    {
+        Some(short) => short.to_string(),
+        None => name,
+    }
+}
+
+/// Key from its name in the preferences file
+fn parse_key(name: &str) -> Option<KeyCode> {
+    BINDABLE_KEYS
+        .iter()
+        .copied()
+        .find(|key| format!("{:?}", key) == name)
+}
+
+/// Default keys of every action
+fn default_key_map() -> KeyMap {
+    Action::ALL.map(Action::default_keys)
+}
+
+/// Contents of the preferences file, empty if there is none
+fn read_preferences_file() -> String {
+    std::fs::read_to_string(session_dir().join(PREFERENCES_FILE)).unwrap_or_default()
+}
+
+/// Read the key bindings from the preferences file contents
+///
+/// Lines look like `bind<TAB>action<TAB>key<TAB>key`; actions missing from
+/// the file keep their default keys, unknown lines are skipped.
+fn read_key_bindings(text: &str) -> KeyMap {
+    let mut keys = default_key_map();
+    for line in text.lines() {
+        let mut fields = line.split('\t');
+        if fields.next() != Some("bind") {
+            continue;
+        }
+        let Some(action) = fields
+            .next()
+            .and_then(|name| Action::ALL.into_iter().find(|action| action.key() == name))
+        else {
+            continue;
+        };
+        let mut slots = [None; 2];
+        for (slot, key) in slots.iter_mut().zip(fields.filter_map(parse_key)) {
+            *slot = Some(key);
+        }
+        keys[action.index()] = slots;
+    }
+    keys
+}
+
+/// Color theme of the user interface
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UiTheme {
+    Dark,
+    Light,
+}
+
+impl UiTheme {
+    /// Key of the theme in the preferences file
+    fn key(self) -> &'static str {
+        match self {
+            UiTheme::Dark => "dark",
+            UiTheme::Light => "light",
+        }
+    }
+}
+
+/// Appearance of the user interface
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct UiSettings {
+    pub theme: UiTheme,
+    /// Body text size in points; the other text styles scale along
+    pub font_size: f32,
+    /// Scale of the user interface, on top of the display scale factor
+    pub scale: f32,
+    pub language: Language,
+}
+
+impl Default for UiSettings {
+    fn default() -> Self {
+        Self {
+            theme: UiTheme::Dark,
+            font_size: DEFAULT_FONT_SIZE,
+            scale: 1.0,
+            language: Language::English,
+        }
+    }
+}
+
+impl UiSettings {
+    /// Position in egui points of a logical window position
+    ///
+    /// Overlays drawn at projected points (e.g. from
+    /// `Camera::world_to_viewport`) convert with this, so they stay in place
+    /// when the UI is scaled.
+    pub fn ui_position(&self, position: Vec2) -> egui::Pos2 {
+        egui::pos2(position.x / self.scale, position.y / self.scale)
+    }
+}
+
+/// Read the appearance from the preferences file contents
+///
+/// Lines look like `theme<TAB>dark`, `font_size<TAB>14`, `ui_scale<TAB>1.5`
+/// and `language<TAB>zh`; missing or invalid values keep their defaults.
+fn read_ui_settings(text: &str) -> UiSettings {
+    let mut settings = UiSettings::default();
+    for line in text.lines() {
+        let Some((name, value)) = line.split_once('\t') else {
+            continue;
+        };
+        match name {
+            "theme" => match value {
+                "dark" => settings.theme = UiTheme::Dark,
+                "light" => settings.theme = UiTheme::Light,
+                _ => {}
+            },
+            "font_size" => {
+                if let Ok(size) = value.parse::<f32>() {
+                    settings.font_size = size.clamp(8.0, 32.0);
+                }
+            }
+            "ui_scale" => {
+                if let Ok(scale) = value.parse::<f32>() {
+                    settings.scale = scale.clamp(0.5, 4.0);
+                }
+            }
+            "language" => {
+                if let Some(language) = Language::from_key(value) {
+                    settings.language = language;
+                }
+            }
+            _ => {}
+        }
+    }
+    settings
+}
+
+/// Write the preferences file
+fn write_preferences(keys: &KeyMap, ui_settings: &UiSettings) {
+    let mut text = String::from("# Preferences of data_visualization\n");
+    text.push_str(&format!("theme\t{}\n", ui_settings.theme.key()));
+    text.push_str(&format!("font_size\t{}\n", ui_settings.font_size));
+    text.push_str(&format!("ui_scale\t{}\n", ui_settings.scale));
+    text.push_str(&format!("language\t{}\n", ui_settings.language.key()));
+    for action in Action::ALL {
+        text.push_str("bind\t");
+        text.push_str(action.key());
+        for key in keys[action.index()].into_iter().flatten() {
+            text.push_str(&format!("\t{:?}", key));
+        }
+        text.push('\n');
+    }
+    let dir = session_dir();
+    if let Err(e) =
+        std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(dir.join(PREFERENCES_FILE), text))
+    {
+        eprintln!("Failed to save preferences: {}", e);
+    }
+}
+
+/// Preferences dialog state
+#[derive(Resource, Default)]
+pub struct PreferencesDialog {
+    /// Show the dialog
+    pub show_panel: bool,
+    /// Key slot waiting for a key press
+    capturing: Option<(Action, usize)>,
+}
+
+pub struct PreferencesPlugin;
+
+impl Plugin for PreferencesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<KeyBindings>()
+            .insert_resource(read_ui_settings(&read_preferences_file()))
+            .init_resource::<PreferencesDialog>()
+            .add_systems(
+                Update,
+                (
+                    preferences_dialog.after(EguiSet::InitContexts),
+                    apply_ui_settings,
+                    save_preferences,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Preferences dialog, key assignment and shortcut suppression
+fn preferences_dialog(
+    mut contexts: EguiContexts,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut dialog: ResMut<PreferencesDialog>,
+    mut bindings: ResMut<KeyBindings>,
+    mut ui_settings: ResMut<UiSettings>,
+    windows: Query<&Window>,
+) {
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    // Keys assigned this frame do not trigger their action
+    let suppressed = dialog.capturing.is_some() || ctx.wants_keyboard_input();
+    if bindings.suppressed != suppressed {
+        bindings.suppressed = suppressed;
+    }
+
+    if let Some((action, slot)) = dialog.capturing {
+        if keyboard_input.just_pressed(KeyCode::Escape) {
+            dialog.capturing = None;
+        } else if let Some(key) = keyboard_input
+            .get_just_pressed()
+            .find(|key| BINDABLE_KEYS.contains(key))
+        {
+            println!(
+                "Shortcut of '{}' set to {}",
+                action.label(),
+                key_label(*key)
+            );
+            bindings.keys[action.index()][slot] = Some(*key);
+            dialog.capturing = None;
+        }
+    }
+
+    if !dialog.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let mut keys = bindings.keys;
+    let mut appearance = *ui_settings;
+    let mut capturing = dialog.capturing;
+    let mut open = true;
+
+    egui::Window::new("Preferences")
+        .default_width(360.0)
+        .open(&mut open)
+        .show(ctx, |ui| {
+            ui.heading("Appearance");
+            egui::Grid::new("appearance").num_columns(2).show(ui, |ui| {
+                ui.label("Language");
+                egui::ComboBox::from_id_salt("language")
+                    .selected_text(appearance.language.label())
+                    .show_ui(ui, |ui| {
+                        for language in Language::ALL {
+                            ui.selectable_value(
+                                &mut appearance.language,
+                                language,
+                                language.label(),
+                            );
+                        }
+                    });
+                ui.end_row();
+                ui.label("Theme");
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut appearance.theme, UiTheme::Dark, "Dark");
+                    ui.radio_value(&mut appearance.theme, UiTheme::Light, "Light");
+                });
+                ui.end_row();
+                ui.label("Font size");
+                ui.add(egui::Slider::new(&mut appearance.font_size, 8.0..=24.0).step_by(0.5));
+                ui.end_row();
+                ui.label("UI scale");
+                // A combo box, since a slider would move under the pointer while scaling
+                egui::ComboBox::from_id_salt("ui_scale")
+                    .selected_text(format!("{:.0}%", appearance.scale * 100.0))
+                    .show_ui(ui, |ui| {
+                        for scale in UI_SCALES {
+                            ui.selectable_value(
+                                &mut appearance.scale,
+                                scale,
+                                format!("{:.0}%", scale * 100.0),
+                            );
+                        }
+                    });
+                ui.end_row();
+            });
+            if ui.button("Reset Appearance").clicked() {
+                appearance = UiSettings {
+                    language: appearance.language,
+                    ..default()
+                };
+            }
+
+            ui.separator();
+            ui.heading("Keyboard Shortcuts");
+            ui.label("Click a key to change it, right click to remove it.");
+            ui.separator();
+
+            egui::Grid::new("key_bindings")
+                .num_columns(3)
+                .striped(true)
+                .show(ui, |ui| {
+                    for action in Action::ALL {
+                        ui.label(action.label());
+                        for (slot, key) in keys[action.index()].iter_mut().enumerate() {
+                            let text = if capturing == Some((action, slot)) {
+                                "Press a key...".to_string()
+                            } else {
+                                key.map_or("-".to_string(), key_label)
+                            };
+                            let response = ui.add_sized([90.0, 18.0], egui::Button::new(text));
+                            if response.clicked() {
+                                capturing = Some((action, slot));
+                            }
+                            if response.secondary_clicked() {
+                                *key = None;
+                                capturing = None;
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+            if capturing.is_some() {
+                ui.label(egui::RichText::new("Press Escape to cancel.").weak());
+            }
+
+            ui.separator();
+            if ui.button("Reset Shortcuts").clicked() {
+                keys = default_key_map();
+                capturing = None;
+            }
+        });
+
+    if !open {
+        dialog.show_panel = false;
+        capturing = None;
+    }
+    if keys != bindings.keys {
+        bindings.keys = keys;
+    }
+    if appearance != *ui_settings {
+        *ui_settings = appearance;
+    }
+    if capturing != dialog.capturing {
+        dialog.capturing = capturing;
+    }
+}
+
+/// Apply theme, font size, UI scale and language when they change
+fn apply_ui_settings(
+    mut contexts: EguiContexts,
+    settings: Res<UiSettings>,
+    mut egui_settings: Query<&mut EguiSettings>,
+    mut applied: Local<Option<UiSettings>>,
+    mut cjk_font_installed: Local<bool>,
+) {
+    if *applied == Some(*settings) {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    ctx.set_theme(match settings.theme {
+        UiTheme::Dark => egui::Theme::Dark,
+        UiTheme::Light => egui::Theme::Light,
+    });
+    let default_styles = egui::Style::default().text_styles;
+    let factor = settings.font_size / DEFAULT_FONT_SIZE;
+    ctx.all_styles_mut(|style| {
+        for (text_style, font) in style.text_styles.iter_mut() {
+            if let Some(default) = default_styles.get(text_style) {
+                font.size = default.size * factor;
+            }
+        }
+    });
+    for mut egui_settings in egui_settings.iter_mut() {
+        egui_settings.scale_factor = settings.scale;
+    }
+    set_language(settings.language);
+    if settings.language == Language::Chinese && !*cjk_font_installed {
+        // Tried once; without a font the text shows as boxes
+        install_cjk_font(ctx);
+        *cjk_font_installed = true;
+    }
+    *applied = Some(*settings);
+}
+
+/// Write the preferences file when the key bindings or the appearance change
+fn save_preferences(
+    bindings: Res<KeyBindings>,
+    ui_settings: Res<UiSettings>,
+    mut saved: Local<Option<(KeyMap, UiSettings)>>,
+) {
+    let current = (bindings.keys, *ui_settings);
+    match *saved {
+        // The preferences were just read from the file
+        None => *saved = Some(current),
+        Some(previous) if previous != current => {
+            write_preferences(&bindings.keys, &ui_settings);
+            *saved = Some(current);
+        }
+        Some(_) => {}
+    }
+}
@@ -0,0 +1,337 @@
+//! # Probe Module
+//!
+//! This module queries attribute values under the cursor:
+//! - Left click on the surface ray-casts against the geometry
+//! - Point attributes are interpolated inside the hit triangle, cell attributes
+//!   are read from the hit cell
+//! - All values are listed in the Probe window and can be copied as CSV
+//! - The hit cell is outlined; the window lists its vertex ids with the point
+//!   attribute values of every vertex
+//!
+//! ## Control Scheme (Tools > Probe)
+//! - Left click on the surface: Probe the clicked location
+
+use crate::camera::WorldModelCamera;
+use crate::mesh::probe::{cell_surface, probe_attributes, vertex_attributes, ProbedValue};
+use crate::mesh::AttributeLocation;
+use crate::picking::{
+    cursor_ray_in_model_space, model_matrix, pointer_over_ui, InteractionMode, PickingBvh,
+};
+use crate::render::OverlayGizmos;
+use crate::ui::{CurrentModelData, ModelLoadedEvent, UserModelMesh};
+use crate::widgets::{widget_drag_input, WidgetState};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Probe marker radius relative to the model size
+const MARKER_RADIUS_FACTOR: f32 = 0.01;
+
+const PROBE_COLOR: Color = Color::srgb(0.2, 1.0, 0.9);
+
+const CELL_OUTLINE_COLOR: Color = Color::srgb(1.0, 0.9, 0.2);
+
+/// Result of a probe
+#[derive(Clone, Debug)]
+pub struct ProbeResult {
+    /// Probed position in model space
+    pub position: Vec3,
+    /// Hit triangle
+    pub triangle: usize,
+    /// Cell owning the hit triangle
+    pub cell: usize,
+    /// Closest mesh vertex
+    pub vertex: u32,
+    /// Values of all attributes at the position
+    pub values: Vec<ProbedValue>,
+    /// Vertices of the hit cell with their point attribute values
+    pub cell_vertices: Vec<(u32, Vec<ProbedValue>)>,
+    /// Outline edges of the hit cell in model space
+    pub cell_outline: Vec<[Vec3; 2]>,
+}
+
+/// Probe state
+#[derive(Resource)]
+pub struct ProbeState {
+    pub result: Option<ProbeResult>,
+    /// Probe marker radius
+    pub marker_radius: f32,
+}
+
+impl Default for ProbeState {
+    fn default() -> Self {
+        Self {
+            result: None,
+            marker_radius: 0.02,
+        }
+    }
+}
+
+impl ProbeState {
+    /// Format the probed values as CSV for the clipboard
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("attribute,location,component,value\n");
+        let Some(ref result) = self.result else {
+            return csv;
+        };
+        csv.push_str(&format!(
+            "position,,x,{}\nposition,,y,{}\nposition,,z,{}\n",
+            result.position.x, result.position.y, result.position.z
+        ));
+        for value in &result.values {
+            for (component, x) in value.components.iter().enumerate() {
+                csv.push_str(&format!(
+                    "{},{:?},{},{}\n",
+                    value.name, value.location, component, x
+                ));
+            }
+        }
+        csv
+    }
+}
+
+pub struct ProbePlugin;
+
+impl Plugin for ProbePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ProbeState>().add_systems(
+            Update,
+            (
+                reset_probe_on_model_load,
+                probe_input.after(widget_drag_input),
+                draw_probe,
+                probe_panel.after(EguiSet::InitContexts),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Clear the probe when a new model is loaded and adapt marker size
+fn reset_probe_on_model_load(
+    mut model_loaded_events: EventReader<ModelLoadedEvent>,
+    current_model: Res<CurrentModelData>,
+    mut probe_state: ResMut<ProbeState>,
+) {
+    for event in model_loaded_events.read() {
+        probe_state.result = None;
+        if let (Some(min), Some(max)) = (event.bounds_min, event.bounds_max) {
+            probe_state.marker_radius = (max - min).length().max(1e-3) * MARKER_RADIUS_FACTOR;
+        }
+    }
+
+    if current_model.geometry.is_none() {
+        probe_state.result = None;
+    }
+}
+
+/// Probe the surface location under the cursor on left click
+#[allow(clippy::too_many_arguments)]
+fn probe_input(
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    interaction_mode: Res<InteractionMode>,
+    current_model: Res<CurrentModelData>,
+    widget_state: Res<WidgetState>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<WorldModelCamera>>,
+    model_query: Query<&GlobalTransform, With<UserModelMesh>>,
+    mut contexts: EguiContexts,
+    mut probe_state: ResMut<ProbeState>,
+    mut picking_bvh: ResMut<PickingBvh>,
+) {
+    if *interaction_mode != InteractionMode::Probe
+        || !mouse_button_input.just_pressed(MouseButton::Left)
+    {
+        return;
+    }
+
+    let alt_pressed =
+        keyboard_input.pressed(KeyCode::AltLeft) || keyboard_input.pressed(KeyCode::AltRight);
+    if alt_pressed || widget_state.is_dragging() || pointer_over_ui(&mut contexts) {
+        return;
+    }
+
+    let Some(ref geometry) = current_model.geometry else {
+        return;
+    };
+
+    let model_to_world = model_matrix(&model_query);
+    let Some((ray_origin, ray_direction)) =
+        cursor_ray_in_model_space(&windows, &camera_query, model_to_world)
+    else {
+        return;
+    };
+
+    if let Some(hit) = picking_bvh.pick(geometry, ray_origin, ray_direction) {
+        let cell = hit.cell_id(geometry);
+        let surface = cell_surface(geometry, cell);
+        let point = |vertex: u32| Vec3::from(geometry.vertices[vertex as usize]);
+        probe_state.result = Some(ProbeResult {
+            position: hit.position,
+            triangle: hit.triangle,
+            cell,
+            vertex: hit.nearest_vertex(geometry),
+            values: probe_attributes(geometry, hit.triangle, hit.barycentric.to_array()),
+            cell_vertices: surface
+                .vertices
+                .iter()
+                .map(|&vertex| (vertex, vertex_attributes(geometry, vertex)))
+                .collect(),
+            cell_outline: surface
+                .edges
+                .iter()
+                .map(|&[a, b]| [point(a), point(b)])
+                .collect(),
+        });
+    }
+}
+
+/// Draw the probe marker and the outline of the probed cell
+fn draw_probe(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    interaction_mode: Res<InteractionMode>,
+    probe_state: Res<ProbeState>,
+    model_query: Query<&GlobalTransform, With<UserModelMesh>>,
+) {
+    if *interaction_mode != InteractionMode::Probe {
+        return;
+    }
+    if let Some(ref result) = probe_state.result {
+        let model_to_world = model_matrix(&model_query);
+        gizmos.sphere(
+            Isometry3d::from_translation(model_to_world.transform_point3(result.position)),
+            probe_state.marker_radius,
+            PROBE_COLOR,
+        );
+        for [a, b] in &result.cell_outline {
+            gizmos.line(
+                model_to_world.transform_point3(*a),
+                model_to_world.transform_point3(*b),
+                CELL_OUTLINE_COLOR,
+            );
+        }
+    }
+}
+
+/// Probe result panel
+fn probe_panel(
+    mut contexts: EguiContexts,
+    mut interaction_mode: ResMut<InteractionMode>,
+    mut probe_state: ResMut<ProbeState>,
+    windows: Query<&Window>,
+) {
+    if *interaction_mode != InteractionMode::Probe || windows.iter().next().is_none() {
+        return;
+    }
+
+    egui::Window::new("Probe")
+        .default_width(300.0)
+        .show(contexts.ctx_mut(), |ui| {
+            let Some(ref result) = probe_state.result else {
+                ui.label("Click on the model to query the values at that location.");
+                ui.separator();
+                if ui.button("Close").clicked() {
+                    *interaction_mode = InteractionMode::Navigate;
+                }
+                return;
+            };
+
+            ui.label(format!(
+                "Position: ({:.4}, {:.4}, {:.4})",
+                result.position.x, result.position.y, result.position.z
+            ));
+            ui.label(format!(
+                "Cell: {}  Triangle: {}  Nearest vertex: {}",
+                result.cell, result.triangle, result.vertex
+            ));
+            let vertex_ids: Vec<String> = result
+                .cell_vertices
+                .iter()
+                .map(|(vertex, _)| vertex.to_string())
+                .collect();
+            ui.label(format!("Cell vertices: {}", vertex_ids.join(", ")));
+            ui.separator();
+
+            if result.values.is_empty() {
+                ui.label("The model has no attributes.");
+            } else {
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("probe_values")
+                            .striped(true)
+                            .num_columns(3)
+                            .show(ui, |ui| {
+                                for value in &result.values {
+                                    ui.label(&value.name);
+                                    ui.label(match value.location {
+                                        AttributeLocation::Point => "point",
+                                        AttributeLocation::Cell => "cell",
+                                    });
+                                    ui.label(value_text(value));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            }
+
+            let has_point_values = result
+                .cell_vertices
+                .iter()
+                .any(|(_, values)| !values.is_empty());
+            if has_point_values {
+                egui::CollapsingHeader::new("Cell vertex values").show(ui, |ui| {
+                    egui::ScrollArea::vertical()
+                        .id_salt("probe_vertex_scroll")
+                        .max_height(200.0)
+                        .show(ui, |ui| {
+                            egui::Grid::new("probe_vertex_values")
+                                .striped(true)
+                                .num_columns(3)
+                                .show(ui, |ui| {
+                                    for (vertex, values) in &result.cell_vertices {
+                                        for value in values {
+                                            ui.label(vertex.to_string());
+                                            ui.label(&value.name);
+                                            ui.label(value_text(value));
+                                            ui.end_row();
+                                        }
+                                    }
+                                });
+                        });
+                });
+            }
+
+            ui.separator();
+            let mut clear = false;
+            ui.horizontal(|ui| {
+                if ui.button("Copy Values").clicked() {
+                    ui.ctx().copy_text(probe_state.to_csv());
+                }
+                if ui.button("Clear").clicked() {
+                    clear = true;
+                }
+                if ui.button("Close").clicked() {
+                    clear = true;
+                    *interaction_mode = InteractionMode::Navigate;
+                }
+            });
+            if clear {
+                probe_state.result = None;
+            }
+        });
+}
+
+/// Components of a probed value, with the magnitude of vectors
+fn value_text(value: &ProbedValue) -> String {
+    let components: Vec<String> = value
+        .components
+        .iter()
+        .map(|x| format!("{:.4}", x))
+        .collect();
+    match value.magnitude() {
+        Some(magnitude) => format!("({})  |{:.4}|", components.join(", "), magnitude),
+        None => components.join(", "),
+    }
+}
@@ -0,0 +1,333 @@
+//! # Project Module
+//!
+//! This module saves the state of the visualization to a project file and
+//! restores it later:
+//! - Loaded model or time series files (and the current time step)
+//! - Color bar configuration (color map, value range, title, unit)
+//! - Camera pose and model transform
+//! - Subdivision steps and generated LOD levels
+//! - View settings (point downsampling, heightmap, periodic tiling)
+//! - Text annotations on the model
+//!
+//! Project files are written in RON (`.ron`). Opening a project loads its files
+//! and applies the settings like a recovered session (see the session module).
+//! Relative file paths are resolved against the directory of the project file.
+
+use crate::annotations::Annotation;
+use crate::session::{SessionSnapshot, SessionState};
+use crate::ui::{DialogSelection, FileDialogs};
+use bevy::prelude::*;
+use rfd::AsyncFileDialog;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Version written to new project files
+const PROJECT_VERSION: u32 = 1;
+
+/// Color bar section of a project file
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ProjectColorBar {
+    pub visible: bool,
+    pub color_map: String,
+    pub min_value: f32,
+    pub max_value: f32,
+    pub title: String,
+    pub unit: String,
+}
+
+/// Camera section of a project file
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ProjectCamera {
+    pub translation: [f32; 3],
+    /// Rotation quaternion (x, y, z, w)
+    pub rotation: [f32; 4],
+}
+
+/// Model transform section of a project file
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ProjectTransform {
+    pub translation: [f32; 3],
+    /// Rotation quaternion (x, y, z, w)
+    pub rotation: [f32; 4],
+    pub scale: [f32; 3],
+}
+
+/// Mesh processing section of a project file
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ProjectMesh {
+    /// Subdivision steps applied to the model
+    pub subdivisions: u32,
+    /// Generate LOD levels for the model
+    pub lod: bool,
+}
+
+/// View settings section of a project file
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ProjectView {
+    pub point_downsampling: bool,
+    pub point_budget: usize,
+    pub heightmap: bool,
+    pub heightmap_relief: f32,
+    pub periodic_tiles: [u32; 3],
+    pub periodic_period: [f32; 3],
+}
+
+/// Text annotation of a project file
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ProjectAnnotation {
+    /// Position in model space
+    pub position: [f32; 3],
+    pub text: String,
+}
+
+/// Contents of a project file
+///
+/// Missing sections and fields keep their defaults, so project files may be
+/// written by hand or by older versions.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ProjectFile {
+    pub version: u32,
+    /// Loaded model file
+    pub model: Option<PathBuf>,
+    /// Loaded time series files, used instead of the model if not empty
+    pub time_series: Vec<PathBuf>,
+    /// Current time step of the time series
+    pub time_step: usize,
+    pub color_bar: ProjectColorBar,
+    pub camera: ProjectCamera,
+    pub model_transform: ProjectTransform,
+    pub mesh: ProjectMesh,
+    pub view: ProjectView,
+    pub annotations: Vec<ProjectAnnotation>,
+}
+
+impl From<&SessionSnapshot> for ProjectFile {
+    fn from(snapshot: &SessionSnapshot) -> Self {
+        Self {
+            version: PROJECT_VERSION,
+            model: snapshot.model.clone(),
+            time_series: snapshot.time_series.clone(),
+            time_step: snapshot.time_step,
+            color_bar: ProjectColorBar {
+                visible: snapshot.color_bar_visible,
+                color_map: snapshot.color_map.clone(),
+                min_value: snapshot.color_range[0],
+                max_value: snapshot.color_range[1],
+                title: snapshot.color_bar_title.clone(),
+                unit: snapshot.color_bar_unit.clone(),
+            },
+            camera: ProjectCamera {
+                translation: snapshot.camera_translation.to_array(),
+                rotation: snapshot.camera_rotation.to_array(),
+            },
+            model_transform: ProjectTransform {
+                translation: snapshot.model_transform.translation.to_array(),
+                rotation: snapshot.model_transform.rotation.to_array(),
+                scale: snapshot.model_transform.scale.to_array(),
+            },
+            mesh: ProjectMesh {
+                subdivisions: snapshot.subdivisions,
+                lod: snapshot.lod,
+            },
+            view: ProjectView {
+                point_downsampling: snapshot.point_downsampling,
+                point_budget: snapshot.point_budget,
+                heightmap: snapshot.heightmap,
+                heightmap_relief: snapshot.heightmap_relief,
+                periodic_tiles: snapshot.periodic_tiles,
+                periodic_period: snapshot.periodic_period,
+            },
+            annotations: snapshot
+                .annotations
+                .iter()
+                .map(|annotation| ProjectAnnotation {
+                    position: annotation.position.to_array(),
+                    text: annotation.text.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Default for ProjectFile {
+    fn default() -> Self {
+        Self::from(&SessionSnapshot::default())
+    }
+}
+
+impl Default for ProjectColorBar {
+    fn default() -> Self {
+        ProjectFile::default().color_bar
+    }
+}
+
+impl Default for ProjectCamera {
+    fn default() -> Self {
+        ProjectFile::default().camera
+    }
+}
+
+impl Default for ProjectTransform {
+    fn default() -> Self {
+        ProjectFile::default().model_transform
+    }
+}
+
+impl Default for ProjectView {
+    fn default() -> Self {
+        ProjectFile::default().view
+    }
+}
+
+impl ProjectFile {
+    /// Convert into a session snapshot
+    ///
+    /// # Parameters
+    /// - `base_dir`: Directory relative file paths are resolved against
+    pub fn to_snapshot(&self, base_dir: &Path) -> SessionSnapshot {
+        let resolve = |path: &PathBuf| base_dir.join(path);
+        SessionSnapshot {
+            model: self.model.as_ref().map(resolve),
+            time_series: self.time_series.iter().map(resolve).collect(),
+            time_step: self.time_step,
+            subdivisions: self.mesh.subdivisions,
+            camera_translation: Vec3::from(self.camera.translation),
+            camera_rotation: Quat::from_array(self.camera.rotation).normalize(),
+            model_transform: Transform {
+                translation: Vec3::from(self.model_transform.translation),
+                rotation: Quat::from_array(self.model_transform.rotation).normalize(),
+                scale: Vec3::from(self.model_transform.scale),
+            },
+            color_map: self.color_bar.color_map.clone(),
+            color_bar_visible: self.color_bar.visible,
+            color_range: [self.color_bar.min_value, self.color_bar.max_value],
+            color_bar_title: self.color_bar.title.clone(),
+            color_bar_unit: self.color_bar.unit.clone(),
+            lod: self.mesh.lod,
+            point_downsampling: self.view.point_downsampling,
+            point_budget: self.view.point_budget,
+            heightmap: self.view.heightmap,
+            heightmap_relief: self.view.heightmap_relief,
+            periodic_tiles: self.view.periodic_tiles.map(|tiles| tiles.max(1)),
+            periodic_period: self.view.periodic_period,
+            annotations: self
+                .annotations
+                .iter()
+                .map(|annotation| Annotation {
+                    position: Vec3::from(annotation.position),
+                    text: annotation.text.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Write a project file
+pub fn save_project_file(project: &ProjectFile, path: &Path) -> Result<(), String> {
+    let text = ron::ser::to_string_pretty(project, ron::ser::PrettyConfig::default())
+        .map_err(|e| e.to_string())?;
+    std::fs::write(path, text).map_err(|e| e.to_string())
+}
+
+/// Read a project file
+pub fn load_project_file(path: &Path) -> Result<ProjectFile, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let project: ProjectFile = ron::from_str(&text).map_err(|e| e.to_string())?;
+    if project.version > PROJECT_VERSION {
+        println!(
+            "Project file version {} is newer than supported version {}",
+            project.version, PROJECT_VERSION
+        );
+    }
+    Ok(project)
+}
+
+/// Project save/open requests
+#[derive(Resource, Default)]
+pub struct ProjectState {
+    /// Save requested from the menu, handled on the next frame
+    pub save_requested: bool,
+    /// Open requested from the menu, handled on the next frame
+    pub open_requested: bool,
+}
+
+pub struct ProjectPlugin;
+
+impl Plugin for ProjectPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ProjectState>()
+            .add_systems(Update, (save_project, open_project));
+    }
+}
+
+/// File dialog filter of project files
+fn project_dialog() -> AsyncFileDialog {
+    AsyncFileDialog::new()
+        .add_filter("Project", &["ron"])
+        .set_directory(std::env::var("HOME").unwrap_or_else(|_| "/".to_string()))
+}
+
+/// Save the current session as project file on the IO task pool
+fn save_project(
+    mut project_state: ResMut<ProjectState>,
+    session: SessionState,
+    mut file_dialogs: ResMut<FileDialogs>,
+) {
+    if !project_state.save_requested {
+        return;
+    }
+    project_state.save_requested = false;
+
+    let snapshot = session.snapshot();
+    if !snapshot.has_data() {
+        println!("No loaded files to save in a project");
+        return;
+    }
+    let project = ProjectFile::from(&snapshot);
+
+    let dialog = project_dialog().set_file_name("project.ron");
+    file_dialogs.open(async move {
+        let file = dialog.save_file().await?;
+        let path = file.path();
+        match save_project_file(&project, path) {
+            Ok(()) => println!("Project saved to {}", path.display()),
+            Err(e) => eprintln!("Failed to save project: {}", e),
+        }
+        None
+    });
+}
+
+/// Ask for a project file and read it on the IO task pool; its session is
+/// restored once the dialog task finishes
+fn open_project(mut project_state: ResMut<ProjectState>, mut file_dialogs: ResMut<FileDialogs>) {
+    if !project_state.open_requested {
+        return;
+    }
+    project_state.open_requested = false;
+
+    let dialog = project_dialog();
+    file_dialogs.open(async move {
+        let file = dialog.pick_file().await?;
+        let path = file.path();
+        match load_project_file(path) {
+            Ok(project) => {
+                let base_dir = path.parent().unwrap_or(Path::new("."));
+                println!("Opened project {}", path.display());
+                Some(DialogSelection::Project(Box::new(
+                    project.to_snapshot(base_dir),
+                )))
+            }
+            Err(e) => {
+                eprintln!("Failed to open project {}: {}", path.display(), e);
+                None
+            }
+        }
+    });
+}
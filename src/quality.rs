@@ -0,0 +1,152 @@
+//! # Mesh Quality Module
+//!
+//! This module computes triangle quality metrics of the model:
+//! - Aspect ratio, minimum angle and skewness per cell
+//! - Metrics are stored as cell scalars, so they can be color-mapped and
+//!   thresholded with selection queries such as `AspectRatio > 3`
+//!
+//! Open the panel via Mesh > Mesh Quality.
+
+use crate::mesh::quality::{add_quality_attributes, QualitySummary};
+use crate::ui::color_bar::apply_custom_color_mapping;
+use crate::ui::{ColorBarConfig, CurrentModelData, ModelLoadedEvent, UserModelMesh};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Mesh quality settings
+#[derive(Resource, Default)]
+pub struct MeshQualitySettings {
+    /// Show the quality panel
+    pub show_panel: bool,
+    /// Quality computation requested in the panel
+    compute: bool,
+    /// Summary of the last computation
+    summary: Option<Vec<QualitySummary>>,
+}
+
+pub struct MeshQualityPlugin;
+
+impl Plugin for MeshQualityPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MeshQualitySettings>().add_systems(
+            Update,
+            (
+                reset_on_model_load,
+                quality_panel.after(EguiSet::InitContexts),
+                compute_quality,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// The summary of the previous model no longer applies
+fn reset_on_model_load(
+    mut loaded_events: EventReader<ModelLoadedEvent>,
+    mut settings: ResMut<MeshQualitySettings>,
+) {
+    if loaded_events.read().count() > 0 {
+        settings.summary = None;
+    }
+}
+
+/// Mesh quality panel
+fn quality_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<MeshQualitySettings>,
+    current_model: Res<CurrentModelData>,
+    windows: Query<&Window>,
+) {
+    if !settings.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let mut compute = false;
+    let mut open = true;
+
+    egui::Window::new("Mesh Quality")
+        .default_width(300.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            if current_model.geometry.is_none() {
+                ui.label("Load a model to check its quality.");
+                return;
+            }
+            if ui.button("Compute Quality").clicked() {
+                compute = true;
+            }
+            let Some(ref summary) = settings.summary else {
+                ui.label(
+                    "Metrics are stored as cell scalars 'AspectRatio', 'MinAngle' and 'Skewness'.",
+                );
+                return;
+            };
+
+            ui.separator();
+            egui::Grid::new("quality_summary")
+                .striped(true)
+                .num_columns(4)
+                .show(ui, |ui| {
+                    for header in ["Metric", "Min", "Mean", "Max"] {
+                        ui.strong(header);
+                    }
+                    ui.end_row();
+                    for metric in summary {
+                        ui.label(metric.name);
+                        ui.label(format!("{:.4}", metric.min));
+                        ui.label(format!("{:.4}", metric.mean));
+                        ui.label(format!("{:.4}", metric.max));
+                        ui.end_row();
+                    }
+                });
+            ui.separator();
+            ui.label("Select poor cells with a query such as 'AspectRatio > 3'.");
+        });
+
+    if compute {
+        settings.compute = true;
+    }
+
+    if !open {
+        settings.show_panel = false;
+    }
+}
+
+/// Compute the metrics and refresh the model colors
+fn compute_quality(
+    mut settings: ResMut<MeshQualitySettings>,
+    mut current_model: ResMut<CurrentModelData>,
+    color_bar_config: Res<ColorBarConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    model_entities: Query<&Mesh3d, With<UserModelMesh>>,
+) {
+    if !settings.compute {
+        return;
+    }
+    settings.compute = false;
+    let Some(geometry) = current_model.geometry.as_mut() else {
+        return;
+    };
+
+    let start = std::time::Instant::now();
+    let summary = add_quality_attributes(geometry);
+    println!(
+        "Computed quality of {} triangles in {:?}",
+        geometry.indices.len() / 3,
+        start.elapsed()
+    );
+    settings.summary = Some(summary);
+
+    if let Some(mesh) = model_entities
+        .get_single()
+        .ok()
+        .and_then(|mesh3d| meshes.get_mut(&mesh3d.0))
+    {
+        if let Err(e) = apply_custom_color_mapping(geometry, mesh, &color_bar_config) {
+            println!(
+                "Failed to apply color mapping after quality computation: {:?}",
+                e
+            );
+        }
+    }
+}
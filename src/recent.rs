@@ -0,0 +1,166 @@
+//! # Recent Files Module
+//!
+//! This module remembers the recently opened models and time series:
+//! - Files imported via File > Import
+//! - Time series folders, collection files and multi-step files imported via
+//!   File > Import Time Series
+//!
+//! The list is kept in `~/.data_visualization/recent.txt`, so it persists
+//! across sessions, and shown under File > Recent, most recent first.
+
+use crate::animation::TimeSeriesEvent;
+use crate::session::session_dir;
+use crate::ui::events::LoadModelEvent;
+use crate::ui::time_series_event;
+use bevy::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// Maximum number of remembered entries
+const MAX_RECENT_FILES: usize = 10;
+/// Recent files list file name
+const RECENT_FILE: &str = "recent.txt";
+
+/// How a recent entry is opened
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecentKind {
+    /// Model file, loaded with a `LoadModelEvent`
+    Model,
+    /// Time series folder or file, loaded with a `TimeSeriesEvent`
+    TimeSeries,
+}
+
+impl RecentKind {
+    /// Key of the kind in the list file
+    fn key(self) -> &'static str {
+        match self {
+            RecentKind::Model => "model",
+            RecentKind::TimeSeries => "series",
+        }
+    }
+}
+
+/// Recently opened file or folder
+#[derive(Clone, Debug)]
+pub struct RecentEntry {
+    pub path: PathBuf,
+    pub kind: RecentKind,
+}
+
+/// Recently opened files, most recent first
+#[derive(Resource)]
+pub struct RecentFiles {
+    pub entries: Vec<RecentEntry>,
+    /// Entry selected in the menu, opened on the next frame
+    pub requested: Option<usize>,
+}
+
+impl Default for RecentFiles {
+    fn default() -> Self {
+        Self {
+            entries: read_recent_files(),
+            requested: None,
+        }
+    }
+}
+
+impl RecentFiles {
+    /// Move a file to the top of the list and save the list
+    ///
+    /// # Parameters
+    /// - `path`: Opened file or folder
+    /// - `kind`: How the file was opened
+    pub fn add(&mut self, path: &Path, kind: RecentKind) {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        self.entries.retain(|entry| entry.path != path);
+        self.entries.insert(0, RecentEntry { path, kind });
+        self.entries.truncate(MAX_RECENT_FILES);
+        self.save();
+    }
+
+    /// Remove all entries
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.save();
+    }
+
+    /// Write the list as `kind<TAB>path` lines
+    fn save(&self) {
+        let text: String = self
+            .entries
+            .iter()
+            .map(|entry| format!("{}\t{}\n", entry.kind.key(), entry.path.display()))
+            .collect();
+        let dir = session_dir();
+        if let Err(e) =
+            std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(dir.join(RECENT_FILE), text))
+        {
+            eprintln!("Failed to save recent files: {}", e);
+        }
+    }
+}
+
+/// Read the saved list; unknown lines are skipped
+fn read_recent_files() -> Vec<RecentEntry> {
+    let Ok(text) = std::fs::read_to_string(session_dir().join(RECENT_FILE)) else {
+        return Vec::new();
+    };
+    text.lines()
+        .filter_map(|line| {
+            let (key, path) = line.split_once('\t')?;
+            let kind = match key {
+                "model" => RecentKind::Model,
+                "series" => RecentKind::TimeSeries,
+                _ => return None,
+            };
+            Some(RecentEntry {
+                path: PathBuf::from(path),
+                kind,
+            })
+        })
+        .take(MAX_RECENT_FILES)
+        .collect()
+}
+
+pub struct RecentFilesPlugin;
+
+impl Plugin for RecentFilesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RecentFiles>()
+            .add_systems(Update, open_recent_file);
+    }
+}
+
+/// Open the entry selected in the File > Recent menu
+fn open_recent_file(
+    mut recent_files: ResMut<RecentFiles>,
+    mut load_events: EventWriter<LoadModelEvent>,
+    mut time_series_events: EventWriter<TimeSeriesEvent>,
+) {
+    let Some(index) = recent_files.requested.take() else {
+        return;
+    };
+    let Some(entry) = recent_files.entries.get(index).cloned() else {
+        return;
+    };
+
+    if !entry.path.exists() {
+        println!("Recent file no longer exists: {}", entry.path.display());
+        recent_files.entries.remove(index);
+        recent_files.save();
+        return;
+    }
+
+    println!("Opening recent file: {}", entry.path.display());
+    match entry.kind {
+        RecentKind::Model => {
+            load_events.send(LoadModelEvent(entry.path.clone()));
+        }
+        RecentKind::TimeSeries => match time_series_event(&entry.path) {
+            Some(event) => {
+                time_series_events.send(event);
+            }
+            None => return,
+        },
+    }
+    recent_files.add(&entry.path, entry.kind);
+}
@@ -3,7 +3,25 @@
 //! Contains rendering-related functionality:
 //! - Wireframe rendering: Global wireframe mode toggle and individual control
 //! - Wave material: Dynamic wave effects implemented with GPU shaders
+//! - Splat material: Gaussian point splats for particle data
+//! - Overlay layer: Helper geometry drawn on top of the model
+//! - Render settings: Anti-aliasing options
+//! - CVD filter: Color vision deficiency simulation post-process
+//! - Clip material: Standard material with user clipping planes
+//! - GPU simplification: Compute shader vertex clustering for LOD generation
+pub mod clip_material;
+pub mod cvd_filter;
+pub mod gpu_simplify;
+pub mod overlay;
+pub mod render_settings;
+pub mod splat_material;
 pub mod wave_material;
+pub use clip_material::{create_clip_material, ClipMaterial, ClipPlanesUniform, MAX_CLIP_PLANES};
+pub use cvd_filter::CvdMode;
+pub use gpu_simplify::{GpuSimplifier, GpuSimplifyPlugin};
+pub use overlay::{OverlayGizmos, OverlayPlugin, OverlaySettings};
+pub use render_settings::{RenderSettings, RenderSettingsPlugin};
+pub use splat_material::{create_splat_mesh, PointStyle, SplatMaterial};
 pub use wave_material::{animate_wave_shader, create_flat_plane_mesh, WaveMaterial};
 
 use crate::ui::events::ToggleWireframeEvent;
@@ -26,12 +44,14 @@ pub struct ProcessedForWireframe;
 /// This system handles wireframe mode toggling with the following features:
 ///
 /// # Parameters
-/// - `keyboard_input`: Keyboard input resource for detecting Z key press
+/// - `keyboard_input`: Keyboard input resource for detecting the shortcut press
+/// - `key_bindings`: Keyboard shortcuts, Z toggles by default
 /// - `wireframe_toggle_events`: Wireframe toggle event reader for handling UI toggle requests
 /// - `config`: Mutable wireframe configuration resource for modifying global wireframe settings
 /// - `query`: Query for all entities with Mesh3d component for counting and processing
 pub fn toggle_wireframe(
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<crate::preferences::KeyBindings>,
     mut wireframe_toggle_events: EventReader<ToggleWireframeEvent>,
     mut config: ResMut<WireframeConfig>,
     query: Query<(Entity, Option<&NoWireframe>, Option<&ProcessedForWireframe>), With<Mesh3d>>,
@@ -48,8 +68,9 @@ pub fn toggle_wireframe(
         );
     }
 
-    // Toggle global wireframe mode by pressing Z key or UI button
-    let should_toggle = keyboard_input.just_pressed(KeyCode::KeyZ)
+    // Toggle global wireframe mode by pressing the shortcut or UI button
+    let should_toggle = key_bindings
+        .just_pressed(&keyboard_input, crate::preferences::Action::ToggleWireframe)
         || wireframe_toggle_events.read().next().is_some();
 
     if should_toggle {
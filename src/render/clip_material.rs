@@ -0,0 +1,69 @@
+//! Clipping plane material implementation
+//!
+//! Extends the standard PBR material with up to `MAX_CLIP_PLANES` world space
+//! planes. Fragments on the positive side of any plane are discarded in the main
+//! pass and in the prepass (depth, normals, motion vectors and shadows), so
+//! clipping never touches the mesh itself.
+// The ShaderType derive generates size checks that are never called
+#![allow(dead_code)]
+
+use bevy::{
+    pbr::{ExtendedMaterial, MaterialExtension},
+    prelude::*,
+    render::render_resource::{AsBindGroup, ShaderRef, ShaderType},
+};
+
+const SHADER_PATH: &str = "shaders/clip_planes.wgsl";
+
+/// Maximum number of simultaneous clipping planes (matches the shader)
+pub const MAX_CLIP_PLANES: usize = 6;
+
+/// Standard material with clipping planes
+pub type ClipMaterial = ExtendedMaterial<StandardMaterial, ClipPlanesExtension>;
+
+/// Clipping planes uniform
+#[derive(Clone, Debug, Default, PartialEq, Reflect, ShaderType)]
+pub struct ClipPlanesUniform {
+    /// World space planes `(normal, -dot(normal, origin))`
+    pub planes: [Vec4; MAX_CLIP_PLANES],
+    /// Number of active planes at the start of `planes`
+    pub count: u32,
+}
+
+/// Material extension holding the clipping planes
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone, Default)]
+pub struct ClipPlanesExtension {
+    #[uniform(100)]
+    pub clip_planes: ClipPlanesUniform,
+}
+
+impl MaterialExtension for ClipPlanesExtension {
+    fn fragment_shader() -> ShaderRef {
+        SHADER_PATH.into()
+    }
+
+    fn prepass_fragment_shader() -> ShaderRef {
+        SHADER_PATH.into()
+    }
+}
+
+/// Create a clip material from a standard material
+///
+/// Opaque materials are switched to alpha mask mode, which makes the prepass run
+/// the clipping fragment shader as well; fully opaque colors render unchanged.
+///
+/// # Parameters
+/// - `base`: Standard material to extend
+/// - `clip_planes`: Initial clipping planes
+pub fn create_clip_material(
+    mut base: StandardMaterial,
+    clip_planes: ClipPlanesUniform,
+) -> ClipMaterial {
+    if base.alpha_mode == AlphaMode::Opaque {
+        base.alpha_mode = AlphaMode::Mask(0.5);
+    }
+    ExtendedMaterial {
+        base,
+        extension: ClipPlanesExtension { clip_planes },
+    }
+}
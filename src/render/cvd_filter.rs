@@ -0,0 +1,280 @@
+//! Color vision deficiency (CVD) simulation filter
+//!
+//! A fullscreen post-process pass after tonemapping transforms the rendered image
+//! with the Machado et al. (2009) simulation matrices, so users can check that
+//! their color map stays readable for protanopes, deuteranopes and tritanopes.
+//! The same matrices are available on the CPU for UI elements such as the color bar.
+// The ShaderType derive generates size checks that are never called
+#![allow(dead_code)]
+
+use bevy::{
+    core_pipeline::{
+        core_3d::graph::{Core3d, Node3d},
+        fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    },
+    ecs::query::QueryItem,
+    image::BevyDefault,
+    prelude::*,
+    render::{
+        extract_component::{
+            ComponentUniforms, DynamicUniformIndex, ExtractComponent, ExtractComponentPlugin,
+            UniformComponentPlugin,
+        },
+        render_graph::{
+            NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner,
+        },
+        render_resource::{
+            binding_types::{sampler, texture_2d, uniform_buffer},
+            BindGroupEntries, BindGroupLayout, BindGroupLayoutEntries, CachedRenderPipelineId,
+            ColorTargetState, ColorWrites, FragmentState, MultisampleState, Operations,
+            PipelineCache, PrimitiveState, RenderPassColorAttachment, RenderPassDescriptor,
+            RenderPipelineDescriptor, Sampler, SamplerBindingType, SamplerDescriptor, ShaderStages,
+            ShaderType, TextureFormat, TextureSampleType,
+        },
+        renderer::{RenderContext, RenderDevice},
+        view::ViewTarget,
+        RenderApp,
+    },
+};
+
+const SHADER_PATH: &str = "shaders/cvd_filter.wgsl";
+
+/// Simulated color vision deficiency
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CvdMode {
+    /// Normal color vision (filter off)
+    #[default]
+    None,
+    /// Missing L (red) cones
+    Protanopia,
+    /// Missing M (green) cones
+    Deuteranopia,
+    /// Missing S (blue) cones
+    Tritanopia,
+}
+
+impl CvdMode {
+    pub const ALL: [CvdMode; 4] = [
+        CvdMode::None,
+        CvdMode::Protanopia,
+        CvdMode::Deuteranopia,
+        CvdMode::Tritanopia,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CvdMode::None => "Off",
+            CvdMode::Protanopia => "Protanopia",
+            CvdMode::Deuteranopia => "Deuteranopia",
+            CvdMode::Tritanopia => "Tritanopia",
+        }
+    }
+
+    /// Simulation matrix for linear RGB (Machado et al. 2009, severity 1.0)
+    pub fn matrix(&self) -> Mat3 {
+        let rows = match self {
+            CvdMode::None => return Mat3::IDENTITY,
+            CvdMode::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            CvdMode::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            CvdMode::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+        };
+        Mat3::from_cols_array_2d(&rows).transpose()
+    }
+
+    /// Simulate the deficiency for an sRGB color (components in `[0, 1]`)
+    pub fn simulate_srgb(&self, rgb: [f32; 3]) -> [f32; 3] {
+        if *self == CvdMode::None {
+            return rgb;
+        }
+        let linear = Color::srgb(rgb[0], rgb[1], rgb[2]).to_linear();
+        let simulated = (self.matrix() * Vec3::new(linear.red, linear.green, linear.blue))
+            .clamp(Vec3::ZERO, Vec3::ONE);
+        let srgb = Color::linear_rgb(simulated.x, simulated.y, simulated.z).to_srgba();
+        [srgb.red, srgb.green, srgb.blue]
+    }
+}
+
+/// Camera component enabling the filter, extracted to the render world
+#[derive(Component, Clone, Copy, ExtractComponent, ShaderType)]
+pub struct CvdFilter {
+    /// Rows of the simulation matrix (w unused)
+    row_r: Vec4,
+    row_g: Vec4,
+    row_b: Vec4,
+}
+
+impl CvdFilter {
+    pub fn new(mode: CvdMode) -> Self {
+        let matrix = mode.matrix();
+        let row = |i: usize| matrix.row(i).extend(0.0);
+        Self {
+            row_r: row(0),
+            row_g: row(1),
+            row_b: row(2),
+        }
+    }
+}
+
+pub struct CvdFilterPlugin;
+
+impl Plugin for CvdFilterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            ExtractComponentPlugin::<CvdFilter>::default(),
+            UniformComponentPlugin::<CvdFilter>::default(),
+        ));
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .add_render_graph_node::<ViewNodeRunner<CvdFilterNode>>(Core3d, CvdFilterLabel)
+            .add_render_graph_edges(
+                Core3d,
+                (
+                    Node3d::Tonemapping,
+                    CvdFilterLabel,
+                    Node3d::EndMainPassPostProcessing,
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app.init_resource::<CvdFilterPipeline>();
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+struct CvdFilterLabel;
+
+#[derive(Default)]
+struct CvdFilterNode;
+
+impl ViewNode for CvdFilterNode {
+    type ViewQuery = (
+        &'static ViewTarget,
+        &'static CvdFilter,
+        &'static DynamicUniformIndex<CvdFilter>,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (view_target, _filter, filter_index): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let filter_pipeline = world.resource::<CvdFilterPipeline>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let Some(pipeline) = pipeline_cache.get_render_pipeline(filter_pipeline.pipeline_id) else {
+            return Ok(());
+        };
+        let Some(filter_binding) = world
+            .resource::<ComponentUniforms<CvdFilter>>()
+            .uniforms()
+            .binding()
+        else {
+            return Ok(());
+        };
+
+        // Read the current image and write the filtered one
+        let post_process = view_target.post_process_write();
+        let bind_group = render_context.render_device().create_bind_group(
+            "cvd_filter_bind_group",
+            &filter_pipeline.layout,
+            &BindGroupEntries::sequential((
+                post_process.source,
+                &filter_pipeline.sampler,
+                filter_binding.clone(),
+            )),
+        );
+
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("cvd_filter_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: post_process.destination,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[filter_index.index()]);
+        render_pass.draw(0..3, 0..1);
+
+        Ok(())
+    }
+}
+
+#[derive(Resource)]
+struct CvdFilterPipeline {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+    pipeline_id: CachedRenderPipelineId,
+}
+
+impl FromWorld for CvdFilterPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let layout = render_device.create_bind_group_layout(
+            "cvd_filter_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    uniform_buffer::<CvdFilter>(true),
+                ),
+            ),
+        );
+        let sampler = render_device.create_sampler(&SamplerDescriptor::default());
+        let shader = world.resource::<AssetServer>().load(SHADER_PATH);
+
+        let pipeline_id =
+            world
+                .resource_mut::<PipelineCache>()
+                .queue_render_pipeline(RenderPipelineDescriptor {
+                    label: Some("cvd_filter_pipeline".into()),
+                    layout: vec![layout.clone()],
+                    vertex: fullscreen_shader_vertex_state(),
+                    fragment: Some(FragmentState {
+                        shader,
+                        shader_defs: vec![],
+                        entry_point: "fragment".into(),
+                        targets: vec![Some(ColorTargetState {
+                            format: TextureFormat::bevy_default(),
+                            blend: None,
+                            write_mask: ColorWrites::ALL,
+                        })],
+                    }),
+                    primitive: PrimitiveState::default(),
+                    depth_stencil: None,
+                    multisample: MultisampleState::default(),
+                    push_constant_ranges: vec![],
+                    zero_initialize_workgroup_memory: false,
+                });
+
+        Self {
+            layout,
+            sampler,
+            pipeline_id,
+        }
+    }
+}
@@ -0,0 +1,322 @@
+//! GPU-accelerated vertex clustering for mesh simplification
+//!
+//! Sorting millions of vertices into grid cells and estimating the number of
+//! occupied cells runs in a compute shader. The grid resolution is searched on
+//! the GPU until the number of clusters matches the requested vertex count;
+//! only the final cluster keys are read back and merged on the CPU.
+//!
+//! Distinct cells are counted by linear counting: cells are hashed into an
+//! occupancy table and the cell count is estimated from the fraction of empty
+//! slots. This needs only atomic exchanges, which every compute backend supports.
+//!
+//! The compute pipelines are created directly on the render device, so
+//! simplification runs synchronously from any main world system.
+
+use crate::mesh::VtkError;
+use bevy::prelude::*;
+use bevy::render::{
+    render_resource::{
+        binding_types::{
+            storage_buffer_read_only_sized, storage_buffer_sized, uniform_buffer_sized,
+        },
+        BindGroupEntries, BindGroupLayout, BindGroupLayoutEntries, Buffer, BufferDescriptor,
+        BufferInitDescriptor, BufferUsages, ComputePassDescriptor, ComputePipeline, DownlevelFlags,
+        Maintain, MapMode, PipelineLayoutDescriptor, RawComputePipelineDescriptor,
+        ShaderModuleDescriptor, ShaderSource, ShaderStages,
+    },
+    renderer::{RenderAdapter, RenderDevice, RenderQueue},
+};
+use std::sync::mpsc;
+
+const SHADER_SOURCE: &str = include_str!("../../assets/shaders/lod_clustering.wgsl");
+/// Must match `WORKGROUP_SIZE` in the shader
+const WORKGROUP_SIZE: u32 = 256;
+/// Maximum workgroups per dispatch dimension guaranteed by WebGPU
+const MAX_WORKGROUPS_PER_DIMENSION: u32 = 65535;
+/// Largest grid resolution (keys of a 1024³ grid fit in 30 bits)
+const MAX_RESOLUTION: u32 = 1024;
+const MIN_RESOLUTION: u32 = 4;
+/// Resolution search iterations
+const MAX_SEARCH_STEPS: usize = 8;
+/// Accepted relative deviation from the target triangle count
+const TARGET_TOLERANCE: f32 = 0.1;
+
+/// Compute pipelines of the clustering pre-pass
+///
+/// Available as a resource once the render device exists (not in headless
+/// command line runs).
+#[derive(Resource, Clone)]
+pub struct GpuSimplifier {
+    device: RenderDevice,
+    queue: RenderQueue,
+    layout: BindGroupLayout,
+    pipeline: ComputePipeline,
+}
+
+pub struct GpuSimplifyPlugin;
+
+impl Plugin for GpuSimplifyPlugin {
+    fn build(&self, _app: &mut App) {}
+
+    fn finish(&self, app: &mut App) {
+        let world = app.world();
+        let (Some(device), Some(queue), Some(adapter)) = (
+            world.get_resource::<RenderDevice>(),
+            world.get_resource::<RenderQueue>(),
+            world.get_resource::<RenderAdapter>(),
+        ) else {
+            println!("No render device, GPU simplification disabled");
+            return;
+        };
+        if !adapter
+            .get_downlevel_capabilities()
+            .flags
+            .contains(DownlevelFlags::COMPUTE_SHADERS)
+        {
+            println!("Compute shaders unsupported, GPU simplification disabled");
+            return;
+        }
+        let simplifier = GpuSimplifier::new(device.clone(), queue.clone());
+        app.insert_resource(simplifier);
+    }
+}
+
+impl GpuSimplifier {
+    fn new(device: RenderDevice, queue: RenderQueue) -> Self {
+        let layout = device.create_bind_group_layout(
+            "lod_clustering_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::COMPUTE,
+                (
+                    uniform_buffer_sized(false, None),
+                    storage_buffer_read_only_sized(false, None),
+                    storage_buffer_sized(false, None),
+                    storage_buffer_sized(false, None),
+                    storage_buffer_sized(false, None),
+                ),
+            ),
+        );
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("lod_clustering_pipeline_layout"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let module = device
+            .wgpu_device()
+            .create_shader_module(ShaderModuleDescriptor {
+                label: Some("lod_clustering_shader"),
+                source: ShaderSource::Wgsl(SHADER_SOURCE.into()),
+            });
+        let pipeline = device.create_compute_pipeline(&RawComputePipelineDescriptor {
+            label: Some("lod_clustering_pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &module,
+            entry_point: Some("assign_clusters"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        Self {
+            device,
+            queue,
+            layout,
+            pipeline,
+        }
+    }
+
+    /// Cluster the vertices into about `target_clusters` grid cells
+    ///
+    /// The vertices are binned into a uniform grid over the bounding box. Each
+    /// search step assigns cluster keys and estimates the distinct occupied
+    /// cells on the GPU; the resolution is then scaled with the square root of the
+    /// remaining ratio, since the cells touching a surface grow quadratically.
+    ///
+    /// # Parameters
+    /// - `vertices`: Vertex positions
+    /// - `target_clusters`: Desired number of vertices after clustering
+    ///
+    /// # Returns
+    /// - `Ok(keys)`: Grid cell key of every vertex
+    /// - `Err(VtkError)`: The mesh exceeds the device limits or the readback failed
+    pub fn cluster_keys(
+        &self,
+        vertices: &[[f32; 3]],
+        target_clusters: usize,
+    ) -> Result<Vec<u32>, VtkError> {
+        let vertex_count = vertices.len() as u32;
+        if vertex_count == 0 {
+            return Err(VtkError::MissingData("vertices"));
+        }
+        // Linear counting stays accurate while the table has more slots than cells
+        let table_size = (vertices.len() * 2).next_power_of_two();
+        let max_binding = self.device.limits().max_storage_buffer_binding_size as usize;
+        if vertices.len() * 12 > max_binding || table_size * 4 > max_binding {
+            return Err(VtkError::GenericError(format!(
+                "mesh exceeds the GPU storage buffer limit of {} bytes",
+                max_binding
+            )));
+        }
+
+        let (min, max) = vertices.iter().fold(
+            (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+            |(min, max), vertex| (min.min(Vec3::from(*vertex)), max.max(Vec3::from(*vertex))),
+        );
+        let extent = (max - min).max_element().max(1e-6);
+
+        let positions: Vec<u8> = vertices
+            .iter()
+            .flatten()
+            .flat_map(|value| value.to_le_bytes())
+            .collect();
+        let position_buffer = self.device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("lod_clustering_positions"),
+            contents: &positions,
+            usage: BufferUsages::STORAGE,
+        });
+        let storage = |label: &str, size: u64| {
+            self.device.create_buffer(&BufferDescriptor {
+                label: Some(label),
+                size,
+                usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        };
+        let key_buffer = storage("lod_clustering_keys", vertex_count as u64 * 4);
+        let table_buffer = storage("lod_clustering_table", table_size as u64 * 4);
+        let counter_buffer = storage("lod_clustering_occupied", 4);
+        let params_buffer = self.device.create_buffer(&BufferDescriptor {
+            label: Some("lod_clustering_params"),
+            size: 32,
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = self.device.create_bind_group(
+            "lod_clustering_bind_group",
+            &self.layout,
+            &BindGroupEntries::sequential((
+                params_buffer.as_entire_binding(),
+                position_buffer.as_entire_binding(),
+                key_buffer.as_entire_binding(),
+                table_buffer.as_entire_binding(),
+                counter_buffer.as_entire_binding(),
+            )),
+        );
+
+        // Assign the cluster keys of a resolution and estimate the cluster count
+        let dispatch = |resolution: u32| -> Result<f32, VtkError> {
+            let cell_size = extent / resolution as f32 * (1.0 + 1e-5);
+            let mut params = Vec::with_capacity(32);
+            for value in min.to_array().into_iter().chain([cell_size]) {
+                params.extend_from_slice(&value.to_le_bytes());
+            }
+            for value in [resolution, vertex_count, table_size as u32 - 1, 0] {
+                params.extend_from_slice(&value.to_le_bytes());
+            }
+            self.queue.write_buffer(&params_buffer, 0, &params);
+
+            let mut encoder = self.device.create_command_encoder(&Default::default());
+            encoder.clear_buffer(&table_buffer, 0, None);
+            encoder.clear_buffer(&counter_buffer, 0, None);
+            {
+                let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor {
+                    label: Some("lod_clustering_pass"),
+                    timestamp_writes: None,
+                });
+                pass.set_bind_group(0, &bind_group, &[]);
+                pass.set_pipeline(&self.pipeline);
+                let (x, y) = workgroups(vertex_count);
+                pass.dispatch_workgroups(x, y, 1);
+            }
+            self.queue.submit([encoder.finish()]);
+
+            let counter = self.read_buffer(&counter_buffer, 4)?;
+            let occupied = u32::from_le_bytes([counter[0], counter[1], counter[2], counter[3]]);
+            Ok(linear_count(occupied, table_size))
+        };
+
+        // Start from the resolution where the clusters would cover the six
+        // faces of the bounding box
+        let target = target_clusters.max(1) as f32;
+        let mut resolution = ((target / 6.0).sqrt() as u32).clamp(MIN_RESOLUTION, MAX_RESOLUTION);
+        let mut best: Option<(u32, f32)> = None;
+
+        for step in 0..MAX_SEARCH_STEPS {
+            let clusters = dispatch(resolution)?;
+            let deviation = (clusters - target).abs() / target;
+            println!(
+                "GPU clustering step {}: resolution {}, {} clusters",
+                step + 1,
+                resolution,
+                clusters
+            );
+            if best.is_none_or(|(_, best_deviation)| deviation < best_deviation) {
+                best = Some((resolution, deviation));
+            }
+            if deviation <= TARGET_TOLERANCE {
+                break;
+            }
+
+            let scale = (target / clusters.max(1.0)).sqrt();
+            let next =
+                ((resolution as f32 * scale).round() as u32).clamp(MIN_RESOLUTION, MAX_RESOLUTION);
+            if next == resolution {
+                break;
+            }
+            resolution = next;
+        }
+
+        // Re-run the closest resolution if the search ended elsewhere
+        if let Some((best_resolution, _)) = best.filter(|(best, _)| *best != resolution) {
+            dispatch(best_resolution)?;
+        }
+
+        let keys = self.read_buffer(&key_buffer, vertex_count as u64 * 4)?;
+        Ok(keys
+            .chunks_exact(4)
+            .map(|key| u32::from_le_bytes([key[0], key[1], key[2], key[3]]))
+            .collect())
+    }
+
+    /// Copy a GPU buffer into mappable memory and wait for the data
+    fn read_buffer(&self, source: &Buffer, size: u64) -> Result<Vec<u8>, VtkError> {
+        let staging = self.device.create_buffer(&BufferDescriptor {
+            label: Some("lod_clustering_readback"),
+            size,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        encoder.copy_buffer_to_buffer(source, 0, &staging, 0, size);
+        self.queue.submit([encoder.finish()]);
+
+        let slice = staging.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        self.device
+            .map_buffer(&slice, MapMode::Read, move |result| {
+                let _ = sender.send(result);
+            });
+        self.device.poll(Maintain::Wait);
+        receiver
+            .recv()
+            .map_err(|e| VtkError::GenericError(e.to_string()))?
+            .map_err(|e| VtkError::GenericError(format!("GPU readback failed: {}", e)))?;
+
+        let data = slice.get_mapped_range().to_vec();
+        staging.unmap();
+        Ok(data)
+    }
+}
+
+/// Estimate the number of distinct hashed values from the occupied table slots
+fn linear_count(occupied: u32, table_size: usize) -> f32 {
+    let table_size = table_size as f32;
+    let empty_fraction = (1.0 - occupied as f32 / table_size).max(1.0 / table_size);
+    -table_size * empty_fraction.ln()
+}
+
+/// Workgroup grid covering `count` invocations
+fn workgroups(count: u32) -> (u32, u32) {
+    let groups = count.div_ceil(WORKGROUP_SIZE);
+    let x = groups.min(MAX_WORKGROUPS_PER_DIMENSION);
+    (x, groups.div_ceil(x))
+}
@@ -0,0 +1,125 @@
+//! Overlay render layer implementation
+//!
+//! Helper geometry (widgets, rulers, edit handles, ...) is drawn with the
+//! `OverlayGizmos` gizmo group on a separate render layer. A second camera that
+//! follows the main camera renders only that layer after the scene, with its own
+//! depth buffer: helpers still occlude each other, but never disappear inside the
+//! model.
+
+use crate::camera::WorldModelCamera;
+use bevy::{prelude::*, render::view::RenderLayers};
+
+/// Render layer of the helper geometry
+pub const OVERLAY_LAYER: usize = 1;
+
+/// Gizmo group for helper geometry drawn on the overlay layer
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct OverlayGizmos;
+
+/// Camera rendering the overlay layer on top of the scene
+#[derive(Component)]
+pub struct OverlayCamera;
+
+/// Overlay settings
+#[derive(Resource)]
+pub struct OverlaySettings {
+    /// Draw helper geometry on top of the model; otherwise it is depth tested
+    /// against the scene like regular geometry
+    pub on_top: bool,
+}
+
+impl Default for OverlaySettings {
+    fn default() -> Self {
+        Self { on_top: true }
+    }
+}
+
+pub struct OverlayPlugin;
+
+impl Plugin for OverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_gizmo_group::<OverlayGizmos>()
+            .init_resource::<OverlaySettings>()
+            .add_systems(
+                Update,
+                (
+                    spawn_overlay_camera,
+                    sync_overlay_projection,
+                    apply_overlay_settings,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Attach the overlay camera to the main camera once it exists
+///
+/// The overlay camera is a child with an identity transform, so it always shares
+/// the main camera's view.
+fn spawn_overlay_camera(
+    mut commands: Commands,
+    main_cameras: Query<Entity, With<WorldModelCamera>>,
+    overlay_cameras: Query<(), With<OverlayCamera>>,
+) {
+    if !overlay_cameras.is_empty() {
+        return;
+    }
+    let Some(main_camera) = main_cameras.iter().next() else {
+        return;
+    };
+
+    commands
+        .spawn((
+            Name::new("Overlay Camera"),
+            Camera3d::default(),
+            Camera {
+                // Render after the main camera without clearing its image
+                order: 1,
+                clear_color: ClearColorConfig::None,
+                ..default()
+            },
+            Transform::IDENTITY,
+            RenderLayers::layer(OVERLAY_LAYER),
+            OverlayCamera,
+        ))
+        .set_parent(main_camera);
+}
+
+/// Keep the overlay camera projection equal to the main camera projection
+fn sync_overlay_projection(
+    main_cameras: Query<Ref<Projection>, (With<WorldModelCamera>, Without<OverlayCamera>)>,
+    mut overlay_cameras: Query<&mut Projection, With<OverlayCamera>>,
+) {
+    let Some(main_projection) = main_cameras.iter().next() else {
+        return;
+    };
+    for mut projection in overlay_cameras.iter_mut() {
+        // Newly spawned overlay cameras always take the main projection once
+        if main_projection.is_changed() || projection.is_added() {
+            *projection = main_projection.clone();
+        }
+    }
+}
+
+/// Move the helper gizmos between the overlay layer and the scene layer
+fn apply_overlay_settings(
+    settings: Res<OverlaySettings>,
+    mut config_store: ResMut<GizmoConfigStore>,
+    mut overlay_cameras: Query<&mut Camera, With<OverlayCamera>>,
+) {
+    let camera_added = overlay_cameras.iter_mut().any(|camera| camera.is_added());
+    if !settings.is_changed() && !camera_added {
+        return;
+    }
+
+    let (config, _) = config_store.config_mut::<OverlayGizmos>();
+    config.render_layers = if settings.on_top {
+        RenderLayers::layer(OVERLAY_LAYER)
+    } else {
+        RenderLayers::default()
+    };
+
+    for mut camera in overlay_cameras.iter_mut() {
+        camera.is_active = settings.on_top;
+    }
+}
@@ -0,0 +1,219 @@
+//! Render settings implementation
+//!
+//! The "Render Settings" panel (View > Render Settings) controls anti-aliasing of
+//! the main camera:
+//! - MSAA sample count (also applied to the overlay camera sharing the window)
+//! - FXAA post-process filter
+//! - TAA (temporal anti-aliasing), which requires MSAA to be off
+//!
+//! It also selects a color vision deficiency simulation filter and offers a
+//! one-click switch to a color map that stays readable under it.
+
+use super::cvd_filter::{CvdFilter, CvdFilterPlugin, CvdMode};
+use super::overlay::OverlayCamera;
+use crate::camera::WorldModelCamera;
+use crate::ui::ColorBarConfig;
+use bevy::{
+    core_pipeline::{
+        experimental::taa::{TemporalAntiAliasPlugin, TemporalAntiAliasing},
+        fxaa::Fxaa,
+        prepass::{DepthPrepass, MotionVectorPrepass},
+    },
+    prelude::*,
+    render::{camera::TemporalJitter, view::Msaa},
+};
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Cameras rendering to the window
+type WindowCameras = Or<(With<WorldModelCamera>, With<OverlayCamera>)>;
+
+/// Color map readable with all simulated deficiencies
+const CVD_SAFE_COLOR_MAP: &str = "viridis";
+
+/// Render settings
+#[derive(Resource)]
+pub struct RenderSettings {
+    /// Show the settings panel
+    pub show_panel: bool,
+    /// MSAA sample count (ignored while TAA is enabled)
+    pub msaa: Msaa,
+    /// FXAA post-process filter
+    pub fxaa: bool,
+    /// Temporal anti-aliasing
+    pub taa: bool,
+    /// Simulated color vision deficiency
+    pub cvd: CvdMode,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            show_panel: false,
+            msaa: Msaa::Sample4,
+            fxaa: false,
+            taa: false,
+            cvd: CvdMode::None,
+        }
+    }
+}
+
+impl RenderSettings {
+    /// MSAA actually used; TAA does not work with multisampling
+    pub fn effective_msaa(&self) -> Msaa {
+        if self.taa {
+            Msaa::Off
+        } else {
+            self.msaa
+        }
+    }
+}
+
+pub struct RenderSettingsPlugin;
+
+impl Plugin for RenderSettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((TemporalAntiAliasPlugin, CvdFilterPlugin))
+            .init_resource::<RenderSettings>()
+            .add_systems(
+                Update,
+                (
+                    render_settings_panel.after(EguiSet::InitContexts),
+                    apply_anti_aliasing,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Apply the anti-aliasing settings to the cameras
+fn apply_anti_aliasing(
+    mut commands: Commands,
+    settings: Res<RenderSettings>,
+    main_cameras: Query<Entity, With<WorldModelCamera>>,
+    mut cameras: Query<(Ref<Camera>, &mut Msaa), WindowCameras>,
+) {
+    let camera_added = cameras.iter().any(|(camera, _)| camera.is_added());
+    if !settings.is_changed() && !camera_added {
+        return;
+    }
+
+    // Cameras rendering to the same window need the same sample count
+    let msaa = settings.effective_msaa();
+    for (_, mut camera_msaa) in cameras.iter_mut() {
+        if *camera_msaa != msaa {
+            *camera_msaa = msaa;
+        }
+    }
+
+    for camera in main_cameras.iter() {
+        let mut entity = commands.entity(camera);
+        if settings.fxaa {
+            entity.insert(Fxaa::default());
+        } else {
+            entity.remove::<Fxaa>();
+        }
+
+        if settings.taa {
+            entity.insert(TemporalAntiAliasing::default());
+        } else {
+            entity.remove::<(
+                TemporalAntiAliasing,
+                TemporalJitter,
+                DepthPrepass,
+                MotionVectorPrepass,
+            )>();
+        }
+
+        if settings.cvd == CvdMode::None {
+            entity.remove::<CvdFilter>();
+        } else {
+            entity.insert(CvdFilter::new(settings.cvd));
+        }
+    }
+}
+
+/// Render settings panel
+fn render_settings_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<RenderSettings>,
+    mut color_bar_config: ResMut<ColorBarConfig>,
+    windows: Query<&Window>,
+) {
+    if !settings.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let mut msaa = settings.msaa;
+    let mut fxaa = settings.fxaa;
+    let mut taa = settings.taa;
+    let mut cvd = settings.cvd;
+    let mut use_safe_map = false;
+    let mut open = true;
+
+    egui::Window::new("Render Settings")
+        .default_width(220.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label("Anti-aliasing:");
+            ui.add_enabled_ui(!taa, |ui| {
+                egui::ComboBox::from_label("MSAA")
+                    .selected_text(msaa_label(msaa))
+                    .show_ui(ui, |ui| {
+                        for option in [Msaa::Off, Msaa::Sample2, Msaa::Sample4, Msaa::Sample8] {
+                            ui.selectable_value(&mut msaa, option, msaa_label(option));
+                        }
+                    });
+            });
+            ui.checkbox(&mut fxaa, "FXAA");
+            ui.checkbox(&mut taa, "TAA")
+                .on_hover_text("Temporal anti-aliasing; disables MSAA");
+
+            ui.separator();
+            ui.label("Color vision:");
+            egui::ComboBox::from_label("Simulate")
+                .selected_text(cvd.label())
+                .show_ui(ui, |ui| {
+                    for option in CvdMode::ALL {
+                        ui.selectable_value(&mut cvd, option, option.label());
+                    }
+                });
+            let safe_map_active = color_bar_config.color_map_name == CVD_SAFE_COLOR_MAP;
+            if ui
+                .add_enabled(
+                    !safe_map_active,
+                    egui::Button::new("Use CVD-safe color map"),
+                )
+                .on_hover_text("Switch to the perceptually uniform viridis map")
+                .clicked()
+            {
+                use_safe_map = true;
+            }
+        });
+
+    if msaa != settings.msaa || fxaa != settings.fxaa || taa != settings.taa || cvd != settings.cvd
+    {
+        settings.msaa = msaa;
+        settings.fxaa = fxaa;
+        settings.taa = taa;
+        settings.cvd = cvd;
+    }
+
+    if use_safe_map {
+        color_bar_config.color_map_name = CVD_SAFE_COLOR_MAP.to_string();
+        color_bar_config.has_changed = true;
+        println!("Switched to CVD-safe color map: {}", CVD_SAFE_COLOR_MAP);
+    }
+
+    if !open {
+        settings.show_panel = false;
+    }
+}
+
+fn msaa_label(msaa: Msaa) -> &'static str {
+    match msaa {
+        Msaa::Off => "Off",
+        Msaa::Sample2 => "2x",
+        Msaa::Sample4 => "4x",
+        Msaa::Sample8 => "8x",
+    }
+}
@@ -0,0 +1,148 @@
+//! Gaussian splat material implementation
+//!
+//! Every particle is drawn as a camera-facing quad that the vertex shader expands
+//! in view space; the fragment shader fades it out with a Gaussian falloff,
+//! giving smooth density-like visuals for particle (SPH/DEM) data. In the
+//! sphere style the quads are opaque, shaded discs instead, which read better
+//! for dense point clouds and need no blending order.
+// The ShaderType derive generates size checks that are never called
+#![allow(dead_code)]
+
+const SHADER_PATH: &str = "shaders/splat.wgsl";
+use bevy::{
+    pbr::{MaterialPipeline, MaterialPipelineKey},
+    prelude::*,
+    render::{
+        mesh::{Indices, MeshVertexAttribute, MeshVertexBufferLayoutRef, PrimitiveTopology},
+        render_asset::RenderAssetUsages,
+        render_resource::{
+            AsBindGroup, RenderPipelineDescriptor, ShaderRef, ShaderType,
+            SpecializedMeshPipelineError, VertexFormat,
+        },
+    },
+};
+
+/// Per-vertex splat radius (in model units)
+pub const ATTRIBUTE_SPLAT_RADIUS: MeshVertexAttribute =
+    MeshVertexAttribute::new("SplatRadius", 988_540_917, VertexFormat::Float32);
+
+/// Data structure for shader binding
+#[derive(Clone, ShaderType)]
+pub struct SplatUniformData {
+    /// Overall opacity multiplier
+    pub opacity: f32,
+    /// Gaussian falloff exponent; the splat edge has weight `exp(-sharpness)`
+    pub sharpness: f32,
+    /// 1 for opaque shaded spheres, 0 for Gaussian splats
+    pub shaded: u32,
+    pub _padding: f32, // Ensure memory alignment
+}
+
+/// How the points are drawn
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PointStyle {
+    /// Blended quads with a Gaussian falloff
+    #[default]
+    Splat,
+    /// Opaque discs shaded like spheres
+    Sphere,
+}
+
+/// Gaussian splat material
+#[derive(Asset, TypePath, AsBindGroup, Clone)]
+pub struct SplatMaterial {
+    #[uniform(0)]
+    pub data: SplatUniformData,
+}
+
+impl SplatMaterial {
+    pub fn new(opacity: f32, sharpness: f32, style: PointStyle) -> Self {
+        Self {
+            data: SplatUniformData {
+                opacity,
+                sharpness,
+                shaded: (style == PointStyle::Sphere) as u32,
+                _padding: 0.0,
+            },
+        }
+    }
+}
+
+impl Material for SplatMaterial {
+    fn vertex_shader() -> ShaderRef {
+        SHADER_PATH.into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        SHADER_PATH.into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        if self.data.shaded == 1 {
+            AlphaMode::Opaque
+        } else {
+            AlphaMode::Blend
+        }
+    }
+
+    fn specialize(
+        _pipeline: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayoutRef,
+        _key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        let vertex_layout = layout.0.get_layout(&[
+            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+            Mesh::ATTRIBUTE_UV_0.at_shader_location(1),
+            Mesh::ATTRIBUTE_COLOR.at_shader_location(2),
+            ATTRIBUTE_SPLAT_RADIUS.at_shader_location(3),
+        ])?;
+        descriptor.vertex.buffers = vec![vertex_layout];
+        descriptor.primitive.cull_mode = None;
+        Ok(())
+    }
+}
+
+/// Create the splat quad mesh for a set of particles
+///
+/// Each particle becomes four vertices at the particle center; the UV holds the
+/// quad corner in `[-1, 1]`, which the shader uses to expand the quad.
+///
+/// # Parameters
+/// - `centers`: Particle positions
+/// - `radii`: Splat radius of every particle
+/// - `colors`: Linear RGBA color of every particle
+///
+/// # Returns
+/// Returns the generated mesh object
+pub fn create_splat_mesh(centers: &[[f32; 3]], radii: &[f32], colors: &[[f32; 4]]) -> Mesh {
+    const CORNERS: [[f32; 2]; 4] = [[-1.0, -1.0], [1.0, -1.0], [1.0, 1.0], [-1.0, 1.0]];
+
+    let mut positions = Vec::with_capacity(centers.len() * 4);
+    let mut corners = Vec::with_capacity(centers.len() * 4);
+    let mut vertex_colors = Vec::with_capacity(centers.len() * 4);
+    let mut vertex_radii = Vec::with_capacity(centers.len() * 4);
+    let mut indices = Vec::with_capacity(centers.len() * 6);
+
+    for (particle, center) in centers.iter().enumerate() {
+        let base = (particle * 4) as u32;
+        for corner in CORNERS {
+            positions.push(*center);
+            corners.push(corner);
+            vertex_colors.push(colors[particle]);
+            vertex_radii.push(radii[particle]);
+        }
+        indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, corners);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, vertex_colors);
+    mesh.insert_attribute(ATTRIBUTE_SPLAT_RADIUS, vertex_radii);
+    mesh.insert_indices(Indices::U32(indices));
+    mesh
+}
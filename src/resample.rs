@@ -0,0 +1,264 @@
+//! # Resample Module
+//!
+//! This module probes the model onto a regular grid:
+//! - Grid resolution and bounds are chosen in the panel (initially the model bounds)
+//! - The resampled image data replaces the model and is shown by volume slices,
+//!   so slices, isosurfaces and streamlines work on it like on a `.vti` file
+//! - The grid can be exported as `.vti` with uniform arrays
+//!
+//! Open the panel via Mesh > Resample to Grid.
+
+use crate::lod::LODManager;
+use crate::mesh;
+use crate::mesh::resample::{
+    resample_to_grid, ResampleGrid, MAX_GRID_POINTS, VALID_MASK_ATTRIBUTE,
+};
+use crate::mesh::volume::VolumeData;
+use crate::ui::color_bar::apply_custom_color_mapping;
+use crate::ui::{ColorBarConfig, CurrentModelData, FileDialogs, ModelLoadedEvent, UserModelMesh};
+use crate::volume_slices::VolumeSlices;
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+use rfd::AsyncFileDialog;
+
+/// Resampling settings
+#[derive(Resource, Default)]
+pub struct ResampleSettings {
+    /// Show the resample panel
+    pub show_panel: bool,
+    /// Grid resolution and bounds, `None` until taken from the model
+    pub grid: Option<ResampleGrid>,
+    /// Resampling requested in the panel
+    apply: bool,
+    /// Outcome of the last resampling, `Err` holds the error message
+    status: Option<Result<String, String>>,
+}
+
+pub struct ResamplePlugin;
+
+impl Plugin for ResamplePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ResampleSettings>().add_systems(
+            Update,
+            (
+                reset_on_model_load,
+                resample_panel.after(EguiSet::InitContexts),
+                resample_model,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// The grid of the previous model no longer applies
+fn reset_on_model_load(
+    mut loaded_events: EventReader<ModelLoadedEvent>,
+    mut settings: ResMut<ResampleSettings>,
+) {
+    if loaded_events.read().count() > 0 {
+        settings.grid = None;
+        settings.status = None;
+    }
+}
+
+/// Grid over the bounds of the model with about 32 points along its longest side
+fn default_grid(geometry: &mesh::GeometryData) -> ResampleGrid {
+    let (min, max) =
+        geometry
+            .vertices
+            .iter()
+            .fold(([f32::MAX; 3], [f32::MIN; 3]), |(min, max), v| {
+                (
+                    std::array::from_fn(|axis| min[axis].min(v[axis])),
+                    std::array::from_fn(|axis| max[axis].max(v[axis])),
+                )
+            });
+    let size: [f32; 3] = std::array::from_fn(|axis| (max[axis] - min[axis]).max(0.0));
+    let longest = size.iter().copied().fold(0.0, f32::max);
+    let dims = size.map(|extent| {
+        if longest > 0.0 {
+            ((extent / longest * 31.0).round() as usize + 1).max(1)
+        } else {
+            1
+        }
+    });
+    ResampleGrid { dims, min, max }
+}
+
+/// Resample panel
+fn resample_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<ResampleSettings>,
+    current_model: Res<CurrentModelData>,
+    volume_slices: Res<VolumeSlices>,
+    mut file_dialogs: ResMut<FileDialogs>,
+    windows: Query<&Window>,
+) {
+    if !settings.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let mut grid = settings.grid;
+    let mut apply = false;
+    let mut open = true;
+
+    egui::Window::new("Resample to Grid")
+        .default_width(320.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            let Some(ref geometry) = current_model.geometry else {
+                ui.label("Load a model to resample.");
+                return;
+            };
+            let grid = grid.get_or_insert_with(|| default_grid(geometry));
+            if geometry.volume_cells.is_none() {
+                ui.label("Surface: the grid needs one point along the axis to probe along.");
+            }
+
+            egui::Grid::new("resample_grid")
+                .num_columns(4)
+                .show(ui, |ui| {
+                    ui.label("");
+                    for axis in ["X", "Y", "Z"] {
+                        ui.strong(axis);
+                    }
+                    ui.end_row();
+                    ui.label("Points:");
+                    for axis in 0..3 {
+                        ui.add(egui::DragValue::new(&mut grid.dims[axis]).range(1..=512));
+                    }
+                    ui.end_row();
+                    for (label, bound) in [("Min:", &mut grid.min), ("Max:", &mut grid.max)] {
+                        ui.label(label);
+                        for value in bound.iter_mut() {
+                            ui.add(egui::DragValue::new(value).speed(0.01));
+                        }
+                        ui.end_row();
+                    }
+                });
+
+            let points = grid.point_count();
+            ui.label(format!("{} grid points", points));
+            ui.horizontal(|ui| {
+                if ui.button("Model Bounds").clicked() {
+                    *grid = ResampleGrid {
+                        dims: grid.dims,
+                        ..default_grid(geometry)
+                    };
+                }
+                if ui
+                    .add_enabled(points <= MAX_GRID_POINTS, egui::Button::new("Resample"))
+                    .clicked()
+                {
+                    apply = true;
+                }
+            });
+
+            match &settings.status {
+                Some(Ok(message)) => {
+                    ui.colored_label(egui::Color32::LIGHT_GREEN, message);
+                }
+                Some(Err(message)) => {
+                    ui.colored_label(egui::Color32::LIGHT_RED, message);
+                }
+                None => {}
+            }
+
+            if let Some(volume) = volume_slices.volume() {
+                ui.separator();
+                if ui.button("Export Volume (.vti)...").clicked() {
+                    export_volume(volume.clone(), &mut file_dialogs);
+                }
+            }
+        });
+
+    if grid != settings.grid {
+        settings.grid = grid;
+    }
+    if apply {
+        settings.apply = true;
+    }
+
+    if !open {
+        settings.show_panel = false;
+    }
+}
+
+/// Replace the model by its resampled grid
+fn resample_model(
+    mut settings: ResMut<ResampleSettings>,
+    mut current_model: ResMut<CurrentModelData>,
+    mut volume_slices: ResMut<VolumeSlices>,
+    color_bar_config: Res<ColorBarConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut model_entities: Query<(&mut Mesh3d, Option<&mut LODManager>), With<UserModelMesh>>,
+) {
+    if !settings.apply {
+        return;
+    }
+    settings.apply = false;
+    let (Some(geometry), Some(grid), Ok((mut mesh3d, lod_manager))) = (
+        current_model.geometry.as_ref(),
+        settings.grid,
+        model_entities.get_single_mut(),
+    ) else {
+        return;
+    };
+
+    let start = std::time::Instant::now();
+    let volume = match resample_to_grid(geometry, &grid) {
+        Ok(volume) => volume,
+        Err(e) => {
+            println!("Resampling failed: {}", e);
+            settings.status = Some(Err(e.to_string()));
+            return;
+        }
+    };
+    println!("Resampled model in {:?}", start.elapsed());
+
+    let inside = match volume.attributes.get(&(
+        VALID_MASK_ATTRIBUTE.to_string(),
+        mesh::AttributeLocation::Point,
+    )) {
+        Some(mesh::AttributeType::Scalar { data, .. }) => {
+            data.iter().filter(|&&valid| valid > 0.0).count()
+        }
+        _ => 0,
+    };
+    settings.status = Some(Ok(format!(
+        "{} of {} grid points inside the model",
+        inside,
+        volume.point_count()
+    )));
+
+    let slice = volume_slices.prepare_import(volume);
+    let mut new_mesh = mesh::create_mesh_from_geometry(&slice);
+    if let Err(e) = apply_custom_color_mapping(&slice, &mut new_mesh, &color_bar_config) {
+        println!("Failed to apply color mapping to resampled grid: {:?}", e);
+    }
+    // Levels of the previous model must not replace the grid
+    if let Some(mut lod_manager) = lod_manager {
+        if let Err(e) = lod_manager.rebuild(slice.clone(), &mut meshes) {
+            println!("Failed to rebuild LOD levels of resampled grid: {:?}", e);
+        }
+    }
+    *mesh3d = Mesh3d(meshes.add(new_mesh));
+    current_model.geometry = Some(slice);
+}
+
+/// Ask for a file name and write the volume to it on the IO task pool
+fn export_volume(volume: VolumeData, file_dialogs: &mut FileDialogs) {
+    let dialog = AsyncFileDialog::new()
+        .add_filter("VTK Image Data", &["vti"])
+        .set_file_name("resampled.vti")
+        .set_directory(std::env::var("HOME").unwrap_or_else(|_| "/".to_string()));
+    file_dialogs.open(async move {
+        let file = dialog.save_file().await?;
+        let path = file.path();
+        match mesh::export::write_vti_ascii(&volume, path) {
+            Ok(()) => println!("Volume exported to {}", path.display()),
+            Err(e) => eprintln!("Failed to export volume: {}", e),
+        }
+        None
+    });
+}
@@ -0,0 +1,184 @@
+//! # Scene Export Module
+//!
+//! This module writes the visualized scene to a binary glTF file (`.glb`):
+//! - Every mesh of the imported models, including glTF scene nodes and periodic copies
+//! - World transforms as node translation, rotation and scale
+//! - Colors baked per vertex: the vertex colors of the color mapping multiplied by
+//!   the material color
+//!
+//! Hidden meshes and non-triangle meshes are skipped. Start the export via
+//! File > Export Scene (.glb).
+
+use crate::mesh::export::{smooth_normals, write_glb_scene, GlbNode};
+use crate::render::ClipMaterial;
+use crate::ui::{FileDialogs, UserModelMesh};
+use bevy::prelude::*;
+use bevy::render::mesh::{PrimitiveTopology, VertexAttributeValues};
+use bevy::utils::HashSet;
+use rfd::AsyncFileDialog;
+
+/// Material of a mesh (standard, or clip material once clipping took over)
+type MeshMaterials = (
+    Option<&'static MeshMaterial3d<StandardMaterial>>,
+    Option<&'static MeshMaterial3d<ClipMaterial>>,
+);
+
+/// Scene export request
+#[derive(Resource, Default)]
+pub struct SceneExport {
+    /// Export requested from the menu, handled on the next frame
+    pub requested: bool,
+}
+
+pub struct SceneExportPlugin;
+
+impl Plugin for SceneExportPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SceneExport>()
+            .add_systems(Update, export_scene);
+    }
+}
+
+/// Collect the meshes of all models and write them on the IO task pool
+#[allow(clippy::too_many_arguments)]
+fn export_scene(
+    mut scene_export: ResMut<SceneExport>,
+    models: Query<Entity, With<UserModelMesh>>,
+    children: Query<&Children>,
+    mesh_entities: Query<(
+        &Mesh3d,
+        &GlobalTransform,
+        &InheritedVisibility,
+        Option<&Name>,
+        MeshMaterials,
+    )>,
+    meshes: Res<Assets<Mesh>>,
+    standard_materials: Res<Assets<StandardMaterial>>,
+    clip_materials: Res<Assets<ClipMaterial>>,
+    mut file_dialogs: ResMut<FileDialogs>,
+) {
+    if !scene_export.requested {
+        return;
+    }
+    scene_export.requested = false;
+
+    // Models and their descendants (glTF nodes, periodic copies), each once
+    let mut visited = HashSet::new();
+    let mut nodes = Vec::new();
+    for model in models.iter() {
+        for entity in std::iter::once(model).chain(children.iter_descendants(model)) {
+            if !visited.insert(entity) {
+                continue;
+            }
+            let Ok((mesh, transform, visibility, name, (standard, clip))) =
+                mesh_entities.get(entity)
+            else {
+                continue;
+            };
+            if !visibility.get() {
+                continue;
+            }
+            let Some(mesh) = meshes.get(&mesh.0) else {
+                continue;
+            };
+
+            let base_color = standard
+                .and_then(|material| standard_materials.get(&material.0))
+                .or_else(|| {
+                    clip.and_then(|material| clip_materials.get(&material.0))
+                        .map(|material| &material.base)
+                })
+                .map_or(LinearRgba::WHITE, |material| {
+                    material.base_color.to_linear()
+                });
+            let name = name.map_or_else(|| format!("model_{}", nodes.len()), Name::to_string);
+
+            match mesh_to_node(mesh, transform, base_color, name) {
+                Some(node) => nodes.push(node),
+                None => println!("Skipping mesh of entity {:?}: not a triangle mesh", entity),
+            }
+        }
+    }
+
+    if nodes.is_empty() {
+        println!("No model meshes to export");
+        return;
+    }
+
+    let dialog = AsyncFileDialog::new()
+        .add_filter("glTF Binary", &["glb"])
+        .set_file_name("scene.glb")
+        .set_directory(std::env::var("HOME").unwrap_or_else(|_| "/".to_string()));
+    file_dialogs.open(async move {
+        let file = dialog.save_file().await?;
+        let path = file.path();
+        match write_glb_scene(&nodes, path) {
+            Ok(()) => println!(
+                "Scene with {} meshes exported to {}",
+                nodes.len(),
+                path.display()
+            ),
+            Err(e) => eprintln!("Failed to export scene: {}", e),
+        }
+        None
+    });
+}
+
+/// Convert a rendered mesh into a glTF node with baked vertex colors
+///
+/// # Parameters
+/// - `mesh`: Rendered mesh
+/// - `transform`: World transform of the mesh entity
+/// - `base_color`: Linear material color, multiplied into the vertex colors
+/// - `name`: Node name
+///
+/// # Returns
+/// - `Some(GlbNode)`: Triangle mesh as glTF node
+/// - `None`: The mesh has no triangles or positions
+fn mesh_to_node(
+    mesh: &Mesh,
+    transform: &GlobalTransform,
+    base_color: LinearRgba,
+    name: String,
+) -> Option<GlbNode> {
+    if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+        return None;
+    }
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return None;
+    };
+
+    let indices: Vec<u32> = match mesh.indices() {
+        Some(indices) => indices.iter().map(|index| index as u32).collect(),
+        None => (0..positions.len() as u32).collect(),
+    };
+    let normals = match mesh.attribute(Mesh::ATTRIBUTE_NORMAL) {
+        Some(VertexAttributeValues::Float32x3(normals)) if normals.len() == positions.len() => {
+            normals.clone()
+        }
+        _ => smooth_normals(positions, &indices),
+    };
+
+    let base = base_color.to_f32_array();
+    let colors = match mesh.attribute(Mesh::ATTRIBUTE_COLOR) {
+        Some(VertexAttributeValues::Float32x4(colors)) if colors.len() == positions.len() => colors
+            .iter()
+            .map(|color| std::array::from_fn(|i| color[i] * base[i]))
+            .collect(),
+        _ => vec![base; positions.len()],
+    };
+
+    let (scale, rotation, translation) = transform.to_scale_rotation_translation();
+    Some(GlbNode {
+        name,
+        positions: positions.clone(),
+        normals,
+        colors: Some(colors),
+        indices,
+        translation: translation.to_array(),
+        rotation: rotation.to_array(),
+        scale: scale.to_array(),
+    })
+}
@@ -0,0 +1,340 @@
+//! # Scene Tree Module
+//!
+//! This module lists the loaded entities in a panel on the left, like a
+//! pipeline browser:
+//! - Models, wave surfaces, heightmaps and point splats are the roots; filter
+//!   outputs (isosurfaces, slices, glyphs, streamlines, ...) are listed under
+//!   the model they were computed from
+//! - Clicking an item selects it; the selected item can be renamed, and any
+//!   item can be deleted with its outputs, instead of clearing the whole scene
+//! - The eye button hides or shows an item, so overlapping results can be
+//!   compared without deleting them; LOD levels and time series frames replace
+//!   the mesh of the model entity and keep its visibility
+//!
+//! Open the panel via View > Scene Tree.
+
+use crate::cell_types::CellTypeExtraction;
+use crate::glyphs::GlyphMesh;
+use crate::heightmap::Heightmap;
+use crate::isosurface::IsosurfaceMesh;
+use crate::pathlines::PathlineMesh;
+use crate::selection::ExtractedSelection;
+use crate::slice_plane::SliceMesh;
+use crate::splatting::SplatCloud;
+use crate::streamlines::StreamlineMesh;
+use crate::tubes::LineTubes;
+use crate::ui::{CurrentModelData, MenuBarSet, UserModelMesh, WaveSurface};
+use bevy::ecs::query::QueryItem;
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Kind of a scene tree item
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SceneItemKind {
+    Model,
+    WaveSurface,
+    Heightmap,
+    PointSplats,
+    Isosurface,
+    Slice,
+    Glyphs,
+    Streamlines,
+    Pathlines,
+    Tubes,
+    ExtractedCells,
+    ExtractedSelection,
+}
+
+impl SceneItemKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            SceneItemKind::Model => "Model",
+            SceneItemKind::WaveSurface => "Wave Surface",
+            SceneItemKind::Heightmap => "Heightmap",
+            SceneItemKind::PointSplats => "Point Splats",
+            SceneItemKind::Isosurface => "Isosurface",
+            SceneItemKind::Slice => "Slice",
+            SceneItemKind::Glyphs => "Vector Glyphs",
+            SceneItemKind::Streamlines => "Streamlines",
+            SceneItemKind::Pathlines => "Pathlines",
+            SceneItemKind::Tubes => "Tubes",
+            SceneItemKind::ExtractedCells => "Extracted Cells",
+            SceneItemKind::ExtractedSelection => "Extracted Selection",
+        }
+    }
+}
+
+/// Marker components telling the kind of a scene tree item
+type SceneItemMarkers = (
+    Has<UserModelMesh>,
+    Has<WaveSurface>,
+    Has<Heightmap>,
+    Has<SplatCloud>,
+    Has<IsosurfaceMesh>,
+    Has<SliceMesh>,
+    Has<GlyphMesh>,
+    Has<StreamlineMesh>,
+    Has<PathlineMesh>,
+    Has<LineTubes>,
+    Has<CellTypeExtraction>,
+    Has<ExtractedSelection>,
+);
+
+/// Components read to list an entity
+type SceneItemData = (
+    Entity,
+    Option<&'static Name>,
+    Option<&'static Parent>,
+    Option<&'static Visibility>,
+    SceneItemMarkers,
+);
+
+/// Kind of an entity from its markers, `None` for entities not listed
+fn item_kind(markers: QueryItem<SceneItemMarkers>) -> Option<SceneItemKind> {
+    let (
+        model,
+        wave,
+        heightmap,
+        splats,
+        isosurface,
+        slice,
+        glyphs,
+        streamlines,
+        pathlines,
+        tubes,
+        cells,
+        selection,
+    ) = markers;
+    [
+        (model, SceneItemKind::Model),
+        (wave, SceneItemKind::WaveSurface),
+        (heightmap, SceneItemKind::Heightmap),
+        (splats, SceneItemKind::PointSplats),
+        (isosurface, SceneItemKind::Isosurface),
+        (slice, SceneItemKind::Slice),
+        (glyphs, SceneItemKind::Glyphs),
+        (streamlines, SceneItemKind::Streamlines),
+        (pathlines, SceneItemKind::Pathlines),
+        (tubes, SceneItemKind::Tubes),
+        (cells, SceneItemKind::ExtractedCells),
+        (selection, SceneItemKind::ExtractedSelection),
+    ]
+    .into_iter()
+    .find_map(|(present, kind)| present.then_some(kind))
+}
+
+/// Entry of the scene tree
+#[derive(Clone, Debug)]
+pub struct SceneItem {
+    pub entity: Entity,
+    pub name: String,
+    pub kind: SceneItemKind,
+    /// Listed item this one is shown under
+    pub parent: Option<Entity>,
+    /// The item is not hidden by its own visibility
+    pub visible: bool,
+}
+
+/// Scene tree state
+#[derive(Resource, Default)]
+pub struct SceneTree {
+    /// Show the scene tree panel
+    pub show_panel: bool,
+    /// Selected item
+    pub selected: Option<Entity>,
+    /// Listed items, roots first, refreshed every frame while the panel is open
+    pub items: Vec<SceneItem>,
+}
+
+pub struct SceneTreePlugin;
+
+impl Plugin for SceneTreePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SceneTree>().add_systems(
+            Update,
+            (
+                collect_scene_items,
+                scene_tree_panel
+                    .after(EguiSet::InitContexts)
+                    .after(MenuBarSet),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// List the entities of the scene tree
+fn collect_scene_items(mut tree: ResMut<SceneTree>, entities: Query<SceneItemData>) {
+    if !tree.show_panel {
+        return;
+    }
+    let mut items: Vec<SceneItem> = entities
+        .iter()
+        .filter_map(|(entity, name, parent, visibility, markers)| {
+            let kind = item_kind(markers)?;
+            Some(SceneItem {
+                entity,
+                name: name.map_or_else(|| kind.label().to_string(), |name| name.to_string()),
+                kind,
+                parent: parent.map(Parent::get),
+                visible: visibility != Some(&Visibility::Hidden),
+            })
+        })
+        .collect();
+
+    // Outputs of entities that are not listed become roots
+    let listed: Vec<Entity> = items.iter().map(|item| item.entity).collect();
+    for item in items.iter_mut() {
+        if item.parent.is_some_and(|parent| !listed.contains(&parent)) {
+            item.parent = None;
+        }
+    }
+    items.sort_by_key(|item| (item.parent.is_some(), item.entity));
+
+    if tree
+        .selected
+        .is_some_and(|selected| !listed.contains(&selected))
+    {
+        tree.selected = None;
+    }
+    tree.items = items;
+}
+
+/// Scene tree panel
+fn scene_tree_panel(
+    mut commands: Commands,
+    mut contexts: EguiContexts,
+    mut tree: ResMut<SceneTree>,
+    mut current_model: ResMut<CurrentModelData>,
+    mut visibilities: Query<&mut Visibility>,
+    windows: Query<&Window>,
+) {
+    if !tree.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let mut selected = tree.selected;
+    let mut deleted = None;
+    let mut toggled = None;
+    let mut renamed = None;
+    let mut close = false;
+
+    egui::SidePanel::left("scene_tree_panel")
+        .default_width(220.0)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                ui.heading("Scene");
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.small_button("x").on_hover_text("Close").clicked() {
+                        close = true;
+                    }
+                });
+            });
+            ui.separator();
+
+            if tree.items.is_empty() {
+                ui.label("Nothing loaded.");
+                return;
+            }
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                let roots = tree.items.iter().filter(|item| item.parent.is_none());
+                for root in roots {
+                    item_row(ui, root, &mut selected, &mut deleted, &mut toggled);
+                    ui.indent(("scene_children", root.entity), |ui| {
+                        for child in tree
+                            .items
+                            .iter()
+                            .filter(|item| item.parent == Some(root.entity))
+                        {
+                            item_row(ui, child, &mut selected, &mut deleted, &mut toggled);
+                        }
+                    });
+                }
+            });
+
+            let Some(item) =
+                selected.and_then(|entity| tree.items.iter().find(|item| item.entity == entity))
+            else {
+                return;
+            };
+            ui.separator();
+            ui.label(egui::RichText::new(item.kind.label()).weak());
+            let mut name = item.name.clone();
+            ui.horizontal(|ui| {
+                ui.label("Name:");
+                if ui.text_edit_singleline(&mut name).changed() && !name.trim().is_empty() {
+                    renamed = Some((item.entity, name.clone()));
+                }
+            });
+        });
+
+    if let Some(entity) = toggled {
+        if let Ok(mut visibility) = visibilities.get_mut(entity) {
+            *visibility = if *visibility == Visibility::Hidden {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+        }
+    }
+    if let Some((entity, name)) = renamed {
+        commands.entity(entity).insert(Name::new(name));
+    }
+    if let Some(entity) = deleted {
+        if let Some(item) = tree.items.iter().find(|item| item.entity == entity) {
+            println!("Deleted {} '{}'", item.kind.label(), item.name);
+            if item.kind == SceneItemKind::Model {
+                current_model.geometry = None;
+            }
+        }
+        commands.entity(entity).despawn_recursive();
+        if selected == Some(entity) {
+            selected = None;
+        }
+    }
+    if selected != tree.selected {
+        tree.selected = selected;
+    }
+    if close {
+        tree.show_panel = false;
+    }
+}
+
+/// One row of the tree: visibility toggle, selectable name and delete button
+fn item_row(
+    ui: &mut egui::Ui,
+    item: &SceneItem,
+    selected: &mut Option<Entity>,
+    deleted: &mut Option<Entity>,
+    toggled: &mut Option<Entity>,
+) {
+    ui.horizontal(|ui| {
+        if ui
+            .selectable_label(item.visible, "👁")
+            .on_hover_text(if item.visible { "Hide" } else { "Show" })
+            .clicked()
+        {
+            *toggled = Some(item.entity);
+        }
+
+        let is_selected = *selected == Some(item.entity);
+        let name = if item.visible {
+            egui::RichText::new(&item.name)
+        } else {
+            egui::RichText::new(&item.name).weak()
+        };
+        if ui
+            .selectable_label(is_selected, name)
+            .on_hover_text(item.kind.label())
+            .clicked()
+        {
+            *selected = if is_selected { None } else { Some(item.entity) };
+        }
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui.small_button("x").on_hover_text("Delete").clicked() {
+                *deleted = Some(item.entity);
+            }
+        });
+    });
+}
@@ -0,0 +1,268 @@
+//! # Screenshot Module
+//!
+//! This module saves the current view as a PNG image:
+//! - Without the UI overlay, the view is rendered by a temporary camera into an
+//!   offscreen image of any resolution (also larger than the window)
+//! - With the UI overlay, the window is captured as shown, at window resolution
+//!
+//! The capture camera copies the pose, projection and MSAA setting of the main
+//! camera; helper geometry on the overlay layer is not included. Open the panel
+//! via File > Capture Screenshot.
+
+use crate::camera::WorldModelCamera;
+use crate::ui::FileDialogs;
+use bevy::prelude::*;
+use bevy::render::{
+    camera::RenderTarget,
+    render_asset::RenderAssetUsages,
+    render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+    view::screenshot::{Screenshot, ScreenshotCaptured},
+};
+use bevy_egui::{egui, EguiContexts, EguiSet};
+use rfd::AsyncFileDialog;
+
+/// Largest supported image side (default GPU texture limit)
+const MAX_IMAGE_SIZE: u32 = 8192;
+/// Frames rendered by the capture camera before the image is read back, so
+/// render pipelines for the new target are ready
+const CAPTURE_DELAY_FRAMES: u32 = 5;
+
+/// Screenshot settings
+#[derive(Resource)]
+pub struct ScreenshotSettings {
+    /// Show the screenshot panel
+    pub show_panel: bool,
+    /// Image width in pixels (offscreen capture)
+    pub width: u32,
+    /// Image height in pixels (offscreen capture)
+    pub height: u32,
+    /// Capture the window including the egui overlay (window resolution)
+    pub include_ui: bool,
+    /// Capture requested from the panel
+    requested: bool,
+    /// Offscreen capture in progress
+    pending: Option<PendingCapture>,
+}
+
+impl Default for ScreenshotSettings {
+    fn default() -> Self {
+        Self {
+            show_panel: false,
+            width: 1920,
+            height: 1080,
+            include_ui: false,
+            requested: false,
+            pending: None,
+        }
+    }
+}
+
+/// Temporary camera rendering into the screenshot image
+struct PendingCapture {
+    camera: Entity,
+    target: Handle<Image>,
+    frames: u32,
+}
+
+pub struct ScreenshotPlugin;
+
+impl Plugin for ScreenshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScreenshotSettings>().add_systems(
+            Update,
+            (
+                screenshot_panel.after(EguiSet::InitContexts),
+                start_capture,
+                finish_offscreen_capture,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Screenshot panel
+fn screenshot_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<ScreenshotSettings>,
+    windows: Query<&Window>,
+) {
+    let Some(window) = windows.iter().next() else {
+        return;
+    };
+    if !settings.show_panel {
+        return;
+    }
+
+    let mut width = settings.width;
+    let mut height = settings.height;
+    let mut include_ui = settings.include_ui;
+    let mut capture = false;
+    let mut open = true;
+
+    egui::Window::new("Capture Screenshot")
+        .default_width(260.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.checkbox(&mut include_ui, "Include UI overlay");
+            ui.add_enabled_ui(!include_ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Size:");
+                    ui.add(egui::DragValue::new(&mut width).range(16..=MAX_IMAGE_SIZE));
+                    ui.label("x");
+                    ui.add(egui::DragValue::new(&mut height).range(16..=MAX_IMAGE_SIZE));
+                });
+                if ui.button("Use Window Size").clicked() {
+                    width = window.physical_width();
+                    height = window.physical_height();
+                }
+            });
+            if include_ui {
+                ui.label(format!(
+                    "The window is captured at {}x{}.",
+                    window.physical_width(),
+                    window.physical_height()
+                ));
+            }
+
+            ui.separator();
+            let busy = settings.requested || settings.pending.is_some();
+            if ui
+                .add_enabled(!busy, egui::Button::new("Capture..."))
+                .clicked()
+            {
+                capture = true;
+            }
+        });
+
+    if width != settings.width || height != settings.height || include_ui != settings.include_ui {
+        settings.width = width;
+        settings.height = height;
+        settings.include_ui = include_ui;
+    }
+    if capture {
+        settings.requested = true;
+    }
+    if !open {
+        settings.show_panel = false;
+    }
+}
+
+/// Start a requested capture: of the window, or by a temporary offscreen camera
+fn start_capture(
+    mut commands: Commands,
+    mut settings: ResMut<ScreenshotSettings>,
+    mut images: ResMut<Assets<Image>>,
+    main_cameras: Query<(&Camera, &GlobalTransform, &Projection, &Msaa), With<WorldModelCamera>>,
+) {
+    if !settings.requested {
+        return;
+    }
+    settings.requested = false;
+
+    if settings.include_ui {
+        commands
+            .spawn(Screenshot::primary_window())
+            .observe(save_captured_image);
+        return;
+    }
+
+    let Ok((camera, transform, projection, msaa)) = main_cameras.get_single() else {
+        println!("No camera to capture the screenshot from");
+        return;
+    };
+
+    let size = Extent3d {
+        width: settings.width,
+        height: settings.height,
+        depth_or_array_layers: 1,
+    };
+    let mut target = Image::new_fill(
+        size,
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    target.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING
+        | TextureUsages::COPY_SRC
+        | TextureUsages::COPY_DST
+        | TextureUsages::RENDER_ATTACHMENT;
+    let target = images.add(target);
+
+    let capture_camera = commands
+        .spawn((
+            Camera3d::default(),
+            Camera {
+                target: RenderTarget::Image(target.clone()),
+                clear_color: camera.clear_color,
+                hdr: camera.hdr,
+                ..default()
+            },
+            projection.clone(),
+            *msaa,
+            transform.compute_transform(),
+        ))
+        .id();
+    println!(
+        "Rendering screenshot at {}x{}",
+        settings.width, settings.height
+    );
+
+    settings.pending = Some(PendingCapture {
+        camera: capture_camera,
+        target,
+        frames: 0,
+    });
+}
+
+/// Read back the offscreen image once rendered and remove the capture camera
+fn finish_offscreen_capture(mut commands: Commands, mut settings: ResMut<ScreenshotSettings>) {
+    let Some(pending) = settings.pending.as_mut() else {
+        return;
+    };
+    pending.frames += 1;
+    if pending.frames < CAPTURE_DELAY_FRAMES {
+        return;
+    }
+
+    let Some(pending) = settings.pending.take() else {
+        return;
+    };
+    let camera = pending.camera;
+    commands
+        .spawn(Screenshot::image(pending.target))
+        .observe(save_captured_image)
+        .observe(
+            move |_: Trigger<ScreenshotCaptured>, mut commands: Commands| {
+                commands.entity(camera).despawn();
+            },
+        );
+}
+
+/// Ask for a file name and save the captured image as PNG
+///
+/// The file dialog and encoding run on the IO task pool to avoid blocking the
+/// main thread.
+fn save_captured_image(
+    trigger: Trigger<ScreenshotCaptured>,
+    mut file_dialogs: ResMut<FileDialogs>,
+) {
+    let image = trigger.event().0.clone();
+    let dialog = AsyncFileDialog::new()
+        .add_filter("PNG", &["png"])
+        .set_file_name("screenshot.png")
+        .set_directory(std::env::var("HOME").unwrap_or_else(|_| "/".to_string()));
+    file_dialogs.open(async move {
+        let file = dialog.save_file().await?;
+        let path = file.path();
+        let result = image
+            .try_into_dynamic()
+            .map_err(|e| e.to_string())
+            .and_then(|image| image.to_rgba8().save(path).map_err(|e| e.to_string()));
+        match result {
+            Ok(()) => println!("Screenshot saved to {}", path.display()),
+            Err(e) => eprintln!("Failed to save screenshot: {}", e),
+        }
+        None
+    });
+}
@@ -0,0 +1,1043 @@
+//! # Selection Module
+//!
+//! This module manages cell selections on the loaded model:
+//! - Pick: select the clicked cell
+//! - Region grow: starting from the clicked seed cell, grow the selection across
+//!   neighboring cells while the scalar value stays within a tolerance of the seed
+//! - Rectangle / Lasso: drag a screen-space shape; every cell whose center projects
+//!   inside the shape is selected, optionally only cells not hidden behind the
+//!   front surface
+//! - Query: select cells matching an expression such as `pressure > 1e5 AND region == 3`
+//!
+//! Selected cells are highlighted with a translucent overlay mesh. They can be
+//! extracted into a separate entity attached to the model, or hidden, which
+//! replaces the model geometry by the remaining cells until Show All restores it.
+//!
+//! ## Control Scheme (Tools > Select)
+//! - Left click / Left drag: Replace selection
+//! - Shift + Left click / drag: Add to selection
+//! - Ctrl + Left click / drag: Remove from selection
+
+use crate::camera::WorldModelCamera;
+use crate::mesh::export::{cells_to_csv, write_vtk_ascii};
+use crate::mesh::query::Query as SelectionQuery;
+use crate::mesh::topology::{
+    cell_count, cell_neighbors, cell_scalar_values, cell_triangles, cell_vertices, extract_cells,
+};
+use crate::mesh::{self, AttributeLocation, GeometryData, VtkError};
+use crate::picking::{
+    cursor_ray_in_model_space, model_matrix, pointer_over_ui, InteractionMode, PickingBvh,
+    TriangleBvh,
+};
+use crate::preferences::UiSettings;
+use crate::ui::color_bar::apply_custom_color_mapping;
+use crate::ui::{ColorBarConfig, CurrentModelData, FileDialogs, ModelLoadedEvent, UserModelMesh};
+use crate::widgets::{widget_drag_input, WidgetState};
+use bevy::pbr::wireframe::NoWireframe;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::window::PrimaryWindow;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+use rfd::AsyncFileDialog;
+use std::collections::{BTreeSet, VecDeque};
+
+/// Selection highlight color
+const SELECTION_COLOR: Color = Color::srgba(1.0, 0.2, 0.8, 0.6);
+
+/// Relative depth difference below which a cell counts as in front of the
+/// surface hit on its camera ray
+const VISIBILITY_TOLERANCE: f32 = 1e-3;
+
+/// Minimum cursor movement (logical pixels) before a new lasso point is recorded
+const LASSO_POINT_SPACING: f32 = 3.0;
+
+/// Selection tools
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelectionTool {
+    /// Select the clicked cell
+    Pick,
+    /// Grow from the clicked seed cell while the scalar stays within tolerance
+    RegionGrow,
+    /// Select cells inside a dragged screen-space rectangle
+    Rectangle,
+    /// Select cells inside a free-hand screen-space lasso
+    Lasso,
+}
+
+impl SelectionTool {
+    /// Whether the tool selects by dragging a screen-space shape
+    pub fn is_drag_tool(&self) -> bool {
+        matches!(self, SelectionTool::Rectangle | SelectionTool::Lasso)
+    }
+}
+
+/// Requested operation on the selected cells
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SelectionAction {
+    /// Copy the selected cells into a separate entity
+    Extract,
+    /// Remove the extracted entities
+    RemoveExtracted,
+    /// Remove the selected cells from the model
+    Hide,
+    /// Restore the model before the first hide
+    ShowAll,
+}
+
+/// How a new selection is combined with the existing one
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelectionModifier {
+    Replace,
+    Add,
+    Subtract,
+}
+
+impl SelectionModifier {
+    /// Determine the modifier from the keyboard state (Shift adds, Ctrl subtracts)
+    pub fn from_keyboard(keyboard_input: &ButtonInput<KeyCode>) -> Self {
+        if keyboard_input.pressed(KeyCode::ShiftLeft) || keyboard_input.pressed(KeyCode::ShiftRight)
+        {
+            SelectionModifier::Add
+        } else if keyboard_input.pressed(KeyCode::ControlLeft)
+            || keyboard_input.pressed(KeyCode::ControlRight)
+        {
+            SelectionModifier::Subtract
+        } else {
+            SelectionModifier::Replace
+        }
+    }
+}
+
+/// Current cell selection
+#[derive(Resource)]
+pub struct SelectionState {
+    /// Active selection tool
+    pub tool: SelectionTool,
+    /// Selected original cell indices
+    pub selected_cells: BTreeSet<usize>,
+    /// Scalar attribute used for region growing
+    pub scalar: Option<(String, AttributeLocation)>,
+    /// Maximum allowed scalar difference to the seed cell
+    pub tolerance: f32,
+    /// Query expression text
+    pub query_text: String,
+    /// Error of the last query evaluation
+    pub query_error: Option<String>,
+    /// Rectangle and lasso skip cells hidden behind the front surface
+    pub visible_only: bool,
+    /// Selection changed, overlay needs to be rebuilt
+    pub changed: bool,
+    /// Operation requested in the panel
+    action: Option<SelectionAction>,
+    /// Model geometry before the first hide
+    hidden_original: Option<GeometryData>,
+    /// Screen-space shape of the rectangle/lasso drag in progress
+    drag_path: Vec<Vec2>,
+    /// Modifier captured when the drag started
+    drag_modifier: SelectionModifier,
+}
+
+impl Default for SelectionState {
+    fn default() -> Self {
+        Self {
+            tool: SelectionTool::Pick,
+            selected_cells: BTreeSet::new(),
+            scalar: None,
+            tolerance: 0.1,
+            query_text: String::new(),
+            query_error: None,
+            visible_only: true,
+            changed: false,
+            action: None,
+            hidden_original: None,
+            drag_path: Vec::new(),
+            drag_modifier: SelectionModifier::Replace,
+        }
+    }
+}
+
+impl SelectionState {
+    /// Combine a set of cells with the current selection
+    pub fn apply(&mut self, cells: impl IntoIterator<Item = usize>, modifier: SelectionModifier) {
+        match modifier {
+            SelectionModifier::Replace => {
+                self.selected_cells = cells.into_iter().collect();
+            }
+            SelectionModifier::Add => {
+                self.selected_cells.extend(cells);
+            }
+            SelectionModifier::Subtract => {
+                for cell in cells {
+                    self.selected_cells.remove(&cell);
+                }
+            }
+        }
+        self.changed = true;
+    }
+
+    /// Evaluate a query expression and combine the matching cells with the selection
+    pub fn apply_query(&mut self, geometry: &GeometryData, modifier: SelectionModifier) {
+        let result =
+            SelectionQuery::parse(&self.query_text).and_then(|query| query.select_cells(geometry));
+        match result {
+            Ok(cells) => {
+                println!("Query '{}' matched {} cells", self.query_text, cells.len());
+                self.query_error = None;
+                self.apply(cells, modifier);
+            }
+            Err(e) => {
+                println!("Query failed: {}", e);
+                self.query_error = Some(e.to_string());
+            }
+        }
+    }
+
+    /// Remove all selected cells
+    pub fn clear(&mut self) {
+        if !self.selected_cells.is_empty() {
+            self.selected_cells.clear();
+            self.changed = true;
+        }
+    }
+}
+
+/// Marker component for the selection highlight mesh
+#[derive(Component)]
+pub struct SelectionOverlay;
+
+/// Marker component for selections extracted into their own entity
+#[derive(Component)]
+pub struct ExtractedSelection;
+
+pub struct SelectionPlugin;
+
+impl Plugin for SelectionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SelectionState>().add_systems(
+            Update,
+            (
+                reset_selection_on_model_load,
+                selection_input.after(widget_drag_input),
+                selection_panel.after(EguiSet::InitContexts),
+                draw_selection_drag.after(EguiSet::InitContexts),
+                apply_selection_action,
+                update_selection_overlay,
+                sync_selection_overlay_transform,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Grow a cell region from a seed cell
+///
+/// Breadth-first traversal over edge-connected cells, accepting every cell whose
+/// scalar value differs from the seed value by at most `tolerance`.
+///
+/// # Parameters
+/// - `geometry`: Geometry data
+/// - `seed`: Seed cell index
+/// - `name`, `location`: Scalar attribute used as growth criterion
+/// - `tolerance`: Maximum absolute difference to the seed value
+///
+/// # Returns
+/// - `Ok(cells)`: Cell indices of the grown region (including the seed)
+/// - `Err(VtkError)`: Scalar attribute is missing or seed is out of range
+pub fn grow_region(
+    geometry: &GeometryData,
+    seed: usize,
+    name: &str,
+    location: &AttributeLocation,
+    tolerance: f32,
+) -> Result<Vec<usize>, VtkError> {
+    let triangles = cell_triangles(geometry);
+    if seed >= triangles.len() {
+        return Err(VtkError::IndexOutOfBounds {
+            index: seed,
+            max: triangles.len(),
+        });
+    }
+
+    let vertices = cell_vertices(geometry, &triangles);
+    let values = cell_scalar_values(geometry, name, location, &vertices)
+        .ok_or(VtkError::MissingData("Scalar attribute for region growing"))?;
+    let neighbors = cell_neighbors(geometry, &triangles);
+
+    let seed_value = values[seed];
+    let mut visited = vec![false; triangles.len()];
+    let mut region = Vec::new();
+    let mut queue = VecDeque::from([seed]);
+    visited[seed] = true;
+
+    while let Some(cell) = queue.pop_front() {
+        region.push(cell);
+        for &neighbor in &neighbors[cell] {
+            if !visited[neighbor] && (values[neighbor] - seed_value).abs() <= tolerance {
+                visited[neighbor] = true;
+                queue.push_back(neighbor);
+            }
+        }
+    }
+
+    Ok(region)
+}
+
+/// Clear selection when a new model is loaded
+fn reset_selection_on_model_load(
+    mut model_loaded_events: EventReader<ModelLoadedEvent>,
+    current_model: Res<CurrentModelData>,
+    mut selection_state: ResMut<SelectionState>,
+) {
+    for _ in model_loaded_events.read() {
+        selection_state.clear();
+        selection_state.hidden_original = None;
+        selection_state.scalar = current_model.geometry.as_ref().and_then(|geometry| {
+            crate::mesh::topology::scalar_attribute_names(geometry)
+                .into_iter()
+                .next()
+        });
+    }
+
+    if current_model.geometry.is_none() {
+        selection_state.clear();
+    }
+}
+
+/// Apply the active selection tool on left click
+#[allow(clippy::too_many_arguments)]
+fn selection_input(
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    interaction_mode: Res<InteractionMode>,
+    current_model: Res<CurrentModelData>,
+    widget_state: Res<WidgetState>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<WorldModelCamera>>,
+    model_query: Query<&GlobalTransform, With<UserModelMesh>>,
+    mut contexts: EguiContexts,
+    mut selection_state: ResMut<SelectionState>,
+    mut picking_bvh: ResMut<PickingBvh>,
+) {
+    if *interaction_mode != InteractionMode::Select {
+        selection_state.drag_path.clear();
+        return;
+    }
+
+    if selection_state.tool.is_drag_tool() && !selection_state.drag_path.is_empty() {
+        update_drag_selection(
+            &mouse_button_input,
+            &current_model,
+            &windows,
+            &camera_query,
+            &model_query,
+            &mut selection_state,
+            &mut picking_bvh,
+        );
+        return;
+    }
+
+    if !mouse_button_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    let alt_pressed =
+        keyboard_input.pressed(KeyCode::AltLeft) || keyboard_input.pressed(KeyCode::AltRight);
+    if alt_pressed || widget_state.is_dragging() || pointer_over_ui(&mut contexts) {
+        return;
+    }
+
+    let Some(ref geometry) = current_model.geometry else {
+        return;
+    };
+
+    let modifier = SelectionModifier::from_keyboard(&keyboard_input);
+    if selection_state.tool.is_drag_tool() {
+        let Some(cursor_position) = windows.get_single().ok().and_then(|w| w.cursor_position())
+        else {
+            return;
+        };
+        selection_state.drag_path = vec![cursor_position];
+        selection_state.drag_modifier = modifier;
+        return;
+    }
+
+    let model_to_world = model_matrix(&model_query);
+    let Some((ray_origin, ray_direction)) =
+        cursor_ray_in_model_space(&windows, &camera_query, model_to_world)
+    else {
+        return;
+    };
+
+    let Some(hit) = picking_bvh.pick(geometry, ray_origin, ray_direction) else {
+        if modifier == SelectionModifier::Replace {
+            selection_state.clear();
+        }
+        return;
+    };
+    let seed = hit.cell_id(geometry);
+
+    match selection_state.tool {
+        SelectionTool::Pick => {
+            selection_state.apply([seed], modifier);
+        }
+        SelectionTool::Rectangle | SelectionTool::Lasso => {}
+        SelectionTool::RegionGrow => {
+            let Some((name, location)) = selection_state.scalar.clone() else {
+                println!("Region growing requires a scalar attribute");
+                return;
+            };
+            match grow_region(geometry, seed, &name, &location, selection_state.tolerance) {
+                Ok(region) => {
+                    println!(
+                        "Region grown from cell {} over {} cells using '{}'",
+                        seed,
+                        region.len(),
+                        name
+                    );
+                    selection_state.apply(region, modifier);
+                }
+                Err(e) => println!("Region growing failed: {:?}", e),
+            }
+        }
+    }
+}
+
+/// Extend the rectangle/lasso shape while dragging and apply it on release
+fn update_drag_selection(
+    mouse_button_input: &ButtonInput<MouseButton>,
+    current_model: &CurrentModelData,
+    windows: &Query<&Window, With<PrimaryWindow>>,
+    camera_query: &Query<(&Camera, &GlobalTransform), With<WorldModelCamera>>,
+    model_query: &Query<&GlobalTransform, With<UserModelMesh>>,
+    selection_state: &mut SelectionState,
+    picking_bvh: &mut PickingBvh,
+) {
+    let cursor_position = windows
+        .get_single()
+        .ok()
+        .and_then(|window| window.cursor_position());
+
+    if mouse_button_input.pressed(MouseButton::Left) {
+        let Some(cursor_position) = cursor_position else {
+            return;
+        };
+        match selection_state.tool {
+            SelectionTool::Rectangle => {
+                selection_state.drag_path.truncate(1);
+                selection_state.drag_path.push(cursor_position);
+            }
+            _ => {
+                let far_enough = selection_state
+                    .drag_path
+                    .last()
+                    .is_none_or(|last| last.distance(cursor_position) >= LASSO_POINT_SPACING);
+                if far_enough {
+                    selection_state.drag_path.push(cursor_position);
+                }
+            }
+        }
+        return;
+    }
+
+    // Mouse released (or focus lost): apply the shape
+    let shape = std::mem::take(&mut selection_state.drag_path);
+    let polygon = match selection_state.tool {
+        SelectionTool::Rectangle => {
+            let (Some(&start), Some(&end)) = (shape.first(), shape.last()) else {
+                return;
+            };
+            let (min, max) = (start.min(end), start.max(end));
+            vec![min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)]
+        }
+        _ => shape,
+    };
+    if polygon.len() < 3 {
+        return;
+    }
+
+    let (Some(geometry), Ok((camera, camera_transform))) =
+        (current_model.geometry.as_ref(), camera_query.get_single())
+    else {
+        return;
+    };
+
+    let visible = selection_state
+        .visible_only
+        .then(|| picking_bvh.tree(geometry));
+    let cells = cells_in_screen_polygon(
+        geometry,
+        model_matrix(model_query),
+        camera,
+        camera_transform,
+        &polygon,
+        visible,
+    );
+    println!(
+        "{} cells inside {:?} selection",
+        cells.len(),
+        selection_state.tool
+    );
+    let modifier = selection_state.drag_modifier;
+    selection_state.apply(cells, modifier);
+}
+
+/// Select cells whose center projects inside a screen-space polygon
+///
+/// # Parameters
+/// - `geometry`: Geometry data
+/// - `model_to_world`: Model transform
+/// - `camera`, `camera_transform`: Camera used for the projection
+/// - `polygon`: Closed polygon in logical viewport coordinates
+/// - `visible`: Picking tree of the geometry to skip cells hidden behind the
+///   front surface, `None` keeps all cells
+///
+/// # Returns
+/// - Original cell indices inside the polygon
+pub fn cells_in_screen_polygon(
+    geometry: &GeometryData,
+    model_to_world: Mat4,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    polygon: &[Vec2],
+    visible: Option<&TriangleBvh>,
+) -> Vec<usize> {
+    let triangles = cell_triangles(geometry);
+    let vertices = cell_vertices(geometry, &triangles);
+
+    let (polygon_min, polygon_max) = polygon.iter().fold(
+        (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+        |(min, max), &point| (min.min(point), max.max(point)),
+    );
+
+    vertices
+        .iter()
+        .enumerate()
+        .filter(|(_, cell_vertices)| !cell_vertices.is_empty())
+        .filter_map(|(cell, cell_vertices)| {
+            let center = cell_vertices
+                .iter()
+                .map(|&v| Vec3::from(geometry.vertices[v as usize]))
+                .sum::<Vec3>()
+                / cell_vertices.len() as f32;
+            let screen_position = camera
+                .world_to_viewport(camera_transform, model_to_world.transform_point3(center))
+                .ok()?;
+            let in_bounds = screen_position.cmpge(polygon_min).all()
+                && screen_position.cmple(polygon_max).all();
+            if !(in_bounds && point_in_polygon(screen_position, polygon)) {
+                return None;
+            }
+            let hidden = visible.is_some_and(|tree| {
+                !cell_in_view(
+                    geometry,
+                    tree,
+                    cell,
+                    &triangles[cell],
+                    model_to_world,
+                    camera,
+                    camera_transform,
+                )
+            });
+            (!hidden).then_some(cell)
+        })
+        .collect()
+}
+
+/// Whether the center of any triangle of a cell is the first surface hit by
+/// the camera ray through it
+fn cell_in_view(
+    geometry: &GeometryData,
+    tree: &TriangleBvh,
+    cell: usize,
+    triangles: &[usize],
+    model_to_world: Mat4,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+) -> bool {
+    let world_to_model = model_to_world.inverse();
+    triangles.iter().any(|&triangle| {
+        let center = geometry.indices[triangle * 3..triangle * 3 + 3]
+            .iter()
+            .map(|&v| Vec3::from(geometry.vertices[v as usize]))
+            .sum::<Vec3>()
+            / 3.0;
+        let Some(ray) = camera
+            .world_to_viewport(camera_transform, model_to_world.transform_point3(center))
+            .ok()
+            .and_then(|position| camera.viewport_to_world(camera_transform, position).ok())
+        else {
+            return false;
+        };
+        let origin = world_to_model.transform_point3(ray.origin);
+        let direction = world_to_model
+            .transform_vector3(*ray.direction)
+            .normalize_or_zero();
+        let distance = (center - origin).dot(direction);
+        tree.pick(geometry, origin, direction).is_none_or(|hit| {
+            hit.triangle == triangle
+                || mesh::topology::triangle_cell(geometry, hit.triangle) == cell
+                || hit.distance >= distance * (1.0 - VISIBILITY_TOLERANCE)
+        })
+    })
+}
+
+/// Even-odd point-in-polygon test
+fn point_in_polygon(point: Vec2, polygon: &[Vec2]) -> bool {
+    let mut inside = false;
+    let mut previous = polygon[polygon.len() - 1];
+    for &current in polygon {
+        if (current.y > point.y) != (previous.y > point.y) {
+            let x = previous.x
+                + (point.y - previous.y) / (current.y - previous.y) * (current.x - previous.x);
+            if point.x < x {
+                inside = !inside;
+            }
+        }
+        previous = current;
+    }
+    inside
+}
+
+/// Draw the rectangle/lasso shape while dragging
+fn draw_selection_drag(
+    mut contexts: EguiContexts,
+    selection_state: Res<SelectionState>,
+    ui_settings: Res<UiSettings>,
+) {
+    if selection_state.drag_path.len() < 2 {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let to_pos = |point: &Vec2| ui_settings.ui_position(*point);
+    let color = egui::Color32::from_rgb(255, 50, 200);
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Foreground,
+        egui::Id::new("selection_drag"),
+    ));
+
+    match selection_state.tool {
+        SelectionTool::Rectangle => {
+            let path = &selection_state.drag_path;
+            let rect = egui::Rect::from_two_pos(to_pos(&path[0]), to_pos(&path[path.len() - 1]));
+            painter.rect_stroke(rect, 0.0, egui::Stroke::new(1.5, color));
+        }
+        _ => {
+            let points = selection_state.drag_path.iter().map(to_pos).collect();
+            painter.add(egui::Shape::closed_line(
+                points,
+                egui::Stroke::new(1.5, color),
+            ));
+        }
+    }
+}
+
+/// Selection tool panel
+fn selection_panel(
+    mut contexts: EguiContexts,
+    mut interaction_mode: ResMut<InteractionMode>,
+    mut selection_state: ResMut<SelectionState>,
+    current_model: Res<CurrentModelData>,
+    mut file_dialogs: ResMut<FileDialogs>,
+    windows: Query<&Window>,
+) {
+    if *interaction_mode != InteractionMode::Select || windows.iter().next().is_none() {
+        return;
+    }
+
+    let scalar_names = current_model
+        .geometry
+        .as_ref()
+        .map(crate::mesh::topology::scalar_attribute_names)
+        .unwrap_or_default();
+
+    egui::Window::new("Selection")
+        .default_width(240.0)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label("Shift: add to selection, Ctrl: remove from selection");
+            ui.separator();
+
+            ui.label("Tool:");
+            ui.radio_value(&mut selection_state.tool, SelectionTool::Pick, "Pick Cell");
+            ui.radio_value(
+                &mut selection_state.tool,
+                SelectionTool::RegionGrow,
+                "Region Grow",
+            );
+            ui.radio_value(
+                &mut selection_state.tool,
+                SelectionTool::Rectangle,
+                "Rectangle",
+            );
+            ui.radio_value(&mut selection_state.tool, SelectionTool::Lasso, "Lasso");
+            if selection_state.tool.is_drag_tool() {
+                ui.checkbox(&mut selection_state.visible_only, "Visible cells only");
+            }
+
+            if selection_state.tool == SelectionTool::RegionGrow {
+                let selected_text = selection_state
+                    .scalar
+                    .as_ref()
+                    .map(|(name, location)| format!("{} ({:?})", name, location))
+                    .unwrap_or_else(|| "None".to_string());
+                egui::ComboBox::from_id_salt("region_grow_scalar")
+                    .selected_text(selected_text)
+                    .show_ui(ui, |ui| {
+                        for (name, location) in &scalar_names {
+                            ui.selectable_value(
+                                &mut selection_state.scalar,
+                                Some((name.clone(), location.clone())),
+                                format!("{} ({:?})", name, location),
+                            );
+                        }
+                    });
+
+                ui.horizontal(|ui| {
+                    ui.label("Tolerance:");
+                    ui.add(
+                        egui::DragValue::new(&mut selection_state.tolerance)
+                            .speed(0.01)
+                            .range(0.0..=f32::MAX),
+                    );
+                });
+            }
+
+            ui.separator();
+            ui.label("Query:");
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut selection_state.query_text)
+                    .hint_text("pressure > 1e5 AND region == 3"),
+            );
+            let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            let mut query_modifier = submitted.then_some(SelectionModifier::Replace);
+            ui.horizontal(|ui| {
+                if ui.button("Select").clicked() {
+                    query_modifier = Some(SelectionModifier::Replace);
+                }
+                if ui.button("Add").clicked() {
+                    query_modifier = Some(SelectionModifier::Add);
+                }
+                if ui.button("Subtract").clicked() {
+                    query_modifier = Some(SelectionModifier::Subtract);
+                }
+            });
+            if let (Some(modifier), Some(geometry)) =
+                (query_modifier, current_model.geometry.as_ref())
+            {
+                selection_state.apply_query(geometry, modifier);
+            }
+            if let Some(ref error) = selection_state.query_error {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+            if !scalar_names.is_empty() {
+                ui.label(format!(
+                    "Fields: id, {}",
+                    scalar_names
+                        .iter()
+                        .map(|(name, _)| name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+
+            ui.separator();
+            ui.label(format!(
+                "Selected cells: {}",
+                selection_state.selected_cells.len()
+            ));
+
+            if let Some(geometry) = current_model.geometry.as_ref() {
+                ui.add_enabled_ui(!selection_state.selected_cells.is_empty(), |ui| {
+                    ui.horizontal(|ui| {
+                        if ui.button("Copy Values").clicked() {
+                            ui.ctx()
+                                .copy_text(cells_to_csv(geometry, &selection_state.selected_cells));
+                            println!(
+                                "Copied values of {} cells to clipboard",
+                                selection_state.selected_cells.len()
+                            );
+                        }
+                        if ui.button("Export CSV...").clicked() {
+                            export_selection(
+                                geometry,
+                                &selection_state.selected_cells,
+                                ExportFormat::Csv,
+                                &mut file_dialogs,
+                            );
+                        }
+                        if ui.button("Export VTK...").clicked() {
+                            export_selection(
+                                geometry,
+                                &selection_state.selected_cells,
+                                ExportFormat::Vtk,
+                                &mut file_dialogs,
+                            );
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        if ui.button("Extract").clicked() {
+                            selection_state.action = Some(SelectionAction::Extract);
+                        }
+                        if ui.button("Hide").clicked() {
+                            selection_state.action = Some(SelectionAction::Hide);
+                        }
+                    });
+                });
+            }
+            ui.horizontal(|ui| {
+                if ui.button("Remove Extracted").clicked() {
+                    selection_state.action = Some(SelectionAction::RemoveExtracted);
+                }
+                if ui
+                    .add_enabled(
+                        selection_state.hidden_original.is_some(),
+                        egui::Button::new("Show All"),
+                    )
+                    .clicked()
+                {
+                    selection_state.action = Some(SelectionAction::ShowAll);
+                }
+            });
+
+            ui.horizontal(|ui| {
+                if ui.button("Clear Selection").clicked() {
+                    selection_state.clear();
+                }
+                if ui.button("Close").clicked() {
+                    *interaction_mode = InteractionMode::Navigate;
+                }
+            });
+        });
+}
+
+/// File formats for selection export
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ExportFormat {
+    Csv,
+    Vtk,
+}
+
+/// Ask for a file name and write the selected cells to it
+///
+/// The data is prepared on the calling thread; the file dialog and writing run
+/// on the IO task pool to avoid blocking the main thread.
+fn export_selection(
+    geometry: &GeometryData,
+    cells: &BTreeSet<usize>,
+    format: ExportFormat,
+    file_dialogs: &mut FileDialogs,
+) {
+    enum Payload {
+        Csv(String),
+        Vtk(Box<GeometryData>),
+    }
+
+    let payload = match format {
+        ExportFormat::Csv => Payload::Csv(cells_to_csv(geometry, cells)),
+        ExportFormat::Vtk => Payload::Vtk(Box::new(extract_cells(geometry, cells))),
+    };
+
+    let (filter_name, extension) = match payload {
+        Payload::Csv(_) => ("CSV", "csv"),
+        Payload::Vtk(_) => ("VTK", "vtk"),
+    };
+    let dialog = AsyncFileDialog::new()
+        .add_filter(filter_name, &[extension])
+        .set_file_name(format!("selection.{}", extension))
+        .set_directory(std::env::var("HOME").unwrap_or_else(|_| "/".to_string()));
+    file_dialogs.open(async move {
+        let file = dialog.save_file().await?;
+        let path = file.path();
+        let result = match payload {
+            Payload::Csv(csv) => std::fs::write(path, csv).map_err(VtkError::IoError),
+            Payload::Vtk(selection) => write_vtk_ascii(&selection, path),
+        };
+        match result {
+            Ok(()) => println!("Selection exported to {}", path.display()),
+            Err(e) => eprintln!("Failed to export selection: {}", e),
+        }
+        None
+    });
+}
+
+/// Extract, hide or show cells as requested in the panel
+#[allow(clippy::too_many_arguments)]
+fn apply_selection_action(
+    mut commands: Commands,
+    mut selection_state: ResMut<SelectionState>,
+    mut current_model: ResMut<CurrentModelData>,
+    color_bar_config: Res<ColorBarConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut model_entities: Query<(Entity, &mut Mesh3d), With<UserModelMesh>>,
+    extracted: Query<Entity, With<ExtractedSelection>>,
+) {
+    let Some(action) = selection_state.action.take() else {
+        return;
+    };
+    if action == SelectionAction::RemoveExtracted {
+        for entity in extracted.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+    let (Some(geometry), Ok((model, mut mesh3d))) = (
+        current_model.geometry.as_ref(),
+        model_entities.get_single_mut(),
+    ) else {
+        println!("No model entity found for the selection");
+        return;
+    };
+
+    let shown = match action {
+        SelectionAction::Extract => {
+            let selection = extract_cells(geometry, &selection_state.selected_cells);
+            let mut mesh = mesh::create_mesh_from_geometry(&selection);
+            if let Err(e) = apply_custom_color_mapping(&selection, &mut mesh, &color_bar_config) {
+                println!("Failed to apply color mapping to extracted cells: {:?}", e);
+            }
+            println!(
+                "Extracted {} cells into a new entity",
+                selection_state.selected_cells.len()
+            );
+            commands
+                .spawn((
+                    Name::new(format!(
+                        "Selection ({} cells)",
+                        selection_state.selected_cells.len()
+                    )),
+                    Mesh3d(meshes.add(mesh)),
+                    MeshMaterial3d(materials.add(StandardMaterial {
+                        base_color: Color::WHITE,
+                        perceptual_roughness: 0.5,
+                        cull_mode: None,
+                        double_sided: true,
+                        ..default()
+                    })),
+                    Transform::IDENTITY,
+                    ExtractedSelection,
+                ))
+                .set_parent(model);
+            return;
+        }
+        SelectionAction::Hide => {
+            let remaining: BTreeSet<usize> = (0..cell_count(geometry))
+                .filter(|cell| !selection_state.selected_cells.contains(cell))
+                .collect();
+            println!(
+                "Hid {} cells, {} remain",
+                selection_state.selected_cells.len(),
+                remaining.len()
+            );
+            let remaining = extract_cells(geometry, &remaining);
+            if selection_state.hidden_original.is_none() {
+                selection_state.hidden_original = Some(geometry.clone());
+            }
+            remaining
+        }
+        SelectionAction::ShowAll => {
+            let Some(original) = selection_state.hidden_original.take() else {
+                return;
+            };
+            println!("Restored hidden cells");
+            original
+        }
+        SelectionAction::RemoveExtracted => return,
+    };
+
+    // Cell indices change with the geometry
+    selection_state.clear();
+    let mut new_mesh = mesh::create_mesh_from_geometry(&shown);
+    if let Err(e) = apply_custom_color_mapping(&shown, &mut new_mesh, &color_bar_config) {
+        println!("Failed to apply color mapping after hiding cells: {:?}", e);
+    }
+    *mesh3d = Mesh3d(meshes.add(new_mesh));
+    current_model.geometry = Some(shown);
+}
+
+/// Build a mesh containing the triangles of the selected cells
+fn build_selection_mesh(geometry: &GeometryData, selected_cells: &BTreeSet<usize>) -> Mesh {
+    let indices: Vec<u32> = geometry
+        .indices
+        .chunks_exact(3)
+        .enumerate()
+        .filter(|(triangle, _)| {
+            selected_cells.contains(&crate::mesh::topology::triangle_cell(geometry, *triangle))
+        })
+        .flat_map(|(_, corners)| corners.iter().copied())
+        .collect();
+
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        VertexAttributeValues::from(geometry.vertices.clone()),
+    );
+    mesh.insert_indices(Indices::U32(indices));
+    mesh.compute_normals();
+    mesh
+}
+
+/// Rebuild the highlight overlay when the selection changed
+fn update_selection_overlay(
+    mut commands: Commands,
+    mut selection_state: ResMut<SelectionState>,
+    current_model: Res<CurrentModelData>,
+    overlays: Query<Entity, With<SelectionOverlay>>,
+    model_query: Query<&Transform, With<UserModelMesh>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if !selection_state.changed {
+        return;
+    }
+    selection_state.changed = false;
+
+    for entity in overlays.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    let Some(ref geometry) = current_model.geometry else {
+        return;
+    };
+    if selection_state.selected_cells.is_empty() {
+        return;
+    }
+
+    let transform = model_query.iter().next().copied().unwrap_or_default();
+    commands.spawn((
+        Mesh3d(meshes.add(build_selection_mesh(
+            geometry,
+            &selection_state.selected_cells,
+        ))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: SELECTION_COLOR,
+            unlit: true,
+            cull_mode: None,
+            alpha_mode: AlphaMode::Blend,
+            depth_bias: 10.0,
+            ..default()
+        })),
+        transform,
+        NoWireframe,
+        SelectionOverlay,
+    ));
+}
+
+/// Keep the overlay aligned with the (possibly transformed) model
+fn sync_selection_overlay_transform(
+    model_query: Query<&Transform, (With<UserModelMesh>, Without<SelectionOverlay>)>,
+    mut overlays: Query<&mut Transform, With<SelectionOverlay>>,
+) {
+    let Some(model_transform) = model_query.iter().next() else {
+        return;
+    };
+    for mut transform in overlays.iter_mut() {
+        *transform = *model_transform;
+    }
+}
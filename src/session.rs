@@ -0,0 +1,665 @@
+//! # Session Recovery Module
+//!
+//! This module protects long interactive sessions against crashes:
+//! - The session (open files, subdivision steps, camera, model transform, view
+//!   settings and annotations) is snapshotted to disk every few seconds when it changed
+//! - A lock file marks the running application and is removed on a clean exit
+//! - If the lock file is still present at startup, the previous run ended
+//!   abnormally and restoring its last snapshot is offered
+//!
+//! Session files are kept in `~/.data_visualization/`. Project files (see the
+//! project module) save and restore the same snapshot on request.
+
+use crate::animation::{TimeSeriesAsset, TimeSeriesEvent};
+use crate::annotations::{Annotation, AnnotationState};
+use crate::camera::{SetCameraViewEvent, WorldModelCamera};
+use crate::downsampling::PointDownsampling;
+use crate::heightmap::HeightmapSettings;
+use crate::lod::LODManager;
+use crate::periodic::PeriodicSettings;
+use crate::ui::events::{
+    ClearAllMeshesEvent, GenerateLODEvent, LoadModelEvent, SubdivideMeshEvent,
+};
+use crate::ui::{ColorBarConfig, ModelLoadFailedEvent, ModelLoadedEvent, UserModelMesh};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Seconds between session snapshots
+const AUTO_SAVE_INTERVAL: f32 = 15.0;
+/// Snapshot file name
+const SESSION_FILE: &str = "session.txt";
+/// Lock file name, present while the application runs
+const LOCK_FILE: &str = "session.lock";
+
+/// Snapshot of an interactive session
+#[derive(Clone, Debug)]
+pub struct SessionSnapshot {
+    /// Loaded model file
+    pub model: Option<PathBuf>,
+    /// Loaded time series files
+    pub time_series: Vec<PathBuf>,
+    /// Current time step of the time series
+    pub time_step: usize,
+    /// Subdivision steps applied to the model
+    pub subdivisions: u32,
+    pub camera_translation: Vec3,
+    pub camera_rotation: Quat,
+    pub model_transform: Transform,
+    pub color_map: String,
+    pub color_bar_visible: bool,
+    /// Value range of the color bar (min, max)
+    pub color_range: [f32; 2],
+    pub color_bar_title: String,
+    pub color_bar_unit: String,
+    /// LOD levels were generated for the model
+    pub lod: bool,
+    pub point_downsampling: bool,
+    pub point_budget: usize,
+    pub heightmap: bool,
+    pub heightmap_relief: f32,
+    pub periodic_tiles: [u32; 3],
+    pub periodic_period: [f32; 3],
+    /// Text notes on the model
+    pub annotations: Vec<Annotation>,
+}
+
+impl SessionSnapshot {
+    /// Whether the snapshot has data to restore
+    pub fn has_data(&self) -> bool {
+        self.model.is_some() || !self.time_series.is_empty()
+    }
+
+    /// Write the snapshot as `key = value` lines
+    pub fn to_text(&self) -> String {
+        fn floats(values: &[f32]) -> String {
+            values
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        }
+
+        let mut lines = Vec::new();
+        if let Some(ref model) = self.model {
+            lines.push(format!("model = {}", model.display()));
+        }
+        for file in &self.time_series {
+            lines.push(format!("series = {}", file.display()));
+        }
+        lines.push(format!("time_step = {}", self.time_step));
+        lines.push(format!("subdivisions = {}", self.subdivisions));
+        lines.push(format!(
+            "camera_translation = {}",
+            floats(&self.camera_translation.to_array())
+        ));
+        lines.push(format!(
+            "camera_rotation = {}",
+            floats(&self.camera_rotation.to_array())
+        ));
+        lines.push(format!(
+            "model_translation = {}",
+            floats(&self.model_transform.translation.to_array())
+        ));
+        lines.push(format!(
+            "model_rotation = {}",
+            floats(&self.model_transform.rotation.to_array())
+        ));
+        lines.push(format!(
+            "model_scale = {}",
+            floats(&self.model_transform.scale.to_array())
+        ));
+        lines.push(format!("color_map = {}", self.color_map));
+        lines.push(format!("color_bar_visible = {}", self.color_bar_visible));
+        lines.push(format!("color_range = {}", floats(&self.color_range)));
+        lines.push(format!("color_bar_title = {}", self.color_bar_title));
+        lines.push(format!("color_bar_unit = {}", self.color_bar_unit));
+        lines.push(format!("lod = {}", self.lod));
+        lines.push(format!("point_downsampling = {}", self.point_downsampling));
+        lines.push(format!("point_budget = {}", self.point_budget));
+        lines.push(format!("heightmap = {}", self.heightmap));
+        lines.push(format!("heightmap_relief = {}", self.heightmap_relief));
+        lines.push(format!(
+            "periodic_tiles = {} {} {}",
+            self.periodic_tiles[0], self.periodic_tiles[1], self.periodic_tiles[2]
+        ));
+        lines.push(format!(
+            "periodic_period = {}",
+            floats(&self.periodic_period)
+        ));
+        for annotation in &self.annotations {
+            lines.push(format!(
+                "annotation = {} {}",
+                floats(&annotation.position.to_array()),
+                annotation.text.replace('\n', " ")
+            ));
+        }
+        lines.join("\n") + "\n"
+    }
+
+    /// Read a snapshot written by [`SessionSnapshot::to_text`]
+    ///
+    /// Unknown keys and malformed values are skipped and keep their defaults.
+    pub fn parse(text: &str) -> Self {
+        fn floats<const N: usize>(value: &str) -> Option<[f32; N]> {
+            let values: Vec<f32> = value
+                .split_whitespace()
+                .map(|v| v.parse().ok())
+                .collect::<Option<_>>()?;
+            values.try_into().ok()
+        }
+
+        let mut snapshot = Self::default();
+        for line in text.lines() {
+            let Some((key, value)) = line.split_once(" = ") else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                "model" => snapshot.model = Some(PathBuf::from(value)),
+                "series" => snapshot.time_series.push(PathBuf::from(value)),
+                "time_step" => snapshot.time_step = value.parse().unwrap_or(0),
+                "subdivisions" => snapshot.subdivisions = value.parse().unwrap_or(0),
+                "camera_translation" => {
+                    if let Some(v) = floats::<3>(value) {
+                        snapshot.camera_translation = Vec3::from(v);
+                    }
+                }
+                "camera_rotation" => {
+                    if let Some(v) = floats::<4>(value) {
+                        snapshot.camera_rotation = Quat::from_array(v).normalize();
+                    }
+                }
+                "model_translation" => {
+                    if let Some(v) = floats::<3>(value) {
+                        snapshot.model_transform.translation = Vec3::from(v);
+                    }
+                }
+                "model_rotation" => {
+                    if let Some(v) = floats::<4>(value) {
+                        snapshot.model_transform.rotation = Quat::from_array(v).normalize();
+                    }
+                }
+                "model_scale" => {
+                    if let Some(v) = floats::<3>(value) {
+                        snapshot.model_transform.scale = Vec3::from(v);
+                    }
+                }
+                "color_map" => snapshot.color_map = value.to_string(),
+                "color_bar_visible" => snapshot.color_bar_visible = value == "true",
+                "color_range" => {
+                    if let Some(v) = floats::<2>(value) {
+                        snapshot.color_range = v;
+                    }
+                }
+                "color_bar_title" => snapshot.color_bar_title = value.to_string(),
+                "color_bar_unit" => snapshot.color_bar_unit = value.to_string(),
+                "lod" => snapshot.lod = value == "true",
+                "point_downsampling" => snapshot.point_downsampling = value == "true",
+                "point_budget" => {
+                    snapshot.point_budget = value.parse().unwrap_or(snapshot.point_budget)
+                }
+                "heightmap" => snapshot.heightmap = value == "true",
+                "heightmap_relief" => {
+                    snapshot.heightmap_relief = value.parse().unwrap_or(snapshot.heightmap_relief)
+                }
+                "periodic_tiles" => {
+                    if let Some(v) = floats::<3>(value) {
+                        snapshot.periodic_tiles = v.map(|tiles| tiles.max(1.0) as u32);
+                    }
+                }
+                "periodic_period" => {
+                    if let Some(v) = floats::<3>(value) {
+                        snapshot.periodic_period = v;
+                    }
+                }
+                "annotation" => {
+                    // Position followed by the text
+                    let mut parts = value.splitn(4, ' ');
+                    let position: Vec<&str> = parts.by_ref().take(3).collect();
+                    if let Some(v) = floats::<3>(&position.join(" ")) {
+                        snapshot.annotations.push(Annotation {
+                            position: Vec3::from(v),
+                            text: parts.next().unwrap_or_default().to_string(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        snapshot
+    }
+}
+
+impl Default for SessionSnapshot {
+    fn default() -> Self {
+        let downsampling = PointDownsampling::default();
+        let heightmap = HeightmapSettings::default();
+        let periodic = PeriodicSettings::default();
+        let color_bar = ColorBarConfig::default();
+        Self {
+            model: None,
+            time_series: Vec::new(),
+            time_step: 0,
+            subdivisions: 0,
+            camera_translation: Vec3::new(10.0, 10.0, 10.0),
+            camera_rotation: Quat::IDENTITY,
+            model_transform: Transform::IDENTITY,
+            color_map: color_bar.color_map_name,
+            color_bar_visible: color_bar.visible,
+            color_range: [color_bar.min_value, color_bar.max_value],
+            color_bar_title: color_bar.title,
+            color_bar_unit: color_bar.unit,
+            lod: false,
+            point_downsampling: downsampling.enabled,
+            point_budget: downsampling.point_budget,
+            heightmap: heightmap.enabled,
+            heightmap_relief: heightmap.relief,
+            periodic_tiles: periodic.tiles,
+            periodic_period: periodic.period,
+            annotations: Vec::new(),
+        }
+    }
+}
+
+/// Session recovery state
+#[derive(Resource, Default)]
+pub struct SessionRecovery {
+    /// Snapshot of an abnormally ended session, offered for restoring
+    recovered: Option<SessionSnapshot>,
+    /// Snapshot to restore, started on the next frame
+    requested: Option<SessionSnapshot>,
+    /// Snapshot being restored, applied once its files are loaded
+    restoring: Option<SessionSnapshot>,
+    /// The files of the restored session finished loading
+    restore_loaded: bool,
+    /// Model file of the current session
+    model: Option<PathBuf>,
+    /// Model file being loaded, becomes the session model once it loaded
+    loading_model: Option<PathBuf>,
+    /// Subdivision steps applied since the model was loaded
+    subdivisions: u32,
+}
+
+/// Periodic snapshot writing
+#[derive(Resource)]
+struct AutoSave {
+    /// Last written snapshot
+    last_saved: String,
+    timer: Timer,
+}
+
+impl Default for AutoSave {
+    fn default() -> Self {
+        Self {
+            last_saved: String::new(),
+            timer: Timer::new(
+                Duration::from_secs_f32(AUTO_SAVE_INTERVAL),
+                TimerMode::Repeating,
+            ),
+        }
+    }
+}
+
+impl SessionRecovery {
+    /// Restore a snapshot: load its files, then apply its settings
+    pub fn restore(&mut self, snapshot: SessionSnapshot) {
+        self.requested = Some(snapshot);
+    }
+}
+
+/// Current state of the session, collected into snapshots
+#[derive(SystemParam)]
+pub struct SessionState<'w, 's> {
+    recovery: Res<'w, SessionRecovery>,
+    time_series: Res<'w, TimeSeriesAsset>,
+    camera_query: Query<'w, 's, &'static Transform, With<WorldModelCamera>>,
+    model_query: Query<'w, 's, (&'static Transform, Has<LODManager>), With<UserModelMesh>>,
+    color_bar_config: Res<'w, ColorBarConfig>,
+    downsampling: Res<'w, PointDownsampling>,
+    heightmap: Res<'w, HeightmapSettings>,
+    periodic: Res<'w, PeriodicSettings>,
+    annotations: Res<'w, AnnotationState>,
+}
+
+impl SessionState<'_, '_> {
+    /// Snapshot of the current session
+    pub fn snapshot(&self) -> SessionSnapshot {
+        let camera = self.camera_query.iter().next().copied().unwrap_or_default();
+        let (model_transform, lod) = self
+            .model_query
+            .iter()
+            .next()
+            .map_or((Transform::default(), false), |(transform, lod)| {
+                (*transform, lod)
+            });
+        let time_series_files = if self.time_series.is_loaded {
+            self.time_series.all_file_paths.clone()
+        } else {
+            Vec::new()
+        };
+        SessionSnapshot {
+            model: self.recovery.model.clone(),
+            time_series: time_series_files,
+            time_step: self.time_series.current_time_step,
+            subdivisions: self.recovery.subdivisions,
+            camera_translation: camera.translation,
+            camera_rotation: camera.rotation,
+            model_transform,
+            color_map: self.color_bar_config.color_map_name.clone(),
+            color_bar_visible: self.color_bar_config.visible,
+            color_range: [
+                self.color_bar_config.min_value,
+                self.color_bar_config.max_value,
+            ],
+            color_bar_title: self.color_bar_config.title.clone(),
+            color_bar_unit: self.color_bar_config.unit.clone(),
+            lod,
+            point_downsampling: self.downsampling.enabled,
+            point_budget: self.downsampling.point_budget,
+            heightmap: self.heightmap.enabled,
+            heightmap_relief: self.heightmap.relief,
+            periodic_tiles: self.periodic.tiles,
+            periodic_period: self.periodic.period,
+            annotations: self.annotations.annotations.clone(),
+        }
+    }
+}
+
+pub struct SessionPlugin;
+
+impl Plugin for SessionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SessionRecovery>()
+            .init_resource::<AutoSave>()
+            .add_systems(Startup, check_previous_session)
+            .add_systems(
+                Update,
+                (
+                    track_session_files,
+                    recovery_dialog.after(EguiSet::InitContexts),
+                    start_restore,
+                    finish_restore,
+                    auto_save_session,
+                )
+                    .chain(),
+            )
+            .add_systems(Last, remove_lock_on_exit);
+    }
+}
+
+/// Directory of the session and configuration files
+pub fn session_dir() -> PathBuf {
+    std::env::var("HOME")
+        .map(|home| PathBuf::from(home).join(".data_visualization"))
+        .unwrap_or_else(|_| std::env::temp_dir().join("data_visualization"))
+}
+
+/// Mark the session as cleanly ended
+///
+/// Called on every regular exit path; a lock file left behind means the
+/// application crashed or was killed.
+pub fn mark_clean_exit() {
+    let _ = std::fs::remove_file(session_dir().join(LOCK_FILE));
+}
+
+/// Offer the last snapshot if the previous run did not exit cleanly, then lock
+/// the session of this run
+fn check_previous_session(mut recovery: ResMut<SessionRecovery>) {
+    let dir = session_dir();
+    if dir.join(LOCK_FILE).exists() {
+        if let Ok(text) = std::fs::read_to_string(dir.join(SESSION_FILE)) {
+            let snapshot = SessionSnapshot::parse(&text);
+            if snapshot.has_data() {
+                println!("Previous session ended abnormally, offering recovery");
+                recovery.recovered = Some(snapshot);
+            }
+        }
+    }
+
+    if let Err(e) = std::fs::create_dir_all(&dir)
+        .and_then(|_| std::fs::write(dir.join(LOCK_FILE), std::process::id().to_string()))
+    {
+        eprintln!("Failed to create session lock: {}", e);
+    }
+}
+
+/// Remove the lock file when the application exits
+fn remove_lock_on_exit(mut exit_events: EventReader<AppExit>) {
+    if exit_events.read().next().is_some() {
+        mark_clean_exit();
+    }
+}
+
+/// Follow the loaded model file and the subdivision steps applied to it
+///
+/// Requested files become the session model once they loaded; files that
+/// failed to load are not recorded.
+fn track_session_files(
+    mut recovery: ResMut<SessionRecovery>,
+    mut load_events: EventReader<LoadModelEvent>,
+    mut loaded_events: EventReader<ModelLoadedEvent>,
+    mut failed_events: EventReader<ModelLoadFailedEvent>,
+    mut subdivide_events: EventReader<SubdivideMeshEvent>,
+    mut clear_events: EventReader<ClearAllMeshesEvent>,
+) {
+    if clear_events.read().next().is_some() {
+        recovery.model = None;
+        recovery.subdivisions = 0;
+    }
+    for LoadModelEvent(path) in load_events.read() {
+        recovery.loading_model = Some(path.clone());
+    }
+    for failed in failed_events.read() {
+        if recovery.loading_model.as_ref() == Some(&failed.path) {
+            recovery.loading_model = None;
+        }
+    }
+    if loaded_events.read().next().is_some() {
+        if let Some(path) = recovery.loading_model.take() {
+            recovery.model = Some(path);
+            recovery.subdivisions = 0;
+        }
+    }
+    recovery.subdivisions += subdivide_events.read().count() as u32;
+}
+
+/// Dialog offering to restore the previous session
+fn recovery_dialog(
+    mut contexts: EguiContexts,
+    mut recovery: ResMut<SessionRecovery>,
+    windows: Query<&Window>,
+) {
+    if windows.iter().next().is_none() {
+        return;
+    }
+    let Some(snapshot) = recovery.recovered.clone() else {
+        return;
+    };
+
+    let mut restore = false;
+    let mut discard = false;
+    egui::Window::new("Recover Session")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label("The previous session ended unexpectedly.");
+            if let Some(ref model) = snapshot.model {
+                ui.label(format!("Model: {}", model.display()));
+            }
+            if let Some(first) = snapshot.time_series.first() {
+                ui.label(format!(
+                    "Time series: {} files starting with {}",
+                    snapshot.time_series.len(),
+                    first.display()
+                ));
+            }
+            ui.horizontal(|ui| {
+                restore = ui.button("Restore").clicked();
+                discard = ui.button("Discard").clicked();
+            });
+        });
+
+    if discard {
+        recovery.recovered = None;
+    }
+    if restore {
+        recovery.recovered = None;
+        recovery.restore(snapshot);
+    }
+}
+
+/// Start restoring a requested snapshot by loading its files
+fn start_restore(
+    mut recovery: ResMut<SessionRecovery>,
+    mut color_bar_config: ResMut<ColorBarConfig>,
+    mut downsampling: ResMut<PointDownsampling>,
+    mut load_events: EventWriter<LoadModelEvent>,
+    mut time_series_events: EventWriter<TimeSeriesEvent>,
+) {
+    let Some(snapshot) = recovery.requested.take() else {
+        return;
+    };
+
+    // Settings used while loading are applied first
+    if color_bar_config.color_map_name != snapshot.color_map {
+        color_bar_config.color_map_name = snapshot.color_map.clone();
+        color_bar_config.has_changed = true;
+    }
+    downsampling.enabled = snapshot.point_downsampling;
+    downsampling.point_budget = snapshot.point_budget;
+
+    let missing: Vec<&PathBuf> = snapshot
+        .model
+        .iter()
+        .chain(&snapshot.time_series)
+        .filter(|path| !path.exists())
+        .collect();
+    if !missing.is_empty() {
+        println!("Session files no longer exist: {:?}", missing);
+        return;
+    }
+
+    if !snapshot.time_series.is_empty() {
+        time_series_events.send(TimeSeriesEvent::LoadSeries(snapshot.time_series.clone()));
+    } else if let Some(ref model) = snapshot.model {
+        load_events.send(LoadModelEvent(model.clone()));
+    } else {
+        println!("Session has no files to restore");
+        return;
+    }
+    println!("Restoring session");
+    recovery.restoring = Some(snapshot);
+    recovery.restore_loaded = false;
+}
+
+/// Apply the camera, transform and view settings of the restored session once
+/// its files are loaded
+///
+/// Settings are applied in the frame after the model loaded, so they are not
+/// overwritten by the defaults other plugins pick for a new model.
+#[allow(clippy::too_many_arguments)]
+fn finish_restore(
+    mut recovery: ResMut<SessionRecovery>,
+    mut model_loaded_events: EventReader<ModelLoadedEvent>,
+    mut failed_events: EventReader<ModelLoadFailedEvent>,
+    time_series: Res<TimeSeriesAsset>,
+    mut model_query: Query<&mut Transform, With<UserModelMesh>>,
+    mut color_bar_config: ResMut<ColorBarConfig>,
+    mut heightmap: ResMut<HeightmapSettings>,
+    mut periodic: ResMut<PeriodicSettings>,
+    mut annotations: ResMut<AnnotationState>,
+    mut camera_events: EventWriter<SetCameraViewEvent>,
+    mut subdivide_events: EventWriter<SubdivideMeshEvent>,
+    mut lod_events: EventWriter<GenerateLODEvent>,
+    mut time_series_events: EventWriter<TimeSeriesEvent>,
+) {
+    let loaded = model_loaded_events.read().count() > 0;
+    let failed: Vec<&ModelLoadFailedEvent> = failed_events.read().collect();
+    let Some(snapshot) = recovery.restoring.clone() else {
+        return;
+    };
+
+    // A file of the session did not load: give up, so auto-saving resumes
+    if let Some(failed) = failed.first() {
+        println!(
+            "Session restore failed: {} could not be loaded: {}",
+            failed.path.display(),
+            failed.error
+        );
+        recovery.restoring = None;
+        recovery.restore_loaded = false;
+        return;
+    }
+
+    let series_ready = snapshot.time_series.is_empty() || time_series.is_loaded;
+    if recovery.restore_loaded && series_ready {
+        recovery.restoring = None;
+
+        for _ in 0..snapshot.subdivisions {
+            subdivide_events.send(SubdivideMeshEvent);
+        }
+        // LOD generation runs after subdivision, so levels use the subdivided model
+        if snapshot.lod {
+            lod_events.send(GenerateLODEvent);
+        }
+        if !snapshot.time_series.is_empty() {
+            time_series_events.send(TimeSeriesEvent::SetTimeStep(snapshot.time_step));
+        }
+        for mut transform in model_query.iter_mut() {
+            *transform = snapshot.model_transform;
+        }
+        camera_events.send(SetCameraViewEvent {
+            translation: snapshot.camera_translation,
+            rotation: snapshot.camera_rotation,
+        });
+
+        color_bar_config.visible = snapshot.color_bar_visible;
+        color_bar_config.min_value = snapshot.color_range[0];
+        color_bar_config.max_value = snapshot.color_range[1];
+        color_bar_config.title = snapshot.color_bar_title.clone();
+        color_bar_config.unit = snapshot.color_bar_unit.clone();
+        color_bar_config.has_changed = true;
+        heightmap.enabled = snapshot.heightmap;
+        heightmap.relief = snapshot.heightmap_relief;
+        heightmap.changed = true;
+        periodic.tiles = snapshot.periodic_tiles;
+        periodic.period = snapshot.periodic_period;
+        periodic.changed = true;
+        annotations.annotations = snapshot.annotations.clone();
+        println!("Session restored");
+    }
+
+    if loaded {
+        recovery.restore_loaded = true;
+    }
+}
+
+/// Write a snapshot of the session when it changed
+fn auto_save_session(time: Res<Time>, mut auto_save: ResMut<AutoSave>, session: SessionState) {
+    if !auto_save.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    // Keep the crashed session until the user decided about it
+    let recovery = &session.recovery;
+    if recovery.recovered.is_some() || recovery.requested.is_some() || recovery.restoring.is_some()
+    {
+        return;
+    }
+
+    let text = session.snapshot().to_text();
+    if text == auto_save.last_saved {
+        return;
+    }
+    let dir = session_dir();
+    // Write to a temporary file first so a crash while saving keeps the old snapshot
+    let temp = dir.join(format!("{}.tmp", SESSION_FILE));
+    match std::fs::write(&temp, &text).and_then(|_| std::fs::rename(&temp, dir.join(SESSION_FILE)))
+    {
+        Ok(()) => auto_save.last_saved = text,
+        Err(e) => eprintln!("Failed to save session: {}", e),
+    }
+}
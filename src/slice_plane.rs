@@ -0,0 +1,432 @@
+//! # Slice Plane Module
+//!
+//! This module cuts the model with an arbitrary plane and shows the cut:
+//! - Volume cells of unstructured grids and image data volumes (`.vti`) are cut
+//!   into polygons
+//! - Surface meshes are cut into lines
+//! - Point attributes are interpolated onto the cut and can color it with the
+//!   color map of the color bar
+//!
+//! The plane is the plane widget: drag its center handle to move the origin and
+//! its tip handle to turn the normal, or edit both in the panel. The cut is
+//! rebuilt when a drag ends. It is a child of the model, so it follows the model
+//! transform and is removed with the model. Open the panel via Mesh > Slice.
+
+use crate::mesh::color_maps::get_color_map;
+use crate::mesh::isosurface::value_range;
+use crate::mesh::slice::{slice_cells, slice_surface, slice_volume, SliceLines};
+use crate::mesh::topology::attribute_value;
+use crate::mesh::{self, AttributeLocation, AttributeType, GeometryData};
+use crate::ui::{ColorBarConfig, CurrentModelData, ModelLoadedEvent, UserModelMesh};
+use crate::volume_slices::VolumeSlices;
+use crate::widgets::{vec3_editor, WidgetChangedEvent, WidgetKind, WidgetState};
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::utils::HashMap;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Color of the cut without a color attribute
+const SLICE_COLOR: Color = Color::srgb(0.2, 0.8, 1.0);
+
+/// Slice settings
+#[derive(Resource, Default)]
+pub struct SliceSettings {
+    /// Show the slice panel
+    pub show_panel: bool,
+    /// Show the cut
+    pub enabled: bool,
+    /// Point attribute coloring the cut, `None` for a plain color
+    pub color_attribute: Option<String>,
+    /// Settings or plane changed, the cut needs to be rebuilt
+    pub changed: bool,
+    /// Triangles or line segments of the current cut
+    elements: usize,
+}
+
+/// Marker component of the slice entity
+#[derive(Component)]
+pub struct SliceMesh;
+
+pub struct SlicePlanePlugin;
+
+impl Plugin for SlicePlanePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SliceSettings>().add_systems(
+            Update,
+            (
+                refresh_on_change,
+                slice_panel.after(EguiSet::InitContexts),
+                update_slice,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Dataset cut by the plane
+enum Source<'a> {
+    /// Image data volume
+    Volume(&'a mesh::volume::VolumeData),
+    /// Geometry with volume cells
+    Cells(&'a GeometryData),
+    /// Surface geometry
+    Surface(&'a GeometryData),
+}
+
+/// Result of a cut
+enum Cut {
+    Polygons(Box<GeometryData>),
+    Lines(SliceLines),
+}
+
+impl Source<'_> {
+    fn find<'a>(
+        current_model: &'a CurrentModelData,
+        volume_slices: &'a VolumeSlices,
+    ) -> Option<Source<'a>> {
+        if let Some(volume) = volume_slices.volume() {
+            return Some(Source::Volume(volume));
+        }
+        let geometry = current_model.geometry.as_ref()?;
+        if geometry
+            .volume_cells
+            .as_ref()
+            .is_some_and(|cells| !cells.is_empty())
+        {
+            Some(Source::Cells(geometry))
+        } else {
+            Some(Source::Surface(geometry))
+        }
+    }
+
+    fn attributes(&self) -> Option<&HashMap<(String, AttributeLocation), AttributeType>> {
+        match self {
+            Source::Volume(volume) => Some(&volume.attributes),
+            Source::Cells(geometry) | Source::Surface(geometry) => geometry.attributes.as_ref(),
+        }
+    }
+
+    fn point_count(&self) -> usize {
+        match self {
+            Source::Volume(volume) => volume.point_count(),
+            Source::Cells(geometry) | Source::Surface(geometry) => geometry.vertices.len(),
+        }
+    }
+
+    /// Point attributes that can color the cut, sorted by name
+    fn attribute_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .attributes()
+            .into_iter()
+            .flatten()
+            .filter(|((name, location), attribute)| {
+                *location == AttributeLocation::Point
+                    && !name.starts_with("__lut_")
+                    && matches!(
+                        attribute,
+                        AttributeType::Scalar { .. } | AttributeType::Vector(_)
+                    )
+            })
+            .map(|((name, _), _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Value range of a point attribute over the whole dataset
+    fn attribute_range(&self, name: &str) -> Option<(f32, f32)> {
+        let attribute = self
+            .attributes()?
+            .get(&(name.to_string(), AttributeLocation::Point))?;
+        value_range(attribute, self.point_count())
+    }
+
+    fn description(&self) -> String {
+        match self {
+            Source::Volume(volume) => format!(
+                "Volume: {} x {} x {} points, cut into polygons",
+                volume.dims[0], volume.dims[1], volume.dims[2]
+            ),
+            Source::Cells(geometry) => format!(
+                "{} volume cells, cut into polygons",
+                geometry
+                    .volume_cells
+                    .as_ref()
+                    .map_or(0, |cells| cells.len())
+            ),
+            Source::Surface(_) => "Surface, cut into lines".to_string(),
+        }
+    }
+
+    fn cut(&self, origin: Vec3, normal: Vec3) -> Option<Cut> {
+        match self {
+            Source::Volume(volume) => {
+                slice_volume(volume, origin, normal).map(|cut| Cut::Polygons(Box::new(cut)))
+            }
+            Source::Cells(geometry) => {
+                slice_cells(geometry, origin, normal).map(|cut| Cut::Polygons(Box::new(cut)))
+            }
+            Source::Surface(geometry) => Some(Cut::Lines(slice_surface(geometry, origin, normal))),
+        }
+    }
+}
+
+/// Rebuild the cut for a newly loaded model or an edited plane widget
+fn refresh_on_change(
+    mut loaded_events: EventReader<ModelLoadedEvent>,
+    mut widget_changed_events: EventReader<WidgetChangedEvent>,
+    mut settings: ResMut<SliceSettings>,
+) {
+    let loaded = loaded_events.read().count() > 0;
+    let plane_changed = widget_changed_events
+        .read()
+        .any(|event| event.0 == WidgetKind::Plane);
+    if loaded || plane_changed {
+        settings.changed = settings.enabled;
+    }
+}
+
+/// Slice panel
+fn slice_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<SliceSettings>,
+    mut widget_state: ResMut<WidgetState>,
+    current_model: Res<CurrentModelData>,
+    volume_slices: Res<VolumeSlices>,
+    windows: Query<&Window>,
+) {
+    if !settings.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let source = Source::find(&current_model, &volume_slices);
+    let mut enabled = settings.enabled;
+    let mut color_attribute = settings.color_attribute.clone();
+    let mut plane = widget_state.plane.clone();
+    let mut plane_changed = false;
+    let speed = (widget_state.scale * 0.005).max(1e-4) as f64;
+    let mut open = true;
+
+    egui::Window::new("Slice")
+        .default_width(280.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            let Some(source) = source else {
+                ui.label("Load a model to slice.");
+                return;
+            };
+            ui.label(source.description());
+            ui.checkbox(&mut enabled, "Show slice");
+            ui.separator();
+
+            ui.add_enabled_ui(enabled, |ui| {
+                plane_changed |= vec3_editor(ui, "Origin", &mut plane.origin, speed);
+                if vec3_editor(ui, "Normal", &mut plane.normal, 0.01) {
+                    plane.normal = plane.normal.try_normalize().unwrap_or(Vec3::X);
+                    plane_changed = true;
+                }
+                ui.horizontal(|ui| {
+                    for (label, normal) in [("X", Vec3::X), ("Y", Vec3::Y), ("Z", Vec3::Z)] {
+                        if ui.button(label).clicked() {
+                            plane.normal = normal;
+                            plane_changed = true;
+                        }
+                    }
+                    if ui.button("Flip").clicked() {
+                        plane.normal = -plane.normal;
+                        plane_changed = true;
+                    }
+                });
+                ui.label("Drag the plane handles in the viewport to move it.");
+                ui.separator();
+
+                let names = source.attribute_names();
+                if color_attribute
+                    .as_ref()
+                    .is_some_and(|name| !names.contains(name))
+                {
+                    color_attribute = None;
+                }
+                egui::ComboBox::from_label("Color by")
+                    .selected_text(color_attribute.clone().unwrap_or("None".to_string()))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut color_attribute, None, "None");
+                        for name in &names {
+                            ui.selectable_value(&mut color_attribute, Some(name.clone()), name);
+                        }
+                    });
+                if enabled {
+                    let unit = match source {
+                        Source::Surface(_) => "line segments",
+                        _ => "triangles",
+                    };
+                    ui.label(format!("{} {}", settings.elements, unit));
+                }
+            });
+        });
+
+    // The plane widget is the gizmo of the slice plane, shown when the slice is
+    if plane_changed || (enabled && !settings.enabled) {
+        plane.enabled = true;
+        widget_state.plane = plane;
+    }
+    if enabled != settings.enabled || color_attribute != settings.color_attribute || plane_changed {
+        settings.enabled = enabled;
+        settings.color_attribute = color_attribute;
+        settings.changed = true;
+    }
+
+    if !open {
+        settings.show_panel = false;
+    }
+}
+
+/// Per-point colors of the cut
+///
+/// # Returns
+/// - Colors, `None` without a color attribute
+fn cut_colors(
+    attributes: &HashMap<(String, AttributeLocation), AttributeType>,
+    point_count: usize,
+    name: Option<&str>,
+    range: Option<(f32, f32)>,
+    color_bar_config: &ColorBarConfig,
+) -> Option<Vec<[f32; 4]>> {
+    let attribute = attributes.get(&(name?.to_string(), AttributeLocation::Point))?;
+    let (min, max) = range?;
+    let color_map = get_color_map(&color_bar_config.color_map_name);
+    let span = (max - min).max(f32::EPSILON);
+    Some(
+        (0..point_count)
+            .map(|point| {
+                let value = attribute_value(attribute, point, None);
+                color_map.get_interpolated_color(((value - min) / span).clamp(0.0, 1.0))
+            })
+            .collect(),
+    )
+}
+
+/// Replace the slice entity after a settings or plane change
+#[allow(clippy::too_many_arguments)]
+fn update_slice(
+    mut commands: Commands,
+    mut settings: ResMut<SliceSettings>,
+    widget_state: Res<WidgetState>,
+    current_model: Res<CurrentModelData>,
+    volume_slices: Res<VolumeSlices>,
+    color_bar_config: Res<ColorBarConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    models: Query<Entity, With<UserModelMesh>>,
+    slices: Query<Entity, With<SliceMesh>>,
+) {
+    if !settings.changed || widget_state.is_dragging() {
+        return;
+    }
+    let remove_slices = |commands: &mut Commands| {
+        for entity in slices.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+    };
+    if !settings.enabled {
+        settings.changed = false;
+        settings.elements = 0;
+        remove_slices(&mut commands);
+        return;
+    }
+    // A newly loaded model may not be spawned yet; retry on the next frame
+    let (Some(source), Some(model)) = (
+        Source::find(&current_model, &volume_slices),
+        models.iter().next(),
+    ) else {
+        return;
+    };
+    settings.changed = false;
+    settings.elements = 0;
+    remove_slices(&mut commands);
+
+    let plane = &widget_state.plane;
+    let Some(cut) = source.cut(plane.origin, plane.normal) else {
+        println!("No slice for the current dataset");
+        return;
+    };
+    let color_attribute = settings.color_attribute.clone();
+    let name = color_attribute.as_deref();
+    let range = name.and_then(|name| source.attribute_range(name));
+
+    let (mesh, colored) = match cut {
+        Cut::Polygons(geometry) => {
+            settings.elements = geometry.indices.len() / 3;
+            if geometry.indices.is_empty() {
+                println!("Slice is empty");
+                return;
+            }
+            let mut mesh = mesh::create_mesh_from_geometry(&geometry);
+            let colors = geometry.attributes.as_ref().and_then(|attributes| {
+                cut_colors(
+                    attributes,
+                    geometry.vertices.len(),
+                    name,
+                    range,
+                    &color_bar_config,
+                )
+            });
+            let colored = colors.is_some();
+            if let Some(colors) = colors {
+                mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+            }
+            (mesh, colored)
+        }
+        Cut::Lines(lines) => {
+            settings.elements = lines.segments.len() / 2;
+            if lines.segments.is_empty() {
+                println!("Slice is empty");
+                return;
+            }
+            let mut mesh = Mesh::new(PrimitiveTopology::LineList, RenderAssetUsages::default());
+            let colors = cut_colors(
+                &lines.attributes,
+                lines.vertices.len(),
+                name,
+                range,
+                &color_bar_config,
+            );
+            let colored = colors.is_some();
+            if let Some(colors) = colors {
+                mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+            }
+            mesh.insert_attribute(
+                Mesh::ATTRIBUTE_NORMAL,
+                vec![plane.normal.to_array(); lines.vertices.len()],
+            );
+            mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, lines.vertices);
+            mesh.insert_indices(Indices::U32(lines.segments));
+            (mesh, colored)
+        }
+    };
+    println!(
+        "Slice at {:?} with normal {:?}: {} elements",
+        plane.origin, plane.normal, settings.elements
+    );
+
+    let is_lines = mesh.primitive_topology() == PrimitiveTopology::LineList;
+    commands
+        .spawn((
+            Name::new("Slice"),
+            Mesh3d(meshes.add(mesh)),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: if colored { Color::WHITE } else { SLICE_COLOR },
+                perceptual_roughness: 0.5,
+                unlit: is_lines,
+                cull_mode: None,
+                double_sided: true,
+                // Lines lie on the surface, draw them in front of it
+                depth_bias: if is_lines { 10.0 } else { 0.0 },
+                ..default()
+            })),
+            Transform::IDENTITY,
+            SliceMesh,
+        ))
+        .set_parent(model);
+}
@@ -0,0 +1,335 @@
+//! # Point Splatting Module
+//!
+//! This module renders the points of the loaded model as Gaussian splats:
+//! - Every point becomes a screen-aligned quad with a Gaussian falloff, giving
+//!   smooth density-like visuals for particle (SPH/DEM) outputs
+//! - The splat radius is read from a point scalar attribute (times a scale factor)
+//!   or uses a default radius derived from the point density
+//! - Splats take the colors of the current color mapping
+//! - The sphere style draws opaque, shaded discs instead, for dense point clouds
+//!
+//! Splatting is enabled automatically for datasets without surface cells and can
+//! be toggled for any model via View > Point Splatting.
+
+use crate::mesh::topology::{attribute_value, scalar_attribute_names};
+use crate::mesh::{AttributeLocation, GeometryData};
+use crate::render::{create_splat_mesh, PointStyle, SplatMaterial};
+use crate::ui::{CurrentModelData, ModelLoadedEvent, UserModelMesh};
+use bevy::pbr::wireframe::NoWireframe;
+use bevy::pbr::{NotShadowCaster, NotShadowReceiver};
+use bevy::prelude::*;
+use bevy::render::mesh::VertexAttributeValues;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Splat rendering settings
+#[derive(Resource)]
+pub struct SplatSettings {
+    /// Render the model points as splats
+    pub enabled: bool,
+    /// Gaussian splats or shaded spheres
+    pub style: PointStyle,
+    /// Point scalar attribute used as splat radius (`None` = default radius)
+    pub radius_attribute: Option<String>,
+    /// Factor applied to the radius attribute values
+    pub radius_scale: f32,
+    /// Radius used without radius attribute (model units)
+    pub default_radius: f32,
+    /// Overall splat opacity
+    pub opacity: f32,
+    /// Gaussian falloff exponent
+    pub sharpness: f32,
+    /// Settings changed, the splats need to be rebuilt
+    pub changed: bool,
+}
+
+impl Default for SplatSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            style: PointStyle::Splat,
+            radius_attribute: None,
+            radius_scale: 1.0,
+            default_radius: 0.01,
+            opacity: 0.8,
+            sharpness: 3.0,
+            changed: false,
+        }
+    }
+}
+
+/// Marker component for the splat entity
+#[derive(Component)]
+pub struct SplatCloud;
+
+pub struct SplatPlugin;
+
+impl Plugin for SplatPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(MaterialPlugin::<SplatMaterial>::default())
+            .init_resource::<SplatSettings>()
+            .add_systems(
+                Update,
+                (
+                    configure_splats_on_model_load,
+                    splat_panel.after(EguiSet::InitContexts),
+                    update_splats,
+                    sync_splat_transform,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Default splat radius from the point density
+///
+/// Assumes the points fill their bounding box; the radius is about the average
+/// point spacing.
+pub fn default_splat_radius(vertices: &[[f32; 3]]) -> f32 {
+    let Some(first) = vertices.first() else {
+        return 0.01;
+    };
+    let (min, max) = vertices
+        .iter()
+        .fold((Vec3::from(*first), Vec3::from(*first)), |(min, max), v| {
+            (min.min(Vec3::from(*v)), max.max(Vec3::from(*v)))
+        });
+    let diagonal = (max - min).length();
+    if diagonal <= f32::EPSILON {
+        return 0.01;
+    }
+    diagonal / (vertices.len() as f32).cbrt()
+}
+
+/// Splat radius of every point
+fn splat_radii(geometry: &GeometryData, settings: &SplatSettings, count: usize) -> Vec<f32> {
+    let attribute = settings
+        .radius_attribute
+        .as_ref()
+        .and_then(|name| geometry.get_attributes(name, AttributeLocation::Point));
+
+    match attribute {
+        Some(attribute) => (0..count)
+            .map(|point| attribute_value(attribute, point, None).abs() * settings.radius_scale)
+            .collect(),
+        None => vec![settings.default_radius; count],
+    }
+}
+
+/// Pick splat defaults for a newly loaded model
+fn configure_splats_on_model_load(
+    mut model_loaded_events: EventReader<ModelLoadedEvent>,
+    current_model: Res<CurrentModelData>,
+    mut settings: ResMut<SplatSettings>,
+) {
+    for _ in model_loaded_events.read() {
+        let Some(ref geometry) = current_model.geometry else {
+            settings.enabled = false;
+            settings.changed = true;
+            continue;
+        };
+
+        settings.default_radius = default_splat_radius(&geometry.vertices);
+        // Prefer a point scalar that looks like a particle size
+        settings.radius_attribute = scalar_attribute_names(geometry)
+            .into_iter()
+            .filter(|(_, location)| *location == AttributeLocation::Point)
+            .map(|(name, _)| name)
+            .find(|name| {
+                let name = name.to_ascii_lowercase();
+                name.contains("radius") || name == "r" || name == "h"
+            });
+        // Particle datasets have no surface cells to show otherwise
+        settings.enabled = geometry.indices.is_empty();
+        settings.changed = true;
+
+        if settings.enabled {
+            println!(
+                "Point dataset with {} points: rendering as splats",
+                geometry.vertices.len()
+            );
+        }
+    }
+}
+
+/// Splat settings panel
+fn splat_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<SplatSettings>,
+    current_model: Res<CurrentModelData>,
+    windows: Query<&Window>,
+) {
+    if !settings.enabled || windows.iter().next().is_none() {
+        return;
+    }
+
+    let point_scalars: Vec<String> = current_model
+        .geometry
+        .as_ref()
+        .map(scalar_attribute_names)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(_, location)| *location == AttributeLocation::Point)
+        .map(|(name, _)| name)
+        .collect();
+
+    let mut style = settings.style;
+    let mut radius_attribute = settings.radius_attribute.clone();
+    let mut radius_scale = settings.radius_scale;
+    let mut default_radius = settings.default_radius;
+    let mut opacity = settings.opacity;
+    let mut sharpness = settings.sharpness;
+    let mut open = true;
+
+    egui::Window::new("Point Splatting")
+        .default_width(240.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Style:");
+                ui.radio_value(&mut style, PointStyle::Splat, "Splats");
+                ui.radio_value(&mut style, PointStyle::Sphere, "Spheres");
+            });
+            ui.separator();
+
+            ui.label("Radius attribute:");
+            egui::ComboBox::from_id_salt("splat_radius_attribute")
+                .selected_text(radius_attribute.as_deref().unwrap_or("None (constant)"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut radius_attribute, None, "None (constant)");
+                    for name in &point_scalars {
+                        ui.selectable_value(&mut radius_attribute, Some(name.clone()), name);
+                    }
+                });
+
+            if radius_attribute.is_some() {
+                ui.horizontal(|ui| {
+                    ui.label("Radius scale:");
+                    ui.add(
+                        egui::DragValue::new(&mut radius_scale)
+                            .speed(0.01)
+                            .range(0.0..=f32::MAX),
+                    );
+                });
+            } else {
+                ui.horizontal(|ui| {
+                    ui.label("Radius:");
+                    ui.add(
+                        egui::DragValue::new(&mut default_radius)
+                            .speed(0.001)
+                            .range(0.0..=f32::MAX),
+                    );
+                });
+            }
+
+            if style == PointStyle::Splat {
+                ui.separator();
+                ui.add(egui::Slider::new(&mut opacity, 0.0..=1.0).text("Opacity"));
+                ui.add(egui::Slider::new(&mut sharpness, 0.5..=10.0).text("Falloff"));
+            }
+        });
+
+    if style != settings.style
+        || radius_attribute != settings.radius_attribute
+        || radius_scale != settings.radius_scale
+        || default_radius != settings.default_radius
+        || opacity != settings.opacity
+        || sharpness != settings.sharpness
+    {
+        settings.style = style;
+        settings.radius_attribute = radius_attribute;
+        settings.radius_scale = radius_scale;
+        settings.default_radius = default_radius;
+        settings.opacity = opacity;
+        settings.sharpness = sharpness;
+        settings.changed = true;
+    }
+
+    if !open {
+        settings.enabled = false;
+        settings.changed = true;
+    }
+}
+
+/// Rebuild the splats when the settings or the model mesh changed
+#[allow(clippy::too_many_arguments)]
+fn update_splats(
+    mut commands: Commands,
+    mut settings: ResMut<SplatSettings>,
+    current_model: Res<CurrentModelData>,
+    model_query: Query<(&Transform, &Mesh3d), With<UserModelMesh>>,
+    splats: Query<Entity, With<SplatCloud>>,
+    mut mesh_events: EventReader<AssetEvent<Mesh>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<SplatMaterial>>,
+) {
+    let model = model_query.iter().next();
+
+    // Positions and colors are taken from the model mesh, so edits and color map
+    // changes are followed
+    let model_mesh_modified = mesh_events.read().any(|event| match (event, model) {
+        (AssetEvent::Modified { id }, Some((_, mesh3d))) => *id == mesh3d.id(),
+        _ => false,
+    });
+    let model_missing = current_model.geometry.is_none() && !splats.is_empty();
+
+    if !(settings.changed || (settings.enabled && model_mesh_modified) || model_missing) {
+        return;
+    }
+    settings.changed = false;
+
+    for entity in splats.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    let (Some(ref geometry), Some((transform, mesh3d))) = (&current_model.geometry, model) else {
+        return;
+    };
+    if !settings.enabled {
+        return;
+    }
+    let Some(model_mesh) = meshes.get(mesh3d.id()) else {
+        return;
+    };
+
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        model_mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return;
+    };
+    let colors = match model_mesh.attribute(Mesh::ATTRIBUTE_COLOR) {
+        Some(VertexAttributeValues::Float32x4(colors)) if colors.len() == positions.len() => {
+            colors.clone()
+        }
+        _ => vec![[1.0, 1.0, 1.0, 1.0]; positions.len()],
+    };
+    let radii = splat_radii(geometry, &settings, positions.len());
+    let splat_mesh = create_splat_mesh(positions, &radii, &colors);
+
+    commands.spawn((
+        Name::new("Point Splats"),
+        Mesh3d(meshes.add(splat_mesh)),
+        MeshMaterial3d(materials.add(SplatMaterial::new(
+            settings.opacity,
+            settings.sharpness,
+            settings.style,
+        ))),
+        *transform,
+        NoWireframe,
+        NotShadowCaster,
+        NotShadowReceiver,
+        SplatCloud,
+    ));
+}
+
+/// Keep the splats aligned with the (possibly transformed) model
+fn sync_splat_transform(
+    model_query: Query<&Transform, (With<UserModelMesh>, Without<SplatCloud>)>,
+    mut splats: Query<&mut Transform, With<SplatCloud>>,
+) {
+    let Some(model_transform) = model_query.iter().next() else {
+        return;
+    };
+    for mut transform in splats.iter_mut() {
+        *transform = *model_transform;
+    }
+}
@@ -0,0 +1,374 @@
+//! # Streaming Import Module
+//!
+//! This module keeps the viewer responsive while model files load:
+//! - Files above a size threshold are read in chunks on a background thread
+//! - Triangulated chunks are shown as preview meshes while the file is read
+//! - Other model files (`.vtu`, `.obj`, `.exo`, ...) are parsed at once on the
+//!   async compute task pool, reporting stages such as parsing the file,
+//!   reading points and triangulating cells
+//! - A progress window shows the section or stage and allows cancelling
+//! - Binary files are memory-mapped, lowering the peak memory (optional)
+//!
+//! Once the file is read, the previews are replaced by the model built by the
+//! regular import path (color mapping, LOD, session tracking). Toggle via
+//! File > Stream Large VTK Files and File > Memory-Map Binary VTK Files.
+
+use crate::localization::{fill, tr};
+use crate::mesh::progress::{self, LoadProgress};
+use crate::mesh::streaming::{supports_streaming, StreamMessage, StreamingLoad};
+use crate::mesh::{GeometryData, VtkError};
+use crate::ui::ModelLoadFailedEvent;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task};
+use bevy_egui::{egui, EguiContexts, EguiSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Preview meshes created per frame, so frames stay short
+const MAX_PREVIEWS_PER_FRAME: usize = 4;
+/// Parsing imports finishing sooner show no progress window
+const PROGRESS_WINDOW_DELAY: Duration = Duration::from_millis(250);
+
+/// Streaming import settings and the running import
+#[derive(Resource)]
+pub struct StreamingImport {
+    /// Stream large legacy VTK files instead of loading them at once
+    pub enabled: bool,
+    /// Files of at least this size (in MB) are streamed
+    pub min_file_size_mb: u64,
+    /// Memory-map binary files instead of reading them through a buffer
+    pub memory_map: bool,
+    active: Option<ActiveImport>,
+    parsing: Option<ParsingImport>,
+    /// Geometry of a finished import, picked up by the regular import path
+    finished: Option<(PathBuf, GeometryData)>,
+    /// Error of the last failed parsing import, shown until dismissed
+    failed: Option<String>,
+}
+
+impl Default for StreamingImport {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_file_size_mb: 200,
+            memory_map: true,
+            active: None,
+            parsing: None,
+            finished: None,
+            failed: None,
+        }
+    }
+}
+
+/// Import running on a background thread
+struct ActiveImport {
+    path: PathBuf,
+    load: StreamingLoad,
+    fraction: f32,
+    phase: &'static str,
+    /// Triangles shown as preview so far
+    preview_triangles: usize,
+}
+
+/// Import parsed at once on the task pool
+struct ParsingImport {
+    path: PathBuf,
+    task: Task<Result<GeometryData, VtkError>>,
+    progress: Arc<LoadProgress>,
+    started: Instant,
+}
+
+impl StreamingImport {
+    /// Whether a file is streamed instead of loaded at once
+    pub fn should_stream(&self, path: &Path) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let size = std::fs::metadata(path).map_or(0, |metadata| metadata.len());
+        size >= self.min_file_size_mb * 1024 * 1024 && supports_streaming(path)
+    }
+
+    /// Start streaming a file, cancelling a running import
+    pub fn start(&mut self, path: &Path) {
+        self.cancel();
+        println!("Streaming import of {}", path.display());
+        self.active = Some(ActiveImport {
+            path: path.to_path_buf(),
+            load: StreamingLoad::spawn(path.to_path_buf(), self.memory_map),
+            fraction: 0.0,
+            phase: "Reading header",
+            preview_triangles: 0,
+        });
+    }
+
+    /// Start parsing a model file on the task pool, cancelling a running import
+    ///
+    /// # Parameters
+    /// - `path`: Model file read by `mesh::load_geometry_file`
+    pub fn start_parsing(&mut self, path: &Path) {
+        self.cancel();
+        self.failed = None;
+        let progress = LoadProgress::new();
+        let task_progress = progress.clone();
+        let task_path = path.to_path_buf();
+        let task = AsyncComputeTaskPool::get().spawn(async move {
+            progress::with_progress(task_progress, || {
+                crate::mesh::load_geometry_file(&task_path)
+            })
+        });
+        self.parsing = Some(ParsingImport {
+            path: path.to_path_buf(),
+            task,
+            progress,
+            started: Instant::now(),
+        });
+    }
+
+    /// Cancel the running import, if any
+    ///
+    /// The result of a cancelled parsing import is dropped right away; its
+    /// loader stops at the next check between pieces.
+    pub fn cancel(&mut self) {
+        if let Some(active) = self.active.take() {
+            active.load.cancel();
+        }
+        if let Some(parsing) = self.parsing.take() {
+            println!("Cancelled import of {}", parsing.path.display());
+            parsing.progress.cancel();
+        }
+    }
+
+    /// Take the geometry of a finished import
+    pub fn take_finished(&mut self) -> Option<(PathBuf, GeometryData)> {
+        self.finished.take()
+    }
+}
+
+/// Marker component of the partial geometry shown while streaming
+#[derive(Component)]
+pub struct StreamingPreview;
+
+pub struct StreamingImportPlugin;
+
+impl Plugin for StreamingImportPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StreamingImport>().add_systems(
+            Update,
+            (
+                receive_stream_messages,
+                poll_parsing_import,
+                streaming_progress_window.after(EguiSet::InitContexts),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Show the chunks read by the loading thread and collect the finished geometry
+fn receive_stream_messages(
+    mut commands: Commands,
+    mut streaming: ResMut<StreamingImport>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut material: Local<Option<Handle<StandardMaterial>>>,
+    previews: Query<Entity, With<StreamingPreview>>,
+    mut failed_events: EventWriter<ModelLoadFailedEvent>,
+) {
+    let Some(active) = streaming.active.as_mut() else {
+        return;
+    };
+
+    let mut created = 0;
+    let mut finished = None;
+    while created < MAX_PREVIEWS_PER_FRAME {
+        let Some(message) = active.load.try_recv() else {
+            break;
+        };
+        match message {
+            StreamMessage::Progress { fraction, phase } => {
+                active.fraction = fraction;
+                active.phase = phase;
+            }
+            StreamMessage::Preview(chunk) => {
+                active.preview_triangles += chunk.indices.len() / 3;
+                let mut mesh = Mesh::new(
+                    PrimitiveTopology::TriangleList,
+                    RenderAssetUsages::default(),
+                );
+                mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, chunk.positions);
+                mesh.insert_indices(Indices::U32(chunk.indices));
+                mesh.compute_normals();
+
+                let material = material
+                    .get_or_insert_with(|| {
+                        materials.add(StandardMaterial {
+                            base_color: Color::srgb(0.7, 0.7, 0.75),
+                            cull_mode: None,
+                            ..default()
+                        })
+                    })
+                    .clone();
+                // Same placement as imported models
+                commands.spawn((
+                    Mesh3d(meshes.add(mesh)),
+                    MeshMaterial3d(material),
+                    Transform::from_translation(Vec3::new(0.0, 0.5, 0.0)),
+                    StreamingPreview,
+                ));
+                created += 1;
+            }
+            StreamMessage::Finished(result) => {
+                finished = Some(*result);
+                break;
+            }
+        }
+    }
+
+    let Some(result) = finished else {
+        return;
+    };
+    let Some(active) = streaming.active.take() else {
+        return;
+    };
+    for entity in previews.iter() {
+        commands.entity(entity).despawn();
+    }
+    match result {
+        Ok(geometry) => streaming.finished = Some((active.path, geometry)),
+        Err(e) => {
+            println!(
+                "Streaming import of {} failed: {}",
+                active.path.display(),
+                e
+            );
+            failed_events.send(ModelLoadFailedEvent {
+                path: active.path,
+                error: e.to_string(),
+            });
+        }
+    }
+}
+
+/// Collect the geometry of a finished parsing import
+fn poll_parsing_import(
+    mut streaming: ResMut<StreamingImport>,
+    mut failed_events: EventWriter<ModelLoadFailedEvent>,
+) {
+    let Some(parsing) = streaming.parsing.as_mut() else {
+        return;
+    };
+    let Some(result) = block_on(future::poll_once(&mut parsing.task)) else {
+        return;
+    };
+    let Some(parsing) = streaming.parsing.take() else {
+        return;
+    };
+
+    match result {
+        Ok(geometry) => {
+            println!(
+                "Parsed {} in {:.2?}",
+                parsing.path.display(),
+                parsing.started.elapsed()
+            );
+            streaming.finished = Some((parsing.path, geometry));
+        }
+        Err(e) => {
+            println!("load model file failed: {:?}", e);
+            streaming.failed = Some(e.to_string());
+            failed_events.send(ModelLoadFailedEvent {
+                path: parsing.path,
+                error: e.to_string(),
+            });
+        }
+    }
+}
+
+/// File name of an import, for the progress window
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map_or_else(String::new, |name| name.to_string_lossy().to_string())
+}
+
+/// Progress window of the running import, and the error of a failed one
+fn streaming_progress_window(
+    mut contexts: EguiContexts,
+    mut streaming: ResMut<StreamingImport>,
+    mut failed_events: EventWriter<ModelLoadFailedEvent>,
+    windows: Query<&Window>,
+) {
+    if windows.iter().next().is_none() {
+        return;
+    }
+
+    if let Some(ref error) = streaming.failed {
+        let mut dismiss = false;
+        egui::Window::new(tr("Error"))
+            .collapsible(false)
+            .resizable(false)
+            .show(contexts.ctx_mut(), |ui| {
+                ui.label(fill("load file failed: {}", &[error]));
+                dismiss = ui.button(tr("OK")).clicked();
+            });
+        if dismiss {
+            streaming.failed = None;
+        }
+    }
+
+    let mut cancel = false;
+    let mut cancel_parsing = false;
+    if let Some(ref active) = streaming.active {
+        egui::Window::new(tr("Loading"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -40.0))
+            .show(contexts.ctx_mut(), |ui| {
+                ui.label(format!("{}: {}", file_name(&active.path), tr(active.phase)));
+                ui.add(
+                    egui::ProgressBar::new(active.fraction)
+                        .show_percentage()
+                        .desired_width(260.0),
+                );
+                ui.label(fill("{} triangles shown", &[&active.preview_triangles]));
+                cancel = ui.button(tr("Cancel")).clicked();
+            });
+    } else if let Some(ref parsing) = streaming.parsing {
+        if parsing.started.elapsed() < PROGRESS_WINDOW_DELAY {
+            return;
+        }
+        let (fraction, stage) = parsing.progress.stage();
+        egui::Window::new(tr("Loading"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -40.0))
+            .show(contexts.ctx_mut(), |ui| {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(format!("{}: {}", file_name(&parsing.path), tr(stage)));
+                });
+                ui.add(egui::ProgressBar::new(fraction).desired_width(260.0));
+                let elapsed = format!("{:.1}", parsing.started.elapsed().as_secs_f32());
+                ui.label(fill("{} s elapsed", &[&elapsed]));
+                cancel_parsing = ui.button(tr("Cancel")).clicked();
+            });
+    }
+
+    // Streaming imports stop at the next chunk and remove their previews
+    if cancel {
+        if let Some(ref active) = streaming.active {
+            active.load.cancel();
+        }
+    }
+    if cancel_parsing {
+        if let Some(ref parsing) = streaming.parsing {
+            failed_events.send(ModelLoadFailedEvent {
+                path: parsing.path.clone(),
+                error: "Import cancelled".to_string(),
+            });
+        }
+        streaming.cancel();
+    }
+}
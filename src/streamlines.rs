@@ -0,0 +1,488 @@
+//! # Streamlines Module
+//!
+//! This module visualizes point vector attributes by streamlines:
+//! - Lines are traced with RK4 inside the volume cells of unstructured grids or
+//!   inside image data volumes (`.vti`)
+//! - Seeds are placed on the line, plane or sphere widget, which can be dragged
+//!   to move them
+//! - Lines are shown as polylines or tubes colored by the velocity magnitude
+//!   with the color map of the color bar
+//!
+//! The streamlines are a child of the model, so they follow the model transform
+//! and are removed with the model. Open the panel via Mesh > Streamlines.
+
+use crate::mesh::color_maps::get_color_map;
+use crate::mesh::streamlines::{
+    line_seeds, plane_seeds, sphere_seeds, trace_streamlines, tube_geometry, tube_speeds,
+    vector_attribute_names, Streamline, TraceDirection, TraceSettings, VectorField,
+};
+use crate::mesh::{self, GeometryData};
+use crate::ui::{ColorBarConfig, CurrentModelData, ModelLoadedEvent, UserModelMesh};
+use crate::volume_slices::VolumeSlices;
+use crate::widgets::{WidgetChangedEvent, WidgetKind, WidgetState};
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Widget the seeds are placed on
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SeedSource {
+    #[default]
+    Line,
+    Plane,
+    Sphere,
+}
+
+impl SeedSource {
+    /// Widget the seeds are placed on
+    pub fn widget(self) -> WidgetKind {
+        match self {
+            SeedSource::Line => WidgetKind::Line,
+            SeedSource::Plane => WidgetKind::Plane,
+            SeedSource::Sphere => WidgetKind::Sphere,
+        }
+    }
+
+    /// Seed points on the widget
+    pub fn seeds(self, widget_state: &WidgetState, count: usize) -> Vec<Vec3> {
+        match self {
+            SeedSource::Line => line_seeds(widget_state.line.start, widget_state.line.end, count),
+            SeedSource::Plane => plane_seeds(
+                widget_state.plane.origin,
+                widget_state.plane.normal,
+                widget_state.plane_half_size(),
+                (count as f32).sqrt().ceil() as usize,
+            ),
+            SeedSource::Sphere => sphere_seeds(
+                widget_state.sphere.center,
+                widget_state.sphere.radius,
+                count,
+            ),
+        }
+    }
+
+    /// Show the widget so the seeds can be placed
+    pub fn enable_widget(self, widget_state: &mut WidgetState) {
+        match self {
+            SeedSource::Line => widget_state.line.enabled = true,
+            SeedSource::Plane => widget_state.plane.enabled = true,
+            SeedSource::Sphere => widget_state.sphere.enabled = true,
+        }
+    }
+}
+
+/// Rendering of the streamlines
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StreamlineStyle {
+    #[default]
+    Lines,
+    Tubes,
+}
+
+/// Streamline settings
+#[derive(Resource)]
+pub struct StreamlineSettings {
+    /// Show the streamline panel
+    pub show_panel: bool,
+    /// Show the streamlines
+    pub enabled: bool,
+    /// Traced point vector attribute
+    pub attribute: Option<String>,
+    pub seed_source: SeedSource,
+    /// Number of seeds (rounded up to a square grid on the plane)
+    pub seed_count: usize,
+    pub direction: TraceDirection,
+    /// Integration step relative to the cell size
+    pub step_factor: f32,
+    /// Largest number of steps per direction
+    pub max_steps: usize,
+    pub style: StreamlineStyle,
+    /// Tube radius relative to the scene size
+    pub tube_radius_factor: f32,
+    /// Sides of the tube cross section
+    pub tube_sides: usize,
+    /// Settings changed, the streamlines need to be rebuilt
+    pub changed: bool,
+    /// A slider is being dragged, rebuild once released
+    dragging: bool,
+    /// Lines and points of the current streamlines
+    line_count: usize,
+    point_count: usize,
+}
+
+impl Default for StreamlineSettings {
+    fn default() -> Self {
+        Self {
+            show_panel: false,
+            enabled: false,
+            attribute: None,
+            seed_source: SeedSource::default(),
+            seed_count: 20,
+            direction: TraceDirection::default(),
+            step_factor: 0.25,
+            max_steps: 2000,
+            style: StreamlineStyle::default(),
+            tube_radius_factor: 0.003,
+            tube_sides: 8,
+            changed: false,
+            dragging: false,
+            line_count: 0,
+            point_count: 0,
+        }
+    }
+}
+
+/// Marker component of the streamline entity
+#[derive(Component)]
+pub struct StreamlineMesh;
+
+pub struct StreamlinePlugin;
+
+impl Plugin for StreamlinePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StreamlineSettings>().add_systems(
+            Update,
+            (
+                refresh_on_change,
+                streamline_panel.after(EguiSet::InitContexts),
+                update_streamlines,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Dataset carrying the vector field
+pub enum Source<'a> {
+    Volume(&'a mesh::volume::VolumeData),
+    Cells(&'a GeometryData),
+}
+
+impl Source<'_> {
+    /// Volume of the model, or the model geometry if it has volume cells
+    pub fn find<'a>(
+        current_model: &'a CurrentModelData,
+        volume_slices: &'a VolumeSlices,
+    ) -> Option<Source<'a>> {
+        if let Some(volume) = volume_slices.volume() {
+            return Some(Source::Volume(volume));
+        }
+        current_model
+            .geometry
+            .as_ref()
+            .filter(|geometry| {
+                geometry
+                    .volume_cells
+                    .as_ref()
+                    .is_some_and(|cells| !cells.is_empty())
+            })
+            .map(Source::Cells)
+    }
+
+    /// Names of the point vector attributes
+    pub fn attribute_names(&self) -> Vec<String> {
+        match self {
+            Source::Volume(volume) => vector_attribute_names(&volume.attributes),
+            Source::Cells(geometry) => geometry
+                .attributes
+                .as_ref()
+                .map(vector_attribute_names)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Vector field of a point vector attribute
+    pub fn field(&self, attribute: &str) -> Option<VectorField> {
+        match self {
+            Source::Volume(volume) => VectorField::from_volume(volume, attribute),
+            Source::Cells(geometry) => VectorField::from_cells(geometry, attribute),
+        }
+    }
+}
+
+/// Rebuild the streamlines for a newly loaded model or a moved seed widget
+fn refresh_on_change(
+    mut loaded_events: EventReader<ModelLoadedEvent>,
+    mut widget_changed_events: EventReader<WidgetChangedEvent>,
+    mut settings: ResMut<StreamlineSettings>,
+) {
+    let loaded = loaded_events.read().count() > 0;
+    let seed_widget = settings.seed_source.widget();
+    let seeds_moved = widget_changed_events
+        .read()
+        .any(|event| event.0 == seed_widget);
+    if loaded || seeds_moved {
+        settings.changed = settings.enabled;
+    }
+}
+
+/// Streamline panel
+fn streamline_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<StreamlineSettings>,
+    mut widget_state: ResMut<WidgetState>,
+    current_model: Res<CurrentModelData>,
+    volume_slices: Res<VolumeSlices>,
+    windows: Query<&Window>,
+) {
+    if !settings.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let source = Source::find(&current_model, &volume_slices);
+    let mut enabled = settings.enabled;
+    let mut attribute = settings.attribute.clone();
+    let mut seed_source = settings.seed_source;
+    let mut seed_count = settings.seed_count;
+    let mut direction = settings.direction;
+    let mut step_factor = settings.step_factor;
+    let mut max_steps = settings.max_steps;
+    let mut style = settings.style;
+    let mut tube_radius_factor = settings.tube_radius_factor;
+    let mut tube_sides = settings.tube_sides;
+    let mut dragging = false;
+    let mut open = true;
+
+    egui::Window::new("Streamlines")
+        .default_width(280.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            let Some(source) = source else {
+                ui.label("Load an unstructured grid with volume cells or a .vti volume.");
+                return;
+            };
+            let names = source.attribute_names();
+            if names.is_empty() {
+                ui.label("The dataset has no point vectors.");
+                return;
+            }
+            if attribute.as_ref().is_none_or(|name| !names.contains(name)) {
+                attribute = names.first().cloned();
+            }
+
+            ui.checkbox(&mut enabled, "Show streamlines");
+            ui.separator();
+            egui::ComboBox::from_label("Vectors")
+                .selected_text(attribute.clone().unwrap_or_default())
+                .show_ui(ui, |ui| {
+                    for name in &names {
+                        ui.selectable_value(&mut attribute, Some(name.clone()), name);
+                    }
+                });
+
+            ui.label("Seeds:");
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut seed_source, SeedSource::Line, "Line");
+                ui.radio_value(&mut seed_source, SeedSource::Plane, "Plane");
+                ui.radio_value(&mut seed_source, SeedSource::Sphere, "Sphere");
+            });
+            let response = ui.add(egui::Slider::new(&mut seed_count, 1..=400).text("Seed count"));
+            dragging |= response.dragged();
+
+            ui.label("Direction:");
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut direction, TraceDirection::Forward, "Forward");
+                ui.radio_value(&mut direction, TraceDirection::Backward, "Backward");
+                ui.radio_value(&mut direction, TraceDirection::Both, "Both");
+            });
+            let response = ui.add(
+                egui::Slider::new(&mut step_factor, 0.05..=2.0)
+                    .logarithmic(true)
+                    .text("Step (cell sizes)"),
+            );
+            dragging |= response.dragged();
+            let response = ui.add(
+                egui::Slider::new(&mut max_steps, 10..=10000)
+                    .logarithmic(true)
+                    .text("Max steps"),
+            );
+            dragging |= response.dragged();
+
+            ui.label("Style:");
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut style, StreamlineStyle::Lines, "Lines");
+                ui.radio_value(&mut style, StreamlineStyle::Tubes, "Tubes");
+            });
+            if style == StreamlineStyle::Tubes {
+                let response = ui.add(
+                    egui::Slider::new(&mut tube_radius_factor, 0.0005..=0.05)
+                        .logarithmic(true)
+                        .text("Tube radius"),
+                );
+                dragging |= response.dragged();
+                let response =
+                    ui.add(egui::Slider::new(&mut tube_sides, 3..=32).text("Tube sides"));
+                dragging |= response.dragged();
+            }
+
+            if enabled {
+                ui.separator();
+                ui.label(format!(
+                    "{} streamlines, {} points",
+                    settings.line_count, settings.point_count
+                ));
+            }
+        });
+
+    // Show the seed widget when the streamlines are turned on or the source changes
+    if enabled && (!settings.enabled || seed_source != settings.seed_source) {
+        seed_source.enable_widget(&mut widget_state);
+    }
+    if enabled != settings.enabled
+        || attribute != settings.attribute
+        || seed_source != settings.seed_source
+        || seed_count != settings.seed_count
+        || direction != settings.direction
+        || step_factor != settings.step_factor
+        || max_steps != settings.max_steps
+        || style != settings.style
+        || tube_radius_factor != settings.tube_radius_factor
+        || tube_sides != settings.tube_sides
+    {
+        settings.enabled = enabled;
+        settings.attribute = attribute;
+        settings.seed_source = seed_source;
+        settings.seed_count = seed_count;
+        settings.direction = direction;
+        settings.step_factor = step_factor;
+        settings.max_steps = max_steps;
+        settings.style = style;
+        settings.tube_radius_factor = tube_radius_factor;
+        settings.tube_sides = tube_sides;
+        settings.changed = true;
+    }
+    if dragging != settings.dragging {
+        settings.dragging = dragging;
+    }
+
+    if !open {
+        settings.show_panel = false;
+    }
+}
+
+/// Polyline mesh of streamlines with speeds per vertex
+pub fn line_mesh(lines: &[Streamline]) -> (Mesh, Vec<f32>) {
+    let mut positions = Vec::new();
+    let mut speeds = Vec::new();
+    let mut indices = Vec::new();
+    for line in lines {
+        let start = positions.len() as u32;
+        positions.extend(line.points.iter().map(|point| point.to_array()));
+        speeds.extend_from_slice(&line.speeds);
+        for i in 0..line.points.len() as u32 - 1 {
+            indices.extend_from_slice(&[start + i, start + i + 1]);
+        }
+    }
+    let mut mesh = Mesh::new(PrimitiveTopology::LineList, RenderAssetUsages::default());
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_NORMAL,
+        vec![[0.0, 1.0, 0.0]; positions.len()],
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_indices(Indices::U32(indices));
+    (mesh, speeds)
+}
+
+/// Replace the streamline entity after a settings or seed change
+#[allow(clippy::too_many_arguments)]
+fn update_streamlines(
+    mut commands: Commands,
+    mut settings: ResMut<StreamlineSettings>,
+    widget_state: Res<WidgetState>,
+    current_model: Res<CurrentModelData>,
+    volume_slices: Res<VolumeSlices>,
+    color_bar_config: Res<ColorBarConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    models: Query<Entity, With<UserModelMesh>>,
+    streamlines: Query<Entity, With<StreamlineMesh>>,
+) {
+    if !settings.changed || settings.dragging || widget_state.is_dragging() {
+        return;
+    }
+    let remove_streamlines = |commands: &mut Commands| {
+        for entity in streamlines.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+    };
+    if !settings.enabled {
+        settings.changed = false;
+        settings.line_count = 0;
+        settings.point_count = 0;
+        remove_streamlines(&mut commands);
+        return;
+    }
+    // A newly loaded model may not be spawned yet; retry on the next frame
+    let (Some(source), Some(attribute), Some(model)) = (
+        Source::find(&current_model, &volume_slices),
+        settings.attribute.clone(),
+        models.iter().next(),
+    ) else {
+        return;
+    };
+    settings.changed = false;
+    settings.line_count = 0;
+    settings.point_count = 0;
+    remove_streamlines(&mut commands);
+
+    let Some(field) = source.field(&attribute) else {
+        println!("No vector field '{}' to trace", attribute);
+        return;
+    };
+    let seeds = settings
+        .seed_source
+        .seeds(&widget_state, settings.seed_count);
+    let trace_settings = TraceSettings {
+        step: field.cell_size() * settings.step_factor,
+        max_steps: settings.max_steps,
+        direction: settings.direction,
+    };
+    let lines = trace_streamlines(&field, &seeds, &trace_settings);
+    settings.line_count = lines.len();
+    settings.point_count = lines.iter().map(|line| line.points.len()).sum();
+    println!(
+        "Traced {} streamlines of '{}' from {} seeds ({} points)",
+        settings.line_count,
+        attribute,
+        seeds.len(),
+        settings.point_count
+    );
+    if lines.is_empty() {
+        return;
+    }
+
+    let (mut mesh, speeds) = match settings.style {
+        StreamlineStyle::Lines => line_mesh(&lines),
+        StreamlineStyle::Tubes => {
+            let radius = widget_state.scale * settings.tube_radius_factor;
+            let tubes = tube_geometry(&lines, radius, settings.tube_sides);
+            (mesh::create_mesh_from_geometry(&tubes), tube_speeds(&tubes))
+        }
+    };
+    let (min, max) = field.magnitude_range().unwrap_or((0.0, 1.0));
+    let color_map = get_color_map(&color_bar_config.color_map_name);
+    let span = (max - min).max(f32::EPSILON);
+    let colors: Vec<[f32; 4]> = speeds
+        .iter()
+        .map(|speed| color_map.get_interpolated_color(((speed - min) / span).clamp(0.0, 1.0)))
+        .collect();
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+
+    let is_lines = settings.style == StreamlineStyle::Lines;
+    commands
+        .spawn((
+            Name::new("Streamlines"),
+            Mesh3d(meshes.add(mesh)),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: Color::WHITE,
+                perceptual_roughness: 0.5,
+                unlit: is_lines,
+                cull_mode: None,
+                ..default()
+            })),
+            Transform::IDENTITY,
+            StreamlineMesh,
+        ))
+        .set_parent(model);
+}
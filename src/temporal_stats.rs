@@ -0,0 +1,212 @@
+//! # Temporal Statistics Module
+//!
+//! This module summarizes a loaded time series per point:
+//! - Mean, minimum, maximum and standard deviation over all frames are stored
+//!   as point scalars of the model, shown and filtered like any loaded attribute
+//! - The global range over all points and frames is reported and can be set on
+//!   the color bar, so every frame of the animation uses the same color scale
+//!
+//! Open the panel via Mesh > Temporal Statistics.
+
+use crate::animation::TimeSeriesAsset;
+use crate::mesh::temporal::{add_statistics_attributes, temporal_statistics};
+use crate::ui::color_bar::apply_custom_color_mapping;
+use crate::ui::{ColorBarConfig, CurrentModelData, UserModelMesh};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Temporal statistics settings
+#[derive(Resource)]
+pub struct TemporalStatsSettings {
+    /// Show the statistics panel
+    pub show_panel: bool,
+    /// Prefix of the attribute names
+    pub prefix: String,
+    /// Computation requested in the panel
+    compute: bool,
+    /// Setting the global range on the color bar requested in the panel
+    apply_range: bool,
+    /// Global range of the last computation
+    range: Option<(f32, f32)>,
+    /// Outcome of the last computation, `Err` holds the error message
+    status: Option<Result<String, String>>,
+}
+
+impl Default for TemporalStatsSettings {
+    fn default() -> Self {
+        Self {
+            show_panel: false,
+            prefix: "temporal".to_string(),
+            compute: false,
+            apply_range: false,
+            range: None,
+            status: None,
+        }
+    }
+}
+
+pub struct TemporalStatsPlugin;
+
+impl Plugin for TemporalStatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TemporalStatsSettings>().add_systems(
+            Update,
+            (
+                temporal_stats_panel.after(EguiSet::InitContexts),
+                compute_temporal_stats,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Temporal statistics panel
+fn temporal_stats_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<TemporalStatsSettings>,
+    time_series: Res<TimeSeriesAsset>,
+    windows: Query<&Window>,
+) {
+    if !settings.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let mut prefix = settings.prefix.clone();
+    let mut compute = false;
+    let mut apply_range = false;
+    let mut open = true;
+
+    egui::Window::new("Temporal Statistics")
+        .default_width(280.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            if !time_series.is_step2_complete || time_series.time_steps.is_empty() {
+                ui.label("Load a time series to summarize.");
+                return;
+            }
+            let frames = time_series.get_total_time_steps();
+            let points = time_series.time_steps[0].scalars.len();
+            ui.label(format!("{} frames, {} points", frames, points));
+
+            ui.horizontal(|ui| {
+                ui.label("Prefix:");
+                ui.text_edit_singleline(&mut prefix);
+            });
+            ui.label(format!(
+                "Adds {0}_mean, {0}_min, {0}_max and {0}_stddev",
+                prefix.trim()
+            ));
+            if ui
+                .add_enabled(!prefix.trim().is_empty(), egui::Button::new("Compute"))
+                .clicked()
+            {
+                compute = true;
+            }
+
+            match &settings.status {
+                Some(Ok(message)) => {
+                    ui.colored_label(egui::Color32::LIGHT_GREEN, message);
+                }
+                Some(Err(message)) => {
+                    ui.colored_label(egui::Color32::LIGHT_RED, message);
+                }
+                None => {}
+            }
+
+            if let Some((min, max)) = settings.range {
+                ui.separator();
+                ui.label(format!("Global range: {} to {}", min, max));
+                if ui
+                    .button("Use Global Range for Color Bar")
+                    .on_hover_text("Color every frame on the same scale")
+                    .clicked()
+                {
+                    apply_range = true;
+                }
+            }
+        });
+
+    if prefix != settings.prefix {
+        settings.prefix = prefix;
+    }
+    if compute {
+        settings.compute = true;
+    }
+    if apply_range {
+        settings.apply_range = true;
+    }
+
+    if !open {
+        settings.show_panel = false;
+    }
+}
+
+/// Compute the statistics into the model geometry and apply the global range
+fn compute_temporal_stats(
+    mut settings: ResMut<TemporalStatsSettings>,
+    time_series: Res<TimeSeriesAsset>,
+    mut current_model: ResMut<CurrentModelData>,
+    mut color_bar_config: ResMut<ColorBarConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    model_entities: Query<&Mesh3d, With<UserModelMesh>>,
+) {
+    if settings.apply_range {
+        settings.apply_range = false;
+        if let Some((min, max)) = settings.range {
+            color_bar_config.min_value = min;
+            color_bar_config.max_value = max;
+            color_bar_config.has_changed = true;
+            println!("Color bar range set to the global range {} to {}", min, max);
+        }
+    }
+
+    if !settings.compute {
+        return;
+    }
+    settings.compute = false;
+    let Some(geometry) = current_model.geometry.as_mut() else {
+        return;
+    };
+
+    let result = temporal_statistics(
+        time_series
+            .time_steps
+            .iter()
+            .map(|step| step.scalars.as_slice()),
+    )
+    .and_then(|statistics| {
+        let names = add_statistics_attributes(geometry, &statistics, settings.prefix.trim())?;
+        Ok((statistics, names))
+    });
+    settings.status = Some(match result {
+        Ok((statistics, names)) => {
+            println!(
+                "Temporal statistics over {} frames: {}, global range {:?}",
+                statistics.frames,
+                names.join(", "),
+                statistics.range
+            );
+            settings.range = statistics.range;
+            Ok(format!(
+                "Created {} over {} frames",
+                names.join(", "),
+                statistics.frames
+            ))
+        }
+        Err(e) => {
+            println!("Temporal statistics failed: {}", e);
+            Err(e.to_string())
+        }
+    });
+
+    // Refresh the colors, a new attribute may be the one shown
+    if let Some(mesh) = model_entities
+        .get_single()
+        .ok()
+        .and_then(|mesh3d| meshes.get_mut(&mesh3d.0))
+    {
+        if let Err(e) = apply_custom_color_mapping(geometry, mesh, &color_bar_config) {
+            println!("Failed to apply color mapping after statistics: {:?}", e);
+        }
+    }
+}
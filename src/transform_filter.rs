@@ -0,0 +1,244 @@
+//! # Transform Filter Module
+//!
+//! This module applies transforms to the geometry itself instead of the entity:
+//! - Translation, rotation (degrees about X, Y and Z) and scale entered in the
+//!   panel are applied to the vertices
+//! - The current model transform (Alt + mouse drag) can be baked into the
+//!   vertices, after which the entity transform is reset
+//! - Vector attributes and normals are rotated and scaled along, so exports and
+//!   filters work in the transformed frame
+//!
+//! Open the panel via Mesh > Transform.
+
+use crate::lod::LODManager;
+use crate::mesh;
+use crate::mesh::transform::transform_geometry;
+use crate::model_transform::ModelCenter;
+use crate::ui::color_bar::apply_custom_color_mapping;
+use crate::ui::{ColorBarConfig, CurrentModelData, UserModelMesh};
+use crate::volume_slices::VolumeSlices;
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Transform applied by the panel
+#[derive(Clone, Copy, PartialEq)]
+enum TransformRequest {
+    /// Transform entered in the panel
+    Entered,
+    /// Current transform of the model entity
+    Model,
+}
+
+/// Transform filter settings
+#[derive(Resource)]
+pub struct TransformFilterSettings {
+    /// Show the transform panel
+    pub show_panel: bool,
+    /// Translation along X, Y and Z
+    pub translation: [f32; 3],
+    /// Rotation about X, Y and Z in degrees, applied in that order
+    pub rotation: [f32; 3],
+    /// Scale along X, Y and Z
+    pub scale: [f32; 3],
+    /// Transform requested in the panel
+    apply: Option<TransformRequest>,
+}
+
+impl Default for TransformFilterSettings {
+    fn default() -> Self {
+        Self {
+            show_panel: false,
+            translation: [0.0; 3],
+            rotation: [0.0; 3],
+            scale: [1.0; 3],
+            apply: None,
+        }
+    }
+}
+
+impl TransformFilterSettings {
+    /// Transform entered in the panel
+    fn transform(&self) -> Transform {
+        let [x, y, z] = self.rotation.map(f32::to_radians);
+        Transform {
+            translation: Vec3::from(self.translation),
+            rotation: Quat::from_euler(EulerRot::ZYX, z, y, x),
+            scale: Vec3::from(self.scale),
+        }
+    }
+}
+
+pub struct TransformFilterPlugin;
+
+impl Plugin for TransformFilterPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TransformFilterSettings>().add_systems(
+            Update,
+            (
+                transform_panel.after(EguiSet::InitContexts),
+                apply_transform,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Transform panel
+fn transform_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<TransformFilterSettings>,
+    current_model: Res<CurrentModelData>,
+    models: Query<&Transform, With<UserModelMesh>>,
+    windows: Query<&Window>,
+) {
+    if !settings.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let mut translation = settings.translation;
+    let mut rotation = settings.rotation;
+    let mut scale = settings.scale;
+    let mut apply = None;
+    let mut open = true;
+
+    egui::Window::new("Transform")
+        .default_width(300.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            if current_model.geometry.is_none() {
+                ui.label("Load a model to transform.");
+                return;
+            }
+
+            egui::Grid::new("transform_filter")
+                .num_columns(4)
+                .show(ui, |ui| {
+                    ui.label("");
+                    for axis in ["X", "Y", "Z"] {
+                        ui.strong(axis);
+                    }
+                    ui.end_row();
+                    ui.label("Translate:");
+                    for value in translation.iter_mut() {
+                        ui.add(egui::DragValue::new(value).speed(0.01));
+                    }
+                    ui.end_row();
+                    ui.label("Rotate (°):");
+                    for value in rotation.iter_mut() {
+                        ui.add(egui::DragValue::new(value).speed(1.0).range(-360.0..=360.0));
+                    }
+                    ui.end_row();
+                    ui.label("Scale:");
+                    for value in scale.iter_mut() {
+                        ui.add(egui::DragValue::new(value).speed(0.01));
+                    }
+                    ui.end_row();
+                });
+
+            ui.horizontal(|ui| {
+                if ui.button("Apply to Geometry").clicked() {
+                    apply = Some(TransformRequest::Entered);
+                }
+                if ui.button("Reset").clicked() {
+                    translation = [0.0; 3];
+                    rotation = [0.0; 3];
+                    scale = [1.0; 3];
+                }
+            });
+
+            ui.separator();
+            let model_transform = models.get_single().copied().unwrap_or_default();
+            let [x, y, z] = model_transform.translation.to_array();
+            ui.label(format!("Model translation: {:.3}, {:.3}, {:.3}", x, y, z));
+            let is_identity = model_transform == Transform::IDENTITY;
+            if ui
+                .add_enabled(!is_identity, egui::Button::new("Bake Model Transform"))
+                .on_hover_text("Move the entity transform into the vertices")
+                .clicked()
+            {
+                apply = Some(TransformRequest::Model);
+            }
+        });
+
+    if translation != settings.translation
+        || rotation != settings.rotation
+        || scale != settings.scale
+    {
+        settings.translation = translation;
+        settings.rotation = rotation;
+        settings.scale = scale;
+    }
+    if apply.is_some() {
+        settings.apply = apply;
+    }
+
+    if !open {
+        settings.show_panel = false;
+    }
+}
+
+/// Transform the vertices and rebuild the model mesh
+#[allow(clippy::too_many_arguments)]
+fn apply_transform(
+    mut settings: ResMut<TransformFilterSettings>,
+    mut current_model: ResMut<CurrentModelData>,
+    mut model_center: ResMut<ModelCenter>,
+    mut volume_slices: ResMut<VolumeSlices>,
+    color_bar_config: Res<ColorBarConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut model_entities: Query<
+        (&mut Mesh3d, &mut Transform, Option<&mut LODManager>),
+        With<UserModelMesh>,
+    >,
+) {
+    let Some(request) = settings.apply else {
+        return;
+    };
+    settings.apply = None;
+    let (Some(geometry), Ok((mut mesh3d, mut entity_transform, lod_manager))) = (
+        current_model.geometry.as_mut(),
+        model_entities.get_single_mut(),
+    ) else {
+        return;
+    };
+
+    let transform = match request {
+        TransformRequest::Entered => settings.transform(),
+        TransformRequest::Model => std::mem::replace(&mut *entity_transform, Transform::IDENTITY),
+    };
+    transform_geometry(geometry, transform.compute_matrix());
+    println!(
+        "Transformed {} vertices by translation {:?}, rotation {:?}, scale {:?}",
+        geometry.vertices.len(),
+        transform.translation,
+        transform.rotation,
+        transform.scale
+    );
+    // The transformed geometry is no longer a slice of the volume
+    if volume_slices.volume().is_some() {
+        volume_slices.clear();
+    }
+
+    let (min, max) = geometry.vertices.iter().fold(
+        (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+        |(min, max), v| (min.min(Vec3::from(*v)), max.max(Vec3::from(*v))),
+    );
+    if min.cmple(max).all() {
+        model_center.center = (min + max) / 2.0;
+    }
+
+    let mut new_mesh = mesh::create_mesh_from_geometry(geometry);
+    if let Err(e) = apply_custom_color_mapping(geometry, &mut new_mesh, &color_bar_config) {
+        println!(
+            "Failed to apply color mapping to transformed model: {:?}",
+            e
+        );
+    }
+    // Levels of the untransformed model must not replace the new mesh
+    if let Some(mut lod_manager) = lod_manager {
+        if let Err(e) = lod_manager.rebuild(geometry.clone(), &mut meshes) {
+            println!("Failed to rebuild LOD levels of transformed model: {:?}", e);
+        }
+    }
+    *mesh3d = Mesh3d(meshes.add(new_mesh));
+}
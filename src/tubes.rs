@@ -0,0 +1,223 @@
+//! # Tubes Module
+//!
+//! This module renders the lines of PolyData and unstructured grids as tubes:
+//! - A circular cross-section is swept along every polyline, so 1D data is
+//!   visible with shading
+//! - The panel sets the tube radius (relative to the model size) and the number
+//!   of sides of the cross-section
+//! - Tubes are colored with the color bar attribute and color map at the time
+//!   they are built
+//!
+//! The tubes are a child of the model, so they follow the model transform and
+//! are removed with the model. Open the panel via Mesh > Tubes.
+
+use crate::mesh;
+use crate::mesh::tubes::tube_geometry;
+use crate::ui::color_bar::apply_custom_color_mapping;
+use crate::ui::{ColorBarConfig, CurrentModelData, ModelLoadedEvent, UserModelMesh};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Tube settings
+#[derive(Resource)]
+pub struct TubeSettings {
+    /// Show the tube panel
+    pub show_panel: bool,
+    /// Show the tubes
+    pub enabled: bool,
+    /// Tube radius relative to the model size
+    pub radius_factor: f32,
+    /// Sides of the tube cross-section
+    pub sides: usize,
+    /// Settings changed, the tubes need to be rebuilt
+    pub changed: bool,
+    /// A slider is being dragged, rebuild once released
+    dragging: bool,
+    /// Triangles of the current tubes
+    triangles: usize,
+}
+
+impl Default for TubeSettings {
+    fn default() -> Self {
+        Self {
+            show_panel: false,
+            enabled: false,
+            radius_factor: 0.005,
+            sides: 8,
+            changed: false,
+            dragging: false,
+            triangles: 0,
+        }
+    }
+}
+
+/// Marker component of the tube entity
+#[derive(Component)]
+pub struct LineTubes;
+
+pub struct TubePlugin;
+
+impl Plugin for TubePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TubeSettings>().add_systems(
+            Update,
+            (
+                refresh_on_model_load,
+                tube_panel.after(EguiSet::InitContexts),
+                update_tubes,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Rebuild the tubes for a newly loaded model
+fn refresh_on_model_load(
+    mut loaded_events: EventReader<ModelLoadedEvent>,
+    mut settings: ResMut<TubeSettings>,
+) {
+    if loaded_events.read().count() > 0 {
+        settings.changed = settings.enabled;
+    }
+}
+
+/// Tube panel
+fn tube_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<TubeSettings>,
+    current_model: Res<CurrentModelData>,
+    windows: Query<&Window>,
+) {
+    if !settings.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let mut enabled = settings.enabled;
+    let mut radius_factor = settings.radius_factor;
+    let mut sides = settings.sides;
+    let mut dragging = false;
+    let mut open = true;
+
+    egui::Window::new("Tubes")
+        .default_width(280.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            let Some(lines) = current_model
+                .geometry
+                .as_ref()
+                .and_then(|geometry| geometry.lines.as_ref())
+            else {
+                ui.label("Load a model with lines or poly lines.");
+                return;
+            };
+            let points: usize = lines.iter().map(|line| line.points.len()).sum();
+            ui.label(format!("{} lines, {} points", lines.len(), points));
+
+            ui.checkbox(&mut enabled, "Show tubes");
+            ui.separator();
+            let response = ui.add(
+                egui::Slider::new(&mut radius_factor, 0.0005..=0.05)
+                    .logarithmic(true)
+                    .text("Radius"),
+            );
+            dragging |= response.dragged();
+            let response = ui.add(egui::Slider::new(&mut sides, 3..=32).text("Sides"));
+            dragging |= response.dragged();
+            if enabled {
+                ui.label(format!("{} triangles", settings.triangles));
+            }
+        });
+
+    if enabled != settings.enabled
+        || radius_factor != settings.radius_factor
+        || sides != settings.sides
+    {
+        settings.enabled = enabled;
+        settings.radius_factor = radius_factor;
+        settings.sides = sides;
+        settings.changed = true;
+    }
+    if dragging != settings.dragging {
+        settings.dragging = dragging;
+    }
+
+    if !open {
+        settings.show_panel = false;
+    }
+}
+
+/// Replace or remove the tube entity
+#[allow(clippy::too_many_arguments)]
+fn update_tubes(
+    mut commands: Commands,
+    mut settings: ResMut<TubeSettings>,
+    current_model: Res<CurrentModelData>,
+    color_bar_config: Res<ColorBarConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    models: Query<Entity, With<UserModelMesh>>,
+    tubes: Query<Entity, With<LineTubes>>,
+) {
+    if !settings.changed || settings.dragging {
+        return;
+    }
+    let remove_tubes = |commands: &mut Commands| {
+        for entity in tubes.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+    };
+    if !settings.enabled {
+        settings.changed = false;
+        settings.triangles = 0;
+        remove_tubes(&mut commands);
+        return;
+    }
+    // A newly loaded model may not be spawned yet; retry on the next frame
+    let (Some(geometry), Some(model)) = (current_model.geometry.as_ref(), models.iter().next())
+    else {
+        return;
+    };
+    settings.changed = false;
+    settings.triangles = 0;
+    remove_tubes(&mut commands);
+
+    let (min, max) = geometry.vertices.iter().fold(
+        (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+        |(min, max), v| (min.min(Vec3::from(*v)), max.max(Vec3::from(*v))),
+    );
+    let size = if min.cmple(max).all() {
+        (max - min).length().max(f32::EPSILON)
+    } else {
+        1.0
+    };
+    let radius = size * settings.radius_factor;
+    let Some(tubes) = tube_geometry(geometry, radius, settings.sides) else {
+        println!("No lines to build tubes around");
+        return;
+    };
+    settings.triangles = tubes.indices.len() / 3;
+    println!(
+        "Tubes of radius {} with {} sides: {} triangles",
+        radius, settings.sides, settings.triangles
+    );
+
+    let mut mesh = mesh::create_mesh_from_geometry(&tubes);
+    if let Err(e) = apply_custom_color_mapping(&tubes, &mut mesh, &color_bar_config) {
+        println!("Failed to apply color mapping to tubes: {:?}", e);
+    }
+    commands
+        .spawn((
+            Name::new("Tubes"),
+            Mesh3d(meshes.add(mesh)),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: Color::WHITE,
+                perceptual_roughness: 0.5,
+                cull_mode: None,
+                double_sided: true,
+                ..default()
+            })),
+            Transform::IDENTITY,
+            LineTubes,
+        ))
+        .set_parent(model);
+}
@@ -1,19 +1,37 @@
 pub mod color_bar;
 pub mod events;
 use crate::animation::TimeSeriesEvent;
+use crate::localization::{fill, tr};
 use crate::mesh;
-use crate::mesh::vtk::VtkMeshExtractor;
+use crate::picking::InteractionMode;
+use crate::preferences::Action;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
+use bevy::tasks::{block_on, futures_lite::future, IoTaskPool, Task};
 use bevy_egui::*;
 pub use color_bar::ColorBarConfig;
-use rfd::FileDialog;
-use std::path::PathBuf;
-use vtkio;
+use rfd::AsyncFileDialog;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+/// Extensions of the model files `load_resource` can import
+const MODEL_EXTENSIONS: &[&str] = &[
+    "obj", "ply", "glb", "gltf", "vtk", "vtu", "vtp", "vts", "vtr", "vti", "pvtu", "pvtp", "e",
+    "exo", "ex2", "cgns", "nc", "nc3", "cdf", "xdmf", "xmf", "bdf", "nas", "csv",
+];
 
 /// Marker component to identify imported models
 #[derive(Component)]
 pub struct UserModelMesh;
 
+/// System set of the menu bar, panels docked below it run after it
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MenuBarSet;
+
+/// Marker component for generated wave surfaces
+#[derive(Component)]
+pub struct WaveSurface;
+
 #[derive(Event)]
 pub struct ModelLoadedEvent {
     pub position: Vec3,
@@ -22,12 +40,141 @@ pub struct ModelLoadedEvent {
     pub bounds_max: Option<Vec3>, // Maximum point of model bounding box
 }
 
+/// Loading a model file failed or was cancelled
+#[derive(Event)]
+pub struct ModelLoadFailedEvent {
+    pub path: PathBuf,
+    pub error: String,
+}
+
+/// Model load requests and the failures reported for them
+#[derive(SystemParam)]
+struct ModelLoadRequests<'w, 's> {
+    requests: EventReader<'w, 's, events::LoadModelEvent>,
+    failures: EventWriter<'w, ModelLoadFailedEvent>,
+}
+
+impl ModelLoadRequests<'_, '_> {
+    /// Report a failed load
+    fn fail(&mut self, path: &Path, error: impl std::fmt::Display) {
+        self.failures.send(ModelLoadFailedEvent {
+            path: path.to_path_buf(),
+            error: error.to_string(),
+        });
+    }
+}
+
 // Store current model's geometry data
 #[derive(Resource, Default)]
 pub struct CurrentModelData {
     pub geometry: Option<mesh::GeometryData>,
 }
 
+/// Import options
+#[derive(Resource, Default)]
+pub struct ImportSettings {
+    /// Place the longitude/latitude grid of netCDF climate files on a sphere
+    /// instead of a plane
+    pub on_sphere: bool,
+    /// Merge duplicate points after extraction (many exports repeat the points
+    /// of every cell)
+    pub weld_points: bool,
+    /// Maximum distance of merged points (0 = identical coordinates)
+    pub weld_tolerance: f32,
+}
+
+/// Result of a file dialog, applied on the main thread once the dialog closes
+pub enum DialogSelection {
+    /// Model file, loaded with a `LoadModelEvent`
+    Model(PathBuf),
+    /// Time series folder, collection or multi-step file, with the load event of the
+    /// files found in it
+    TimeSeries {
+        source: PathBuf,
+        event: TimeSeriesEvent,
+    },
+    /// Color map registered from a ParaView preset file, shown by the color bar
+    ColorMap(String),
+    /// Session of an opened project file, restored like a recovered session
+    Project(Box<crate::session::SessionSnapshot>),
+}
+
+/// File dialogs, open on the IO task pool
+///
+/// Every dialog is its own task, so several can be open at once. Export dialogs
+/// write their file in the task; dialogs picking a file to load resolve to a
+/// `DialogSelection`, applied once the dialog closes.
+#[derive(Resource, Default)]
+pub struct FileDialogs {
+    tasks: Vec<Task<Option<DialogSelection>>>,
+}
+
+impl FileDialogs {
+    /// Open a dialog on the IO task pool
+    ///
+    /// # Parameters
+    /// - `dialog`: The dialog, resolving to the picked file or `None` when cancelled
+    pub fn open(&mut self, dialog: impl Future<Output = Option<DialogSelection>> + Send + 'static) {
+        self.tasks.push(IoTaskPool::get().spawn(dialog));
+    }
+}
+
+/// Resources of interactive tools controlled from the menu bar
+#[derive(SystemParam)]
+struct ToolResources<'w, 's> {
+    widget_state: ResMut<'w, crate::widgets::WidgetState>,
+    interaction_mode: ResMut<'w, InteractionMode>,
+    gltf_import: ResMut<'w, crate::gltf_scene::GltfImportState>,
+    splat_settings: ResMut<'w, crate::splatting::SplatSettings>,
+    point_downsampling: ResMut<'w, crate::downsampling::PointDownsampling>,
+    heightmap_settings: ResMut<'w, crate::heightmap::HeightmapSettings>,
+    periodic_settings: ResMut<'w, crate::periodic::PeriodicSettings>,
+    volume_slices: ResMut<'w, crate::volume_slices::VolumeSlices>,
+    label_settings: ResMut<'w, crate::labels::LabelSettings>,
+    overlay_settings: ResMut<'w, crate::render::OverlaySettings>,
+    render_settings: ResMut<'w, crate::render::RenderSettings>,
+    clipping_settings: ResMut<'w, crate::clipping::ClippingSettings>,
+    performance_hud: ResMut<'w, crate::performance::PerformanceHud>,
+    import_settings: ResMut<'w, ImportSettings>,
+    file_dialogs: ResMut<'w, FileDialogs>,
+    scene_export: ResMut<'w, crate::scene_export::SceneExport>,
+    screenshot_settings: ResMut<'w, crate::screenshot::ScreenshotSettings>,
+    project: ResMut<'w, crate::project::ProjectState>,
+    recent_files: ResMut<'w, crate::recent::RecentFiles>,
+    streaming_import: ResMut<'w, crate::streaming::StreamingImport>,
+    isosurface: ResMut<'w, crate::isosurface::IsosurfaceSettings>,
+    slice: ResMut<'w, crate::slice_plane::SliceSettings>,
+    clip_filter: ResMut<'w, crate::clip_filter::ClipFilterSettings>,
+    streamlines: ResMut<'w, crate::streamlines::StreamlineSettings>,
+    glyphs: ResMut<'w, crate::glyphs::GlyphPanelSettings>,
+    warp: ResMut<'w, crate::warp::WarpSettings>,
+    calculator: ResMut<'w, crate::calculator::CalculatorSettings>,
+    data_conversion: ResMut<'w, crate::data_conversion::DataConversionSettings>,
+    connectivity: ResMut<'w, crate::connectivity::ConnectivitySettings>,
+    integrate: ResMut<'w, crate::integrate::IntegrateSettings>,
+    quality: ResMut<'w, crate::quality::MeshQualitySettings>,
+    delaunay: ResMut<'w, crate::delaunay::DelaunaySettings>,
+    resample: ResMut<'w, crate::resample::ResampleSettings>,
+    cell_types: ResMut<'w, crate::cell_types::CellTypeSettings>,
+    transform: ResMut<'w, crate::transform_filter::TransformFilterSettings>,
+    tubes: ResMut<'w, crate::tubes::TubeSettings>,
+    extrude: ResMut<'w, crate::extrude::ExtrudeSettings>,
+    outline: ResMut<'w, crate::outline::OutlineSettings>,
+    temporal_stats: ResMut<'w, crate::temporal_stats::TemporalStatsSettings>,
+    pathlines: ResMut<'w, crate::pathlines::PathlineSettings>,
+    derivatives: ResMut<'w, crate::derivatives::DerivativeSettings>,
+    hover: ResMut<'w, crate::hover::HoverSettings>,
+    orientation_axes: ResMut<'w, crate::orientation_axes::OrientationAxesSettings>,
+    cube_axes: ResMut<'w, crate::cube_axes::CubeAxesSettings>,
+    viewports: ResMut<'w, crate::viewports::ViewportSettings>,
+    scene_tree: ResMut<'w, crate::scene_tree::SceneTree>,
+    material_editor: ResMut<'w, crate::material_editor::MaterialEditor>,
+    preferences: ResMut<'w, crate::preferences::PreferencesDialog>,
+    key_bindings: Res<'w, crate::preferences::KeyBindings>,
+    lod_managers: Query<'w, 's, &'static crate::lod::LODManager, With<UserModelMesh>>,
+    lod_export_events: EventWriter<'w, events::ExportLODEvent>,
+}
+
 pub struct UIPlugin;
 impl Plugin for UIPlugin {
     fn build(&self, app: &mut App) {
@@ -38,25 +185,31 @@ impl Plugin for UIPlugin {
             .add_event::<events::GenerateWaveShaderEvent>()
             .add_event::<events::ClearAllMeshesEvent>()
             .add_event::<events::GenerateLODEvent>()
+            .add_event::<events::ExportLODEvent>()
             .add_event::<ModelLoadedEvent>()
+            .add_event::<ModelLoadFailedEvent>()
             .init_resource::<CurrentModelData>()
             .init_resource::<ColorBarConfig>()
+            .init_resource::<ImportSettings>()
+            .init_resource::<FileDialogs>()
             .add_systems(
                 Update,
                 (
-                    initialize_ui_systems,
-                    check_pending_file_load,
+                    initialize_ui_systems.in_set(MenuBarSet),
+                    receive_file_dialog_selections,
                     load_resource,
                     handle_subdivision,
                     handle_wave_generation,
                     handle_wave_shader_generation,
                     handle_clear_all_meshes,
-                    handle_lod_generation,
+                    // Restored sessions subdivide and generate LOD in the same frame
+                    handle_lod_generation.after(handle_subdivision),
+                    handle_lod_export,
                     color_bar::apply_color_map_changes,
+                    color_bar::update_legend_categories,
                 )
                     .after(EguiSet::InitContexts),
             );
-        // .add_plugins(ObjPlugin);
     }
 }
 
@@ -74,10 +227,14 @@ fn initialize_ui_systems(
     current_model: Res<CurrentModelData>,
     animation_asset: Res<crate::animation::TimeSeriesAsset>,
     mut color_bar_config: ResMut<ColorBarConfig>,
+    mut tools: ToolResources,
     windows: Query<&Window>,
 ) {
     // Handle keyboard shortcuts
-    if keyboard_input.just_pressed(KeyCode::Delete) {
+    if tools
+        .key_bindings
+        .just_pressed(&keyboard_input, Action::ClearMeshes)
+    {
         clear_events.send(events::ClearAllMeshesEvent);
     }
 
@@ -86,115 +243,172 @@ fn initialize_ui_systems(
         egui::TopBottomPanel::top("Menu Bar").show(contexts.ctx_mut(), |ui| {
             // The top panel is often a good place for a menu bar:
             egui::menu::bar(ui, |ui| {
-                egui::menu::menu_button(ui, "File", |ui| {
-                    if ui.button("Import").clicked() {
-                        // Use async file dialog to avoid main thread blocking
-                        std::thread::spawn(move || {
-                            if let Some(file) = FileDialog::new()
-                                .add_filter("model", &["obj", "glb", "vtk", "vtu"])
-                                .set_directory(
-                                    &std::env::var("HOME").unwrap_or_else(|_| "/".to_string()),
-                                )
-                                .pick_file()
-                            {
-                                println!("Selected file: {}", file.display());
-
-                                let temp_file = std::env::temp_dir().join("pending_file_load.txt");
-                                if let Err(e) =
-                                    std::fs::write(&temp_file, file.to_string_lossy().as_bytes())
-                                {
-                                    eprintln!("Failed to write pending file: {}", e);
-                                }
-                            }
+                egui::menu::menu_button(ui, tr("File"), |ui| {
+                    if ui.button(tr("Import")).clicked() {
+                        // Async file dialog, so the main thread is not blocked
+                        let dialog = AsyncFileDialog::new()
+                            .add_filter(tr("model"), MODEL_EXTENSIONS)
+                            .set_directory(
+                                std::env::var("HOME").unwrap_or_else(|_| "/".to_string()),
+                            );
+                        tools.file_dialogs.open(async move {
+                            let file = dialog.pick_file().await?;
+                            println!("Selected file: {}", file.path().display());
+                            Some(DialogSelection::Model(file.path().to_path_buf()))
                         });
                     }
+                    ui.checkbox(
+                        &mut tools.import_settings.on_sphere,
+                        tr("netCDF Grids on Sphere"),
+                    );
+                    ui.checkbox(
+                        &mut tools.import_settings.weld_points,
+                        tr("Merge Duplicate Points"),
+                    )
+                    .on_hover_text(tr("Weld coincident points of imported models"));
+                    let weld_points = tools.import_settings.weld_points;
+                    ui.add_enabled_ui(weld_points, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(tr("Weld tolerance:"));
+                            ui.add(
+                                egui::DragValue::new(&mut tools.import_settings.weld_tolerance)
+                                    .speed(1e-4)
+                                    .range(0.0..=f32::MAX),
+                            );
+                        });
+                    });
+                    ui.checkbox(
+                        &mut tools.streaming_import.enabled,
+                        tr("Stream Large VTK Files"),
+                    )
+                    .on_hover_text(fill(
+                        "Legacy VTK files of {} MB or more load in the background",
+                        &[&tools.streaming_import.min_file_size_mb],
+                    ));
+                    let streaming = tools.streaming_import.enabled;
+                    ui.add_enabled(
+                        streaming,
+                        egui::Checkbox::new(
+                            &mut tools.streaming_import.memory_map,
+                            tr("Memory-Map Binary VTK Files"),
+                        ),
+                    )
+                    .on_hover_text(tr(
+                        "Decode streamed binary files straight from the mapped file",
+                    ));
 
                     ui.separator();
 
-                    if ui.button("Import Time Series").clicked() {
+                    if ui.button(tr("Import Time Series")).clicked() {
                         // Select time series folder
-                        std::thread::spawn(move || {
-                            if let Some(folder) = FileDialog::new()
-                                .set_directory(
-                                    &std::env::var("HOME").unwrap_or_else(|_| "/".to_string()),
-                                )
-                                .pick_folder()
-                            {
-                                println!("Selected time series folder: {}", folder.display());
-                                // Scan VTK files in the folder
-                                let mut vtk_files = Vec::new();
-                                if let Ok(entries) = std::fs::read_dir(&folder) {
-                                    for entry in entries {
-                                        if let Ok(entry) = entry {
-                                            let path = entry.path();
-                                            if path.extension().and_then(|ext| ext.to_str())
-                                                == Some("vtu")
-                                            {
-                                                vtk_files.push(path);
-                                            }
-                                        }
-                                    }
+                        let dialog = AsyncFileDialog::new().set_directory(
+                            std::env::var("HOME").unwrap_or_else(|_| "/".to_string()),
+                        );
+                        tools.file_dialogs.open(async move {
+                            let folder = dialog.pick_folder().await?;
+                            println!("Selected time series folder: {}", folder.path().display());
+                            // Scan the folder here, off the main thread
+                            let source = folder.path().to_path_buf();
+                            let event = time_series_event(&source)?;
+                            Some(DialogSelection::TimeSeries { source, event })
+                        });
+                    }
+
+                    if ui
+                        .button(tr("Import Time Series (.pvd, Exodus, netCDF, XDMF)"))
+                        .clicked()
+                    {
+                        // Select a ParaView collection file listing the files with their times,
+                        // or an Exodus or netCDF file holding all time steps
+                        let dialog = AsyncFileDialog::new()
+                            .add_filter(
+                                tr("Time series"),
+                                &["pvd", "e", "exo", "ex2", "nc", "nc3", "cdf", "xdmf", "xmf"],
+                            )
+                            .set_directory(
+                                std::env::var("HOME").unwrap_or_else(|_| "/".to_string()),
+                            );
+                        tools.file_dialogs.open(async move {
+                            let file = dialog.pick_file().await?;
+                            // Read the collection here; the time steps of multi-step files
+                            // are expanded when the series is loaded
+                            let source = file.path().to_path_buf();
+                            let event = time_series_event(&source)?;
+                            Some(DialogSelection::TimeSeries { source, event })
+                        });
+                    }
+
+                    ui.menu_button(tr("Recent"), |ui| {
+                        if tools.recent_files.entries.is_empty() {
+                            ui.label(tr("No recent files"));
+                            return;
+                        }
+                        let mut selected = None;
+                        for (index, entry) in tools.recent_files.entries.iter().enumerate() {
+                            let name = entry.path.file_name().map_or_else(
+                                || entry.path.display().to_string(),
+                                |name| name.to_string_lossy().to_string(),
+                            );
+                            let label = match entry.kind {
+                                crate::recent::RecentKind::Model => name,
+                                crate::recent::RecentKind::TimeSeries => {
+                                    fill("{} (time series)", &[&name])
                                 }
+                            };
+                            if ui
+                                .button(label)
+                                .on_hover_text(entry.path.display().to_string())
+                                .clicked()
+                            {
+                                selected = Some(index);
+                                ui.close_menu();
+                            }
+                        }
+                        ui.separator();
+                        if ui.button(tr("Clear Recent")).clicked() {
+                            tools.recent_files.clear();
+                            ui.close_menu();
+                        }
+                        if selected.is_some() {
+                            tools.recent_files.requested = selected;
+                        }
+                    });
 
-                                // Sort by numerical order (ensure correct time sequence)
-                                vtk_files.sort_by(|a, b| {
-                                    // Extract numeric part from filename for comparison
-                                    let extract_number = |path: &std::path::Path| -> Option<u32> {
-                                        let file_stem = path.file_stem()?.to_str()?;
-                                        // Find the number after the last underscore
-                                        if let Some(pos) = file_stem.rfind('_') {
-                                            file_stem[pos + 1..].parse().ok()
-                                        } else {
-                                            // If no underscore, try to parse the whole filename as number
-                                            file_stem.parse().ok()
-                                        }
-                                    };
+                    ui.separator();
 
-                                    match (extract_number(a), extract_number(b)) {
-                                        (Some(num_a), Some(num_b)) => num_a.cmp(&num_b),
-                                        (Some(_), None) => std::cmp::Ordering::Less,
-                                        (None, Some(_)) => std::cmp::Ordering::Greater,
-                                        (None, None) => a.cmp(b),
-                                    }
-                                });
+                    if ui.button(tr("Open Project...")).clicked() {
+                        tools.project.open_requested = true;
+                    }
+                    if ui.button(tr("Save Project...")).clicked() {
+                        tools.project.save_requested = true;
+                    }
 
-                                println!("Found {} VTK files in time series", vtk_files.len());
-                                if vtk_files.len() > 0 {
-                                    println!("First file: {}", vtk_files[0].display());
-                                    println!(
-                                        "Last file: {}",
-                                        vtk_files[vtk_files.len() - 1].display()
-                                    );
-                                }
+                    ui.separator();
 
-                                if !vtk_files.is_empty() {
-                                    let temp_file =
-                                        std::env::temp_dir().join("pending_time_series.txt");
-                                    let file_list = vtk_files
-                                        .iter()
-                                        .map(|p| p.to_string_lossy().to_string())
-                                        .collect::<Vec<_>>()
-                                        .join("\n");
-                                    if let Err(e) = std::fs::write(&temp_file, file_list) {
-                                        eprintln!("Failed to write pending time series: {}", e);
-                                    }
-                                } else {
-                                    eprintln!("No VTK files found in selected folder");
-                                }
-                            }
-                        });
+                    if ui.button(tr("Export Scene (.glb)")).clicked() {
+                        tools.scene_export.requested = true;
+                    }
+                    if ui.button(tr("Capture Screenshot")).clicked() {
+                        tools.screenshot_settings.show_panel = true;
                     }
 
                     ui.separator();
 
-                    if ui.button("Quit").clicked() {
+                    if ui.button(tr("Preferences...")).clicked() {
+                        tools.preferences.show_panel = true;
+                    }
+
+                    ui.separator();
+
+                    if ui.button(tr("Quit")).clicked() {
+                        crate::session::mark_clean_exit();
                         std::process::exit(0);
                     }
                 });
 
                 // Add View menu
-                egui::menu::menu_button(ui, "View", |ui| {
-                    if ui.button("Wireframe").clicked() {
+                egui::menu::menu_button(ui, tr("View"), |ui| {
+                    if ui.button(tr("Wireframe")).clicked() {
                         wireframe_toggle_events.send(events::ToggleWireframeEvent);
                     }
 
@@ -202,9 +416,9 @@ fn initialize_ui_systems(
 
                     // Color bar control
                     let color_bar_text = if color_bar_config.visible {
-                        "hide color bar"
+                        tr("hide color bar")
                     } else {
-                        "show color bar"
+                        tr("show color bar")
                     };
                     if ui.button(color_bar_text).clicked() {
                         color_bar_config.visible = !color_bar_config.visible;
@@ -212,67 +426,312 @@ fn initialize_ui_systems(
 
                     ui.separator();
 
-                    if ui.button("Clear User Meshes (Delete)").clicked() {
+                    // Manipulation widgets
+                    ui.menu_button(tr("Widgets"), |ui| {
+                        ui.checkbox(&mut tools.widget_state.plane.enabled, tr("Plane Widget"));
+                        ui.checkbox(&mut tools.widget_state.box_widget.enabled, tr("Box Widget"));
+                        ui.checkbox(&mut tools.widget_state.sphere.enabled, tr("Sphere Widget"));
+                        ui.checkbox(&mut tools.widget_state.line.enabled, tr("Line Widget"));
+                    });
+
+                    ui.checkbox(&mut tools.scene_tree.show_panel, tr("Scene Tree"));
+                    ui.checkbox(&mut tools.material_editor.show_panel, tr("Material Editor"));
+                    ui.checkbox(
+                        &mut tools.clipping_settings.show_panel,
+                        tr("Clipping Planes"),
+                    );
+
+                    ui.checkbox(&mut tools.gltf_import.show_panel, tr("glTF Scene Panel"));
+
+                    ui.checkbox(&mut tools.outline.model_outline, tr("Bounding Box"));
+                    ui.menu_button(tr("Cube Axes"), |ui| {
+                        ui.checkbox(&mut tools.cube_axes.visible, tr("Show Cube Axes"));
+                        ui.checkbox(&mut tools.cube_axes.grid, tr("Grid Lines"));
+                        ui.checkbox(&mut tools.cube_axes.titles, tr("Axis Titles"));
+                        ui.add(
+                            egui::Slider::new(&mut tools.cube_axes.ticks, 2..=12).text(tr("Ticks")),
+                        );
+                    });
+
+                    if ui
+                        .checkbox(&mut tools.splat_settings.enabled, tr("Point Splatting"))
+                        .changed()
+                    {
+                        tools.splat_settings.changed = true;
+                    }
+                    ui.checkbox(
+                        &mut tools.point_downsampling.show_panel,
+                        tr("Point Downsampling"),
+                    );
+
+                    if ui
+                        .checkbox(&mut tools.heightmap_settings.enabled, tr("Heightmap"))
+                        .changed()
+                    {
+                        tools.heightmap_settings.changed = true;
+                    }
+                    ui.checkbox(
+                        &mut tools.periodic_settings.show_panel,
+                        tr("Periodic Replication"),
+                    );
+                    ui.checkbox(&mut tools.volume_slices.show_panel, tr("Volume Slices"));
+
+                    ui.checkbox(&mut tools.label_settings.show_panel, tr("ID Labels"));
+                    ui.checkbox(&mut tools.hover.enabled, tr("Hover Values"));
+                    ui.checkbox(&mut tools.orientation_axes.visible, tr("Orientation Axes"));
+                    ui.checkbox(&mut tools.viewports.show_toolbar, tr("Viewport Toolbar"));
+
+                    ui.checkbox(&mut tools.overlay_settings.on_top, tr("Helpers on Top"));
+                    ui.checkbox(&mut tools.render_settings.show_panel, tr("Render Settings"));
+                    let hud_label = fill(
+                        "Performance HUD ({})",
+                        &[&tools.key_bindings.label(Action::TogglePerformanceHud)],
+                    );
+                    ui.checkbox(&mut tools.performance_hud.visible, hud_label);
+
+                    ui.separator();
+
+                    let clear_label = fill(
+                        "Clear User Meshes ({})",
+                        &[&tools.key_bindings.label(Action::ClearMeshes)],
+                    );
+                    if ui.button(clear_label).clicked() {
                         clear_events.send(events::ClearAllMeshesEvent);
                     }
 
                     ui.separator();
 
                     // Debug information
-                    ui.label("Debug Info:");
+                    ui.label(tr("Debug Info:"));
                     if animation_asset.is_loaded && animation_asset.get_total_time_steps() > 1 {
-                        ui.label(format!(
+                        ui.label(fill(
                             "Time series loaded: {} frames",
-                            animation_asset.get_total_time_steps()
+                            &[&animation_asset.get_total_time_steps()],
                         ));
                         if let Some(mesh_entity) = animation_asset.mesh_entity {
-                            ui.label(format!("Mesh entity: {:?}", mesh_entity));
+                            ui.label(fill("Mesh entity: {}", &[&format!("{:?}", mesh_entity)]));
                         } else {
-                            ui.label("No mesh entity");
+                            ui.label(tr("No mesh entity"));
                         }
                     } else if animation_asset.is_loaded
                         && animation_asset.get_total_time_steps() == 1
                     {
-                        ui.label("Single file loaded (not a time series)");
+                        ui.label(tr("Single file loaded (not a time series)"));
                     } else {
-                        ui.label("No time series loaded");
+                        ui.label(tr("No time series loaded"));
                     }
                 });
 
                 // Add Mesh menu
-                egui::menu::menu_button(ui, "Mesh", |ui| {
+                egui::menu::menu_button(ui, tr("Mesh"), |ui| {
                     // Subdivision options
                     if current_model.geometry.is_some() {
-                        ui.label("Operations:");
+                        ui.label(tr("Operations:"));
 
-                        if ui.button("Subdivide").clicked() {
+                        if ui.button(tr("Subdivide")).clicked() {
                             subdivide_events.send(events::SubdivideMeshEvent);
                         }
 
-                        if ui.button("Generate LOD").clicked() {
+                        if ui.button(tr("Generate LOD")).clicked() {
                             lod_events.send(events::GenerateLODEvent);
                         }
+
+                        if ui.button(tr("Isosurface")).clicked() {
+                            tools.isosurface.show_panel = true;
+                            ui.close_menu();
+                        }
+
+                        if ui.button(tr("Slice")).clicked() {
+                            tools.slice.show_panel = true;
+                            ui.close_menu();
+                        }
+
+                        if ui.button(tr("Clip")).clicked() {
+                            tools.clip_filter.show_panel = true;
+                            ui.close_menu();
+                        }
+
+                        if ui.button(tr("Streamlines")).clicked() {
+                            tools.streamlines.show_panel = true;
+                            ui.close_menu();
+                        }
+
+                        if ui
+                            .checkbox(&mut tools.glyphs.enabled, tr("Vector Glyphs"))
+                            .changed()
+                        {
+                            tools.glyphs.changed = true;
+                            tools.glyphs.show_panel = tools.glyphs.enabled;
+                        }
+
+                        if ui.button(tr("Warp by Vector")).clicked() {
+                            tools.warp.show_panel = true;
+                            ui.close_menu();
+                        }
+
+                        if ui.button(tr("Calculator")).clicked() {
+                            tools.calculator.show_panel = true;
+                            ui.close_menu();
+                        }
+
+                        if ui.button(tr("Cell/Point Data")).clicked() {
+                            tools.data_conversion.show_panel = true;
+                            ui.close_menu();
+                        }
+
+                        if ui.button(tr("Connectivity")).clicked() {
+                            tools.connectivity.show_panel = true;
+                            ui.close_menu();
+                        }
+
+                        if ui.button(tr("Integrate Variables")).clicked() {
+                            tools.integrate.show_panel = true;
+                            ui.close_menu();
+                        }
+
+                        if ui.button(tr("Mesh Quality")).clicked() {
+                            tools.quality.show_panel = true;
+                            ui.close_menu();
+                        }
+
+                        if ui.button(tr("Delaunay 2.5D")).clicked() {
+                            tools.delaunay.show_panel = true;
+                            ui.close_menu();
+                        }
+
+                        if ui.button(tr("Resample to Grid")).clicked() {
+                            tools.resample.show_panel = true;
+                            ui.close_menu();
+                        }
+
+                        if ui.button(tr("Extract Cells by Type")).clicked() {
+                            tools.cell_types.show_panel = true;
+                            ui.close_menu();
+                        }
+
+                        if ui.button(tr("Transform")).clicked() {
+                            tools.transform.show_panel = true;
+                            ui.close_menu();
+                        }
+
+                        if ui.button(tr("Tubes")).clicked() {
+                            tools.tubes.show_panel = true;
+                            ui.close_menu();
+                        }
+
+                        if ui.button(tr("Extrude")).clicked() {
+                            tools.extrude.show_panel = true;
+                            ui.close_menu();
+                        }
+
+                        if ui.button(tr("Temporal Statistics")).clicked() {
+                            tools.temporal_stats.show_panel = true;
+                            ui.close_menu();
+                        }
+
+                        if ui.button(tr("Pathlines")).clicked() {
+                            tools.pathlines.show_panel = true;
+                            ui.close_menu();
+                        }
+
+                        if ui.button(tr("Divergence and Curl")).clicked() {
+                            tools.derivatives.show_panel = true;
+                            ui.close_menu();
+                        }
+
+                        // Generated levels can be saved for 3D printing or meshing
+                        if let Some(lod_manager) = tools.lod_managers.iter().next() {
+                            ui.menu_button(tr("Export LOD to STL"), |ui| {
+                                for (level, lod_data) in &lod_manager.lod_meshes {
+                                    let label = fill(
+                                        "{} ({} triangles)",
+                                        &[&format!("{:?}", level), &lod_data.triangle_count],
+                                    );
+                                    if ui.button(label).clicked() {
+                                        tools
+                                            .lod_export_events
+                                            .send(events::ExportLODEvent(*level));
+                                        ui.close_menu();
+                                    }
+                                }
+                            });
+                        }
                     } else {
-                        ui.label("Load a model first");
+                        ui.label(tr("Load a model first"));
                     }
 
                     ui.separator();
 
                     // Wave generation
-                    ui.label("Generate:");
-                    if ui.button("Create Wave Surface (CPU)").clicked() {
+                    ui.label(tr("Generate:"));
+                    if ui.button(tr("Create Wave Surface (CPU)")).clicked() {
                         wave_events.send(events::GenerateWaveEvent);
                     }
 
-                    if ui.button("Create Wave Surface (GPU Shader)").clicked() {
+                    if ui.button(tr("Create Wave Surface (GPU Shader)")).clicked() {
                         wave_shader_events.send(events::GenerateWaveShaderEvent);
                     }
                 });
+
+                // Add Tools menu
+                egui::menu::menu_button(ui, tr("Tools"), |ui| {
+                    ui.label(tr("Mouse Mode:"));
+                    ui.radio_value(
+                        &mut *tools.interaction_mode,
+                        InteractionMode::Navigate,
+                        tr("Navigate"),
+                    );
+                    ui.radio_value(
+                        &mut *tools.interaction_mode,
+                        InteractionMode::Ruler,
+                        tr("Ruler"),
+                    );
+                    if current_model.geometry.is_some() {
+                        ui.radio_value(
+                            &mut *tools.interaction_mode,
+                            InteractionMode::VertexEdit,
+                            tr("Edit Vertices"),
+                        );
+                        ui.radio_value(
+                            &mut *tools.interaction_mode,
+                            InteractionMode::Select,
+                            tr("Select Cells"),
+                        );
+                        ui.radio_value(
+                            &mut *tools.interaction_mode,
+                            InteractionMode::Probe,
+                            tr("Probe"),
+                        );
+                        ui.radio_value(
+                            &mut *tools.interaction_mode,
+                            InteractionMode::Annotate,
+                            tr("Annotate"),
+                        );
+                    }
+                });
             });
         });
 
         if color_bar_config.visible {
-            color_bar::render_color_bar_inline(&mut contexts, color_bar_config);
+            let attributes = current_model
+                .geometry
+                .as_ref()
+                .map(crate::mesh::topology::attribute_names)
+                .unwrap_or_default();
+            let active_attribute = current_model.geometry.as_ref().and_then(|geometry| {
+                crate::mesh::color_maps::ColorMapper::colored_attribute(
+                    geometry,
+                    color_bar_config.attribute.as_ref(),
+                )
+            });
+            color_bar::render_color_bar_inline(
+                &mut contexts,
+                color_bar_config,
+                &attributes,
+                active_attribute.as_ref().map(|(name, _)| name.as_str()),
+                tools.render_settings.cvd,
+                &mut tools.file_dialogs,
+            );
         }
 
         // Add time series animation control panel
@@ -283,12 +742,12 @@ fn initialize_ui_systems(
                 .show(contexts.ctx_mut(), |ui| {
                     ui.vertical(|ui| {
                         ui.horizontal(|ui| {
-                            ui.heading("Time Series Animation Control");
+                            ui.heading(tr("Time Series Animation Control"));
                             ui.separator();
                             if animation_asset.is_step2_complete {
-                                ui.colored_label(egui::Color32::GREEN, "✓ Animation Ready");
+                                ui.colored_label(egui::Color32::GREEN, tr("✓ Animation Ready"));
                             } else {
-                                ui.colored_label(egui::Color32::YELLOW, "● Loading...");
+                                ui.colored_label(egui::Color32::YELLOW, tr("● Loading..."));
                             }
                         });
 
@@ -300,27 +759,27 @@ fn initialize_ui_systems(
                             ui.horizontal(|ui| {
                                 // Play/Pause button
                                 if animation_asset.is_playing {
-                                    if ui.button("⏸ Pause").clicked() {
+                                    if ui.button(tr("⏸ Pause")).clicked() {
                                         time_series_events.send(TimeSeriesEvent::Pause);
                                     }
                                 } else {
-                                    if ui.button("▶ Play").clicked() {
+                                    if ui.button(tr("▶ Play")).clicked() {
                                         time_series_events.send(TimeSeriesEvent::Play);
                                     }
                                 }
 
                                 // Stop button
-                                if ui.button("⏹ Stop").clicked() {
+                                if ui.button(tr("⏹ Stop")).clicked() {
                                     time_series_events.send(TimeSeriesEvent::Stop);
                                 }
 
                                 ui.separator();
 
                                 // Single step control
-                                if ui.button("⏮ Prev Frame").clicked() {
+                                if ui.button(tr("⏮ Prev Frame")).clicked() {
                                     time_series_events.send(TimeSeriesEvent::PrevTimeStep);
                                 }
-                                if ui.button("⏭ Next Frame").clicked() {
+                                if ui.button(tr("⏭ Next Frame")).clicked() {
                                     time_series_events.send(TimeSeriesEvent::NextTimeStep);
                                 }
 
@@ -328,9 +787,9 @@ fn initialize_ui_systems(
 
                                 // Loop playback toggle
                                 let loop_text = if animation_asset.loop_animation {
-                                    "🔄 Loop On"
+                                    tr("🔄 Loop On")
                                 } else {
-                                    "🔄 Loop Off"
+                                    tr("🔄 Loop Off")
                                 };
                                 if ui.button(loop_text).clicked() {
                                     time_series_events.send(TimeSeriesEvent::ToggleLoop);
@@ -339,7 +798,7 @@ fn initialize_ui_systems(
 
                             // Time step progress bar
                             ui.horizontal(|ui| {
-                                ui.label("Time Step:");
+                                ui.label(tr("Time Step:"));
                                 let total_steps = animation_asset.get_total_time_steps();
                                 let mut current_step = animation_asset.current_time_step;
 
@@ -349,7 +808,7 @@ fn initialize_ui_systems(
                                             &mut current_step,
                                             0..=(total_steps.saturating_sub(1)),
                                         )
-                                        .text("Frame")
+                                        .text(tr("Frame"))
                                         .show_value(true),
                                     )
                                     .changed()
@@ -361,9 +820,21 @@ fn initialize_ui_systems(
                                 ui.label(format!("{}/{}", current_step + 1, total_steps));
                             });
 
+                            // Physical time of collection (.pvd) series
+                            if animation_asset.has_physical_time() {
+                                if let Some(current_data) =
+                                    animation_asset.get_current_time_step_data()
+                                {
+                                    ui.horizontal(|ui| {
+                                        ui.label(tr("Time:"));
+                                        ui.monospace(format!("{}", current_data.time));
+                                    });
+                                }
+                            }
+
                             // FPS control
                             ui.horizontal(|ui| {
-                                ui.label("Playback Speed:");
+                                ui.label(tr("Playback Speed:"));
                                 let mut fps = animation_asset.fps;
                                 if ui
                                     .add(
@@ -381,7 +852,7 @@ fn initialize_ui_systems(
                             if let Some(current_data) = animation_asset.get_current_time_step_data()
                             {
                                 ui.horizontal(|ui| {
-                                    ui.label("Current File:");
+                                    ui.label(tr("Current File:"));
                                     if let Some(file_name) = current_data.file_path.file_name() {
                                         if let Some(name_str) = file_name.to_str() {
                                             ui.monospace(name_str);
@@ -392,11 +863,13 @@ fn initialize_ui_systems(
                         } else {
                             // Step 2 loading status
                             ui.horizontal(|ui| {
-                                ui.label("Status: Loading time series data...");
-                                ui.label(format!(
+                                ui.label(tr("Status: Loading time series data..."));
+                                ui.label(fill(
                                     "Loaded: {}/{}",
-                                    animation_asset.time_steps.len(),
-                                    animation_asset.all_file_paths.len()
+                                    &[
+                                        &animation_asset.time_steps.len(),
+                                        &animation_asset.all_file_paths.len(),
+                                    ],
                                 ));
                             });
                         }
@@ -411,11 +884,16 @@ fn load_resource(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    mut load_events: EventReader<events::LoadModelEvent>,
+    mut model_material: crate::material_editor::NewModelMaterial,
+    mut gltf_import: ResMut<crate::gltf_scene::GltfImportState>,
+    mut load_requests: ModelLoadRequests,
     mut model_loaded_events: EventWriter<ModelLoadedEvent>,
     mut current_model: ResMut<CurrentModelData>,
+    mut point_downsampling: ResMut<crate::downsampling::PointDownsampling>,
+    mut volume_slices: ResMut<crate::volume_slices::VolumeSlices>,
     color_bar_config: ResMut<ColorBarConfig>,
+    import_settings: Res<ImportSettings>,
+    mut streaming_import: ResMut<crate::streaming::StreamingImport>,
     mut egui_context: EguiContexts,
     windows: Query<&Window>,
     mesh_entities: Query<Entity, With<UserModelMesh>>,
@@ -423,7 +901,47 @@ fn load_resource(
     // Check if window exists
     let window_exists = windows.iter().next().is_some();
 
-    for events::LoadModelEvent(path) in load_events.read() {
+    // The geometry of a finished streaming or parsing import continues like a loaded file
+    let streamed = streaming_import.take_finished();
+    let requests: Vec<(PathBuf, Option<mesh::GeometryData>)> = load_requests
+        .requests
+        .read()
+        .map(|events::LoadModelEvent(path)| (path.clone(), None))
+        .chain(streamed.map(|(path, geometry)| (path, Some(geometry))))
+        .collect();
+
+    for (path, streamed_geometry) in requests {
+        let path = &path;
+        // Files read in the background keep the current model until their geometry
+        // comes back through `take_finished`; a failed or cancelled import leaves it
+        if streamed_geometry.is_none() {
+            match path.extension().and_then(|ext| ext.to_str()) {
+                // Large legacy VTK files are read in chunks on a background thread
+                Some("vtk") if streaming_import.should_stream(path) => {
+                    streaming_import.start(path);
+                    continue;
+                }
+                // VTK extension:
+                // Legacy: .vtk
+                // XML: .vtu, .vtp, .vts, .vtr (parsed by the XML pipeline)
+                // Parallel: .pvtu, .pvtp (pieces merged into one model)
+                // Exodus II: .e, .exo, .ex2 (first time step)
+                // CGNS: .cgns (HDF5 storage, unstructured zones)
+                // XDMF: .xdmf, .xmf (HDF5 heavy data, first time step)
+                // Nastran: .bdf, .nas (bulk data GRID and element cards)
+                // CSV: .csv (scattered points with scalar columns)
+                // Parsed on the task pool with a progress window
+                Some(
+                    "obj" | "ply" | "vtk" | "vtu" | "vtp" | "vts" | "vtr" | "pvtu" | "pvtp" | "e"
+                    | "exo" | "ex2" | "cgns" | "xdmf" | "xmf" | "bdf" | "nas" | "csv",
+                ) => {
+                    streaming_import.start_parsing(path);
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
         // Clear existing user models from scene before importing new model
         let cleared_count =
             clear_existing_models_silent(&mut commands, &mesh_entities, &mut current_model);
@@ -433,198 +951,161 @@ fn load_resource(
                 cleared_count
             );
         }
-
-        match path.extension().and_then(|ext| ext.to_str()) {
-            Some("obj") => {
-                let position = Vec3::new(0.0, 0.5, 0.0);
-                let scale = Vec3::splat(1.0);
-
-                commands.spawn((
-                    Mesh3d(asset_server.load(format!("{}", path.to_string_lossy()))),
-                    MeshMaterial3d(materials.add(StandardMaterial {
-                        base_color: Color::WHITE,
-                        metallic: 0.2,
-                        perceptual_roughness: 0.4,
-                        reflectance: 0.5,
-                        unlit: false,
-                        alpha_mode: AlphaMode::Opaque,
-                        ..default()
-                    })),
-                    Transform::from_translation(position).with_scale(scale),
-                ));
-
-                current_model.geometry = None;
-
-                model_loaded_events.send(ModelLoadedEvent {
-                    position,
-                    scale,
-                    bounds_min: None,
-                    bounds_max: None,
-                });
-            }
-            // VTK extension:
-            // Legacy: .vtk
-            Some("vtk" | "vtu") => {
-                // 1. Import VTK file
-                let vtk = match vtkio::Vtk::import(PathBuf::from(format!(
-                    "{}",
-                    path.to_string_lossy()
-                ))) {
-                    Ok(vtk) => vtk,
+        volume_slices.clear();
+
+        let geometry = match streamed_geometry {
+            Some(geometry) => geometry,
+            None => match path.extension().and_then(|ext| ext.to_str()) {
+                // netCDF (CF conventions): .nc, longitude/latitude grid on a plane or sphere
+                Some("nc" | "nc3" | "cdf") => {
+                    let projection = if import_settings.on_sphere {
+                        mesh::climate::Projection::Sphere
+                    } else {
+                        mesh::climate::Projection::Plane
+                    };
+                    match mesh::climate::load_climate(path, projection) {
+                        Ok(geometry) => geometry,
+                        Err(err) => {
+                            println!("load netCDF file failed: {:?}", err);
+                            load_requests.fail(path, &err);
+                            if window_exists {
+                                egui::Window::new(tr("Error")).show(egui_context.ctx_mut(), |ui| {
+                                    ui.label(fill("load file failed: {}", &[&err]));
+                                });
+                            }
+                            continue;
+                        }
+                    }
+                }
+                // glTF scenes keep their node hierarchy and are spawned once loaded
+                Some("glb" | "gltf") => {
+                    gltf_import.request_load(&asset_server, path);
+                    continue;
+                }
+                // XML: .vti (image data), shown by slices of the kept volume
+                Some("vti") => match mesh::vtk::load_vtk_volume(path) {
+                    Ok(volume) => volume_slices.prepare_import(volume),
                     Err(err) => {
-                        println!("load VTK file failed: {:?}", err);
+                        println!("load volume file failed: {:?}", err);
+                        load_requests.fail(path, &err);
                         if window_exists {
-                            egui::Window::new("Error").show(egui_context.ctx_mut(), |ui| {
-                                ui.label(format!("load file failed: {:?}", err));
+                            egui::Window::new(tr("Error")).show(egui_context.ctx_mut(), |ui| {
+                                ui.label(fill("load file failed: {}", &[&err]));
                             });
                         }
                         continue;
                     }
-                };
-
-                // Print VTK information
-                // mesh::print_vtk_info(&vtk);
-
-                // 2. Parse VTK file to get geometry data
-                let geometry = match vtk.data {
-                    // 2.1 Process UnstructuredGrid
-                    vtkio::model::DataSet::UnstructuredGrid { meta: _, pieces } => {
-                        let extractor = mesh::vtk::UnstructuredGridExtractor;
-                        match extractor.process_legacy(pieces) {
-                            Ok(geo) => geo,
-                            Err(err) => {
-                                println!("Failed to extract UnstructuredGrid: {:?}", err);
-                                continue;
-                            }
-                        }
-                    }
-                    // 2.2 Process PolyData
-                    vtkio::model::DataSet::PolyData { meta: _, pieces } => {
-                        let extractor = mesh::vtk::PolyDataExtractor;
-                        match extractor.process_legacy(pieces) {
-                            Ok(geo) => geo,
-                            Err(err) => {
-                                println!("Failed to extract PolyData: {:?}", err);
-                                continue;
-                            }
-                        }
-                    }
-                    // 2.3 TODO: Support other data types
-                    _ => {
-                        println!("Unsupported VTK data type");
-                        continue;
+                },
+                _ => {
+                    println!("currently not supported other formats, please select another model.");
+                    load_requests.fail(path, "unsupported file format");
+                    // 12. show the message that this format is not supported
+                    if window_exists {
+                        egui::Window::new(tr("Not supported format")).show(
+                            egui_context.ctx_mut(),
+                            |ui| {
+                                let extensions = MODEL_EXTENSIONS
+                                    .iter()
+                                    .map(|extension| format!(".{}", extension))
+                                    .collect::<Vec<_>>()
+                                    .join(", ");
+                                ui.label(fill(
+                                    "not supported this file format, please select one of: {}",
+                                    &[&extensions],
+                                ));
+                            },
+                        );
                     }
-                };
-
-                println!(
-                    "Extracted geometry data attributes: {:?}",
-                    &geometry.attributes
-                );
-
-                // Print geometry info for debugging
-                // mesh::print_geometry_info(&geometry);
-
-                // 3. Save geometry data to CurrentModelData
-                current_model.geometry = Some(geometry.clone());
+                    continue;
+                }
+            },
+        };
 
-                // color_bar::update_color_bar_range_from_geometry(&geometry, &mut color_bar_config);
+        // Print geometry info for debugging
+        // mesh::print_geometry_info(&geometry);
 
-                // 4. Use parsed geometry to directly create mesh
-                let mut mesh = mesh::create_mesh_from_geometry(&geometry);
+        // Points repeated per cell are merged before anything is built from them
+        let geometry = if import_settings.weld_points {
+            mesh::weld::weld_points(geometry, import_settings.weld_tolerance)
+        } else {
+            geometry
+        };
 
-                // 5. Apply selected color mapping
-                if let Err(e) =
-                    color_bar::apply_custom_color_mapping(&geometry, &mut mesh, &color_bar_config)
-                {
-                    println!("Failed to apply initial color mapping: {:?}", e);
-                }
+        // Huge point clouds are shown downsampled, the original is kept for export
+        let geometry = point_downsampling.prepare_import(geometry);
 
-                let position = Vec3::new(0.0, 0.5, 0.0);
-                let scale = Vec3::ONE;
+        // 3. Save geometry data to CurrentModelData
+        current_model.geometry = Some(geometry.clone());
 
-                // 6. Calculate model bounding box
-                let mut bounds_min = None;
-                let mut bounds_max = None;
+        // color_bar::update_color_bar_range_from_geometry(&geometry, &mut color_bar_config);
 
-                if let Some(positions) = mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
-                    if let bevy::render::mesh::VertexAttributeValues::Float32x3(positions) =
-                        positions
-                    {
-                        // 7. Initialize bounding box
-                        if !positions.is_empty() {
-                            let mut min = Vec3::new(f32::MAX, f32::MAX, f32::MAX);
-                            let mut max = Vec3::new(f32::MIN, f32::MIN, f32::MIN);
-
-                            // 8. Iterate through all vertices, update bounding box
-                            for pos in positions {
-                                let pos_vec = Vec3::new(pos[0], pos[1], pos[2]);
-                                min = min.min(pos_vec);
-                                max = max.max(pos_vec);
-                            }
+        // 4. Use parsed geometry to directly create mesh
+        let mut mesh = mesh::create_mesh_from_geometry(&geometry);
 
-                            bounds_min = Some(min);
-                            bounds_max = Some(max);
+        // 5. Apply selected color mapping
+        if let Err(e) =
+            color_bar::apply_custom_color_mapping(&geometry, &mut mesh, &color_bar_config)
+        {
+            println!("Failed to apply initial color mapping: {:?}", e);
+        }
 
-                            println!("Model bounds: min={:?}, max={:?}", min, max);
-                        }
+        let position = Vec3::new(0.0, 0.5, 0.0);
+        let scale = Vec3::ONE;
+
+        // 6. Calculate model bounding box
+        let mut bounds_min = None;
+        let mut bounds_max = None;
+
+        if let Some(positions) = mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+            if let bevy::render::mesh::VertexAttributeValues::Float32x3(positions) = positions {
+                // 7. Initialize bounding box
+                if !positions.is_empty() {
+                    let mut min = Vec3::new(f32::MAX, f32::MAX, f32::MAX);
+                    let mut max = Vec3::new(f32::MIN, f32::MIN, f32::MIN);
+
+                    // 8. Iterate through all vertices, update bounding box
+                    for pos in positions {
+                        let pos_vec = Vec3::new(pos[0], pos[1], pos[2]);
+                        min = min.min(pos_vec);
+                        max = max.max(pos_vec);
                     }
-                }
 
-                // 9. Create entity
-                commands.spawn((
-                    Mesh3d(meshes.add(mesh.clone())),
-                    MeshMaterial3d(materials.add(StandardMaterial {
-                        base_color: Color::srgb(1.0, 1.0, 1.0),
-                        metallic: 0.2,
-                        perceptual_roughness: 0.4,
-                        reflectance: 0.5,
-                        cull_mode: None,
-                        unlit: false,
-                        alpha_mode: AlphaMode::Opaque,
-                        ..default()
-                    })),
-                    Transform::from_translation(position),
-                    Visibility::Visible,
-                    UserModelMesh,
-                ));
-
-                println!("number of vertices: {:?}", mesh.count_vertices());
-
-                // 10. Send model loaded complete event
-                model_loaded_events.send(ModelLoadedEvent {
-                    position,
-                    scale,
-                    bounds_min,
-                    bounds_max,
-                });
-            }
-            // XML: .vtp (polygon data), .vts (structured grid),
-            //      .vtr (rectilinear grid), .vti (image data)
-            Some("vtp" | "vts" | "vtr" | "vti") => {
-                // 11. show the message that this format is not supported
-                if window_exists {
-                    egui::Window::new("Note").show(egui_context.ctx_mut(), |ui| {
-                        ui.label("currently not supported this format, developing...");
-                    });
-                }
-            }
-            _ => {
-                println!("currently not supported other formats, please select another model.");
-                // 12. show the message that this format is not supported
-                if window_exists {
-                    egui::Window::new("Not supported format").show(egui_context.ctx_mut(), |ui| {
-                        ui.label(
-                            "not supported this file format, please select .obj or .vtk file.",
-                        );
-                    });
+                    bounds_min = Some(min);
+                    bounds_max = Some(max);
+
+                    println!("Model bounds: min={:?}, max={:?}", min, max);
                 }
             }
         }
+
+        // 9. Create entity
+        commands.spawn((
+            Mesh3d(meshes.add(mesh.clone())),
+            MeshMaterial3d(model_material.add()),
+            Transform::from_translation(position),
+            Visibility::Visible,
+            UserModelMesh,
+            Name::new(
+                path.file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "Model".to_string()),
+            ),
+        ));
+
+        println!("number of vertices: {:?}", mesh.count_vertices());
+
+        // 10. Send model loaded complete event
+        model_loaded_events.send(ModelLoadedEvent {
+            position,
+            scale,
+            bounds_min,
+            bounds_max,
+        });
     }
 }
 
 /// Handle mesh subdivision events
-fn handle_subdivision(
+pub fn handle_subdivision(
     _commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     _materials: ResMut<Assets<StandardMaterial>>,
@@ -662,11 +1143,11 @@ fn handle_subdivision(
                     } else {
                         println!("Error: No user model entity found for subdivision! This should not happen.");
                         if window_exists {
-                            egui::Window::new("Subdivision Error").show(
+                            egui::Window::new(tr("Subdivision Error")).show(
                                 egui_context.ctx_mut(),
                                 |ui| {
-                                    ui.label("Error: No user model found for subdivision");
-                                    ui.label("This should not happen - please report this bug");
+                                    ui.label(tr("Error: No user model found for subdivision"));
+                                    ui.label(tr("This should not happen - please report this bug"));
                                 },
                             );
                         }
@@ -677,10 +1158,10 @@ fn handle_subdivision(
                     current_model.geometry = Some(subdivided_geometry);
 
                     if window_exists {
-                        egui::Window::new("Subdivision Success").show(
+                        egui::Window::new(tr("Subdivision Success")).show(
                             egui_context.ctx_mut(),
                             |ui| {
-                                ui.label("Successfully completed one subdivision");
+                                ui.label(tr("Successfully completed one subdivision"));
                             },
                         );
                     }
@@ -688,17 +1169,20 @@ fn handle_subdivision(
                 Err(err) => {
                     println!("Subdivision failed: {:?}", err);
                     if window_exists {
-                        egui::Window::new("Subdivision Error").show(egui_context.ctx_mut(), |ui| {
-                            ui.label(format!("Subdivision failed: {:?}", err));
-                        });
+                        egui::Window::new(tr("Subdivision Error")).show(
+                            egui_context.ctx_mut(),
+                            |ui| {
+                                ui.label(fill("Subdivision failed: {}", &[&format!("{:?}", err)]));
+                            },
+                        );
                     }
                 }
             }
         } else {
             println!("No model loaded for subdivision");
             if window_exists {
-                egui::Window::new("No Model").show(egui_context.ctx_mut(), |ui| {
-                    ui.label("Please load a model first before subdivision");
+                egui::Window::new(tr("No Model")).show(egui_context.ctx_mut(), |ui| {
+                    ui.label(tr("Please load a model first before subdivision"));
                 });
             }
         }
@@ -747,6 +1231,8 @@ fn handle_wave_generation(
             })),
             Transform::from_translation(position),
             Visibility::Visible,
+            WaveSurface,
+            Name::new("Wave Surface"),
         ));
 
         // Clear current model data
@@ -758,13 +1244,13 @@ fn handle_wave_generation(
         );
 
         if window_exists {
-            egui::Window::new("Wave Generated").show(egui_context.ctx_mut(), |ui| {
-                ui.label("Successfully generated wave surface!");
-                ui.label("Parameters:");
-                ui.label("  • Amplitude: 1.0");
-                ui.label("  • Wave vector: (0.5, 0.3)");
-                ui.label("  • Frequency: 2.0");
-                ui.label("  • Resolution: 50x50");
+            egui::Window::new(tr("Wave Generated")).show(egui_context.ctx_mut(), |ui| {
+                ui.label(tr("Successfully generated wave surface!"));
+                ui.label(tr("Parameters:"));
+                ui.label(fill("  • Amplitude: {}", &[&"1.0"]));
+                ui.label(fill("  • Wave vector: {}", &[&"(0.5, 0.3)"]));
+                ui.label(fill("  • Frequency: {}", &[&"2.0"]));
+                ui.label(fill("  • Resolution: {}", &[&"50x50"]));
             });
         }
     }
@@ -810,6 +1296,8 @@ fn handle_wave_shader_generation(
             Mesh3d(meshes.add(plane_mesh.clone())),
             MeshMaterial3d(wave_materials.add(wave_material)),
             Transform::from_translation(position),
+            WaveSurface,
+            Name::new("GPU Wave Surface"),
         ));
 
         // Clear current model data
@@ -821,17 +1309,17 @@ fn handle_wave_shader_generation(
         );
 
         if window_exists {
-            egui::Window::new("GPU Wave Generated").show(egui_context.ctx_mut(), |ui| {
-                ui.label("Successfully generated GPU shader wave surface!");
-                ui.label("Features:");
-                ui.label("  • Real-time GPU wave calculation");
-                ui.label("  • Animated wave motion");
-                ui.label("  • Dynamic lighting with normals");
-                ui.label("Parameters:");
-                ui.label("  • Amplitude: 3.0");
-                ui.label("  • Wave vector: (1.0, 1.0)");
-                ui.label("  • Frequency: 2.0");
-                ui.label("  • Resolution: 50x50");
+            egui::Window::new(tr("GPU Wave Generated")).show(egui_context.ctx_mut(), |ui| {
+                ui.label(tr("Successfully generated GPU shader wave surface!"));
+                ui.label(tr("Features:"));
+                ui.label(tr("  • Real-time GPU wave calculation"));
+                ui.label(tr("  • Animated wave motion"));
+                ui.label(tr("  • Dynamic lighting with normals"));
+                ui.label(tr("Parameters:"));
+                ui.label(fill("  • Amplitude: {}", &[&"3.0"]));
+                ui.label(fill("  • Wave vector: {}", &[&"(1.0, 1.0)"]));
+                ui.label(fill("  • Frequency: {}", &[&"2.0"]));
+                ui.label(fill("  • Resolution: {}", &[&"50x50"]));
             });
         }
     }
@@ -848,7 +1336,7 @@ pub fn clear_existing_models_silent(
     if mesh_count > 0 {
         // Iterate through all user imported mesh entities and delete them
         for entity in mesh_entities.iter() {
-            commands.entity(entity).despawn();
+            commands.entity(entity).despawn_recursive();
         }
 
         // Clear current model data
@@ -873,7 +1361,7 @@ fn handle_clear_all_meshes(
 
         if mesh_count > 0 {
             for entity in mesh_entities.iter() {
-                commands.entity(entity).despawn();
+                commands.entity(entity).despawn_recursive();
             }
 
             // Clear current model data
@@ -882,15 +1370,15 @@ fn handle_clear_all_meshes(
             println!("Cleared {} user mesh entities", mesh_count);
 
             if window_exists {
-                egui::Window::new("Clear Complete").show(egui_context.ctx_mut(), |ui| {
-                    ui.label(format!("Successfully cleared {} meshes", mesh_count));
+                egui::Window::new(tr("Clear Complete")).show(egui_context.ctx_mut(), |ui| {
+                    ui.label(fill("Successfully cleared {} meshes", &[&mesh_count]));
                 });
             }
         } else {
             println!("No user meshes in scene to clear");
             if window_exists {
-                egui::Window::new("Notice").show(egui_context.ctx_mut(), |ui| {
-                    ui.label("No user meshes in scene to clear");
+                egui::Window::new(tr("Notice")).show(egui_context.ctx_mut(), |ui| {
+                    ui.label(tr("No user meshes in scene to clear"));
                 });
             }
         }
@@ -898,12 +1386,14 @@ fn handle_clear_all_meshes(
 }
 
 /// Handle LOD generation events
-fn handle_lod_generation(
+#[allow(clippy::too_many_arguments)]
+pub fn handle_lod_generation(
     mut commands: Commands,
     mut lod_events: EventReader<events::GenerateLODEvent>,
     mut meshes: ResMut<Assets<Mesh>>,
     current_model: Res<CurrentModelData>,
     model_entities: Query<Entity, (With<UserModelMesh>, Without<crate::lod::LODManager>)>,
+    gpu_simplifier: Option<Res<crate::render::GpuSimplifier>>,
     mut egui_context: EguiContexts,
     windows: Query<&Window>,
 ) {
@@ -916,7 +1406,13 @@ fn handle_lod_generation(
             for entity in model_entities.iter() {
                 match crate::lod::LODManager::new(geometry.clone(), &mut meshes) {
                     Ok(lod_manager) => {
-                        commands.entity(entity).insert(lod_manager);
+                        commands.entity(entity).insert((
+                            lod_manager,
+                            crate::lod::LODGenerationTask::spawn(
+                                geometry,
+                                gpu_simplifier.as_deref(),
+                            ),
+                        ));
                         entities_processed += 1;
                         println!("Successfully created LOD manager for entity {:?}", entity);
                     }
@@ -935,80 +1431,202 @@ fn handle_lod_generation(
                     entities_processed
                 );
                 if window_exists {
-                    egui::Window::new("LOD Generation Complete").show(
+                    egui::Window::new(tr("LOD Generation Complete")).show(
                         egui_context.ctx_mut(),
                         |ui| {
-                            ui.label(format!(
+                            ui.label(fill(
                                 "Successfully generated LOD for {} models",
-                                entities_processed
+                                &[&entities_processed],
                             ));
-                            ui.label("LOD will automatically switch based on camera distance");
+                            ui.label(tr("LOD will automatically switch based on camera distance"));
                         },
                     );
                 }
             } else {
                 println!("No model entities found that can generate LOD");
                 if window_exists {
-                    egui::Window::new("Notice").show(egui_context.ctx_mut(), |ui| {
-                        ui.label("No models found that can generate LOD");
-                        ui.label("Please import a model first, or LOD already exists");
+                    egui::Window::new(tr("Notice")).show(egui_context.ctx_mut(), |ui| {
+                        ui.label(tr("No models found that can generate LOD"));
+                        ui.label(tr("Please import a model first, or LOD already exists"));
                     });
                 }
             }
         } else {
             println!("Currently no geometry data, cannot generate LOD");
             if window_exists {
-                egui::Window::new("Error").show(egui_context.ctx_mut(), |ui| {
-                    ui.label("Currently no model data");
-                    ui.label("Please import a VTK file first");
+                egui::Window::new(tr("Error")).show(egui_context.ctx_mut(), |ui| {
+                    ui.label(tr("Currently no model data"));
+                    ui.label(tr("Please import a VTK file first"));
                 });
             }
         }
     }
 }
 
-/// Check for pending file load requests
-fn check_pending_file_load(
-    mut load_events: EventWriter<events::LoadModelEvent>,
-    mut time_series_events: EventWriter<TimeSeriesEvent>,
+/// Handle LOD export events
+///
+/// Writes the requested level of the model to binary STL; the file dialog and
+/// writing run on the IO task pool.
+pub fn handle_lod_export(
+    mut export_events: EventReader<events::ExportLODEvent>,
+    lod_managers: Query<&crate::lod::LODManager, With<UserModelMesh>>,
+    mut file_dialogs: ResMut<FileDialogs>,
 ) {
-    // Check for regular file loading
-    let temp_file = std::env::temp_dir().join("pending_file_load.txt");
-    if temp_file.exists() {
-        if let Ok(file_path_str) = std::fs::read_to_string(&temp_file) {
-            let file_path = PathBuf::from(file_path_str.trim());
-            if file_path.exists() {
-                println!(
-                    "Loading file from background thread: {}",
-                    file_path.display()
-                );
-                load_events.send(events::LoadModelEvent(file_path));
+    for events::ExportLODEvent(level) in export_events.read() {
+        let Some(lod_data) = lod_managers
+            .iter()
+            .find_map(|lod_manager| lod_manager.lod_meshes.get(level))
+        else {
+            println!("{:?} level has not been generated yet", level);
+            continue;
+        };
+
+        let geometry = lod_data.geometry.clone();
+        let level = *level;
+        let dialog = AsyncFileDialog::new()
+            .add_filter("STL", &["stl"])
+            .set_file_name(format!("model_{:?}.stl", level).to_lowercase())
+            .set_directory(std::env::var("HOME").unwrap_or_else(|_| "/".to_string()));
+        file_dialogs.open(async move {
+            let file = dialog.save_file().await?;
+            let path = file.path();
+            match mesh::export::write_stl_binary(&geometry, path) {
+                Ok(()) => println!(
+                    "{:?} level with {} triangles exported to {}",
+                    level,
+                    geometry.indices.len() / 3,
+                    path.display()
+                ),
+                Err(e) => eprintln!("Failed to export {:?} level: {}", level, e),
+            }
+            None
+        });
+    }
+}
+
+/// Event loading a time series source
+///
+/// # Parameters
+/// - `source`: Folder of `.vtu` files, ParaView collection file (`.pvd`), or Exodus,
+///   netCDF or XDMF file holding all time steps
+///
+/// # Returns
+/// - `Some(TimeSeriesEvent)`: Load event of the time series files
+/// - `None`: The source has no time series files
+pub fn time_series_event(source: &Path) -> Option<TimeSeriesEvent> {
+    if source.is_dir() {
+        // Scan VTK files in the folder
+        let mut vtk_files: Vec<PathBuf> = std::fs::read_dir(source)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("vtu"))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Sort by numerical order (ensure correct time sequence)
+        vtk_files.sort_by(|a, b| {
+            // Extract numeric part from filename for comparison
+            let extract_number = |path: &Path| -> Option<u32> {
+                let file_stem = path.file_stem()?.to_str()?;
+                // Find the number after the last underscore
+                if let Some(pos) = file_stem.rfind('_') {
+                    file_stem[pos + 1..].parse().ok()
+                } else {
+                    // If no underscore, try to parse the whole filename as number
+                    file_stem.parse().ok()
+                }
+            };
+
+            match (extract_number(a), extract_number(b)) {
+                (Some(num_a), Some(num_b)) => num_a.cmp(&num_b),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => a.cmp(b),
+            }
+        });
+
+        println!("Found {} VTK files in time series", vtk_files.len());
+        let (Some(first), Some(last)) = (vtk_files.first(), vtk_files.last()) else {
+            eprintln!("No VTK files found in {}", source.display());
+            return None;
+        };
+        println!("First file: {}", first.display());
+        println!("Last file: {}", last.display());
+        return Some(TimeSeriesEvent::LoadSeries(vtk_files));
+    }
+
+    if mesh::exodus::is_exodus_file(source)
+        || mesh::climate::is_netcdf_file(source)
+        || mesh::xdmf::is_xdmf_file(source)
+    {
+        // The time steps are expanded when the series is loaded
+        return Some(TimeSeriesEvent::LoadSeries(vec![source.to_path_buf()]));
+    }
+
+    match mesh::pvd::read_pvd(source) {
+        Ok(entries) => {
+            let mut file_paths = Vec::new();
+            let mut times = Vec::new();
+            for entry in entries {
+                if entry.file.exists() {
+                    file_paths.push(entry.file);
+                    times.push(entry.time);
+                } else {
+                    eprintln!("Time series file not found: {}", entry.file.display());
+                }
             }
+            if file_paths.is_empty() {
+                return None;
+            }
+            Some(TimeSeriesEvent::LoadCollection(file_paths, times))
+        }
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", source.display(), e);
+            None
         }
-        let _ = std::fs::remove_file(&temp_file);
     }
+}
 
-    // Check for time series file loading
-    let time_series_file = std::env::temp_dir().join("pending_time_series.txt");
-    if time_series_file.exists() {
-        if let Ok(file_list_str) = std::fs::read_to_string(&time_series_file) {
-            let file_paths: Vec<PathBuf> = file_list_str
-                .lines()
-                .filter_map(|line| {
-                    let path = PathBuf::from(line.trim());
-                    if path.exists() {
-                        Some(path)
-                    } else {
-                        None
-                    }
-                })
-                .collect();
+/// Apply the selections of closed file dialogs
+fn receive_file_dialog_selections(
+    mut file_dialogs: ResMut<FileDialogs>,
+    mut load_events: EventWriter<events::LoadModelEvent>,
+    mut time_series_events: EventWriter<TimeSeriesEvent>,
+    mut recent_files: ResMut<crate::recent::RecentFiles>,
+    mut color_bar_config: ResMut<ColorBarConfig>,
+    mut recovery: ResMut<crate::session::SessionRecovery>,
+) {
+    let mut selections = Vec::new();
+    file_dialogs
+        .tasks
+        .retain_mut(|task| match block_on(future::poll_once(task)) {
+            Some(selection) => {
+                selections.extend(selection);
+                false
+            }
+            None => true,
+        });
 
-            if !file_paths.is_empty() {
-                println!("Loading time series with {} files", file_paths.len());
-                time_series_events.send(TimeSeriesEvent::LoadSeries(file_paths));
+    for selection in selections {
+        match selection {
+            DialogSelection::Model(file_path) => {
+                println!("Loading file from file dialog: {}", file_path.display());
+                recent_files.add(&file_path, crate::recent::RecentKind::Model);
+                load_events.send(events::LoadModelEvent(file_path));
+            }
+            // A folder, collection or multi-step file
+            DialogSelection::TimeSeries { source, event } => {
+                recent_files.add(&source, crate::recent::RecentKind::TimeSeries);
+                time_series_events.send(event);
+            }
+            DialogSelection::ColorMap(name) => {
+                color_bar_config.color_map_name = name;
+                color_bar_config.has_changed = true;
             }
+            DialogSelection::Project(snapshot) => recovery.restore(*snapshot),
         }
-        let _ = std::fs::remove_file(&time_series_file);
     }
 }
@@ -2,9 +2,17 @@
 //!
 //! This module provides color bar functionality for displaying color mappings.
 use crate::mesh;
-use crate::mesh::color_maps::{get_color_map, ColorMap, ColorMapper, ColorMappingConfig};
+use crate::mesh::color_maps::{
+    color_map_names, get_color_map, register_color_map, ColorMap, ColorMapper, ColorMappingConfig,
+    ColorMode, ValueScale, DEFAULT_NAN_COLOR, MAX_CATEGORIES,
+};
+use crate::mesh::color_presets::load_color_presets;
+use crate::render::CvdMode;
+use crate::ui::{DialogSelection, FileDialogs};
 use bevy::prelude::*;
 use bevy_egui::*;
+use rfd::AsyncFileDialog;
+use std::future::Future;
 
 /// Color bar configuration
 ///
@@ -23,6 +31,26 @@ pub struct ColorBarConfig {
     pub title: String,
     /// Value unit
     pub unit: String,
+    /// Number of ticks on the legend, including minimum and maximum
+    pub tick_count: usize,
+    /// Decimals of the tick labels
+    pub precision: usize,
+    /// Map the logarithm of the values, for fields spanning several orders of magnitude
+    pub log_scale: bool,
+    /// Continuous, binned or categorical mapping
+    pub mode: ColorMode,
+    /// Number of bins in binned mode
+    pub bins: usize,
+    /// Color of NaN and infinite values
+    pub nan_color: [f32; 4],
+    /// Color of values below the range, `None` uses the lowest color
+    pub below_color: Option<[f32; 4]>,
+    /// Color of values above the range, `None` uses the highest color
+    pub above_color: Option<[f32; 4]>,
+    /// Categories of the colored attribute, listed by the legend in categorical mode
+    pub categories: Vec<i64>,
+    /// Attribute to color by, `None` picks the first scalar automatically
+    pub attribute: Option<(String, mesh::AttributeLocation)>,
     /// Flag indicating if configuration has changed
     pub has_changed: bool,
 }
@@ -35,8 +63,18 @@ impl Default for ColorBarConfig {
             color_map_name: "default".to_string(),
             min_value: -1.0,
             max_value: 1.0,
-            title: "value".to_string(),
+            title: "".to_string(),
             unit: "".to_string(),
+            tick_count: 5,
+            precision: 2,
+            log_scale: false,
+            mode: ColorMode::Continuous,
+            bins: 8,
+            nan_color: DEFAULT_NAN_COLOR,
+            below_color: None,
+            above_color: None,
+            categories: Vec::new(),
+            attribute: None,
             has_changed: false,
         }
     }
@@ -45,18 +83,27 @@ impl Default for ColorBarConfig {
 /// Color bar UI panel
 ///
 /// Displays a color bar panel on the right side, providing the following features:
+/// - Attribute selection (dropdown menu)
 /// - Color map selection (dropdown menu)
 /// - Value range control (min/max value input boxes)
-/// - Color gradient bar display
-/// - Label settings (title and unit)
+/// - Color legend with ticks and value labels
+/// - Label settings (title, unit, ticks and decimals)
 /// - Hide color bar button
 ///
 /// # Parameters
 /// - `contexts`: egui context for rendering UI
 /// - `color_bar_config`: Color bar configuration resource
+/// - `attributes`: Names and locations of the model attributes to choose from
+/// - `active_attribute`: Name of the attribute the model is colored by
+/// - `cvd`: Simulated color vision deficiency, applied to the gradient like the 3D view
+/// - `file_dialogs`: Open file dialogs, the preset import dialog is added to them
 pub fn render_color_bar_inline(
     contexts: &mut EguiContexts,
     mut color_bar_config: ResMut<ColorBarConfig>,
+    attributes: &[(String, mesh::AttributeLocation)],
+    active_attribute: Option<&str>,
+    cvd: CvdMode,
+    file_dialogs: &mut FileDialogs,
 ) {
     egui::SidePanel::right("color_bar_panel")
         .min_width(180.0) // Minimum width
@@ -71,17 +118,43 @@ pub fn render_color_bar_inline(
 
                 ui.separator();
 
+                // Attribute selection
+                ui.label("Attribute:");
+                let mut attribute = color_bar_config.attribute.clone();
+                egui::ComboBox::from_id_salt("color_attribute")
+                    .selected_text(
+                        attribute
+                            .as_ref()
+                            .map_or("Automatic".to_string(), attribute_label),
+                    )
+                    .width(150.0)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut attribute, None, "Automatic");
+                        for entry in attributes {
+                            ui.selectable_value(
+                                &mut attribute,
+                                Some(entry.clone()),
+                                attribute_label(entry),
+                            );
+                        }
+                    });
+                if attribute != color_bar_config.attribute {
+                    color_bar_config.attribute = attribute;
+                    color_bar_config.has_changed = true;
+                }
+
+                ui.separator();
+
                 // Color map selection
                 ui.label("Color Map:");
                 egui::ComboBox::from_id_salt("color_map")
                     .selected_text(&color_bar_config.color_map_name)
                     .width(100.0)
                     .show_ui(ui, |ui| {
-                        let color_maps = ["default", "viridis", "hot", "cool", "warm"];
-                        for &color_map in &color_maps {
+                        for color_map in color_map_names() {
                             let value = ui.selectable_value(
                                 &mut color_bar_config.color_map_name,
-                                color_map.to_string(),
+                                color_map.clone(),
                                 color_map,
                             );
                             if value.changed() {
@@ -89,6 +162,9 @@ pub fn render_color_bar_inline(
                             }
                         }
                     });
+                if ui.button("Import ParaView Preset...").clicked() {
+                    file_dialogs.open(import_color_presets());
+                }
 
                 ui.separator();
 
@@ -117,11 +193,72 @@ pub fn render_color_bar_inline(
                     }
                 });
 
+                if ui
+                    .checkbox(&mut color_bar_config.log_scale, "Logarithmic")
+                    .changed()
+                {
+                    color_bar_config.has_changed = true;
+                }
+                if color_bar_config.log_scale
+                    && color_bar_config.min_value.min(color_bar_config.max_value) <= 0.0
+                {
+                    ui.label(
+                        egui::RichText::new("Min ≤ 0: the range starts six decades below Max")
+                            .small()
+                            .weak(),
+                    );
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label("Mapping:");
+                    let mut mode = color_bar_config.mode;
+                    egui::ComboBox::from_id_salt("color_mode")
+                        .selected_text(match mode {
+                            ColorMode::Continuous => "Continuous",
+                            ColorMode::Binned => "Bins",
+                            ColorMode::Categorical => "Categories",
+                        })
+                        .width(90.0)
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut mode, ColorMode::Continuous, "Continuous");
+                            ui.selectable_value(&mut mode, ColorMode::Binned, "Bins");
+                            ui.selectable_value(&mut mode, ColorMode::Categorical, "Categories");
+                        });
+                    if mode != color_bar_config.mode {
+                        color_bar_config.mode = mode;
+                        color_bar_config.has_changed = true;
+                    }
+                });
+                if color_bar_config.mode == ColorMode::Binned {
+                    ui.horizontal(|ui| {
+                        ui.label("Bins:");
+                        let bins_response =
+                            ui.add(egui::DragValue::new(&mut color_bar_config.bins).range(2..=32));
+                        if bins_response.changed() {
+                            color_bar_config.has_changed = true;
+                        }
+                    });
+                }
+
+                // Colors of NaN and out-of-range values
+                ui.horizontal(|ui| {
+                    ui.label("NaN:");
+                    if color_button(ui, &mut color_bar_config.nan_color) {
+                        color_bar_config.has_changed = true;
+                    }
+                });
+                if optional_color_row(ui, "Below range", &mut color_bar_config.below_color) {
+                    color_bar_config.has_changed = true;
+                }
+                if optional_color_row(ui, "Above range", &mut color_bar_config.above_color) {
+                    color_bar_config.has_changed = true;
+                }
+
                 ui.separator();
 
                 // Color map selection and rendering
                 let color_map = get_color_map(&color_bar_config.color_map_name);
-                render_color_gradient_simple(ui, &color_map, &color_bar_config);
+                render_color_legend(ui, &color_map, &color_bar_config, active_attribute, cvd);
 
                 ui.separator();
 
@@ -132,7 +269,8 @@ pub fn render_color_bar_inline(
                     ui.label("Title:");
                     let title_response = ui.add_sized(
                         [80.0, 20.0],
-                        egui::TextEdit::singleline(&mut color_bar_config.title),
+                        egui::TextEdit::singleline(&mut color_bar_config.title)
+                            .hint_text("attribute"),
                     );
                     if title_response.changed() {
                         color_bar_config.has_changed = true;
@@ -150,6 +288,14 @@ pub fn render_color_bar_inline(
                     }
                 });
 
+                ui.horizontal(|ui| {
+                    // Labels only, the mesh colors stay as they are
+                    ui.label("Ticks:");
+                    ui.add(egui::DragValue::new(&mut color_bar_config.tick_count).range(2..=11));
+                    ui.label("Decimals:");
+                    ui.add(egui::DragValue::new(&mut color_bar_config.precision).range(0..=6));
+                });
+
                 ui.separator();
 
                 // Hide color bar button
@@ -163,95 +309,259 @@ pub fn render_color_bar_inline(
         });
 }
 
-/// Render color gradient bar and value labels
+/// Pick a ParaView preset file and register its color maps
+///
+/// # Returns
+/// - Dialog resolving to the name of the first imported color map, `None` if
+///   cancelled or failed
+fn import_color_presets() -> impl Future<Output = Option<DialogSelection>> {
+    let dialog = AsyncFileDialog::new()
+        .add_filter("ParaView color map preset", &["json", "xml"])
+        .set_directory(std::env::var("HOME").unwrap_or_else(|_| "/".to_string()));
+    async move {
+        let file = dialog.pick_file().await?;
+        let path = file.path();
+        match load_color_presets(path) {
+            Ok(maps) => {
+                let names: Vec<String> = maps.into_iter().map(register_color_map).collect();
+                println!(
+                    "Imported {} color map(s) from {}: {}",
+                    names.len(),
+                    path.display(),
+                    names.join(", ")
+                );
+                names.into_iter().next().map(DialogSelection::ColorMap)
+            }
+            Err(e) => {
+                println!("Failed to import color map presets: {}", e);
+                None
+            }
+        }
+    }
+}
+
+/// Dropdown label of an attribute: its name and location
+fn attribute_label((name, location): &(String, mesh::AttributeLocation)) -> String {
+    match location {
+        mesh::AttributeLocation::Point => format!("{} (Point)", name),
+        mesh::AttributeLocation::Cell => format!("{} (Cell)", name),
+    }
+}
+
+/// Format a legend value with the configured number of decimals, switching to
+/// scientific notation for very large or very small magnitudes
+///
+/// # Parameters
+/// - `value`: Value to format
+/// - `precision`: Number of decimals
+/// - `unit`: Unit appended to the number
+pub fn format_legend_value(value: f32, precision: usize, unit: &str) -> String {
+    let magnitude = value.abs();
+    let smallest = 10f32.powi(-(precision as i32));
+    if magnitude >= 1e4 || (magnitude > 0.0 && magnitude < smallest) {
+        format!("{:.*e}{}", precision, value, unit)
+    } else {
+        format!("{:.*}{}", precision, value, unit)
+    }
+}
+
+/// Render the color legend: gradient strip with ticks and value labels
+///
+/// The title is the configured title, or the name of the colored attribute when
+/// the title is empty.
 ///
 /// # Parameters
 /// - `ui`: egui UI context
 /// - `color_map`: Currently used color map
-/// - `config`: Color bar configuration, including value range and style settings
-fn render_color_gradient_simple(ui: &mut egui::Ui, color_map: &ColorMap, config: &ColorBarConfig) {
-    // Fixed dimensions
+/// - `config`: Color bar configuration, including value range and label settings
+/// - `attribute`: Name of the attribute the model is colored by
+/// - `cvd`: Simulated color vision deficiency
+fn render_color_legend(
+    ui: &mut egui::Ui,
+    color_map: &ColorMap,
+    config: &ColorBarConfig,
+    attribute: Option<&str>,
+    cvd: CvdMode,
+) {
+    // Fixed dimensions, the margin keeps the end labels inside the panel
     let bar_width = 30.0;
     let bar_height = 250.0;
+    let margin = 8.0;
+    let tick_length = 6.0;
 
-    // Ensure minimum value is less than maximum value
-    let min_val = config.min_value.min(config.max_value);
-    let max_val = config.min_value.max(config.max_value);
-    let value_range = max_val - min_val;
+    // Same placement of values as the mesh colors
+    let mut scale = ValueScale::new(config.min_value, config.max_value, config.log_scale);
+    if config.mode == ColorMode::Binned {
+        scale = scale.with_bins(config.bins);
+    }
 
     // Title
-    if !config.title.is_empty() {
-        ui.label(&config.title);
+    let title = if config.title.is_empty() {
+        attribute.unwrap_or_default()
+    } else {
+        &config.title
+    };
+    if !title.is_empty() {
+        ui.label(egui::RichText::new(title).strong());
         ui.add_space(5.0);
     }
 
-    // Horizontal layout
-    ui.horizontal(|ui| {
-        // Color bar
-        let (rect, _) =
-            ui.allocate_exact_size(egui::Vec2::new(bar_width, bar_height), egui::Sense::hover());
-
-        if ui.is_rect_visible(rect) {
-            let painter = ui.painter();
-
-            // Draw color gradient
-            let segments = 50;
-            let segment_height = bar_height / segments as f32;
-
-            for i in 0..segments {
-                let t = 1.0 - (i as f32 / (segments - 1) as f32);
-                let color_rgba = color_map.get_interpolated_color(t);
-
-                let color = egui::Color32::from_rgba_premultiplied(
-                    (color_rgba[0] * 255.0) as u8,
-                    (color_rgba[1] * 255.0) as u8,
-                    (color_rgba[2] * 255.0) as u8,
-                    (color_rgba[3] * 255.0) as u8,
-                );
-
-                let segment_rect = egui::Rect::from_min_size(
-                    egui::Pos2::new(rect.min.x, rect.min.y + i as f32 * segment_height),
-                    egui::Vec2::new(bar_width, segment_height + 1.0),
-                );
-
-                painter.rect_filled(segment_rect, 0.0, color);
-            }
+    if config.mode == ColorMode::Categorical {
+        render_category_legend(ui, color_map, config, cvd);
+        legend_swatch(ui, legend_color(config.nan_color, cvd), "NaN".to_string());
+        return;
+    }
 
-            // Draw border
-            painter.rect_stroke(rect, 1.0, egui::Stroke::new(1.0, egui::Color32::GRAY));
-        }
+    let (area, _) = ui.allocate_exact_size(
+        egui::Vec2::new(ui.available_width(), bar_height + 2.0 * margin),
+        egui::Sense::hover(),
+    );
+    let rect = egui::Rect::from_min_size(
+        area.min + egui::Vec2::new(0.0, margin),
+        egui::Vec2::new(bar_width, bar_height),
+    );
+    let painter = ui.painter();
+
+    // Draw color gradient, maximum at the top
+    let segments = 64;
+    let segment_height = bar_height / segments as f32;
+    for i in 0..segments {
+        let t = scale.quantize(1.0 - (i as f32 + 0.5) / segments as f32);
+        let color = legend_color(color_map.get_interpolated_color(t), cvd);
+
+        let segment_rect = egui::Rect::from_min_size(
+            egui::Pos2::new(rect.min.x, rect.min.y + i as f32 * segment_height),
+            egui::Vec2::new(bar_width, segment_height + 1.0),
+        );
+        painter.rect_filled(segment_rect, 0.0, color);
+    }
 
-        ui.add_space(8.0);
+    // Draw border
+    painter.rect_stroke(rect, 1.0, egui::Stroke::new(1.0, egui::Color32::GRAY));
 
-        // Value labels
-        ui.vertical(|ui| {
-            let format_value = |val: f32, unit: &str| {
-                if val.abs() < 1000.0 {
-                    format!("{:.2}{}", val, unit)
-                } else {
-                    format!("{:.1e}{}", val, unit)
-                }
-            };
+    // Ticks and labels at even intervals (in decades on logarithmic scales),
+    // at the bin edges for binned colors
+    let text_color = ui.visuals().text_color();
+    let font = egui::TextStyle::Small.resolve(ui.style());
+    let intervals = match scale.bins() {
+        0 => config.tick_count.max(2) - 1,
+        bins => bins,
+    };
+    for tick in 0..=intervals {
+        let fraction = tick as f32 / intervals as f32;
+        let y = rect.max.y - fraction * bar_height;
+        let value = scale.value_at(fraction);
+        painter.line_segment(
+            [
+                egui::Pos2::new(rect.max.x, y),
+                egui::Pos2::new(rect.max.x + tick_length, y),
+            ],
+            egui::Stroke::new(1.0, text_color),
+        );
+        painter.text(
+            egui::Pos2::new(rect.max.x + tick_length + 3.0, y),
+            egui::Align2::LEFT_CENTER,
+            format_legend_value(value, config.precision, &config.unit),
+            font.clone(),
+            text_color,
+        );
+    }
 
-            // Maximum value
-            ui.label(format_value(max_val, &config.unit));
+    // Colors outside the gradient
+    if let Some(color) = config.above_color {
+        legend_swatch(ui, legend_color(color, cvd), "Above range".to_string());
+    }
+    if let Some(color) = config.below_color {
+        legend_swatch(ui, legend_color(color, cvd), "Below range".to_string());
+    }
+    legend_swatch(ui, legend_color(config.nan_color, cvd), "NaN".to_string());
+}
 
-            // Fixed spacing
-            ui.add_space(95.0);
+/// Legend of categorical colors: one swatch and value per category
+fn render_category_legend(
+    ui: &mut egui::Ui,
+    color_map: &ColorMap,
+    config: &ColorBarConfig,
+    cvd: CvdMode,
+) {
+    if config.categories.is_empty() {
+        ui.label(egui::RichText::new("No categories").weak());
+        return;
+    }
+    egui::ScrollArea::vertical()
+        .id_salt("color_categories")
+        .max_height(250.0)
+        .show(ui, |ui| {
+            for (index, category) in config.categories.iter().enumerate() {
+                let color = ColorMapper::category_color(color_map, index, config.categories.len());
+                legend_swatch(
+                    ui,
+                    legend_color(color, cvd),
+                    format!("{}{}", category, config.unit),
+                );
+            }
+            if config.categories.len() == MAX_CATEGORIES {
+                ui.label(
+                    egui::RichText::new(format!("First {} values only", MAX_CATEGORIES))
+                        .small()
+                        .weak(),
+                );
+            }
+        });
+}
 
-            // Middle value
-            let mid_val = min_val + value_range * 0.5;
-            ui.label(format_value(mid_val, &config.unit));
+/// RGB color button for an RGBA color
+///
+/// # Returns
+/// - Whether the color changed
+fn color_button(ui: &mut egui::Ui, color: &mut [f32; 4]) -> bool {
+    let mut rgb = [color[0], color[1], color[2]];
+    let changed = ui.color_edit_button_rgb(&mut rgb).changed();
+    if changed {
+        *color = [rgb[0], rgb[1], rgb[2], 1.0];
+    }
+    changed
+}
 
-            // Fixed spacing
-            ui.add_space(95.0);
+/// Checkbox enabling an optional color, with its color button
+///
+/// # Returns
+/// - Whether the color was enabled, disabled or changed
+fn optional_color_row(ui: &mut egui::Ui, label: &str, color: &mut Option<[f32; 4]>) -> bool {
+    ui.horizontal(|ui| {
+        let mut enabled = color.is_some();
+        let mut changed = ui.checkbox(&mut enabled, label).changed();
+        if changed {
+            *color = enabled.then_some([1.0, 1.0, 1.0, 1.0]);
+        }
+        if let Some(color) = color {
+            changed |= color_button(ui, color);
+        }
+        changed
+    })
+    .inner
+}
 
-            // Minimum value
-            ui.label(format_value(min_val, &config.unit));
-        });
+/// Legend row: a color swatch and its label
+fn legend_swatch(ui: &mut egui::Ui, color: egui::Color32, label: String) {
+    ui.horizontal(|ui| {
+        let (rect, _) = ui.allocate_exact_size(egui::Vec2::new(24.0, 14.0), egui::Sense::hover());
+        ui.painter().rect_filled(rect, 2.0, color);
+        ui.label(label);
     });
 }
 
+/// Legend color of a color map color, with the simulated color vision deficiency
+fn legend_color(color: [f32; 4], cvd: CvdMode) -> egui::Color32 {
+    let rgb = cvd.simulate_srgb([color[0], color[1], color[2]]);
+    egui::Color32::from_rgba_premultiplied(
+        (rgb[0] * 255.0) as u8,
+        (rgb[1] * 255.0) as u8,
+        (rgb[2] * 255.0) as u8,
+        (color[3] * 255.0) as u8,
+    )
+}
+
 /// Monitor color bar configuration changes and apply to existing meshes
 ///
 /// Real-time monitoring of color bar configuration changes and updating mesh colors
@@ -264,11 +574,13 @@ fn render_color_gradient_simple(ui: &mut egui::Ui, color_map: &ColorMap, config:
 /// - `current_model`: Current model data resource
 /// - `meshes`: Mesh resource collection
 /// - `mesh_entities`: User model mesh entity query
+/// - `time_series`: Loaded animation, recolored from its current time step
 pub fn apply_color_map_changes(
     mut color_bar_config: ResMut<ColorBarConfig>,
     current_model: Res<crate::ui::CurrentModelData>,
     mut meshes: ResMut<Assets<Mesh>>,
     mesh_entities: Query<&Mesh3d, With<crate::ui::UserModelMesh>>,
+    time_series: Option<ResMut<crate::animation::TimeSeriesAsset>>,
 ) {
     // Only update when has_changed is true
     if !color_bar_config.has_changed {
@@ -278,6 +590,13 @@ pub fn apply_color_map_changes(
     // Reset change flag
     color_bar_config.has_changed = false;
 
+    // Animation frames carry their own values, recolor the current one too
+    if let Some(mut time_series) = time_series {
+        if time_series.is_step2_complete {
+            time_series.colors_need_update = true;
+        }
+    }
+
     // Check if current model data exists
     let Some(ref geometry) = current_model.geometry else {
         println!("No geometry data available for color map update");
@@ -311,6 +630,53 @@ pub fn apply_color_map_changes(
     }
 }
 
+/// Selected attribute and mapping mode the legend categories were collected for
+type LegendSource = (Option<(String, mesh::AttributeLocation)>, ColorMode);
+
+/// Keep the legend categories in step with the colored attribute
+///
+/// Categories are collected again when the model data, the selected attribute
+/// or the mapping mode changes; outside categorical mode the list is empty.
+///
+/// # Parameters
+/// - `color_bar_config`: Color bar configuration receiving the categories
+/// - `current_model`: Current model data resource
+/// - `shown`: Attribute and mode of the current categories
+pub fn update_legend_categories(
+    mut color_bar_config: ResMut<ColorBarConfig>,
+    current_model: Res<crate::ui::CurrentModelData>,
+    mut shown: Local<Option<LegendSource>>,
+) {
+    if color_bar_config.mode != ColorMode::Categorical {
+        if !color_bar_config.categories.is_empty() {
+            color_bar_config.categories.clear();
+        }
+        *shown = None;
+        return;
+    }
+    let key = (color_bar_config.attribute.clone(), color_bar_config.mode);
+    if !current_model.is_changed() && shown.as_ref() == Some(&key) {
+        return;
+    }
+    *shown = Some(key);
+
+    let categories = current_model
+        .geometry
+        .as_ref()
+        .and_then(|geometry| {
+            let (name, location) =
+                ColorMapper::colored_attribute(geometry, color_bar_config.attribute.as_ref())?;
+            match geometry.get_attributes(&name, location)? {
+                mesh::AttributeType::ColorScalar { .. } => None,
+                attribute => Some(ColorMapper::attribute_categories(attribute)),
+            }
+        })
+        .unwrap_or_default();
+    if categories != color_bar_config.categories {
+        color_bar_config.categories = categories;
+    }
+}
+
 /// Apply custom color mapping to mesh
 ///
 /// Applies the specified color map to the mesh based on color bar configuration.
@@ -334,6 +700,13 @@ pub fn apply_custom_color_mapping(
         min_value: color_bar_config.min_value,
         max_value: color_bar_config.max_value,
         use_custom_range: true, // Always use custom range from UI
+        attribute: color_bar_config.attribute.clone(),
+        log_scale: color_bar_config.log_scale,
+        mode: color_bar_config.mode,
+        bins: color_bar_config.bins,
+        nan_color: color_bar_config.nan_color,
+        below_color: color_bar_config.below_color,
+        above_color: color_bar_config.above_color,
     };
 
     ColorMapper::apply_scalar_attributes_with_color_map(geometry, mesh, &config)
@@ -22,6 +22,9 @@ pub struct ClearAllMeshesEvent;
 #[derive(Event)]
 pub struct GenerateLODEvent;
 
+#[derive(Event)]
+pub struct ExportLODEvent(pub crate::lod::LODLevel);
+
 impl Default for SubdivideMeshEvent {
     fn default() -> Self {
         Self
@@ -0,0 +1,405 @@
+//! # Vertex Editing Module
+//!
+//! This module provides a simple edit mode for quick mesh fixes:
+//! - Left click on the model: Select the closest vertex of the clicked triangle
+//! - Left drag on the selected vertex: Move it in the view plane
+//! - Edit panel: Type exact coordinates or restore the original position
+//!
+//! Changes are written back to `CurrentModelData` and the rendered mesh. If the
+//! model has LOD levels, they are regenerated once the edit is finished.
+
+use crate::camera::WorldModelCamera;
+use crate::lod::{LODGenerationTask, LODManager};
+use crate::mesh::export::point_to_csv;
+use crate::picking::{
+    cursor_ray_in_model_space, model_matrix, pick_surface, pointer_over_ui, ray_plane_intersection,
+    ray_sphere_distance, InteractionMode,
+};
+use crate::render::{GpuSimplifier, OverlayGizmos};
+use crate::ui::{CurrentModelData, ModelLoadedEvent, UserModelMesh};
+use crate::widgets::{widget_drag_input, WidgetState};
+use bevy::prelude::*;
+use bevy::render::mesh::VertexAttributeValues;
+use bevy::window::PrimaryWindow;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Vertex marker radius relative to the model size
+const MARKER_RADIUS_FACTOR: f32 = 0.008;
+
+const SELECTED_VERTEX_COLOR: Color = Color::srgb(1.0, 0.2, 0.2);
+
+/// Vertex editing state
+#[derive(Resource)]
+pub struct VertexEditState {
+    /// Currently selected vertex index
+    pub selected: Option<u32>,
+    /// Position of the selected vertex before any edit
+    pub original_position: Option<Vec3>,
+    /// Vertex marker radius
+    pub marker_radius: f32,
+    /// View-aligned drag plane (point, normal) in model space
+    drag_plane: Option<(Vec3, Vec3)>,
+    /// LOD levels need to be regenerated after the edit
+    lod_dirty: bool,
+}
+
+impl Default for VertexEditState {
+    fn default() -> Self {
+        Self {
+            selected: None,
+            original_position: None,
+            marker_radius: 0.01,
+            drag_plane: None,
+            lod_dirty: false,
+        }
+    }
+}
+
+impl VertexEditState {
+    fn clear_selection(&mut self) {
+        self.selected = None;
+        self.original_position = None;
+        self.drag_plane = None;
+    }
+}
+
+pub struct VertexEditPlugin;
+
+impl Plugin for VertexEditPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VertexEditState>().add_systems(
+            Update,
+            (
+                reset_vertex_edit_on_model_load,
+                vertex_edit_input.after(widget_drag_input),
+                vertex_edit_panel.after(EguiSet::InitContexts),
+                rebuild_lod_after_edit,
+                draw_selected_vertex,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Clear selection when a new model is loaded and adapt marker size
+fn reset_vertex_edit_on_model_load(
+    mut model_loaded_events: EventReader<ModelLoadedEvent>,
+    mut edit_state: ResMut<VertexEditState>,
+) {
+    for event in model_loaded_events.read() {
+        edit_state.clear_selection();
+        edit_state.lod_dirty = false;
+        if let (Some(min), Some(max)) = (event.bounds_min, event.bounds_max) {
+            edit_state.marker_radius = (max - min).length().max(1e-3) * MARKER_RADIUS_FACTOR;
+        }
+    }
+}
+
+/// Select and drag vertices
+#[allow(clippy::too_many_arguments)]
+fn vertex_edit_input(
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    interaction_mode: Res<InteractionMode>,
+    widget_state: Res<WidgetState>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<WorldModelCamera>>,
+    transform_query: Query<&GlobalTransform, With<UserModelMesh>>,
+    mut model_entities: Query<(&Mesh3d, Option<&mut LODManager>), With<UserModelMesh>>,
+    mut contexts: EguiContexts,
+    mut current_model: ResMut<CurrentModelData>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut edit_state: ResMut<VertexEditState>,
+) {
+    if *interaction_mode != InteractionMode::VertexEdit {
+        edit_state.drag_plane = None;
+        return;
+    }
+
+    if mouse_button_input.just_released(MouseButton::Left) {
+        if edit_state.drag_plane.take().is_some() {
+            println!("Vertex {:?} moved", edit_state.selected);
+        }
+        return;
+    }
+
+    let Some(ref geometry) = current_model.geometry else {
+        return;
+    };
+
+    let model_to_world = model_matrix(&transform_query);
+    let Some((ray_origin, ray_direction)) =
+        cursor_ray_in_model_space(&windows, &camera_query, model_to_world)
+    else {
+        return;
+    };
+
+    if mouse_button_input.just_pressed(MouseButton::Left) {
+        let alt_pressed =
+            keyboard_input.pressed(KeyCode::AltLeft) || keyboard_input.pressed(KeyCode::AltRight);
+        if alt_pressed || widget_state.is_dragging() || pointer_over_ui(&mut contexts) {
+            return;
+        }
+
+        // Grab the selected vertex to start dragging
+        if let Some(selected) = edit_state.selected {
+            let position = Vec3::from(geometry.vertices[selected as usize]);
+            let grab_radius = edit_state.marker_radius * 1.5;
+            if ray_sphere_distance(ray_origin, ray_direction, position, grab_radius).is_some() {
+                edit_state.drag_plane = Some((position, -ray_direction));
+                return;
+            }
+        }
+
+        // Otherwise select a new vertex
+        match pick_surface(geometry, ray_origin, ray_direction) {
+            Some(hit) => {
+                let vertex = hit.nearest_vertex(geometry);
+                edit_state.selected = Some(vertex);
+                edit_state.original_position = Some(Vec3::from(geometry.vertices[vertex as usize]));
+                println!("Selected vertex {}", vertex);
+            }
+            None => edit_state.clear_selection(),
+        }
+        return;
+    }
+
+    if mouse_button_input.pressed(MouseButton::Left) {
+        if let (Some(vertex), Some((plane_point, plane_normal))) =
+            (edit_state.selected, edit_state.drag_plane)
+        {
+            if let Some(position) =
+                ray_plane_intersection(ray_origin, ray_direction, plane_point, plane_normal)
+            {
+                move_vertex(
+                    vertex,
+                    position,
+                    &mut current_model,
+                    &mut meshes,
+                    &mut model_entities,
+                );
+                edit_state.lod_dirty = true;
+            }
+        }
+    }
+}
+
+/// Write a new vertex position to the geometry data and the rendered mesh
+///
+/// If the model has LOD levels, only LOD0 is updated here; the simplified levels
+/// are regenerated by `rebuild_lod_after_edit` once the edit is finished.
+fn move_vertex(
+    vertex: u32,
+    position: Vec3,
+    current_model: &mut CurrentModelData,
+    meshes: &mut Assets<Mesh>,
+    model_entities: &mut Query<(&Mesh3d, Option<&mut LODManager>), With<UserModelMesh>>,
+) {
+    let Some(ref mut geometry) = current_model.geometry else {
+        return;
+    };
+    let Some(vertex_position) = geometry.vertices.get_mut(vertex as usize) else {
+        return;
+    };
+    *vertex_position = position.to_array();
+
+    for (mesh3d, lod_manager) in model_entities.iter_mut() {
+        let mesh_handle = match lod_manager {
+            Some(mut lod_manager) => {
+                let Some(lod0) = lod_manager.lod_meshes.get_mut(&crate::lod::LODLevel::LOD0) else {
+                    continue;
+                };
+                if let Some(lod_vertex) = lod0.geometry.vertices.get_mut(vertex as usize) {
+                    *lod_vertex = position.to_array();
+                }
+                lod0.mesh_handle.clone()
+            }
+            None => mesh3d.0.clone(),
+        };
+
+        if let Some(mesh) = meshes.get_mut(&mesh_handle) {
+            update_mesh_vertex(mesh, vertex as usize, position);
+        }
+    }
+}
+
+/// Update a single position of a mesh and recompute its normals
+///
+/// Copies of the vertex made to keep sharp edges are moved along.
+fn update_mesh_vertex(mesh: &mut Mesh, vertex: usize, position: Vec3) {
+    let copies: Vec<usize> = crate::mesh::normals::source_vertices(mesh)
+        .map(|sources| {
+            sources
+                .iter()
+                .enumerate()
+                .skip(vertex + 1)
+                .filter(|(_, &source)| source as usize == vertex)
+                .map(|(copy, _)| copy)
+                .collect()
+        })
+        .unwrap_or_default();
+    if let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+    {
+        for index in std::iter::once(vertex).chain(copies) {
+            let Some(mesh_position) = positions.get_mut(index) else {
+                return;
+            };
+            *mesh_position = position.to_array();
+        }
+    }
+    mesh.compute_normals();
+}
+
+/// Regenerate LOD levels from the edited geometry once the mouse is released
+fn rebuild_lod_after_edit(
+    mut commands: Commands,
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    current_model: Res<CurrentModelData>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut lod_entities: Query<(Entity, &mut LODManager), With<UserModelMesh>>,
+    mut edit_state: ResMut<VertexEditState>,
+    gpu_simplifier: Option<Res<GpuSimplifier>>,
+) {
+    if !edit_state.lod_dirty || mouse_button_input.pressed(MouseButton::Left) {
+        return;
+    }
+    edit_state.lod_dirty = false;
+
+    let Some(ref geometry) = current_model.geometry else {
+        return;
+    };
+
+    for (entity, mut lod_manager) in lod_entities.iter_mut() {
+        match lod_manager.rebuild(geometry.clone(), &mut meshes) {
+            Ok(()) => {
+                commands.entity(entity).insert(LODGenerationTask::spawn(
+                    geometry,
+                    gpu_simplifier.as_deref(),
+                ));
+                println!("Regenerating LOD levels after vertex edit");
+            }
+            Err(e) => println!("Failed to regenerate LOD levels: {:?}", e),
+        }
+    }
+}
+
+/// Highlight the selected vertex
+fn draw_selected_vertex(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    interaction_mode: Res<InteractionMode>,
+    edit_state: Res<VertexEditState>,
+    current_model: Res<CurrentModelData>,
+    model_query: Query<&GlobalTransform, With<UserModelMesh>>,
+) {
+    if *interaction_mode != InteractionMode::VertexEdit {
+        return;
+    }
+    let (Some(vertex), Some(geometry)) = (edit_state.selected, current_model.geometry.as_ref())
+    else {
+        return;
+    };
+    let Some(position) = geometry.vertices.get(vertex as usize) else {
+        return;
+    };
+
+    let world_position = model_matrix(&model_query).transform_point3(Vec3::from(*position));
+    gizmos.sphere(
+        Isometry3d::from_translation(world_position),
+        edit_state.marker_radius,
+        SELECTED_VERTEX_COLOR,
+    );
+}
+
+/// Panel showing the selected vertex
+#[allow(clippy::too_many_arguments)]
+fn vertex_edit_panel(
+    mut contexts: EguiContexts,
+    mut interaction_mode: ResMut<InteractionMode>,
+    mut edit_state: ResMut<VertexEditState>,
+    mut current_model: ResMut<CurrentModelData>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut model_entities: Query<(&Mesh3d, Option<&mut LODManager>), With<UserModelMesh>>,
+    windows: Query<&Window>,
+) {
+    if *interaction_mode != InteractionMode::VertexEdit || windows.iter().next().is_none() {
+        return;
+    }
+
+    let selected_position = edit_state.selected.and_then(|vertex| {
+        current_model
+            .geometry
+            .as_ref()
+            .and_then(|geometry| geometry.vertices.get(vertex as usize))
+            .map(|position| Vec3::from(*position))
+    });
+
+    let mut new_position = None;
+    let mut close = false;
+
+    egui::Window::new("Vertex Edit")
+        .default_width(240.0)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label("Click on the model to select a vertex, drag it to move.");
+            ui.separator();
+
+            match (edit_state.selected, selected_position) {
+                (Some(vertex), Some(mut position)) => {
+                    ui.label(format!("Vertex: {}", vertex));
+                    let mut changed = false;
+                    ui.horizontal(|ui| {
+                        ui.label("Position:");
+                        for component in [&mut position.x, &mut position.y, &mut position.z] {
+                            changed |= ui
+                                .add(
+                                    egui::DragValue::new(component)
+                                        .speed(edit_state.marker_radius * 0.1),
+                                )
+                                .changed();
+                        }
+                    });
+                    if changed {
+                        new_position = Some(position);
+                    }
+
+                    ui.horizontal(|ui| {
+                        if let Some(original) = edit_state.original_position {
+                            if ui.button("Reset Vertex").clicked() {
+                                new_position = Some(original);
+                            }
+                        }
+                        if let Some(geometry) = current_model.geometry.as_ref() {
+                            if ui.button("Copy Values").clicked() {
+                                ui.ctx().copy_text(point_to_csv(geometry, vertex));
+                                println!("Copied values of vertex {} to clipboard", vertex);
+                            }
+                        }
+                    });
+                }
+                _ => {
+                    ui.label("No vertex selected");
+                }
+            }
+
+            ui.separator();
+            if ui.button("Close").clicked() {
+                close = true;
+            }
+        });
+
+    if let (Some(vertex), Some(position)) = (edit_state.selected, new_position) {
+        move_vertex(
+            vertex,
+            position,
+            &mut current_model,
+            &mut meshes,
+            &mut model_entities,
+        );
+        edit_state.lod_dirty = true;
+    }
+
+    if close {
+        edit_state.clear_selection();
+        *interaction_mode = InteractionMode::Navigate;
+    }
+}
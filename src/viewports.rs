@@ -0,0 +1,504 @@
+//! # Viewports Module
+//!
+//! This module splits the window into up to four viewports:
+//! - Viewport 1 is the main camera, always at the top left, so picking, tools
+//!   and labels keep working in it; the other viewports get their own camera,
+//!   starting with the view of the main camera
+//! - Every viewport has its own representation of the model (surface or
+//!   wireframe): each extra viewport renders a copy of the model on its own
+//!   render layer, sharing the mesh and material of the model
+//! - The camera of the viewport under the cursor follows the mouse and keyboard;
+//!   with "Link cameras" every viewport shows the same view, for side-by-side
+//!   comparison of representations
+//!
+//! Choose the layout and representations in the viewport toolbar, shown via
+//! View > Viewport Toolbar.
+
+use crate::camera::{viewport_camera, ViewportCamera, WorldModelCamera};
+use crate::periodic::ModelMaterials;
+use crate::render::overlay::OverlayCamera;
+use crate::render::ClipMaterial;
+use crate::ui::UserModelMesh;
+use bevy::pbr::wireframe::{NoWireframe, Wireframe};
+use bevy::prelude::*;
+use bevy::render::camera::Viewport;
+use bevy::render::view::RenderLayers;
+use bevy::window::PrimaryWindow;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Maximum number of viewports
+pub const MAX_VIEWPORTS: usize = 4;
+/// Render layer of the model in viewport 1; viewport `i` uses the layer after
+/// it by `i`
+pub const VIEWPORT_LAYER_BASE: usize = 2;
+/// Render order of the first extra viewport camera, after the overlay camera
+const VIEWPORT_CAMERA_ORDER: isize = 10;
+
+const BORDER_COLOR: egui::Color32 = egui::Color32::from_gray(90);
+
+/// Arrangement of the viewports
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ViewportLayout {
+    /// One viewport filling the window
+    #[default]
+    Single,
+    /// Two viewports next to each other
+    SideBySide,
+    /// Two viewports above each other
+    Stacked,
+    /// One viewport on the left, two stacked on the right
+    Three,
+    /// Two by two viewports
+    Grid,
+}
+
+impl ViewportLayout {
+    pub const ALL: [ViewportLayout; 5] = [
+        ViewportLayout::Single,
+        ViewportLayout::SideBySide,
+        ViewportLayout::Stacked,
+        ViewportLayout::Three,
+        ViewportLayout::Grid,
+    ];
+
+    /// Number of viewports
+    pub fn count(self) -> usize {
+        match self {
+            ViewportLayout::Single => 1,
+            ViewportLayout::SideBySide | ViewportLayout::Stacked => 2,
+            ViewportLayout::Three => 3,
+            ViewportLayout::Grid => 4,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ViewportLayout::Single => "Single",
+            ViewportLayout::SideBySide => "1 | 2",
+            ViewportLayout::Stacked => "1 / 2",
+            ViewportLayout::Three => "1 | 2 / 3",
+            ViewportLayout::Grid => "2 x 2",
+        }
+    }
+
+    /// Viewport rectangles as fractions of the window, viewport 1 first
+    ///
+    /// # Returns
+    /// - One rectangle per viewport with coordinates in 0..1, y pointing down
+    pub fn rects(self) -> Vec<Rect> {
+        let rect = |x0: f32, y0: f32, x1: f32, y1: f32| Rect::new(x0, y0, x1, y1);
+        match self {
+            ViewportLayout::Single => vec![rect(0.0, 0.0, 1.0, 1.0)],
+            ViewportLayout::SideBySide => {
+                vec![rect(0.0, 0.0, 0.5, 1.0), rect(0.5, 0.0, 1.0, 1.0)]
+            }
+            ViewportLayout::Stacked => {
+                vec![rect(0.0, 0.0, 1.0, 0.5), rect(0.0, 0.5, 1.0, 1.0)]
+            }
+            ViewportLayout::Three => vec![
+                rect(0.0, 0.0, 0.5, 1.0),
+                rect(0.5, 0.0, 1.0, 0.5),
+                rect(0.5, 0.5, 1.0, 1.0),
+            ],
+            ViewportLayout::Grid => vec![
+                rect(0.0, 0.0, 0.5, 0.5),
+                rect(0.5, 0.0, 1.0, 0.5),
+                rect(0.0, 0.5, 0.5, 1.0),
+                rect(0.5, 0.5, 1.0, 1.0),
+            ],
+        }
+    }
+}
+
+/// How a viewport shows the model
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Representation {
+    #[default]
+    Surface,
+    /// Surface with its triangle edges
+    Wireframe,
+}
+
+impl Representation {
+    pub fn label(self) -> &'static str {
+        match self {
+            Representation::Surface => "Surface",
+            Representation::Wireframe => "Wireframe",
+        }
+    }
+}
+
+/// Split view settings
+#[derive(Resource)]
+pub struct ViewportSettings {
+    /// Show the viewport toolbar
+    pub show_toolbar: bool,
+    pub layout: ViewportLayout,
+    /// Representation of the model per viewport
+    pub representations: [Representation; MAX_VIEWPORTS],
+    /// All viewport cameras share the view of the camera being moved
+    pub link_cameras: bool,
+}
+
+impl Default for ViewportSettings {
+    fn default() -> Self {
+        Self {
+            show_toolbar: false,
+            layout: ViewportLayout::Single,
+            representations: [Representation::Surface; MAX_VIEWPORTS],
+            link_cameras: false,
+        }
+    }
+}
+
+impl ViewportSettings {
+    /// The window is split into several viewports
+    pub fn is_split(&self) -> bool {
+        self.layout.count() > 1
+    }
+}
+
+/// Copy of the model shown in an extra viewport, with the viewport index
+#[derive(Component)]
+pub struct ViewportModelCopy(pub usize);
+
+pub struct ViewportPlugin;
+
+impl Plugin for ViewportPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ViewportSettings>().add_systems(
+            Update,
+            (
+                viewport_toolbar.after(EguiSet::InitContexts),
+                update_viewport_cameras,
+                update_model_copies,
+                apply_representations,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Render layers seen by the camera of a viewport
+fn viewport_layers(index: usize) -> RenderLayers {
+    RenderLayers::from_layers(&[0, VIEWPORT_LAYER_BASE + index])
+}
+
+/// Physical pixel viewport of a window fraction
+fn physical_viewport(fraction: Rect, window_size: UVec2) -> Viewport {
+    let size = window_size.as_vec2();
+    let min = (fraction.min * size).round();
+    let max = (fraction.max * size).round();
+    Viewport {
+        physical_position: min.as_uvec2(),
+        physical_size: (max - min).max(Vec2::ONE).as_uvec2(),
+        ..default()
+    }
+}
+
+/// Set the viewport of a camera, leaving it unchanged if it is the same
+fn set_viewport(camera: &mut Mut<Camera>, viewport: Option<Viewport>) {
+    let same = match (&camera.viewport, &viewport) {
+        (Some(current), Some(new)) => {
+            current.physical_position == new.physical_position
+                && current.physical_size == new.physical_size
+        }
+        (None, None) => true,
+        _ => false,
+    };
+    if !same {
+        camera.viewport = viewport;
+    }
+}
+
+/// Spawn, remove and place the viewport cameras
+#[allow(clippy::type_complexity)]
+fn update_viewport_cameras(
+    mut commands: Commands,
+    settings: Res<ViewportSettings>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut main_cameras: Query<
+        (Entity, &mut Camera, &Transform),
+        (With<WorldModelCamera>, Without<OverlayCamera>),
+    >,
+    mut overlay_cameras: Query<&mut Camera, (With<OverlayCamera>, Without<WorldModelCamera>)>,
+    mut viewport_cameras: Query<
+        (Entity, &ViewportCamera, &mut Camera),
+        (Without<WorldModelCamera>, Without<OverlayCamera>),
+    >,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let Ok((main_entity, mut main_camera, main_transform)) = main_cameras.get_single_mut() else {
+        return;
+    };
+    let window_size = window.physical_size();
+    if window_size.x == 0 || window_size.y == 0 {
+        return;
+    }
+
+    let rects = settings.layout.rects();
+    let viewport = |index: usize| {
+        settings
+            .is_split()
+            .then(|| physical_viewport(rects[index], window_size))
+    };
+
+    set_viewport(&mut main_camera, viewport(0));
+    for mut overlay_camera in overlay_cameras.iter_mut() {
+        set_viewport(&mut overlay_camera, viewport(0));
+    }
+    if settings.is_changed() {
+        if settings.is_split() {
+            commands.entity(main_entity).insert(viewport_layers(0));
+        } else {
+            commands.entity(main_entity).remove::<RenderLayers>();
+        }
+    }
+
+    let mut existing = [false; MAX_VIEWPORTS];
+    for (entity, ViewportCamera(index), mut camera) in viewport_cameras.iter_mut() {
+        if *index >= rects.len() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        existing[*index] = true;
+        set_viewport(&mut camera, viewport(*index));
+    }
+
+    for index in (1..rects.len()).filter(|&index| !existing[index]) {
+        commands.spawn((
+            Name::new(format!("Viewport {} Camera", index + 1)),
+            viewport_camera(index, *main_transform),
+            Camera {
+                // The main camera already cleared the window
+                order: VIEWPORT_CAMERA_ORDER + index as isize,
+                clear_color: ClearColorConfig::None,
+                viewport: viewport(index),
+                ..default()
+            },
+            viewport_layers(index),
+        ));
+    }
+}
+
+/// Keep one copy of the model per extra viewport, on its render layer
+#[allow(clippy::type_complexity)]
+fn update_model_copies(
+    mut commands: Commands,
+    settings: Res<ViewportSettings>,
+    models: Query<
+        (Entity, &Mesh3d, ModelMaterials, Has<RenderLayers>),
+        (With<UserModelMesh>, Without<ViewportModelCopy>),
+    >,
+    mut copies: Query<(
+        Entity,
+        &ViewportModelCopy,
+        &Parent,
+        &mut Mesh3d,
+        ModelMaterials,
+    )>,
+) {
+    let count = settings.layout.count();
+    let Some((model, mesh, (standard_material, clip_material), has_layers)) = models.iter().next()
+    else {
+        return;
+    };
+
+    // While split, the model itself is only seen by viewport 1
+    if settings.is_split() && !has_layers {
+        commands
+            .entity(model)
+            .insert(RenderLayers::layer(VIEWPORT_LAYER_BASE));
+    } else if !settings.is_split() && has_layers {
+        commands.entity(model).remove::<RenderLayers>();
+    }
+
+    let mut existing = [false; MAX_VIEWPORTS];
+    for (entity, ViewportModelCopy(index), parent, mut copy_mesh, copy_materials) in
+        copies.iter_mut()
+    {
+        if *index >= count || parent.get() != model {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        existing[*index] = true;
+
+        // The model mesh changes with LOD switches, its material with clipping
+        if copy_mesh.0 != mesh.0 {
+            copy_mesh.0 = mesh.0.clone();
+        }
+        if let Some(material) = clip_material {
+            if copy_materials.1.is_none_or(|copy| copy.0 != material.0) {
+                commands
+                    .entity(entity)
+                    .remove::<MeshMaterial3d<StandardMaterial>>()
+                    .insert(material.clone());
+            }
+        } else if let Some(material) = standard_material {
+            if copy_materials.0.is_none_or(|copy| copy.0 != material.0) {
+                commands
+                    .entity(entity)
+                    .remove::<MeshMaterial3d<ClipMaterial>>()
+                    .insert(material.clone());
+            }
+        }
+    }
+
+    for index in (1..count).filter(|&index| !existing[index]) {
+        let mut copy = commands.spawn((
+            Name::new(format!("Viewport {} Model", index + 1)),
+            mesh.clone(),
+            Transform::IDENTITY,
+            RenderLayers::layer(VIEWPORT_LAYER_BASE + index),
+            ViewportModelCopy(index),
+        ));
+        if let Some(material) = clip_material {
+            copy.insert(material.clone());
+        } else if let Some(material) = standard_material {
+            copy.insert(material.clone());
+        }
+        copy.set_parent(model);
+    }
+}
+
+/// Show the model of every viewport with the chosen representation
+///
+/// While the window is not split, the model follows the global wireframe mode.
+fn apply_representations(
+    mut commands: Commands,
+    settings: Res<ViewportSettings>,
+    models: Query<Entity, (With<UserModelMesh>, Without<ViewportModelCopy>)>,
+    copies: Query<(Entity, &ViewportModelCopy)>,
+    new_copies: Query<(), Added<ViewportModelCopy>>,
+    new_models: Query<(), Added<UserModelMesh>>,
+) {
+    if !settings.is_changed() && new_copies.is_empty() && new_models.is_empty() {
+        return;
+    }
+
+    let apply = |commands: &mut Commands, entity: Entity, representation: Representation| {
+        match representation {
+            Representation::Surface => {
+                commands
+                    .entity(entity)
+                    .remove::<Wireframe>()
+                    .insert(NoWireframe);
+            }
+            Representation::Wireframe => {
+                commands
+                    .entity(entity)
+                    .remove::<NoWireframe>()
+                    .insert(Wireframe);
+            }
+        }
+    };
+
+    for model in models.iter() {
+        if settings.is_split() {
+            apply(&mut commands, model, settings.representations[0]);
+        } else {
+            commands.entity(model).remove::<(Wireframe, NoWireframe)>();
+        }
+    }
+    for (entity, ViewportModelCopy(index)) in copies.iter() {
+        apply(&mut commands, entity, settings.representations[*index]);
+    }
+}
+
+/// Layout and representation controls at the top right of every viewport,
+/// and the borders between the viewports
+fn viewport_toolbar(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<ViewportSettings>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+) {
+    if windows.get_single().is_err() {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    // In egui points, which differ from window pixels with a UI scale
+    let size = ctx.screen_rect().size();
+    let rects: Vec<egui::Rect> = settings
+        .layout
+        .rects()
+        .iter()
+        .map(|rect| {
+            egui::Rect::from_min_max(
+                egui::pos2(rect.min.x * size.x, rect.min.y * size.y),
+                egui::pos2(rect.max.x * size.x, rect.max.y * size.y),
+            )
+        })
+        .collect();
+
+    if rects.len() > 1 {
+        let painter = ctx.layer_painter(egui::LayerId::new(
+            egui::Order::Background,
+            egui::Id::new("viewport_borders"),
+        ));
+        for rect in &rects {
+            painter.rect_stroke(*rect, 0.0, egui::Stroke::new(1.0, BORDER_COLOR));
+        }
+    }
+    if !settings.show_toolbar {
+        return;
+    }
+
+    let mut layout = settings.layout;
+    let mut representations = settings.representations;
+    let mut link_cameras = settings.link_cameras;
+    for (index, rect) in rects.iter().enumerate() {
+        // Keep clear of the menu bar in the top row
+        let top = if rect.min.y <= 0.0 { 32.0 } else { 8.0 };
+        egui::Area::new(egui::Id::new(("viewport_toolbar", index)))
+            .fixed_pos(rect.right_top() + egui::vec2(-8.0, top))
+            .pivot(egui::Align2::RIGHT_TOP)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("View {}", index + 1));
+                        egui::ComboBox::from_id_salt(("viewport_representation", index))
+                            .selected_text(representations[index].label())
+                            .show_ui(ui, |ui| {
+                                for representation in
+                                    [Representation::Surface, Representation::Wireframe]
+                                {
+                                    ui.selectable_value(
+                                        &mut representations[index],
+                                        representation,
+                                        representation.label(),
+                                    );
+                                }
+                            });
+                        if index == 0 {
+                            egui::ComboBox::from_id_salt("viewport_layout")
+                                .selected_text(layout.label())
+                                .show_ui(ui, |ui| {
+                                    for option in ViewportLayout::ALL {
+                                        ui.selectable_value(&mut layout, option, option.label());
+                                    }
+                                });
+                            if layout.count() > 1 {
+                                ui.checkbox(&mut link_cameras, "Link cameras");
+                            }
+                        }
+                    });
+                });
+            });
+    }
+
+    if layout != settings.layout
+        || representations != settings.representations
+        || link_cameras != settings.link_cameras
+    {
+        if layout != settings.layout {
+            println!("Viewport layout: {}", layout.label());
+        }
+        settings.layout = layout;
+        settings.representations = representations;
+        settings.link_cameras = link_cameras;
+    }
+}
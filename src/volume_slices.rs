@@ -0,0 +1,186 @@
+//! # Volume Slices Module
+//!
+//! This module displays image data volumes (`.vti`) by axis-aligned slices:
+//! - The full voxel data is kept in [`VolumeData`] for downstream filters
+//! - The model shows one slice as colored quads, initially the middle one
+//! - The panel selects the slice axis (X, Y, Z) and the slice position
+//!
+//! The panel opens when a volume is imported; reopen it via View > Volume Slices.
+
+use crate::lod::LODManager;
+use crate::mesh::volume::VolumeData;
+use crate::mesh::{self, GeometryData};
+use crate::ui::{color_bar, ColorBarConfig, CurrentModelData, UserModelMesh};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Volume slicing state
+#[derive(Resource, Default)]
+pub struct VolumeSlices {
+    /// Slice normal (0 = X, 1 = Y, 2 = Z)
+    pub axis: usize,
+    /// Point layer of the slice along the axis
+    pub index: usize,
+    /// Show the slice panel
+    pub show_panel: bool,
+    /// Slice changed, the model mesh needs to be rebuilt
+    pub changed: bool,
+    /// Voxel data of the current model, if it is a volume
+    volume: Option<VolumeData>,
+}
+
+impl VolumeSlices {
+    /// Keep an imported volume and return the slice to show
+    ///
+    /// # Parameters
+    /// - `volume`: Imported voxel data
+    ///
+    /// # Returns
+    /// - Middle slice along the default axis
+    pub fn prepare_import(&mut self, volume: VolumeData) -> GeometryData {
+        self.axis = volume.default_axis();
+        self.index = volume.dims[self.axis] / 2;
+        self.show_panel = true;
+        self.changed = false;
+        let slice = volume.slice(self.axis, self.index);
+        self.volume = Some(volume);
+        slice
+    }
+
+    /// Forget the volume (the current model is not a volume)
+    pub fn clear(&mut self) {
+        self.volume = None;
+    }
+
+    /// Voxel data of the current model, if it is a volume
+    pub fn volume(&self) -> Option<&VolumeData> {
+        self.volume.as_ref()
+    }
+}
+
+pub struct VolumeSlicePlugin;
+
+impl Plugin for VolumeSlicePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VolumeSlices>().add_systems(
+            Update,
+            (
+                clear_volume_with_model,
+                volume_slice_panel.after(EguiSet::InitContexts),
+                update_volume_slice,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Forget the volume once the model is cleared
+fn clear_volume_with_model(current_model: Res<CurrentModelData>, mut slices: ResMut<VolumeSlices>) {
+    if current_model.geometry.is_none() && slices.volume.is_some() {
+        slices.clear();
+    }
+}
+
+/// Volume slice panel
+fn volume_slice_panel(
+    mut contexts: EguiContexts,
+    mut slices: ResMut<VolumeSlices>,
+    windows: Query<&Window>,
+) {
+    if !slices.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let mut axis = slices.axis;
+    let mut index = slices.index;
+    let mut open = true;
+
+    egui::Window::new("Volume Slices")
+        .default_width(260.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            let Some(volume) = slices.volume() else {
+                ui.label("Import a .vti file to slice its volume.");
+                return;
+            };
+
+            ui.label(format!(
+                "Volume: {} x {} x {} points",
+                volume.dims[0], volume.dims[1], volume.dims[2]
+            ));
+            ui.label(format!(
+                "Spacing: {:.4}, {:.4}, {:.4}",
+                volume.spacing[0], volume.spacing[1], volume.spacing[2]
+            ));
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.label("Axis:");
+                for (value, name) in ["X", "Y", "Z"].iter().enumerate() {
+                    ui.radio_value(&mut axis, value, *name);
+                }
+            });
+
+            let last = volume.dims[axis].saturating_sub(1);
+            if axis != slices.axis {
+                index = volume.dims[axis] / 2;
+            }
+            index = index.min(last);
+            ui.add_enabled(
+                last > 0,
+                egui::Slider::new(&mut index, 0..=last).text("Slice"),
+            );
+            let position = volume.origin[axis] + volume.spacing[axis] * index as f32;
+            ui.label(format!("Position: {:.4}", position));
+        });
+
+    if axis != slices.axis || index != slices.index {
+        slices.axis = axis;
+        slices.index = index;
+        slices.changed = true;
+    }
+
+    if !open {
+        slices.show_panel = false;
+    }
+}
+
+/// Replace the model mesh with the selected slice
+fn update_volume_slice(
+    mut slices: ResMut<VolumeSlices>,
+    mut current_model: ResMut<CurrentModelData>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut model_entities: Query<(&mut Mesh3d, Option<&mut LODManager>), With<UserModelMesh>>,
+    color_bar_config: Res<ColorBarConfig>,
+) {
+    if !slices.changed {
+        return;
+    }
+    slices.changed = false;
+
+    let Some(volume) = slices.volume() else {
+        return;
+    };
+    let geometry = volume.slice(slices.axis, slices.index);
+
+    let mut mesh = mesh::create_mesh_from_geometry(&geometry);
+    if let Err(e) = color_bar::apply_custom_color_mapping(&geometry, &mut mesh, &color_bar_config) {
+        println!("Failed to apply color mapping to volume slice: {:?}", e);
+    }
+
+    if let Ok((mut mesh3d, lod_manager)) = model_entities.get_single_mut() {
+        // Levels of the previous slice must not replace the new one
+        if let Some(mut lod_manager) = lod_manager {
+            if let Err(e) = lod_manager.rebuild(geometry.clone(), &mut meshes) {
+                println!("Failed to rebuild LOD levels of volume slice: {:?}", e);
+            }
+        }
+        *mesh3d = Mesh3d(meshes.add(mesh));
+    }
+    current_model.geometry = Some(geometry);
+    println!(
+        "Showing volume slice {} along {}",
+        slices.index,
+        ["X", "Y", "Z"][slices.axis]
+    );
+}
@@ -0,0 +1,260 @@
+//! # Warp Module
+//!
+//! This module shows displacement results (e.g. of FE analyses) by warping the
+//! model geometry:
+//! - Every vertex is offset by scale × its point vector
+//! - The model mesh follows the scale slider live
+//! - Turning the warp off restores the undeformed vertices
+//!
+//! The warped vertices replace the model geometry, so picking, measurements and
+//! export see the deformed shape. Open the panel via Mesh > Warp by Vector.
+
+use crate::mesh::streamlines::vector_attribute_names;
+use crate::mesh::warp::{max_magnitude, warp_by_vector};
+use crate::mesh::{self, normals, AttributeLocation, AttributeType};
+use crate::ui::color_bar::apply_custom_color_mapping;
+use crate::ui::{ColorBarConfig, CurrentModelData, ModelLoadedEvent, UserModelMesh};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Largest displacement of the auto scale relative to the model size
+const AUTO_SCALE_FRACTION: f32 = 0.1;
+
+/// Vertex positions and optional normals of the undeformed model
+type UndeformedVertices = (Vec<[f32; 3]>, Option<Vec<[f32; 3]>>);
+
+/// Warp by vector settings
+#[derive(Resource)]
+pub struct WarpSettings {
+    /// Show the warp panel
+    pub show_panel: bool,
+    /// Show the model warped
+    pub enabled: bool,
+    /// Point vector attribute used as displacement
+    pub attribute: Option<String>,
+    /// Factor applied to the displacement
+    pub scale: f32,
+    /// Settings changed, the model needs to be warped again
+    pub changed: bool,
+    /// Undeformed vertices and normals of the model
+    original: Option<UndeformedVertices>,
+}
+
+impl Default for WarpSettings {
+    fn default() -> Self {
+        Self {
+            show_panel: false,
+            enabled: false,
+            attribute: None,
+            scale: 1.0,
+            changed: false,
+            original: None,
+        }
+    }
+}
+
+pub struct WarpPlugin;
+
+impl Plugin for WarpPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WarpSettings>().add_systems(
+            Update,
+            (
+                reset_on_model_load,
+                warp_panel.after(EguiSet::InitContexts),
+                update_warp,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// A newly loaded model starts undeformed
+fn reset_on_model_load(
+    mut loaded_events: EventReader<ModelLoadedEvent>,
+    mut settings: ResMut<WarpSettings>,
+) {
+    if loaded_events.read().count() > 0 {
+        settings.enabled = false;
+        settings.original = None;
+    }
+}
+
+/// Warp panel
+fn warp_panel(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<WarpSettings>,
+    current_model: Res<CurrentModelData>,
+    windows: Query<&Window>,
+) {
+    if !settings.show_panel || windows.iter().next().is_none() {
+        return;
+    }
+
+    let mut enabled = settings.enabled;
+    let mut attribute = settings.attribute.clone();
+    let mut scale = settings.scale;
+    let mut open = true;
+
+    egui::Window::new("Warp by Vector")
+        .default_width(260.0)
+        .open(&mut open)
+        .show(contexts.ctx_mut(), |ui| {
+            let Some(geometry) = current_model.geometry.as_ref() else {
+                ui.label("Load a model with point vectors.");
+                return;
+            };
+            let names = geometry
+                .attributes
+                .as_ref()
+                .map(vector_attribute_names)
+                .unwrap_or_default();
+            if names.is_empty() {
+                ui.label("The model has no point vectors.");
+                return;
+            }
+            if attribute.as_ref().is_none_or(|name| !names.contains(name)) {
+                attribute = names.first().cloned();
+            }
+
+            ui.checkbox(&mut enabled, "Warp");
+            egui::ComboBox::from_label("Displacement")
+                .selected_text(attribute.clone().unwrap_or_default())
+                .show_ui(ui, |ui| {
+                    for name in &names {
+                        ui.selectable_value(&mut attribute, Some(name.clone()), name);
+                    }
+                });
+            ui.add(
+                egui::Slider::new(&mut scale, 0.0..=10_000.0)
+                    .logarithmic(true)
+                    .text("Scale"),
+            );
+
+            // Largest displacement of the undeformed model
+            let key = (
+                attribute.clone().unwrap_or_default(),
+                AttributeLocation::Point,
+            );
+            let magnitude = match geometry.attributes.as_ref().and_then(|a| a.get(&key)) {
+                Some(AttributeType::Vector(vectors)) => max_magnitude(vectors),
+                _ => 0.0,
+            };
+            ui.label(format!("Max displacement: {:.4e}", magnitude));
+            if ui
+                .add_enabled(magnitude > 0.0, egui::Button::new("Auto Scale"))
+                .on_hover_text("Largest displacement at 10% of the model size")
+                .clicked()
+            {
+                let vertices = settings
+                    .original
+                    .as_ref()
+                    .map_or(&geometry.vertices, |(vertices, _)| vertices);
+                scale = model_size(vertices) * AUTO_SCALE_FRACTION / magnitude;
+                enabled = true;
+            }
+        });
+
+    if enabled != settings.enabled || attribute != settings.attribute || scale != settings.scale {
+        settings.enabled = enabled;
+        settings.attribute = attribute;
+        settings.scale = scale;
+        settings.changed = true;
+    }
+
+    if !open {
+        settings.show_panel = false;
+    }
+}
+
+/// Largest extent of the bounding box of the vertices
+fn model_size(vertices: &[[f32; 3]]) -> f32 {
+    let Some(first) = vertices.first() else {
+        return 0.0;
+    };
+    let (min, max) = vertices
+        .iter()
+        .fold((Vec3::from(*first), Vec3::from(*first)), |(min, max), v| {
+            (min.min(Vec3::from(*v)), max.max(Vec3::from(*v)))
+        });
+    (max - min).max_element()
+}
+
+/// Warp the model geometry or restore the undeformed vertices
+fn update_warp(
+    mut settings: ResMut<WarpSettings>,
+    mut current_model: ResMut<CurrentModelData>,
+    color_bar_config: Res<ColorBarConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut model_entities: Query<&mut Mesh3d, With<UserModelMesh>>,
+) {
+    if !settings.changed {
+        return;
+    }
+    let Some(geometry) = current_model.geometry.as_mut() else {
+        return;
+    };
+    // A newly loaded model may not be spawned yet; retry on the next frame
+    let Ok(mut mesh3d) = model_entities.get_single_mut() else {
+        return;
+    };
+    settings.changed = false;
+
+    // Another filter may have replaced the geometry since it was warped
+    if settings
+        .original
+        .as_ref()
+        .is_some_and(|(vertices, _)| vertices.len() != geometry.vertices.len())
+    {
+        settings.original = None;
+    }
+
+    if settings.enabled {
+        let key = (
+            settings.attribute.clone().unwrap_or_default(),
+            AttributeLocation::Point,
+        );
+        let Some(AttributeType::Vector(vectors)) =
+            geometry.attributes.as_ref().and_then(|a| a.get(&key))
+        else {
+            println!("No point vectors '{}' to warp by", key.0);
+            return;
+        };
+        let scale = settings.scale;
+        let (vertices, _) = settings
+            .original
+            .get_or_insert_with(|| (geometry.vertices.clone(), geometry.normals.clone()));
+        geometry.vertices = warp_by_vector(vertices, vectors, scale);
+        geometry.normals = None;
+    } else {
+        let Some((vertices, normals)) = settings.original.take() else {
+            return;
+        };
+        geometry.vertices = vertices;
+        geometry.normals = normals;
+        println!("Restored undeformed model");
+    }
+
+    // Update the positions in place while the mesh matches the geometry, so
+    // the scale slider stays responsive on large models. Stored normals only
+    // fit a mesh without vertices split at sharp edges.
+    if let Some(mesh) = meshes.get_mut(&mesh3d.0) {
+        let split = normals::source_vertices(mesh).is_some();
+        if normals::geometry_vertex_count(mesh) == geometry.vertices.len()
+            && !(split && geometry.normals.is_some())
+        {
+            let positions = normals::expand_to_mesh(mesh, geometry.vertices.clone(), [0.0; 3]);
+            mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+            match geometry.normals {
+                Some(ref stored) => mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, stored.clone()),
+                None => mesh.compute_normals(),
+            }
+            return;
+        }
+    }
+    let mut new_mesh = mesh::create_mesh_from_geometry(geometry);
+    if let Err(e) = apply_custom_color_mapping(geometry, &mut new_mesh, &color_bar_config) {
+        println!("Failed to apply color mapping to warped mesh: {:?}", e);
+    }
+    *mesh3d = Mesh3d(meshes.add(new_mesh));
+}
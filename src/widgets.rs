@@ -0,0 +1,722 @@
+//! # Manipulation Widget Module
+//!
+//! This module provides reusable 3D widgets that can be placed in the viewport
+//! and edited directly with the mouse:
+//! - Plane widget: center handle moves the origin, tip handle rotates the normal
+//! - Box widget: center handle moves the box, face handles resize it
+//! - Sphere widget: center handle moves the sphere, rim handle changes the radius
+//! - Line widget: end point handles move the ends of a line segment
+//! - Clip plane widgets: one plane widget per user clipping plane
+//!
+//! Widget geometry is stored in model space (the same space as `GeometryData`),
+//! so filters such as slice, clip and seed sources can use it without any
+//! conversion. A `WidgetChangedEvent` is sent whenever a widget has been edited.
+//!
+//! ## Control Scheme
+//! - Left mouse drag on a handle: Move the handle
+//! - Alt + Left drag is left to the model transform controls
+
+use crate::camera::WorldModelCamera;
+use crate::picking::{
+    cursor_ray_in_model_space, model_matrix, pointer_over_ui, ray_plane_intersection,
+    ray_sphere_distance,
+};
+use crate::render::{OverlayGizmos, MAX_CLIP_PLANES};
+use crate::ui::{ModelLoadedEvent, UserModelMesh};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+/// Handle radius relative to the widget scale
+const HANDLE_RADIUS_FACTOR: f32 = 0.02;
+/// Half size of the drawn plane relative to the widget scale
+const PLANE_HALF_SIZE_FACTOR: f32 = 0.3;
+/// Length of the plane normal arrow relative to the widget scale
+const PLANE_NORMAL_LENGTH_FACTOR: f32 = 0.25;
+/// Smallest allowed box extent and sphere radius
+const MIN_WIDGET_EXTENT: f32 = 1e-4;
+
+const PLANE_COLOR: Color = Color::srgb(0.2, 0.8, 1.0);
+const BOX_COLOR: Color = Color::srgb(1.0, 0.6, 0.1);
+const SPHERE_COLOR: Color = Color::srgb(0.4, 1.0, 0.4);
+const LINE_COLOR: Color = Color::srgb(1.0, 0.9, 0.3);
+const CLIP_PLANE_COLOR: Color = Color::srgb(1.0, 0.3, 0.8);
+const HANDLE_COLOR: Color = Color::srgb(1.0, 1.0, 1.0);
+const ACTIVE_HANDLE_COLOR: Color = Color::srgb(1.0, 1.0, 0.0);
+
+/// Widget types
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WidgetKind {
+    Plane,
+    Box,
+    Sphere,
+    Line,
+    /// Clip plane with the given index
+    ClipPlane(usize),
+}
+
+/// Infinite plane defined by an origin and a unit normal
+#[derive(Clone, Debug)]
+pub struct PlaneWidget {
+    pub enabled: bool,
+    pub origin: Vec3,
+    pub normal: Vec3,
+}
+
+impl PlaneWidget {
+    /// Signed distance from a point to the plane (positive on the normal side)
+    #[allow(dead_code)]
+    pub fn signed_distance(&self, point: Vec3) -> f32 {
+        (point - self.origin).dot(self.normal)
+    }
+
+    /// Rotate the normal to point from the origin towards a position
+    fn point_normal_at(&mut self, position: Vec3) {
+        let direction = (position - self.origin).normalize_or_zero();
+        if direction != Vec3::ZERO {
+            self.normal = direction;
+        }
+    }
+}
+
+/// Axis-aligned box
+#[derive(Clone, Debug)]
+pub struct BoxWidget {
+    pub enabled: bool,
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl BoxWidget {
+    /// Check whether a point lies inside the box
+    #[allow(dead_code)]
+    pub fn contains(&self, point: Vec3) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+}
+
+/// Sphere defined by center and radius
+#[derive(Clone, Debug)]
+pub struct SphereWidget {
+    pub enabled: bool,
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+impl SphereWidget {
+    /// Check whether a point lies inside the sphere
+    #[allow(dead_code)]
+    pub fn contains(&self, point: Vec3) -> bool {
+        point.distance_squared(self.center) <= self.radius * self.radius
+    }
+}
+
+/// Line segment between two points
+#[derive(Clone, Debug)]
+pub struct LineWidget {
+    pub enabled: bool,
+    pub start: Vec3,
+    pub end: Vec3,
+}
+
+/// Draggable handle of a widget
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum WidgetHandle {
+    PlaneOrigin,
+    PlaneNormal,
+    BoxCenter,
+    /// Box face handle: axis index and whether it is the max side
+    BoxFace(usize, bool),
+    SphereCenter,
+    SphereRadius,
+    LineStart,
+    LineEnd,
+    ClipPlaneOrigin(usize),
+    ClipPlaneNormal(usize),
+}
+
+impl WidgetHandle {
+    fn kind(&self) -> WidgetKind {
+        match self {
+            WidgetHandle::PlaneOrigin | WidgetHandle::PlaneNormal => WidgetKind::Plane,
+            WidgetHandle::BoxCenter | WidgetHandle::BoxFace(..) => WidgetKind::Box,
+            WidgetHandle::SphereCenter | WidgetHandle::SphereRadius => WidgetKind::Sphere,
+            WidgetHandle::LineStart | WidgetHandle::LineEnd => WidgetKind::Line,
+            WidgetHandle::ClipPlaneOrigin(index) | WidgetHandle::ClipPlaneNormal(index) => {
+                WidgetKind::ClipPlane(*index)
+            }
+        }
+    }
+}
+
+/// Drag in progress
+#[derive(Clone, Copy, Debug)]
+struct ActiveDrag {
+    handle: WidgetHandle,
+    /// Point on the view-aligned drag plane (model space)
+    plane_point: Vec3,
+    /// Normal of the view-aligned drag plane (model space)
+    plane_normal: Vec3,
+}
+
+/// State of all manipulation widgets
+#[derive(Resource)]
+pub struct WidgetState {
+    pub plane: PlaneWidget,
+    pub box_widget: BoxWidget,
+    pub sphere: SphereWidget,
+    pub line: LineWidget,
+    /// User clipping planes (at most `MAX_CLIP_PLANES`); geometry on the normal
+    /// side of an enabled plane is clipped away
+    pub clip_planes: Vec<PlaneWidget>,
+    /// Draw and allow dragging of the enabled clip planes
+    pub show_clip_widgets: bool,
+    /// Characteristic size of the scene, used for handle size and plane extent
+    pub scale: f32,
+    /// Center of the current model bounds, used to place new clip planes
+    center: Vec3,
+    active_drag: Option<ActiveDrag>,
+}
+
+impl Default for WidgetState {
+    fn default() -> Self {
+        Self {
+            plane: PlaneWidget {
+                enabled: false,
+                origin: Vec3::ZERO,
+                normal: Vec3::X,
+            },
+            box_widget: BoxWidget {
+                enabled: false,
+                min: Vec3::splat(-0.5),
+                max: Vec3::splat(0.5),
+            },
+            sphere: SphereWidget {
+                enabled: false,
+                center: Vec3::ZERO,
+                radius: 0.5,
+            },
+            line: LineWidget {
+                enabled: false,
+                start: Vec3::new(-0.5, 0.0, 0.0),
+                end: Vec3::new(0.5, 0.0, 0.0),
+            },
+            clip_planes: Vec::new(),
+            show_clip_widgets: true,
+            scale: 2.0,
+            center: Vec3::ZERO,
+            active_drag: None,
+        }
+    }
+}
+
+impl WidgetState {
+    /// Place all widgets to fit the given model bounds
+    pub fn fit_to_bounds(&mut self, min: Vec3, max: Vec3) {
+        let center = (min + max) * 0.5;
+        let diagonal = (max - min).length().max(MIN_WIDGET_EXTENT);
+
+        self.plane.origin = center;
+        self.box_widget.min = min;
+        self.box_widget.max = max;
+        self.sphere.center = center;
+        self.sphere.radius = diagonal * 0.25;
+        self.line.start = min.lerp(max, 0.25);
+        self.line.end = min.lerp(max, 0.75);
+        for clip_plane in &mut self.clip_planes {
+            clip_plane.origin = center;
+        }
+        self.scale = diagonal;
+        self.center = center;
+        self.active_drag = None;
+    }
+
+    /// Add an enabled clip plane through the model center
+    ///
+    /// New planes cycle through the +X, -X, +Y, -Y, +Z and -Z normals.
+    ///
+    /// # Returns
+    /// - `true` if the plane was added, `false` if the maximum count is reached
+    pub fn add_clip_plane(&mut self) -> bool {
+        if self.clip_planes.len() >= MAX_CLIP_PLANES {
+            return false;
+        }
+        let normals = [
+            Vec3::X,
+            Vec3::NEG_X,
+            Vec3::Y,
+            Vec3::NEG_Y,
+            Vec3::Z,
+            Vec3::NEG_Z,
+        ];
+        self.clip_planes.push(PlaneWidget {
+            enabled: true,
+            origin: self.center,
+            normal: normals[self.clip_planes.len() % normals.len()],
+        });
+        true
+    }
+
+    /// Remove a clip plane; cancels a drag since handle indices shift
+    pub fn remove_clip_plane(&mut self, index: usize) {
+        if index < self.clip_planes.len() {
+            self.clip_planes.remove(index);
+            self.active_drag = None;
+        }
+    }
+
+    /// Check whether a widget is currently being dragged
+    pub fn is_dragging(&self) -> bool {
+        self.active_drag.is_some()
+    }
+
+    /// Half size of the drawn plane widget square
+    pub fn plane_half_size(&self) -> f32 {
+        self.scale * PLANE_HALF_SIZE_FACTOR
+    }
+
+    fn handle_radius(&self) -> f32 {
+        self.scale * HANDLE_RADIUS_FACTOR
+    }
+
+    /// Collect positions of all handles of enabled widgets (model space)
+    fn handles(&self) -> Vec<(WidgetHandle, Vec3)> {
+        let mut handles = Vec::new();
+
+        if self.plane.enabled {
+            handles.push((WidgetHandle::PlaneOrigin, self.plane.origin));
+            handles.push((
+                WidgetHandle::PlaneNormal,
+                self.plane.origin + self.plane.normal * self.scale * PLANE_NORMAL_LENGTH_FACTOR,
+            ));
+        }
+
+        if self.box_widget.enabled {
+            let center = self.box_widget.center();
+            handles.push((WidgetHandle::BoxCenter, center));
+            for axis in 0..3 {
+                let mut min_face = center;
+                min_face[axis] = self.box_widget.min[axis];
+                handles.push((WidgetHandle::BoxFace(axis, false), min_face));
+
+                let mut max_face = center;
+                max_face[axis] = self.box_widget.max[axis];
+                handles.push((WidgetHandle::BoxFace(axis, true), max_face));
+            }
+        }
+
+        if self.sphere.enabled {
+            handles.push((WidgetHandle::SphereCenter, self.sphere.center));
+            handles.push((
+                WidgetHandle::SphereRadius,
+                self.sphere.center + Vec3::X * self.sphere.radius,
+            ));
+        }
+
+        if self.line.enabled {
+            handles.push((WidgetHandle::LineStart, self.line.start));
+            handles.push((WidgetHandle::LineEnd, self.line.end));
+        }
+
+        if self.show_clip_widgets {
+            for (index, clip_plane) in self.clip_planes.iter().enumerate() {
+                if clip_plane.enabled {
+                    handles.push((WidgetHandle::ClipPlaneOrigin(index), clip_plane.origin));
+                    handles.push((
+                        WidgetHandle::ClipPlaneNormal(index),
+                        clip_plane.origin
+                            + clip_plane.normal * self.scale * PLANE_NORMAL_LENGTH_FACTOR,
+                    ));
+                }
+            }
+        }
+
+        handles
+    }
+
+    /// Move a handle to a new position (model space)
+    fn move_handle(&mut self, handle: WidgetHandle, position: Vec3) {
+        match handle {
+            WidgetHandle::PlaneOrigin => {
+                self.plane.origin = position;
+            }
+            WidgetHandle::PlaneNormal => {
+                self.plane.point_normal_at(position);
+            }
+            WidgetHandle::ClipPlaneOrigin(index) => {
+                if let Some(clip_plane) = self.clip_planes.get_mut(index) {
+                    clip_plane.origin = position;
+                }
+            }
+            WidgetHandle::ClipPlaneNormal(index) => {
+                if let Some(clip_plane) = self.clip_planes.get_mut(index) {
+                    clip_plane.point_normal_at(position);
+                }
+            }
+            WidgetHandle::BoxCenter => {
+                let offset = position - self.box_widget.center();
+                self.box_widget.min += offset;
+                self.box_widget.max += offset;
+            }
+            WidgetHandle::BoxFace(axis, true) => {
+                self.box_widget.max[axis] =
+                    position[axis].max(self.box_widget.min[axis] + MIN_WIDGET_EXTENT);
+            }
+            WidgetHandle::BoxFace(axis, false) => {
+                self.box_widget.min[axis] =
+                    position[axis].min(self.box_widget.max[axis] - MIN_WIDGET_EXTENT);
+            }
+            WidgetHandle::SphereCenter => {
+                self.sphere.center = position;
+            }
+            WidgetHandle::SphereRadius => {
+                self.sphere.radius = position.distance(self.sphere.center).max(MIN_WIDGET_EXTENT);
+            }
+            WidgetHandle::LineStart => {
+                self.line.start = position;
+            }
+            WidgetHandle::LineEnd => {
+                self.line.end = position;
+            }
+        }
+    }
+}
+
+/// Sent after a widget has been edited (drag finished or value changed in the panel)
+#[derive(Event)]
+#[allow(dead_code)]
+pub struct WidgetChangedEvent(pub WidgetKind);
+
+pub struct WidgetPlugin;
+
+impl Plugin for WidgetPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WidgetState>()
+            .add_event::<WidgetChangedEvent>()
+            .add_systems(
+                Update,
+                (
+                    fit_widgets_to_model,
+                    widget_drag_input,
+                    draw_widgets,
+                    widget_panel.after(EguiSet::InitContexts),
+                ),
+            );
+    }
+}
+
+/// Fit widgets to newly loaded models
+fn fit_widgets_to_model(
+    mut model_loaded_events: EventReader<ModelLoadedEvent>,
+    mut widget_state: ResMut<WidgetState>,
+) {
+    for event in model_loaded_events.read() {
+        if let (Some(min), Some(max)) = (event.bounds_min, event.bounds_max) {
+            widget_state.fit_to_bounds(min, max);
+        }
+    }
+}
+
+/// Handle picking and dragging of widget handles
+#[allow(clippy::too_many_arguments)]
+pub fn widget_drag_input(
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<WorldModelCamera>>,
+    model_query: Query<&GlobalTransform, With<UserModelMesh>>,
+    mut contexts: EguiContexts,
+    mut widget_state: ResMut<WidgetState>,
+    mut widget_changed_events: EventWriter<WidgetChangedEvent>,
+) {
+    // Finish drag
+    if mouse_button_input.just_released(MouseButton::Left) {
+        if let Some(drag) = widget_state.active_drag.take() {
+            println!("Widget {:?} updated", drag.handle.kind());
+            widget_changed_events.send(WidgetChangedEvent(drag.handle.kind()));
+        }
+        return;
+    }
+
+    let model_to_world = model_matrix(&model_query);
+    let Some((ray_origin, ray_direction)) =
+        cursor_ray_in_model_space(&windows, &camera_query, model_to_world)
+    else {
+        return;
+    };
+
+    // Start drag
+    if mouse_button_input.just_pressed(MouseButton::Left) {
+        let alt_pressed =
+            keyboard_input.pressed(KeyCode::AltLeft) || keyboard_input.pressed(KeyCode::AltRight);
+        if alt_pressed || pointer_over_ui(&mut contexts) {
+            return;
+        }
+
+        let handle_radius = widget_state.handle_radius();
+        let picked = widget_state
+            .handles()
+            .into_iter()
+            .filter_map(|(handle, position)| {
+                ray_sphere_distance(ray_origin, ray_direction, position, handle_radius)
+                    .map(|distance| (handle, position, distance))
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2));
+
+        if let Some((handle, position, _)) = picked {
+            widget_state.active_drag = Some(ActiveDrag {
+                handle,
+                plane_point: position,
+                plane_normal: -ray_direction,
+            });
+        }
+        return;
+    }
+
+    // Continue drag
+    if mouse_button_input.pressed(MouseButton::Left) {
+        if let Some(drag) = widget_state.active_drag {
+            if let Some(position) = ray_plane_intersection(
+                ray_origin,
+                ray_direction,
+                drag.plane_point,
+                drag.plane_normal,
+            ) {
+                widget_state.move_handle(drag.handle, position);
+            }
+        }
+    }
+}
+
+/// Draw enabled widgets and their handles with gizmos
+fn draw_widgets(
+    mut gizmos: Gizmos<OverlayGizmos>,
+    widget_state: Res<WidgetState>,
+    model_query: Query<&GlobalTransform, With<UserModelMesh>>,
+) {
+    let model_to_world = model_matrix(&model_query);
+    let to_world = |point: Vec3| model_to_world.transform_point3(point);
+
+    // Plane: square outline and normal arrow
+    if widget_state.plane.enabled {
+        draw_plane(
+            &mut gizmos,
+            &widget_state.plane,
+            widget_state.scale,
+            PLANE_COLOR,
+            to_world,
+        );
+    }
+
+    // Clip planes
+    if widget_state.show_clip_widgets {
+        for clip_plane in widget_state.clip_planes.iter().filter(|p| p.enabled) {
+            draw_plane(
+                &mut gizmos,
+                clip_plane,
+                widget_state.scale,
+                CLIP_PLANE_COLOR,
+                to_world,
+            );
+        }
+    }
+
+    // Box: 12 edges
+    if widget_state.box_widget.enabled {
+        let min = widget_state.box_widget.min;
+        let max = widget_state.box_widget.max;
+        let corner = |i: usize| {
+            Vec3::new(
+                if i & 1 == 0 { min.x } else { max.x },
+                if i & 2 == 0 { min.y } else { max.y },
+                if i & 4 == 0 { min.z } else { max.z },
+            )
+        };
+        for i in 0..8 {
+            for bit in [1, 2, 4] {
+                if i & bit == 0 {
+                    gizmos.line(to_world(corner(i)), to_world(corner(i | bit)), BOX_COLOR);
+                }
+            }
+        }
+    }
+
+    // Sphere: three great circles
+    if widget_state.sphere.enabled {
+        let sphere = &widget_state.sphere;
+        let segments = 48;
+        for axis in 0..3 {
+            let (u, v) = match axis {
+                0 => (Vec3::Y, Vec3::Z),
+                1 => (Vec3::X, Vec3::Z),
+                _ => (Vec3::X, Vec3::Y),
+            };
+            for i in 0..segments {
+                let a0 = i as f32 / segments as f32 * std::f32::consts::TAU;
+                let a1 = (i + 1) as f32 / segments as f32 * std::f32::consts::TAU;
+                let p0 = sphere.center + (u * a0.cos() + v * a0.sin()) * sphere.radius;
+                let p1 = sphere.center + (u * a1.cos() + v * a1.sin()) * sphere.radius;
+                gizmos.line(to_world(p0), to_world(p1), SPHERE_COLOR);
+            }
+        }
+    }
+
+    // Line: segment between the end points
+    if widget_state.line.enabled {
+        gizmos.line(
+            to_world(widget_state.line.start),
+            to_world(widget_state.line.end),
+            LINE_COLOR,
+        );
+    }
+
+    // Handles
+    let handle_radius = widget_state.handle_radius();
+    let active_handle = widget_state.active_drag.map(|drag| drag.handle);
+    for (handle, position) in widget_state.handles() {
+        let color = if Some(handle) == active_handle {
+            ACTIVE_HANDLE_COLOR
+        } else {
+            HANDLE_COLOR
+        };
+        gizmos.sphere(
+            Isometry3d::from_translation(to_world(position)),
+            handle_radius,
+            color,
+        );
+    }
+}
+
+/// Draw a plane widget as a square outline with diagonals and a normal arrow
+fn draw_plane(
+    gizmos: &mut Gizmos<OverlayGizmos>,
+    plane: &PlaneWidget,
+    scale: f32,
+    color: Color,
+    to_world: impl Fn(Vec3) -> Vec3,
+) {
+    let half_size = scale * PLANE_HALF_SIZE_FACTOR;
+    let (u, v) = plane.normal.any_orthonormal_pair();
+    let corners = [
+        plane.origin + (u + v) * half_size,
+        plane.origin + (u - v) * half_size,
+        plane.origin + (-u - v) * half_size,
+        plane.origin + (-u + v) * half_size,
+    ];
+    for i in 0..4 {
+        gizmos.line(to_world(corners[i]), to_world(corners[(i + 1) % 4]), color);
+    }
+    gizmos.line(to_world(corners[0]), to_world(corners[2]), color);
+    gizmos.line(to_world(corners[1]), to_world(corners[3]), color);
+    gizmos.arrow(
+        to_world(plane.origin),
+        to_world(plane.origin + plane.normal * scale * PLANE_NORMAL_LENGTH_FACTOR),
+        color,
+    );
+}
+
+/// Panel for editing widget parameters numerically
+fn widget_panel(
+    mut contexts: EguiContexts,
+    mut widget_state: ResMut<WidgetState>,
+    mut widget_changed_events: EventWriter<WidgetChangedEvent>,
+    windows: Query<&Window>,
+) {
+    if windows.iter().next().is_none() {
+        return;
+    }
+
+    let state = widget_state.as_mut();
+    if !state.plane.enabled
+        && !state.box_widget.enabled
+        && !state.sphere.enabled
+        && !state.line.enabled
+    {
+        return;
+    }
+
+    let speed = (state.scale * 0.005).max(1e-4) as f64;
+    let mut changed = Vec::new();
+
+    egui::Window::new("Widgets")
+        .default_width(220.0)
+        .show(contexts.ctx_mut(), |ui| {
+            if state.plane.enabled {
+                ui.label("Plane");
+                let mut plane_changed = vec3_editor(ui, "Origin", &mut state.plane.origin, speed);
+                plane_changed |= vec3_editor(ui, "Normal", &mut state.plane.normal, 0.01);
+                if plane_changed {
+                    state.plane.normal = state.plane.normal.try_normalize().unwrap_or(Vec3::X);
+                    changed.push(WidgetKind::Plane);
+                }
+                ui.separator();
+            }
+
+            if state.box_widget.enabled {
+                ui.label("Box");
+                let mut box_changed = vec3_editor(ui, "Min", &mut state.box_widget.min, speed);
+                box_changed |= vec3_editor(ui, "Max", &mut state.box_widget.max, speed);
+                if box_changed {
+                    let (min, max) = (state.box_widget.min, state.box_widget.max);
+                    state.box_widget.min = min.min(max);
+                    state.box_widget.max = min.max(max);
+                    changed.push(WidgetKind::Box);
+                }
+                ui.separator();
+            }
+
+            if state.sphere.enabled {
+                ui.label("Sphere");
+                let mut sphere_changed = vec3_editor(ui, "Center", &mut state.sphere.center, speed);
+                ui.horizontal(|ui| {
+                    ui.label("Radius:");
+                    sphere_changed |= ui
+                        .add(
+                            egui::DragValue::new(&mut state.sphere.radius)
+                                .speed(speed)
+                                .range(MIN_WIDGET_EXTENT..=f32::MAX),
+                        )
+                        .changed();
+                });
+                if sphere_changed {
+                    changed.push(WidgetKind::Sphere);
+                }
+                ui.separator();
+            }
+
+            if state.line.enabled {
+                ui.label("Line");
+                let mut line_changed = vec3_editor(ui, "Start", &mut state.line.start, speed);
+                line_changed |= vec3_editor(ui, "End", &mut state.line.end, speed);
+                if line_changed {
+                    changed.push(WidgetKind::Line);
+                }
+            }
+        });
+
+    for kind in changed {
+        widget_changed_events.send(WidgetChangedEvent(kind));
+    }
+}
+
+/// Row of three drag values for a vector
+pub fn vec3_editor(ui: &mut egui::Ui, label: &str, value: &mut Vec3, speed: f64) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label(format!("{}:", label));
+        changed |= ui
+            .add(egui::DragValue::new(&mut value.x).speed(speed))
+            .changed();
+        changed |= ui
+            .add(egui::DragValue::new(&mut value.y).speed(speed))
+            .changed();
+        changed |= ui
+            .add(egui::DragValue::new(&mut value.z).speed(speed))
+            .changed();
+    });
+    changed
+}